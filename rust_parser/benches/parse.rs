@@ -0,0 +1,98 @@
+//! Бенчмарки по корпусу `tests/fixtures/`: `cargo bench --bench parse`.
+//!
+//! - `adapter_classifier` — построение `TransactionAdapter` и `InstructionClassifier`;
+//! - `parse_all` — полный разбор с копией транзакции на итерацию;
+//! - `parse_all_shared` — то же через `parse_all_shared` без копии;
+//! - `parse_balances` — только балансы и переводы, без парсеров протоколов;
+//! - `zero_copy` — `ZcTransaction::parse` + `DexParser::parse_zc` (только base64 fixtures).
+
+use std::sync::Arc;
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+
+use solana_dex_parser::core::instruction_classifier::InstructionClassifier;
+use solana_dex_parser::core::transaction_adapter::TransactionAdapter;
+use solana_dex_parser::core::zero_copy::ZcTransaction;
+use solana_dex_parser::{fixtures, DexParser, ParseConfig};
+
+fn adapter_classifier(c: &mut Criterion) {
+    let corpus = fixtures::load_all().expect("fixtures");
+    let mut group = c.benchmark_group("adapter_classifier");
+    for (name, tx) in corpus {
+        let tx = Arc::new(tx);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &tx, |b, tx| {
+            b.iter(|| {
+                let adapter = TransactionAdapter::new(tx.clone(), ParseConfig::default());
+                black_box(InstructionClassifier::new(&adapter));
+            })
+        });
+    }
+    group.finish();
+}
+
+fn parse_all(c: &mut Criterion) {
+    let corpus = fixtures::load_all().expect("fixtures");
+    let parser = DexParser::new();
+    let mut group = c.benchmark_group("parse_all");
+    for (name, tx) in &corpus {
+        group.bench_with_input(BenchmarkId::from_parameter(name), tx, |b, tx| {
+            b.iter(|| black_box(parser.parse_all(tx.clone(), None)))
+        });
+    }
+    group.finish();
+}
+
+fn parse_all_shared(c: &mut Criterion) {
+    let corpus = fixtures::load_all().expect("fixtures");
+    let parser = DexParser::new();
+    let mut group = c.benchmark_group("parse_all_shared");
+    for (name, tx) in corpus {
+        let tx = Arc::new(tx);
+        group.bench_with_input(BenchmarkId::from_parameter(name), &tx, |b, tx| {
+            b.iter(|| black_box(parser.parse_all_shared(tx.clone(), None)))
+        });
+    }
+    group.finish();
+}
+
+fn parse_balances(c: &mut Criterion) {
+    let corpus = fixtures::load_all().expect("fixtures");
+    let parser = DexParser::new();
+    let mut group = c.benchmark_group("parse_balances");
+    for (name, tx) in &corpus {
+        group.bench_with_input(BenchmarkId::from_parameter(name), tx, |b, tx| {
+            b.iter(|| black_box(parser.parse_balances(tx.clone(), None)))
+        });
+    }
+    group.finish();
+}
+
+fn zero_copy(c: &mut Criterion) {
+    let parser = DexParser::new();
+    let mut group = c.benchmark_group("zero_copy");
+    for name in fixtures::names().expect("fixtures") {
+        let value = fixtures::load_value(&name).expect("fixture");
+        let Some(raw) = fixtures::raw_transaction(&value) else {
+            continue;
+        };
+        let signature = fixtures::raw_signature(&raw).unwrap_or_default();
+        let meta = value.get("meta");
+        group.bench_function(BenchmarkId::from_parameter(&name), |b| {
+            b.iter(|| {
+                let zc_tx = ZcTransaction::parse(&raw, 0, &signature, 0, meta).expect("zc parse");
+                black_box(parser.parse_zc(&zc_tx, meta, None))
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    adapter_classifier,
+    parse_all,
+    parse_all_shared,
+    parse_balances,
+    zero_copy
+);
+criterion_main!(benches);
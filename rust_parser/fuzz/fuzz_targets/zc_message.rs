@@ -0,0 +1,22 @@
+//! `cargo +nightly fuzz run zc_message` из `rust_parser/`: zero-copy разбор
+//! произвольных байтов не должен паниковать и читать за пределами буфера.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use solana_dex_parser::core::zero_copy::{parse_signatures, ZcMessage, ZcTransaction};
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok((_, message_start)) = parse_signatures(data) {
+        if let Ok(message) = ZcMessage::parse(data, message_start) {
+            for ix in &message.instructions {
+                let _ = message.get_program_id(ix);
+                let _ = message.get_instruction_accounts(ix);
+            }
+        }
+    }
+    if let Ok(tx) = ZcTransaction::parse(data, 0, "fuzz", 0, None) {
+        let _ = tx.get_signers();
+        let _ = tx.get_instructions();
+    }
+});
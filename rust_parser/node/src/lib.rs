@@ -0,0 +1,232 @@
+//! N-API биндинги: `DexParser` для Node.js с API TS-пакета.
+//!
+//! Транзакции передаются строкой в любом виде, который принимает
+//! `rpc_json::transaction_from_json` (тот же декодер, что у wasm): `JSON.stringify`
+//! ответа RPC `getTransaction` (`json`, `jsonParsed`, `base64`) либо нормализованный
+//! `SolanaTransaction`. `ParseResult` выводится через `compat::to_json` — в форме
+//! TS-пакета при `tsCompatOutput`.
+
+use std::sync::OnceLock;
+
+use napi::{Error, Result, Status};
+use napi_derive::napi;
+use serde::Serialize;
+use serde_json::Value;
+use solana_dex_parser::core::rpc_json::transaction_from_json;
+use solana_dex_parser::{
+    compat, BlockInput, BlockParseResult, DexParser, ParseConfig, ParseResult, SolanaTransaction,
+};
+
+/// Повторяет API класса `DexParser` из TS-пакета.
+#[napi(js_name = "DexParser")]
+pub struct JsDexParser {
+    inner: DexParser,
+}
+
+impl Default for JsDexParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[napi]
+impl JsDexParser {
+    #[napi(constructor)]
+    pub fn new() -> Self {
+        Self {
+            inner: DexParser::new(),
+        }
+    }
+
+    #[napi]
+    pub fn parse_all(&self, tx_json: String, config: Option<Value>) -> Result<Value> {
+        parse_all_with(&self.inner, &tx_json, config)
+    }
+
+    #[napi]
+    pub fn parse_trades(&self, tx_json: String, config: Option<Value>) -> Result<Value> {
+        let tx = decode_transaction(&tx_json)?;
+        to_js(&self.inner.parse_trades(tx, decode_config(config)?))
+    }
+
+    #[napi]
+    pub fn parse_liquidity(&self, tx_json: String, config: Option<Value>) -> Result<Value> {
+        let tx = decode_transaction(&tx_json)?;
+        to_js(&self.inner.parse_liquidity(tx, decode_config(config)?))
+    }
+
+    #[napi]
+    pub fn parse_transfers(&self, tx_json: String, config: Option<Value>) -> Result<Value> {
+        let tx = decode_transaction(&tx_json)?;
+        to_js(&self.inner.parse_transfers(tx, decode_config(config)?))
+    }
+
+    #[napi]
+    pub fn parse_block(&self, block_json: String, config: Option<Value>) -> Result<Value> {
+        parse_block_with(&self.inner, &block_json, config)
+    }
+}
+
+fn shared_parser() -> &'static DexParser {
+    static PARSER: OnceLock<DexParser> = OnceLock::new();
+    PARSER.get_or_init(DexParser::new)
+}
+
+#[napi]
+pub fn parse_all(tx_json: String, config: Option<Value>) -> Result<Value> {
+    parse_all_with(shared_parser(), &tx_json, config)
+}
+
+#[napi]
+pub fn parse_block(block_json: String, config: Option<Value>) -> Result<Value> {
+    parse_block_with(shared_parser(), &block_json, config)
+}
+
+fn parse_all_with(parser: &DexParser, tx_json: &str, config: Option<Value>) -> Result<Value> {
+    from_json(&parse_all_json(parser, tx_json, config)?)
+}
+
+fn parse_all_json(parser: &DexParser, tx_json: &str, config: Option<Value>) -> Result<String> {
+    let tx = decode_transaction(tx_json)?;
+    let config = decode_config(config)?.unwrap_or_default();
+    // throwError: как в TS — исключение вместо `state = false`
+    let result = if config.throw_error {
+        parser.try_parse_all(tx, Some(config.clone())).map_err(invalid_arg)?
+    } else {
+        parser.parse_all(tx, Some(config.clone()))
+    };
+    result_json(&result, &config)
+}
+
+/// `blockJson` — `BlockInput` (`{"type": "raw" | "parsed" | "rpc_json", ...}`) либо массив
+/// транзакций.
+fn parse_block_with(
+    parser: &DexParser,
+    block_json: &str,
+    config: Option<Value>,
+) -> Result<Value> {
+    let config = decode_config(config)?.unwrap_or_default();
+    let value: Value = serde_json::from_str(block_json).map_err(invalid_arg)?;
+    let Value::Array(transactions) = value else {
+        let input: BlockInput = serde_json::from_value(value).map_err(invalid_arg)?;
+        let block = parser
+            .parse_block(&input, Some(config.clone()))
+            .map_err(invalid_arg)?;
+        return block_value(&block, &config);
+    };
+
+    let results = transactions
+        .iter()
+        .map(|tx| {
+            let tx = transaction_from_json(tx).map_err(invalid_arg)?;
+            result_value(&parser.parse_all(tx, Some(config.clone())), &config)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Value::Array(results))
+}
+
+/// `BlockParseResult`, транзакции которого сериализованы через `compat::to_json`.
+fn block_value(block: &BlockParseResult, config: &ParseConfig) -> Result<Value> {
+    let transactions = block
+        .transactions
+        .iter()
+        .map(|result| result_value(result, config))
+        .collect::<Result<Vec<_>>>()?;
+    let mut value = serde_json::json!({
+        "slot": block.slot,
+        "timestamp": block.timestamp,
+        "transactions": transactions,
+    });
+    if let Some(blockhash) = &block.blockhash {
+        value["blockhash"] = Value::from(blockhash.as_str());
+    }
+    Ok(value)
+}
+
+fn decode_transaction(tx_json: &str) -> Result<SolanaTransaction> {
+    let value: Value = serde_json::from_str(tx_json).map_err(invalid_arg)?;
+    transaction_from_json(&value).map_err(invalid_arg)
+}
+
+fn decode_config(config: Option<Value>) -> Result<Option<ParseConfig>> {
+    config
+        .filter(|value| !value.is_null())
+        .map(|value| serde_json::from_value(value).map_err(invalid_arg))
+        .transpose()
+}
+
+fn result_json(result: &ParseResult, config: &ParseConfig) -> Result<String> {
+    compat::to_json(result, config).map_err(generic_failure)
+}
+
+fn result_value(result: &ParseResult, config: &ParseConfig) -> Result<Value> {
+    from_json(&result_json(result, config)?)
+}
+
+fn from_json(json: &str) -> Result<Value> {
+    serde_json::from_str(json).map_err(generic_failure)
+}
+
+fn to_js<T: Serialize>(value: &T) -> Result<Value> {
+    serde_json::to_value(value).map_err(generic_failure)
+}
+
+fn generic_failure(err: impl std::fmt::Display) -> Error {
+    Error::new(Status::GenericFailure, err.to_string())
+}
+
+fn invalid_arg(err: impl std::fmt::Display) -> Error {
+    Error::new(Status::InvalidArg, err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> String {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/fixtures/");
+        std::fs::read_to_string(format!("{path}{name}")).unwrap()
+    }
+
+    #[test]
+    fn parse_all_outputs_ts_shape_through_compat() {
+        let config = serde_json::json!({ "tsCompatOutput": true });
+        let json = parse_all_json(shared_parser(), &fixture("sample_tx.json"), Some(config)).unwrap();
+        // порядок ключей TS: `state` первым, без Rust-расширений
+        assert!(json.starts_with(r#"{"state":true"#), "{json}");
+        assert!(!json.contains("orderKey"));
+
+        let rust_json = parse_all_json(shared_parser(), &fixture("sample_tx.json"), None).unwrap();
+        assert!(rust_json.contains(r#""txStatus":"SUCCESS""#));
+    }
+
+    #[test]
+    fn block_input_outputs_ts_shape_through_compat() {
+        let tx: Value = serde_json::from_str(&fixture("sample_tx.json")).unwrap();
+        let block = serde_json::json!({ "type": "raw", "transactions": [tx] }).to_string();
+
+        let config = serde_json::json!({ "tsCompatOutput": true });
+        let compat = parse_block_with(shared_parser(), &block, Some(config)).unwrap();
+        let compat_tx = &compat["transactions"][0];
+        assert_eq!(compat_tx["state"], true);
+        assert_eq!(compat_tx["txStatus"], "success", "{compat_tx}");
+
+        let rust = parse_block_with(shared_parser(), &block, None).unwrap();
+        assert_eq!(rust["transactions"][0]["txStatus"], "SUCCESS");
+    }
+
+    #[test]
+    fn get_transaction_json_goes_through_shared_decoder() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/../tests/fixtures/raydium_clmm");
+        let path = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| !path.to_string_lossy().ends_with(".expected.json"))
+            .unwrap();
+        let tx_json = std::fs::read_to_string(path).unwrap();
+
+        let result = parse_all_with(shared_parser(), &tx_json, None).unwrap();
+        assert_eq!(result["state"], true);
+        assert!(!result["trades"].as_array().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,401 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::core::constants::{JITO_TIP_ACCOUNTS, TOKENS};
+use crate::types::{BlockParseResult, ParseResult, TransactionStatus};
+
+/// Jito пропускает в бандл не больше пяти транзакций.
+const MAX_BUNDLE_LEN: usize = 5;
+
+/// Откуда известен состав бандла.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BundleSource {
+    /// Tip и общие подписанты соседних транзакций.
+    Heuristic,
+    /// Ответ Jito block engine (`JitoBundleLookup`).
+    BlockEngine,
+}
+
+/// Суммарное движение mint'а по trades бандла.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleFlow {
+    pub mint: String,
+    /// Отдано в пулы (input trades).
+    pub spent: f64,
+    /// Получено из пулов (output trades).
+    pub received: f64,
+}
+
+impl BundleFlow {
+    pub fn net(&self) -> f64 {
+        self.received - self.spent
+    }
+}
+
+/// Подряд идущие транзакции блока из одного Jito бандла.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleInfo {
+    pub slot: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle_id: Option<String>,
+    pub source: BundleSource,
+    /// Индексы в `BlockParseResult::transactions` в порядке исполнения.
+    pub tx_indices: Vec<usize>,
+    pub signatures: Vec<String>,
+    /// Отправитель tip; при нескольких tip-транзакциях — первой из них.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tip_payer: Option<String>,
+    pub tip_lamports: u64,
+    /// Подписанты всех транзакций без повторов, в порядке появления.
+    pub signers: Vec<String>,
+    /// По mint в алфавитном порядке.
+    pub flow: Vec<BundleFlow>,
+}
+
+impl BundleInfo {
+    pub fn len(&self) -> usize {
+        self.tx_indices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tx_indices.is_empty()
+    }
+
+    fn build(
+        block: &BlockParseResult,
+        tx_indices: Vec<usize>,
+        source: BundleSource,
+        bundle_id: Option<String>,
+    ) -> Self {
+        let txs: Vec<&ParseResult> = tx_indices
+            .iter()
+            .map(|&index| &block.transactions[index])
+            .collect();
+
+        let mut signers: Vec<String> = Vec::new();
+        for signer in txs.iter().flat_map(|tx| &tx.signer) {
+            if !signers.contains(signer) {
+                signers.push(signer.clone());
+            }
+        }
+
+        let mut flow: BTreeMap<&str, BundleFlow> = BTreeMap::new();
+        for trade in txs.iter().flat_map(|tx| &tx.trades) {
+            flow_entry(&mut flow, &trade.input_token.mint).spent += trade.input_token.amount;
+            flow_entry(&mut flow, &trade.output_token.mint).received += trade.output_token.amount;
+        }
+
+        Self {
+            slot: block.slot,
+            bundle_id,
+            source,
+            signatures: txs.iter().map(|tx| tx.signature.clone()).collect(),
+            tip_payer: txs.iter().copied().find_map(tip_payer),
+            tip_lamports: txs.iter().copied().map(tip_lamports).sum(),
+            signers,
+            flow: flow.into_values().collect(),
+            tx_indices,
+        }
+    }
+}
+
+/// Группирует транзакции блока в бандлы. Транзакция с tip (`ParseResult::jito_tip`)
+/// закрывает бандл: к ней присоединяются до четырёх предыдущих транзакций без tip
+/// вплоть до самой ранней, у которой есть общий подписант с tip-транзакцией, — чужие
+/// транзакции между ними (жертвы сэндвича) тоже входят в бандл. Если таких нет, tip
+/// считается стоящим в начале бандла и так же ищутся последующие транзакции.
+/// Каждая транзакция входит максимум в один бандл; упавшие не входят.
+pub fn group_bundles(block: &BlockParseResult) -> Vec<BundleInfo> {
+    let txs = &block.transactions;
+    let mut claimed = vec![false; txs.len()];
+    let free = |index: usize, claimed: &[bool]| {
+        !claimed[index] && landed(&txs[index]) && txs[index].jito_tip.is_none()
+    };
+
+    let mut bundles = Vec::new();
+    for tip in 0..txs.len() {
+        if claimed[tip] || !landed(&txs[tip]) || txs[tip].jito_tip.is_none() {
+            continue;
+        }
+        let shares_signer = |index: usize| {
+            txs[index]
+                .signer
+                .iter()
+                .any(|signer| txs[tip].signer.contains(signer))
+        };
+
+        let mut start = tip;
+        for prev in (tip.saturating_sub(MAX_BUNDLE_LEN - 1)..tip).rev() {
+            if !free(prev, &claimed) {
+                break;
+            }
+            if shares_signer(prev) {
+                start = prev;
+            }
+        }
+        let mut end = tip;
+        if start == tip {
+            for next in tip + 1..txs.len().min(tip + MAX_BUNDLE_LEN) {
+                if !free(next, &claimed) {
+                    break;
+                }
+                if shares_signer(next) {
+                    end = next;
+                }
+            }
+        }
+
+        let members: Vec<usize> = (start..=end).collect();
+        for &index in &members {
+            claimed[index] = true;
+        }
+        bundles.push(BundleInfo::build(
+            block,
+            members,
+            BundleSource::Heuristic,
+            None,
+        ));
+    }
+    bundles
+}
+
+fn flow_entry<'a, 'b>(
+    flow: &'b mut BTreeMap<&'a str, BundleFlow>,
+    mint: &'a str,
+) -> &'b mut BundleFlow {
+    flow.entry(mint).or_insert_with(|| BundleFlow {
+        mint: mint.to_string(),
+        ..BundleFlow::default()
+    })
+}
+
+fn landed(tx: &ParseResult) -> bool {
+    tx.state && tx.tx_status != TransactionStatus::Failed
+}
+
+fn tip_lamports(tx: &ParseResult) -> u64 {
+    tx.jito_tip
+        .as_ref()
+        .and_then(|tip| tip.amount.parse().ok())
+        .unwrap_or(0)
+}
+
+/// Источник SOL-перевода на tip-аккаунт Jito, иначе fee payer tip-транзакции.
+fn tip_payer(tx: &ParseResult) -> Option<String> {
+    tx.jito_tip.as_ref()?;
+    tx.transfers
+        .iter()
+        .find(|transfer| {
+            transfer.info.mint == TOKENS.SOL
+                && JITO_TIP_ACCOUNTS.contains(&transfer.info.destination.as_str())
+        })
+        .map(|transfer| transfer.info.source.to_string())
+        .or_else(|| (!tx.fee_payer.is_empty()).then(|| tx.fee_payer.clone()))
+}
+
+/// Состав бандлов из Jito block engine explorer API:
+/// `GET {endpoint}/transaction/{signature}` → `[{"bundle_id": ...}]`,
+/// `GET {endpoint}/bundle/{id}` → `[{"txSignatures": [...]}]`.
+#[cfg(feature = "rpc")]
+pub struct JitoBundleLookup {
+    endpoint: String,
+    client: reqwest::Client,
+}
+
+#[cfg(feature = "rpc")]
+impl Default for JitoBundleLookup {
+    fn default() -> Self {
+        Self::new("https://bundles.jito.wtf/api/v1/bundles")
+    }
+}
+
+#[cfg(feature = "rpc")]
+impl JitoBundleLookup {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into().trim_end_matches('/').to_string(),
+            client: reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(5))
+                .build()
+                .expect("reqwest client"),
+        }
+    }
+
+    /// Id бандла и подписи его транзакций; `None`, если транзакция пришла не бандлом.
+    pub async fn bundle_for_transaction(
+        &self,
+        signature: &str,
+    ) -> anyhow::Result<Option<(String, Vec<String>)>> {
+        let found = self
+            .get(&format!("{}/transaction/{signature}", self.endpoint))
+            .await?;
+        let Some(bundle_id) = first_str(&found, &["bundle_id", "bundleId"]) else {
+            return Ok(None);
+        };
+        let bundle = self
+            .get(&format!("{}/bundle/{bundle_id}", self.endpoint))
+            .await?;
+        let signatures = first_entry(&bundle)
+            .and_then(|entry| {
+                ["txSignatures", "transactions"]
+                    .iter()
+                    .find_map(|key| entry.get(key)?.as_array())
+            })
+            .map(|list| {
+                list.iter()
+                    .filter_map(|sig| sig.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(Some((bundle_id, signatures)))
+    }
+
+    async fn get(&self, url: &str) -> anyhow::Result<serde_json::Value> {
+        let response = self.client.get(url).send().await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(serde_json::Value::Null);
+        }
+        Ok(response.error_for_status()?.json().await?)
+    }
+}
+
+#[cfg(feature = "rpc")]
+fn first_entry(body: &serde_json::Value) -> Option<&serde_json::Value> {
+    match body {
+        serde_json::Value::Array(entries) => entries.first(),
+        serde_json::Value::Object(_) => Some(body),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "rpc")]
+fn first_str(body: &serde_json::Value, keys: &[&str]) -> Option<String> {
+    let entry = first_entry(body)?;
+    keys.iter()
+        .find_map(|key| entry.get(key)?.as_str())
+        .map(str::to_string)
+}
+
+/// `group_bundles`, уточнённый через block engine: для tip-транзакции каждого
+/// эвристического бандла запрашивается настоящий состав. Ошибки запроса и подписи
+/// вне блока оставляют эвристический вариант.
+#[cfg(feature = "rpc")]
+pub async fn group_bundles_with_lookup(
+    block: &BlockParseResult,
+    lookup: &JitoBundleLookup,
+) -> Vec<BundleInfo> {
+    let positions: std::collections::HashMap<&str, usize> = block
+        .transactions
+        .iter()
+        .enumerate()
+        .map(|(index, tx)| (tx.signature.as_str(), index))
+        .collect();
+
+    let mut bundles = Vec::new();
+    for heuristic in group_bundles(block) {
+        let tip_signature = heuristic
+            .tx_indices
+            .iter()
+            .map(|&index| &block.transactions[index])
+            .find(|tx| tx.jito_tip.is_some())
+            .map(|tx| tx.signature.as_str())
+            .unwrap_or_default();
+        let confirmed = match lookup.bundle_for_transaction(tip_signature).await {
+            Ok(confirmed) => confirmed,
+            Err(err) => {
+                tracing::warn!("jito bundle lookup for {tip_signature} failed: {err}");
+                None
+            }
+        };
+        let Some((bundle_id, signatures)) = confirmed else {
+            bundles.push(heuristic);
+            continue;
+        };
+        let mut members: Vec<usize> = signatures
+            .iter()
+            .filter_map(|signature| positions.get(signature.as_str()).copied())
+            .collect();
+        members.sort_unstable();
+        members.dedup();
+        if members.len() != signatures.len() || members.is_empty() {
+            bundles.push(heuristic);
+            continue;
+        }
+        bundles.push(BundleInfo::build(
+            block,
+            members,
+            BundleSource::BlockEngine,
+            Some(bundle_id),
+        ));
+    }
+    bundles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{TokenAmount, TokenInfo, TradeInfo};
+
+    fn tx(signature: &str, signer: &str, tip: Option<u64>) -> ParseResult {
+        let mut result = ParseResult::new();
+        result.signature = signature.to_string();
+        result.signer = vec![signer.to_string()];
+        result.fee_payer = signer.to_string();
+        result.tx_status = TransactionStatus::Success;
+        result.jito_tip = tip.map(|lamports| TokenAmount::new(lamports.to_string(), 9, None));
+        result
+    }
+
+    fn swap(input: &str, amount_in: f64, output: &str, amount_out: f64) -> TradeInfo {
+        let token = |mint: &str, amount: f64| TokenInfo {
+            mint: mint.to_string(),
+            amount,
+            ..TokenInfo::default()
+        };
+        TradeInfo {
+            input_token: token(input, amount_in),
+            output_token: token(output, amount_out),
+            ..TradeInfo::default()
+        }
+    }
+
+    #[test]
+    fn groups_sandwich_around_victim() {
+        let mut front = tx("front", "bot", None);
+        front.trades.push(swap("SOL", 10.0, "MEME", 1000.0));
+        let mut victim = tx("victim", "user", None);
+        victim.trades.push(swap("SOL", 5.0, "MEME", 400.0));
+        let mut back = tx("back", "bot", Some(100_000));
+        back.trades.push(swap("MEME", 1000.0, "SOL", 10.5));
+        let block = BlockParseResult {
+            slot: 7,
+            timestamp: None,
+            blockhash: None,
+            transactions: vec![
+                tx("other", "someone", None),
+                front,
+                victim,
+                back,
+                tx("solo", "sniper", Some(5_000)),
+                tx("after", "sniper", None),
+            ],
+        };
+
+        let bundles = group_bundles(&block);
+        assert_eq!(bundles.len(), 2);
+        let sandwich = &bundles[0];
+        assert_eq!(sandwich.tx_indices, [1, 2, 3]);
+        assert_eq!(sandwich.signers, ["bot", "user"]);
+        assert_eq!(sandwich.tip_payer.as_deref(), Some("bot"));
+        assert_eq!(sandwich.tip_lamports, 100_000);
+        let sol = sandwich.flow.iter().find(|f| f.mint == "SOL").unwrap();
+        assert!((sol.net() - -4.5).abs() < 1e-9);
+
+        // tip в начале бандла
+        assert_eq!(bundles[1].signatures, ["solo", "after"]);
+    }
+}
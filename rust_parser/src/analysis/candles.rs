@@ -0,0 +1,202 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{ParseResult, TradeInfo, TransactionStatus};
+
+/// Ключ рынка свечей: пул сделки или пара `base/quote` поверх всех пулов.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CandleMarket {
+    #[default]
+    Pool,
+    Pair,
+}
+
+/// OHLCV-бар; цена — quote за единицу base (`TradeInfo::price`), объёмы в UI amount.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Candle {
+    pub market: String,
+    pub base_mint: String,
+    pub quote_mint: String,
+    /// Начало интервала, секунды (кратно интервалу).
+    pub open_time: u64,
+    pub interval: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub quote_volume: f64,
+    pub trade_count: u64,
+}
+
+impl Candle {
+    /// Бар без сделок: OHLC по close предыдущего.
+    fn flat(previous: &Candle, open_time: u64) -> Self {
+        Self {
+            open_time,
+            open: previous.close,
+            high: previous.close,
+            low: previous.close,
+            volume: 0.0,
+            quote_volume: 0.0,
+            trade_count: 0,
+            ..previous.clone()
+        }
+    }
+}
+
+/// OHLCV по потоку сделок в порядке исполнения. `push` возвращает бары, закрытые
+/// сделкой из следующего интервала; сделки из уже закрытых интервалов отбрасываются.
+#[derive(Clone, Debug)]
+pub struct CandleBuilder {
+    interval: u64,
+    market: CandleMarket,
+    fill_gaps: bool,
+    open: HashMap<String, Candle>,
+}
+
+impl CandleBuilder {
+    pub fn new(interval_secs: u64, market: CandleMarket) -> Self {
+        Self {
+            interval: interval_secs.max(1),
+            market,
+            fill_gaps: false,
+            open: HashMap::new(),
+        }
+    }
+
+    /// Пустые интервалы между сделками отдавать плоскими барами с нулевым объёмом.
+    pub fn with_gap_fill(mut self, fill_gaps: bool) -> Self {
+        self.fill_gaps = fill_gaps;
+        self
+    }
+
+    pub fn ingest(&mut self, result: &ParseResult) -> Vec<Candle> {
+        if !result.state || result.tx_status == TransactionStatus::Failed {
+            return Vec::new();
+        }
+        result
+            .trades
+            .iter()
+            .flat_map(|trade| self.push(trade))
+            .collect()
+    }
+
+    pub fn push(&mut self, trade: &TradeInfo) -> Vec<Candle> {
+        let Some(price) = trade.price() else {
+            return Vec::new();
+        };
+        let (base_mint, quote_mint) = (trade.base_mint(), trade.quote_mint());
+        let market = match self.market {
+            CandleMarket::Pool => match trade.pool.first() {
+                Some(pool) => pool.clone(),
+                None => return Vec::new(),
+            },
+            CandleMarket::Pair => format!("{base_mint}/{quote_mint}"),
+        };
+        let (base_amount, quote_amount) = if trade.input_token.mint == quote_mint {
+            (trade.output_token.amount, trade.input_token.amount)
+        } else {
+            (trade.input_token.amount, trade.output_token.amount)
+        };
+        let open_time = trade.timestamp - trade.timestamp % self.interval;
+
+        let mut closed = Vec::new();
+        if let Some(current) = self.open.get(&market) {
+            if open_time < current.open_time {
+                return closed;
+            }
+            if open_time > current.open_time {
+                let current = self.open.remove(&market).expect("open candle");
+                let mut gap = current.open_time + self.interval;
+                closed.push(current);
+                while self.fill_gaps && gap < open_time {
+                    closed.push(Candle::flat(&closed[0], gap));
+                    gap += self.interval;
+                }
+            }
+        }
+
+        let candle = self.open.entry(market.clone()).or_insert_with(|| Candle {
+            market,
+            base_mint: base_mint.to_string(),
+            quote_mint: quote_mint.to_string(),
+            open_time,
+            interval: self.interval,
+            open: price,
+            high: price,
+            low: price,
+            ..Default::default()
+        });
+        candle.high = candle.high.max(price);
+        candle.low = candle.low.min(price);
+        candle.close = price;
+        candle.volume += base_amount;
+        candle.quote_volume += quote_amount;
+        candle.trade_count += 1;
+        closed
+    }
+
+    /// Текущий (незакрытый) бар рынка.
+    pub fn current(&self, market: &str) -> Option<&Candle> {
+        self.open.get(market)
+    }
+
+    /// Закрывает все открытые бары (конец потока), по возрастанию `open_time`.
+    pub fn flush(&mut self) -> Vec<Candle> {
+        let mut candles: Vec<Candle> = self.open.drain().map(|(_, candle)| candle).collect();
+        candles.sort_by(|a, b| (a.open_time, &a.market).cmp(&(b.open_time, &b.market)));
+        candles
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::constants::TOKENS;
+    use crate::types::TokenInfo;
+
+    fn buy(timestamp: u64, sol: f64, meme: f64) -> TradeInfo {
+        let token = |mint: &str, amount: f64| TokenInfo {
+            mint: mint.to_string(),
+            amount,
+            ..Default::default()
+        };
+        TradeInfo {
+            input_token: token(TOKENS.SOL, sol),
+            output_token: token("MEME", meme),
+            pool: vec!["pool".to_string()],
+            timestamp,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn builds_ohlcv_bars_and_fills_gaps() {
+        let mut builder = CandleBuilder::new(60, CandleMarket::Pool).with_gap_fill(true);
+        assert!(builder.push(&buy(120, 1.0, 100.0)).is_empty());
+        assert!(builder.push(&buy(150, 3.0, 100.0)).is_empty());
+        assert!(builder.push(&buy(170, 2.0, 100.0)).is_empty());
+
+        // следующая сделка через интервал: закрывается бар 120 и плоский бар 180
+        let closed = builder.push(&buy(250, 1.0, 50.0));
+        assert_eq!(closed.len(), 2);
+        let bar = &closed[0];
+        assert_eq!(bar.open_time, 120);
+        assert_eq!(
+            (bar.open, bar.high, bar.low, bar.close),
+            (0.01, 0.03, 0.01, 0.02)
+        );
+        assert!((bar.volume - 300.0).abs() < 1e-9);
+        assert_eq!(bar.trade_count, 3);
+        let gap = &closed[1];
+        assert_eq!((gap.open_time, gap.open, gap.trade_count), (180, 0.02, 0));
+
+        let open = builder.flush();
+        assert_eq!(open.len(), 1);
+        assert_eq!((open[0].open_time, open[0].close), (240, 0.02));
+    }
+}
@@ -0,0 +1,60 @@
+//! Анализ поверх готовых результатов парсера: паттерны между транзакциями
+//! блока (trades уже в порядке исполнения), Jito бандлы, self-trade, а также позиции кошельков,
+//! rolling-объёмы и OHLCV-свечи по потоку `ParseResult`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{BlockParseResult, TradeInfo, TransactionStatus};
+
+pub mod bundles;
+pub mod candles;
+pub mod sandwich;
+pub mod self_trade;
+pub mod volume;
+pub mod wallet;
+
+#[cfg(feature = "rpc")]
+pub use bundles::{group_bundles_with_lookup, JitoBundleLookup};
+pub use bundles::{group_bundles, BundleFlow, BundleInfo, BundleSource};
+pub use candles::{Candle, CandleBuilder, CandleMarket};
+pub use sandwich::{detect_sandwiches, SandwichEvent};
+pub use self_trade::{flag_bundle_self_trades, flag_self_trades, LinkedWallets};
+pub use volume::{VolumeAggregator, VolumeSnapshot, VolumeStats, VolumeWindow};
+pub use wallet::{Position, WalletTracker};
+
+/// Позиция trade в блоке: `transactions[tx_index].trades[trade_index]`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeRef {
+    pub tx_index: usize,
+    pub trade_index: usize,
+    pub signature: String,
+}
+
+/// Trades успешных транзакций блока в порядке исполнения.
+pub(crate) fn block_trades(block: &BlockParseResult) -> Vec<(TradeRef, &TradeInfo)> {
+    block
+        .transactions
+        .iter()
+        .enumerate()
+        .filter(|(_, tx)| tx.state && tx.tx_status != TransactionStatus::Failed)
+        .flat_map(|(tx_index, tx)| {
+            tx.trades.iter().enumerate().map(move |(trade_index, trade)| {
+                let trade_ref = TradeRef {
+                    tx_index,
+                    trade_index,
+                    signature: tx.signature.clone(),
+                };
+                (trade_ref, trade)
+            })
+        })
+        .collect()
+}
+
+/// Кто совершил trade: `user`, иначе первый подписант.
+pub(crate) fn trader(trade: &TradeInfo) -> Option<&str> {
+    trade
+        .user
+        .as_deref()
+        .or_else(|| trade.signer.as_ref()?.first().map(String::as_str))
+}
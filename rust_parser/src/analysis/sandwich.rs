@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{BlockParseResult, TradeInfo};
+
+use super::{block_trades, trader, TradeRef};
+
+/// Front-run → жертвы → back-run одного адреса на одном пуле в пределах блока.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SandwichEvent {
+    pub slot: u64,
+    pub pool: String,
+    pub attacker: String,
+    pub front_run: TradeRef,
+    pub victims: Vec<TradeRef>,
+    pub back_run: TradeRef,
+    /// Mint, которым атакующий зашёл в front-run.
+    pub profit_mint: String,
+    /// `back_run.output - front_run.input` в `profit_mint`; `None`, если back-run вышел в другой mint.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub profit: Option<f64>,
+}
+
+/// Ищет классический сэндвич: атакующий покупает перед жертвой (или несколькими)
+/// в том же направлении и продаёт обратно после неё, все trades — в разных
+/// транзакциях одного пула. Каждый trade входит максимум в один сэндвич.
+pub fn detect_sandwiches(block: &BlockParseResult) -> Vec<SandwichEvent> {
+    let trades = block_trades(block);
+
+    // Trades по пулам с сохранением порядка исполнения
+    let mut by_pool: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (pos, (_, trade)) in trades.iter().enumerate() {
+        if let Some(pool) = trade.pool.first() {
+            by_pool.entry(pool.as_str()).or_default().push(pos);
+        }
+    }
+
+    let mut used = vec![false; trades.len()];
+    let mut events = Vec::new();
+    for positions in by_pool.values() {
+        for (i, &front) in positions.iter().enumerate() {
+            if used[front] {
+                continue;
+            }
+            let (front_ref, front_trade) = &trades[front];
+            let Some(attacker) = trader(front_trade) else {
+                continue;
+            };
+
+            let mut victims = Vec::new();
+            for &next in &positions[i + 1..] {
+                let (next_ref, next_trade) = &trades[next];
+                if used[next] || next_ref.tx_index == front_ref.tx_index {
+                    continue;
+                }
+                let is_attacker = trader(next_trade) == Some(attacker);
+                if is_attacker && is_reverse(front_trade, next_trade) {
+                    if !victims.is_empty() && victims_before(&trades, &victims, next_ref) {
+                        for &pos in victims.iter().chain([&front, &next]) {
+                            used[pos] = true;
+                        }
+                        events.push(build_event(block.slot, &trades, front, victims, next));
+                    }
+                    break;
+                }
+                if !is_attacker && same_direction(front_trade, next_trade) {
+                    victims.push(next);
+                }
+            }
+        }
+    }
+
+    events.sort_by_key(|event| (event.front_run.tx_index, event.front_run.trade_index));
+    events
+}
+
+fn same_direction(a: &TradeInfo, b: &TradeInfo) -> bool {
+    a.input_token.mint == b.input_token.mint && a.output_token.mint == b.output_token.mint
+}
+
+fn is_reverse(front: &TradeInfo, back: &TradeInfo) -> bool {
+    front.output_token.mint == back.input_token.mint
+}
+
+/// Back-run должен быть в транзакции строго после всех жертв.
+fn victims_before(trades: &[(TradeRef, &TradeInfo)], victims: &[usize], back: &TradeRef) -> bool {
+    victims
+        .iter()
+        .all(|&pos| trades[pos].0.tx_index < back.tx_index)
+}
+
+fn build_event(
+    slot: u64,
+    trades: &[(TradeRef, &TradeInfo)],
+    front: usize,
+    victims: Vec<usize>,
+    back: usize,
+) -> SandwichEvent {
+    let (front_ref, front_trade) = &trades[front];
+    let (back_ref, back_trade) = &trades[back];
+    let profit_mint = front_trade.input_token.mint.clone();
+    let profit = (back_trade.output_token.mint == profit_mint)
+        .then_some(back_trade.output_token.amount - front_trade.input_token.amount);
+
+    SandwichEvent {
+        slot,
+        pool: front_trade.pool[0].clone(),
+        attacker: trader(front_trade).unwrap_or_default().to_string(),
+        front_run: front_ref.clone(),
+        victims: victims.into_iter().map(|pos| trades[pos].0.clone()).collect(),
+        back_run: back_ref.clone(),
+        profit_mint,
+        profit,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ParseResult, TokenInfo};
+
+    fn trade(user: &str, input: (&str, f64), output: (&str, f64)) -> TradeInfo {
+        let token = |(mint, amount): (&str, f64)| TokenInfo {
+            mint: mint.to_string(),
+            amount,
+            ..Default::default()
+        };
+        TradeInfo {
+            pool: vec!["pool".to_string()],
+            input_token: token(input),
+            output_token: token(output),
+            user: Some(user.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn block(trades: Vec<TradeInfo>) -> BlockParseResult {
+        BlockParseResult {
+            slot: 42,
+            timestamp: None,
+            transactions: trades
+                .into_iter()
+                .enumerate()
+                .map(|(i, trade)| ParseResult {
+                    signature: format!("sig-{i}"),
+                    trades: vec![trade],
+                    ..ParseResult::new()
+                })
+                .collect(),
+            blockhash: None,
+        }
+    }
+
+    #[test]
+    fn detects_front_victim_back_on_same_pool() {
+        let block = block(vec![
+            trade("bot", ("SOL", 10.0), ("MEME", 1_000.0)),
+            trade("alice", ("SOL", 5.0), ("MEME", 450.0)),
+            trade("bob", ("MEME", 100.0), ("SOL", 1.0)),
+            trade("bot", ("MEME", 1_000.0), ("SOL", 10.5)),
+        ]);
+
+        let events = detect_sandwiches(&block);
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.attacker, "bot");
+        assert_eq!(event.front_run.tx_index, 0);
+        assert_eq!(
+            event.victims.iter().map(|v| v.signature.as_str()).collect::<Vec<_>>(),
+            ["sig-1"]
+        );
+        assert_eq!(event.back_run.tx_index, 3);
+        assert_eq!(event.profit_mint, "SOL");
+        assert!((event.profit.unwrap() - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn ignores_round_trip_without_victim() {
+        let block = block(vec![
+            trade("bot", ("SOL", 10.0), ("MEME", 1_000.0)),
+            trade("bot", ("MEME", 1_000.0), ("SOL", 10.5)),
+        ]);
+        assert!(detect_sandwiches(&block).is_empty());
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use crate::types::{ParseResult, TradeFlag, TradeInfo, TransactionStatus};
+
+use super::trader;
+
+/// Группы кошельков одного владельца (бот-кошельки, известные связки) для `flag_self_trades`.
+#[derive(Clone, Debug, Default)]
+pub struct LinkedWallets {
+    groups: HashMap<String, usize>,
+    next_group: usize,
+}
+
+impl LinkedWallets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Связывает кошельки в одну группу; пересекающиеся группы сливаются.
+    pub fn link<I, S>(&mut self, wallets: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let wallets: Vec<String> = wallets.into_iter().map(Into::into).collect();
+        let group = wallets
+            .iter()
+            .filter_map(|wallet| self.groups.get(wallet).copied())
+            .min()
+            .unwrap_or_else(|| {
+                self.next_group += 1;
+                self.next_group
+            });
+        let merged: Vec<usize> = wallets
+            .iter()
+            .filter_map(|wallet| self.groups.get(wallet).copied())
+            .filter(|&other| other != group)
+            .collect();
+        for member in self.groups.values_mut() {
+            if merged.contains(member) {
+                *member = group;
+            }
+        }
+        for wallet in wallets {
+            self.groups.insert(wallet, group);
+        }
+    }
+
+    pub fn same_owner(&self, a: &str, b: &str) -> bool {
+        a == b
+            || matches!(
+                (self.groups.get(a), self.groups.get(b)),
+                (Some(x), Some(y)) if x == y
+            )
+    }
+}
+
+/// Помечает `SelfTrade` встречные сделки одной транзакции: quote ушёл в пул от кошелька
+/// и вернулся ему же (или связанному) встречной сделкой по тому же пулу.
+pub fn flag_self_trades(result: &mut ParseResult, linked: &LinkedWallets) -> usize {
+    if !result.state || result.tx_status == TransactionStatus::Failed {
+        return 0;
+    }
+    flag(result.trades.iter_mut().collect(), linked)
+}
+
+/// То же для бандла: встречные сделки ищутся во всех транзакциях вместе.
+pub fn flag_bundle_self_trades(bundle: &mut [ParseResult], linked: &LinkedWallets) -> usize {
+    let trades = bundle
+        .iter_mut()
+        .filter(|result| result.state && result.tx_status != TransactionStatus::Failed)
+        .flat_map(|result| result.trades.iter_mut())
+        .collect();
+    flag(trades, linked)
+}
+
+/// Кто отдал вход: authority входной ноги, иначе trader.
+fn input_owner(trade: &TradeInfo) -> Option<&str> {
+    trade
+        .input_token
+        .authority
+        .as_deref()
+        .or_else(|| trader(trade))
+}
+
+/// Кто получил выход: владелец token account назначения, иначе trader.
+fn output_owner(trade: &TradeInfo) -> Option<&str> {
+    trade
+        .output_token
+        .destination_owner
+        .as_deref()
+        .or_else(|| trader(trade))
+}
+
+/// Встречная пара: вход одной — выход другой, в одном пуле.
+fn opposite(a: &TradeInfo, b: &TradeInfo) -> bool {
+    a.input_token.mint == b.output_token.mint
+        && a.output_token.mint == b.input_token.mint
+        && a.pool
+            .first()
+            .is_some_and(|pool| b.pool.first() == Some(pool))
+}
+
+fn flag(mut trades: Vec<&mut TradeInfo>, linked: &LinkedWallets) -> usize {
+    let mut self_trades = vec![false; trades.len()];
+    for (i, a) in trades.iter().enumerate() {
+        for (j, b) in trades.iter().enumerate().skip(i + 1) {
+            if !opposite(a, b) {
+                continue;
+            }
+            let round_trip = |from: &TradeInfo, to: &TradeInfo| {
+                matches!(
+                    (input_owner(from), output_owner(to)),
+                    (Some(from), Some(to)) if linked.same_owner(from, to)
+                )
+            };
+            if round_trip(a, b) || round_trip(b, a) {
+                self_trades[i] = true;
+                self_trades[j] = true;
+            }
+        }
+    }
+
+    let mut flagged = 0;
+    for (trade, _) in trades
+        .iter_mut()
+        .zip(self_trades)
+        .filter(|(_, self_trade)| *self_trade)
+    {
+        if !trade.flags.contains(&TradeFlag::SelfTrade) {
+            trade.flags.push(TradeFlag::SelfTrade);
+            flagged += 1;
+        }
+    }
+    flagged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::constants::TOKENS;
+    use crate::types::TokenInfo;
+
+    fn trade(user: &str, input: &str, output: &str) -> TradeInfo {
+        let token = |mint: &str| TokenInfo {
+            mint: mint.to_string(),
+            amount: 1.0,
+            ..Default::default()
+        };
+        TradeInfo {
+            input_token: token(input),
+            output_token: token(output),
+            pool: vec!["pool".to_string()],
+            user: Some(user.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn flags_round_trip_between_linked_wallets() {
+        let mut bundle = vec![
+            ParseResult {
+                state: true,
+                trades: vec![trade("bot-a", TOKENS.SOL, "MEME")],
+                ..Default::default()
+            },
+            ParseResult {
+                state: true,
+                trades: vec![
+                    trade("bot-b", "MEME", TOKENS.SOL),
+                    trade("stranger", "MEME", TOKENS.SOL),
+                ],
+                ..Default::default()
+            },
+        ];
+        assert_eq!(
+            flag_bundle_self_trades(&mut bundle, &LinkedWallets::new()),
+            0
+        );
+
+        let mut linked = LinkedWallets::new();
+        linked.link(["bot-a", "bot-b"]);
+        assert_eq!(flag_bundle_self_trades(&mut bundle, &linked), 2);
+        assert_eq!(bundle[0].trades[0].flags, [TradeFlag::SelfTrade]);
+        assert_eq!(bundle[1].trades[0].flags, [TradeFlag::SelfTrade]);
+        assert!(bundle[1].trades[1].flags.is_empty());
+    }
+}
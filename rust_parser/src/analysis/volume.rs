@@ -0,0 +1,248 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{ParseResult, TradeInfo, TransactionStatus};
+
+use super::trader;
+
+/// Скользящие окна агрегатора; время — `timestamp` сделок (секунды), а не часы процесса.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VolumeWindow {
+    #[serde(rename = "1m")]
+    OneMinute,
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "1h")]
+    OneHour,
+}
+
+impl VolumeWindow {
+    pub const ALL: [VolumeWindow; 3] = [Self::OneMinute, Self::FiveMinutes, Self::OneHour];
+
+    pub fn seconds(self) -> u64 {
+        match self {
+            Self::OneMinute => 60,
+            Self::FiveMinutes => 300,
+            Self::OneHour => 3_600,
+        }
+    }
+}
+
+/// Объём за окно. Для пула `volume` — в quote-токене сделки, для mint — в самом mint.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeStats {
+    pub volume: f64,
+    /// Сумма `TradeInfo::value_usd` (только сделки с оценкой).
+    pub volume_usd: f64,
+    pub trade_count: u64,
+    pub unique_traders: u64,
+}
+
+/// Снимок всех ключей: окно → статистика.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeSnapshot {
+    /// Последний `timestamp` среди принятых сделок — конец всех окон.
+    pub timestamp: u64,
+    pub pools: HashMap<String, HashMap<VolumeWindow, VolumeStats>>,
+    pub mints: HashMap<String, HashMap<VolumeWindow, VolumeStats>>,
+}
+
+#[derive(Clone, Debug)]
+struct Sample {
+    timestamp: u64,
+    volume: f64,
+    volume_usd: f64,
+    trader: Option<String>,
+}
+
+/// Rolling-объёмы по пулам и mint'ам из потока сделок (`stream`, `parse_block_*`).
+/// При приёме подрезается только затронутый ключ; затихшие ключи убирает `prune`.
+#[derive(Clone, Debug, Default)]
+pub struct VolumeAggregator {
+    pools: HashMap<String, VecDeque<Sample>>,
+    mints: HashMap<String, VecDeque<Sample>>,
+    now: u64,
+}
+
+impl VolumeAggregator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ingest(&mut self, result: &ParseResult) {
+        if !result.state || result.tx_status == TransactionStatus::Failed {
+            return;
+        }
+        for trade in &result.trades {
+            self.apply_trade(trade);
+        }
+    }
+
+    pub fn ingest_all<'a>(&mut self, results: impl IntoIterator<Item = &'a ParseResult>) {
+        for result in results {
+            self.ingest(result);
+        }
+    }
+
+    pub fn apply_trade(&mut self, trade: &TradeInfo) {
+        // опоздавшая сделка уже вне всех окон
+        if self.now > 0 && trade.timestamp <= self.horizon() {
+            return;
+        }
+        self.now = self.now.max(trade.timestamp);
+
+        let sample = |volume: f64| Sample {
+            timestamp: trade.timestamp,
+            volume,
+            volume_usd: trade.value_usd.unwrap_or(0.0),
+            trader: trader(trade).map(ToString::to_string),
+        };
+        if let Some(pool) = trade.pool.first() {
+            let quote_mint = trade.quote_mint();
+            let quote = if trade.input_token.mint == quote_mint {
+                &trade.input_token
+            } else {
+                &trade.output_token
+            };
+            let horizon = self.horizon();
+            push(&mut self.pools, pool, sample(quote.amount), horizon);
+        }
+        for token in [&trade.input_token, &trade.output_token] {
+            if !token.mint.is_empty() {
+                let horizon = self.horizon();
+                push(&mut self.mints, &token.mint, sample(token.amount), horizon);
+            }
+        }
+    }
+
+    pub fn pool_stats(&self, pool: &str, window: VolumeWindow) -> VolumeStats {
+        self.pools
+            .get(pool)
+            .map(|samples| self.stats(samples, window))
+            .unwrap_or_default()
+    }
+
+    pub fn mint_stats(&self, mint: &str, window: VolumeWindow) -> VolumeStats {
+        self.mints
+            .get(mint)
+            .map(|samples| self.stats(samples, window))
+            .unwrap_or_default()
+    }
+
+    pub fn snapshot(&self) -> VolumeSnapshot {
+        let export = |keys: &HashMap<String, VecDeque<Sample>>| {
+            keys.iter()
+                .filter(|(_, samples)| samples.iter().any(|s| s.timestamp > self.horizon()))
+                .map(|(key, samples)| {
+                    let windows = VolumeWindow::ALL
+                        .iter()
+                        .map(|&window| (window, self.stats(samples, window)))
+                        .collect();
+                    (key.clone(), windows)
+                })
+                .collect()
+        };
+        VolumeSnapshot {
+            timestamp: self.now,
+            pools: export(&self.pools),
+            mints: export(&self.mints),
+        }
+    }
+
+    /// Начало самого длинного окна.
+    fn horizon(&self) -> u64 {
+        self.now.saturating_sub(VolumeWindow::OneHour.seconds())
+    }
+
+    fn stats(&self, samples: &VecDeque<Sample>, window: VolumeWindow) -> VolumeStats {
+        let start = self.now.saturating_sub(window.seconds());
+        let mut stats = VolumeStats::default();
+        let mut traders = HashSet::new();
+        for sample in samples.iter().filter(|sample| sample.timestamp > start) {
+            stats.volume += sample.volume;
+            stats.volume_usd += sample.volume_usd;
+            stats.trade_count += 1;
+            if let Some(trader) = &sample.trader {
+                traders.insert(trader.as_str());
+            }
+        }
+        stats.unique_traders = traders.len() as u64;
+        stats
+    }
+
+    /// Удаляет сэмплы вне часового окна и опустевшие ключи.
+    pub fn prune(&mut self) {
+        let horizon = self.horizon();
+        for keys in [&mut self.pools, &mut self.mints] {
+            keys.retain(|_, samples| {
+                samples.retain(|sample| sample.timestamp > horizon);
+                !samples.is_empty()
+            });
+        }
+    }
+}
+
+fn push(keys: &mut HashMap<String, VecDeque<Sample>>, key: &str, sample: Sample, horizon: u64) {
+    let samples = keys.entry(key.to_string()).or_default();
+    // сделки приходят почти по порядку: старые сэмплы — в начале очереди
+    while samples
+        .front()
+        .is_some_and(|front| front.timestamp <= horizon)
+    {
+        samples.pop_front();
+    }
+    samples.push_back(sample);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::constants::TOKENS;
+    use crate::types::TokenInfo;
+
+    fn trade(timestamp: u64, user: &str, sol: f64) -> TradeInfo {
+        let token = |mint: &str, amount: f64| TokenInfo {
+            mint: mint.to_string(),
+            amount,
+            ..Default::default()
+        };
+        TradeInfo {
+            input_token: token(TOKENS.SOL, sol),
+            output_token: token("MEME", 1_000.0),
+            pool: vec!["pool".to_string()],
+            user: Some(user.to_string()),
+            timestamp,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rolls_volume_windows_by_trade_time() {
+        let mut aggregator = VolumeAggregator::new();
+        aggregator.apply_trade(&trade(1_000, "a", 1.0));
+        aggregator.apply_trade(&trade(1_200, "b", 2.0));
+        aggregator.apply_trade(&trade(1_290, "a", 3.0));
+
+        let minute = aggregator.pool_stats("pool", VolumeWindow::OneMinute);
+        assert_eq!((minute.trade_count, minute.unique_traders), (1, 1));
+        let five = aggregator.pool_stats("pool", VolumeWindow::FiveMinutes);
+        assert_eq!((five.trade_count, five.unique_traders), (3, 2));
+        assert!((five.volume - 6.0).abs() < 1e-9);
+        let meme = aggregator.mint_stats("MEME", VolumeWindow::OneHour);
+        assert!((meme.volume - 3_000.0).abs() < 1e-9);
+
+        // через час первая сделка выпадает из всех окон
+        aggregator.apply_trade(&trade(4_700, "c", 1.0));
+        let hour = aggregator.pool_stats("pool", VolumeWindow::OneHour);
+        assert_eq!((hour.trade_count, hour.unique_traders), (3, 3));
+        let snapshot = aggregator.snapshot();
+        assert_eq!(snapshot.timestamp, 4_700);
+        assert_eq!(
+            snapshot.pools["pool"][&VolumeWindow::OneMinute].trade_count,
+            1
+        );
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{ParseResult, TradeInfo, TransactionStatus};
+
+use super::trader;
+
+/// Позиция кошелька в одном mint; стоимость — в quote-токене первой сделки.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub mint: String,
+    pub quote_mint: String,
+    /// Текущий остаток, UI amount.
+    pub amount: f64,
+    /// Средневзвешенная стоимость остатка в quote.
+    pub cost_basis: f64,
+    pub realized_pnl: f64,
+    /// Цена последней сделки по mint (quote за единицу) — метка для unrealized PnL.
+    pub last_price: f64,
+    pub bought: f64,
+    pub sold: f64,
+}
+
+impl Position {
+    pub fn average_cost(&self) -> f64 {
+        if self.amount > 0.0 {
+            self.cost_basis / self.amount
+        } else {
+            0.0
+        }
+    }
+
+    pub fn unrealized_pnl_at(&self, price: f64) -> f64 {
+        self.amount * price - self.cost_basis
+    }
+
+    pub fn unrealized_pnl(&self) -> f64 {
+        self.unrealized_pnl_at(self.last_price)
+    }
+
+    fn buy(&mut self, amount: f64, cost: f64) {
+        self.amount += amount;
+        self.cost_basis += cost;
+        self.bought += amount;
+    }
+
+    /// Продажа сверх известного остатка (купленного до начала потока) идёт с нулевой стоимостью.
+    fn sell(&mut self, amount: f64, proceeds: f64) {
+        let covered = amount.min(self.amount);
+        let cost = self.average_cost() * covered;
+        self.realized_pnl += proceeds - cost;
+        self.cost_basis -= cost;
+        self.amount -= covered;
+        self.sold += amount;
+    }
+}
+
+/// Позиции и PnL по кошелькам из потока `ParseResult` (по `trades`, в порядке подачи).
+#[derive(Clone, Debug, Default)]
+pub struct WalletTracker {
+    wallets: HashMap<String, HashMap<String, Position>>,
+}
+
+impl WalletTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ingest(&mut self, result: &ParseResult) {
+        if !result.state || result.tx_status == TransactionStatus::Failed {
+            return;
+        }
+        for trade in &result.trades {
+            self.apply_trade(trade);
+        }
+    }
+
+    pub fn ingest_all<'a>(&mut self, results: impl IntoIterator<Item = &'a ParseResult>) {
+        for result in results {
+            self.ingest(result);
+        }
+    }
+
+    pub fn apply_trade(&mut self, trade: &TradeInfo) {
+        let Some(wallet) = trader(trade) else {
+            return;
+        };
+        let quote_mint = trade.quote_mint();
+        let is_buy = trade.input_token.mint == quote_mint;
+        let (quote, base) = if is_buy {
+            (&trade.input_token, &trade.output_token)
+        } else {
+            (&trade.output_token, &trade.input_token)
+        };
+        if base.amount <= 0.0 || base.mint == quote.mint {
+            return;
+        }
+
+        let position = self
+            .wallets
+            .entry(wallet.to_string())
+            .or_default()
+            .entry(base.mint.clone())
+            .or_insert_with(|| Position {
+                mint: base.mint.clone(),
+                quote_mint: quote.mint.clone(),
+                ..Default::default()
+            });
+        // Стоимость в разных quote не складываем: сделки в другом quote пропускаем
+        if position.quote_mint != quote.mint {
+            return;
+        }
+
+        position.last_price = quote.amount / base.amount;
+        if is_buy {
+            position.buy(base.amount, quote.amount);
+        } else {
+            position.sell(base.amount, quote.amount);
+        }
+    }
+
+    pub fn position(&self, wallet: &str, mint: &str) -> Option<&Position> {
+        self.wallets.get(wallet)?.get(mint)
+    }
+
+    pub fn positions(&self, wallet: &str) -> impl Iterator<Item = &Position> {
+        self.wallets.get(wallet).into_iter().flat_map(HashMap::values)
+    }
+
+    pub fn wallets(&self) -> impl Iterator<Item = &str> {
+        self.wallets.keys().map(String::as_str)
+    }
+
+    /// Realized PnL кошелька по quote mint.
+    pub fn realized_pnl(&self, wallet: &str) -> HashMap<String, f64> {
+        self.sum_by_quote(wallet, |position| position.realized_pnl)
+    }
+
+    /// Unrealized PnL по цене последней сделки; `prices` (quote за единицу, ключ — mint)
+    /// переопределяет метку.
+    pub fn unrealized_pnl(&self, wallet: &str, prices: &HashMap<String, f64>) -> HashMap<String, f64> {
+        self.sum_by_quote(wallet, |position| match prices.get(&position.mint) {
+            Some(price) => position.unrealized_pnl_at(*price),
+            None => position.unrealized_pnl(),
+        })
+    }
+
+    fn sum_by_quote(&self, wallet: &str, value: impl Fn(&Position) -> f64) -> HashMap<String, f64> {
+        let mut totals = HashMap::new();
+        for position in self.positions(wallet) {
+            *totals.entry(position.quote_mint.clone()).or_insert(0.0) += value(position);
+        }
+        totals
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::constants::TOKENS;
+    use crate::types::TokenInfo;
+
+    fn trade(input: (&str, f64), output: (&str, f64)) -> TradeInfo {
+        let token = |(mint, amount): (&str, f64)| TokenInfo {
+            mint: mint.to_string(),
+            amount,
+            ..Default::default()
+        };
+        TradeInfo {
+            input_token: token(input),
+            output_token: token(output),
+            user: Some("wallet".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn tracks_cost_basis_and_realized_pnl() {
+        let mut tracker = WalletTracker::new();
+        tracker.apply_trade(&trade((TOKENS.SOL, 1.0), ("MEME", 100.0)));
+        tracker.apply_trade(&trade((TOKENS.SOL, 3.0), ("MEME", 100.0)));
+        // средняя стоимость 0.02 SOL, продаём половину по 0.05
+        tracker.apply_trade(&trade(("MEME", 100.0), (TOKENS.SOL, 5.0)));
+
+        let position = tracker.position("wallet", "MEME").expect("position");
+        assert_eq!(position.quote_mint, TOKENS.SOL);
+        assert!((position.amount - 100.0).abs() < 1e-9);
+        assert!((position.cost_basis - 2.0).abs() < 1e-9);
+        assert!((position.realized_pnl - 3.0).abs() < 1e-9);
+        // метка — последняя цена 0.05
+        assert!((position.unrealized_pnl() - 3.0).abs() < 1e-9);
+
+        let prices = HashMap::from([("MEME".to_string(), 0.01)]);
+        let unrealized = tracker.unrealized_pnl("wallet", &prices);
+        assert!((unrealized[TOKENS.SOL] + 1.0).abs() < 1e-9);
+        assert!((tracker.realized_pnl("wallet")[TOKENS.SOL] - 3.0).abs() < 1e-9);
+    }
+}
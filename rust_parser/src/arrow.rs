@@ -0,0 +1,325 @@
+//! Вывод парсера в Arrow `RecordBatch` (feature `arrow`): для Parquet-писателей
+//! и DataFusion без ручного маппинга строк.
+//!
+//! Схемы плоские, одна строка — одна сделка / событие / перевод:
+//! - `trade_schema` — `TradeInfo`: input/output токены развёрнуты в `input_*`/`output_*`,
+//!   `pool` — `List<Utf8>`, `fee_*` — из `TradeInfo::fee`;
+//! - `pool_event_schema` — `PoolEvent`: первые две ноги в `token0_*`/`token1_*`
+//!   (остальные ноги многотокенных пулов не попадают);
+//! - `transfer_schema` — `TransferData`: `info` развёрнут, сумма в `amount_raw`/`ui_amount`.
+//!
+//! Enum'ы пишутся строками как в JSON (`BUY`, `ADD`, ...); `Option` — nullable колонки.
+
+use std::sync::Arc;
+
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::{
+    ArrayRef, BooleanArray, Float64Array, RecordBatch, StringArray, UInt64Array, UInt8Array,
+};
+use arrow_schema::{ArrowError, DataType, Field, Schema, SchemaRef};
+
+use crate::types::{PoolEvent, TokenLeg, TradeInfo, TransferData};
+
+fn utf8(name: &str, nullable: bool) -> Field {
+    Field::new(name, DataType::Utf8, nullable)
+}
+
+fn u64_field(name: &str) -> Field {
+    Field::new(name, DataType::UInt64, false)
+}
+
+fn f64_field(name: &str, nullable: bool) -> Field {
+    Field::new(name, DataType::Float64, nullable)
+}
+
+fn u8_field(name: &str, nullable: bool) -> Field {
+    Field::new(name, DataType::UInt8, nullable)
+}
+
+fn strings<'a, T: 'a>(rows: &'a [T], get: impl Fn(&'a T) -> Option<&'a str>) -> ArrayRef {
+    Arc::new(rows.iter().map(get).collect::<StringArray>())
+}
+
+/// Схема `trades_to_record_batch`.
+pub fn trade_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        utf8("signature", false),
+        u64_field("slot"),
+        u64_field("timestamp"),
+        utf8("idx", false),
+        utf8("trade_type", false),
+        utf8("user", true),
+        utf8("program_id", true),
+        utf8("amm", true),
+        utf8("route", true),
+        Field::new(
+            "pool",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            false,
+        ),
+        utf8("input_mint", false),
+        f64_field("input_amount", false),
+        utf8("input_amount_raw", false),
+        u8_field("input_decimals", false),
+        utf8("output_mint", false),
+        f64_field("output_amount", false),
+        utf8("output_amount_raw", false),
+        u8_field("output_decimals", false),
+        utf8("fee_mint", true),
+        f64_field("fee_amount", true),
+        utf8("fee_amount_raw", true),
+        Field::new("slippage_bps", DataType::UInt64, true),
+        f64_field("price_quote_per_base", true),
+        f64_field("value_usd", true),
+    ]))
+}
+
+pub fn trades_to_record_batch(trades: &[TradeInfo]) -> Result<RecordBatch, ArrowError> {
+    let mut pool = ListBuilder::new(StringBuilder::new());
+    for trade in trades {
+        for address in &trade.pool {
+            pool.values().append_value(address);
+        }
+        pool.append(true);
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        strings(trades, |t| Some(t.signature.as_str())),
+        Arc::new(trades.iter().map(|t| t.slot).collect::<UInt64Array>()),
+        Arc::new(trades.iter().map(|t| t.timestamp).collect::<UInt64Array>()),
+        strings(trades, |t| Some(t.idx.as_str())),
+        strings(trades, |t| Some(t.trade_type.as_str())),
+        strings(trades, |t| t.user.as_deref()),
+        strings(trades, |t| t.program_id.as_deref()),
+        strings(trades, |t| t.amm.as_deref()),
+        strings(trades, |t| t.route.as_deref()),
+        Arc::new(pool.finish()),
+        strings(trades, |t| Some(t.input_token.mint.as_str())),
+        Arc::new(
+            trades
+                .iter()
+                .map(|t| t.input_token.amount)
+                .collect::<Float64Array>(),
+        ),
+        strings(trades, |t| Some(t.input_token.amount_raw.as_str())),
+        Arc::new(
+            trades
+                .iter()
+                .map(|t| t.input_token.decimals)
+                .collect::<UInt8Array>(),
+        ),
+        strings(trades, |t| Some(t.output_token.mint.as_str())),
+        Arc::new(
+            trades
+                .iter()
+                .map(|t| t.output_token.amount)
+                .collect::<Float64Array>(),
+        ),
+        strings(trades, |t| Some(t.output_token.amount_raw.as_str())),
+        Arc::new(
+            trades
+                .iter()
+                .map(|t| t.output_token.decimals)
+                .collect::<UInt8Array>(),
+        ),
+        strings(trades, |t| t.fee.as_ref().map(|fee| fee.mint.as_str())),
+        Arc::new(
+            trades
+                .iter()
+                .map(|t| t.fee.as_ref().map(|fee| fee.amount))
+                .collect::<Float64Array>(),
+        ),
+        strings(trades, |t| {
+            t.fee.as_ref().map(|fee| fee.amount_raw.as_str())
+        }),
+        Arc::new(
+            trades
+                .iter()
+                .map(|t| t.slippage_bps)
+                .collect::<UInt64Array>(),
+        ),
+        Arc::new(
+            trades
+                .iter()
+                .map(|t| t.price_quote_per_base)
+                .collect::<Float64Array>(),
+        ),
+        Arc::new(trades.iter().map(|t| t.value_usd).collect::<Float64Array>()),
+    ];
+    RecordBatch::try_new(trade_schema(), columns)
+}
+
+/// Схема `pool_events_to_record_batch`.
+pub fn pool_event_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        utf8("signature", false),
+        u64_field("slot"),
+        u64_field("timestamp"),
+        utf8("idx", false),
+        utf8("event_type", false),
+        utf8("user", false),
+        utf8("program_id", true),
+        utf8("amm", true),
+        utf8("pool_id", false),
+        utf8("pool_lp_mint", true),
+        f64_field("lp_amount", true),
+        utf8("lp_amount_raw", true),
+        utf8("token0_mint", true),
+        f64_field("token0_amount", true),
+        utf8("token0_amount_raw", true),
+        u8_field("token0_decimals", true),
+        utf8("token1_mint", true),
+        f64_field("token1_amount", true),
+        utf8("token1_amount_raw", true),
+        u8_field("token1_decimals", true),
+        f64_field("value_usd", true),
+    ]))
+}
+
+fn leg_columns(events: &[PoolEvent], leg: fn(&PoolEvent) -> Option<&TokenLeg>) -> [ArrayRef; 4] {
+    [
+        strings(events, move |e| leg(e).map(|l| l.mint.as_str())),
+        Arc::new(
+            events
+                .iter()
+                .map(|e| leg(e).map(|l| l.amount))
+                .collect::<Float64Array>(),
+        ),
+        strings(events, move |e| leg(e).map(|l| l.amount_raw.as_str())),
+        Arc::new(
+            events
+                .iter()
+                .map(|e| leg(e).map(|l| l.decimals))
+                .collect::<UInt8Array>(),
+        ),
+    ]
+}
+
+pub fn pool_events_to_record_batch(events: &[PoolEvent]) -> Result<RecordBatch, ArrowError> {
+    let mut columns: Vec<ArrayRef> = vec![
+        strings(events, |e| Some(e.signature.as_str())),
+        Arc::new(events.iter().map(|e| e.slot).collect::<UInt64Array>()),
+        Arc::new(events.iter().map(|e| e.timestamp).collect::<UInt64Array>()),
+        strings(events, |e| Some(e.idx.as_str())),
+        strings(events, |e| Some(e.event_type.as_str())),
+        strings(events, |e| Some(e.user.as_str())),
+        strings(events, |e| e.program_id.as_deref()),
+        strings(events, |e| e.amm.as_deref()),
+        strings(events, |e| Some(e.pool_id.as_str())),
+        strings(events, |e| e.pool_lp_mint.as_deref()),
+        Arc::new(events.iter().map(|e| e.lp_amount).collect::<Float64Array>()),
+        strings(events, |e| e.lp_amount_raw.as_deref()),
+    ];
+    columns.extend(leg_columns(events, PoolEvent::token0));
+    columns.extend(leg_columns(events, PoolEvent::token1));
+    columns.push(Arc::new(
+        events.iter().map(|e| e.value_usd).collect::<Float64Array>(),
+    ));
+    RecordBatch::try_new(pool_event_schema(), columns)
+}
+
+/// Схема `transfers_to_record_batch`.
+pub fn transfer_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        utf8("signature", false),
+        u64_field("timestamp"),
+        utf8("idx", false),
+        utf8("transfer_type", false),
+        utf8("program_id", false),
+        utf8("mint", false),
+        utf8("source", false),
+        utf8("destination", false),
+        utf8("authority", true),
+        utf8("destination_owner", true),
+        utf8("amount_raw", false),
+        u8_field("decimals", false),
+        f64_field("ui_amount", true),
+        Field::new("is_fee", DataType::Boolean, false),
+    ]))
+}
+
+pub fn transfers_to_record_batch(transfers: &[TransferData]) -> Result<RecordBatch, ArrowError> {
+    let columns: Vec<ArrayRef> = vec![
+        strings(transfers, |t| Some(t.signature.as_str())),
+        Arc::new(
+            transfers
+                .iter()
+                .map(|t| t.timestamp)
+                .collect::<UInt64Array>(),
+        ),
+        strings(transfers, |t| Some(t.idx.as_str())),
+        strings(transfers, |t| Some(t.transfer_type.as_str())),
+        strings(transfers, |t| Some(t.program_id.as_str())),
+        strings(transfers, |t| Some(t.info.mint.as_str())),
+        strings(transfers, |t| Some(t.info.source.as_str())),
+        strings(transfers, |t| Some(t.info.destination.as_str())),
+        strings(transfers, |t| t.info.authority.as_deref()),
+        strings(transfers, |t| t.info.destination_owner.as_deref()),
+        strings(transfers, |t| Some(t.info.token_amount.amount.as_str())),
+        Arc::new(
+            transfers
+                .iter()
+                .map(|t| t.info.token_amount.decimals)
+                .collect::<UInt8Array>(),
+        ),
+        Arc::new(
+            transfers
+                .iter()
+                .map(|t| t.info.token_amount.ui_amount)
+                .collect::<Float64Array>(),
+        ),
+        Arc::new(
+            transfers
+                .iter()
+                .map(|t| Some(t.is_fee))
+                .collect::<BooleanArray>(),
+        ),
+    ];
+    RecordBatch::try_new(transfer_schema(), columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::UInt64Type;
+
+    use super::*;
+    use crate::types::{TokenInfo, TradeType};
+
+    #[test]
+    fn trades_batch_matches_schema() {
+        let trade = TradeInfo {
+            trade_type: TradeType::Buy,
+            pool: vec!["pool".to_string()],
+            input_token: TokenInfo {
+                mint: "So11111111111111111111111111111111111111112".to_string(),
+                amount: 1.5,
+                amount_raw: "1500000000".to_string(),
+                decimals: 9,
+                ..Default::default()
+            },
+            slot: 42,
+            signature: "sig".to_string(),
+            idx: "0-1".to_string(),
+            ..Default::default()
+        };
+        let batch = trades_to_record_batch(&[trade.clone(), trade]).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.schema(), trade_schema());
+
+        let trade_type = batch
+            .column_by_name("trade_type")
+            .unwrap()
+            .as_string::<i32>();
+        assert_eq!(trade_type.value(0), "BUY");
+        let slot = batch
+            .column_by_name("slot")
+            .unwrap()
+            .as_primitive::<UInt64Type>();
+        assert_eq!(slot.value(1), 42);
+        assert!(batch.column_by_name("user").unwrap().is_null(0));
+
+        assert_eq!(pool_events_to_record_batch(&[]).unwrap().num_rows(), 0);
+        assert_eq!(transfers_to_record_batch(&[]).unwrap().num_rows(), 0);
+    }
+}
@@ -1,43 +1,24 @@
-// cargo run --release --bin analog
+// cargo run --release --bin analog -- --api-key <key>
+//
+// Endpoints: --ws-url (повторяемый), SOLANA_WS_URL, HELIUS_API_KEY или --config <toml>,
+// см. config::RuntimeConfig
 //
 // Rust analog of test.ts - WebSocket DEX parser with full timing breakdown
 // Subscribes to Helius WebSocket and parses transactions using DexParser
 
 use anyhow::{Context, Result};
-use base64_simd::STANDARD as B64;
-use bincode::deserialize;
-use bs58;
 use futures_util::{SinkExt, StreamExt};
-use serde_json::{json, Value};
-use solana_dex_parser::config::ParseConfig;
+use serde_json::Value;
+use solana_dex_parser::config::{redact_url, ParseConfig, RuntimeConfig};
 use solana_dex_parser::core::dex_parser::DexParser;
-use solana_dex_parser::types::{
-    BalanceChange, InnerInstruction, SolanaInstruction, SolanaTransaction, TokenAmount,
-    TokenBalance, TransactionMeta, TransactionStatus,
-};
-use solana_sdk::transaction::VersionedTransaction;
-use std::collections::HashMap;
+use solana_dex_parser::rpc::ws::{convert_binary_to_solana_tx, extract_base64_tx, Commitment};
+use solana_dex_parser::rpc::{connect_with_failover, SubscriberConfig, SubscriptionFilter, TransactionSubscriber};
+use solana_dex_parser::{ProgramCatalog, ProtocolFamily};
+use solana_dex_parser::types::TransactionStatus;
 use std::time::Instant;
 use tokio::time::{interval, Duration, MissedTickBehavior};
 use tokio_tungstenite::tungstenite::Message;
 
-
-const API_KEY: &str = "767f42d9-06c2-46f8-8031-9869035d6ce4";
-// Pumpfun и Meteor program IDs для парсинга
-const ACCOUNT_INCLUDE: &[&str] = &[
-    // Pumpfun
-    "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P",
-    // Pumpswap
-    "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA",
-    // Meteor DLMM
-    "LBUZKhRxPF3XUpBCjp4YzTKgLccjZhTSDM9YuVaPwxo",
-    // Meteor DAMM
-    "Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB",
-    // Meteor DAMM V2
-    "cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG",
-    // Meteor DBC
-    "dbcij3LWUppWqq96dh6gJWwBifmcGfLSB5D4DuSMaqN",
-];
 const MAX_EVENTS: usize = 50;
 const VERBOSE_JSON: bool = false;
 const WSOL: &str = "So11111111111111111111111111111111111111112";
@@ -53,33 +34,29 @@ async fn main() -> Result<()> {
         .with_max_level(tracing::Level::INFO)
         .init();
     
-    let ws_url = format!("wss://atlas-mainnet.helius-rpc.com/?api-key={}", API_KEY);
-    println!("🔌 Connecting to {}", ws_url);
+    let (runtime, _) = RuntimeConfig::load(std::env::args().skip(1))?;
+    let endpoints = runtime.ws_failover()?;
 
-    let (ws_stream, _) = tokio_tungstenite::connect_async(&ws_url)
-        .await
-        .context("WebSocket connection failed")?;
+    println!("🔌 Connecting to {}", redact_url(endpoints.current()));
+    let ws_stream = connect_with_failover(&endpoints).await?;
     let (mut sink, mut stream) = ws_stream.split();
 
+    // Pumpfun/Pumpswap и программы Meteora
+    let account_include: Vec<String> = ProgramCatalog::family(ProtocolFamily::Pump)
+        .chain(ProgramCatalog::family(ProtocolFamily::Meteora))
+        .map(|program| program.id.to_string())
+        .collect();
+
     // Subscribe: base64 + full + v0 support
-    let sub = json!({
-        "jsonrpc": "2.0",
-        "id": 1,
-        "method": "transactionSubscribe",
-        "params": [
-            {
-                "accountInclude": ACCOUNT_INCLUDE,
-                "vote": false,
-                "failed": false
-            },
-            {
-                "commitment": "processed",
-                "encoding": "base64",
-                "transactionDetails": "full",
-                "maxSupportedTransactionVersion": 0
-            }
-        ]
-    });
+    let sub = TransactionSubscriber::new(SubscriberConfig {
+        filter: SubscriptionFilter {
+            account_include,
+            ..Default::default()
+        },
+        commitment: Commitment::Processed,
+        ..Default::default()
+    })
+    .subscription_request();
 
     sink.send(Message::Text(sub.to_string()))
         .await
@@ -134,13 +111,8 @@ async fn main() -> Result<()> {
         };
 
         // === 2️⃣ Decode base64 transaction ===
-        let tx_raw = r
-            .pointer("/transaction/transaction")
-            .or_else(|| r.get("transaction"));
-        let mut t_decoded = t_json_parsed;
-
-        let tx = match extract_and_decode_tx(tx_raw, r, t_json_parsed, &mut t_decoded) {
-            Ok(Some(tx)) => tx,
+        let raw_tx = match extract_base64_tx(r) {
+            Ok(Some(bytes)) => bytes,
             Ok(None) => {
                 eprintln!("⚠️ decode failed: transaction is not in base64 format");
                 continue;
@@ -150,9 +122,24 @@ async fn main() -> Result<()> {
                 continue;
             }
         };
+        let t_decoded = Instant::now();
 
-        // === 3️⃣ Prepare txLike and call parser ===
         let slot = r.get("slot").and_then(|s| s.as_u64()).unwrap_or(0);
+        let meta = r.pointer("/transaction/meta").or_else(|| r.get("meta"));
+        let tx = match convert_binary_to_solana_tx(
+            &raw_tx,
+            slot,
+            r.get("signature").and_then(|s| s.as_str()).unwrap_or("unknown"),
+            meta,
+        ) {
+            Ok(tx) => tx,
+            Err(e) => {
+                eprintln!("⚠️ decode failed: {:#}", e);
+                continue;
+            }
+        };
+
+        // === 3️⃣ Prepare txLike and call parser ===
         let block_time = r
             .get("blockTime")
             .and_then(|b| b.as_u64())
@@ -165,7 +152,7 @@ async fn main() -> Result<()> {
 
         // Сохраняем информацию о программах из инструкций для вывода ошибок
         let tx_programs: Vec<String> = tx.instructions.iter()
-            .map(|ix| ix.program_id.clone())
+            .map(|ix| ix.program_id.to_string())
             .collect();
 
         let t_parse0 = Instant::now();
@@ -220,7 +207,7 @@ async fn main() -> Result<()> {
 
         // Вывод деталей ошибки, если транзакция провалилась
         if res.tx_status == TransactionStatus::Failed {
-            if let Some(meta) = r.pointer("/transaction/meta").or_else(|| r.get("meta")) {
+            if let Some(meta) = meta {
                 if let Some(err) = meta.get("err") {
                     let err_str = format_error(err);
                     println!("❌ Error: {}", err_str);
@@ -460,407 +447,6 @@ fn format_error(err: &Value) -> String {
     }
 }
 
-/// Extract and decode transaction from WebSocket result
-/// Returns (transaction, decode_time)
-fn extract_and_decode_tx(
-    tx_raw: Option<&Value>,
-    result: &Value,
-    t_json_parsed: Instant,
-    t_decoded: &mut Instant,
-) -> Result<Option<SolanaTransaction>> {
-    if let Some(arr) = tx_raw.and_then(|v| v.as_array()) {
-        if arr.len() == 2 {
-            if let (Some(b64), Some(enc)) = (arr[0].as_str(), arr[1].as_str()) {
-                if enc == "base64" {
-                    let raw_bytes = B64.decode_to_vec(b64).context("base64 decode failed")?;
-                    *t_decoded = Instant::now();
-                    let meta = result
-                        .pointer("/transaction/meta")
-                        .or_else(|| result.get("meta"));
-                    let signature = result
-                        .get("signature")
-                        .and_then(|s| s.as_str())
-                        .unwrap_or("unknown");
-                    let slot = result.get("slot").and_then(|s| s.as_u64()).unwrap_or(0);
-                    let tx = convert_binary_to_solana_tx(&raw_bytes, slot, signature, meta)?;
-                    return Ok(Some(tx));
-                }
-            }
-        }
-    }
-    // No decoding needed, time is same as JSON parse
-    *t_decoded = t_json_parsed;
-    Ok(None)
-}
-
-/// Convert binary transaction bytes to SolanaTransaction
-fn convert_binary_to_solana_tx(
-    bytes: &[u8],
-    slot: u64,
-    signature: &str,
-    meta: Option<&Value>,
-) -> Result<SolanaTransaction> {
-    // Deserialize binary transaction
-    let versioned_tx: VersionedTransaction = deserialize(bytes)
-        .context("Failed to deserialize VersionedTransaction")?;
-
-    let message = &versioned_tx.message;
-    let account_keys = message.static_account_keys();
-
-    // Extract signers (first N accounts where N = num_required_signatures)
-    let num_signatures = message.header().num_required_signatures as usize;
-    let signers: Vec<String> = account_keys
-        .iter()
-        .take(num_signatures)
-        .map(|pk| bs58::encode(pk.as_ref()).into_string())
-        .collect();
-
-    // Extract all account keys (static + loaded from ALT if v0)
-    let mut all_account_keys: Vec<String> = account_keys
-        .iter()
-        .map(|pk| bs58::encode(pk.as_ref()).into_string())
-        .collect();
-
-    // Add loaded addresses from ALT if present
-    if let Some(meta_val) = meta {
-        if let Some(loaded) = meta_val.pointer("/loadedAddresses") {
-            if let Some(writable) = loaded.get("writable").and_then(|v| v.as_array()) {
-                for addr in writable {
-                    if let Some(s) = addr.as_str() {
-                        all_account_keys.push(s.to_string());
-                    }
-                }
-            }
-            if let Some(readonly) = loaded.get("readonly").and_then(|v| v.as_array()) {
-                for addr in readonly {
-                    if let Some(s) = addr.as_str() {
-                        all_account_keys.push(s.to_string());
-                    }
-                }
-            }
-        }
-    }
-
-    // Extract instructions
-    let instructions: Vec<SolanaInstruction> = message
-        .instructions()
-        .iter()
-        .map(|ix| {
-            let program_id = if (ix.program_id_index as usize) < all_account_keys.len() {
-                all_account_keys[ix.program_id_index as usize].clone()
-            } else {
-                "".to_string()
-            };
-
-            let accounts: Vec<String> = ix
-                .accounts
-                .iter()
-                .filter_map(|&idx| {
-                    if (idx as usize) < all_account_keys.len() {
-                        Some(all_account_keys[idx as usize].clone())
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-
-            // Encode instruction data as base64
-            let data_base64 = B64.encode_to_string(&ix.data);
-
-            SolanaInstruction {
-                program_id,
-                accounts,
-                data: data_base64,
-            }
-        })
-        .collect();
-
-    // Extract inner instructions from meta if present
-    let inner_instructions = if let Some(meta_val) = meta {
-        let inner_ixs = extract_inner_instructions(meta_val, &all_account_keys);
-        tracing::debug!(
-            "Extracted {} inner instruction groups, total instructions: {}",
-            inner_ixs.len(),
-            inner_ixs.iter().map(|g| g.instructions.len()).sum::<usize>()
-        );
-        // Log all program IDs found in inner instructions
-        let mut inner_programs: std::collections::HashSet<String> = std::collections::HashSet::new();
-        for group in &inner_ixs {
-            for ix in &group.instructions {
-                inner_programs.insert(ix.program_id.clone());
-            }
-        }
-        if !inner_programs.is_empty() {
-            tracing::info!(
-                "Found {} unique program IDs in inner instructions: {:?}",
-                inner_programs.len(),
-                inner_programs.iter().collect::<Vec<_>>()
-            );
-        }
-        inner_ixs
-    } else {
-        Vec::new()
-    };
-
-    // Extract token balances from meta if present
-    let (pre_token_balances, post_token_balances) = if let Some(meta_val) = meta {
-        let pre = extract_token_balances(meta_val.pointer("/preTokenBalances"), &all_account_keys);
-        let post = extract_token_balances(meta_val.pointer("/postTokenBalances"), &all_account_keys);
-        tracing::debug!(
-            "Extracted {} pre_token_balances, {} post_token_balances",
-            pre.len(),
-            post.len()
-        );
-        // Log first few balances for debugging
-        if !post.is_empty() {
-            tracing::debug!(
-                "First 3 post_token_balances: {:?}",
-                post.iter().take(3).map(|b| format!("account={}, mint={}", b.account, b.mint)).collect::<Vec<_>>()
-            );
-        }
-        (pre, post)
-    } else {
-        (Vec::new(), Vec::new())
-    };
-
-    // Extract transaction meta
-    let tx_meta = if let Some(meta_val) = meta {
-        extract_transaction_meta(meta_val, &all_account_keys)
-    } else {
-        TransactionMeta {
-            fee: 0,
-            compute_units: 0,
-            status: TransactionStatus::Success,
-            sol_balance_changes: HashMap::new(),
-            token_balance_changes: HashMap::new(),
-        }
-    };
-
-    // Extract block time from meta if present
-    let block_time = meta
-        .and_then(|m| m.get("blockTime").and_then(|v| v.as_u64()))
-        .unwrap_or(0);
-
-    Ok(SolanaTransaction {
-        slot,
-        signature: signature.to_string(),
-        block_time,
-        signers,
-        instructions,
-        inner_instructions,
-        transfers: Vec::new(), // Will be populated by DexParser
-        pre_token_balances,
-        post_token_balances,
-        meta: tx_meta,
-    })
-}
-
-fn extract_inner_instructions(meta: &Value, account_keys: &[String]) -> Vec<InnerInstruction> {
-    let mut result = Vec::new();
-
-    if let Some(inner_arr) = meta.get("innerInstructions").and_then(|v| v.as_array()) {
-        for group in inner_arr {
-            let index = group.get("index").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
-
-            let mut instructions = Vec::new();
-            if let Some(ixs) = group.get("instructions").and_then(|v| v.as_array()) {
-                for ix_val in ixs {
-                    let program_id = ix_val
-                        .get("programId")
-                        .and_then(|v| v.as_str())
-                        .or_else(|| {
-                            ix_val
-                                .get("programIdIndex")
-                                .and_then(|idx| idx.as_u64())
-                                .and_then(|idx| account_keys.get(idx as usize))
-                                .map(|s| s.as_str())
-                        })
-                        .unwrap_or("")
-                        .to_string();
-
-                    let accounts: Vec<String> = if let Some(acc_arr) =
-                        ix_val.get("accounts").and_then(|v| v.as_array())
-                    {
-                        acc_arr
-                            .iter()
-                            .filter_map(|v| {
-                                if let Some(s) = v.as_str() {
-                                    Some(s.to_string())
-                                } else if let Some(idx) = v.as_u64() {
-                                    account_keys.get(idx as usize).cloned()
-                                } else {
-                                    None
-                                }
-                            })
-                            .collect()
-                    } else {
-                        Vec::new()
-                    };
-
-                    // Data might be base58 or base64 - encode as base64 for consistency
-                    let data = ix_val
-                        .get("data")
-                        .and_then(|v| v.as_str())
-                        .map(|s| {
-                            // If it's base58, decode and re-encode as base64
-                            if let Ok(bytes) = bs58::decode(s).into_vec() {
-                                B64.encode_to_string(&bytes)
-                            } else {
-                                // Assume it's already base64 or empty
-                                s.to_string()
-                            }
-                        })
-                        .unwrap_or_default();
-
-                    instructions.push(SolanaInstruction {
-                        program_id,
-                        accounts,
-                        data,
-                    });
-                }
-            }
-
-            if !instructions.is_empty() {
-                result.push(InnerInstruction {
-                    index,
-                    instructions,
-                });
-            }
-        }
-    }
-
-    result
-}
-
-fn extract_token_balances(
-    meta_opt: Option<&Value>,
-    account_keys: &[String],
-) -> Vec<TokenBalance> {
-    let mut result = Vec::new();
-
-    if let Some(balances) = meta_opt.and_then(|v| v.as_array()) {
-        for bal_val in balances {
-            // In TypeScript: const accountKey = this.accountKeys[balance.accountIndex];
-            // So we need to use accountIndex first, then fallback to account as string
-            let account = bal_val
-                .get("accountIndex")
-                .and_then(|v| v.as_u64())
-                .and_then(|idx| account_keys.get(idx as usize))
-                .cloned()
-                .or_else(|| {
-                    // Fallback to account as string
-                    bal_val
-                        .get("account")
-                        .and_then(|v| v.as_str())
-                        .map(|s| s.to_string())
-                })
-                .or_else(|| {
-                    // Last fallback: account as number (index)
-                    bal_val
-                        .get("account")
-                        .and_then(|v| v.as_u64())
-                        .and_then(|idx| account_keys.get(idx as usize))
-                        .cloned()
-                })
-                .unwrap_or_else(|| "".to_string());
-
-            let mint = bal_val
-                .get("mint")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-
-            let owner = bal_val
-                .get("owner")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string());
-
-            let ui_amount = bal_val
-                .get("uiTokenAmount")
-                .and_then(|v| {
-                    let amount = v.get("amount").and_then(|a| a.as_str()).unwrap_or("0");
-                    let decimals = v.get("decimals").and_then(|d| d.as_u64()).unwrap_or(0) as u8;
-                    let ui_amount = v.get("uiAmount").and_then(|u| u.as_f64());
-                    Some(TokenAmount::new(amount, decimals, ui_amount))
-                })
-                .unwrap_or_default();
-
-            result.push(TokenBalance {
-                account,
-                mint,
-                owner,
-                ui_token_amount: ui_amount,
-            });
-        }
-    }
-
-    result
-}
-
-fn extract_transaction_meta(meta: &Value, account_keys: &[String]) -> TransactionMeta {
-    let fee = meta.get("fee").and_then(|v| v.as_u64()).unwrap_or(0);
-
-    let compute_units = meta
-        .get("computeUnitsConsumed")
-        .or_else(|| meta.get("computeUnits"))
-        .and_then(|v| v.as_u64())
-        .unwrap_or(0);
-
-    let status = if meta.get("err").is_some() {
-        TransactionStatus::Failed
-    } else {
-        TransactionStatus::Success
-    };
-
-    let sol_balance_changes = extract_sol_balance_changes(meta, account_keys);
-
-    TransactionMeta {
-        fee,
-        compute_units,
-        status,
-        sol_balance_changes,
-        token_balance_changes: HashMap::new(), // Will be populated by DexParser
-    }
-}
-
-fn extract_sol_balance_changes(
-    meta: &Value,
-    account_keys: &[String],
-) -> HashMap<String, BalanceChange> {
-    let mut result = HashMap::new();
-
-    let pre_balances = meta.get("preBalances").and_then(|v| v.as_array());
-    let post_balances = meta.get("postBalances").and_then(|v| v.as_array());
-
-    if let Some(balances) = pre_balances {
-        for (idx, pre_val) in balances.iter().enumerate() {
-            let pre = pre_val.as_i64().unwrap_or(0) as i128;
-            let post = post_balances
-                .and_then(|arr| arr.get(idx))
-                .and_then(|v| v.as_i64())
-                .unwrap_or(0) as i128;
-
-            if pre != post {
-                let account = account_keys
-                    .get(idx)
-                    .cloned()
-                    .unwrap_or_else(|| format!("unknown_{}", idx));
-
-                result.insert(
-                    account,
-                    BalanceChange {
-                        pre,
-                        post,
-                        change: post - pre,
-                    },
-                );
-            }
-        }
-    }
-
-    result
-}
-
 /// Convert Unix timestamp to (year, month, day, hour, minute, second)
 fn seconds_to_datetime(secs: u64) -> (u32, u32, u32, u32, u32, u32) {
     const SECS_PER_DAY: u64 = 86400;
@@ -1,23 +1,24 @@
-// cargo run --release --bin analog_rpc
+// cargo run --release --bin analog_rpc -- [signature] --rpc-url <url>
 //
 // Rust analog of analog.rs but fetches transaction via RPC by signature hash
 // Parses transaction using DexParser and outputs results in the same format
-// Все данные (signature и rpc_url) прописаны в коде
+// Endpoints: --rpc-url (повторяемый), SOLANA_RPC_URL, HELIUS_API_KEY или --config <toml>,
+// см. config::RuntimeConfig
 
 use anyhow::{anyhow, bail, Context, Result};
 use base64_simd::STANDARD as B64;
 use reqwest::blocking::Client;
 use serde::Deserialize;
 use serde_json::{json, Value};
-use solana_dex_parser::config::ParseConfig;
+use solana_dex_parser::config::{redact_url, ParseConfig, RuntimeConfig};
 use solana_dex_parser::core::dex_parser::DexParser;
 use solana_dex_parser::core::zero_copy::ZcTransaction;
 use solana_dex_parser::types::{ParseResult, TransactionStatus};
 use std::time::{Duration, Instant};
 
 const WSOL: &str = "So11111111111111111111111111111111111111112";
-const SIGNATURE: &str = "4fesiuBKwrBkE9Aaqv1D8ZTeQPL8Tyd7vQfzfiCJKefTbkrsXqkuEnngwAd2q2uaF5579DFtsSGUTrtuyVYMqUh6"; // Замените на нужный хеш транзакции
-const RPC_URL: &str = "https://mainnet.helius-rpc.com/?api-key=d83c5403-fccf-434c-b337-8d1b5b693f49"; // Замените на нужный RPC URL
+/// Транзакция по умолчанию, если signature не передан аргументом.
+const SIGNATURE: &str = "4fesiuBKwrBkE9Aaqv1D8ZTeQPL8Tyd7vQfzfiCJKefTbkrsXqkuEnngwAd2q2uaF5579DFtsSGUTrtuyVYMqUh6";
 
 fn main() -> Result<()> {
     // Initialize tracing subscriber for logging
@@ -29,7 +30,15 @@ fn main() -> Result<()> {
         .with_max_level(tracing::Level::INFO)
         .init();
 
-    println!("🔍 Получаю транзакцию {} через RPC {}...", SIGNATURE, RPC_URL);
+    let (runtime, rest) = RuntimeConfig::load(std::env::args().skip(1))?;
+    let signature = rest.first().map(String::as_str).unwrap_or(SIGNATURE);
+    let endpoints = runtime.rpc_failover();
+
+    println!(
+        "🔍 Получаю транзакцию {} через RPC {}...",
+        signature,
+        redact_url(endpoints.current())
+    );
 
     let t0 = Instant::now();
 
@@ -45,7 +54,7 @@ fn main() -> Result<()> {
         "id": 1,
         "method": "getTransaction",
         "params": [
-            SIGNATURE,
+            signature,
             {
                 "encoding": "base64",
                 "maxSupportedTransactionVersion": 0
@@ -53,17 +62,22 @@ fn main() -> Result<()> {
         ]
     });
 
-    let resp = client
-        .post(RPC_URL)
-        .json(&body)
-        .send()
-        .context("RPC запрос не удался")?;
-
-    if !resp.status().is_success() {
-        bail!("RPC вернул статус: {}", resp.status());
+    // при сетевой ошибке или не-2xx пробуем следующий endpoint
+    let mut text = None;
+    for attempt in 0..endpoints.len() {
+        let rpc_url = if attempt == 0 { endpoints.current() } else { endpoints.advance() };
+        match client.post(rpc_url).json(&body).send() {
+            Ok(resp) if resp.status().is_success() => {
+                text = Some(resp.text().context("Не удалось прочитать ответ RPC")?);
+                break;
+            }
+            Ok(resp) => eprintln!("⚠️ {} вернул статус: {}", redact_url(rpc_url), resp.status()),
+            Err(err) => eprintln!("⚠️ {} недоступен: {err}", redact_url(rpc_url)),
+        }
     }
-
-    let text = resp.text().context("Не удалось прочитать ответ RPC")?;
+    let Some(text) = text else {
+        bail!("RPC запрос не удался на всех endpoints");
+    };
     let bytes = text.as_bytes();
     let rpc_resp: JsonRpcResponseGetTx =
         serde_json::from_slice(bytes).context("Не удалось распарсить JSON RPC-ответ")?;
@@ -104,7 +118,7 @@ fn main() -> Result<()> {
     let block_time = result.block_time.unwrap_or(0) as u64;
     
     // Zero-copy парсинг: парсим напрямую из raw bytes
-    let zc_tx = ZcTransaction::parse(&raw_bytes, slot, SIGNATURE, block_time, meta)
+    let zc_tx = ZcTransaction::parse(&raw_bytes, slot, signature, block_time, meta)
         .context("Не удалось распарсить транзакцию (zero-copy)")?;
 
     println!("✅ Транзакция получена!");
@@ -0,0 +1,70 @@
+//! Пропускная способность парсера на сохранённом блоке:
+//! `cargo run --release --bin bench_block <block.json> [iterations]`.
+//!
+//! `block.json` — `result` ответа `getBlock` (любой encoding транзакций)
+//! или JSON-массив транзакций (`core::rpc_json::block_transactions_from_json`).
+
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use solana_dex_parser::core::rpc_json::block_transactions_from_json;
+use solana_dex_parser::{DexParser, ParseConfig};
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    if args.len() < 2 {
+        eprintln!("Использование: cargo run --release --bin bench_block <block.json> [iterations]");
+        std::process::exit(1);
+    }
+    let iterations: usize = match args.get(2) {
+        Some(value) => value.parse().context("iterations должно быть числом")?,
+        None => 10,
+    };
+
+    let data =
+        std::fs::read(&args[1]).with_context(|| format!("Не удалось прочитать {}", args[1]))?;
+    let block: Value = serde_json::from_slice(&data).context("Невалидный JSON блока")?;
+    let txs = block_transactions_from_json(&block)?;
+    if txs.is_empty() {
+        bail!("В блоке нет транзакций");
+    }
+
+    let parser = DexParser::new();
+    let config = ParseConfig::default();
+
+    // прогрев: ленивые таблицы, аллокатор
+    let trades: usize = parser
+        .parse_block_iter(txs.iter().cloned(), Some(config.clone()))
+        .map(|result| result.trades.len())
+        .sum();
+
+    let mut times = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let started = Instant::now();
+        for result in parser.parse_block_iter(txs.iter().cloned(), Some(config.clone())) {
+            std::hint::black_box(result);
+        }
+        times.push(started.elapsed());
+    }
+    times.sort();
+
+    let total: Duration = times.iter().sum();
+    let mean = total / iterations.max(1) as u32;
+    let median = times.get(times.len() / 2).copied().unwrap_or_default();
+    let tx_per_sec = txs.len() as f64 / mean.as_secs_f64().max(f64::EPSILON);
+
+    println!("Транзакций в блоке: {}, trades: {}", txs.len(), trades);
+    println!("Итераций: {iterations}");
+    println!("Блок: mean {:.3} ms, median {:.3} ms", ms(mean), ms(median));
+    println!(
+        "Транзакция: {:.2} μs",
+        mean.as_secs_f64() * 1e6 / txs.len() as f64
+    );
+    println!("Пропускная способность: {tx_per_sec:.0} tx/s");
+    Ok(())
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1e3
+}
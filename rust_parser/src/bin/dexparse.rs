@@ -0,0 +1,351 @@
+//! `dexparse` — разбор транзакций без написания кода:
+//!
+//! - `dexparse tx <signature> --rpc-url <url>`
+//! - `dexparse block <slot> --rpc-url <url>`
+//! - `dexparse stream --ws-url <url> --programs <id,id> [--metrics-addr 0.0.0.0:9100] [--record <dir>]`
+//! - `dexparse replay <dir|file> [--speed 1|10|max]` — кадры, записанные `stream --record`
+//! - `dexparse file <json>` — транзакция или блок в любом формате `core::rpc_json`
+//!
+//! `--format json` печатает `ParseResult` (в `stream` — по строке на транзакцию),
+//! `csv`/`table` — по строке на сделку.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use futures::StreamExt;
+use serde_json::Value;
+use solana_dex_parser::compat::{self, TsParseResult};
+use solana_dex_parser::core::rpc_json::{
+    block_transactions_from_json, is_block_json, transaction_from_json,
+};
+use solana_dex_parser::rpc::replay::decode_frame;
+use solana_dex_parser::rpc::{
+    self, BlockFetcher, ReplaySpeed, SubscriberConfig, SubscriptionFilter, TransactionSubscriber,
+};
+use solana_dex_parser::{DexParser, ParseConfig, ParseResult, TradeInfo};
+
+const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+
+#[derive(Parser)]
+#[command(name = "dexparse", version, about = "Parse Solana DEX transactions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    /// Output format
+    #[arg(long, value_enum, default_value = "json", global = true)]
+    format: Format,
+    /// JSON in the shape of the TypeScript solana-dex-parser package
+    #[arg(long, global = true)]
+    ts_compat: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Fetch and parse a transaction by signature
+    Tx {
+        signature: String,
+        #[arg(long, env = "SOLANA_RPC_URL", default_value = DEFAULT_RPC_URL)]
+        rpc_url: String,
+    },
+    /// Fetch and parse a block via getBlock
+    Block {
+        slot: u64,
+        #[arg(long, env = "SOLANA_RPC_URL", default_value = DEFAULT_RPC_URL)]
+        rpc_url: String,
+    },
+    /// Subscribe to transactions over WebSocket (`transactionSubscribe`) and parse them
+    Stream {
+        #[arg(long, env = "SOLANA_WS_URL")]
+        ws_url: String,
+        /// Program ids to subscribe to (comma separated)
+        #[arg(long, value_delimiter = ',', required = true)]
+        programs: Vec<String>,
+        /// Print only transactions with trades or liquidity events
+        #[arg(long)]
+        only_dex: bool,
+        /// Serve Prometheus metrics on this address (requires the `prometheus` feature)
+        #[arg(long, env = "METRICS_ADDR")]
+        metrics_addr: Option<SocketAddr>,
+        /// Record received WebSocket frames as NDJSON into this directory
+        #[arg(long)]
+        record: Option<PathBuf>,
+    },
+    /// Replay WebSocket frames recorded with `stream --record` (a file or a directory)
+    Replay {
+        path: PathBuf,
+        /// Speed multiplier relative to the recording (`1` — original timing), or `max`
+        #[arg(long, default_value = "1", value_parser = parse_speed)]
+        speed: ReplaySpeed,
+        /// Print only transactions with trades or liquidity events
+        #[arg(long)]
+        only_dex: bool,
+    },
+    /// Parse a transaction or block JSON file
+    File { path: PathBuf },
+}
+
+fn parse_speed(value: &str) -> Result<ReplaySpeed, String> {
+    ReplaySpeed::parse(value)
+        .ok_or_else(|| format!("expected a positive multiplier or `max`, got {value}"))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Json,
+    Csv,
+    Table,
+}
+
+const COLUMNS: [&str; 9] = [
+    "signature",
+    "slot",
+    "type",
+    "amm",
+    "user",
+    "input_mint",
+    "input_amount",
+    "output_mint",
+    "output_amount",
+];
+
+fn trade_row(trade: &TradeInfo) -> [String; 9] {
+    [
+        trade.signature.clone(),
+        trade.slot.to_string(),
+        trade.trade_type.as_str().to_string(),
+        trade.amm.clone().unwrap_or_default(),
+        trade.user.clone().unwrap_or_default(),
+        trade.input_token.mint.clone(),
+        trade.input_token.amount.to_string(),
+        trade.output_token.mint.clone(),
+        trade.output_token.amount.to_string(),
+    ]
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Адреса в таблице сокращаются до `abcd…wxyz`.
+fn short(value: &str) -> String {
+    if value.chars().count() < 32 {
+        return value.to_string();
+    }
+    let head: String = value.chars().take(4).collect();
+    let tail: String = value.chars().skip(value.chars().count() - 4).collect();
+    format!("{head}…{tail}")
+}
+
+const TABLE_WIDTHS: [usize; 9] = [11, 10, 8, 12, 11, 11, 18, 11, 18];
+
+fn table_line(cells: &[String]) -> String {
+    cells
+        .iter()
+        .zip(TABLE_WIDTHS)
+        .map(|(cell, width)| format!("{:<width$}", short(cell)))
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end()
+        .to_string()
+}
+
+struct Printer {
+    format: Format,
+    ts_compat: bool,
+    header_printed: bool,
+}
+
+impl Printer {
+    fn new(format: Format, ts_compat: bool) -> Self {
+        Self {
+            format,
+            ts_compat,
+            header_printed: false,
+        }
+    }
+
+    fn header(&mut self) {
+        if std::mem::replace(&mut self.header_printed, true) {
+            return;
+        }
+        let columns = COLUMNS.map(str::to_string);
+        match self.format {
+            Format::Csv => println!("{}", COLUMNS.join(",")),
+            Format::Table => println!("{}", table_line(&columns)),
+            Format::Json => {}
+        }
+    }
+
+    fn trades(&mut self, result: &ParseResult) {
+        self.header();
+        for trade in &result.trades {
+            let row = trade_row(trade);
+            match self.format {
+                Format::Csv => {
+                    let fields: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+                    println!("{}", fields.join(","));
+                }
+                Format::Table => println!("{}", table_line(&row)),
+                Format::Json => {}
+            }
+        }
+    }
+
+    /// Разовый вывод: JSON — весь документ с отступами; с `--ts-compat` — массив
+    /// результатов транзакций в форме TS.
+    fn print<T: serde::Serialize>(&mut self, document: &T, results: &[ParseResult]) -> Result<()> {
+        if self.format == Format::Json && self.ts_compat {
+            let results: Vec<TsParseResult> = results.iter().map(TsParseResult::from).collect();
+            println!("{}", serde_json::to_string_pretty(&results)?);
+            return Ok(());
+        }
+        if self.format == Format::Json {
+            println!("{}", serde_json::to_string_pretty(document)?);
+            return Ok(());
+        }
+        self.header();
+        for result in results {
+            self.trades(result);
+        }
+        Ok(())
+    }
+
+    /// Одна транзакция: с `--ts-compat` — объект, а не массив.
+    fn print_tx(&mut self, result: &ParseResult) -> Result<()> {
+        if self.format == Format::Json && self.ts_compat {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&TsParseResult::from(result))?
+            );
+            return Ok(());
+        }
+        self.print(result, std::slice::from_ref(result))
+    }
+
+    /// Потоковый вывод: JSON — одна строка на транзакцию.
+    fn print_streamed(&mut self, result: &ParseResult) -> Result<()> {
+        if self.format == Format::Json {
+            let line = if self.ts_compat {
+                compat::to_ts_json(result)?
+            } else {
+                serde_json::to_string(result)?
+            };
+            println!("{line}");
+        } else {
+            self.trades(result);
+        }
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut printer = Printer::new(cli.format, cli.ts_compat);
+    let parser = DexParser::new();
+    let config = ParseConfig {
+        ts_compat_output: cli.ts_compat,
+        ..ParseConfig::default()
+    };
+
+    match cli.command {
+        Command::Tx { signature, rpc_url } => {
+            let tx =
+                tokio::task::spawn_blocking(move || rpc::fetch_transaction(&rpc_url, &signature))
+                    .await??;
+            let result = parser.parse_all(tx, Some(config));
+            printer.print_tx(&result)?;
+        }
+        Command::Block { slot, rpc_url } => {
+            let Some(block) = BlockFetcher::new(&rpc_url).fetch_block(slot).await? else {
+                bail!("slot {slot} was skipped or is not available");
+            };
+            printer.print(&block, &block.transactions)?;
+        }
+        Command::Stream {
+            ws_url,
+            programs,
+            only_dex,
+            metrics_addr,
+            record,
+        } => {
+            if let Some(addr) = metrics_addr {
+                #[cfg(feature = "prometheus")]
+                solana_dex_parser::metrics::install_prometheus(addr)?;
+                #[cfg(not(feature = "prometheus"))]
+                bail!("--metrics-addr {addr} requires building with --features prometheus");
+            }
+            let subscriber = TransactionSubscriber::new(SubscriberConfig {
+                ws_url,
+                filter: SubscriptionFilter {
+                    account_include: programs,
+                    ..Default::default()
+                },
+                parse_config: Some(config),
+                record_dir: record,
+                ..Default::default()
+            });
+            let mut results = std::pin::pin!(subscriber.subscribe());
+            while let Some(result) = results.next().await {
+                if only_dex && result.trades.is_empty() && result.liquidities.is_empty() {
+                    continue;
+                }
+                printer.print_streamed(&result)?;
+            }
+        }
+        Command::Replay {
+            path,
+            speed,
+            only_dex,
+        } => {
+            let mut frames = std::pin::pin!(rpc::replay(&path, speed)?);
+            let (started, mut received, mut parsed) = (Instant::now(), 0u64, 0u64);
+            while let Some(recorded) = frames.next().await {
+                received += 1;
+                let tx = match decode_frame(&recorded.frame) {
+                    Ok(Some(tx)) => tx,
+                    Ok(None) => continue,
+                    Err(err) => {
+                        eprintln!("skipping frame recorded at {}: {err:#}", recorded.ts_ms);
+                        continue;
+                    }
+                };
+                let result = parser.parse_all(tx, Some(config.clone()));
+                parsed += 1;
+                if only_dex && result.trades.is_empty() && result.liquidities.is_empty() {
+                    continue;
+                }
+                printer.print_streamed(&result)?;
+            }
+            let elapsed = started.elapsed().as_secs_f64();
+            eprintln!(
+                "replayed {received} frames, parsed {parsed} transactions in {elapsed:.3}s ({:.0} tx/s)",
+                parsed as f64 / elapsed.max(f64::EPSILON)
+            );
+        }
+        Command::File { path } => {
+            let data = std::fs::read(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            let value: Value = serde_json::from_slice(&data)
+                .with_context(|| format!("invalid JSON in {}", path.display()))?;
+            if is_block_json(&value) {
+                let txs = block_transactions_from_json(&value)?;
+                let results: Vec<ParseResult> =
+                    parser.parse_block_iter(txs, Some(config)).collect();
+                printer.print(&results, &results)?;
+            } else {
+                let tx = transaction_from_json(&value)?;
+                let result = parser.parse_all(tx, Some(config));
+                printer.print_tx(&result)?;
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,85 @@
+//! `gen_fixture` — регрессионный fixture из mainnet-транзакции:
+//!
+//! `cargo run --bin gen_fixture -- <signature> [protocol] [rpc_url]`
+//!
+//! Пишет `tests/fixtures/<protocol>/<sig>.json` (`SolanaTransaction` как есть) и
+//! `<sig>.expected.json` (текущий `ParseResult`); `tests/protocol_fixtures.rs` сверяет их.
+//! Протокол по умолчанию — `amm` первой сделки или события ликвидности.
+
+use std::fs;
+
+use anyhow::{Context, Result};
+use solana_dex_parser::{fixtures, rpc, DexParser, ParseResult};
+
+const DEFAULT_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+
+/// `amm` в имя каталога: `Raydium CPMM` -> `raydium_cpmm`.
+fn protocol_dir_name(result: &ParseResult) -> String {
+    let amm = result
+        .trades
+        .iter()
+        .filter_map(|trade| trade.amm.as_deref())
+        .chain(
+            result
+                .liquidities
+                .iter()
+                .filter_map(|pool| pool.amm.as_deref()),
+        )
+        .chain(
+            result
+                .meme_events
+                .iter()
+                .filter_map(|event| event.protocol.as_deref()),
+        )
+        .next()
+        .unwrap_or("unknown");
+    amm.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(signature) = args.get(1) else {
+        eprintln!("Использование: cargo run --bin gen_fixture -- <signature> [protocol] [rpc_url]");
+        std::process::exit(1);
+    };
+    let rpc_url = args
+        .get(3)
+        .cloned()
+        .or_else(|| std::env::var("SOLANA_RPC_URL").ok())
+        .unwrap_or_else(|| DEFAULT_RPC_URL.to_string());
+
+    let tx = rpc::fetch_transaction(&rpc_url, signature)
+        .with_context(|| format!("failed to fetch transaction {signature}"))?;
+    let result = DexParser::new().parse_all(tx.clone(), None);
+    let protocol = args
+        .get(2)
+        .cloned()
+        .unwrap_or_else(|| protocol_dir_name(&result));
+
+    let dir = fixtures::dir().join(&protocol);
+    fs::create_dir_all(&dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    let tx_path = dir.join(format!("{signature}.json"));
+    let expected_path = dir.join(format!("{signature}.expected.json"));
+    fs::write(&tx_path, serde_json::to_string_pretty(&tx)?)
+        .with_context(|| format!("failed to write {}", tx_path.display()))?;
+    fs::write(&expected_path, serde_json::to_string_pretty(&result)?)
+        .with_context(|| format!("failed to write {}", expected_path.display()))?;
+
+    println!("{}", tx_path.display());
+    println!("{}", expected_path.display());
+    println!(
+        "trades: {}, liquidities: {}, transfers: {}",
+        result.trades.len(),
+        result.liquidities.len(),
+        result.transfers.len()
+    );
+    Ok(())
+}
@@ -144,16 +144,12 @@ fn main() -> Result<()> {
                 pool.event_type,
                 pool.pool_id.chars().take(16).collect::<String>()
             );
-            if let Some(ref token0) = pool.token0_mint {
-                println!("     Token0: {} (amount: {:?})", 
-                    token0.chars().take(8).collect::<String>(),
-                    pool.token0_amount
-                );
-            }
-            if let Some(ref token1) = pool.token1_mint {
-                println!("     Token1: {} (amount: {:?})", 
-                    token1.chars().take(8).collect::<String>(),
-                    pool.token1_amount
+            for (leg_index, leg) in pool.tokens.iter().enumerate() {
+                println!("     Token{}: {} (amount: {}, {:?})", 
+                    leg_index,
+                    leg.mint.chars().take(8).collect::<String>(),
+                    leg.amount,
+                    leg.direction
                 );
             }
             println!();
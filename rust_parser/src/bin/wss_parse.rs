@@ -1,10 +1,16 @@
-// cargo run --release --bin ws_raw -- <API_KEY> [MINT1,MINT2,...]
+// cargo run --release --bin wss_parse -- [MINT1,MINT2,...] --api-key <key>
+//
+// Endpoints: --ws-url (повторяемый), SOLANA_WS_URL, HELIUS_API_KEY или --config <toml>,
+// см. config::RuntimeConfig
 
 use anyhow::{anyhow, bail, Context, Result};
 use base64_simd::STANDARD as B64;
 use bs58;
 use futures_util::{SinkExt, StreamExt};
-use serde_json::{json, Value};
+use serde_json::Value;
+use solana_dex_parser::config::{redact_url, RuntimeConfig};
+use solana_dex_parser::rpc::ws::extract_base64_tx;
+use solana_dex_parser::rpc::{connect_with_failover, SubscriberConfig, SubscriptionFilter, TransactionSubscriber};
 use std::fmt::Write as _;
 use std::time::Instant;
 use tokio::time::{interval, Duration};
@@ -14,39 +20,30 @@ use tokio_tungstenite::tungstenite::Message;
 
 #[tokio::main(flavor = "multi_thread")]
 async fn main() -> Result<()> {
-    let mut args = std::env::args().skip(1);
-    let api_key = "767f42d9-06c2-46f8-8031-9869035d6ce4".to_string();
-    let include_mints: Vec<String> = args
-        .next()
+    let (runtime, rest) = RuntimeConfig::load(std::env::args().skip(1))?;
+    let endpoints = runtime.ws_failover()?;
+    let include_mints: Vec<String> = rest
+        .first()
+        .cloned()
         .unwrap_or_else(|| "EKpQGSJtjMFqKZ9KQanSqYXRcF8fBopzLHYxdM65zcjm".to_string())
         .split(',')
         .map(|s| s.trim().to_string())
         .filter(|s| !s.is_empty())
         .collect();
 
-    let ws_url = format!("wss://atlas-mainnet.helius-rpc.com/?api-key={}", api_key);
-    println!("🔌 connecting {}", ws_url);
-
-    let (ws_stream, _) = tokio_tungstenite::connect_async(&ws_url)
-        .await
-        .context("ws connect failed")?;
+    println!("🔌 connecting {}", redact_url(endpoints.current()));
+    let ws_stream = connect_with_failover(&endpoints).await?;
     let (mut sink, mut stream) = ws_stream.split();
 
     // subscribe: base64 + full + v0 support
-    let sub = json!({
-        "jsonrpc": "2.0",
-        "id": 1,
-        "method": "transactionSubscribe",
-        "params": [
-            { "accountInclude": include_mints, "vote": false, "failed": false },
-            {
-                "commitment": "processed",
-                "encoding": "base64",
-                "transactionDetails": "full",
-                "maxSupportedTransactionVersion": 0
-            }
-        ]
-    });
+    let sub = TransactionSubscriber::new(SubscriberConfig {
+        filter: SubscriptionFilter {
+            account_include: include_mints.clone(),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .subscription_request();
 
     sink.send(Message::Text(sub.to_string()))
         .await
@@ -153,7 +150,7 @@ async fn main() -> Result<()> {
 // === Helpers ===
 
 fn ms(d: std::time::Duration) -> f64 {
-    (d.as_secs_f64() * 1_000.0)
+    d.as_secs_f64() * 1_000.0
 }
 
 fn timing(kind: &str, t0: Instant, t_json: Instant, t_b64: Instant, t_parsed: Instant, t_printed: Instant) {
@@ -165,36 +162,6 @@ fn timing(kind: &str, t0: Instant, t_json: Instant, t_b64: Instant, t_parsed: In
     println!("⏱️ Timing[{kind}]: JSON={json_ms:.3}ms  Decode={dec_ms:.3}ms  Parse={par_ms:.3}ms  Print={prn_ms:.3}ms  TOTAL={tot_ms:.3}ms");
 }
 
-/// Пытаемся вытащить base64-массив из result.transaction.*
-/// Форматы из доков Helius:
-/// - result.transaction.transaction = ["<base64>", "base64"]
-/// - либо напрямую result.transaction = ["<base64>", "base64"]
-fn extract_base64_tx(result: &Value) -> Result<Option<Vec<u8>>> {
-    // 1) result.transaction.transaction = ["..","base64"]
-    if let Some(arr) = result.pointer("/transaction/transaction").and_then(|v| v.as_array()) {
-        if arr.len() == 2 {
-            if let (Some(b64), Some(enc)) = (arr[0].as_str(), arr[1].as_str()) {
-                if enc == "base64" {
-                    let bytes = B64.decode_to_vec(b64).context("base64 decode failed")?;
-                    return Ok(Some(bytes));
-                }
-            }
-        }
-    }
-    // 2) result.transaction == ["..","base64"]
-    if let Some(arr) = result.get("transaction").and_then(|v| v.as_array()) {
-        if arr.len() == 2 {
-            if let (Some(b64), Some(enc)) = (arr[0].as_str(), arr[1].as_str()) {
-                if enc == "base64" {
-                    let bytes = B64.decode_to_vec(b64).context("base64 decode failed")?;
-                    return Ok(Some(bytes));
-                }
-            }
-        }
-    }
-    Ok(None)
-}
-
 /// Извлекает загруженные адреса из ALT из meta.loadedAddresses
 fn extract_loaded_addresses(result: &Value) -> (Vec<[u8;32]>, Vec<[u8;32]>) {
     fn to32(s: &str) -> Result<[u8;32]> {
@@ -233,11 +200,6 @@ fn extract_loaded_addresses(result: &Value) -> (Vec<[u8;32]>, Vec<[u8;32]>) {
 
 // === Byte-level parser (как в твоём HTTP-бине) ===
 
-#[derive(Debug, Clone, Copy)]
-enum TxVersion {
-    Legacy,
-    V0,
-}
 #[derive(Debug, Clone, Copy)]
 struct Header {
     num_required_signatures: u8,
@@ -248,7 +210,6 @@ struct Header {
 struct IxView {
     program_id_index: u8,
     program_id: [u8; 32],
-    account_indices: Vec<u8>,
     accounts: Vec<[u8; 32]>,
     data_base64: String,
     data_hex: String,
@@ -257,7 +218,6 @@ struct IxView {
 struct TxView {
     slot: u64,
     signature: String,
-    version: TxVersion,
     header: Header,
     recent_blockhash: [u8; 32],
     account_keys: Vec<[u8; 32]>,
@@ -284,7 +244,6 @@ fn parse_transaction_view(
     // version / legacy
     ensure!(p < bytes.len(), "empty message");
     let versioned = (bytes[p] & 0x80) != 0;
-    let version = if versioned { TxVersion::V0 } else { TxVersion::Legacy };
     if versioned { p += 1; }
 
     // header
@@ -397,7 +356,6 @@ fn parse_transaction_view(
         ixs.push(IxView {
             program_id_index: raw.pid_idx,
             program_id,
-            account_indices: raw.acc_idx,
             accounts,
             data_base64,
             data_hex,
@@ -407,7 +365,6 @@ fn parse_transaction_view(
     Ok(TxView {
         slot,
         signature: sig.to_string(),
-        version,
         header,
         recent_blockhash: rb,
         account_keys: all_keys, // <- тут уже общий список
@@ -481,7 +438,6 @@ fn parse_json_transaction_view(result: &Value, slot: u64, sig: &str) -> Result<T
         };
 
         // accounts: либо массив строк pubkey, либо массив индексов
-        let mut account_indices: Vec<u8> = Vec::new();
         let mut accounts: Vec<[u8;32]> = Vec::new();
 
         if let Some(accs) = ixv.get("accounts").and_then(|v| v.as_array()) {
@@ -489,28 +445,24 @@ fn parse_json_transaction_view(result: &Value, slot: u64, sig: &str) -> Result<T
                 for s in accs {
                     let pk = pk_to32(s.as_str().unwrap())?;
                     accounts.push(pk);
-                    // попробуем найти индекс
-                    if let Some(idx) = account_keys.iter().position(|k| k == &pk) {
-                        account_indices.push(idx as u8);
-                    }
                 }
             } else if accs.first().and_then(|x| x.as_u64()).is_some() {
                 for idxv in accs {
                     let idx = idxv.as_u64().unwrap() as usize;
-                    account_indices.push(idx as u8);
                     accounts.push(*account_keys.get(idx).ok_or_else(|| anyhow!("bad account index"))?);
                 }
             }
         }
 
-        // data: строка — чаще base58; если не декодится как base58, пробуем base64; иначе пусто
+        // data: строка — чаще base58, иначе base64; ни то ни другое — ошибка
         let mut data_bytes: Vec<u8> = Vec::new();
         if let Some(dstr) = ixv.get("data").and_then(|v| v.as_str()) {
-            if let Ok(b) = bs58::decode(dstr).into_vec() {
-                data_bytes = b;
-            } else if let Ok(b) = B64.decode_to_vec(dstr) {
-                data_bytes = b;
-            }
+            data_bytes = match bs58::decode(dstr).into_vec() {
+                Ok(b) => b,
+                Err(_) => B64
+                    .decode_to_vec(dstr)
+                    .map_err(|_| anyhow!("instruction data is neither base58 nor base64: {dstr}"))?,
+            };
         }
 
         let data_base64 = B64.encode_to_string(&data_bytes);
@@ -520,7 +472,6 @@ fn parse_json_transaction_view(result: &Value, slot: u64, sig: &str) -> Result<T
         ixs.push(IxView {
             program_id_index,
             program_id,
-            account_indices,
             accounts,
             data_base64,
             data_hex,
@@ -530,7 +481,6 @@ fn parse_json_transaction_view(result: &Value, slot: u64, sig: &str) -> Result<T
     Ok(TxView {
         slot,
         signature: sig.to_string(),
-        version: TxVersion::V0, // нельзя надёжно узнать из jsonParsed → пусть будет V0/Legacy не критично
         header: hdr,
         recent_blockhash: rb,
         account_keys,
@@ -0,0 +1,238 @@
+//! Сравнение вывода с TypeScript-парсером (golden-тесты, `tests/golden`).
+//!
+//! Оба JSON сначала нормализуются (`normalize`): TS опускает `undefined`, Rust —
+//! `None`, поэтому `null` и отсутствующий ключ считаются одним и тем же. Дальше
+//! `diff` сравнивает поле за полем; числа — с допуском `Tolerance`.
+//!
+//! `to_ts_json` выдаёт `ParseResult` байт-в-байт в форме TS-пакета
+//! (`ParseConfig::ts_compat_output`).
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::ParseResult;
+
+mod ts_output;
+
+pub use ts_output::{to_json, to_ts_json, TsParseResult};
+
+/// Допуск для float-полей: совпадает, если `|a - b| <= abs` или `|a - b| <= rel * max(|a|, |b|)`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct Tolerance {
+    pub abs: f64,
+    pub rel: f64,
+}
+
+impl Default for Tolerance {
+    fn default() -> Self {
+        Self {
+            abs: 1e-9,
+            rel: 1e-9,
+        }
+    }
+}
+
+impl Tolerance {
+    pub fn matches(&self, expected: f64, actual: f64) -> bool {
+        let delta = (expected - actual).abs();
+        delta <= self.abs || delta <= self.rel * expected.abs().max(actual.abs())
+    }
+}
+
+/// Настройки сравнения; в golden-кейсе лежат в `compat.json`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CompatOptions {
+    pub tolerance: Tolerance,
+    /// Пути, которые не сравниваются: `trades.*.fee`, `aggregateTrade`;
+    /// `*` — любой ключ или индекс.
+    pub ignore: Vec<String>,
+    /// Сравнивать только ключи, которые есть в выводе TS (Rust-расширения вроде
+    /// `fees` или `diagnostics` не считаются расхождением).
+    pub expected_keys_only: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Mismatch {
+    /// `trades.0.inputToken.amount`
+    pub path: String,
+    pub expected: Option<Value>,
+    pub actual: Option<Value>,
+}
+
+impl std::fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let show = |value: &Option<Value>| match value {
+            Some(value) => value.to_string(),
+            None => "<missing>".to_string(),
+        };
+        write!(
+            f,
+            "{}: expected {}, got {}",
+            self.path,
+            show(&self.expected),
+            show(&self.actual)
+        )
+    }
+}
+
+/// Удаляет `null`-поля объектов рекурсивно.
+pub fn normalize(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, field| !field.is_null());
+            map.values_mut().for_each(normalize);
+        }
+        Value::Array(items) => items.iter_mut().for_each(normalize),
+        _ => {}
+    }
+}
+
+/// Расхождения `actual` относительно `expected` (оба уже нормализованы).
+pub fn diff(expected: &Value, actual: &Value, options: &CompatOptions) -> Vec<Mismatch> {
+    let mut mismatches = Vec::new();
+    let mut path = Vec::new();
+    diff_at(expected, actual, options, &mut path, &mut mismatches);
+    mismatches
+}
+
+/// Сравнивает `ParseResult` (в TS-форме `TsParseResult`) с JSON-выводом
+/// TS-парсера для той же транзакции.
+pub fn compare_parse_result(
+    ts_output: &Value,
+    result: &ParseResult,
+    options: &CompatOptions,
+) -> Vec<Mismatch> {
+    let mut expected = ts_output.clone();
+    let mut actual = serde_json::to_value(TsParseResult::from(result)).unwrap_or(Value::Null);
+    normalize(&mut expected);
+    normalize(&mut actual);
+    diff(&expected, &actual, options)
+}
+
+fn is_ignored(path: &[String], options: &CompatOptions) -> bool {
+    options.ignore.iter().any(|pattern| {
+        let segments: Vec<&str> = pattern.split('.').collect();
+        segments.len() == path.len()
+            && segments
+                .iter()
+                .zip(path)
+                .all(|(segment, key)| *segment == "*" || segment == key)
+    })
+}
+
+fn push_mismatch(
+    out: &mut Vec<Mismatch>,
+    path: &[String],
+    expected: Option<&Value>,
+    actual: Option<&Value>,
+) {
+    out.push(Mismatch {
+        path: path.join("."),
+        expected: expected.cloned(),
+        actual: actual.cloned(),
+    });
+}
+
+fn diff_at(
+    expected: &Value,
+    actual: &Value,
+    options: &CompatOptions,
+    path: &mut Vec<String>,
+    out: &mut Vec<Mismatch>,
+) {
+    if is_ignored(path, options) {
+        return;
+    }
+    match (expected, actual) {
+        (Value::Object(expected_map), Value::Object(actual_map)) => {
+            let mut keys: Vec<&String> = expected_map.keys().collect();
+            if !options.expected_keys_only {
+                keys.extend(
+                    actual_map
+                        .keys()
+                        .filter(|key| !expected_map.contains_key(*key)),
+                );
+            }
+            for key in keys {
+                path.push(key.clone());
+                match (expected_map.get(key), actual_map.get(key)) {
+                    (Some(expected), Some(actual)) => diff_at(expected, actual, options, path, out),
+                    (expected, actual) => {
+                        if !is_ignored(path, options) {
+                            push_mismatch(out, path, expected, actual);
+                        }
+                    }
+                }
+                path.pop();
+            }
+        }
+        (Value::Array(expected_items), Value::Array(actual_items)) => {
+            if expected_items.len() != actual_items.len() {
+                path.push("length".to_string());
+                push_mismatch(
+                    out,
+                    path,
+                    Some(&expected_items.len().into()),
+                    Some(&actual_items.len().into()),
+                );
+                path.pop();
+            }
+            for (pos, (expected, actual)) in expected_items.iter().zip(actual_items).enumerate() {
+                path.push(pos.to_string());
+                diff_at(expected, actual, options, path, out);
+                path.pop();
+            }
+        }
+        (Value::Number(expected_number), Value::Number(actual_number)) => {
+            let equal = match (expected_number.as_f64(), actual_number.as_f64()) {
+                (Some(e), Some(a)) => options.tolerance.matches(e, a),
+                _ => expected_number == actual_number,
+            };
+            if !equal {
+                push_mismatch(out, path, Some(expected), Some(actual));
+            }
+        }
+        _ => {
+            if expected != actual {
+                push_mismatch(out, path, Some(expected), Some(actual));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn diff_applies_tolerance_nulls_and_ignores() {
+        let mut expected = json!({
+            "trades": [{ "amount": 1.0000000001, "user": "a", "fee": { "amount": 1 } }],
+            "msg": null
+        });
+        let mut actual = json!({
+            "trades": [{ "amount": 1.0, "user": "b", "fee": { "amount": 2 } }],
+            "fees": []
+        });
+        normalize(&mut expected);
+        normalize(&mut actual);
+
+        let options = CompatOptions {
+            ignore: vec!["trades.*.fee".to_string()],
+            ..Default::default()
+        };
+        let mismatches = diff(&expected, &actual, &options);
+        let paths: Vec<&str> = mismatches.iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, ["trades.0.user", "fees"]);
+
+        let options = CompatOptions {
+            expected_keys_only: true,
+            ..options
+        };
+        assert_eq!(diff(&expected, &actual, &options).len(), 1);
+    }
+}
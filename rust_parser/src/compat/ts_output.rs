@@ -0,0 +1,682 @@
+//! Вывод `ParseResult` в форме TS-пакета `solana-dex-parser` (`ParseConfig::ts_compat_output`).
+//!
+//! Ключи идут в порядке, в котором их заполняет TS (`dex-parser.ts` для корня,
+//! порядок полей интерфейсов для вложенных объектов), так что `JSON.stringify`
+//! и `to_ts_json` дают одинаковые байты. Rust-расширения (`diagnostics`, `flags`,
+//! `order_key`, ...) не выводятся; необязательные поля TS опускаются, а не пишутся `null`.
+
+use std::borrow::Cow;
+
+use serde::ser::Error as _;
+use serde::{Serialize, Serializer};
+use serde_json::value::RawValue;
+
+use crate::config::ParseConfig;
+use crate::types::{
+    BalanceChange, FeeInfo, MemeEvent, ParseResult, PoolEvent, TokenAmount, TokenInfo, TokenLeg,
+    TradeInfo, TradeType, TransactionStatus, TransferData,
+};
+
+/// `JSON.stringify` TS-результата; Rust-форма — через `serde_json::to_string(result)`.
+pub fn to_ts_json(result: &ParseResult) -> serde_json::Result<String> {
+    serde_json::to_string(&TsParseResult::from(result))
+}
+
+/// JSON результата с учётом `ParseConfig::ts_compat_output`.
+pub fn to_json(result: &ParseResult, config: &ParseConfig) -> serde_json::Result<String> {
+    if config.ts_compat_output {
+        to_ts_json(result)
+    } else {
+        serde_json::to_string(result)
+    }
+}
+
+/// `ParseResult` TS-пакета: порядок ключей — как в литерале `parseWithClassifier`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TsParseResult<'a> {
+    state: bool,
+    fee: TsTokenAmount<'a>,
+    trades: Vec<TsTradeInfo<'a>>,
+    liquidities: Vec<TsPoolEvent<'a>>,
+    transfers: Vec<TsTransferData<'a>>,
+    meme_events: Vec<TsMemeEvent<'a>>,
+    slot: u64,
+    msg: &'a str,
+    timestamp: u64,
+    signature: &'a str,
+    signer: &'a [String],
+    compute_units: u64,
+    tx_status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sol_balance_change: Option<TsBalanceChange>,
+    /// В TS это `Map`, и `JSON.stringify` пишет её как `{}`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token_balance_change: Option<EmptyObject>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    aggregate_trade: Option<TsTradeInfo<'a>>,
+}
+
+impl<'a> From<&'a ParseResult> for TsParseResult<'a> {
+    fn from(result: &'a ParseResult) -> Self {
+        Self {
+            state: result.state,
+            fee: (&result.fee).into(),
+            trades: result.trades.iter().map(TsTradeInfo::from).collect(),
+            liquidities: result.liquidities.iter().map(TsPoolEvent::from).collect(),
+            transfers: result.transfers.iter().map(TsTransferData::from).collect(),
+            meme_events: result.meme_events.iter().map(TsMemeEvent::from).collect(),
+            slot: result.slot,
+            msg: result.msg.as_deref().unwrap_or(""),
+            timestamp: result.timestamp,
+            signature: &result.signature,
+            signer: &result.signer,
+            compute_units: result.compute_units,
+            tx_status: match result.tx_status {
+                TransactionStatus::Unknown => "unknown",
+                TransactionStatus::Success => "success",
+                TransactionStatus::Failed => "failed",
+            },
+            sol_balance_change: result.sol_balance_change.as_ref().map(TsBalanceChange::sol),
+            token_balance_change: (!result.token_balance_change.is_empty())
+                .then_some(EmptyObject {}),
+            aggregate_trade: result.aggregate_trade.as_ref().map(TsTradeInfo::from),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct EmptyObject {}
+
+/// Число как у `JSON.stringify`: запись `Number.prototype.toString`, а не ryu
+/// (`0.000005`, а не `5e-6`; `2`, а не `2.0`; `1e+21`).
+#[derive(Clone, Copy)]
+struct JsNumber(f64);
+
+impl Serialize for JsNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RawValue::from_string(js_number_string(self.0))
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+}
+
+/// `Number::toString` из ECMA-262: кратчайшие цифры, десятичная запись при
+/// `1e-7 <= |x| < 1e21`, иначе экспонента со знаком. NaN и ±Infinity — `null`, как
+/// в `JSON.stringify`.
+fn js_number_string(value: f64) -> String {
+    if !value.is_finite() {
+        return "null".to_string();
+    }
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    // `{:e}` даёт те же кратчайшие цифры, что и JS: `1.5e-6`
+    let scientific = format!("{:e}", value.abs());
+    let (mantissa, exponent) = scientific
+        .split_once('e')
+        .expect("LowerExp always has an exponent");
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let k = digits.len() as i32;
+    // позиция десятичной точки относительно цифр
+    let n = exponent.parse::<i32>().expect("LowerExp exponent is an integer") + 1;
+    let body = if k <= n && n <= 21 {
+        format!("{digits}{}", "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        let (int, frac) = digits.split_at(n as usize);
+        format!("{int}.{frac}")
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{digits}", "0".repeat(-n as usize))
+    } else {
+        let (first, rest) = digits.split_at(1);
+        let rest = if rest.is_empty() { String::new() } else { format!(".{rest}") };
+        let sign = if n > 0 { '+' } else { '-' };
+        format!("{first}{rest}e{sign}{}", (n - 1).abs())
+    };
+    if value < 0.0 {
+        format!("-{body}")
+    } else {
+        body
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TsTokenAmount<'a> {
+    amount: Cow<'a, str>,
+    ui_amount: Option<JsNumber>,
+    decimals: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ui_amount_string: Option<&'a str>,
+}
+
+impl<'a> From<&'a TokenAmount> for TsTokenAmount<'a> {
+    fn from(amount: &'a TokenAmount) -> Self {
+        Self {
+            amount: Cow::Borrowed(&amount.amount),
+            ui_amount: amount.ui_amount.map(JsNumber),
+            decimals: amount.decimals,
+            ui_amount_string: amount.ui_amount_string.as_deref(),
+        }
+    }
+}
+
+/// `BalanceChange` TS: три `TokenAmount` с `decimals: 9` для SOL.
+#[derive(Serialize)]
+struct TsBalanceChange {
+    pre: TsTokenAmount<'static>,
+    post: TsTokenAmount<'static>,
+    change: TsTokenAmount<'static>,
+}
+
+impl TsBalanceChange {
+    fn sol(change: &BalanceChange) -> Self {
+        let lamports = |value: i128| TsTokenAmount {
+            amount: Cow::Owned(value.to_string()),
+            ui_amount: Some(JsNumber(value as f64 / 1e9)),
+            decimals: 9,
+            ui_amount_string: None,
+        };
+        Self {
+            pre: lamports(change.pre),
+            post: lamports(change.post),
+            change: lamports(change.change),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TsTokenInfo<'a> {
+    mint: &'a str,
+    amount: JsNumber,
+    amount_raw: &'a str,
+    decimals: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    authority: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    destination: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    destination_owner: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    destination_balance: Option<TsTokenAmount<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    destination_pre_balance: Option<TsTokenAmount<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_balance: Option<TsTokenAmount<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_pre_balance: Option<TsTokenAmount<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    balance_change: Option<&'a str>,
+}
+
+impl<'a> From<&'a TokenInfo> for TsTokenInfo<'a> {
+    fn from(token: &'a TokenInfo) -> Self {
+        Self {
+            mint: &token.mint,
+            amount: JsNumber(token.amount),
+            amount_raw: &token.amount_raw,
+            decimals: token.decimals,
+            authority: token.authority.as_deref(),
+            destination: token.destination.as_deref(),
+            destination_owner: token.destination_owner.as_deref(),
+            destination_balance: token.destination_balance.as_ref().map(TsTokenAmount::from),
+            destination_pre_balance: token
+                .destination_pre_balance
+                .as_ref()
+                .map(TsTokenAmount::from),
+            source: token.source.as_deref(),
+            source_balance: token.source_balance.as_ref().map(TsTokenAmount::from),
+            source_pre_balance: token.source_pre_balance.as_ref().map(TsTokenAmount::from),
+            balance_change: token.balance_change.as_deref(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TsFeeInfo<'a> {
+    mint: &'a str,
+    amount: JsNumber,
+    amount_raw: &'a str,
+    decimals: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dex: Option<&'a str>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    fee_type: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    recipient: Option<&'a str>,
+}
+
+impl<'a> From<&'a FeeInfo> for TsFeeInfo<'a> {
+    fn from(fee: &'a FeeInfo) -> Self {
+        Self {
+            mint: &fee.mint,
+            amount: JsNumber(fee.amount),
+            amount_raw: &fee.amount_raw,
+            decimals: fee.decimals,
+            dex: fee.dex.as_deref(),
+            fee_type: fee.fee_type.as_deref(),
+            recipient: fee.recipient.as_deref(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TsTradeInfo<'a> {
+    user: &'a str,
+    #[serde(rename = "type")]
+    trade_type: &'a TradeType,
+    #[serde(rename = "Pool")]
+    pool: &'a [String],
+    input_token: TsTokenInfo<'a>,
+    output_token: TsTokenInfo<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    slippage_bps: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fee: Option<TsFeeInfo<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    program_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amm: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amms: Option<&'a [String]>,
+    /// TS пишет `dexInfo.route || ''`.
+    route: &'a str,
+    slot: u64,
+    timestamp: u64,
+    signature: &'a str,
+    idx: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signer: Option<&'a [String]>,
+}
+
+impl<'a> From<&'a TradeInfo> for TsTradeInfo<'a> {
+    fn from(trade: &'a TradeInfo) -> Self {
+        Self {
+            user: trade.user.as_deref().unwrap_or(""),
+            trade_type: &trade.trade_type,
+            pool: &trade.pool,
+            input_token: (&trade.input_token).into(),
+            output_token: (&trade.output_token).into(),
+            slippage_bps: trade.slippage_bps,
+            fee: trade.fee.as_ref().map(TsFeeInfo::from),
+            program_id: trade.program_id.as_deref(),
+            amm: trade.amm.as_deref(),
+            amms: trade.amms.as_deref(),
+            route: trade.route.as_deref().unwrap_or(""),
+            slot: trade.slot,
+            timestamp: trade.timestamp,
+            signature: &trade.signature,
+            idx: &trade.idx,
+            signer: trade.signer.as_deref(),
+        }
+    }
+}
+
+/// `PoolEvent` TS: ноги `tokens[0]`/`tokens[1]` становятся плоскими `token0*`/`token1*`.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TsPoolEvent<'a> {
+    user: &'a str,
+    #[serde(rename = "type")]
+    event_type: &'a TradeType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    program_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    amm: Option<&'a str>,
+    slot: u64,
+    timestamp: u64,
+    signature: &'a str,
+    idx: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    signer: Option<&'a [String]>,
+    pool_id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    config: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pool_lp_mint: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token0_mint: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token0_amount: Option<JsNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token0_amount_raw: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token0_balance_change: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token0_decimals: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token1_mint: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token1_amount: Option<JsNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token1_amount_raw: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token1_balance_change: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token1_decimals: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lp_amount: Option<JsNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    lp_amount_raw: Option<&'a str>,
+}
+
+impl<'a> From<&'a PoolEvent> for TsPoolEvent<'a> {
+    fn from(event: &'a PoolEvent) -> Self {
+        let token0 = event.tokens.first();
+        let token1 = event.tokens.get(1);
+        Self {
+            user: &event.user,
+            event_type: &event.event_type,
+            program_id: event.program_id.as_deref(),
+            amm: event.amm.as_deref(),
+            slot: event.slot,
+            timestamp: event.timestamp,
+            signature: &event.signature,
+            idx: &event.idx,
+            signer: event.signer.as_deref(),
+            pool_id: &event.pool_id,
+            config: event.config.as_deref(),
+            pool_lp_mint: event.pool_lp_mint.as_deref(),
+            token0_mint: token0.map(|leg| leg.mint.as_str()),
+            token0_amount: token0.map(|leg| JsNumber(leg.amount)),
+            token0_amount_raw: token0.map(|leg| leg.amount_raw.as_str()),
+            token0_balance_change: token0.and_then(leg_balance_change),
+            token0_decimals: token0.map(|leg| leg.decimals),
+            token1_mint: token1.map(|leg| leg.mint.as_str()),
+            token1_amount: token1.map(|leg| JsNumber(leg.amount)),
+            token1_amount_raw: token1.map(|leg| leg.amount_raw.as_str()),
+            token1_balance_change: token1.and_then(leg_balance_change),
+            token1_decimals: token1.map(|leg| leg.decimals),
+            lp_amount: event.lp_amount.map(JsNumber),
+            lp_amount_raw: event.lp_amount_raw.as_deref(),
+        }
+    }
+}
+
+fn leg_balance_change(leg: &TokenLeg) -> Option<&str> {
+    leg.balance_change.as_deref()
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TsTransferData<'a> {
+    #[serde(rename = "type")]
+    transfer_type: &'a str,
+    program_id: &'a str,
+    info: TsTransferInfo<'a>,
+    idx: &'a str,
+    timestamp: u64,
+    signature: &'a str,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    is_fee: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TsTransferInfo<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    authority: Option<&'a str>,
+    destination: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    destination_owner: Option<&'a str>,
+    mint: &'a str,
+    source: &'a str,
+    token_amount: TsUiAmount<'a>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_balance: Option<TsTokenAmount<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source_pre_balance: Option<TsTokenAmount<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    destination_balance: Option<TsTokenAmount<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    destination_pre_balance: Option<TsTokenAmount<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sol_balance_change: Option<&'a str>,
+}
+
+/// `tokenAmount` перевода: в TS `uiAmount` всегда число.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TsUiAmount<'a> {
+    amount: &'a str,
+    ui_amount: JsNumber,
+    decimals: u8,
+}
+
+impl<'a> From<&'a TransferData> for TsTransferData<'a> {
+    fn from(transfer: &'a TransferData) -> Self {
+        let info = &transfer.info;
+        let amount = &info.token_amount;
+        Self {
+            transfer_type: &transfer.transfer_type,
+            program_id: &transfer.program_id,
+            info: TsTransferInfo {
+                authority: info.authority.as_deref(),
+                destination: &info.destination,
+                destination_owner: info.destination_owner.as_deref(),
+                mint: &info.mint,
+                source: &info.source,
+                token_amount: TsUiAmount {
+                    amount: &amount.amount,
+                    ui_amount: JsNumber(amount.ui_amount.unwrap_or_else(|| {
+                        amount.amount_decimal().map_or(0.0, |value| value.to_f64())
+                    })),
+                    decimals: amount.decimals,
+                },
+                source_balance: info.source_balance.as_ref().map(TsTokenAmount::from),
+                source_pre_balance: info.source_pre_balance.as_ref().map(TsTokenAmount::from),
+                destination_balance: info.destination_balance.as_ref().map(TsTokenAmount::from),
+                destination_pre_balance: info
+                    .destination_pre_balance
+                    .as_ref()
+                    .map(TsTokenAmount::from),
+                sol_balance_change: info.sol_balance_change.as_deref(),
+            },
+            idx: &transfer.idx,
+            timestamp: transfer.timestamp,
+            signature: &transfer.signature,
+            is_fee: transfer.is_fee,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TsMemeEvent<'a> {
+    #[serde(rename = "type")]
+    event_type: &'a TradeType,
+    timestamp: u64,
+    idx: &'a str,
+    slot: u64,
+    signature: &'a str,
+    user: &'a str,
+    base_mint: &'a str,
+    quote_mint: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    input_token: Option<TsTokenInfo<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output_token: Option<TsTokenInfo<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    symbol: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decimals: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    total_supply: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fee: Option<JsNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    protocol_fee: Option<JsNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    platform_fee: Option<JsNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    share_fee: Option<JsNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    creator_fee: Option<JsNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    protocol: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    platform_config: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    creator: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bonding_curve: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pool: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pool_dex: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pool_a_reserve: Option<JsNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pool_b_reserve: Option<JsNumber>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pool_fee_rate: Option<JsNumber>,
+}
+
+impl<'a> From<&'a MemeEvent> for TsMemeEvent<'a> {
+    fn from(event: &'a MemeEvent) -> Self {
+        Self {
+            event_type: &event.event_type,
+            timestamp: event.timestamp,
+            idx: &event.idx,
+            slot: event.slot,
+            signature: &event.signature,
+            user: &event.user,
+            base_mint: &event.base_mint,
+            quote_mint: &event.quote_mint,
+            input_token: event.input_token.as_ref().map(TsTokenInfo::from),
+            output_token: event.output_token.as_ref().map(TsTokenInfo::from),
+            name: event.name.as_deref(),
+            symbol: event.symbol.as_deref(),
+            uri: event.uri.as_deref(),
+            decimals: event.decimals,
+            total_supply: event.total_supply,
+            fee: event.fee.map(JsNumber),
+            protocol_fee: event.protocol_fee.map(JsNumber),
+            platform_fee: event.platform_fee.map(JsNumber),
+            share_fee: event.share_fee.map(JsNumber),
+            creator_fee: event.creator_fee.map(JsNumber),
+            protocol: event.protocol.as_deref(),
+            platform_config: event.platform_config.as_deref(),
+            creator: event.creator.as_deref(),
+            bonding_curve: event.bonding_curve.as_deref(),
+            pool: event.pool.as_deref(),
+            pool_dex: event.pool_dex.as_deref(),
+            pool_a_reserve: event.pool_a_reserve.map(JsNumber),
+            pool_b_reserve: event.pool_b_reserve.map(JsNumber),
+            pool_fee_rate: event.pool_fee_rate.map(JsNumber),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{LegDirection, TradeFlag};
+
+    #[test]
+    fn matches_ts_key_order_and_optional_fields() {
+        let mut result = ParseResult::new();
+        result.signature = "sig".to_string();
+        result.tx_status = TransactionStatus::Success;
+        result.trades.push(TradeInfo {
+            user: Some("wallet".to_string()),
+            trade_type: TradeType::Buy,
+            pool: vec!["pool".to_string()],
+            amm: Some("Pumpfun".to_string()),
+            flags: vec![TradeFlag::SelfTrade],
+            ..TradeInfo::default()
+        });
+        result.liquidities.push(PoolEvent {
+            event_type: TradeType::Add,
+            pool_id: "pool".to_string(),
+            tokens: vec![TokenLeg::from_raw("A", 1_000, 3, LegDirection::In)],
+            ..PoolEvent::default()
+        });
+        result.sol_balance_change = Some(BalanceChange {
+            pre: 2_000_000_000,
+            post: 1_500_000_000,
+            change: -500_000_000,
+        });
+        result
+            .token_balance_change
+            .insert("A".to_string(), BalanceChange::default());
+
+        let json = to_ts_json(&result).unwrap();
+        assert!(json.starts_with(
+            "{\"state\":true,\"fee\":{\"amount\":\"0\",\"uiAmount\":0,\"decimals\":9},\"trades\":["
+        ));
+        assert!(json.contains(
+            "],\"memeEvents\":[],\"slot\":0,\"msg\":\"\",\"timestamp\":0,\"signature\":\"sig\",\
+             \"signer\":[],\"computeUnits\":0,\"txStatus\":\"success\",\"solBalanceChange\":{\"pre\":"
+        ));
+        assert!(json.ends_with("\"tokenBalanceChange\":{}}"));
+
+        assert!(json
+            .contains("{\"user\":\"wallet\",\"type\":\"BUY\",\"Pool\":[\"pool\"],\"inputToken\""));
+        assert!(json.contains("\"amm\":\"Pumpfun\",\"route\":\"\",\"slot\""));
+        assert!(json
+            .contains("\"change\":{\"amount\":\"-500000000\",\"uiAmount\":-0.5,\"decimals\":9}"));
+        assert!(json.contains("\"poolId\":\"pool\",\"token0Mint\":\"A\",\"token0Amount\":1,\"token0AmountRaw\":\"1000\""));
+        for absent in [
+            "flags",
+            "confidence",
+            "instructionIndex",
+            "token1Mint",
+            "null",
+        ] {
+            assert!(!json.contains(absent), "{absent} in {json}");
+        }
+    }
+
+    /// Ожидаемые строки — `JSON.stringify` тех же чисел в Node.
+    #[test]
+    fn numbers_are_formatted_like_json_stringify() {
+        let cases = [
+            (0.000005, "0.000005"),
+            (0.0000015, "0.0000015"),
+            (0.000001, "0.000001"),
+            (3.3e-8, "3.3e-8"),
+            (1e-7, "1e-7"),
+            (1.5e-7, "1.5e-7"),
+            (5e-324, "5e-324"),
+            (0.1, "0.1"),
+            (-0.5, "-0.5"),
+            (2.0, "2"),
+            (-0.0, "0"),
+            (123_456_789_012_345_680_000.0, "123456789012345680000"),
+            (1e21, "1e+21"),
+            (1.2345e22, "1.2345e+22"),
+            (f64::MAX, "1.7976931348623157e+308"),
+            (f64::NAN, "null"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(js_number_string(value), expected, "{value:e}");
+            assert_eq!(serde_json::to_string(&JsNumber(value)).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn fee_and_tiny_amounts_keep_decimal_notation() {
+        let mut result = ParseResult::new();
+        result.fee = TokenAmount::new("5000", 9, Some(0.000005));
+        result.liquidities.push(PoolEvent {
+            tokens: vec![TokenLeg::from_raw("A", 1_500, 9, LegDirection::In)],
+            ..PoolEvent::default()
+        });
+
+        let json = to_ts_json(&result).unwrap();
+        assert!(json.contains("\"fee\":{\"amount\":\"5000\",\"uiAmount\":0.000005,\"decimals\":9}"));
+        assert!(json.contains("\"token0Amount\":0.0000015,"));
+        let value = serde_json::to_value(TsParseResult::from(&result)).unwrap();
+        assert_eq!(value["fee"]["uiAmount"], 0.000005);
+    }
+}
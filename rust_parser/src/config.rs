@@ -1,5 +1,13 @@
+use std::collections::HashSet;
+
 use serde::{Deserialize, Serialize};
 
+use crate::core::constants::{JITO_TIP_ACCOUNTS, QUOTE_MINTS};
+
+mod runtime;
+
+pub use runtime::{redact_url, ConfigError, Failover, RuntimeConfig};
+
 /// Configuration for the parser mirroring the TypeScript structure.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
@@ -17,6 +25,111 @@ pub struct ParseConfig {
     pub throw_error: bool,
     #[serde(default = "ParseConfig::default_aggregate_trades")]
     pub aggregate_trades: bool,
+    /// Как строится `ParseResult::aggregate_trade` при `aggregate_trades`.
+    #[serde(default)]
+    pub aggregate_strategy: AggregateStrategy,
+    #[serde(default)]
+    pub include_failed: FailedTxMode,
+    /// Собирать `ParseResult::diagnostics` (программы, парсеры, пропуски, тайминги).
+    #[serde(default)]
+    pub collect_diagnostics: bool,
+    /// Логировать тайминги стадий через `tracing::debug!`. Без feature `parse-metrics` игнорируется.
+    #[serde(default)]
+    pub verbose_timing: bool,
+    /// Заполнять `TradeInfo::price_quote_per_base`.
+    #[serde(default)]
+    pub compute_prices: bool,
+    /// Искать в trades цикл обратно во входной mint и заполнять `ParseResult::arbitrage`.
+    #[serde(default)]
+    pub detect_arbitrage: bool,
+    /// Оставлять только transfers, trades, pool и meme events с участием этих mint'ов;
+    /// транзакции, не затрагивающие их балансы, не разбираются вовсе.
+    #[serde(default)]
+    pub mint_filter: Option<HashSet<String>>,
+    /// Заполнять `input_symbol`/`output_symbol` из `TokenMetadataSource` парсера
+    /// (`DexParser::with_metadata_source`).
+    #[serde(default)]
+    pub resolve_metadata: bool,
+    /// Прикладывать к результату `ParseResult::classified_instructions`.
+    #[serde(default)]
+    pub include_classified_instructions: bool,
+    /// Tip-аккаунты сверх `JITO_TIP_ACCOUNTS` (другие block engine, свои relayer'ы).
+    #[serde(default)]
+    pub tip_accounts: Option<HashSet<String>>,
+    /// В `DexParser::parse_zc` разбирать Pumpswap zero-copy парсером без конвертации
+    /// в `SolanaTransaction`. Выключение — owned-парсер, как в `parse_all`.
+    #[serde(default = "ParseConfig::default_prefer_zero_copy")]
+    pub prefer_zero_copy: bool,
+    /// Quote-токены по убыванию приоритета (например JLP, BONK для своих рынков):
+    /// по ним выбираются quote/base в `price_quote_per_base` и распознаются свапы
+    /// неизвестных DEX. Пустой список — `QUOTE_MINTS` (USDC, USDT, SOL).
+    #[serde(default)]
+    pub quote_mints: Vec<String>,
+    /// Какой токен сделки считать base для `TradeInfo::side`.
+    #[serde(default)]
+    pub base_token: BaseTokenConvention,
+    /// Переписывать WSOL-ноги trades и transfers на кошелёк владельца и убирать
+    /// wrap/unwrap переводы (`core::wsol::normalize_wsol`).
+    #[serde(default)]
+    pub normalize_wsol: bool,
+    /// Когда срабатывает `try_unknown_dex`, собирать `ParseResult::unknown_programs`:
+    /// дискриминаторы и число аккаунтов инструкций программ без парсера.
+    #[serde(default)]
+    pub collect_unknown_programs: bool,
+    /// Проверять инварианты trades (разные mint'ы, ненулевые суммы, decimals по token
+    /// balances, сумма ног против изменения балансов) и уводить нарушителей
+    /// в `ParseResult::rejected_trades` (`core::invariants`).
+    #[serde(default)]
+    pub strict: bool,
+    /// Отдавать trades в `ParseResult::trades_lite` (`TradeInfoLite`) вместо полных
+    /// `TradeInfo`: меньше сообщение и дешевле сериализация для стриминга.
+    #[serde(default)]
+    pub lite_trades: bool,
+    /// JSON-вывод (`compat::to_json`, CLI, wasm) в форме TS-пакета solana-dex-parser:
+    /// те же ключи, регистр `txStatus`, опущенные вместо `null` поля, без Rust-расширений.
+    /// `impl Serialize for ParseResult` флаг не видит и всегда пишет Rust-форму:
+    /// сериализуйте через `compat::to_json` (или `compat::TsParseResult`).
+    #[serde(default)]
+    pub ts_compat_output: bool,
+}
+
+/// Сборка сводной сделки из хопов маршрута.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum AggregateStrategy {
+    /// Последний хоп как есть (прежнее поведение).
+    #[default]
+    LastHop,
+    /// Вход первого хопа и выход последнего.
+    RouteEndpoints,
+    /// Из изменений балансов signer'а по mint'ам сделок: больше всего потрачено —
+    /// вход, больше всего получено — выход. Комиссия и tip в SOL не учитываются.
+    /// Без подходящих балансов — как `RouteEndpoints`.
+    NetSignerFlow,
+}
+
+/// Выбор base-токена сделки для `TradeInfo::side`.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BaseTokenConvention {
+    /// Quote — mint с большим приоритетом в `quote_mints` (SOL/USDC: base — SOL).
+    #[default]
+    QuotePriority,
+    /// SOL всегда quote, если участвует в сделке (SOL/USDC: base — USDC).
+    SolQuote,
+}
+
+/// What to do with transactions whose `meta.err` is set.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum FailedTxMode {
+    /// Return an empty result with `state = false`.
+    Skip,
+    /// Fill metadata (signature, fee, error) but skip trade/liquidity/transfer parsing.
+    MetadataOnly,
+    /// Parse as usual; whatever instruction data survived is reported.
+    #[default]
+    AttemptFull,
 }
 
 impl Default for ParseConfig {
@@ -27,11 +140,55 @@ impl Default for ParseConfig {
             ignore_program_ids: None,
             throw_error: Self::default_throw_error(),
             aggregate_trades: Self::default_aggregate_trades(),
+            aggregate_strategy: AggregateStrategy::default(),
+            include_failed: FailedTxMode::default(),
+            collect_diagnostics: false,
+            verbose_timing: false,
+            compute_prices: false,
+            detect_arbitrage: false,
+            mint_filter: None,
+            resolve_metadata: false,
+            include_classified_instructions: false,
+            tip_accounts: None,
+            prefer_zero_copy: Self::default_prefer_zero_copy(),
+            quote_mints: Vec::new(),
+            base_token: BaseTokenConvention::default(),
+            normalize_wsol: false,
+            collect_unknown_programs: false,
+            strict: false,
+            lite_trades: false,
+            ts_compat_output: false,
         }
     }
 }
 
 impl ParseConfig {
+    /// `true`, если `mint_filter` не задан или содержит `mint`.
+    pub fn allows_mint(&self, mint: &str) -> bool {
+        self.mint_filter
+            .as_ref()
+            .is_none_or(|mints| mints.contains(mint))
+    }
+
+    /// Jito tip-аккаунт или адрес из `tip_accounts`.
+    pub fn is_tip_account(&self, address: &str) -> bool {
+        JITO_TIP_ACCOUNTS.contains(&address)
+            || self
+                .tip_accounts
+                .as_ref()
+                .is_some_and(|accounts| accounts.contains(address))
+    }
+
+    /// Позиция `mint` в `quote_mints` (или в `QUOTE_MINTS`, если список пуст);
+    /// меньше — сильнее quote.
+    pub fn quote_priority(&self, mint: &str) -> Option<usize> {
+        if self.quote_mints.is_empty() {
+            QUOTE_MINTS.iter().position(|quote| *quote == mint)
+        } else {
+            self.quote_mints.iter().position(|quote| quote == mint)
+        }
+    }
+
     const fn default_try_unknown_dex() -> bool {
         true
     }
@@ -43,4 +200,8 @@ impl ParseConfig {
     const fn default_aggregate_trades() -> bool {
         true
     }
+
+    const fn default_prefer_zero_copy() -> bool {
+        true
+    }
 }
@@ -0,0 +1,308 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// Путь к TOML-файлу, если не передан `--config`.
+pub const CONFIG_ENV: &str = "DEX_PARSER_CONFIG";
+/// Список RPC endpoints через запятую.
+pub const RPC_URL_ENV: &str = "SOLANA_RPC_URL";
+/// Список WebSocket endpoints через запятую.
+pub const WS_URL_ENV: &str = "SOLANA_WS_URL";
+pub const API_KEY_ENV: &str = "HELIUS_API_KEY";
+/// `host:port` Prometheus-эндпоинта бинарников (feature `prometheus`).
+pub const METRICS_ADDR_ENV: &str = "METRICS_ADDR";
+
+const PUBLIC_RPC_URL: &str = "https://api.mainnet-beta.solana.com";
+const HELIUS_RPC_URL: &str = "https://mainnet.helius-rpc.com/?api-key={api_key}";
+const HELIUS_WS_URL: &str = "wss://atlas-mainnet.helius-rpc.com/?api-key={api_key}";
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to read config {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("invalid config {path}: {source}")]
+    Toml {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("flag {0} requires a value")]
+    MissingValue(String),
+    #[error("no {0} endpoint configured (set {1} or pass --{0}-url)")]
+    MissingEndpoint(&'static str, &'static str),
+}
+
+/// Endpoints и ключи для бинарей и сервисов поверх парсера.
+///
+/// Источники по возрастанию приоритета: TOML-файл (`--config` или `DEX_PARSER_CONFIG`),
+/// переменные окружения (`SOLANA_RPC_URL`, `SOLANA_WS_URL`, `HELIUS_API_KEY`, `METRICS_ADDR`),
+/// флаги `--rpc-url`/`--ws-url` (повторяемые), `--api-key` и `--metrics-addr`. Непустой
+/// список из источника выше заменяет список целиком.
+///
+/// ```toml
+/// api_key = "..."
+/// rpc_urls = ["https://mainnet.helius-rpc.com/?api-key={api_key}", "https://api.mainnet-beta.solana.com"]
+/// ws_urls = ["wss://atlas-mainnet.helius-rpc.com/?api-key={api_key}"]
+/// metrics_addr = "0.0.0.0:9100"
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RuntimeConfig {
+    /// В порядке приоритета; `{api_key}` подставляется из `api_key`.
+    pub rpc_urls: Vec<String>,
+    pub ws_urls: Vec<String>,
+    pub api_key: Option<String>,
+    /// Где отдавать `/metrics` (`metrics::install_prometheus`), например `0.0.0.0:9100`.
+    pub metrics_addr: Option<String>,
+}
+
+impl RuntimeConfig {
+    /// Файл, окружение и флаги из `args` (без имени программы). Возвращает конфиг
+    /// и оставшиеся позиционные аргументы.
+    pub fn load(
+        args: impl IntoIterator<Item = String>,
+    ) -> Result<(Self, Vec<String>), ConfigError> {
+        let (flags, config_path, rest) = Self::parse_args(args)?;
+        let mut config =
+            match config_path.or_else(|| std::env::var(CONFIG_ENV).ok().map(PathBuf::from)) {
+                Some(path) => Self::from_toml_file(&path)?,
+                None => Self::default(),
+            };
+        config.merge(Self::from_env());
+        config.merge(flags);
+        Ok((config, rest))
+    }
+
+    pub fn from_toml_file(path: &Path) -> Result<Self, ConfigError> {
+        let text = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        toml::from_str(&text).map_err(|source| ConfigError::Toml {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    pub fn from_env() -> Self {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    fn from_lookup(get: impl Fn(&str) -> Option<String>) -> Self {
+        let list = |name| {
+            get(name)
+                .map(|value| {
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|url| !url.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        Self {
+            rpc_urls: list(RPC_URL_ENV),
+            ws_urls: list(WS_URL_ENV),
+            api_key: get(API_KEY_ENV).filter(|key| !key.is_empty()),
+            metrics_addr: get(METRICS_ADDR_ENV).filter(|addr| !addr.is_empty()),
+        }
+    }
+
+    /// `--flag value` и `--flag=value`; всё, что не флаг конфига, уходит в остаток.
+    fn parse_args(
+        args: impl IntoIterator<Item = String>,
+    ) -> Result<(Self, Option<PathBuf>, Vec<String>), ConfigError> {
+        let mut flags = Self::default();
+        let mut config_path = None;
+        let mut rest = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (flag, inline) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => {
+                    (flag.to_string(), Some(value.to_string()))
+                }
+                _ => (arg.clone(), None),
+            };
+            if !matches!(
+                flag.as_str(),
+                "--config" | "--rpc-url" | "--ws-url" | "--api-key" | "--metrics-addr"
+            ) {
+                rest.push(arg);
+                continue;
+            }
+            let value = match inline {
+                Some(value) => value,
+                None => args
+                    .next()
+                    .ok_or_else(|| ConfigError::MissingValue(flag.clone()))?,
+            };
+            match flag.as_str() {
+                "--config" => config_path = Some(PathBuf::from(value)),
+                "--rpc-url" => flags.rpc_urls.push(value),
+                "--ws-url" => flags.ws_urls.push(value),
+                "--metrics-addr" => flags.metrics_addr = Some(value),
+                _ => flags.api_key = Some(value),
+            }
+        }
+        Ok((flags, config_path, rest))
+    }
+
+    /// Поля `other` поверх текущих: непустые списки и заданный ключ.
+    pub fn merge(&mut self, other: Self) {
+        if !other.rpc_urls.is_empty() {
+            self.rpc_urls = other.rpc_urls;
+        }
+        if !other.ws_urls.is_empty() {
+            self.ws_urls = other.ws_urls;
+        }
+        if other.api_key.is_some() {
+            self.api_key = other.api_key;
+        }
+        if other.metrics_addr.is_some() {
+            self.metrics_addr = other.metrics_addr;
+        }
+    }
+
+    fn expand(&self, url: &str) -> String {
+        match &self.api_key {
+            Some(key) => url.replace("{api_key}", key),
+            None => url.to_string(),
+        }
+    }
+
+    /// RPC endpoints с подставленным ключом. Без явных URL — Helius при заданном
+    /// `api_key`, иначе публичный mainnet RPC.
+    pub fn rpc_endpoints(&self) -> Vec<String> {
+        if !self.rpc_urls.is_empty() {
+            return self.rpc_urls.iter().map(|url| self.expand(url)).collect();
+        }
+        match self.api_key {
+            Some(_) => vec![self.expand(HELIUS_RPC_URL)],
+            None => vec![PUBLIC_RPC_URL.to_string()],
+        }
+    }
+
+    /// WebSocket endpoints с подставленным ключом. Без явных URL — Helius Atlas
+    /// (`transactionSubscribe`) при заданном `api_key`.
+    pub fn ws_endpoints(&self) -> Vec<String> {
+        if !self.ws_urls.is_empty() {
+            return self.ws_urls.iter().map(|url| self.expand(url)).collect();
+        }
+        match self.api_key {
+            Some(_) => vec![self.expand(HELIUS_WS_URL)],
+            None => Vec::new(),
+        }
+    }
+
+    pub fn rpc_failover(&self) -> Failover {
+        Failover::new(self.rpc_endpoints())
+    }
+
+    pub fn ws_failover(&self) -> Result<Failover, ConfigError> {
+        let endpoints = self.ws_endpoints();
+        if endpoints.is_empty() {
+            return Err(ConfigError::MissingEndpoint("ws", WS_URL_ENV));
+        }
+        Ok(Failover::new(endpoints))
+    }
+}
+
+/// Список endpoints с текущим: после ошибки `advance` переключает на следующий по кругу.
+/// Общий между задачами (`&self`).
+#[derive(Debug)]
+pub struct Failover {
+    urls: Vec<String>,
+    current: AtomicUsize,
+}
+
+impl Failover {
+    /// `urls` не должен быть пустым.
+    pub fn new(urls: Vec<String>) -> Self {
+        assert!(!urls.is_empty(), "Failover needs at least one endpoint");
+        Self {
+            urls,
+            current: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn current(&self) -> &str {
+        &self.urls[self.current.load(Ordering::Relaxed) % self.urls.len()]
+    }
+
+    /// Переключиться на следующий endpoint и вернуть его.
+    pub fn advance(&self) -> &str {
+        let next = self.current.fetch_add(1, Ordering::Relaxed) + 1;
+        &self.urls[next % self.urls.len()]
+    }
+
+    pub fn urls(&self) -> &[String] {
+        &self.urls
+    }
+
+    pub fn len(&self) -> usize {
+        self.urls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.urls.is_empty()
+    }
+}
+
+/// URL для логов: значения `api-key`/`api_key`/`token` в query заменены на `***`.
+pub fn redact_url(url: &str) -> String {
+    let Some((base, query)) = url.split_once('?') else {
+        return url.to_string();
+    };
+    let query: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if matches!(key, "api-key" | "api_key" | "token") => {
+                format!("{key}=***")
+            }
+            _ => pair.to_string(),
+        })
+        .collect();
+    format!("{base}?{}", query.join("&"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_override_env_and_expand_api_key() {
+        let env = RuntimeConfig::from_lookup(|name| match name {
+            RPC_URL_ENV => {
+                Some("https://a.example/?api-key={api_key}, https://b.example".to_string())
+            }
+            API_KEY_ENV => Some("env-key".to_string()),
+            _ => None,
+        });
+        let args = ["--api-key=cli-key", "mints", "--ws-url", "wss://w.example"].map(String::from);
+        let (flags, config_path, rest) = RuntimeConfig::parse_args(args).unwrap();
+        assert!(config_path.is_none());
+        assert_eq!(rest, ["mints"]);
+
+        let mut config = RuntimeConfig::default();
+        config.merge(env);
+        config.merge(flags);
+        assert_eq!(
+            config.rpc_endpoints(),
+            ["https://a.example/?api-key=cli-key", "https://b.example"]
+        );
+        assert_eq!(config.ws_endpoints(), ["wss://w.example"]);
+
+        let failover = config.rpc_failover();
+        assert_eq!(failover.advance(), "https://b.example");
+        assert_eq!(failover.advance(), "https://a.example/?api-key=cli-key");
+        assert_eq!(
+            redact_url(failover.current()),
+            "https://a.example/?api-key=***"
+        );
+    }
+}
@@ -0,0 +1,164 @@
+use crate::config::AggregateStrategy;
+use crate::core::constants::TOKENS;
+use crate::protocols::pumpfun::util::convert_to_ui_amount;
+use crate::types::{FeeInfo, ParseResult, TokenInfo, TradeInfo};
+
+/// Сводная сделка по `result.trades` (уже в порядке `idx`) с комиссией транзакции.
+pub fn aggregate_trade(result: &ParseResult, strategy: AggregateStrategy) -> Option<TradeInfo> {
+    let mut trade = match strategy {
+        AggregateStrategy::LastHop => result.trades.last().cloned(),
+        AggregateStrategy::RouteEndpoints => route_endpoints(&result.trades),
+        AggregateStrategy::NetSignerFlow => {
+            net_signer_flow(result).or_else(|| route_endpoints(&result.trades))
+        }
+    }?;
+    if result.fee.amount != "0" {
+        trade.fee = Some(FeeInfo {
+            mint: "SOL".to_string(),
+            amount: result.fee.ui_amount.unwrap_or(0.0),
+            amount_raw: result.fee.amount.clone(),
+            decimals: result.fee.decimals,
+            ..Default::default()
+        });
+    }
+    Some(trade)
+}
+
+fn route_endpoints(trades: &[TradeInfo]) -> Option<TradeInfo> {
+    let first = trades.first()?;
+    let mut trade = trades.last()?.clone();
+    if trades.len() == 1 {
+        return Some(trade);
+    }
+    trade.input_token = first.input_token.clone();
+    trade.input_symbol = first.input_symbol.clone();
+    trade.idx = first.idx.clone();
+    let mut amms: Vec<String> = Vec::new();
+    for amm in trades.iter().filter_map(|hop| hop.amm.as_ref()) {
+        if !amms.contains(amm) {
+            amms.push(amm.clone());
+        }
+    }
+    trade.amms = (!amms.is_empty()).then_some(amms);
+    Some(trade)
+}
+
+/// Вход/выход по чистому изменению балансов signer'а. SOL и WSOL — один mint;
+/// к изменению SOL возвращаются комиссия транзакции и Jito tip.
+fn net_signer_flow(result: &ParseResult) -> Option<TradeInfo> {
+    let mut mints: Vec<&TokenInfo> = Vec::new();
+    for token in result
+        .trades
+        .iter()
+        .flat_map(|trade| [&trade.input_token, &trade.output_token])
+    {
+        if !mints.iter().any(|known| known.mint == token.mint) {
+            mints.push(token);
+        }
+    }
+
+    let raw = |amount: &str| amount.parse::<i128>().unwrap_or(0);
+    let net = |mint: &str| -> i128 {
+        let mut change = result
+            .token_balance_change
+            .get(mint)
+            .map_or(0, |change| change.change);
+        if mint == TOKENS.SOL {
+            if let Some(sol) = &result.sol_balance_change {
+                change += sol.change + raw(&result.fee.amount);
+                change += result.jito_tip.as_ref().map_or(0, |tip| raw(&tip.amount));
+            }
+        }
+        change
+    };
+    let flows: Vec<(&TokenInfo, i128)> = mints.into_iter().map(|t| (t, net(&t.mint))).collect();
+    let (input, spent) = flows.iter().min_by_key(|(_, change)| *change)?;
+    let (output, received) = flows.iter().max_by_key(|(_, change)| *change)?;
+    if *spent >= 0 || *received <= 0 {
+        return None;
+    }
+
+    let leg = |token: &TokenInfo, amount: i128| {
+        let amount = amount.unsigned_abs();
+        TokenInfo {
+            mint: token.mint.clone(),
+            amount: convert_to_ui_amount(amount, token.decimals),
+            amount_raw: amount.to_string(),
+            decimals: token.decimals,
+            ..Default::default()
+        }
+    };
+    let mut trade = route_endpoints(&result.trades)?;
+    trade.input_symbol = None;
+    trade.output_symbol = None;
+    trade.input_token = leg(input, *spent);
+    trade.output_token = leg(output, *received);
+    Some(trade)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{BalanceChange, TokenAmount};
+
+    fn token(mint: &str, raw: u64) -> TokenInfo {
+        TokenInfo {
+            mint: mint.to_string(),
+            amount: convert_to_ui_amount(raw, 6),
+            amount_raw: raw.to_string(),
+            decimals: 6,
+            ..Default::default()
+        }
+    }
+
+    fn hop(input: TokenInfo, output: TokenInfo, idx: &str, amm: &str) -> TradeInfo {
+        TradeInfo {
+            input_token: input,
+            output_token: output,
+            idx: idx.to_string(),
+            amm: Some(amm.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn strategies_build_different_aggregates() {
+        let mut result = ParseResult::new();
+        result.fee = TokenAmount::new("5000", 9, Some(0.000005));
+        result.trades = vec![
+            hop(token("A", 100), token("B", 50), "0-0", "Raydium"),
+            hop(token("B", 50), token("C", 20), "0-1", "Orca"),
+        ];
+        let change = |change| BalanceChange {
+            pre: 0,
+            post: 0,
+            change,
+        };
+        // часть A вернулась signer'у как сдача
+        result.token_balance_change = [
+            ("A".to_string(), change(-90)),
+            ("C".to_string(), change(20)),
+        ]
+        .into();
+
+        let last = aggregate_trade(&result, AggregateStrategy::LastHop).unwrap();
+        assert_eq!(last.input_token.mint, "B");
+        assert_eq!(last.fee.unwrap().amount_raw, "5000");
+
+        let route = aggregate_trade(&result, AggregateStrategy::RouteEndpoints).unwrap();
+        assert_eq!(
+            (
+                route.input_token.mint.as_str(),
+                route.output_token.mint.as_str()
+            ),
+            ("A", "C")
+        );
+        assert_eq!(route.input_token.amount_raw, "100");
+        assert_eq!(route.amms.unwrap(), ["Raydium", "Orca"]);
+
+        let net = aggregate_trade(&result, AggregateStrategy::NetSignerFlow).unwrap();
+        assert_eq!(net.input_token.amount_raw, "90");
+        assert_eq!(net.output_token.amount_raw, "20");
+        assert_eq!(net.idx, "0-0");
+    }
+}
@@ -0,0 +1,240 @@
+//! События Anchor из `logMessages`: `emit!` пишет `Program data: base64(discriminator || borsh)`.
+//!
+//! Строки `Program data:` привязываются к программе по стеку `invoke`/`success`,
+//! тело декодируется по зарегистрированному 8-байтному дискриминатору.
+
+use std::collections::HashMap;
+
+use base64_simd::STANDARD;
+
+/// Сырое событие из лога.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEvent {
+    /// Программа, в контексте которой записана строка.
+    pub program_id: String,
+    /// Номер вызова этой программы в транзакции (с нуля, по всем уровням стека).
+    pub invocation: usize,
+    /// Глубина вызова из `invoke [N]`.
+    pub stack_height: u32,
+    /// Дискриминатор и borsh-тело.
+    pub data: Vec<u8>,
+}
+
+impl LogEvent {
+    pub fn discriminator(&self) -> Option<&[u8]> {
+        self.data.get(..8)
+    }
+
+    pub fn body(&self) -> &[u8] {
+        self.data.get(8..).unwrap_or_default()
+    }
+}
+
+enum LogLine<'a> {
+    Invoke {
+        program_id: &'a str,
+        stack_height: u32,
+    },
+    Exit,
+    Data(&'a str),
+}
+
+fn classify_line(line: &str) -> Option<LogLine<'_>> {
+    if let Some(encoded) = line
+        .strip_prefix("Program data: ")
+        .or_else(|| line.strip_prefix("Program log: data: "))
+    {
+        return Some(LogLine::Data(encoded.trim()));
+    }
+    let (program_id, tail) = line.strip_prefix("Program ")?.split_once(' ')?;
+    if let Some(height) = tail.strip_prefix("invoke [") {
+        let stack_height = height.trim_end_matches(']').parse().unwrap_or(0);
+        Some(LogLine::Invoke {
+            program_id,
+            stack_height,
+        })
+    } else if tail == "success" || tail.starts_with("failed") {
+        Some(LogLine::Exit)
+    } else {
+        None
+    }
+}
+
+/// Проход по логам; `on_invoke` получает программу и её номер вызова,
+/// `on_data` — декодированный payload с текущей программой стека.
+fn walk_logs(
+    log_messages: &[String],
+    mut on_invoke: impl FnMut(&str, usize),
+    mut on_data: impl FnMut(LogEvent),
+) {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    // (program_id, invocation, stack_height)
+    let mut stack: Vec<(&str, usize, u32)> = Vec::new();
+
+    for line in log_messages {
+        match classify_line(line) {
+            Some(LogLine::Invoke {
+                program_id,
+                stack_height,
+            }) => {
+                let count = counts.entry(program_id).or_insert(0);
+                on_invoke(program_id, *count);
+                stack.push((program_id, *count, stack_height));
+                *count += 1;
+            }
+            Some(LogLine::Exit) => {
+                stack.pop();
+            }
+            Some(LogLine::Data(encoded)) => {
+                let Some(&(program_id, invocation, stack_height)) = stack.last() else {
+                    continue;
+                };
+                let Ok(data) = STANDARD.decode_to_vec(encoded) else {
+                    continue;
+                };
+                on_data(LogEvent {
+                    program_id: program_id.to_string(),
+                    invocation,
+                    stack_height,
+                    data,
+                });
+            }
+            None => {}
+        }
+    }
+}
+
+/// Вызовы программ `(program_id, stack_height)` по строкам `invoke [N]`, в порядке исполнения.
+pub fn invocations(log_messages: &[String]) -> Vec<(&str, u32)> {
+    log_messages
+        .iter()
+        .filter_map(|line| match classify_line(line) {
+            Some(LogLine::Invoke {
+                program_id,
+                stack_height,
+            }) => Some((program_id, stack_height)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Все `Program data:` транзакции в порядке исполнения.
+pub fn log_events(log_messages: &[String]) -> Vec<LogEvent> {
+    let mut events = Vec::new();
+    walk_logs(log_messages, |_, _| {}, |event| events.push(event));
+    events
+}
+
+pub type EventDecodeFn<T> = fn(&[u8]) -> Option<T>;
+
+/// Набор layout'ов событий одной программы: дискриминатор → декодер borsh-тела.
+#[derive(Debug, Clone)]
+pub struct AnchorEventDecoder<T> {
+    program_id: &'static str,
+    layouts: Vec<([u8; 8], EventDecodeFn<T>)>,
+}
+
+impl<T> AnchorEventDecoder<T> {
+    pub fn new(program_id: &'static str) -> Self {
+        Self {
+            program_id,
+            layouts: Vec::new(),
+        }
+    }
+
+    pub fn register(mut self, discriminator: [u8; 8], decode: EventDecodeFn<T>) -> Self {
+        self.layouts.push((discriminator, decode));
+        self
+    }
+
+    pub fn program_id(&self) -> &str {
+        self.program_id
+    }
+
+    /// Декодирует событие, если оно от этой программы и дискриминатор известен.
+    pub fn decode(&self, event: &LogEvent) -> Option<T> {
+        if event.program_id != self.program_id {
+            return None;
+        }
+        let discriminator = event.discriminator()?;
+        let (_, decode) = self
+            .layouts
+            .iter()
+            .find(|(known, _)| known.as_slice() == discriminator)?;
+        decode(event.body())
+    }
+
+    /// События программы в порядке исполнения.
+    pub fn decode_all(&self, log_messages: &[String]) -> Vec<T> {
+        self.by_invocation(log_messages)
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// События по вызовам программы: i-й элемент — события i-го `Program <id> invoke`
+    /// (вызовы без событий дают пустой вектор).
+    pub fn by_invocation(&self, log_messages: &[String]) -> Vec<Vec<T>> {
+        let mut invocations: Vec<Vec<T>> = Vec::new();
+        let mut decoded = Vec::new();
+        walk_logs(
+            log_messages,
+            |program_id, _| {
+                if program_id == self.program_id {
+                    invocations.push(Vec::new());
+                }
+            },
+            |event| {
+                if let Some(value) = self.decode(&event) {
+                    decoded.push((event.invocation, value));
+                }
+            },
+        );
+        for (invocation, value) in decoded {
+            invocations[invocation].push(value);
+        }
+        invocations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = "Prog111111111111111111111111111111111111111";
+    const EVENT: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    fn data_line(discriminator: [u8; 8], value: u64) -> String {
+        let mut data = discriminator.to_vec();
+        data.extend_from_slice(&value.to_le_bytes());
+        format!("Program data: {}", STANDARD.encode_to_string(data))
+    }
+
+    #[test]
+    fn attributes_events_to_invocations_of_program() {
+        let logs: Vec<String> = vec![
+            format!("Program {PROGRAM} invoke [1]"),
+            data_line(EVENT, 7),
+            "Program Other11111111111111111111111111111111 invoke [2]".to_string(),
+            data_line(EVENT, 99),
+            "Program Other11111111111111111111111111111111 success".to_string(),
+            data_line([9; 8], 1),
+            format!("Program {PROGRAM} success"),
+            format!("Program {PROGRAM} invoke [1]"),
+            format!("Program {PROGRAM} success"),
+        ];
+
+        let decoder = AnchorEventDecoder::new(PROGRAM).register(EVENT, |body| {
+            Some(u64::from_le_bytes(body.try_into().ok()?))
+        });
+        assert_eq!(decoder.by_invocation(&logs), vec![vec![7], vec![]]);
+
+        let events = log_events(&logs);
+        assert_eq!(events.len(), 3);
+        assert_eq!(
+            events[1].program_id,
+            "Other11111111111111111111111111111111"
+        );
+        assert_eq!(events[1].stack_height, 2);
+    }
+}
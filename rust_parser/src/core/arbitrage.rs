@@ -0,0 +1,42 @@
+use crate::types::{ArbitrageInfo, TradeInfo};
+
+/// Ищет цепочку trades (в порядке `idx`), где вход каждого хопа — выход
+/// предыдущего, а последний хоп возвращается во входной mint первого.
+/// Берётся первый найденный цикл минимум из двух хопов.
+pub fn detect_arbitrage(trades: &[TradeInfo]) -> Option<ArbitrageInfo> {
+    (0..trades.len()).find_map(|start| cycle_from(trades, start))
+}
+
+fn cycle_from(trades: &[TradeInfo], start: usize) -> Option<ArbitrageInfo> {
+    let first = &trades[start];
+    let start_mint = first.input_token.mint.as_str();
+    if first.output_token.mint == start_mint {
+        return None;
+    }
+
+    let mut legs = vec![start];
+    let mut current = first.output_token.mint.as_str();
+    for (pos, trade) in trades.iter().enumerate().skip(start + 1) {
+        if trade.input_token.mint != current {
+            continue;
+        }
+        legs.push(pos);
+        current = trade.output_token.mint.as_str();
+        if current == start_mint {
+            return Some(build_info(first, trade, legs));
+        }
+    }
+    None
+}
+
+fn build_info(first: &TradeInfo, last: &TradeInfo, legs: Vec<usize>) -> ArbitrageInfo {
+    let raw = |amount: &str| amount.parse::<i128>().unwrap_or(0);
+    let profit_raw = raw(&last.output_token.amount_raw) - raw(&first.input_token.amount_raw);
+    ArbitrageInfo {
+        legs,
+        profit_mint: first.input_token.mint.clone(),
+        profit_amount: last.output_token.amount - first.input_token.amount,
+        profit_amount_raw: profit_raw.to_string(),
+        decimals: first.input_token.decimals,
+    }
+}
@@ -0,0 +1,93 @@
+pub const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+
+/// Лимит CU на инструкцию без `SetComputeUnitLimit`.
+pub const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+pub const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// Запрошенные в транзакции `SetComputeUnitLimit`/`SetComputeUnitPrice`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComputeBudget {
+    pub unit_limit: Option<u32>,
+    /// Цена CU в micro-lamports.
+    pub unit_price: Option<u64>,
+    /// Outer-инструкции, кроме ComputeBudget: от них зависит лимит по умолчанию.
+    pub other_instructions: usize,
+}
+
+impl ComputeBudget {
+    /// По outer-инструкциям `(program_id, data)`; при повторах побеждает последняя.
+    pub fn from_instructions<'a, D: AsRef<[u8]>>(
+        instructions: impl IntoIterator<Item = (&'a str, D)>,
+    ) -> Self {
+        let mut budget = Self::default();
+        for (program_id, data) in instructions {
+            if program_id != COMPUTE_BUDGET_PROGRAM_ID {
+                budget.other_instructions += 1;
+                continue;
+            }
+            let data = data.as_ref();
+            match data.first() {
+                Some(&SET_COMPUTE_UNIT_LIMIT) => {
+                    if let Some(bytes) = data.get(1..5) {
+                        budget.unit_limit = bytes.try_into().ok().map(u32::from_le_bytes);
+                    }
+                }
+                Some(&SET_COMPUTE_UNIT_PRICE) => {
+                    if let Some(bytes) = data.get(1..9) {
+                        budget.unit_price = bytes.try_into().ok().map(u64::from_le_bytes);
+                    }
+                }
+                _ => {}
+            }
+        }
+        budget
+    }
+
+    /// Лимит, по которому считается priority fee: явный или 200k на инструкцию.
+    pub fn effective_unit_limit(&self) -> u64 {
+        let limit = match self.unit_limit {
+            Some(limit) => u64::from(limit),
+            None => DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT * self.other_instructions as u64,
+        };
+        limit.min(MAX_COMPUTE_UNIT_LIMIT)
+    }
+
+    /// `ceil(unit_price * limit / 1_000_000)`; без цены — 0.
+    pub fn priority_fee_lamports(&self) -> u64 {
+        let price = u128::from(self.unit_price.unwrap_or(0));
+        let fee = (price * u128::from(self.effective_unit_limit())).div_ceil(1_000_000);
+        u64::try_from(fee).unwrap_or(u64::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priority_fee_uses_requested_limit() {
+        let limit = [2, 0x40, 0x0d, 0x03, 0x00]; // 200_000
+        let mut price = vec![3];
+        price.extend_from_slice(&1_500u64.to_le_bytes());
+        let budget = ComputeBudget::from_instructions([
+            (COMPUTE_BUDGET_PROGRAM_ID, &limit[..]),
+            (COMPUTE_BUDGET_PROGRAM_ID, &price[..]),
+            ("swap-program", &[][..]),
+        ]);
+        assert_eq!(budget.unit_limit, Some(200_000));
+        assert_eq!(budget.unit_price, Some(1_500));
+        assert_eq!(budget.priority_fee_lamports(), 300);
+
+        // без SetComputeUnitLimit — 200k на каждую инструкцию
+        let budget = ComputeBudget::from_instructions([
+            (COMPUTE_BUDGET_PROGRAM_ID, &price[..]),
+            ("a", &[][..]),
+            ("b", &[][..]),
+        ]);
+        assert_eq!(budget.effective_unit_limit(), 400_000);
+        assert_eq!(budget.priority_fee_lamports(), 600);
+    }
+}
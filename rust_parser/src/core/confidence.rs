@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+use crate::types::{ParseResult, TokenBalance, TokenInfo, TradeConfidence, TradeInfo};
+
+/// Допуск `Approximate`: комиссии протокола и хопы через тот же аккаунт.
+const APPROXIMATE_TOLERANCE: f64 = 0.01;
+
+/// Изменение raw-баланса token account'а из pre/post token balances.
+#[derive(Clone, Debug)]
+struct AccountDelta {
+    mint: String,
+    owner: Option<String>,
+    delta: i128,
+}
+
+/// Изменения балансов token account'ов транзакции; по ним сверяются суммы trades.
+#[derive(Clone, Debug, Default)]
+pub struct TokenDeltas {
+    accounts: HashMap<String, AccountDelta>,
+}
+
+impl TokenDeltas {
+    pub fn from_balances<'a>(
+        pre: impl IntoIterator<Item = &'a TokenBalance>,
+        post: impl IntoIterator<Item = &'a TokenBalance>,
+    ) -> Self {
+        let mut accounts: HashMap<String, AccountDelta> = HashMap::new();
+        let mut apply = |balance: &TokenBalance, sign: i128| {
+            let amount = balance.ui_token_amount.amount.parse::<i128>().unwrap_or(0);
+            let entry = accounts
+                .entry(balance.account.clone())
+                .or_insert_with(|| AccountDelta {
+                    mint: balance.mint.clone(),
+                    owner: None,
+                    delta: 0,
+                });
+            entry.delta += sign * amount;
+            entry.owner = entry.owner.take().or_else(|| balance.owner.clone());
+        };
+        pre.into_iter().for_each(|balance| apply(balance, -1));
+        post.into_iter().for_each(|balance| apply(balance, 1));
+        Self { accounts }
+    }
+
+    fn account(&self, account: Option<&String>, mint: &str) -> Option<i128> {
+        let delta = self.accounts.get(account?)?;
+        (delta.mint == mint).then_some(delta.delta)
+    }
+
+    /// Суммарное изменение всех аккаунтов `owner` в `mint`.
+    fn owner(&self, owner: Option<&str>, mint: &str) -> Option<i128> {
+        let owner = owner?;
+        let mut deltas = self
+            .accounts
+            .values()
+            .filter(|delta| delta.mint == mint && delta.owner.as_deref() == Some(owner))
+            .peekable();
+        deltas.peek()?;
+        Some(deltas.map(|delta| delta.delta).sum())
+    }
+}
+
+/// Проставляет `confidence` всем trades и aggregate trade.
+pub fn annotate_confidence(result: &mut ParseResult, deltas: &TokenDeltas) {
+    for trade in result
+        .trades
+        .iter_mut()
+        .chain(result.aggregate_trade.as_mut())
+    {
+        trade.confidence = trade_confidence(trade, deltas);
+    }
+}
+
+/// Сверка ног trade с балансами: user-сторона (token account или все аккаунты trader'а
+/// в mint'е) и vault пула. Обе ноги точно — `Exact`, в пределах допуска — `Approximate`;
+/// без балансов (native SOL) или при расхождении — `Unverified`.
+pub fn trade_confidence(trade: &TradeInfo, deltas: &TokenDeltas) -> TradeConfidence {
+    let trader = trade
+        .user
+        .as_deref()
+        .or_else(|| trade.signer.as_ref()?.first().map(String::as_str));
+    let input = &trade.input_token;
+    let output = &trade.output_token;
+    let legs = [
+        leg_confidence(
+            input,
+            -1,
+            [
+                deltas.account(input.source.as_ref(), &input.mint),
+                deltas.owner(trader, &input.mint),
+                deltas
+                    .account(input.destination.as_ref(), &input.mint)
+                    .map(|d| -d),
+            ],
+        ),
+        leg_confidence(
+            output,
+            1,
+            [
+                deltas.account(output.destination.as_ref(), &output.mint),
+                deltas.owner(trader, &output.mint),
+                deltas
+                    .account(output.source.as_ref(), &output.mint)
+                    .map(|d| -d),
+            ],
+        ),
+    ];
+    match legs {
+        [TradeConfidence::Exact, TradeConfidence::Exact] => TradeConfidence::Exact,
+        [TradeConfidence::Unverified, _] | [_, TradeConfidence::Unverified] => {
+            TradeConfidence::Unverified
+        }
+        _ => TradeConfidence::Approximate,
+    }
+}
+
+/// `observed` — изменения со стороны user'а (vault — с обратным знаком); берётся лучшее.
+fn leg_confidence(token: &TokenInfo, sign: i128, observed: [Option<i128>; 3]) -> TradeConfidence {
+    let Ok(amount) = token.amount_raw.parse::<i128>() else {
+        return TradeConfidence::Unverified;
+    };
+    let expected = sign * amount;
+    let slack = (amount as f64 * APPROXIMATE_TOLERANCE) as i128;
+    observed
+        .into_iter()
+        .flatten()
+        .map(|delta| match (delta - expected).abs() {
+            0 => TradeConfidence::Exact,
+            diff if diff <= slack => TradeConfidence::Approximate,
+            _ => TradeConfidence::Unverified,
+        })
+        .min()
+        .unwrap_or(TradeConfidence::Unverified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TokenAmount;
+
+    fn balance(account: &str, mint: &str, owner: &str, amount: u64) -> TokenBalance {
+        TokenBalance {
+            account: account.to_string(),
+            mint: mint.to_string(),
+            owner: Some(owner.to_string()),
+            ui_token_amount: TokenAmount::new(amount.to_string(), 6, None),
+        }
+    }
+
+    #[test]
+    fn grades_trade_amounts_against_balance_deltas() {
+        let pre = [
+            balance("user-usdc", "USDC", "user", 5_000_000),
+            balance("vault-meme", "MEME", "pool", 1_000_000),
+        ];
+        let post = [
+            balance("user-usdc", "USDC", "user", 4_000_000),
+            balance("vault-meme", "MEME", "pool", 900_000),
+            balance("user-meme", "MEME", "user", 99_500),
+        ];
+        let deltas = TokenDeltas::from_balances(&pre, &post);
+        let token = |mint: &str, amount: u64| TokenInfo {
+            mint: mint.to_string(),
+            amount_raw: amount.to_string(),
+            ..Default::default()
+        };
+        let mut trade = TradeInfo {
+            input_token: token("USDC", 1_000_000),
+            output_token: TokenInfo {
+                source: Some("vault-meme".to_string()),
+                ..token("MEME", 100_000)
+            },
+            user: Some("user".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(trade_confidence(&trade, &deltas), TradeConfidence::Exact);
+
+        // vault не указан — остаётся зачисление user'у за вычетом комиссии 0.5%
+        trade.output_token.source = None;
+        assert_eq!(
+            trade_confidence(&trade, &deltas),
+            TradeConfidence::Approximate
+        );
+
+        trade.input_token.amount_raw = "3000000".to_string();
+        assert_eq!(
+            trade_confidence(&trade, &deltas),
+            TradeConfidence::Unverified
+        );
+    }
+}
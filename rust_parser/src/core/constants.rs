@@ -1,6 +1,12 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+
 pub mod dex_programs {
     pub const JUPITER: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
     pub const RAYDIUM: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+    pub const RAYDIUM_CL: &str = "CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK";
     pub const PUMP_FUN: &str = "6EF8rrecthR5Dkzon8Nwu78hRvfCKubJ14M5uBEwF6P";
     pub const PUMP_SWAP: &str = "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA";
     pub const ORCA: &str = "whirLbMiicVdio4qvUfM5KAg6Ct8VwpYzGff3uctyCc";
@@ -8,36 +14,164 @@ pub mod dex_programs {
     pub const METEORA_DAMM: &str = "Eo7WjKq67rjJQSZxS6z3YkapzY3eMj6Xy8X5EQVn5UaB";
     pub const METEORA_DAMM_V2: &str = "cpamdpZCGKUy5JxQXB4dcpGPiikHawvSWAd6mEn1sGG";
     pub const METEORA_DBC: &str = "dbcij3LWUppWqq96dh6gJWwBifmcGfLSB5D4DuSMaqN";
+    pub const LIFINITY_V2: &str = "2wT8Yq49kHgDzXuPxZSaeLaH1qbmGXtEyPy64bL7aD3c";
+    pub const PHOENIX: &str = "PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY";
+    pub const MOONSHOT: &str = "MoonCVVNZFSYkqNXP6bxHLPL6QQJiMagDL3qcqUQTrG";
+    pub const RAYDIUM_LAUNCHLAB: &str = "LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj";
+    pub const OPENBOOK_V2: &str = "opnb2LAfJYbRMAHHvqjCwQxanZn7ReEHp1k81EohpZb";
+    pub const SABER: &str = "SSwpkEEcbUqx4vtoEByFjSkhKdCT862DNVb52nZg1UZ";
+    pub const MERCURIAL: &str = "MERLuDFBMmsHnsBPZw2sDQZHvXFMwp8EdjudcU2HKky";
     pub const UNKNOWN: &str = "UNKNOWN";
 }
 
 pub mod dex_program_names {
-    use super::dex_programs;
-    use once_cell::sync::Lazy;
-    use std::collections::HashMap;
-
-    static PROGRAM_NAME: Lazy<HashMap<&'static str, &'static str>> = Lazy::new(|| {
-        let mut map = HashMap::new();
-        map.insert(dex_programs::JUPITER, "Jupiter");
-        map.insert(dex_programs::RAYDIUM, "Raydium");
-        map.insert(dex_programs::PUMP_FUN, "Pumpfun");
-        map.insert(dex_programs::PUMP_SWAP, "Pumpswap");
-        map.insert(dex_programs::ORCA, "Orca");
-        map.insert(dex_programs::METEORA, "MeteoraDLMM");
-        map.insert(dex_programs::METEORA_DAMM, "MeteoraDamm");
-        map.insert(dex_programs::METEORA_DAMM_V2, "MeteoraDammV2");
-        map.insert(dex_programs::METEORA_DBC, "MeteoraDBC");
-        map
-    });
+    use super::ProgramCatalog;
 
     pub fn name(program_id: &str) -> &'static str {
-        PROGRAM_NAME
-            .get(program_id)
-            .copied()
+        ProgramCatalog::get(program_id)
+            .map(|program| program.name)
             .unwrap_or("Unknown DEX")
     }
 }
 
+/// Протокол, к которому относится программа: у одного протокола бывает несколько
+/// программ (Meteora DLMM/DAMM/DBC, Pumpfun и Pumpswap).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProtocolFamily {
+    Jupiter,
+    Raydium,
+    Orca,
+    Pump,
+    Meteora,
+    Lifinity,
+    Phoenix,
+    Moonshot,
+    OpenBook,
+    StableSwap,
+}
+
+/// Программа каталога: id, имя для `amm` и какие парсеры `DexParser` для неё есть.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct ProgramEntry {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub family: ProtocolFamily,
+    pub trades: bool,
+    pub liquidity: bool,
+    pub transfers: bool,
+    pub meme: bool,
+}
+
+impl ProgramEntry {
+    const fn new(id: &'static str, name: &'static str, family: ProtocolFamily) -> Self {
+        Self {
+            id,
+            name,
+            family,
+            trades: false,
+            liquidity: false,
+            transfers: false,
+            meme: false,
+        }
+    }
+
+    const fn trades(mut self) -> Self {
+        self.trades = true;
+        self
+    }
+
+    const fn liquidity(mut self) -> Self {
+        self.liquidity = true;
+        self
+    }
+
+    const fn transfers(mut self) -> Self {
+        self.transfers = true;
+        self
+    }
+
+    const fn meme(mut self) -> Self {
+        self.meme = true;
+        self
+    }
+}
+
+/// Единственный список поддерживаемых программ: из него строятся `dex_program_names`
+/// и реестр парсеров `DexParser::new`; снаружи — фильтры подписок (`accountInclude`).
+pub struct ProgramCatalog;
+
+impl ProgramCatalog {
+    const PROGRAMS: &'static [ProgramEntry] = &{
+        use dex_programs::*;
+        use ProtocolFamily::*;
+        [
+            ProgramEntry::new(JUPITER, "Jupiter", Jupiter).trades().liquidity().transfers(),
+            ProgramEntry::new(RAYDIUM, "RaydiumV4", Raydium).trades().liquidity().transfers(),
+            ProgramEntry::new(RAYDIUM_CL, "RaydiumCL", Raydium).trades().liquidity(),
+            ProgramEntry::new(ORCA, "Orca", Orca).trades().liquidity().transfers(),
+            ProgramEntry::new(PUMP_FUN, "Pumpfun", Pump).trades().meme(),
+            ProgramEntry::new(PUMP_SWAP, "Pumpswap", Pump).trades().liquidity().transfers(),
+            ProgramEntry::new(METEORA, "MeteoraDLMM", Meteora).trades().liquidity(),
+            ProgramEntry::new(METEORA_DAMM, "MeteoraDamm", Meteora).liquidity(),
+            ProgramEntry::new(METEORA_DAMM_V2, "MeteoraDammV2", Meteora).trades().liquidity(),
+            ProgramEntry::new(METEORA_DBC, "MeteoraDBC", Meteora).trades().meme(),
+            ProgramEntry::new(LIFINITY_V2, "LifinityV2", Lifinity).trades(),
+            ProgramEntry::new(PHOENIX, "Phoenix", Phoenix).trades(),
+            ProgramEntry::new(MOONSHOT, "Moonshot", Moonshot).trades().meme(),
+            ProgramEntry::new(RAYDIUM_LAUNCHLAB, "RaydiumLaunchLab", Raydium).trades().meme(),
+            ProgramEntry::new(OPENBOOK_V2, "OpenBookV2", OpenBook).trades(),
+            ProgramEntry::new(SABER, "Saber", StableSwap).trades().liquidity(),
+            ProgramEntry::new(MERCURIAL, "Mercurial", StableSwap).trades().liquidity(),
+        ]
+    };
+
+    pub fn all() -> &'static [ProgramEntry] {
+        Self::PROGRAMS
+    }
+
+    pub fn get(program_id: &str) -> Option<&'static ProgramEntry> {
+        static BY_ID: Lazy<HashMap<&'static str, &'static ProgramEntry>> = Lazy::new(|| {
+            ProgramCatalog::all()
+                .iter()
+                .map(|program| (program.id, program))
+                .collect()
+        });
+        BY_ID.get(program_id).copied()
+    }
+
+    pub fn family(family: ProtocolFamily) -> impl Iterator<Item = &'static ProgramEntry> {
+        Self::all().iter().filter(move |program| program.family == family)
+    }
+
+    /// Program id всех программ каталога, например для `accountInclude` подписки.
+    pub fn ids() -> impl Iterator<Item = &'static str> {
+        Self::all().iter().map(|program| program.id)
+    }
+}
+
+/// Роутеры и торговые боты: outer-инструкция такой программы даёт `TradeInfo::router`
+/// (venue свапа остаётся в `amm`). У Trojan своей программы нет — свапы идут напрямую
+/// через Jupiter; его и прочие метки добавляются через `DexParser::register_router`.
+pub const KNOWN_ROUTERS: &[(&str, &str)] = &[
+    (dex_programs::JUPITER, "Jupiter"),
+    ("6m2CDdhRgxpH4WjvdzxAYbGxwdGUz5MziiL5jek2kBma", "OKX"),
+    ("HV1KXxWFaSeriyFvXyx48FqG9BoFbfinB8njCJonqP7K", "OKXRouter"),
+    ("BSfD6SHZigAfDWSjzD5Q41jw8LmKwtmjskPH9XW1mrRW", "Photon"),
+    ("BANANAjs7FJiPQqJTGFzkZJndT9o7UmKiYYGaJz6frGu", "BananaGun"),
+    ("MaestroAAe9ge5HTc64VbBQZ6fP77pwvrhM8i1XWSAx", "Maestro"),
+    ("minTcHYRLVPubRK8nt6sqe2ZpWrGDLQoNLipDJCGocY", "Mintech"),
+    ("b1oomGGqPKGD6errbyfbVMBuzSC8WtAAYo8MwNafWW1", "Bloom"),
+    ("NoVA1TmDUqksaj2hB1nayFkPysjJbFiU76dT4qPw2wm", "Nova"),
+    ("JSW99DKmxNyREQM14SQLDykeBvEUG63TeohrvmofEiw", "Apepro"),
+];
+
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// SPL Token и Token-2022.
+pub const TOKEN_PROGRAM_IDS: [&str; 2] = [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID];
+
 pub const SYSTEM_PROGRAMS: &[&str] = &[
     "ComputeBudget111111111111111111111111111111",
     "11111111111111111111111111111111",
@@ -69,3 +203,18 @@ pub const TOKENS: Tokens = Tokens {
     USDC: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
     USDT: "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB",
 };
+
+/// Tip-аккаунты Jito block engine. Дополнительные адреса — `ParseConfig::tip_accounts`.
+pub const JITO_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// Quote-токены по убыванию приоритета: в паре SOL/USDC котировка — USDC.
+pub const QUOTE_MINTS: [&str; 3] = [TOKENS.USDC, TOKENS.USDT, TOKENS.SOL];
@@ -1,32 +1,64 @@
 
 // Temporary file - will replace dex_parser.rs
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Instant;
 
-use crate::config::ParseConfig;
-use crate::core::constants::{dex_program_names, dex_programs};
+use crate::config::{FailedTxMode, ParseConfig};
+use crate::core::aggregate::aggregate_trade;
+use crate::core::confidence::{annotate_confidence, TokenDeltas};
+use crate::core::arbitrage::detect_arbitrage;
+use crate::core::compute_budget::ComputeBudget;
+use crate::core::tips::{jito_tip, mark_tip_transfers};
+use crate::core::wsol::normalize_wsol;
+use crate::core::utils::parse_idx;
+use crate::core::constants::{
+    dex_program_names, dex_programs, ProgramCatalog, KNOWN_ROUTERS, TOKENS,
+};
 use crate::core::error::ParserError;
 use crate::core::instruction_classifier::InstructionClassifier;
+use crate::core::invariants::reject_invalid_trades;
+use crate::core::idl::IdlRegistry;
+use crate::core::owner_cache::AccountOwnerCache;
+use crate::state::ProtocolAccountStore;
+use crate::core::rpc_json::block_from_rpc_json;
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::core::transaction_utils::TransactionUtils;
 use crate::core::zc_adapter::ZcAdapter;
+use crate::metadata::{annotate_metadata, TokenMetadataSource};
+use crate::price::{annotate_usd, PriceOracle};
 use crate::core::zc_instruction_classifier::ZcInstructionClassifier;
 use crate::core::zc_transaction_utils::ZcTransactionUtils;
 use crate::core::zero_copy::ZcTransaction;
+use crate::protocols::jupiter::build_jupiter_trade_parser;
+use crate::protocols::lifinity::build_lifinity_trade_parser;
 use crate::protocols::meteora::{
-    build_meteora_damm_v2_liquidity_parser, build_meteora_dbc_meme_parser, build_meteora_dbc_trade_parser,
-    build_meteora_dlmm_liquidity_parser, build_meteora_pools_liquidity_parser, build_meteora_trade_parser,
+    build_meteora_damm_v2_liquidity_parser, build_meteora_damm_v2_trade_parser,
+    build_meteora_dbc_meme_parser, build_meteora_dbc_trade_parser,
+    build_meteora_dlmm_liquidity_parser, build_meteora_dlmm_trade_parser,
+    build_meteora_pools_liquidity_parser,
 };
+use crate::protocols::moonshot::{build_moonshot_meme_parser, build_moonshot_trade_parser};
+use crate::protocols::openbook_v2::build_openbook_v2_trade_parser;
+use crate::protocols::orca::build_orca_liquidity_parser;
+use crate::protocols::phoenix::build_phoenix_trade_parser;
 use crate::protocols::pumpfun::{
     build_pumpfun_meme_parser, build_pumpfun_trade_parser, build_pumpswap_liquidity_parser,
     build_pumpswap_trade_parser, build_pumpswap_transfer_parser,
 };
+use crate::protocols::raydium::{build_raydium_liquidity_parser, build_raydium_trade_parser};
+use crate::protocols::raydium_launchlab::{
+    build_raydium_launchlab_meme_parser, build_raydium_launchlab_trade_parser,
+};
 use crate::protocols::simple::{
     LiquidityParser, MemeEventParser, SimpleLiquidityParser, SimpleMemeParser, SimpleTradeParser,
     SimpleTransferParser, TradeParser, TransferParser,
 };
+use crate::protocols::stable::{build_stable_liquidity_parser, build_stable_trade_parser};
 use crate::types::{
-    BlockInput, BlockParseResult, ClassifiedInstruction, DexInfo, FromJsonValue, ParseResult,
-    PoolEvent, SolanaBlock, SolanaTransaction, TradeInfo, TransferData, TransferMap,
+    BlockContext, BlockInput, BlockParseResult, ClassifiedInstruction, DexInfo, FromJsonValue,
+    ParseDiagnostics, ParseResult, ParseStage, PoolEvent, SkipReason, SolanaBlock, SolanaInstruction,
+    SolanaTransaction, TradeInfo, TransactionStatus, TransferData, TransferMap, UnknownProgramInfo,
 };
 use bs58;
 use serde_json::Value;
@@ -37,6 +69,8 @@ enum ParseType {
     Liquidity,
     Transfer,
     All,
+    /// Только балансы signer'а и переводы: без классификатора и парсеров протоколов.
+    Balances,
 }
 
 impl ParseType {
@@ -53,30 +87,53 @@ impl ParseType {
     }
 }
 
+/// `Instant::now()` паникует на wasm32-unknown-unknown — там тайминги всегда нулевые.
+#[inline]
+fn start_timer(enabled: bool) -> Option<Instant> {
+    (enabled && !cfg!(target_arch = "wasm32")).then(Instant::now)
+}
+
+/// `None` — диагностика выключена, время не замеряем.
+#[inline]
+fn elapsed_us(started: Option<Instant>) -> u64 {
+    started.map_or(0, |started| started.elapsed().as_micros() as u64)
+}
+
+// Адаптер и карта переводов общие для всех парсеров транзакции: клон `Arc`, не данных.
 type TradeParserBuilder = fn(
-    TransactionAdapter,
+    Arc<TransactionAdapter>,
     DexInfo,
-    TransferMap,
+    Arc<TransferMap>,
     Vec<ClassifiedInstruction>,
 ) -> Box<dyn TradeParser>;
 
-type LiquidityParserBuilder =
-    fn(TransactionAdapter, TransferMap, Vec<ClassifiedInstruction>) -> Box<dyn LiquidityParser>;
+type LiquidityParserBuilder = fn(
+    Arc<TransactionAdapter>,
+    Arc<TransferMap>,
+    Vec<ClassifiedInstruction>,
+) -> Box<dyn LiquidityParser>;
 
 type TransferParserBuilder = fn(
-    TransactionAdapter,
+    Arc<TransactionAdapter>,
     DexInfo,
-    TransferMap,
+    Arc<TransferMap>,
     Vec<ClassifiedInstruction>,
 ) -> Box<dyn TransferParser>;
 
-type MemeParserBuilder = fn(TransactionAdapter, TransferMap) -> Box<dyn MemeEventParser>;
+type MemeParserBuilder = fn(Arc<TransactionAdapter>, Arc<TransferMap>) -> Box<dyn MemeEventParser>;
 
 pub struct DexParser {
     trade_parsers: HashMap<String, TradeParserBuilder>,
     liquidity_parsers: HashMap<String, LiquidityParserBuilder>,
     transfer_parsers: HashMap<String, TransferParserBuilder>,
     meme_parsers: HashMap<String, MemeParserBuilder>,
+    price_oracle: Option<Arc<dyn PriceOracle>>,
+    metadata_source: Option<Arc<dyn TokenMetadataSource>>,
+    owner_cache: Option<Arc<AccountOwnerCache>>,
+    protocol_accounts: Option<Arc<ProtocolAccountStore>>,
+    idls: Option<Arc<IdlRegistry>>,
+    /// program id роутера/бота → метка `TradeInfo::router`.
+    routers: HashMap<String, String>,
 }
 
 impl Default for DexParser {
@@ -92,131 +149,330 @@ impl DexParser {
         let mut transfer_parsers: HashMap<String, TransferParserBuilder> = HashMap::new();
         let mut meme_parsers: HashMap<String, MemeParserBuilder> = HashMap::new();
 
-        let default_programs = [
-            dex_programs::JUPITER,
-            dex_programs::RAYDIUM,
-            dex_programs::ORCA,
-        ];
-
-        for program in default_programs {
-            trade_parsers.insert(program.to_string(), SimpleTradeParser::boxed);
-            liquidity_parsers.insert(program.to_string(), SimpleLiquidityParser::boxed);
-            transfer_parsers.insert(program.to_string(), SimpleTransferParser::boxed);
-            meme_parsers.insert(program.to_string(), SimpleMemeParser::boxed);
+        for program in ProgramCatalog::all() {
+            let id = program.id.to_string();
+            if program.trades {
+                trade_parsers.insert(id.clone(), Self::trade_parser_for(program.id));
+            }
+            if program.liquidity {
+                liquidity_parsers.insert(id.clone(), Self::liquidity_parser_for(program.id));
+            }
+            if program.transfers {
+                transfer_parsers.insert(id.clone(), Self::transfer_parser_for(program.id));
+            }
+            if program.meme {
+                meme_parsers.insert(id, Self::meme_parser_for(program.id));
+            }
         }
 
-        // Meteor parsers
-        trade_parsers.insert(
-            dex_programs::METEORA.to_string(),
-            build_meteora_trade_parser,
-        );
-        trade_parsers.insert(
-            dex_programs::METEORA_DBC.to_string(),
-            build_meteora_dbc_trade_parser,
-        );
-        
-        liquidity_parsers.insert(
-            dex_programs::METEORA.to_string(),
-            build_meteora_dlmm_liquidity_parser,
-        );
-        liquidity_parsers.insert(
-            dex_programs::METEORA_DAMM.to_string(),
-            build_meteora_pools_liquidity_parser,
-        );
-        liquidity_parsers.insert(
-            dex_programs::METEORA_DAMM_V2.to_string(),
-            build_meteora_damm_v2_liquidity_parser,
-        );
-        
-        meme_parsers.insert(
-            dex_programs::METEORA_DBC.to_string(),
-            build_meteora_dbc_meme_parser,
-        );
-
-        trade_parsers.insert(
-            dex_programs::PUMP_FUN.to_string(),
-            build_pumpfun_trade_parser,
-        );
-        trade_parsers.insert(
-            dex_programs::PUMP_SWAP.to_string(),
-            build_pumpswap_trade_parser,
-        );
-        liquidity_parsers.insert(
-            dex_programs::PUMP_SWAP.to_string(),
-            build_pumpswap_liquidity_parser,
-        );
-        transfer_parsers.insert(
-            dex_programs::PUMP_SWAP.to_string(),
-            build_pumpswap_transfer_parser,
-        );
-        meme_parsers.insert(
-            dex_programs::PUMP_FUN.to_string(),
-            build_pumpfun_meme_parser,
-        );
-
         Self {
             trade_parsers,
             liquidity_parsers,
             transfer_parsers,
             meme_parsers,
+            price_oracle: None,
+            metadata_source: None,
+            owner_cache: None,
+            protocol_accounts: None,
+            idls: None,
+            routers: KNOWN_ROUTERS
+                .iter()
+                .map(|&(id, label)| (id.to_string(), label.to_string()))
+                .collect(),
+        }
+    }
+
+    // Парсеры программ `ProgramCatalog`; без собственного парсера — `Simple*`.
+
+    fn trade_parser_for(program_id: &str) -> TradeParserBuilder {
+        match program_id {
+            dex_programs::JUPITER => build_jupiter_trade_parser,
+            dex_programs::METEORA => build_meteora_dlmm_trade_parser,
+            dex_programs::METEORA_DBC => build_meteora_dbc_trade_parser,
+            dex_programs::METEORA_DAMM_V2 => build_meteora_damm_v2_trade_parser,
+            dex_programs::PUMP_FUN => build_pumpfun_trade_parser,
+            dex_programs::PUMP_SWAP => build_pumpswap_trade_parser,
+            dex_programs::LIFINITY_V2 => build_lifinity_trade_parser,
+            dex_programs::PHOENIX => build_phoenix_trade_parser,
+            dex_programs::MOONSHOT => build_moonshot_trade_parser,
+            dex_programs::RAYDIUM_LAUNCHLAB => build_raydium_launchlab_trade_parser,
+            dex_programs::OPENBOOK_V2 => build_openbook_v2_trade_parser,
+            dex_programs::RAYDIUM | dex_programs::RAYDIUM_CL => build_raydium_trade_parser,
+            dex_programs::SABER | dex_programs::MERCURIAL => build_stable_trade_parser,
+            _ => SimpleTradeParser::boxed,
+        }
+    }
+
+    fn liquidity_parser_for(program_id: &str) -> LiquidityParserBuilder {
+        match program_id {
+            dex_programs::METEORA => build_meteora_dlmm_liquidity_parser,
+            dex_programs::METEORA_DAMM => build_meteora_pools_liquidity_parser,
+            dex_programs::METEORA_DAMM_V2 => build_meteora_damm_v2_liquidity_parser,
+            dex_programs::PUMP_SWAP => build_pumpswap_liquidity_parser,
+            dex_programs::ORCA => build_orca_liquidity_parser,
+            dex_programs::RAYDIUM | dex_programs::RAYDIUM_CL => build_raydium_liquidity_parser,
+            dex_programs::SABER | dex_programs::MERCURIAL => build_stable_liquidity_parser,
+            _ => SimpleLiquidityParser::boxed,
+        }
+    }
+
+    fn transfer_parser_for(program_id: &str) -> TransferParserBuilder {
+        match program_id {
+            dex_programs::PUMP_SWAP => build_pumpswap_transfer_parser,
+            _ => SimpleTransferParser::boxed,
+        }
+    }
+
+    fn meme_parser_for(program_id: &str) -> MemeParserBuilder {
+        match program_id {
+            dex_programs::METEORA_DBC => build_meteora_dbc_meme_parser,
+            dex_programs::PUMP_FUN => build_pumpfun_meme_parser,
+            dex_programs::MOONSHOT => build_moonshot_meme_parser,
+            dex_programs::RAYDIUM_LAUNCHLAB => build_raydium_launchlab_meme_parser,
+            _ => SimpleMemeParser::boxed,
+        }
+    }
+
+    /// Оракул цен для `value_usd` у trades и pool events.
+    pub fn with_price_oracle(mut self, oracle: Arc<dyn PriceOracle>) -> Self {
+        self.price_oracle = Some(oracle);
+        self
+    }
+
+    /// Источник symbol'ов для `input_symbol`/`output_symbol`; применяется при
+    /// `ParseConfig::resolve_metadata`.
+    pub fn with_metadata_source(mut self, source: Arc<dyn TokenMetadataSource>) -> Self {
+        self.metadata_source = Some(source);
+        self
+    }
+
+    /// Кэш owner'ов токен-аккаунтов, общий для всех `parse_*` вызовов парсера:
+    /// заполняется из token balances разобранных транзакций (или `AccountOwnerCache::hydrate`).
+    pub fn with_owner_cache(mut self, cache: Arc<AccountOwnerCache>) -> Self {
+        self.owner_cache = Some(cache);
+        self
+    }
+
+    pub fn owner_cache(&self) -> Option<&Arc<AccountOwnerCache>> {
+        self.owner_cache.as_ref()
+    }
+
+    /// Перед разбором блока дозагружает в кэш owner'ов токен-аккаунтов, которые
+    /// двигаются в транзакциях, но не попали ни в их balances, ни в кэш
+    /// (`AccountOwnerCache::hydrate`). Без `with_owner_cache` ничего не делает.
+    #[cfg(feature = "rpc")]
+    pub fn hydrate_owners(
+        &self,
+        client: &solana_client::rpc_client::RpcClient,
+        transactions: &[SolanaTransaction],
+    ) -> anyhow::Result<usize> {
+        let Some(cache) = &self.owner_cache else {
+            return Ok(0);
+        };
+        let accounts: HashSet<String> = transactions
+            .iter()
+            .flat_map(|tx| cache.unknown_token_accounts(tx))
+            .collect();
+        cache.hydrate(client, &accounts.into_iter().collect::<Vec<_>>())
+    }
+
+    /// Разобранные аккаунты пулов (`LbPair` DLMM и т.п.): из них парсеры берут
+    /// параметры, которых нет в транзакции. Заполняет вызывающий код.
+    pub fn with_protocol_accounts(mut self, store: Arc<ProtocolAccountStore>) -> Self {
+        self.protocol_accounts = Some(store);
+        self
+    }
+
+    pub fn protocol_accounts(&self) -> Option<&Arc<ProtocolAccountStore>> {
+        self.protocol_accounts.as_ref()
+    }
+
+    /// Адаптер с подключёнными к парсеру кэшами; owner'ы транзакции попадают в кэш.
+    fn build_adapter(
+        &self,
+        tx: impl Into<Arc<SolanaTransaction>>,
+        config: &ParseConfig,
+    ) -> TransactionAdapter {
+        let tx = tx.into();
+        let mut adapter = match &self.owner_cache {
+            Some(cache) => {
+                cache.learn_from(&tx);
+                TransactionAdapter::new(tx, config.clone()).with_owner_cache(cache)
+            }
+            None => TransactionAdapter::new(tx, config.clone()),
+        };
+        if let Some(store) = &self.protocol_accounts {
+            adapter = adapter.with_protocol_accounts(store.clone());
+        }
+        adapter
+    }
+
+    /// IDL-декодеры: инструкции этих программ получают `ClassifiedInstruction::decoded`
+    /// (и в выделенных парсерах, и для программ без парсера).
+    pub fn with_idl_registry(mut self, idls: Arc<IdlRegistry>) -> Self {
+        self.idls = Some(idls);
+        self
+    }
+
+    /// Метка `TradeInfo::router` для программы роутера или бота сверх `KNOWN_ROUTERS`;
+    /// заменяет метку уже известной программы.
+    pub fn register_router(&mut self, program_id: impl Into<String>, label: impl Into<String>) {
+        self.routers.insert(program_id.into(), label.into());
+    }
+
+    /// `TradeInfo::router` по программе outer-инструкции сделки (`instruction_index`).
+    fn annotate_routers<S: AsRef<str>>(
+        &self,
+        result: &mut ParseResult,
+        outer_program: impl Fn(usize) -> Option<S>,
+    ) {
+        for trade in result
+            .trades
+            .iter_mut()
+            .chain(result.aggregate_trade.as_mut())
+        {
+            trade.router = outer_program(usize::from(trade.instruction_index))
+                .and_then(|program_id| self.routers.get(program_id.as_ref()).cloned());
         }
     }
 
     fn try_parse(
         &self,
-        tx: SolanaTransaction,
+        tx: Arc<SolanaTransaction>,
+        config: ParseConfig,
+        parse_type: ParseType,
+    ) -> Result<ParseResult, ParserError> {
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+        let outcome = self.parse_transaction(tx, config, parse_type);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_parse("owned", started.elapsed(), &outcome);
+        outcome
+    }
+
+    #[cfg_attr(
+        feature = "parse-metrics",
+        tracing::instrument(level = "debug", skip_all, fields(signature = %tx.signature))
+    )]
+    fn parse_transaction(
+        &self,
+        tx: Arc<SolanaTransaction>,
         config: ParseConfig,
         parse_type: ParseType,
     ) -> Result<ParseResult, ParserError> {
-        let adapter = TransactionAdapter::new(tx, config.clone());
-        let utils = TransactionUtils::new(adapter);
-        let classifier = InstructionClassifier::new(&utils.adapter);
+        if tx.meta.status == TransactionStatus::Failed && config.include_failed == FailedTxMode::Skip
+        {
+            let mut result = Self::unparsed_result(&tx);
+            result.msg = Some("transaction failed".to_string());
+            return Ok(result);
+        }
+
+        let mut diagnostics = (config.collect_diagnostics || Self::verbose_timing(&config))
+            .then(ParseDiagnostics::default);
+        let started = start_timer(diagnostics.is_some());
+
+        // Нативный SOL в token balances не виден, поэтому с SOL в фильтре не отсекаем
+        let touches_mint_filter = config.mint_filter.as_ref().is_none_or(|mints| {
+            mints.contains(TOKENS.SOL)
+                || tx
+                    .pre_token_balances
+                    .iter()
+                    .chain(&tx.post_token_balances)
+                    .any(|balance| mints.contains(&balance.mint))
+                || tx.transfers.iter().any(|transfer| mints.contains(transfer.info.mint.as_str()))
+        });
+
+        let adapter = self.build_adapter(tx, &config);
+        let utils = TransactionUtils::new(Arc::new(adapter));
+        if parse_type == ParseType::Balances {
+            let mut result = Self::balances_result(&utils, &config, touches_mint_filter);
+            result.diagnostics = Self::finish_diagnostics(&config, diagnostics, started);
+            return Ok(result);
+        }
+        let mut classifier = InstructionClassifier::new(&utils.adapter);
+        if let Some(idls) = &self.idls {
+            classifier.decode_with_idls(idls);
+        }
         let dex_info = utils.get_dex_info(&classifier);
-        let transfer_actions = utils.get_transfer_actions();
-        // ZERO-COPY: используем итератор напрямую, не создаем Vec
+        let transfer_actions = Arc::new(utils.get_transfer_actions());
+        if let Some(diag) = diagnostics.as_mut() {
+            diag.program_ids = classifier.get_all_program_ids_iter().map(str::to_string).collect();
+            diag.timings.setup_us = elapsed_us(started);
+        }
         // all_program_ids больше не нужен, используем classifier.get_all_program_ids_iter() напрямую
 
-        let mut result = ParseResult::new();
-        result.slot = utils.adapter.slot();
-        result.timestamp = utils.adapter.block_time();
-        result.signature = utils.adapter.signature().to_string();
-        result.signer = utils.adapter.signers().to_vec();
-        result.compute_units = utils.adapter.compute_units();
-        result.tx_status = utils.adapter.tx_status();
-        result.fee = utils.adapter.fee();
-
-        if let Some(change) = utils.adapter.signer_sol_balance_change() {
-            result.sol_balance_change = Some(change);
-        }
-        if let Some(token_change) = utils.adapter.signer_token_balance_changes() {
-            result.token_balance_change = token_change.clone();
+        let mut result = Self::base_result(&utils.adapter);
+        if config.include_classified_instructions {
+            result.classified_instructions = Some(classifier.in_execution_order());
+        }
+
+        if result.tx_status == TransactionStatus::Failed
+            && config.include_failed == FailedTxMode::MetadataOnly
+        {
+            result.diagnostics = Self::finish_diagnostics(&config, diagnostics, started);
+            return Ok(result);
         }
 
-        // ZERO-COPY: проверяем фильтр используя итератор
         if let Some(program_filter) = config.program_ids.as_ref() {
             let has_match = classifier.get_all_program_ids_iter()
                 .any(|pid| program_filter.iter().any(|id| id == pid));
             if !has_match {
-                result.state = false;
+                result.filtered = true;
+                result.diagnostics = Self::finish_diagnostics(&config, diagnostics, started);
                 return Ok(result);
             }
         }
+
+        if !touches_mint_filter {
+            result.filtered = true;
+            result.diagnostics = Self::finish_diagnostics(&config, diagnostics, started);
+            return Ok(result);
+        }
+
+        // Программы с битыми instruction data не разбираются; ошибка относится только
+        // к ним, остальные программы транзакции парсятся как обычно
+        let mut program_errors: Vec<ParserError> = Vec::new();
+        let invalid_programs: HashSet<&str> = classifier
+            .get_all_program_ids_iter()
+            .filter(|program_id| {
+                self.has_parser(program_id) && Self::skip_reason(&config, program_id).is_none()
+            })
+            .filter(|program_id| match classifier.decode_error(program_id) {
+                Some(err) => {
+                    program_errors.push(err);
+                    true
+                }
+                None => false,
+            })
+            .collect();
+        let mint_filtered =
+            Self::mint_filtered_programs(&config, &utils.adapter, &classifier, &transfer_actions);
         
+        let stage_started = start_timer(diagnostics.is_some());
         if parse_type.includes_trades() {
-            // ZERO-COPY: используем итератор по ссылкам
             for program_id in classifier.get_all_program_ids_iter() {
-                if let Some(filter) = config.program_ids.as_ref() {
-                    if !filter.iter().any(|id| id == program_id) {
-                        continue;
+                if let Some(reason) = Self::skip_reason(&config, program_id) {
+                    if let Some(diag) = diagnostics.as_mut() {
+                        let instructions = classifier.get_instructions(program_id).len();
+                        diag.record_skip(program_id, ParseStage::Trades, reason, instructions);
+                    }
+                    continue;
+                }
+                if invalid_programs.contains(program_id) {
+                    if let Some(diag) = diagnostics.as_mut() {
+                        let instructions = classifier.get_instructions(program_id).len();
+                        diag.record_skip(program_id, ParseStage::Trades, SkipReason::InvalidInstructionData, instructions);
                     }
+                    continue;
                 }
-                if let Some(ignore) = config.ignore_program_ids.as_ref() {
-                    if ignore.iter().any(|id| id == program_id) {
-                        continue;
+                if mint_filtered.contains(program_id) {
+                    if let Some(diag) = diagnostics.as_mut() {
+                        let instructions = classifier.get_instructions(program_id).len();
+                        diag.record_skip(program_id, ParseStage::Trades, SkipReason::MintFilter, instructions);
                     }
+                    continue;
                 }
                 
-                // ZERO-COPY: используем &str для lookup в HashMap
                 if let Some(builder) = self.trade_parsers.get(program_id) {
                     let amm_name = dex_info.amm.as_deref()
                         .or_else(|| Some(dex_program_names::name(program_id)))
@@ -227,119 +483,196 @@ impl DexParser {
                         route: None,
                     };
                     
-                    let adapter_clone = utils.adapter.clone();
-                    let transfer_clone = transfer_actions.clone();
-                    // ZERO-COPY: получаем ссылку, клонируем только для парсера (необходимо для ownership)
                     let classified_instructions = classifier.get_instructions(program_id).to_vec();
                     
+                    let instructions = classified_instructions.len();
+                    let parser_started = start_timer(diagnostics.is_some());
                     let mut parser = builder(
-                        adapter_clone,
+                        Arc::clone(&utils.adapter),
                         program_info,
-                        transfer_clone,
+                        Arc::clone(&transfer_actions),
                         classified_instructions,
                     );
                     
                     let trades = parser.process_trades();
+                    if let Some(diag) = diagnostics.as_mut() {
+                        diag.record_run(program_id, ParseStage::Trades, false, instructions, trades.len(), elapsed_us(parser_started));
+                    }
                     result.trades.extend(trades);
                 } else if config.try_unknown_dex {
+                    let parser_started = start_timer(diagnostics.is_some());
+                    let mut matched = false;
                     if let Some(transfers) = transfer_actions.get(program_id) {
                         let has_supported = transfers
                             .iter()
                             .any(|transfer| utils.adapter.is_supported_token(&transfer.info.mint));
                         
                         if transfers.len() >= 2 && has_supported {
+                            matched = true;
                             let program_info = DexInfo {
                                 program_id: Some(program_id.to_string()),
                                 amm: dex_info.amm.clone().or_else(|| Some(dex_program_names::name(program_id).to_string())),
                                 route: None,
                             };
                             
+                            let before = result.trades.len();
                             if let Some(trade) = utils.process_swap_data(transfers, &program_info) {
                                 let trade = utils.attach_token_transfer_info(trade, &transfer_actions);
                                 result.trades.push(trade);
                             }
+                            if let Some(diag) = diagnostics.as_mut() {
+                                let instructions = classifier.get_instructions(program_id).len();
+                                let outputs = result.trades.len() - before;
+                                diag.record_run(program_id, ParseStage::Trades, true, instructions, outputs, elapsed_us(parser_started));
+                            }
                         }
                     }
+                    if !matched {
+                        if let Some(diag) = diagnostics.as_mut() {
+                            let instructions = classifier.get_instructions(program_id).len();
+                            diag.record_skip(program_id, ParseStage::Trades, SkipReason::UnknownDexNoMatch, instructions);
+                        }
+                    }
+                    if config.collect_unknown_programs {
+                        Self::record_unknown_program(
+                            &mut result.unknown_programs,
+                            program_id,
+                            classifier.get_instructions(program_id),
+                            matched,
+                        );
+                    }
+                } else if let Some(diag) = diagnostics.as_mut() {
+                    let instructions = classifier.get_instructions(program_id).len();
+                    diag.record_skip(program_id, ParseStage::Trades, SkipReason::NoParser, instructions);
                 }
             }
         }
+        if let Some(diag) = diagnostics.as_mut() {
+            diag.timings.trades_us = elapsed_us(stage_started);
+        }
 
+        let stage_started = start_timer(diagnostics.is_some());
         if parse_type.includes_liquidity() {
-            // ZERO-COPY: используем итератор по ссылкам
             for program_id in classifier.get_all_program_ids_iter() {
-                if let Some(filter) = config.program_ids.as_ref() {
-                    if !filter.iter().any(|id| id == program_id) {
-                        continue;
+                if let Some(reason) = Self::skip_reason(&config, program_id) {
+                    if let Some(diag) = diagnostics.as_mut() {
+                        let instructions = classifier.get_instructions(program_id).len();
+                        diag.record_skip(program_id, ParseStage::Liquidity, reason, instructions);
                     }
+                    continue;
+                }
+                if invalid_programs.contains(program_id) {
+                    if let Some(diag) = diagnostics.as_mut() {
+                        let instructions = classifier.get_instructions(program_id).len();
+                        diag.record_skip(program_id, ParseStage::Liquidity, SkipReason::InvalidInstructionData, instructions);
+                    }
+                    continue;
                 }
-                if let Some(ignore) = config.ignore_program_ids.as_ref() {
-                    if ignore.iter().any(|id| id == program_id) {
-                        continue;
+                if mint_filtered.contains(program_id) {
+                    if let Some(diag) = diagnostics.as_mut() {
+                        let instructions = classifier.get_instructions(program_id).len();
+                        diag.record_skip(program_id, ParseStage::Liquidity, SkipReason::MintFilter, instructions);
                     }
+                    continue;
                 }
                 
-                // ZERO-COPY: используем &str для lookup в HashMap
                 if let Some(builder) = self.liquidity_parsers.get(program_id) {
-                    let adapter_clone = utils.adapter.clone();
-                    let transfer_clone = transfer_actions.clone();
-                    // ZERO-COPY: получаем ссылку, клонируем только для парсера (необходимо для ownership)
                     let classified_instructions = classifier.get_instructions(program_id).to_vec();
                     
+                    let instructions = classified_instructions.len();
+                    let parser_started = start_timer(diagnostics.is_some());
                     let mut parser = builder(
-                        adapter_clone,
-                        transfer_clone,
+                        Arc::clone(&utils.adapter),
+                        Arc::clone(&transfer_actions),
                         classified_instructions,
                     );
                     
                     let liquidities = parser.process_liquidity();
+                    if let Some(diag) = diagnostics.as_mut() {
+                        diag.record_run(program_id, ParseStage::Liquidity, false, instructions, liquidities.len(), elapsed_us(parser_started));
+                    }
                     result.liquidities.extend(liquidities);
                 }
             }
         }
+        if let Some(diag) = diagnostics.as_mut() {
+            diag.timings.liquidity_us = elapsed_us(stage_started);
+        }
 
+        let stage_started = start_timer(diagnostics.is_some());
         if parse_type == ParseType::All {
-            // ZERO-COPY: используем итератор по ссылкам
             for program_id in classifier.get_all_program_ids_iter() {
-                if let Some(filter) = config.program_ids.as_ref() {
-                    if !filter.iter().any(|id| id == program_id) {
-                        continue;
+                if let Some(reason) = Self::skip_reason(&config, program_id) {
+                    if let Some(diag) = diagnostics.as_mut() {
+                        let instructions = classifier.get_instructions(program_id).len();
+                        diag.record_skip(program_id, ParseStage::Meme, reason, instructions);
+                    }
+                    continue;
+                }
+                if invalid_programs.contains(program_id) {
+                    if let Some(diag) = diagnostics.as_mut() {
+                        let instructions = classifier.get_instructions(program_id).len();
+                        diag.record_skip(program_id, ParseStage::Meme, SkipReason::InvalidInstructionData, instructions);
                     }
+                    continue;
                 }
-                if let Some(ignore) = config.ignore_program_ids.as_ref() {
-                    if ignore.iter().any(|id| id == program_id) {
-                        continue;
+                if mint_filtered.contains(program_id) {
+                    if let Some(diag) = diagnostics.as_mut() {
+                        let instructions = classifier.get_instructions(program_id).len();
+                        diag.record_skip(program_id, ParseStage::Meme, SkipReason::MintFilter, instructions);
                     }
+                    continue;
                 }
                 
-                // ZERO-COPY: используем &str для lookup в HashMap
                 if let Some(builder) = self.meme_parsers.get(program_id) {
-                    let mut parser = builder(utils.adapter.clone(), transfer_actions.clone());
+                    let parser_started = start_timer(diagnostics.is_some());
+                    let mut parser =
+                        builder(Arc::clone(&utils.adapter), Arc::clone(&transfer_actions));
                     let events = parser.process_events();
+                    if let Some(diag) = diagnostics.as_mut() {
+                        let instructions = classifier.get_instructions(program_id).len();
+                        diag.record_run(program_id, ParseStage::Meme, false, instructions, events.len(), elapsed_us(parser_started));
+                    }
                     result.meme_events.extend(events);
                 }
             }
         }
+        if let Some(diag) = diagnostics.as_mut() {
+            diag.timings.meme_us = elapsed_us(stage_started);
+        }
 
+        let stage_started = start_timer(diagnostics.is_some());
         if result.trades.is_empty()
             && result.liquidities.is_empty()
             && parse_type.includes_transfer()
         {
             if let Some(program_id) = dex_info.program_id.clone() {
-                if let Some(builder) = self.transfer_parsers.get(&program_id) {
-                    // ZERO-COPY: получаем ссылку, клонируем только для парсера (необходимо для ownership)
+                let builder = self
+                    .transfer_parsers
+                    .get(&program_id)
+                    .filter(|_| {
+                        !invalid_programs.contains(program_id.as_str())
+                            && !mint_filtered.contains(program_id.as_str())
+                    });
+                if let Some(builder) = builder {
                     let classified_instructions = classifier.get_instructions(&program_id).to_vec();
+                    let instructions = classified_instructions.len();
+                    let parser_started = start_timer(diagnostics.is_some());
                     let program_info = DexInfo {
                         program_id: dex_info.program_id.clone(),
                         amm: dex_info.amm.clone(),
                         route: None,
                     };
                     let mut parser = builder(
-                        utils.adapter.clone(),
+                        Arc::clone(&utils.adapter),
                         program_info,
-                        transfer_actions.clone(),
+                        Arc::clone(&transfer_actions),
                         classified_instructions,
                     );
                     let transfers = parser.process_transfers();
+                    if let Some(diag) = diagnostics.as_mut() {
+                        diag.record_run(&program_id, ParseStage::Transfer, false, instructions, transfers.len(), elapsed_us(parser_started));
+                    }
                     result.transfers.extend(transfers);
                 }
             }
@@ -349,6 +682,15 @@ impl DexParser {
                 result.transfers.extend(fallback_transfers);
             }
         }
+        result.jito_tip = jito_tip(transfer_actions.values().flatten(), &config);
+        mark_tip_transfers(&mut result.transfers, &config);
+        if let Some(diag) = diagnostics.as_mut() {
+            diag.timings.transfers_us = elapsed_us(stage_started);
+        }
+        if config.normalize_wsol {
+            Self::normalize_wsol_owned(&utils.adapter, &mut result);
+        }
+        Self::retain_filtered_mints(&config, &mut result);
         
         if !result.trades.is_empty() {
             let before_dedup = result.trades.len();
@@ -365,24 +707,319 @@ impl DexParser {
             result.trades = deduped_trades;
             result.trades.sort_unstable_by(|a, b| a.idx.cmp(&b.idx));
             
-            if utils.adapter.config().aggregate_trades {
-                if let Some(last_trade) = result.trades.last().cloned() {
-                    let trade_with_fee = utils.attach_trade_fee(last_trade);
-                    result.aggregate_trade = Some(trade_with_fee);
+            for trade in &mut result.trades {
+                trade.attribute_authority(&result.signer);
+                trade.side = trade.side_for(&config);
+                if config.compute_prices {
+                    trade.price_quote_per_base = trade.price_for(&config);
                 }
             }
+            if config.strict {
+                reject_invalid_trades(&mut result, |mint| utils.adapter.token_decimals(mint));
+            }
+            if config.detect_arbitrage {
+                result.arbitrage = detect_arbitrage(&result.trades);
+            }
+
+            if utils.adapter.config().aggregate_trades {
+                result.aggregate_trade = aggregate_trade(&result, config.aggregate_strategy);
+            }
+            if let Some(trade) = result.aggregate_trade.as_mut() {
+                trade.attribute_authority(&result.signer);
+                trade.side = trade.side_for(&config);
+            }
+            let deltas = TokenDeltas::from_balances(
+                utils.adapter.pre_token_balances(),
+                utils.adapter.post_token_balances(),
+            );
+            annotate_confidence(&mut result, &deltas);
+        }
+
+        Self::link_instruction_indices(&mut result);
+        self.annotate_routers(&mut result, |index| {
+            utils
+                .adapter
+                .instructions()
+                .get(index)
+                .map(|ix| ix.program_id.as_str())
+        });
+        if let Some(oracle) = &self.price_oracle {
+            annotate_usd(&mut result, oracle.as_ref());
+        }
+        if let (true, Some(source)) = (config.resolve_metadata, &self.metadata_source) {
+            annotate_metadata(&mut result, source.as_ref());
+        }
+        if config.lite_trades {
+            result.trades_lite = result.trades.drain(..).map(|trade| (&trade).into()).collect();
+        }
+
+        program_errors.extend(utils.adapter.take_errors());
+        if !program_errors.is_empty() {
+            if config.throw_error {
+                return Err(program_errors.swap_remove(0));
+            }
+            let messages: Vec<String> = program_errors.iter().map(ToString::to_string).collect();
+            result.msg = Some(messages.join("; "));
         }
 
+        result.diagnostics = Self::finish_diagnostics(&config, diagnostics, started);
         Ok(result)
     }
 
+    fn has_parser(&self, program_id: &str) -> bool {
+        self.trade_parsers.contains_key(program_id)
+            || self.liquidity_parsers.contains_key(program_id)
+            || self.meme_parsers.contains_key(program_id)
+            || self.transfer_parsers.contains_key(program_id)
+    }
+
+    /// Группирует инструкции программы без парсера по (дискриминатор, число аккаунтов).
+    fn record_unknown_program(
+        unknown: &mut Vec<UnknownProgramInfo>,
+        program_id: &str,
+        instructions: &[ClassifiedInstruction],
+        matched_swap: bool,
+    ) {
+        for classified in instructions {
+            let data = crate::protocols::pumpfun::util::get_instruction_data(&classified.data)
+                .unwrap_or_default();
+            let discriminator = hex::encode(&data[..data.len().min(8)]);
+            let account_count = classified.data.accounts.len();
+            match unknown.iter_mut().find(|info| {
+                info.program_id == program_id
+                    && info.discriminator == discriminator
+                    && info.account_count == account_count
+            }) {
+                Some(info) => info.count += 1,
+                None => unknown.push(UnknownProgramInfo {
+                    program_id: program_id.to_string(),
+                    discriminator,
+                    account_count,
+                    count: 1,
+                    matched_swap,
+                }),
+            }
+        }
+    }
+
+    fn skip_reason(config: &ParseConfig, program_id: &str) -> Option<SkipReason> {
+        if let Some(filter) = config.program_ids.as_ref() {
+            if !filter.iter().any(|id| id == program_id) {
+                return Some(SkipReason::NotInProgramFilter);
+            }
+        }
+        if let Some(ignore) = config.ignore_program_ids.as_ref() {
+            if ignore.iter().any(|id| id == program_id) {
+                return Some(SkipReason::Ignored);
+            }
+        }
+        None
+    }
+
+    /// `verbose_timing` действует только в сборке с feature `parse-metrics`.
+    #[inline]
+    fn verbose_timing(config: &ParseConfig) -> bool {
+        cfg!(feature = "parse-metrics") && config.verbose_timing
+    }
+
+    fn finish_diagnostics(
+        config: &ParseConfig,
+        diagnostics: Option<ParseDiagnostics>,
+        started: Option<Instant>,
+    ) -> Option<ParseDiagnostics> {
+        let mut diag = diagnostics?;
+        diag.timings.total_us = elapsed_us(started);
+        #[cfg(feature = "parse-metrics")]
+        if config.verbose_timing {
+            tracing::debug!(timings = ?diag.timings, parsers_run = diag.parsers_run.len(), "try_parse timings");
+        }
+        config.collect_diagnostics.then_some(diag)
+    }
+
+    /// Заполняет `instruction_index`/`inner_index` из `idx` у trades, pool events и transfers.
+    fn link_instruction_indices(result: &mut ParseResult) {
+        for trade in result
+            .trades
+            .iter_mut()
+            .chain(result.aggregate_trade.as_mut())
+        {
+            (trade.instruction_index, trade.inner_index) = parse_idx(&trade.idx);
+        }
+        for event in &mut result.liquidities {
+            (event.instruction_index, event.inner_index) = parse_idx(&event.idx);
+        }
+        for transfer in &mut result.transfers {
+            (transfer.instruction_index, transfer.inner_index) = parse_idx(&transfer.idx);
+        }
+    }
+
+    /// `ParseConfig::mint_filter` до запуска парсеров: программы, ни одна outer-инструкция
+    /// которых (вместе с её CPI) не упоминает mint из фильтра ни в аккаунтах, ни в
+    /// переводах. Агрегатор и вложенные в него DEX делят outer-инструкцию и отсекаются
+    /// вместе. С SOL в фильтре не отсекается ничего — нативный SOL в аккаунтах не виден.
+    fn mint_filtered_programs<'a>(
+        config: &ParseConfig,
+        adapter: &TransactionAdapter,
+        classifier: &'a InstructionClassifier,
+        transfer_actions: &TransferMap,
+    ) -> HashSet<&'a str> {
+        let Some(mints) = config
+            .mint_filter
+            .as_ref()
+            .filter(|mints| !mints.contains(TOKENS.SOL))
+        else {
+            return HashSet::new();
+        };
+        let mentions_mint =
+            |ix: &SolanaInstruction| ix.accounts.iter().any(|account| mints.contains(account.as_str()));
+        let mut relevant: HashSet<usize> = transfer_actions
+            .values()
+            .flatten()
+            .filter(|transfer| mints.contains(transfer.info.mint.as_str()))
+            .map(|transfer| parse_idx(&transfer.idx).0 as usize)
+            .collect();
+        relevant.extend(
+            adapter
+                .instructions()
+                .iter()
+                .enumerate()
+                .filter(|(_, ix)| mentions_mint(ix))
+                .map(|(index, _)| index),
+        );
+        relevant.extend(
+            adapter
+                .inner_instructions()
+                .iter()
+                .filter(|inner| inner.instructions.iter().any(mentions_mint))
+                .map(|inner| inner.index),
+        );
+        classifier
+            .get_all_program_ids_iter()
+            .filter(|program_id| {
+                !classifier
+                    .get_instructions(program_id)
+                    .iter()
+                    .any(|ix| relevant.contains(&ix.outer_index))
+            })
+            .collect()
+    }
+
+    /// `ParseConfig::mint_filter`: выкидывает записи без единого mint'а из фильтра.
+    fn retain_filtered_mints(config: &ParseConfig, result: &mut ParseResult) {
+        if config.mint_filter.is_none() {
+            return;
+        }
+        result.trades.retain(|trade| {
+            config.allows_mint(&trade.input_token.mint) || config.allows_mint(&trade.output_token.mint)
+        });
+        result
+            .liquidities
+            .retain(|event| event.tokens.iter().any(|leg| config.allows_mint(&leg.mint)));
+        result.meme_events.retain(|event| {
+            config.allows_mint(&event.base_mint) || config.allows_mint(&event.quote_mint)
+        });
+        result
+            .transfers
+            .retain(|transfer| config.allows_mint(&transfer.info.mint));
+    }
+
+    /// Поля результата, которым не нужен классификатор: идентификаторы, статус,
+    /// комиссии и изменения балансов signer'а.
+    fn base_result(adapter: &TransactionAdapter) -> ParseResult {
+        let mut result = ParseResult::new();
+        result.slot = adapter.slot();
+        result.timestamp = adapter.block_time();
+        result.signature = adapter.signature().to_string();
+        result.signer = adapter.signers().to_vec();
+        result.fee_payer = result.signer.first().cloned().unwrap_or_default();
+        result.compute_units = adapter.compute_units();
+        let budget = ComputeBudget::from_instructions(
+            adapter
+                .instructions()
+                .iter()
+                .filter_map(|ix| Some((ix.program_id.as_str(), crate::core::utils::get_instruction_data(ix).ok()?))),
+        );
+        result.cu_price_micro_lamports = budget.unit_price.unwrap_or(0);
+        result.priority_fee_lamports = budget.priority_fee_lamports();
+        result.tx_status = adapter.tx_status();
+        result.tx_error = adapter.tx_error().map(str::to_string);
+        result.fee = adapter.fee();
+        result.sol_balance_change = adapter.signer_sol_balance_change();
+        if let Some(token_change) = adapter.signer_token_balance_changes() {
+            result.token_balance_change = token_change.clone();
+        }
+        result
+    }
+
+    /// `ParseType::Balances`: `base_result` плюс все переводы из инструкций по `idx`.
+    /// `ParseConfig::program_ids` не проверяется — без классификатора список программ не строится.
+    fn balances_result(
+        utils: &TransactionUtils,
+        config: &ParseConfig,
+        touches_mint_filter: bool,
+    ) -> ParseResult {
+        let mut result = Self::base_result(&utils.adapter);
+        if result.tx_status == TransactionStatus::Failed
+            && config.include_failed == FailedTxMode::MetadataOnly
+        {
+            return result;
+        }
+        if !touches_mint_filter {
+            result.filtered = true;
+            return result;
+        }
+        let transfer_actions = utils.get_transfer_actions();
+        result.jito_tip = jito_tip(transfer_actions.values().flatten(), config);
+        result.transfers = transfer_actions.into_values().flatten().collect();
+        result.transfers.sort_by(|a, b| a.idx.cmp(&b.idx));
+        mark_tip_transfers(&mut result.transfers, config);
+        if config.normalize_wsol {
+            Self::normalize_wsol_owned(&utils.adapter, &mut result);
+        }
+        Self::retain_filtered_mints(config, &mut result);
+        result
+    }
+
+    fn normalize_wsol_owned(adapter: &TransactionAdapter, result: &mut ParseResult) {
+        normalize_wsol(result, |account| {
+            adapter
+                .token_account_info(account)
+                .filter(|info| info.mint == TOKENS.SOL)
+                .and_then(|_| adapter.get_token_account_owner_string(account))
+        });
+    }
+
+    /// `state = false` и только идентификаторы транзакции, без запуска парсеров.
+    fn unparsed_result(tx: &SolanaTransaction) -> ParseResult {
+        let mut result = ParseResult::new();
+        result.state = false;
+        result.slot = tx.slot;
+        result.timestamp = tx.block_time;
+        result.signature = tx.signature.clone();
+        result.signer = tx.signers.clone();
+        result.fee_payer = result.signer.first().cloned().unwrap_or_default();
+        result.tx_status = tx.meta.status;
+        result.tx_error = tx.meta.err.clone();
+        result
+    }
+
     fn parse_with_classifier(
         &self,
-        tx: SolanaTransaction,
+        tx: Arc<SolanaTransaction>,
         config: Option<ParseConfig>,
         parse_type: ParseType,
     ) -> ParseResult {
         let config = config.unwrap_or_default();
+        if let Err(err) = validate_transaction(&tx) {
+            #[cfg(feature = "metrics")]
+            crate::metrics::record_error("owned", &err);
+            if config.throw_error {
+                tracing::error!("parser error: {err}");
+            }
+            let mut result = Self::unparsed_result(&tx);
+            result.msg = Some(err.to_string());
+            return result;
+        }
         let config_clone = config.clone();
         match self.try_parse(tx, config_clone, parse_type) {
             Ok(result) => result,
@@ -403,7 +1040,7 @@ impl DexParser {
         tx: SolanaTransaction,
         config: Option<ParseConfig>,
     ) -> Vec<TradeInfo> {
-        self.parse_with_classifier(tx, config, ParseType::Trades)
+        self.parse_with_classifier(tx.into(), config, ParseType::Trades)
             .trades
     }
 
@@ -412,7 +1049,7 @@ impl DexParser {
         tx: SolanaTransaction,
         config: Option<ParseConfig>,
     ) -> Vec<PoolEvent> {
-        self.parse_with_classifier(tx, config, ParseType::Liquidity)
+        self.parse_with_classifier(tx.into(), config, ParseType::Liquidity)
             .liquidities
     }
 
@@ -421,13 +1058,50 @@ impl DexParser {
         tx: SolanaTransaction,
         config: Option<ParseConfig>,
     ) -> Vec<TransferData> {
-        self.parse_with_classifier(tx, config, ParseType::Transfer)
+        self.parse_with_classifier(tx.into(), config, ParseType::Transfer)
             .transfers
     }
 
     pub fn parse_all(&self, tx: SolanaTransaction, config: Option<ParseConfig>) -> ParseResult {
+        self.parse_with_classifier(tx.into(), config, ParseType::All)
+    }
+
+    /// Быстрый режим для мониторинга кошельков: изменения SOL/token балансов signer'а,
+    /// комиссии и список переводов. Классификатор и парсеры протоколов не запускаются,
+    /// поэтому `trades`, `liquidities` и `meme_events` всегда пустые.
+    pub fn parse_balances(
+        &self,
+        tx: SolanaTransaction,
+        config: Option<ParseConfig>,
+    ) -> ParseResult {
+        self.parse_with_classifier(tx.into(), config, ParseType::Balances)
+    }
+
+    /// `parse_all` для транзакции, общей с вызывающим кодом: адаптер и парсеры
+    /// программ работают с тем же `Arc`, без копии транзакции на каждый разбор.
+    pub fn parse_all_shared(
+        &self,
+        tx: Arc<SolanaTransaction>,
+        config: Option<ParseConfig>,
+    ) -> ParseResult {
         self.parse_with_classifier(tx, config, ParseType::All)
     }
+
+    /// Как `parse_all`, но возвращает `ParserError` вместо `state = false` + `msg`:
+    /// и для невалидной транзакции, и для ошибок протокольных парсеров (битые data
+    /// инструкций разбираемой программы, отсутствующие token balances).
+    pub fn try_parse_all(
+        &self,
+        tx: SolanaTransaction,
+        config: Option<ParseConfig>,
+    ) -> Result<ParseResult, ParserError> {
+        validate_transaction(&tx)?;
+        let config = ParseConfig {
+            throw_error: true,
+            ..config.unwrap_or_default()
+        };
+        self.try_parse(tx.into(), config, ParseType::All)
+    }
     
     /// Parse transaction using zero-copy structures (ZcTransaction, ZcAdapter)
     /// 
@@ -451,15 +1125,49 @@ impl DexParser {
         zc_tx: &'a ZcTransaction<'a>,
         meta: Option<&'a Value>,
         config: Option<ParseConfig>,
+    ) -> Result<ParseResult, ParserError> {
+        #[cfg(feature = "metrics")]
+        let started = Instant::now();
+        let outcome = self.parse_zero_copy(zc_tx, meta, config);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_parse("zero_copy", started.elapsed(), &outcome);
+        outcome
+    }
+
+    #[cfg_attr(
+        feature = "parse-metrics",
+        tracing::instrument(level = "debug", skip_all, fields(signature = %zc_tx.signature))
+    )]
+    fn parse_zero_copy<'a>(
+        &self,
+        zc_tx: &'a ZcTransaction<'a>,
+        meta: Option<&'a Value>,
+        config: Option<ParseConfig>,
     ) -> Result<ParseResult, ParserError> {
         let config = config.unwrap_or_default();
         
+        if meta.is_some_and(|m| !m.is_object()) {
+            return Err(ParserError::MetaMalformed("meta is not a JSON object".to_string()));
+        }
+
         // Create zero-copy adapter
         let zc_adapter = ZcAdapter::new(zc_tx, meta, config.clone());
+        let failed = zc_adapter.tx_status() == TransactionStatus::Failed;
+        if failed && config.include_failed == FailedTxMode::Skip {
+            let mut result = ParseResult::new();
+            result.state = false;
+            result.slot = zc_adapter.slot();
+            result.timestamp = zc_adapter.block_time();
+            result.signature = zc_adapter.signature().to_string();
+            result.tx_status = TransactionStatus::Failed;
+            result.tx_error = zc_adapter.tx_error();
+            result.msg = Some("transaction failed".to_string());
+            return Ok(result);
+        }
         let zc_utils = ZcTransactionUtils::new(&zc_adapter);
         let zc_classifier = ZcInstructionClassifier::new(&zc_adapter);
         let dex_info = zc_utils.get_dex_info(&zc_classifier);
-        let transfer_actions = zc_utils.get_transfer_actions();
+        let transfer_actions = Arc::new(zc_utils.get_transfer_actions());
         
         let mut result = ParseResult::new();
         result.slot = zc_adapter.slot();
@@ -468,16 +1176,52 @@ impl DexParser {
         result.signer = zc_adapter.signers_iter()
             .map(|pk| bs58::encode(pk).into_string())
             .collect();
+        result.fee_payer = result.signer.first().cloned().unwrap_or_default();
         result.compute_units = zc_adapter.compute_units();
+        let program_ids: Vec<String> = zc_adapter
+            .instructions()
+            .iter()
+            .map(|ix| {
+                zc_adapter
+                    .program_id(ix)
+                    .map(|pk| bs58::encode(pk).into_string())
+                    .unwrap_or_default()
+            })
+            .collect();
+        let budget = ComputeBudget::from_instructions(
+            program_ids
+                .iter()
+                .zip(zc_adapter.instructions())
+                .map(|(program_id, ix)| (program_id.as_str(), ix.data)),
+        );
+        result.cu_price_micro_lamports = budget.unit_price.unwrap_or(0);
+        result.priority_fee_lamports = budget.priority_fee_lamports();
+        if config.include_classified_instructions {
+            // классификатор zc-пути не хранит инструкции целиком
+            let tx = crate::core::zero_copy::convert_zc_to_solana_tx(zc_tx, meta)?;
+            let adapter = TransactionAdapter::new(tx, config.clone());
+            let mut classifier = InstructionClassifier::new(&adapter);
+            if let Some(idls) = &self.idls {
+                classifier.decode_with_idls(idls);
+            }
+            result.classified_instructions = Some(classifier.in_execution_order());
+        }
         result.tx_status = zc_adapter.tx_status();
+        result.tx_error = zc_adapter.tx_error();
         result.fee = crate::types::TokenAmount {
             amount: zc_adapter.fee().to_string(),
             decimals: 9,
             ui_amount: Some(zc_adapter.fee() as f64 / 1_000_000_000.0),
+            ui_amount_string: None,
         };
+        if failed && config.include_failed == FailedTxMode::MetadataOnly {
+            return Ok(result);
+        }
         
-        // TODO: Extract sol_balance_change and token_balance_change from meta JSON
-        // For now, skip these as they require parsing from meta JSON
+        result.sol_balance_change = zc_adapter.signer_sol_balance_change();
+        if let Some(token_change) = zc_adapter.signer_token_balance_changes() {
+            result.token_balance_change = token_change;
+        }
         
         // Check program filter (zero-copy: compare 32-byte arrays)
         if let Some(program_filter) = config.program_ids.as_ref() {
@@ -488,7 +1232,7 @@ impl DexParser {
                     program_filter.iter().any(|id| id == &pid_str)
                 });
             if !has_match {
-                result.state = false;
+                result.filtered = true;
                 return Ok(result);
             }
         }
@@ -512,7 +1256,7 @@ impl DexParser {
             }
             
             // Check if this is pumpswap and use zero-copy parser
-            if program_id_str == dex_programs::PUMP_SWAP {
+            if program_id_str == dex_programs::PUMP_SWAP && config.prefer_zero_copy {
                 // Use zero-copy parser for pumpswap
                 use crate::core::zc_adapter_helpers::ZcCachedBalanceMaps;
                 use crate::protocols::pumpfun::pumpswap_parser_zc::process_pumpswap_trades_zc;
@@ -541,11 +1285,13 @@ impl DexParser {
             } else {
                 // For other protocols, convert to SolanaTransaction for compatibility
                 // TODO: Create zero-copy versions for all parsers
-                let tx = crate::core::zero_copy::convert_zc_to_solana_tx(zc_tx, meta)
-                    .map_err(|e| ParserError::generic(format!("failed to convert zc_tx: {}", e)))?;
-                let adapter = TransactionAdapter::new(tx, config.clone());
-                let utils = TransactionUtils::new(adapter);
-                let classifier = InstructionClassifier::new(&utils.adapter);
+                let tx = crate::core::zero_copy::convert_zc_to_solana_tx(zc_tx, meta)?;
+                let adapter = self.build_adapter(tx, &config);
+                let utils = TransactionUtils::new(Arc::new(adapter));
+                let mut classifier = InstructionClassifier::new(&utils.adapter);
+                if let Some(idls) = &self.idls {
+                    classifier.decode_with_idls(idls);
+                }
                 
                 if let Some(builder) = self.trade_parsers.get(&program_id_str) {
                     let amm_name = dex_info.amm.as_deref()
@@ -562,7 +1308,7 @@ impl DexParser {
                     let mut parser = builder(
                         utils.adapter,
                         program_info,
-                        transfer_actions.clone(),
+                        Arc::clone(&transfer_actions),
                         classified_instructions,
                     );
                     
@@ -572,7 +1318,27 @@ impl DexParser {
             }
         }
         
+        if result.trades.is_empty() && result.liquidities.is_empty() {
+            result.transfers.extend(transfer_actions.values().flatten().cloned());
+        }
+        result.jito_tip = jito_tip(transfer_actions.values().flatten(), &config);
+        mark_tip_transfers(&mut result.transfers, &config);
+        if config.normalize_wsol {
+            use crate::core::zc_adapter_helpers::ZcCachedBalanceMaps;
+
+            let maps = ZcCachedBalanceMaps::from_adapter(&zc_adapter);
+            normalize_wsol(&mut result, |account| {
+                maps.post_balance_map
+                    .get(account)
+                    .or_else(|| maps.pre_balance_map.get(account))
+                    .filter(|balance| balance.mint == TOKENS.SOL)
+                    .and_then(|balance| balance.owner.clone())
+            });
+        }
+
         // Deduplicate trades
+        Self::retain_filtered_mints(&config, &mut result);
+
         if !result.trades.is_empty() {
             let before_dedup = result.trades.len();
             let mut seen: HashSet<(String, String)> = HashSet::with_capacity(before_dedup);
@@ -588,34 +1354,159 @@ impl DexParser {
             result.trades = deduped_trades;
             result.trades.sort_unstable_by(|a, b| a.idx.cmp(&b.idx));
             
-            if config.aggregate_trades {
-                if let Some(last_trade) = result.trades.last().cloned() {
-                    // TODO: Implement attach_trade_fee for zero-copy
-                    result.aggregate_trade = Some(last_trade);
+            for trade in &mut result.trades {
+                trade.attribute_authority(&result.signer);
+                trade.side = trade.side_for(&config);
+                if config.compute_prices {
+                    trade.price_quote_per_base = trade.price_for(&config);
                 }
             }
+            if config.strict {
+                use crate::core::zc_adapter_helpers::ZcCachedBalanceMaps;
+
+                let maps = ZcCachedBalanceMaps::from_adapter(&zc_adapter);
+                reject_invalid_trades(&mut result, |mint| maps.decimals_map.get(mint).copied());
+            }
+            if config.detect_arbitrage {
+                result.arbitrage = detect_arbitrage(&result.trades);
+            }
+
+            if config.aggregate_trades {
+                result.aggregate_trade = aggregate_trade(&result, config.aggregate_strategy);
+            }
+            if let Some(trade) = result.aggregate_trade.as_mut() {
+                trade.attribute_authority(&result.signer);
+                trade.side = trade.side_for(&config);
+            }
+            {
+                use crate::core::zc_adapter_helpers::ZcCachedBalanceMaps;
+
+                let maps = ZcCachedBalanceMaps::from_adapter(&zc_adapter);
+                let deltas = TokenDeltas::from_balances(
+                    maps.pre_balance_map.values(),
+                    maps.post_balance_map.values(),
+                );
+                annotate_confidence(&mut result, &deltas);
+            }
         }
-        
+
+        Self::link_instruction_indices(&mut result);
+        self.annotate_routers(&mut result, |index| {
+            let instruction = zc_adapter.instructions().get(index)?;
+            zc_adapter
+                .program_id(instruction)
+                .map(|key| bs58::encode(key).into_string())
+        });
+        if let Some(oracle) = &self.price_oracle {
+            annotate_usd(&mut result, oracle.as_ref());
+        }
+        if let (true, Some(source)) = (config.resolve_metadata, &self.metadata_source) {
+            annotate_metadata(&mut result, source.as_ref());
+        }
+        if config.lite_trades {
+            result.trades_lite = result.trades.drain(..).map(|trade| (&trade).into()).collect();
+        }
+
         Ok(result)
     }
 
+    /// Потоковый разбор блока: транзакции парсятся по одной при продвижении
+    /// итератора, весь `BlockParseResult` в памяти не собирается. `order_key`
+    /// берёт `tx_index` из позиции во входе; повторы подписей не схлопываются.
+    pub fn parse_block_iter<'a, I>(
+        &'a self,
+        txs: I,
+        config: Option<ParseConfig>,
+    ) -> impl Iterator<Item = ParseResult> + 'a
+    where
+        I: IntoIterator<Item = SolanaTransaction>,
+        I::IntoIter: 'a,
+    {
+        let cfg = config.unwrap_or_default();
+        txs.into_iter().enumerate().map(move |(tx_index, tx)| {
+            let mut result = self.parse_all(tx, Some(cfg.clone()));
+            result.assign_order_keys(result.slot, tx_index as u32);
+            result
+        })
+    }
+
+    /// Потоковый `parse_block_raw`: JSON транзакции декодируется лениво,
+    /// ошибка декодирования — `Err` этого элемента, остальные продолжают разбираться.
+    pub fn try_parse_block_iter<'a>(
+        &'a self,
+        transactions: &'a [Value],
+        config: Option<ParseConfig>,
+    ) -> impl Iterator<Item = Result<ParseResult, ParserError>> + 'a {
+        let cfg = config.unwrap_or_default();
+        transactions.iter().enumerate().map(move |(tx_index, tx_value)| {
+            let tx = SolanaTransaction::from_value(tx_value, &cfg)
+                .map_err(|err| ParserError::Deserialize(err.to_string()))?;
+            let mut result = self.parse_all(tx, Some(cfg.clone()));
+            result.assign_order_keys(result.slot, tx_index as u32);
+            Ok(result)
+        })
+    }
+
     pub fn parse_block_raw(
         &self,
         transactions: &[Value],
         config: Option<ParseConfig>,
     ) -> Result<BlockParseResult, ParserError> {
-        let cfg = config.unwrap_or_default();
-        let mut results = Vec::with_capacity(transactions.len());
-        for tx_value in transactions {
-            let tx = SolanaTransaction::from_value(tx_value, &cfg)
-                .map_err(|err| ParserError::generic(err.to_string()))?;
-            results.push(self.parse_all(tx, Some(cfg.clone())));
+        self.parse_block_raw_with_context(transactions, None, config)
+    }
+
+    /// `parse_block_raw` с контекстом блока: slot/blockTime проставляются
+    /// транзакциям, в JSON которых их нет.
+    pub fn parse_block_raw_with_context(
+        &self,
+        transactions: &[Value],
+        context: Option<&BlockContext>,
+        config: Option<ParseConfig>,
+    ) -> Result<BlockParseResult, ParserError> {
+        let mut results = self
+            .try_parse_block_iter(transactions, config)
+            .collect::<Result<Vec<_>, _>>()?;
+        let Some(context) = context else {
+            return Ok(BlockParseResult::new(0, None, results));
+        };
+        for result in &mut results {
+            result.apply_block_context(context);
         }
-        Ok(BlockParseResult {
-            slot: 0,
-            timestamp: None,
-            transactions: results,
-        })
+        let mut block = BlockParseResult::new(context.slot, context.block_time, results);
+        block.blockhash.clone_from(&context.blockhash);
+        Ok(block)
+    }
+
+    /// Ответ `getBlock` целиком: контекст берётся из полей блока.
+    pub fn parse_block_json(
+        &self,
+        block: &Value,
+        config: Option<ParseConfig>,
+    ) -> Result<BlockParseResult, ParserError> {
+        let transactions = block
+            .get("transactions")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| {
+                ParserError::Deserialize("block has no transactions array".to_string())
+            })?;
+        let context = BlockContext::from_block_json(block);
+        self.parse_block_raw_with_context(transactions, Some(&context), config)
+    }
+
+    /// Ответ `getBlock` без предварительной нормализации: account keys, loadedAddresses
+    /// и meta разбираются внутри (`rpc_json::block_from_rpc_json`). `slot` — номер
+    /// запрошенного блока, в самом ответе его нет.
+    pub fn parse_block_rpc_json(
+        &self,
+        block: &Value,
+        slot: Option<u64>,
+        config: Option<ParseConfig>,
+    ) -> Result<BlockParseResult, ParserError> {
+        let (context, transactions) = block_from_rpc_json(block, slot)?;
+        let results = self.parse_block_iter(transactions, config).collect();
+        let mut result = BlockParseResult::new(context.slot, context.block_time, results);
+        result.blockhash = context.blockhash;
+        Ok(result)
     }
     
     pub fn parse_block_raw_bytes(
@@ -625,21 +1516,17 @@ impl DexParser {
     ) -> Result<BlockParseResult, ParserError> {
         let cfg = config.unwrap_or_default();
         let transactions: Vec<Value> = serde_json::from_slice(transactions_json)
-            .map_err(|err| ParserError::generic(format!("failed to parse transactions array: {err}")))?;
+            .map_err(|err| ParserError::Deserialize(format!("failed to parse transactions array: {err}")))?;
         
         let mut results = Vec::with_capacity(transactions.len());
         for tx_value in &transactions {
             let bytes = serde_json::to_vec(tx_value)
-                .map_err(|err| ParserError::generic(format!("failed to serialize transaction: {err}")))?;
+                .map_err(|err| ParserError::Deserialize(format!("failed to serialize transaction: {err}")))?;
             let tx = SolanaTransaction::from_slice(&bytes, &cfg)
-                .map_err(|err| ParserError::generic(err.to_string()))?;
+                .map_err(|err| ParserError::Deserialize(err.to_string()))?;
             results.push(self.parse_all(tx, Some(cfg.clone())));
         }
-        Ok(BlockParseResult {
-            slot: 0,
-            timestamp: None,
-            transactions: results,
-        })
+        Ok(BlockParseResult::new(0, None, results))
     }
 
     pub fn parse_block_parsed(
@@ -652,11 +1539,7 @@ impl DexParser {
         for tx in &block.transactions {
             results.push(self.parse_all(tx.clone(), Some(cfg.clone())));
         }
-        BlockParseResult {
-            slot: block.slot,
-            timestamp: block.block_time,
-            transactions: results,
-        }
+        BlockParseResult::new(block.slot, block.block_time, results)
     }
 
     pub fn parse_block(
@@ -665,121 +1548,38 @@ impl DexParser {
         config: Option<ParseConfig>,
     ) -> Result<BlockParseResult, ParserError> {
         match input {
-            BlockInput::Raw { transactions } => self.parse_block_raw(transactions, config),
+            BlockInput::Raw {
+                transactions,
+                context,
+            } => self.parse_block_raw_with_context(transactions, context.as_ref(), config),
             BlockInput::Parsed { block } => Ok(self.parse_block_parsed(block, config)),
+            BlockInput::RpcJson { block, slot } => self.parse_block_rpc_json(block, *slot, config),
+        }
+    }
+}
+
+/// Проверки meta до запуска протокольных парсеров. Битые instruction data здесь не
+/// проверяются: они отсекают только свою программу (`InstructionClassifier::decode_error`).
+fn validate_transaction(tx: &SolanaTransaction) -> Result<(), ParserError> {
+    for balance in tx.pre_token_balances.iter().chain(&tx.post_token_balances) {
+        let amount = &balance.ui_token_amount.amount;
+        if !amount.is_empty() && amount.parse::<u128>().is_err() {
+            return Err(ParserError::MetaMalformed(format!(
+                "token balance of {} has non-numeric amount {amount:?}",
+                balance.account
+            )));
         }
     }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
     use super::*;
-    use crate::config::ParseConfig;
-    use crate::core::constants::dex_programs;
-    use crate::types::{
-        BalanceChange, SolanaInstruction, TokenAmount, TransactionMeta, TransactionStatus,
-        TransferData,
-    };
-
-    fn sample_transaction() -> SolanaTransaction {
-        let mut sol_changes = HashMap::new();
-        sol_changes.insert(
-            "user".to_string(),
-            BalanceChange {
-                pre: 10_000_000,
-                post: 9_995_000,
-                change: -5_000,
-            },
-        );
-
-        let mut token_changes = HashMap::new();
-        let mut signer_tokens = HashMap::new();
-        signer_tokens.insert(
-            "BASE".to_string(),
-            BalanceChange {
-                pre: 0,
-                post: -1_000_000,
-                change: -1_000_000,
-            },
-        );
-        signer_tokens.insert(
-            "QUOTE".to_string(),
-            BalanceChange {
-                pre: 0,
-                post: 2_000_000,
-                change: 2_000_000,
-            },
-        );
-        token_changes.insert("user".to_string(), signer_tokens);
-
-        SolanaTransaction {
-            slot: 1,
-            signature: "sample-signature".to_string(),
-            block_time: 1_234_567,
-            signers: vec!["user".to_string()],
-            instructions: vec![SolanaInstruction {
-                program_id: dex_programs::JUPITER.to_string(),
-                accounts: vec!["BASE".to_string(), "QUOTE".to_string()],
-                data: "swap".to_string(),
-            }],
-            inner_instructions: Vec::new(),
-            transfers: vec![
-                TransferData {
-                    transfer_type: "transfer".to_string(),
-                    program_id: dex_programs::JUPITER.to_string(),
-                    info: crate::types::TransferInfo {
-                        authority: Some("user".to_string()),
-                        destination: "pool".to_string(),
-                        destination_owner: Some("pool-owner".to_string()),
-                        mint: "BASE".to_string(),
-                        source: "user-token".to_string(),
-                        token_amount: TokenAmount::new("1000000", 6, Some(1.0)),
-                        source_balance: None,
-                        source_pre_balance: None,
-                        destination_balance: None,
-                        destination_pre_balance: None,
-                        sol_balance_change: None,
-                    },
-                    idx: "0-0".to_string(),
-                    timestamp: 1_234_567,
-                    signature: "sample-signature".to_string(),
-                    is_fee: false,
-                },
-                TransferData {
-                    transfer_type: "transfer".to_string(),
-                    program_id: dex_programs::JUPITER.to_string(),
-                    info: crate::types::TransferInfo {
-                        authority: Some("pool".to_string()),
-                        destination: "user".to_string(),
-                        destination_owner: Some("user".to_string()),
-                        mint: "QUOTE".to_string(),
-                        source: "pool-token".to_string(),
-                        token_amount: TokenAmount::new("2000000", 6, Some(2.0)),
-                        source_balance: None,
-                        source_pre_balance: None,
-                        destination_balance: None,
-                        destination_pre_balance: None,
-                        sol_balance_change: None,
-                    },
-                    idx: "0-1".to_string(),
-                    timestamp: 1_234_567,
-                    signature: "sample-signature".to_string(),
-                    is_fee: false,
-                },
-            ],
-            pre_token_balances: Vec::new(),
-            post_token_balances: Vec::new(),
-            meta: TransactionMeta {
-                fee: 5_000,
-                compute_units: 200_000,
-                status: TransactionStatus::Success,
-                sol_balance_changes: sol_changes,
-                token_balance_changes: token_changes,
-            },
-        }
-    }
+    use crate::config::{AggregateStrategy, BaseTokenConvention, ParseConfig};
+    use crate::core::constants::{dex_programs, TOKENS};
+    use crate::core::test_utils::sample_transaction;
+    use crate::types::{SolanaInstruction, TokenAmount, TransactionStatus};
 
     #[test]
     fn parses_trade_and_aggregates() {
@@ -797,12 +1597,66 @@ mod tests {
         assert!(result.sol_balance_change.is_some());
     }
 
+    #[test]
+    fn output_amount_excludes_token2022_transfer_fee() {
+        let mut tx = sample_transaction();
+        tx.transfers[1].transfer_type = "transferCheckedWithFee".to_string();
+        tx.transfers[1].fee_amount = Some(TokenAmount::new("20000", 6, Some(0.02)));
+        assert_eq!(tx.transfers[1].net_amount_raw(), 1_980_000);
+
+        let result = DexParser::new().parse_all(tx, None);
+        let trade = &result.trades[0];
+        assert_eq!(trade.output_token.amount_raw, "1980000");
+        assert!((trade.output_token.amount - 1.98).abs() < 1e-9);
+    }
+
+    #[test]
+    fn attaches_classified_instructions_on_request() {
+        let parser = DexParser::new();
+        assert!(parser.parse_all(sample_transaction(), None).classified_instructions.is_none());
+
+        let config = ParseConfig {
+            include_classified_instructions: true,
+            ..Default::default()
+        };
+        let result = parser.parse_all(sample_transaction(), Some(config));
+        let instructions = result.classified_instructions.expect("classified instructions");
+        assert_eq!(instructions.len(), 1);
+        assert_eq!(instructions[0].program_id, dex_programs::JUPITER);
+        assert_eq!(instructions[0].stack_height, Some(1));
+        assert_eq!(instructions[0].data_bytes, [0xb3, 0x06, 0xa9]);
+    }
+
+    #[test]
+    fn links_results_to_instruction_indices() {
+        assert_eq!(parse_idx("3"), (3, None));
+        assert_eq!(parse_idx("3-12"), (3, Some(12)));
+        assert_eq!(
+            parse_idx("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin-2-1"),
+            (2, Some(1))
+        );
+        assert_eq!(parse_idx(""), (0, None));
+
+        let mut tx = sample_transaction();
+        tx.instructions.insert(0, tx.instructions[0].clone());
+        for transfer in &mut tx.transfers {
+            transfer.idx = format!("1-{}", &transfer.idx[2..]);
+        }
+        let result = DexParser::new().parse_all(tx, None);
+        let trade = &result.trades[0];
+        assert_eq!(
+            (trade.instruction_index, trade.inner_index),
+            parse_idx(&trade.idx)
+        );
+        assert_eq!(trade.instruction_index, 1);
+    }
+
     #[test]
     fn falls_back_to_transfers_when_no_trade() {
         let mut tx = sample_transaction();
-        tx.instructions[0].program_id = "UNKNOWN_PROGRAM".to_string();
+        tx.instructions[0].program_id = "UNKNOWN_PROGRAM".into();
         tx.transfers.iter_mut().for_each(|transfer| {
-            transfer.program_id = "UNKNOWN_PROGRAM".to_string();
+            transfer.program_id = "UNKNOWN_PROGRAM".into();
         });
 
         let parser = DexParser::new();
@@ -811,11 +1665,523 @@ mod tests {
             program_ids: None,
             ignore_program_ids: None,
             aggregate_trades: false,
+            aggregate_strategy: AggregateStrategy::LastHop,
             throw_error: false,
+            include_failed: FailedTxMode::AttemptFull,
+            collect_diagnostics: false,
+            verbose_timing: false,
+            compute_prices: false,
+            detect_arbitrage: false,
+            mint_filter: None,
+            resolve_metadata: false,
+            include_classified_instructions: false,
+            tip_accounts: None,
+            prefer_zero_copy: true,
+            quote_mints: Vec::new(),
+            base_token: BaseTokenConvention::QuotePriority,
+            normalize_wsol: false,
+            collect_unknown_programs: false,
+            strict: false,
+            lite_trades: false,
+            ts_compat_output: false,
         };
         let transfers = parser.parse_transfers(tx.clone(), Some(config.clone()));
         assert_eq!(transfers.len(), 2);
         assert!(parser.parse_trades(tx, Some(config)).is_empty());
     }
-}
 
+    #[test]
+    fn registry_is_generated_from_program_catalog() {
+        let parser = DexParser::new();
+        for program in ProgramCatalog::all() {
+            assert_eq!(parser.trade_parsers.contains_key(program.id), program.trades);
+            assert_eq!(parser.liquidity_parsers.contains_key(program.id), program.liquidity);
+            assert_eq!(parser.transfer_parsers.contains_key(program.id), program.transfers);
+            assert_eq!(parser.meme_parsers.contains_key(program.id), program.meme);
+            assert_eq!(dex_program_names::name(program.id), program.name);
+        }
+        let trade_programs = ProgramCatalog::all().iter().filter(|p| p.trades).count();
+        assert_eq!(parser.trade_parsers.len(), trade_programs);
+    }
+
+    #[test]
+    fn parse_balances_skips_protocol_parsers() {
+        let parser = DexParser::new();
+        let full = parser.parse_all(sample_transaction(), None);
+        let balances = parser.parse_balances(sample_transaction(), None);
+
+        assert!(!full.trades.is_empty());
+        assert!(balances.trades.is_empty() && balances.aggregate_trade.is_none());
+        assert_eq!(balances.transfers.len(), 2);
+        assert_eq!(balances.fee, full.fee);
+        assert_eq!(balances.sol_balance_change, full.sol_balance_change);
+        assert_eq!(balances.token_balance_change, full.token_balance_change);
+    }
+
+    #[test]
+    fn failed_transactions_follow_include_failed_mode() {
+        let mut tx = sample_transaction();
+        tx.meta.status = TransactionStatus::Failed;
+        tx.meta.err = Some(r#"{"InstructionError":[0,{"Custom":6001}]}"#.to_string());
+        let parser = DexParser::new();
+        let config_for = |mode| ParseConfig {
+            include_failed: mode,
+            ..Default::default()
+        };
+
+        let skipped = parser.parse_all(tx.clone(), Some(config_for(FailedTxMode::Skip)));
+        assert!(!skipped.state);
+        assert!(skipped.trades.is_empty());
+        assert_eq!(skipped.tx_error, tx.meta.err);
+
+        let metadata = parser.parse_all(tx.clone(), Some(config_for(FailedTxMode::MetadataOnly)));
+        assert!(metadata.state);
+        assert!(metadata.trades.is_empty() && metadata.transfers.is_empty());
+        assert_eq!(metadata.fee.amount, "5000");
+        assert_eq!(metadata.tx_error, tx.meta.err);
+
+        let full = parser.parse_all(tx, Some(config_for(FailedTxMode::AttemptFull)));
+        assert_eq!(full.trades.len(), 1);
+        assert_eq!(full.tx_status, TransactionStatus::Failed);
+    }
+
+    #[test]
+    fn reports_instruction_decode_error() {
+        let mut tx = sample_transaction();
+        tx.instructions[0].data = "not base64!".to_string();
+        let parser = DexParser::new();
+
+        match parser.try_parse_all(tx.clone(), None) {
+            Err(ParserError::InstructionDecode { program_id, idx, .. }) => {
+                assert_eq!(program_id, dex_programs::JUPITER);
+                assert_eq!(idx, "0-0");
+            }
+            other => panic!("expected InstructionDecode, got {other:?}"),
+        }
+
+        // parse_all: программа с битыми data пропускается, ошибка — в msg
+        let result = parser.parse_all(tx, None);
+        assert_eq!(result.signature, "sample-signature");
+        assert!(result.trades.is_empty());
+        assert!(result.msg.as_deref().is_some_and(|msg| msg.contains(dex_programs::JUPITER)));
+    }
+
+    #[test]
+    fn invalid_data_of_unparsed_program_does_not_reject_transaction() {
+        let mut tx = sample_transaction();
+        tx.inner_instructions = vec![crate::types::InnerInstruction {
+            index: 0,
+            instructions: vec![SolanaInstruction {
+                program_id: "SomeUnrelatedProgram111111111111111111111111".into(),
+                accounts: Vec::new(),
+                data: "not base64!".to_string(),
+            }],
+            stack_heights: vec![Some(2)],
+        }];
+
+        let result = DexParser::new()
+            .try_parse_all(tx, None)
+            .expect("unrelated program does not fail the transaction");
+        assert_eq!(result.trades.len(), 1);
+        assert!(result.msg.is_none());
+    }
+
+    #[test]
+    fn collects_diagnostics_when_enabled() {
+        let parser = DexParser::new();
+        let tx = sample_transaction();
+        assert!(parser.parse_all(tx.clone(), None).diagnostics.is_none());
+
+        let config = ParseConfig {
+            collect_diagnostics: true,
+            ignore_program_ids: Some(vec!["UNKNOWN_PROGRAM".to_string()]),
+            ..Default::default()
+        };
+        let mut tx_with_ignored = tx;
+        let mut ignored = tx_with_ignored.instructions[0].clone();
+        ignored.program_id = "UNKNOWN_PROGRAM".into();
+        tx_with_ignored.instructions.push(ignored);
+
+        let result = parser.parse_all(tx_with_ignored, Some(config));
+        let diag = result.diagnostics.expect("diagnostics");
+        assert!(diag.program_ids.iter().any(|id| id == dex_programs::JUPITER));
+        let run = diag
+            .parsers_run
+            .iter()
+            .find(|run| run.stage == ParseStage::Trades)
+            .expect("trade parser run");
+        assert_eq!(run.program_id, dex_programs::JUPITER);
+        assert_eq!(run.outputs, result.trades.len());
+        assert!(diag.skipped.iter().any(|skip| {
+            skip.program_id == "UNKNOWN_PROGRAM" && skip.reason == SkipReason::Ignored
+        }));
+    }
+
+    #[test]
+    fn computes_prices_against_known_quote_mint() {
+        let mut tx = sample_transaction();
+        tx.transfers[1].info.mint = TOKENS.USDC.into();
+        let parser = DexParser::new();
+        let config = ParseConfig {
+            compute_prices: true,
+            ..Default::default()
+        };
+
+        let result = parser.parse_all(tx.clone(), Some(config));
+        let trade = &result.trades[0];
+        assert_eq!(trade.quote_mint(), TOKENS.USDC);
+        assert_eq!(trade.base_mint(), "BASE");
+        let expected = trade.output_token.amount / trade.input_token.amount;
+        assert_eq!(trade.price_quote_per_base, Some(expected));
+        assert_eq!(
+            result.aggregate_trade.and_then(|t| t.price_quote_per_base),
+            Some(expected)
+        );
+
+        let without = parser.parse_all(tx, None);
+        assert!(without.trades[0].price_quote_per_base.is_none());
+    }
+
+    #[test]
+    fn trade_side_follows_quote_priority_and_base_convention() {
+        use crate::types::TradeSide;
+
+        let parser = DexParser::new();
+        let mut tx = sample_transaction();
+        tx.transfers[1].info.mint = TOKENS.USDC.into();
+        let result = parser.parse_all(tx.clone(), None);
+        assert_eq!(result.trades[0].side, Some(TradeSide::Sell));
+        assert_eq!(result.aggregate_trade.and_then(|t| t.side), Some(TradeSide::Sell));
+
+        // SOL -> USDC: по приоритету продали SOL, при SolQuote купили USDC
+        tx.transfers[0].info.mint = TOKENS.SOL.into();
+        let result = parser.parse_all(tx.clone(), None);
+        assert_eq!(result.trades[0].side, Some(TradeSide::Sell));
+        let config = ParseConfig {
+            base_token: BaseTokenConvention::SolQuote,
+            ..Default::default()
+        };
+        let result = parser.parse_all(tx, Some(config));
+        assert_eq!(result.trades[0].side, Some(TradeSide::Buy));
+    }
+
+    #[test]
+    fn trade_user_follows_signing_authority_not_fee_payer() {
+        let parser = DexParser::new();
+        let mut tx = sample_transaction();
+        tx.signers = vec!["payer".to_string(), "bot".to_string()];
+        tx.transfers[0].info.authority = Some("bot".into());
+
+        let result = parser.parse_all(tx.clone(), None);
+        assert_eq!(result.fee_payer, "payer");
+        assert_eq!(result.trades[0].authority.as_deref(), Some("bot"));
+        assert_eq!(result.trades[0].user.as_deref(), Some("bot"));
+
+        // authority-PDA не подписант — user остаётся от протокольного парсера
+        tx.transfers[0].info.authority = Some("router-pda".into());
+        let result = parser.parse_all(tx, None);
+        assert_eq!(result.trades[0].authority.as_deref(), Some("router-pda"));
+        assert_ne!(result.trades[0].user.as_deref(), Some("router-pda"));
+    }
+
+    #[test]
+    fn quote_mints_from_config_override_builtin_priority() {
+        let parser = DexParser::new();
+        let config = ParseConfig {
+            compute_prices: true,
+            quote_mints: vec!["BASE".to_string()],
+            ..Default::default()
+        };
+
+        let result = parser.parse_all(sample_transaction(), Some(config.clone()));
+        let trade = &result.trades[0];
+        assert_eq!(trade.quote_mint_for(&config), "BASE");
+        let expected = trade.input_token.amount / trade.output_token.amount;
+        assert_eq!(trade.price_quote_per_base, Some(expected));
+
+        let adapter = TransactionAdapter::new(sample_transaction(), config);
+        assert!(adapter.is_supported_token("BASE"));
+        assert!(!adapter.is_supported_token("QUOTE"));
+    }
+
+    #[test]
+    fn detects_arbitrage_cycle_back_to_input_mint() {
+        let mut tx = sample_transaction();
+        // второй хоп: QUOTE -> BASE обратно в тот же outer instruction
+        let mut back = tx.transfers.clone();
+        back[0].info.mint = "QUOTE".into();
+        back[0].info.token_amount = TokenAmount::new("2000000", 6, Some(2.0));
+        back[1].info.mint = "BASE".into();
+        back[1].info.token_amount = TokenAmount::new("1100000", 6, Some(1.1));
+        for transfer in &mut back {
+            transfer.program_id = dex_programs::RAYDIUM.into();
+            transfer.idx = transfer.idx.replacen("0-", "1-", 1);
+        }
+        tx.transfers.extend(back);
+        let mut second = tx.instructions[0].clone();
+        second.program_id = dex_programs::RAYDIUM.into();
+        tx.instructions.push(second);
+
+        let parser = DexParser::new();
+        let config = ParseConfig {
+            detect_arbitrage: true,
+            ..Default::default()
+        };
+        let result = parser.parse_all(tx.clone(), Some(config));
+        let arbitrage = result.arbitrage.expect("arbitrage");
+        assert_eq!(arbitrage.legs, [0, 1]);
+        assert_eq!(arbitrage.profit_mint, "BASE");
+        assert_eq!(arbitrage.profit_amount_raw, "100000");
+
+        assert!(parser.parse_all(tx, None).arbitrage.is_none());
+    }
+
+    #[test]
+    fn owner_cache_carries_owners_across_transactions() {
+        use crate::types::TokenBalance;
+
+        let cache = Arc::new(AccountOwnerCache::default());
+        let parser = DexParser::new().with_owner_cache(cache.clone());
+        let mut first = sample_transaction();
+        first.post_token_balances.push(TokenBalance {
+            account: "pool-token".to_string(),
+            mint: "QUOTE".to_string(),
+            owner: Some("pool-authority".to_string()),
+            ui_token_amount: TokenAmount::new("0", 6, Some(0.0)),
+        });
+        parser.parse_all(first, None);
+        assert_eq!(cache.get("pool-token").as_deref(), Some("pool-authority"));
+
+        // во второй транзакции баланс pool-token не менялся
+        let mut second = sample_transaction();
+        second.instructions[0].accounts.push("pool-token".into());
+        let adapter = TransactionAdapter::new(second, ParseConfig::default());
+        assert_eq!(adapter.get_token_account_owner("pool-token"), None);
+        let adapter = adapter.with_owner_cache(&cache);
+        assert_eq!(adapter.get_token_account_owner("pool-token"), Some("pool-authority"));
+    }
+
+    #[test]
+    fn mint_filter_drops_unrelated_transactions_and_transfers() {
+        let parser = DexParser::new();
+        let config_for = |mints: &[&str]| ParseConfig {
+            try_unknown_dex: false,
+            mint_filter: Some(mints.iter().map(|mint| mint.to_string()).collect()),
+            ..Default::default()
+        };
+
+        let result = parser.parse_all(sample_transaction(), Some(config_for(&["BASE"])));
+        assert!(result.state);
+        assert_eq!(result.trades.len(), 1);
+
+        let result = parser.parse_all(sample_transaction(), Some(config_for(&["OTHER"])));
+        assert!(result.state);
+        assert!(result.filtered);
+        assert!(result.trades.is_empty());
+
+        let mut tx = sample_transaction();
+        tx.instructions[0].program_id = "UNKNOWN_PROGRAM".into();
+        let transfers = parser.parse_transfers(tx, Some(config_for(&["QUOTE"])));
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].info.mint, "QUOTE");
+    }
+
+    #[test]
+    fn mint_filter_skips_programs_before_parsing() {
+        let parser = DexParser::new();
+        let mut tx = sample_transaction();
+        tx.instructions.push(SolanaInstruction {
+            program_id: dex_programs::RAYDIUM.into(),
+            accounts: vec!["pool".into(), "OTHER".into()],
+            data: "swap".to_string(),
+        });
+        let config = ParseConfig {
+            collect_diagnostics: true,
+            mint_filter: Some(["BASE".to_string()].into_iter().collect()),
+            ..Default::default()
+        };
+
+        let result = parser.parse_all(tx, Some(config));
+        assert!(!result.filtered);
+        assert_eq!(result.trades.len(), 1);
+        let diag = result.diagnostics.expect("diagnostics");
+        assert!(diag.skipped.iter().any(|skip| {
+            skip.program_id == dex_programs::RAYDIUM && skip.reason == SkipReason::MintFilter
+        }));
+        assert!(!diag
+            .skipped
+            .iter()
+            .any(|skip| skip.program_id == dex_programs::JUPITER));
+    }
+
+    #[test]
+    fn parse_block_iter_parses_lazily() {
+        use std::cell::Cell;
+
+        let parser = DexParser::new();
+        let produced = Cell::new(0);
+        let txs = (0..3).map(|_| {
+            produced.set(produced.get() + 1);
+            sample_transaction()
+        });
+        let mut results = parser.parse_block_iter(txs, None);
+        assert_eq!(produced.get(), 0);
+        let first = results.next().expect("result");
+        assert_eq!(first.trades.len(), 1);
+        assert_eq!(produced.get(), 1);
+        assert_eq!(results.count(), 2);
+
+        let values = [serde_json::json!(42)];
+        let mut raw = parser.try_parse_block_iter(&values, None);
+        assert!(matches!(raw.next(), Some(Err(ParserError::Deserialize(_)))));
+        assert!(raw.next().is_none());
+    }
+
+    #[test]
+    fn block_events_are_deduplicated_and_ordered() {
+        use crate::types::{EventOrderKey, SolanaBlock};
+
+        let mut failed_retry = sample_transaction();
+        failed_retry.meta.status = TransactionStatus::Failed;
+        let mut other = sample_transaction();
+        other.signature = "other-signature".to_string();
+        let block = SolanaBlock {
+            slot: 7,
+            block_time: None,
+            transactions: vec![failed_retry, sample_transaction(), other],
+        };
+
+        let result = DexParser::new().parse_block_parsed(&block, None);
+        assert_eq!(result.transactions.len(), 2);
+        assert_eq!(result.transactions[0].tx_status, TransactionStatus::Success);
+
+        let keys: Vec<EventOrderKey> = result
+            .iter_events_ordered()
+            .map(|event| event.order_key())
+            .collect();
+        let first = &result.transactions[0];
+        let per_tx = first.trades.len() + first.liquidities.len() + first.transfers.len();
+        assert_eq!(keys.len(), 2 * per_tx);
+        assert!(keys.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!((keys[0].slot, keys[0].tx_index), (1, 0));
+        assert_eq!(keys.last().unwrap().tx_index, 1);
+        assert_eq!(
+            result.transactions[1].trades[0].order_key.map(|key| key.tx_index),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn block_json_context_fills_slot_time_and_blockhash() {
+        let mut tx = sample_transaction();
+        tx.slot = 0;
+        tx.block_time = 0;
+        let block = serde_json::json!({
+            "blockTime": 1_700_000_000u64,
+            "blockhash": "hash",
+            "parentSlot": 41,
+            "slot": 42,
+            "transactions": [serde_json::to_value(&tx).expect("serialize")],
+        });
+
+        let result = DexParser::new()
+            .parse_block_json(&block, None)
+            .expect("block");
+        assert_eq!(result.slot, 42);
+        assert_eq!(result.timestamp, Some(1_700_000_000));
+        assert_eq!(result.blockhash.as_deref(), Some("hash"));
+        let parsed = &result.transactions[0];
+        assert_eq!((parsed.slot, parsed.timestamp), (42, 1_700_000_000));
+        assert_eq!(parsed.trades[0].slot, 42);
+        assert_eq!(parsed.trades[0].timestamp, 1_700_000_000);
+    }
+
+    #[test]
+    fn collects_unknown_program_discriminators() {
+        use base64_simd::STANDARD;
+
+        let unknown = "UnknownDex1111111111111111111111111111111111";
+        let mut tx = sample_transaction();
+        let instruction = SolanaInstruction {
+            program_id: unknown.into(),
+            accounts: vec!["BASE".into(), "QUOTE".into()],
+            data: STANDARD.encode_to_string([1, 2, 3, 4, 5, 6, 7, 8, 9, 10]),
+        };
+        tx.instructions = vec![instruction.clone(), instruction];
+        tx.transfers.iter_mut().for_each(|transfer| {
+            transfer.program_id = unknown.into();
+        });
+
+        let config = ParseConfig {
+            collect_unknown_programs: true,
+            ..Default::default()
+        };
+        let result = DexParser::new().parse_all(tx.clone(), Some(config));
+        assert_eq!(
+            result.unknown_programs,
+            [UnknownProgramInfo {
+                program_id: unknown.to_string(),
+                discriminator: "0102030405060708".to_string(),
+                account_count: 2,
+                count: 2,
+                matched_swap: false,
+            }]
+        );
+        assert!(DexParser::new()
+            .parse_all(tx, None)
+            .unknown_programs
+            .is_empty());
+    }
+
+    #[test]
+    fn tags_trades_with_outer_router_program() {
+        let result = DexParser::new().parse_all(sample_transaction(), None);
+        assert!(!result.trades.is_empty());
+        assert!(result
+            .trades
+            .iter()
+            .all(|trade| trade.router.as_deref() == Some("Jupiter")));
+
+        let mut parser = DexParser::new();
+        parser.register_router(dex_programs::JUPITER, "MyBot");
+        let result = parser.parse_all(sample_transaction(), None);
+        assert_eq!(result.trades[0].router.as_deref(), Some("MyBot"));
+    }
+
+    #[cfg(feature = "prometheus")]
+    #[test]
+    fn reports_parse_metrics() {
+        use metrics_exporter_prometheus::PrometheusBuilder;
+
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let result = metrics::with_local_recorder(&recorder, || {
+            DexParser::new().parse_all(sample_transaction(), None)
+        });
+
+        let rendered = handle.render();
+        assert!(rendered
+            .contains(r#"dex_parser_transactions_parsed_total{path="owned",status="success"} 1"#));
+        let amm = result.trades[0].amm.as_deref().unwrap();
+        assert!(rendered.contains(&format!(r#"dex_parser_trades_total{{amm="{amm}"}}"#)));
+        assert!(rendered.contains("dex_parser_parse_duration_seconds"));
+    }
+
+    #[test]
+    fn lite_trades_replace_full_trade_info() {
+        let full = DexParser::new().parse_all(sample_transaction(), None);
+        let config = ParseConfig {
+            lite_trades: true,
+            ..ParseConfig::default()
+        };
+        let lite = DexParser::new().parse_all(sample_transaction(), Some(config));
+        assert!(lite.trades.is_empty());
+        assert_eq!(lite.trades_lite.len(), full.trades.len());
+        let (trade, compact) = (&full.trades[0], &lite.trades_lite[0]);
+        assert_eq!(compact.input_amount_raw, trade.input_token.amount_raw);
+        assert_eq!(compact.pool.as_ref(), trade.pool.first());
+
+        let size = |value: &ParseResult| serde_json::to_vec(value).unwrap().len();
+        assert!(size(&lite) < size(&full));
+    }
+}
@@ -1,13 +1,85 @@
 use thiserror::Error;
 
+use crate::core::zero_copy::ParseError as ZcParseError;
+
+/// Ошибки парсинга по стадиям, чтобы вызывающий код мог ветвиться по виду ошибки.
 #[derive(Debug, Error)]
 pub enum ParserError {
+    /// Данные инструкции не декодируются (невалидный base64 и т.п.).
+    #[error("failed to decode instruction {idx} of program {program_id}: {reason}")]
+    InstructionDecode {
+        program_id: String,
+        /// `"outer-inner"`, как в `TradeInfo::idx`.
+        idx: String,
+        reason: String,
+    },
+    #[error("missing token balance for account {account}")]
+    MissingTokenBalance { account: String },
+    #[error("malformed transaction meta: {0}")]
+    MetaMalformed(String),
+    #[error("invalid base58 value: {value}")]
+    Base58Decode { value: String },
+    #[error("failed to deserialize transaction: {0}")]
+    Deserialize(String),
+    #[error("malformed zero-copy transaction: {0}")]
+    ZeroCopy(ZcParseError),
     #[error("transaction parsing failed: {0}")]
     Generic(String),
 }
 
 impl ParserError {
+    /// Имя варианта — label для метрик и логов.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::InstructionDecode { .. } => "instruction_decode",
+            Self::MissingTokenBalance { .. } => "missing_token_balance",
+            Self::MetaMalformed(_) => "meta_malformed",
+            Self::Base58Decode { .. } => "base58_decode",
+            Self::Deserialize(_) => "deserialize",
+            Self::ZeroCopy(_) => "zero_copy",
+            Self::Generic(_) => "generic",
+        }
+    }
+
     pub fn generic(message: impl Into<String>) -> Self {
         Self::Generic(message.into())
     }
+
+    /// `InstructionDecode` без позиции инструкции; см. [`ParserError::at`].
+    pub fn instruction_data(program_id: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::InstructionDecode {
+            program_id: program_id.into(),
+            idx: String::new(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Проставляет позицию инструкции в `InstructionDecode`; прочие варианты не меняются.
+    pub fn at(self, outer_index: usize, inner_index: Option<usize>) -> Self {
+        match self {
+            Self::InstructionDecode { program_id, reason, .. } => {
+                Self::instruction_decode(program_id, outer_index, inner_index, reason)
+            }
+            other => other,
+        }
+    }
+
+    pub fn instruction_decode(
+        program_id: impl Into<String>,
+        outer_index: usize,
+        inner_index: Option<usize>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self::InstructionDecode {
+            program_id: program_id.into(),
+            idx: format!("{}-{}", outer_index, inner_index.unwrap_or(0)),
+            reason: reason.into(),
+        }
+    }
+}
+
+impl From<ZcParseError> for ParserError {
+    fn from(err: ZcParseError) -> Self {
+        Self::ZeroCopy(err)
+    }
 }
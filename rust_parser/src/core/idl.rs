@@ -0,0 +1,522 @@
+//! Декодер инструкций по Anchor IDL, загружаемому в рантайме.
+//!
+//! Поддерживаются оба формата IDL: legacy (`metadata.address`, camelCase имена,
+//! `publicKey`, `{"defined": "Name"}`) и Anchor 0.30+ (`address`, явные
+//! `discriminator`, `pubkey`, `{"defined": {"name": ...}}`). Без явного
+//! дискриминатора он считается как `sha256("global:<snake_name>")[..8]`.
+
+use std::collections::HashMap;
+
+use base64_simd::STANDARD;
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+use crate::types::{AccountKey, DecodedInstruction, IdlAccountMeta, SolanaInstruction};
+
+#[derive(Debug, Error)]
+pub enum IdlError {
+    #[error("invalid IDL JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("IDL has no program address; pass it explicitly")]
+    MissingAddress,
+    #[error("unsupported IDL type: {0}")]
+    UnsupportedType(String),
+    #[error("malformed IDL: {0}")]
+    Malformed(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum IdlType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    U128,
+    I128,
+    F32,
+    F64,
+    String,
+    Bytes,
+    PublicKey,
+    Option(Box<IdlType>),
+    Vec(Box<IdlType>),
+    Array(Box<IdlType>, usize),
+    Defined(String),
+}
+
+/// Поля структуры или варианта; у tuple-полей имена `None`.
+pub type IdlFields = Vec<(Option<String>, IdlType)>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum IdlTypeDef {
+    Struct(IdlFields),
+    Enum(Vec<(String, IdlFields)>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct IdlInstruction {
+    pub name: String,
+    pub discriminator: [u8; 8],
+    /// Имена аккаунтов в порядке `accounts` (вложенные группы развёрнуты).
+    pub accounts: Vec<String>,
+    pub args: Vec<(String, IdlType)>,
+}
+
+/// Декодер инструкций одной программы.
+#[derive(Clone, Debug)]
+pub struct IdlDecoder {
+    program_id: String,
+    instructions: Vec<IdlInstruction>,
+    by_discriminator: HashMap<[u8; 8], usize>,
+    types: HashMap<String, IdlTypeDef>,
+}
+
+impl IdlDecoder {
+    /// Адрес программы берётся из `address` или `metadata.address`.
+    pub fn from_json(json: &str) -> Result<Self, IdlError> {
+        let idl: Value = serde_json::from_str(json)?;
+        let address = idl
+            .get("address")
+            .or_else(|| idl.pointer("/metadata/address"))
+            .and_then(Value::as_str)
+            .ok_or(IdlError::MissingAddress)?
+            .to_string();
+        Self::from_value(address, &idl)
+    }
+
+    pub fn from_json_for(program_id: &str, json: &str) -> Result<Self, IdlError> {
+        Self::from_value(program_id.to_string(), &serde_json::from_str(json)?)
+    }
+
+    fn from_value(program_id: String, idl: &Value) -> Result<Self, IdlError> {
+        let mut types = HashMap::new();
+        for def in idl
+            .get("types")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let name = str_field(def, "name")?;
+            types.insert(name.to_string(), parse_type_def(def)?);
+        }
+
+        let mut instructions = Vec::new();
+        for ix in idl
+            .get("instructions")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+        {
+            let name = str_field(ix, "name")?.to_string();
+            let discriminator = match ix.get("discriminator").and_then(Value::as_array) {
+                Some(bytes) => bytes
+                    .iter()
+                    .map(|byte| byte.as_u64().map(|b| b as u8))
+                    .collect::<Option<Vec<u8>>>()
+                    .and_then(|bytes| bytes.try_into().ok())
+                    .ok_or_else(|| IdlError::Malformed(format!("discriminator of {name}")))?,
+                None => sighash(&name),
+            };
+            let mut accounts = Vec::new();
+            flatten_accounts(ix.get("accounts"), &mut accounts);
+            let args = ix
+                .get("args")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .map(|arg| {
+                    Ok((
+                        str_field(arg, "name")?.to_string(),
+                        parse_type(&arg["type"])?,
+                    ))
+                })
+                .collect::<Result<_, IdlError>>()?;
+            instructions.push(IdlInstruction {
+                name,
+                discriminator,
+                accounts,
+                args,
+            });
+        }
+
+        let by_discriminator = instructions
+            .iter()
+            .enumerate()
+            .map(|(pos, ix)| (ix.discriminator, pos))
+            .collect();
+        Ok(Self {
+            program_id,
+            instructions,
+            by_discriminator,
+            types,
+        })
+    }
+
+    pub fn program_id(&self) -> &str {
+        &self.program_id
+    }
+
+    pub fn instructions(&self) -> &[IdlInstruction] {
+        &self.instructions
+    }
+
+    /// `data` — сырые байты инструкции; `None`, если дискриминатор неизвестен
+    /// или аргументы не читаются по layout'у.
+    pub fn decode(&self, data: &[u8], accounts: &[AccountKey]) -> Option<DecodedInstruction> {
+        let discriminator: [u8; 8] = data.get(..8)?.try_into().ok()?;
+        let ix = &self.instructions[*self.by_discriminator.get(&discriminator)?];
+
+        let mut reader = Reader { data: &data[8..] };
+        let mut args = Map::new();
+        for (name, ty) in &ix.args {
+            args.insert(name.clone(), self.read_value(&mut reader, ty, 0)?);
+        }
+        let accounts = accounts
+            .iter()
+            .enumerate()
+            .map(|(pos, pubkey)| IdlAccountMeta {
+                name: ix.accounts.get(pos).cloned(),
+                pubkey: pubkey.clone(),
+            })
+            .collect();
+        Some(DecodedInstruction {
+            name: ix.name.clone(),
+            args: Value::Object(args),
+            accounts,
+        })
+    }
+
+    pub fn decode_instruction(
+        &self,
+        instruction: &SolanaInstruction,
+    ) -> Option<DecodedInstruction> {
+        if instruction.program_id != self.program_id {
+            return None;
+        }
+        let data = STANDARD.decode_to_vec(&instruction.data).ok()?;
+        self.decode(&data, &instruction.accounts)
+    }
+
+    fn read_value(&self, reader: &mut Reader, ty: &IdlType, depth: usize) -> Option<Value> {
+        // защита от рекурсивных defined-типов в кривом IDL
+        if depth > 32 {
+            return None;
+        }
+        Some(match ty {
+            IdlType::Bool => Value::Bool(reader.take::<1>()?[0] != 0),
+            IdlType::U8 => json!(reader.take::<1>()?[0]),
+            IdlType::I8 => json!(reader.take::<1>()?[0] as i8),
+            IdlType::U16 => json!(u16::from_le_bytes(reader.take()?)),
+            IdlType::I16 => json!(i16::from_le_bytes(reader.take()?)),
+            IdlType::U32 => json!(u32::from_le_bytes(reader.take()?)),
+            IdlType::I32 => json!(i32::from_le_bytes(reader.take()?)),
+            IdlType::U64 => json!(u64::from_le_bytes(reader.take()?)),
+            IdlType::I64 => json!(i64::from_le_bytes(reader.take()?)),
+            // 128-битные — строкой, JSON-числа их не удержат
+            IdlType::U128 => json!(u128::from_le_bytes(reader.take()?).to_string()),
+            IdlType::I128 => json!(i128::from_le_bytes(reader.take()?).to_string()),
+            IdlType::F32 => json!(f32::from_le_bytes(reader.take()?)),
+            IdlType::F64 => json!(f64::from_le_bytes(reader.take()?)),
+            IdlType::String => {
+                let len = u32::from_le_bytes(reader.take()?) as usize;
+                json!(String::from_utf8(reader.slice(len)?.to_vec()).ok()?)
+            }
+            IdlType::Bytes => {
+                let len = u32::from_le_bytes(reader.take()?) as usize;
+                json!(STANDARD.encode_to_string(reader.slice(len)?))
+            }
+            IdlType::PublicKey => json!(bs58::encode(reader.take::<32>()?).into_string()),
+            IdlType::Option(inner) => match reader.take::<1>()?[0] {
+                0 => Value::Null,
+                _ => self.read_value(reader, inner, depth + 1)?,
+            },
+            IdlType::Vec(inner) => {
+                let len = u32::from_le_bytes(reader.take()?) as usize;
+                // длина из данных: не даём аллоцировать больше, чем осталось байт
+                if len > reader.data.len() {
+                    return None;
+                }
+                let items = (0..len)
+                    .map(|_| self.read_value(reader, inner, depth + 1))
+                    .collect::<Option<Vec<_>>>()?;
+                Value::Array(items)
+            }
+            IdlType::Array(inner, len) => {
+                let items = (0..*len)
+                    .map(|_| self.read_value(reader, inner, depth + 1))
+                    .collect::<Option<Vec<_>>>()?;
+                Value::Array(items)
+            }
+            IdlType::Defined(name) => match self.types.get(name)? {
+                IdlTypeDef::Struct(fields) => self.read_fields(reader, fields, depth)?,
+                IdlTypeDef::Enum(variants) => {
+                    let (variant, fields) = variants.get(reader.take::<1>()?[0] as usize)?;
+                    if fields.is_empty() {
+                        json!(variant)
+                    } else {
+                        json!({ variant.as_str(): self.read_fields(reader, fields, depth)? })
+                    }
+                }
+            },
+        })
+    }
+
+    /// Именованные поля — объект, tuple — массив.
+    fn read_fields(
+        &self,
+        reader: &mut Reader,
+        fields: &[(Option<String>, IdlType)],
+        depth: usize,
+    ) -> Option<Value> {
+        if fields.iter().all(|(name, _)| name.is_some()) {
+            let mut object = Map::new();
+            for (name, ty) in fields {
+                let value = self.read_value(reader, ty, depth + 1)?;
+                object.insert(name.clone().unwrap_or_default(), value);
+            }
+            Some(Value::Object(object))
+        } else {
+            fields
+                .iter()
+                .map(|(_, ty)| self.read_value(reader, ty, depth + 1))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array)
+        }
+    }
+}
+
+/// IDL-декодеры по program_id; подключается к `DexParser::with_idl_registry`.
+#[derive(Clone, Debug, Default)]
+pub struct IdlRegistry {
+    decoders: HashMap<String, IdlDecoder>,
+}
+
+impl IdlRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, decoder: IdlDecoder) {
+        self.decoders.insert(decoder.program_id.clone(), decoder);
+    }
+
+    pub fn load_json(&mut self, json: &str) -> Result<&IdlDecoder, IdlError> {
+        let decoder = IdlDecoder::from_json(json)?;
+        let program_id = decoder.program_id.clone();
+        self.register(decoder);
+        Ok(&self.decoders[&program_id])
+    }
+
+    pub fn get(&self, program_id: &str) -> Option<&IdlDecoder> {
+        self.decoders.get(program_id)
+    }
+
+    pub fn contains(&self, program_id: &str) -> bool {
+        self.decoders.contains_key(program_id)
+    }
+
+    pub fn decode(&self, instruction: &SolanaInstruction) -> Option<DecodedInstruction> {
+        self.get(&instruction.program_id)?
+            .decode_instruction(instruction)
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn slice(&mut self, len: usize) -> Option<&'a [u8]> {
+        if self.data.len() < len {
+            return None;
+        }
+        let (head, tail) = self.data.split_at(len);
+        self.data = tail;
+        Some(head)
+    }
+
+    fn take<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.slice(N)?.try_into().ok()
+    }
+}
+
+fn str_field<'a>(value: &'a Value, key: &str) -> Result<&'a str, IdlError> {
+    value
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| IdlError::Malformed(format!("missing `{key}` in {value}")))
+}
+
+/// Anchor sighash: `sha256("global:<snake_case name>")[..8]`.
+fn sighash(name: &str) -> [u8; 8] {
+    let preimage = format!("global:{}", to_snake_case(name));
+    let hash = solana_program::hash::hash(preimage.as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash.to_bytes()[..8]);
+    discriminator
+}
+
+fn to_snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (pos, ch) in name.chars().enumerate() {
+        if ch.is_ascii_uppercase() {
+            if pos > 0 {
+                out.push('_');
+            }
+            out.push(ch.to_ascii_lowercase());
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+fn flatten_accounts(accounts: Option<&Value>, out: &mut Vec<String>) {
+    for account in accounts.and_then(Value::as_array).into_iter().flatten() {
+        match account.get("accounts") {
+            Some(nested) => flatten_accounts(Some(nested), out),
+            None => out.push(account["name"].as_str().unwrap_or_default().to_string()),
+        }
+    }
+}
+
+fn parse_type(ty: &Value) -> Result<IdlType, IdlError> {
+    if let Some(name) = ty.as_str() {
+        return Ok(match name {
+            "bool" => IdlType::Bool,
+            "u8" => IdlType::U8,
+            "i8" => IdlType::I8,
+            "u16" => IdlType::U16,
+            "i16" => IdlType::I16,
+            "u32" => IdlType::U32,
+            "i32" => IdlType::I32,
+            "u64" => IdlType::U64,
+            "i64" => IdlType::I64,
+            "u128" => IdlType::U128,
+            "i128" => IdlType::I128,
+            "f32" => IdlType::F32,
+            "f64" => IdlType::F64,
+            "string" => IdlType::String,
+            "bytes" => IdlType::Bytes,
+            "publicKey" | "pubkey" => IdlType::PublicKey,
+            other => return Err(IdlError::UnsupportedType(other.to_string())),
+        });
+    }
+    if let Some(inner) = ty.get("option") {
+        return Ok(IdlType::Option(Box::new(parse_type(inner)?)));
+    }
+    if let Some(inner) = ty.get("vec") {
+        return Ok(IdlType::Vec(Box::new(parse_type(inner)?)));
+    }
+    if let Some([inner, len]) = ty.get("array").and_then(Value::as_array).map(Vec::as_slice) {
+        let len = len
+            .as_u64()
+            .ok_or_else(|| IdlError::UnsupportedType(format!("array length {len}")))?;
+        return Ok(IdlType::Array(Box::new(parse_type(inner)?), len as usize));
+    }
+    if let Some(defined) = ty.get("defined") {
+        let name = defined
+            .as_str()
+            .or_else(|| defined.get("name").and_then(Value::as_str))
+            .ok_or_else(|| IdlError::UnsupportedType(ty.to_string()))?;
+        return Ok(IdlType::Defined(name.to_string()));
+    }
+    Err(IdlError::UnsupportedType(ty.to_string()))
+}
+
+fn parse_fields(fields: Option<&Value>) -> Result<IdlFields, IdlError> {
+    fields
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|field| match field.get("type") {
+            Some(ty) => Ok((field["name"].as_str().map(str::to_string), parse_type(ty)?)),
+            // tuple-поле: тип без обёртки
+            None => Ok((None, parse_type(field)?)),
+        })
+        .collect()
+}
+
+fn parse_type_def(def: &Value) -> Result<IdlTypeDef, IdlError> {
+    let body = &def["type"];
+    match body["kind"].as_str() {
+        Some("struct") => Ok(IdlTypeDef::Struct(parse_fields(body.get("fields"))?)),
+        Some("enum") => body["variants"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|variant| {
+                Ok((
+                    str_field(variant, "name")?.to_string(),
+                    parse_fields(variant.get("fields"))?,
+                ))
+            })
+            .collect::<Result<_, IdlError>>()
+            .map(IdlTypeDef::Enum),
+        other => Err(IdlError::UnsupportedType(format!("type kind {other:?}"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDL: &str = r#"{
+        "version": "0.1.0",
+        "name": "toy_amm",
+        "metadata": { "address": "Toy1111111111111111111111111111111111111111" },
+        "instructions": [{
+            "name": "swapExactIn",
+            "accounts": [
+                { "name": "user", "isMut": true, "isSigner": true },
+                { "name": "pool", "accounts": [{ "name": "poolState" }, { "name": "vault" }] }
+            ],
+            "args": [
+                { "name": "amountIn", "type": "u64" },
+                { "name": "referrer", "type": { "option": "publicKey" } },
+                { "name": "params", "type": { "defined": "SwapParams" } }
+            ]
+        }],
+        "types": [
+            { "name": "SwapParams", "type": { "kind": "struct", "fields": [
+                { "name": "side", "type": { "defined": "Side" } },
+                { "name": "limits", "type": { "vec": "u16" } }
+            ] } },
+            { "name": "Side", "type": { "kind": "enum", "variants": [{ "name": "Bid" }, { "name": "Ask" }] } }
+        ]
+    }"#;
+
+    #[test]
+    fn decodes_legacy_idl_instruction_args_and_accounts() {
+        let decoder = IdlDecoder::from_json(IDL).unwrap();
+        assert_eq!(
+            decoder.program_id(),
+            "Toy1111111111111111111111111111111111111111"
+        );
+
+        let mut data = sighash("swapExactIn").to_vec();
+        data.extend_from_slice(&1_500u64.to_le_bytes());
+        data.push(0); // referrer: None
+        data.push(1); // Side::Ask
+        data.extend_from_slice(&2u32.to_le_bytes());
+        data.extend_from_slice(&[10, 0, 20, 0]);
+
+        let accounts: Vec<AccountKey> = ["u", "p", "v"].map(AccountKey::new).into();
+        let decoded = decoder.decode(&data, &accounts).expect("decoded");
+        assert_eq!(decoded.name, "swapExactIn");
+        assert_eq!(
+            decoded.args,
+            json!({ "amountIn": 1500, "referrer": null, "params": { "side": "Ask", "limits": [10, 20] } })
+        );
+        let names: Vec<_> = decoded.accounts.iter().map(|a| a.name.as_deref()).collect();
+        assert_eq!(names, [Some("user"), Some("poolState"), Some("vault")]);
+
+        // усечённые данные не декодируются
+        assert!(decoder.decode(&data[..data.len() - 1], &accounts).is_none());
+    }
+}
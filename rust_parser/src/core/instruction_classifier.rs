@@ -1,85 +1,63 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
+use crate::core::error::ParserError;
+use crate::core::idl::IdlRegistry;
 use crate::core::transaction_adapter::TransactionAdapter;
-use crate::types::ClassifiedInstruction;
+use crate::types::{AccountKey, ClassifiedInstruction, SolanaInstruction};
 
 use crate::core::constants::{SKIP_PROGRAM_IDS, SYSTEM_PROGRAMS};
-use crate::core::utils::get_instruction_data;
+use crate::core::utils::{get_instruction_data, sort_by_execution_order};
 
 #[derive(Clone, Debug)]
 pub struct InstructionClassifier {
-    instruction_map: HashMap<String, Vec<ClassifiedInstruction>>,
+    // ключи — те же `AccountKey`, что в инструкциях адаптера: вставка без аллокаций
+    instruction_map: HashMap<AccountKey, Vec<ClassifiedInstruction>>,
     // храним порядок «первого появления» program_id (как в TS порядок ключей Map)
-    order: Vec<String>,
+    order: Vec<AccountKey>,
+    // первая инструкция программы с невалидным base64 в data: (outer, inner, причина)
+    decode_errors: HashMap<AccountKey, (usize, Option<usize>, String)>,
 }
 
 impl InstructionClassifier {
+    #[cfg_attr(feature = "parse-metrics", tracing::instrument(level = "debug", skip_all))]
     pub fn new(adapter: &TransactionAdapter) -> Self {
-        #[cfg(debug_assertions)]
+        #[cfg(feature = "parse-metrics")]
         let t0 = std::time::Instant::now();
-        
-               // Pre-allocate with estimated capacity
-               let outer_count = adapter.instructions().len();
-               let mut instruction_map: HashMap<String, Vec<ClassifiedInstruction>> = HashMap::with_capacity(outer_count / 2);
-        let mut order: Vec<String> = Vec::with_capacity(outer_count / 2);
-        let mut seen: HashSet<String> = HashSet::with_capacity(outer_count / 2);
 
-        // OUTER instructions - ZERO-COPY: минимизируем клонирования program_id
+        let outer_count = adapter.instructions().len();
+        let mut classifier = Self {
+            instruction_map: HashMap::with_capacity(outer_count / 2),
+            order: Vec::with_capacity(outer_count / 2),
+            decode_errors: HashMap::new(),
+        };
+
         for (outer_index, instruction) in adapter.instructions().iter().enumerate() {
-            if instruction.program_id.is_empty() {
-                continue;
-            }
-            // ZERO-COPY: клонируем program_id только один раз для HashMap ключа
-            let program_id = instruction.program_id.clone();
-            let classified = ClassifiedInstruction {
-                program_id: program_id.clone(), // Переиспользуем уже клонированный program_id
-                outer_index,
-                inner_index: None,
-                data: instruction.clone(),
-            };
-            instruction_map
-                .entry(program_id.clone()) // Переиспользуем клон
-                .or_default()
-                .push(classified);
-            if seen.insert(program_id.clone()) {
-                order.push(program_id); // Используем уже клонированный program_id
-            }
+            classifier.push(instruction, outer_index, None, Some(1));
         }
-        #[cfg(debug_assertions)]
+        #[cfg(feature = "parse-metrics")]
         let t1 = std::time::Instant::now();
 
-        // INNER instructions - ZERO-COPY: минимизируем клонирования program_id
-        #[cfg(debug_assertions)]
+        #[cfg(feature = "parse-metrics")]
         let mut inner_count = 0;
         for inner in adapter.inner_instructions() {
             for (inner_index, instruction) in inner.instructions.iter().enumerate() {
-                if instruction.program_id.is_empty() {
-                    continue;
-                }
-                #[cfg(debug_assertions)]
+                #[cfg(feature = "parse-metrics")]
                 {
                     inner_count += 1;
                 }
-                // ZERO-COPY: клонируем program_id только один раз для HashMap ключа
-                let program_id = instruction.program_id.clone();
-                let classified = ClassifiedInstruction {
-                    program_id: program_id.clone(), // Переиспользуем уже клонированный program_id
-                    outer_index: inner.index,
-                    inner_index: Some(inner_index),
-                    data: instruction.clone(),
-                };
-                instruction_map
-                    .entry(program_id.clone()) // Переиспользуем клон
-                    .or_default()
-                    .push(classified);
-                if seen.insert(program_id.clone()) {
-                    order.push(program_id); // Используем уже клонированный program_id
-                }
+                classifier.push(
+                    instruction,
+                    inner.index,
+                    Some(inner_index),
+                    inner.stack_height(inner_index),
+                );
             }
         }
+        #[cfg(feature = "parse-metrics")]
+        let order = &classifier.order;
         
-        #[cfg(debug_assertions)]
-        {
+        #[cfg(feature = "parse-metrics")]
+        if adapter.config().verbose_timing {
             let t2 = std::time::Instant::now();
             tracing::debug!(
                 "InstructionClassifier: processed {} inner instructions from {} groups",
@@ -92,22 +70,77 @@ impl InstructionClassifier {
                 (t2 - t1).as_secs_f64() * 1_000_000.0, inner_count,
                 (t2 - t0).as_secs_f64() * 1_000_000.0,
             );
-            tracing::info!(
+            tracing::debug!(
                 "InstructionClassifier: found {} unique program IDs: {:?}",
                 order.len(),
                 order
             );
         }
 
-        Self {
-            instruction_map,
-            order,
+        classifier
+    }
+
+    fn push(
+        &mut self,
+        instruction: &SolanaInstruction,
+        outer_index: usize,
+        inner_index: Option<usize>,
+        stack_height: Option<u32>,
+    ) {
+        if instruction.program_id.is_empty() {
+            return;
+        }
+        let program_id = &instruction.program_id;
+        // base64 только проверяется: декодируют data сами парсеры
+        if let Err(err) = base64_simd::STANDARD.check(instruction.data.as_bytes()) {
+            self.decode_errors
+                .entry(program_id.clone())
+                .or_insert_with(|| (outer_index, inner_index, format!("invalid base64: {err}")));
+        }
+        let classified = ClassifiedInstruction {
+            program_id: program_id.clone(),
+            outer_index,
+            inner_index,
+            data: instruction.clone(),
+            data_bytes: Vec::new(),
+            stack_height,
+            decoded: None,
+        };
+        match self.instruction_map.get_mut(program_id.as_str()) {
+            Some(instructions) => instructions.push(classified),
+            None => {
+                self.order.push(program_id.clone());
+                self.instruction_map.insert(program_id.clone(), vec![classified]);
+            }
+        }
+    }
+
+    /// Ошибка декодирования data первой битой инструкции программы. Парсер такой
+    /// программы не запускается; остальные программы транзакции разбираются как обычно.
+    pub fn decode_error(&self, program_id: &str) -> Option<ParserError> {
+        let (outer_index, inner_index, reason) = self.decode_errors.get(program_id)?;
+        Some(ParserError::instruction_decode(
+            program_id,
+            *outer_index,
+            *inner_index,
+            reason.as_str(),
+        ))
+    }
+
+    /// Заполняет `decoded` у инструкций программ, для которых в реестре есть IDL.
+    pub fn decode_with_idls(&mut self, idls: &IdlRegistry) {
+        for (program_id, instructions) in self.instruction_map.iter_mut() {
+            let Some(decoder) = idls.get(program_id) else {
+                continue;
+            };
+            for classified in instructions {
+                classified.decoded = decoder.decode_instruction(&classified.data);
+            }
         }
     }
 
     /// Полный список program_id в порядке первого появления,
     /// но с фильтром как в TS: исключаем системные и «skip».
-    /// ZERO-COPY: возвращает итератор по ссылкам
     pub fn get_all_program_ids_iter(&self) -> impl Iterator<Item = &str> {
         self.order.iter()
             .map(|pid| pid.as_str())
@@ -118,14 +151,11 @@ impl InstructionClassifier {
     
     /// Получить все program_id как Vec (для обратной совместимости)
     pub fn get_all_program_ids(&self) -> Vec<String> {
-        // ZERO-COPY: используем итератор, клонируем только в конце
         self.get_all_program_ids_iter().map(|s| s.to_string()).collect()
     }
 
     /// Все инструкции по одному program_id
-    /// ZERO-COPY: возвращает ссылку вместо клона
     pub fn get_instructions(&self, program_id: &str) -> &[ClassifiedInstruction] {
-        // ZERO-COPY: возвращаем срез вместо клонирования
         self.instruction_map
             .get(program_id)
             .map(|v| v.as_slice())
@@ -163,8 +193,9 @@ impl InstructionClassifier {
     ) -> Option<ClassifiedInstruction> {
         for instructions in self.instruction_map.values() {
             for ci in instructions {
-                // get_instruction_data должен вернуть &[u8] / Vec<u8> с реальными байтами data
-                let data = get_instruction_data(&ci.data);
+                let Ok(data) = get_instruction_data(&ci.data) else {
+                    continue;
+                };
                 if data.len() >= slice && &data[..slice] == discriminator {
                     return Some(ci.clone());
                 }
@@ -173,6 +204,17 @@ impl InstructionClassifier {
         None
     }
 
+    /// Все инструкции в порядке исполнения с заполненным `data_bytes`
+    /// (в классификаторе байты не декодируются ради скорости).
+    pub fn in_execution_order(&self) -> Vec<ClassifiedInstruction> {
+        let mut instructions = self.flatten();
+        sort_by_execution_order(&mut instructions);
+        for ci in &mut instructions {
+            ci.data_bytes = get_instruction_data(&ci.data).unwrap_or_default();
+        }
+        instructions
+    }
+
     /// Опционально оставил (в TS нет, но вдруг пригодится)
     pub fn flatten(&self) -> Vec<ClassifiedInstruction> {
         self.instruction_map.values().flatten().cloned().collect()
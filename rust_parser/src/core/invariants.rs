@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+use crate::types::{ParseResult, RejectedTrade, TokenAmount, TokenInfo, TradeInfo, TradeRejection};
+
+/// Допустимое расхождение суммы ног trades с изменением баланса token account'а:
+/// доля от оборота плюс единица на округление.
+const BALANCE_TOLERANCE: f64 = 0.01;
+
+/// `ParseConfig::strict`: trades, нарушившие инварианты, переезжают из `trades`
+/// в `rejected_trades` с причинами. `decimals` — mint → decimals из token balances.
+pub fn reject_invalid_trades(result: &mut ParseResult, decimals: impl Fn(&str) -> Option<u8>) {
+    if result.trades.is_empty() {
+        return;
+    }
+    let mismatched = balance_mismatches(&result.trades);
+    let (valid, rejected): (Vec<_>, Vec<_>) = std::mem::take(&mut result.trades)
+        .into_iter()
+        .map(|trade| {
+            let mut reasons = trade_violations(&trade, &decimals);
+            for token in [&trade.input_token, &trade.output_token] {
+                for account in [&token.source, &token.destination].into_iter().flatten() {
+                    if let Some(reason) = mismatched.get(account) {
+                        if !reasons.contains(reason) {
+                            reasons.push(reason.clone());
+                        }
+                    }
+                }
+            }
+            (trade, reasons)
+        })
+        .partition(|(_, reasons)| reasons.is_empty());
+    result.trades = valid.into_iter().map(|(trade, _)| trade).collect();
+    result.rejected_trades.extend(
+        rejected
+            .into_iter()
+            .map(|(trade, reasons)| RejectedTrade { trade, reasons }),
+    );
+}
+
+/// Проверки одной сделки, без учёта соседних.
+pub fn trade_violations(
+    trade: &TradeInfo,
+    decimals: impl Fn(&str) -> Option<u8>,
+) -> Vec<TradeRejection> {
+    let mut reasons = Vec::new();
+    if trade.input_token.mint == trade.output_token.mint {
+        reasons.push(TradeRejection::SameMint);
+    }
+    for token in [&trade.input_token, &trade.output_token] {
+        if token.amount <= 0.0 || raw(&token.amount_raw) == Some(0) {
+            reasons.push(TradeRejection::ZeroAmount {
+                mint: token.mint.clone(),
+            });
+        }
+        if let Some(expected) = decimals(&token.mint).filter(|&d| d != token.decimals) {
+            reasons.push(TradeRejection::DecimalsMismatch {
+                mint: token.mint.clone(),
+                expected,
+                actual: token.decimals,
+            });
+        }
+    }
+    reasons
+}
+
+/// account → (ожидаемое изменение, оборот, фактическое изменение)
+type AccountFlow<'a> = HashMap<&'a str, (i128, i128, Option<i128>)>;
+
+/// Token account'ы, чьё изменение баланса не сходится с суммой ног trades через них.
+/// Ноги native SOL (кошелёк, а не WSOL-аккаунт) не проверяются: token balances у них
+/// нет, а lamports двигают ещё комиссия и rent.
+fn balance_mismatches(trades: &[TradeInfo]) -> HashMap<String, TradeRejection> {
+    let mut accounts = AccountFlow::new();
+    for trade in trades {
+        let input = &trade.input_token;
+        let output = &trade.output_token;
+        let spent = delta(&input.source_pre_balance, &input.source_balance);
+        add_flow(&mut accounts, input.source.as_ref(), input, -1, spent);
+        let received = delta(&output.destination_pre_balance, &output.destination_balance);
+        add_flow(
+            &mut accounts,
+            output.destination.as_ref(),
+            output,
+            1,
+            received,
+        );
+    }
+
+    accounts
+        .into_iter()
+        .filter_map(|(account, (expected, turnover, actual))| {
+            let actual = actual?;
+            let slack = (turnover as f64 * BALANCE_TOLERANCE) as i128 + 1;
+            ((actual - expected).abs() > slack).then(|| {
+                let reason = TradeRejection::BalanceMismatch {
+                    account: account.to_string(),
+                    expected: expected.to_string(),
+                    actual: actual.to_string(),
+                };
+                (account.to_string(), reason)
+            })
+        })
+        .collect()
+}
+
+fn add_flow<'a>(
+    accounts: &mut AccountFlow<'a>,
+    account: Option<&'a String>,
+    token: &TokenInfo,
+    sign: i128,
+    actual: Option<i128>,
+) {
+    let (Some(account), Some(amount)) = (account, raw(&token.amount_raw)) else {
+        return;
+    };
+    let entry = accounts.entry(account.as_str()).or_insert((0, 0, None));
+    entry.0 += sign * amount;
+    entry.1 += amount;
+    entry.2 = entry.2.or(actual);
+}
+
+fn raw(amount: &str) -> Option<i128> {
+    amount.parse().ok()
+}
+
+fn delta(pre: &Option<TokenAmount>, post: &Option<TokenAmount>) -> Option<i128> {
+    Some(raw(&post.as_ref()?.amount)? - raw(&pre.as_ref()?.amount)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(mint: &str, raw_amount: u64, decimals: u8) -> TokenInfo {
+        TokenInfo {
+            mint: mint.to_string(),
+            amount: raw_amount as f64 / 10f64.powi(i32::from(decimals)),
+            amount_raw: raw_amount.to_string(),
+            decimals,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn rejects_trades_breaking_invariants() {
+        let good = TradeInfo {
+            input_token: TokenInfo {
+                source: Some("user-usdc".to_string()),
+                source_pre_balance: Some(TokenAmount::new("5000000", 6, None)),
+                source_balance: Some(TokenAmount::new("4000000", 6, None)),
+                ..token("USDC", 1_000_000, 6)
+            },
+            output_token: token("MEME", 42, 0),
+            idx: "0".to_string(),
+            ..Default::default()
+        };
+        let same_mint = TradeInfo {
+            input_token: token("USDC", 1_000_000, 6),
+            output_token: token("USDC", 1, 6),
+            idx: "1".to_string(),
+            ..TradeInfo::default()
+        };
+        let mut leaking = good.clone();
+        leaking.idx = "2".to_string();
+        leaking.input_token.source = Some("other-usdc".to_string());
+        leaking.input_token.source_balance = Some(TokenAmount::new("4900000", 6, None));
+        let wrong_decimals = TradeInfo {
+            output_token: token("MEME", 42, 9),
+            idx: "3".to_string(),
+            ..TradeInfo::default()
+        };
+
+        let mut result = ParseResult {
+            trades: vec![good, same_mint, leaking, wrong_decimals],
+            ..Default::default()
+        };
+        reject_invalid_trades(&mut result, |mint| (mint == "MEME").then_some(0));
+
+        assert_eq!(result.trades.len(), 1);
+        assert_eq!(result.trades[0].idx, "0");
+        let reasons: Vec<_> = result
+            .rejected_trades
+            .iter()
+            .map(|rejected| (rejected.trade.idx.as_str(), rejected.reasons.clone()))
+            .collect();
+        assert_eq!(reasons[0], ("1", vec![TradeRejection::SameMint]));
+        assert!(matches!(
+            reasons[1].1.as_slice(),
+            [TradeRejection::BalanceMismatch { account, .. }] if account == "other-usdc"
+        ));
+        assert!(reasons[2].1.contains(&TradeRejection::DecimalsMismatch {
+            mint: "MEME".to_string(),
+            expected: 0,
+            actual: 9,
+        }));
+    }
+
+    #[test]
+    fn checks_wsol_accounts_but_not_native_sol() {
+        use crate::core::constants::TOKENS;
+
+        let wsol = |raw_amount: u64| token(TOKENS.SOL, raw_amount, 9);
+        let native = TradeInfo {
+            input_token: TokenInfo {
+                source: Some("wallet".to_string()),
+                ..wsol(1_000_000_000)
+            },
+            output_token: token("MEME", 42, 0),
+            idx: "0".to_string(),
+            ..TradeInfo::default()
+        };
+        let wrapped = TradeInfo {
+            input_token: TokenInfo {
+                source: Some("user-wsol".to_string()),
+                source_pre_balance: Some(TokenAmount::new("3000000000", 9, None)),
+                source_balance: Some(TokenAmount::new("1000000000", 9, None)),
+                ..wsol(1_000_000_000)
+            },
+            output_token: token("MEME", 42, 0),
+            idx: "1".to_string(),
+            ..TradeInfo::default()
+        };
+
+        let mut result = ParseResult {
+            trades: vec![native, wrapped],
+            ..Default::default()
+        };
+        reject_invalid_trades(&mut result, |_| None);
+
+        assert_eq!(result.trades.len(), 1);
+        assert_eq!(result.trades[0].idx, "0");
+        assert!(matches!(
+            result.rejected_trades[0].reasons.as_slice(),
+            [TradeRejection::BalanceMismatch { account, .. }] if account == "user-wsol"
+        ));
+    }
+}
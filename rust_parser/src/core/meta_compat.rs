@@ -0,0 +1,134 @@
+//! meta транзакций из long-term storage (solana-storage-bigtable) и старых слотов.
+//! До ~середины 2022 в meta нет `computeUnitsConsumed`, ещё раньше — `logMessages`,
+//! token balances и `err` (только `status: {"Ok"|"Err"}`). Экстракторы owned и zc путей
+//! читают эти поля отсюда, чтобы бэкфилл не отдавал молча нули.
+
+use serde_json::Value;
+
+/// Поколение layout'а meta.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetaVersion {
+    /// Ни логов, ни CU: compute units неизвестны.
+    Legacy,
+    /// Логи есть, `computeUnitsConsumed` нет: CU восстанавливаются по логам.
+    LogsOnly,
+    /// `computeUnitsConsumed` в meta.
+    Current,
+}
+
+impl MetaVersion {
+    pub fn detect(meta: &Value) -> Self {
+        if compute_units_field(meta).is_some() {
+            Self::Current
+        } else if meta.get("logMessages").is_some_and(Value::is_array) {
+            Self::LogsOnly
+        } else {
+            Self::Legacy
+        }
+    }
+}
+
+fn compute_units_field(meta: &Value) -> Option<u64> {
+    meta.get("computeUnitsConsumed")
+        .or_else(|| meta.get("computeUnits"))
+        .and_then(Value::as_u64)
+}
+
+/// CU транзакции; для старых meta — сумма `consumed` top-level инструкций из логов,
+/// `0` если нет и логов.
+pub fn compute_units(meta: &Value) -> u64 {
+    compute_units_field(meta)
+        .or_else(|| {
+            let logs = meta.get("logMessages")?.as_array()?;
+            compute_units_from_logs(logs.iter().filter_map(Value::as_str))
+        })
+        .unwrap_or(0)
+}
+
+/// `Program <id> consumed <n> of <m> compute units` на глубине 1. Builtin-программы
+/// (System, Vote) не логируют consumed — это нижняя оценка.
+pub fn compute_units_from_logs<'a>(logs: impl IntoIterator<Item = &'a str>) -> Option<u64> {
+    let mut depth = 0usize;
+    let mut total = None;
+    for line in logs {
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+        if ["log:", "data:", "return:"]
+            .iter()
+            .any(|prefix| rest.starts_with(prefix))
+        {
+            continue;
+        }
+        let words: Vec<&str> = rest.split_whitespace().collect();
+        match words.as_slice() {
+            [_, "invoke", _] => depth += 1,
+            [_, "success"] => depth = depth.saturating_sub(1),
+            [_, "failed:", ..] => depth = depth.saturating_sub(1),
+            [_, "consumed", units, "of", _, "compute", "units"] if depth == 1 => {
+                if let Ok(units) = units.parse::<u64>() {
+                    *total.get_or_insert(0) += units;
+                }
+            }
+            _ => {}
+        }
+    }
+    total
+}
+
+/// Ошибка транзакции: `err`, у старых meta — `status.Err`.
+pub fn meta_error(meta: &Value) -> Option<&Value> {
+    meta.get("err")
+        .filter(|err| !err.is_null())
+        .or_else(|| meta.pointer("/status/Err"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_pre_v0_meta_layouts() {
+        // слот 2021 года: логи без computeUnitsConsumed и loadedAddresses
+        let logs_only = json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [10, 0],
+            "postBalances": [5, 0],
+            "logMessages": [
+                "Program 9W959DqEETiGZocYWCQPaJ6sBmUzgfxXfqGeTEdp3aQP invoke [1]",
+                "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+                "Program log: Instruction: Transfer",
+                "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA consumed 3121 of 180000 compute units",
+                "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+                "Program 9W959DqEETiGZocYWCQPaJ6sBmUzgfxXfqGeTEdp3aQP consumed 38000 of 200000 compute units",
+                "Program 9W959DqEETiGZocYWCQPaJ6sBmUzgfxXfqGeTEdp3aQP success",
+                "Program 11111111111111111111111111111111 invoke [1]",
+                "Program 11111111111111111111111111111111 success"
+            ]
+        });
+        assert_eq!(MetaVersion::detect(&logs_only), MetaVersion::LogsOnly);
+        assert_eq!(compute_units(&logs_only), 38_000);
+        assert!(meta_error(&logs_only).is_none());
+
+        // bigtable до 1.5: только status и балансы
+        let legacy = json!({
+            "status": { "Err": { "InstructionError": [0, { "Custom": 1 }] } },
+            "fee": 5000,
+            "preBalances": [10],
+            "postBalances": [5]
+        });
+        assert_eq!(MetaVersion::detect(&legacy), MetaVersion::Legacy);
+        assert_eq!(compute_units(&legacy), 0);
+        assert_eq!(
+            meta_error(&legacy),
+            Some(&json!({ "InstructionError": [0, { "Custom": 1 }] }))
+        );
+
+        let current = json!({ "err": null, "computeUnitsConsumed": 1234, "logMessages": [] });
+        assert_eq!(MetaVersion::detect(&current), MetaVersion::Current);
+        assert_eq!(compute_units(&current), 1234);
+    }
+}
@@ -1,10 +1,24 @@
+pub mod aggregate;
+pub mod anchor_events;
+pub mod arbitrage;
+pub mod compute_budget;
+pub mod confidence;
 pub mod constants;
 pub mod dex_parser;
 pub mod error;
+pub mod idl;
 pub mod instruction_classifier;
+pub mod invariants;
+pub mod meta_compat;
+pub mod owner_cache;
+pub mod rpc_json;
+#[cfg(test)]
+pub(crate) mod test_utils;
+pub mod tips;
 pub mod transaction_adapter;
 pub mod transaction_utils;
 pub mod utils;
+pub mod wsol;
 pub mod zero_copy;
 pub mod zc_adapter;
 pub mod zc_adapter_helpers;
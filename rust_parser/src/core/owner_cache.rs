@@ -0,0 +1,310 @@
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+use lru::LruCache;
+
+use crate::core::constants::TOKEN_PROGRAM_IDS;
+use crate::core::utils::get_instruction_data;
+use crate::types::{SolanaInstruction, SolanaTransaction, TokenBalance};
+
+/// Инструкции SPL Token / Token-2022, меняющие owner токен-аккаунта.
+const SET_AUTHORITY: u8 = 6;
+const CLOSE_ACCOUNT: u8 = 9;
+/// `AuthorityType::AccountOwner`.
+const ACCOUNT_OWNER: u8 = 2;
+/// Размер аккаунта SPL Token; у Token-2022 с расширениями за ним идёт `AccountType`.
+const TOKEN_ACCOUNT_LEN: usize = 165;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+const MULTISIG_LEN: usize = 355;
+/// Позиции токен-аккаунтов в инструкциях, двигающих баланс: transfer, mintTo,
+/// burn и их `Checked`-варианты.
+const TOKEN_ACCOUNT_POSITIONS: &[(u8, &[usize])] = &[
+    (3, &[0, 1]),
+    (7, &[1]),
+    (8, &[0]),
+    (12, &[0, 2]),
+    (14, &[1]),
+    (15, &[0]),
+];
+
+/// Владельцы токен-аккаунтов, накопленные между транзакциями.
+///
+/// Owner известен только для аккаунтов из pre/post token balances самой
+/// транзакции; кэш, общий для `DexParser`, запоминает их и подставляет в
+/// следующих транзакциях блока (или потока), где баланс аккаунта не менялся.
+///
+/// Owner меняется через `SetAuthority(AccountOwner)`, а закрытый аккаунт можно
+/// создать заново с другим owner'ом: `learn_from` применяет такие инструкции
+/// увиденных транзакций, но кэш видит только то, что через него разобрано.
+#[derive(Debug)]
+pub struct AccountOwnerCache {
+    owners: Mutex<LruCache<String, String>>,
+}
+
+impl Default for AccountOwnerCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_MAX_ENTRIES)
+    }
+}
+
+impl AccountOwnerCache {
+    pub const DEFAULT_MAX_ENTRIES: usize = 1_000_000;
+
+    /// При переполнении вытесняются давно не встречавшиеся аккаунты.
+    pub fn new(max_entries: usize) -> Self {
+        let capacity = NonZeroUsize::new(max_entries).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            owners: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    pub fn get(&self, account: &str) -> Option<String> {
+        self.owners.lock().ok()?.get(account).cloned()
+    }
+
+    pub fn insert(&self, account: String, owner: String) {
+        if let Ok(mut owners) = self.owners.lock() {
+            owners.put(account, owner);
+        }
+    }
+
+    pub fn remove(&self, account: &str) -> Option<String> {
+        self.owners.lock().ok()?.pop(account)
+    }
+
+    pub fn len(&self) -> usize {
+        self.owners.lock().map(|owners| owners.len()).unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Запоминает owner'ов из pre/post token balances транзакции. Между ними
+    /// применяются `SetAuthority(AccountOwner)` и `CloseAccount`: post balances —
+    /// итоговое состояние, в том числе аккаунта, пересозданного после закрытия.
+    pub fn learn_from(&self, tx: &SolanaTransaction) {
+        self.learn_from_balances(&tx.pre_token_balances);
+        let inner = tx
+            .inner_instructions
+            .iter()
+            .flat_map(|inner| &inner.instructions);
+        for instruction in tx.instructions.iter().chain(inner) {
+            self.apply_owner_change(instruction);
+        }
+        self.learn_from_balances(&tx.post_token_balances);
+    }
+
+    pub fn learn_from_balances(&self, balances: &[TokenBalance]) {
+        for balance in balances {
+            if let Some(owner) = &balance.owner {
+                self.insert(balance.account.clone(), owner.clone());
+            }
+        }
+    }
+
+    fn apply_owner_change(&self, instruction: &SolanaInstruction) {
+        if !TOKEN_PROGRAM_IDS.contains(&instruction.program_id.as_str()) {
+            return;
+        }
+        let Some(account) = instruction.accounts.first() else {
+            return;
+        };
+        let Ok(data) = get_instruction_data(instruction) else {
+            return;
+        };
+        match data.as_slice() {
+            [CLOSE_ACCOUNT, ..] => {
+                self.remove(account);
+            }
+            // SetAuthority: тип, COption<Pubkey> нового owner'а
+            [SET_AUTHORITY, ACCOUNT_OWNER, 1, owner @ ..] if owner.len() >= 32 => {
+                self.insert(
+                    account.to_string(),
+                    bs58::encode(&owner[..32]).into_string(),
+                );
+            }
+            [SET_AUTHORITY, ACCOUNT_OWNER, ..] => {
+                self.remove(account);
+            }
+            _ => {}
+        }
+    }
+
+    /// Аккаунты, которых нет в кэше, — кандидаты для `hydrate`.
+    pub fn missing<'a>(&self, accounts: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        let Ok(owners) = self.owners.lock() else {
+            return Vec::new();
+        };
+        accounts
+            .into_iter()
+            .filter(|account| !owners.contains(*account))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Токен-аккаунты из transfer/mintTo/burn инструкций транзакции, owner'а которых
+    /// нет ни в её token balances, ни в кэше, — что отдать в `hydrate`.
+    pub fn unknown_token_accounts(&self, tx: &SolanaTransaction) -> Vec<String> {
+        let known = |account: &str| {
+            tx.pre_token_balances
+                .iter()
+                .chain(&tx.post_token_balances)
+                .any(|balance| balance.account == account)
+        };
+        let inner = tx
+            .inner_instructions
+            .iter()
+            .flat_map(|inner| &inner.instructions);
+        let mut accounts: Vec<&str> = Vec::new();
+        for instruction in tx.instructions.iter().chain(inner) {
+            if !TOKEN_PROGRAM_IDS.contains(&instruction.program_id.as_str()) {
+                continue;
+            }
+            let Some(discriminator) = get_instruction_data(instruction)
+                .ok()
+                .and_then(|data| data.first().copied())
+            else {
+                continue;
+            };
+            let Some((_, positions)) = TOKEN_ACCOUNT_POSITIONS
+                .iter()
+                .find(|(instruction, _)| *instruction == discriminator)
+            else {
+                continue;
+            };
+            for position in *positions {
+                if let Some(account) = instruction.accounts.get(*position) {
+                    if !known(account) && !accounts.contains(&account.as_str()) {
+                        accounts.push(account);
+                    }
+                }
+            }
+        }
+        self.missing(accounts)
+    }
+
+    /// Дозагружает owner'ов через `getMultipleAccounts` (по 100 за запрос).
+    /// Аккаунты, не являющиеся токен-аккаунтами Token/Token-2022, пропускаются.
+    /// Возвращает число добавленных.
+    #[cfg(feature = "rpc")]
+    pub fn hydrate(
+        &self,
+        client: &solana_client::rpc_client::RpcClient,
+        accounts: &[String],
+    ) -> anyhow::Result<usize> {
+        use std::str::FromStr;
+
+        use solana_sdk::pubkey::Pubkey;
+
+        let mut added = 0;
+        for chunk in accounts.chunks(100) {
+            let keys: Vec<Pubkey> = chunk
+                .iter()
+                .filter_map(|account| Pubkey::from_str(account).ok())
+                .collect();
+            for (key, account) in keys.iter().zip(client.get_multiple_accounts(&keys)?) {
+                let Some(account) = account else {
+                    continue;
+                };
+                let program = account.owner.to_string();
+                if !TOKEN_PROGRAM_IDS.contains(&program.as_str())
+                    || !is_token_account(&account.data)
+                {
+                    continue;
+                }
+                // SPL token account: mint (32), owner (32), ...
+                let owner = bs58::encode(&account.data[32..64]).into_string();
+                self.insert(key.to_string(), owner);
+                added += 1;
+            }
+        }
+        Ok(added)
+    }
+}
+
+/// Данные токен-аккаунта, а не mint'а или multisig той же программы.
+#[cfg_attr(not(feature = "rpc"), allow(dead_code))]
+fn is_token_account(data: &[u8]) -> bool {
+    data.len() == TOKEN_ACCOUNT_LEN
+        || (data.len() != MULTISIG_LEN
+            && data.get(TOKEN_ACCOUNT_LEN) == Some(&ACCOUNT_TYPE_ACCOUNT))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use base64_simd::STANDARD;
+
+    fn token_instruction(data: &[u8]) -> SolanaInstruction {
+        SolanaInstruction {
+            program_id: TOKEN_PROGRAM_IDS[0].into(),
+            accounts: vec!["token-account".into(), "other".into()],
+            data: STANDARD.encode_to_string(data),
+        }
+    }
+
+    #[test]
+    fn evicts_least_recently_used_instead_of_clearing() {
+        let cache = AccountOwnerCache::new(2);
+        cache.insert("a".to_string(), "owner-a".to_string());
+        cache.insert("b".to_string(), "owner-b".to_string());
+        assert!(cache.get("a").is_some());
+        cache.insert("c".to_string(), "owner-c".to_string());
+
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("a").as_deref(), Some("owner-a"));
+    }
+
+    #[test]
+    fn set_authority_and_close_update_cached_owner() {
+        let cache = AccountOwnerCache::default();
+        cache.insert("token-account".to_string(), "old-owner".to_string());
+
+        let new_owner = [7u8; 32];
+        let mut set_owner = vec![SET_AUTHORITY, ACCOUNT_OWNER, 1];
+        set_owner.extend_from_slice(&new_owner);
+        cache.learn_from(&SolanaTransaction {
+            instructions: vec![token_instruction(&set_owner)],
+            ..Default::default()
+        });
+        let expected = bs58::encode(new_owner).into_string();
+        assert_eq!(cache.get("token-account"), Some(expected));
+
+        cache.learn_from(&SolanaTransaction {
+            instructions: vec![token_instruction(&[CLOSE_ACCOUNT])],
+            ..Default::default()
+        });
+        assert!(cache.get("token-account").is_none());
+    }
+
+    #[test]
+    fn recreated_account_keeps_owner_from_post_balances() {
+        use crate::types::TokenAmount;
+
+        let cache = AccountOwnerCache::default();
+        cache.insert("token-account".to_string(), "old-owner".to_string());
+        cache.learn_from(&SolanaTransaction {
+            instructions: vec![token_instruction(&[CLOSE_ACCOUNT])],
+            post_token_balances: vec![TokenBalance {
+                account: "token-account".to_string(),
+                mint: "MINT".to_string(),
+                owner: Some("new-owner".to_string()),
+                ui_token_amount: TokenAmount::new("0", 6, Some(0.0)),
+            }],
+            ..Default::default()
+        });
+        assert_eq!(cache.get("token-account").as_deref(), Some("new-owner"));
+    }
+
+    #[test]
+    fn only_token_account_layouts_are_hydrated() {
+        assert!(is_token_account(&[0; TOKEN_ACCOUNT_LEN]));
+        // mint Token-2022 с расширениями: AccountType::Mint
+        let mut mint = vec![0; TOKEN_ACCOUNT_LEN + 1];
+        mint[TOKEN_ACCOUNT_LEN] = 1;
+        assert!(!is_token_account(&mint));
+        assert!(!is_token_account(&[0; 82]));
+    }
+}
@@ -0,0 +1,556 @@
+//! Ответы `getTransaction` / `getBlock` (encoding `json`, `jsonParsed`, `base64`) напрямую
+//! из `serde_json::Value`, без solana-transaction-status: доступно и в сборке без feature
+//! `rpc` (wasm).
+
+use std::collections::HashMap;
+
+use base64_simd::STANDARD;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::core::error::ParserError;
+use crate::core::meta_compat;
+use crate::core::zero_copy::{convert_zc_to_solana_tx, parse_signatures, ZcTransaction};
+use crate::types::{
+    AccountKey, BalanceChange, BlockContext, InnerInstruction, KeyInterner, SolanaInstruction,
+    SolanaTransaction, TokenAmount, TokenBalance, TransactionMeta, TransactionStatus,
+};
+
+/// `value` — `result` ответа `getTransaction`: `{ slot, blockTime, transaction, meta }`.
+pub fn transaction_from_rpc_json(value: &Value) -> Result<SolanaTransaction, ParserError> {
+    let meta = value
+        .get("meta")
+        .filter(|meta| meta.is_object())
+        .ok_or_else(|| ParserError::MetaMalformed("transaction missing status meta".to_string()))?;
+    let tx = value
+        .get("transaction")
+        .ok_or_else(|| ParserError::Deserialize("missing `transaction` field".to_string()))?;
+    if let Some(encoded) = tx.as_array() {
+        return transaction_from_wire(value, encoded, meta);
+    }
+
+    let signature = tx
+        .pointer("/signatures/0")
+        .and_then(Value::as_str)
+        .ok_or_else(|| ParserError::Deserialize("transaction missing signature".to_string()))?
+        .to_string();
+    let message = tx
+        .get("message")
+        .ok_or_else(|| ParserError::Deserialize("transaction missing message".to_string()))?;
+
+    let (mut account_keys, signers) = extract_account_keys(message);
+    // jsonParsed уже включает адреса из lookup tables (`source: lookupTable`)
+    let parsed_keys = message.pointer("/accountKeys/0").is_some_and(Value::is_object);
+    for kind in ["writable", "readonly"] {
+        match meta.pointer(&format!("/loadedAddresses/{kind}")) {
+            Some(loaded) if !parsed_keys => account_keys.extend(str_array(loaded)),
+            _ => {}
+        }
+    }
+    // инструкции ссылаются на общие `Arc<str>` ключей, а не копируют строки
+    let mut interner = KeyInterner::new();
+    let keys: Vec<AccountKey> = account_keys.iter().map(|key| interner.intern(key)).collect();
+
+    let instructions = message
+        .get("instructions")
+        .and_then(Value::as_array)
+        .map(|items| items.iter().map(|ix| convert_instruction(ix, &keys, &mut interner)).collect())
+        .unwrap_or_default();
+    let inner_instructions = meta
+        .get("innerInstructions")
+        .and_then(Value::as_array)
+        .map(|sets| {
+            sets.iter()
+                .map(|set| InnerInstruction {
+                    index: set.get("index").and_then(Value::as_u64).unwrap_or(0) as usize,
+                    instructions: set
+                        .get("instructions")
+                        .and_then(Value::as_array)
+                        .map(|items| {
+                            items.iter().map(|ix| convert_instruction(ix, &keys, &mut interner)).collect()
+                        })
+                        .unwrap_or_default(),
+                    stack_heights: set
+                        .get("instructions")
+                        .and_then(Value::as_array)
+                        .map(|items| items.iter().map(json_stack_height).collect())
+                        .unwrap_or_default(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let err = meta_compat::meta_error(meta);
+    Ok(SolanaTransaction {
+        slot: value.get("slot").and_then(Value::as_u64).unwrap_or(0),
+        signature,
+        block_time: value.get("blockTime").and_then(Value::as_i64).unwrap_or(0) as u64,
+        signers,
+        instructions,
+        inner_instructions,
+        transfers: Vec::new(),
+        pre_token_balances: convert_token_balances(meta.get("preTokenBalances"), &account_keys),
+        post_token_balances: convert_token_balances(meta.get("postTokenBalances"), &account_keys),
+        meta: TransactionMeta {
+            fee: meta.get("fee").and_then(Value::as_u64).unwrap_or(0),
+            compute_units: meta_compat::compute_units(meta),
+            status: if err.is_some() {
+                TransactionStatus::Failed
+            } else {
+                TransactionStatus::Success
+            },
+            sol_balance_changes: collect_sol_balance_changes(meta, &account_keys),
+            token_balance_changes: HashMap::new(),
+            err: err.map(Value::to_string),
+            log_messages: log_messages(meta),
+        },
+    })
+}
+
+/// `["<data>", "base64" | "base58"]`: сообщение разбирает zero-copy парсер,
+/// meta и loadedAddresses — те же поля JSON.
+fn transaction_from_wire(
+    value: &Value,
+    encoded: &[Value],
+    meta: &Value,
+) -> Result<SolanaTransaction, ParserError> {
+    let raw = match encoded {
+        [Value::String(data), Value::String(encoding)] if encoding == "base64" => STANDARD
+            .decode_to_vec(data)
+            .map_err(|err| ParserError::Deserialize(format!("invalid base64 transaction: {err}")))?,
+        [Value::String(data), Value::String(encoding)] if encoding == "base58" => {
+            bs58::decode(data).into_vec().map_err(|_| ParserError::Base58Decode {
+                value: data.clone(),
+            })?
+        }
+        _ => {
+            return Err(ParserError::Deserialize(
+                "unsupported transaction encoding, expected `base64` or `base58`".to_string(),
+            ))
+        }
+    };
+    let slot = value.get("slot").and_then(Value::as_u64).unwrap_or(0);
+    let block_time = value.get("blockTime").and_then(Value::as_i64).unwrap_or(0) as u64;
+    transaction_from_raw(&raw, slot, block_time, meta)
+}
+
+/// Wire-формат транзакции + meta в JSON-форме RPC.
+pub(crate) fn transaction_from_raw(
+    raw: &[u8],
+    slot: u64,
+    block_time: u64,
+    meta: &Value,
+) -> Result<SolanaTransaction, ParserError> {
+    let (num_signatures, message_start) = parse_signatures(raw)?;
+    if num_signatures == 0 {
+        return Err(ParserError::Deserialize("transaction missing signature".to_string()));
+    }
+    let first = message_start - num_signatures * 64;
+    let signature = bs58::encode(&raw[first..first + 64]).into_string();
+    let zc_tx = ZcTransaction::parse(raw, slot, &signature, block_time, Some(meta))?;
+    Ok(convert_zc_to_solana_tx(&zc_tx, Some(meta))?)
+}
+
+/// `result` ответа `getBlock` с `transactionDetails: full` в любом encoding: контекст
+/// блока и транзакции с его slot/blockTime. В ответе RPC нет номера слота — `slot`
+/// его задаёт, иначе берётся `slot`/`parentSlot + 1` из JSON. Транзакции без meta
+/// (`null` у старых блоков) пропускаются.
+pub fn block_from_rpc_json(
+    block: &Value,
+    slot: Option<u64>,
+) -> Result<(BlockContext, Vec<SolanaTransaction>), ParserError> {
+    let items = block
+        .get("transactions")
+        .and_then(Value::as_array)
+        .ok_or_else(|| ParserError::Deserialize("block has no transactions array".to_string()))?;
+    let mut context = BlockContext::from_block_json(block);
+    if let Some(slot) = slot {
+        context.slot = slot;
+    }
+
+    let mut transactions = Vec::with_capacity(items.len());
+    for item in items {
+        if item.get("meta").is_none_or(Value::is_null) {
+            continue;
+        }
+        let mut tx = transaction_from_rpc_json(item)?;
+        tx.slot = context.slot;
+        tx.block_time = context.block_time.unwrap_or(0);
+        transactions.push(tx);
+    }
+    Ok((context, transactions))
+}
+
+/// Транзакция в любом из поддерживаемых видов: `result` ответа `getTransaction`
+/// ([`transaction_from_rpc_json`]) или сериализованный `SolanaTransaction` (ключи
+/// которого проходят через [`KeyInterner::scope`]).
+pub fn transaction_from_json(value: &Value) -> Result<SolanaTransaction, ParserError> {
+    if value.get("transaction").is_none() {
+        return KeyInterner::scope(|| SolanaTransaction::deserialize(value))
+            .map_err(|err| ParserError::Deserialize(format!("not a SolanaTransaction: {err}")));
+    }
+    transaction_from_rpc_json(value)
+}
+
+/// Транзакции блока: `result` ответа `getBlock` (любой encoding) или JSON-массив
+/// транзакций в видах [`transaction_from_json`]. Транзакции без meta и те, что не
+/// разобрались, пропускаются — одна битая транзакция не валит весь блок.
+pub fn block_transactions_from_json(block: &Value) -> Result<Vec<SolanaTransaction>, ParserError> {
+    if let Some(items) = block.as_array() {
+        return items.iter().map(transaction_from_json).collect();
+    }
+    let items = block.get("transactions").and_then(Value::as_array).ok_or_else(|| {
+        ParserError::Deserialize(
+            "expected getBlock result with `transactions` or an array of transactions".to_string(),
+        )
+    })?;
+    let context = BlockContext::from_block_json(block);
+
+    let mut transactions = Vec::with_capacity(items.len());
+    for item in items {
+        if item.get("meta").is_none_or(Value::is_null) {
+            continue;
+        }
+        match transaction_from_rpc_json(item) {
+            Ok(mut tx) => {
+                tx.slot = context.slot;
+                tx.block_time = context.block_time.unwrap_or(0);
+                transactions.push(tx);
+            }
+            Err(err) => tracing::debug!("skipping block transaction: {err}"),
+        }
+    }
+    Ok(transactions)
+}
+
+/// Блок (`getBlock` result или массив транзакций), а не одна транзакция.
+pub fn is_block_json(value: &Value) -> bool {
+    value.is_array() || value.get("transactions").is_some_and(Value::is_array)
+}
+
+/// `json`: ключи строками, подписанты — первые `numRequiredSignatures`;
+/// `jsonParsed`: объекты `{ pubkey, signer }`.
+fn extract_account_keys(message: &Value) -> (Vec<String>, Vec<String>) {
+    let keys = message
+        .get("accountKeys")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    let mut account_keys = Vec::with_capacity(keys.len());
+    let mut signers = Vec::new();
+    let required = message
+        .pointer("/header/numRequiredSignatures")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as usize;
+
+    for (idx, key) in keys.iter().enumerate() {
+        match key {
+            Value::String(pubkey) => {
+                if idx < required {
+                    signers.push(pubkey.clone());
+                }
+                account_keys.push(pubkey.clone());
+            }
+            Value::Object(account) => {
+                let pubkey = account
+                    .get("pubkey")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string();
+                if account.get("signer").and_then(Value::as_bool).unwrap_or(false) {
+                    signers.push(pubkey.clone());
+                }
+                account_keys.push(pubkey);
+            }
+            _ => account_keys.push(String::new()),
+        }
+    }
+    (account_keys, signers)
+}
+
+pub(crate) fn json_stack_height(ix: &Value) -> Option<u32> {
+    ix.get("stackHeight")
+        .and_then(Value::as_u64)
+        .and_then(|height| u32::try_from(height).ok())
+}
+
+fn convert_instruction(
+    instruction: &Value,
+    keys: &[AccountKey],
+    interner: &mut KeyInterner,
+) -> SolanaInstruction {
+    let data = instruction.get("data").and_then(Value::as_str).unwrap_or_default();
+
+    // compiled: индексы в account_keys
+    if let Some(program_idx) = instruction.get("programIdIndex").and_then(Value::as_u64) {
+        return SolanaInstruction {
+            program_id: keys.get(program_idx as usize).cloned().unwrap_or_default(),
+            accounts: instruction
+                .get("accounts")
+                .and_then(Value::as_array)
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(Value::as_u64)
+                        .filter_map(|idx| keys.get(idx as usize).cloned())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            data: bs58_to_base64(data),
+        };
+    }
+
+    let program_id =
+        interner.intern(instruction.get("programId").and_then(Value::as_str).unwrap_or_default());
+    match instruction.get("parsed") {
+        // jsonParsed для известных программ: data уже разобрана RPC
+        Some(parsed) => SolanaInstruction {
+            program_id,
+            accounts: Vec::new(),
+            data: parsed.to_string(),
+        },
+        None => SolanaInstruction {
+            program_id,
+            accounts: instruction
+                .get("accounts")
+                .and_then(Value::as_array)
+                .map(|items| {
+                    items.iter().filter_map(Value::as_str).map(|key| interner.intern(key)).collect()
+                })
+                .unwrap_or_default(),
+            data: bs58_to_base64(data),
+        },
+    }
+}
+
+fn convert_token_balances(balances: Option<&Value>, account_keys: &[String]) -> Vec<TokenBalance> {
+    let Some(items) = balances.and_then(Value::as_array) else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|balance| {
+            let index = balance.get("accountIndex").and_then(Value::as_u64)?;
+            let account = account_keys.get(index as usize)?.clone();
+            let ui_amount = balance.get("uiTokenAmount")?;
+            Some(TokenBalance {
+                account,
+                mint: balance.get("mint").and_then(Value::as_str)?.to_string(),
+                owner: balance.get("owner").and_then(Value::as_str).map(str::to_string),
+                ui_token_amount: TokenAmount {
+                    amount: ui_amount
+                        .get("amount")
+                        .and_then(Value::as_str)
+                        .unwrap_or("0")
+                        .to_string(),
+                    ui_amount: ui_amount.get("uiAmount").and_then(Value::as_f64),
+                    decimals: ui_amount.get("decimals").and_then(Value::as_u64).unwrap_or(0) as u8,
+                    ui_amount_string: ui_amount
+                        .get("uiAmountString")
+                        .and_then(Value::as_str)
+                        .map(str::to_string),
+                },
+            })
+        })
+        .collect()
+}
+
+fn collect_sol_balance_changes(
+    meta: &Value,
+    account_keys: &[String],
+) -> HashMap<String, BalanceChange> {
+    let balances = |field: &str| -> Vec<u64> {
+        meta.get(field)
+            .and_then(Value::as_array)
+            .map(|items| items.iter().map(|v| v.as_u64().unwrap_or(0)).collect())
+            .unwrap_or_default()
+    };
+    let (pre_balances, post_balances) = (balances("preBalances"), balances("postBalances"));
+
+    let mut changes = HashMap::new();
+    for (idx, key) in account_keys.iter().enumerate() {
+        if let (Some(&pre), Some(&post)) = (pre_balances.get(idx), post_balances.get(idx)) {
+            if pre != post {
+                changes.insert(
+                    key.clone(),
+                    BalanceChange {
+                        pre: pre as i128,
+                        post: post as i128,
+                        change: post as i128 - pre as i128,
+                    },
+                );
+            }
+        }
+    }
+    changes
+}
+
+/// `meta.logMessages`; `null`, если RPC запущен без логов.
+pub fn log_messages(meta: &Value) -> Vec<String> {
+    meta.get("logMessages").map(str_array).unwrap_or_default()
+}
+
+fn str_array(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|items| items.iter().filter_map(Value::as_str).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// JSON encoding отдаёт data инструкций в bs58, а парсер ожидает base64
+pub(crate) fn bs58_to_base64(data: &str) -> String {
+    match bs58::decode(data).into_vec() {
+        Ok(bytes) => STANDARD.encode_to_string(&bytes),
+        Err(_) => data.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn converts_json_encoded_transaction() {
+        let value = json!({
+            "slot": 42,
+            "blockTime": 1_700_000_000,
+            "transaction": {
+                "signatures": ["sig"],
+                "message": {
+                    "header": { "numRequiredSignatures": 1 },
+                    "accountKeys": ["user", "program", "pool"],
+                    "instructions": [
+                        { "programIdIndex": 1, "accounts": [0, 2], "data": "3Bxs4" }
+                    ]
+                }
+            },
+            "meta": {
+                "err": null,
+                "fee": 5000,
+                "preBalances": [1_000_000, 1, 0],
+                "postBalances": [994_000, 1, 1_000],
+                "innerInstructions": [
+                    { "index": 0, "instructions": [
+                        { "programIdIndex": 3, "accounts": [2], "data": "" }
+                    ] }
+                ],
+                "preTokenBalances": [],
+                "postTokenBalances": [
+                    { "accountIndex": 2, "mint": "mint", "owner": "user",
+                      "uiTokenAmount": { "amount": "10", "decimals": 1, "uiAmount": 1.0 } }
+                ],
+                "loadedAddresses": { "writable": ["alt-account"], "readonly": [] },
+                "computeUnitsConsumed": 1234
+            }
+        });
+
+        let tx = transaction_from_rpc_json(&value).expect("converted");
+        assert_eq!(tx.slot, 42);
+        assert_eq!(tx.signature, "sig");
+        assert_eq!(tx.signers, vec!["user".to_string()]);
+        assert_eq!(tx.instructions[0].program_id, "program");
+        assert_eq!(tx.instructions[0].accounts, vec!["user", "pool"]);
+        let expected_data = STANDARD.encode_to_string(bs58::decode("3Bxs4").into_vec().unwrap());
+        assert_eq!(tx.instructions[0].data, expected_data);
+        // индекс 3 указывает на адрес из lookup table
+        assert_eq!(tx.inner_instructions[0].instructions[0].program_id, "alt-account");
+        assert_eq!(tx.post_token_balances[0].account, "pool");
+        assert_eq!(tx.meta.compute_units, 1234);
+        assert_eq!(tx.meta.status, TransactionStatus::Success);
+        assert_eq!(tx.meta.sol_balance_changes["user"].change, -6_000);
+    }
+
+    #[test]
+    fn converts_get_block_with_base64_transactions() {
+        let (user, program) = ([1u8; 32], [2u8; 32]);
+        // legacy сообщение: 1 подпись, ключи [user, program], одна инструкция
+        let mut raw = vec![1];
+        raw.extend([7u8; 64]);
+        raw.extend([1, 0, 1, 2]);
+        raw.extend(user);
+        raw.extend(program);
+        raw.extend([0u8; 32]);
+        raw.extend([1, 1, 1, 0, 2, 0xAA, 0xBB]);
+        let meta = json!({
+            "err": null,
+            "fee": 5000,
+            "preBalances": [1_000_000, 1],
+            "postBalances": [995_000, 1],
+            "innerInstructions": [],
+            "preTokenBalances": [],
+            "postTokenBalances": []
+        });
+        let block = json!({
+            "blockhash": "hash",
+            "blockTime": 1_700_000_000,
+            "parentSlot": 99,
+            "transactions": [
+                { "transaction": [STANDARD.encode_to_string(&raw), "base64"], "meta": meta },
+                { "transaction": [STANDARD.encode_to_string(&raw), "base64"], "meta": null }
+            ]
+        });
+
+        let (context, txs) = block_from_rpc_json(&block, None).expect("converted");
+        assert_eq!(context.slot, 100);
+        assert_eq!(context.blockhash.as_deref(), Some("hash"));
+        assert_eq!(txs.len(), 1);
+        let tx = &txs[0];
+        assert_eq!((tx.slot, tx.block_time), (100, 1_700_000_000));
+        assert_eq!(tx.signature, bs58::encode([7u8; 64]).into_string());
+        assert_eq!(tx.signers, vec![bs58::encode(user).into_string()]);
+        assert_eq!(tx.instructions[0].program_id, bs58::encode(program).into_string());
+        assert_eq!(tx.instructions[0].data, STANDARD.encode_to_string([0xAA, 0xBB]));
+        assert_eq!(tx.meta.fee, 5000);
+
+        let (context, _) = block_from_rpc_json(&block, Some(105)).expect("converted");
+        assert_eq!(context.slot, 105);
+    }
+
+    #[test]
+    fn backfills_compute_units_and_status_from_pre_v0_meta() {
+        let mut raw = vec![1];
+        raw.extend([7u8; 64]);
+        raw.extend([1, 0, 1, 2]);
+        raw.extend([1u8; 32]);
+        raw.extend([2u8; 32]);
+        raw.extend([0u8; 32]);
+        raw.extend([1, 1, 1, 0, 0]);
+        // bigtable-слот 2021 года: без computeUnitsConsumed, ошибка только в `status`
+        let meta = json!({
+            "status": { "Err": { "InstructionError": [0, { "Custom": 6 }] } },
+            "fee": 5000,
+            "preBalances": [1_000_000, 1],
+            "postBalances": [995_000, 1],
+            "logMessages": [
+                "Program 22222222222222222222222222222222 invoke [1]",
+                "Program 22222222222222222222222222222222 consumed 52000 of 200000 compute units",
+                "Program 22222222222222222222222222222222 failed: custom program error: 0x6"
+            ]
+        });
+        let json_tx = json!({
+            "slot": 90_000_000,
+            "transaction": {
+                "signatures": ["sig"],
+                "message": {
+                    "header": { "numRequiredSignatures": 1 },
+                    "accountKeys": ["user", "program"],
+                    "instructions": [{ "programIdIndex": 1, "accounts": [0], "data": "" }]
+                }
+            },
+            "meta": meta.clone()
+        });
+        let wire_tx = json!({
+            "slot": 90_000_000,
+            "transaction": [STANDARD.encode_to_string(&raw), "base64"],
+            "meta": meta
+        });
+
+        for value in [json_tx, wire_tx] {
+            let tx = transaction_from_rpc_json(&value).expect("converted");
+            assert_eq!(tx.meta.compute_units, 52_000);
+            assert_eq!(tx.meta.status, TransactionStatus::Failed);
+            assert!(tx.meta.err.as_deref().is_some_and(|err| err.contains("Custom")));
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Транзакции для unit-тестов парсеров.
+
+use std::collections::HashMap;
+
+use crate::core::constants::dex_programs;
+use crate::types::{
+    BalanceChange, SolanaInstruction, SolanaTransaction, TokenAmount, TransactionMeta,
+    TransactionStatus, TransferData,
+};
+
+/// Свап 1 BASE → 2 QUOTE через Jupiter: две ноги в `transfers` (`0-0`, `0-1`) и
+/// изменения балансов signer'а `user`. Тесты протоколов подменяют программу и инструкции.
+pub(crate) fn sample_transaction() -> SolanaTransaction {
+    let mut sol_changes = HashMap::new();
+    sol_changes.insert(
+        "user".to_string(),
+        BalanceChange {
+            pre: 10_000_000,
+            post: 9_995_000,
+            change: -5_000,
+        },
+    );
+
+    let mut token_changes = HashMap::new();
+    let mut signer_tokens = HashMap::new();
+    signer_tokens.insert(
+        "BASE".to_string(),
+        BalanceChange {
+            pre: 0,
+            post: -1_000_000,
+            change: -1_000_000,
+        },
+    );
+    signer_tokens.insert(
+        "QUOTE".to_string(),
+        BalanceChange {
+            pre: 0,
+            post: 2_000_000,
+            change: 2_000_000,
+        },
+    );
+    token_changes.insert("user".to_string(), signer_tokens);
+
+    SolanaTransaction {
+        slot: 1,
+        signature: "sample-signature".to_string(),
+        block_time: 1_234_567,
+        signers: vec!["user".to_string()],
+        instructions: vec![SolanaInstruction {
+            program_id: dex_programs::JUPITER.into(),
+            accounts: vec!["BASE".into(), "QUOTE".into()],
+            data: "swap".to_string(),
+        }],
+        inner_instructions: Vec::new(),
+        transfers: vec![
+            TransferData {
+                transfer_type: "transfer".to_string(),
+                program_id: dex_programs::JUPITER.into(),
+                info: crate::types::TransferInfo {
+                    authority: Some("user".into()),
+                    destination: "pool".into(),
+                    destination_owner: Some("pool-owner".into()),
+                    mint: "BASE".into(),
+                    source: "user-token".into(),
+                    token_amount: TokenAmount::new("1000000", 6, Some(1.0)),
+                    source_balance: None,
+                    source_pre_balance: None,
+                    destination_balance: None,
+                    destination_pre_balance: None,
+                    sol_balance_change: None,
+                },
+                idx: "0-0".to_string(),
+                timestamp: 1_234_567,
+                signature: "sample-signature".to_string(),
+                is_fee: false,
+                fee_amount: None,
+                instruction_index: 0,
+                inner_index: None,
+                order_key: None,
+            },
+            TransferData {
+                transfer_type: "transfer".to_string(),
+                program_id: dex_programs::JUPITER.into(),
+                info: crate::types::TransferInfo {
+                    authority: Some("pool".into()),
+                    destination: "user".into(),
+                    destination_owner: Some("user".into()),
+                    mint: "QUOTE".into(),
+                    source: "pool-token".into(),
+                    token_amount: TokenAmount::new("2000000", 6, Some(2.0)),
+                    source_balance: None,
+                    source_pre_balance: None,
+                    destination_balance: None,
+                    destination_pre_balance: None,
+                    sol_balance_change: None,
+                },
+                idx: "0-1".to_string(),
+                timestamp: 1_234_567,
+                signature: "sample-signature".to_string(),
+                is_fee: false,
+                fee_amount: None,
+                instruction_index: 0,
+                inner_index: None,
+                order_key: None,
+            },
+        ],
+        pre_token_balances: Vec::new(),
+        post_token_balances: Vec::new(),
+        meta: TransactionMeta {
+            fee: 5_000,
+            compute_units: 200_000,
+            status: TransactionStatus::Success,
+            sol_balance_changes: sol_changes,
+            token_balance_changes: token_changes,
+            err: None,
+            log_messages: Vec::new(),
+        },
+    }
+}
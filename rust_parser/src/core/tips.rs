@@ -0,0 +1,100 @@
+use crate::config::ParseConfig;
+use crate::core::constants::TOKENS;
+use crate::protocols::pumpfun::util::convert_to_ui_amount;
+use crate::types::{TokenAmount, TransferData};
+
+fn is_tip(transfer: &TransferData, config: &ParseConfig) -> bool {
+    transfer.info.mint == TOKENS.SOL && config.is_tip_account(&transfer.info.destination)
+}
+
+/// Сумма SOL-переводов на tip-аккаунты (`ParseConfig::is_tip_account`).
+pub fn jito_tip<'a>(
+    transfers: impl IntoIterator<Item = &'a TransferData>,
+    config: &ParseConfig,
+) -> Option<TokenAmount> {
+    let lamports: Option<u64> = transfers
+        .into_iter()
+        .filter(|transfer| is_tip(transfer, config))
+        .map(|transfer| {
+            transfer
+                .info
+                .token_amount
+                .amount
+                .parse::<u64>()
+                .unwrap_or(0)
+        })
+        .reduce(u64::saturating_add);
+    lamports.map(|lamports| {
+        TokenAmount::new(
+            lamports.to_string(),
+            9,
+            Some(convert_to_ui_amount(lamports, 9)),
+        )
+    })
+}
+
+/// Помечает переводы на tip-аккаунты как `is_fee`.
+pub fn mark_tip_transfers(transfers: &mut [TransferData], config: &ParseConfig) {
+    for transfer in transfers {
+        if is_tip(transfer, config) {
+            transfer.is_fee = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::constants::JITO_TIP_ACCOUNTS;
+    use crate::types::TransferInfo;
+
+    fn sol_transfer(destination: &str, lamports: u64) -> TransferData {
+        TransferData {
+            transfer_type: "transfer".to_string(),
+            program_id: "11111111111111111111111111111111".into(),
+            info: TransferInfo {
+                authority: None,
+                destination: destination.into(),
+                destination_owner: None,
+                mint: TOKENS.SOL.into(),
+                source: "signer".into(),
+                token_amount: TokenAmount::new(lamports.to_string(), 9, None),
+                source_balance: None,
+                source_pre_balance: None,
+                destination_balance: None,
+                destination_pre_balance: None,
+                sol_balance_change: None,
+            },
+            idx: "0".to_string(),
+            timestamp: 0,
+            signature: String::new(),
+            is_fee: false,
+            fee_amount: None,
+            instruction_index: 0,
+            inner_index: None,
+            order_key: None,
+        }
+    }
+
+    #[test]
+    fn sums_tips_including_configured_accounts() {
+        let config = ParseConfig {
+            tip_accounts: Some(["my-relayer".to_string()].into()),
+            ..Default::default()
+        };
+        let mut transfers = vec![
+            sol_transfer(JITO_TIP_ACCOUNTS[0], 10_000),
+            sol_transfer("my-relayer", 5_000),
+            sol_transfer("pool", 1_000_000),
+        ];
+        let tip = jito_tip(&transfers, &config).unwrap();
+        assert_eq!(tip.amount, "15000");
+
+        mark_tip_transfers(&mut transfers, &config);
+        assert_eq!(
+            transfers.iter().map(|t| t.is_fee).collect::<Vec<_>>(),
+            [true, true, false]
+        );
+        assert!(jito_tip(&transfers[2..], &ParseConfig::default()).is_none());
+    }
+}
@@ -1,9 +1,16 @@
 use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use rustc_hash::FxHashMap;
 
 use crate::config::ParseConfig;
 use crate::core::constants::TOKENS;
+use crate::core::error::ParserError;
+use crate::core::owner_cache::AccountOwnerCache;
+use crate::state::ProtocolAccountStore;
+use crate::state_decoders::ProtocolAccount;
 use crate::types::{
-    BalanceChange, InnerInstruction, SolanaInstruction, SolanaTransaction, TokenAmount, TokenBalance, TokenInfo,
+    AccountKey, BalanceChange, InnerInstruction, SolanaInstruction, SolanaTransaction, TokenAmount, TokenBalance, TokenInfo,
     PoolEventBase, PoolEventType, TransactionStatus, TransferData, TransferMap,
 };
 
@@ -17,31 +24,135 @@ use crate::types::{
 ///                       transfers: Vec<TransferData> }
 #[derive(Clone, Debug)]
 pub struct TransactionAdapter {
-    tx: SolanaTransaction,
+    // общий с вызывающим кодом: адаптер строится без копии транзакции
+    tx: Arc<SolanaTransaction>,
     config: ParseConfig,
 
-    // Собранные ключи аккаунтов (uniq)
-    account_keys: Vec<String>,
+    // Собранные ключи аккаунтов (uniq); общие `Arc<str>` с инструкциями
+    account_keys: Vec<AccountKey>,
+    // Индексы, построенные один раз в `new`: адрес -> позиция в `account_keys`,
+    // токен-аккаунт -> позиция в pre/post token balances
+    account_index: FxHashMap<AccountKey, usize>,
+    pre_token_index: FxHashMap<AccountKey, usize>,
+    post_token_index: FxHashMap<AccountKey, usize>,
 
     // Карты как в TS: токен-аккаунт -> инфо, и mint -> decimals
     spl_token_map: HashMap<String, TokenInfo>,
     spl_decimals_map: HashMap<String, u8>,
+
+    // Owner'ы из `AccountOwnerCache` для аккаунтов без token balances в этой транзакции
+    cached_owners: HashMap<String, String>,
+    // Аккаунты пулов, подключённые через `DexParser::with_protocol_accounts`
+    protocol_accounts: Option<Arc<ProtocolAccountStore>>,
+    // Ошибки протокольных парсеров, не прерывающие разбор (см. `report_error`)
+    errors: Arc<Mutex<Vec<ParserError>>>,
 }
 
 impl TransactionAdapter {
-    pub fn new(tx: SolanaTransaction, config: ParseConfig) -> Self {
+    /// Принимает `SolanaTransaction` или `Arc<SolanaTransaction>`; общая транзакция
+    /// копируется только если глубины inner-инструкций приходится восстанавливать из логов.
+    pub fn new(tx: impl Into<Arc<SolanaTransaction>>, config: ParseConfig) -> Self {
+        let mut tx = tx.into();
+        if Self::needs_stack_heights(&tx) {
+            Self::fill_stack_heights_from_logs(Arc::make_mut(&mut tx));
+        }
         let account_keys = Self::extract_account_keys(&tx);
         let (spl_token_map, spl_decimals_map) = Self::extract_token_maps(&tx);
+        let account_index: FxHashMap<AccountKey, usize> = account_keys
+            .iter()
+            .enumerate()
+            .map(|(index, key)| (key.clone(), index))
+            .collect();
+        let pre_token_index = Self::token_balance_index(&tx.pre_token_balances, &account_index);
+        let post_token_index = Self::token_balance_index(&tx.post_token_balances, &account_index);
 
         Self {
             tx,
             config,
             account_keys,
+            account_index,
+            pre_token_index,
+            post_token_index,
             spl_token_map,
             spl_decimals_map,
+            cached_owners: HashMap::new(),
+            protocol_accounts: None,
+            errors: Arc::default(),
+        }
+    }
+
+    fn needs_stack_heights(tx: &SolanaTransaction) -> bool {
+        !tx.inner_instructions.is_empty()
+            && !tx.meta.log_messages.is_empty()
+            && tx.inner_instructions.iter().all(|set| set.stack_heights.is_empty())
+    }
+
+    /// Восстанавливает `stack_heights` inner-инструкций по `invoke [N]` в логах, если
+    /// источник их не отдал. Вызов depth 1 сопоставляется со следующей outer-инструкцией
+    /// той же программы, глубже — с очередной inner-инструкцией; при расхождении
+    /// program_id (обрезанные логи) остаток группы остаётся без глубины.
+    fn fill_stack_heights_from_logs(tx: &mut SolanaTransaction) {
+        let invocations = crate::core::anchor_events::invocations(&tx.meta.log_messages);
+        for set in &mut tx.inner_instructions {
+            set.stack_heights = vec![None; set.instructions.len()];
+        }
+
+        let mut next_outer = 0;
+        // (группа inner-инструкций текущей outer, позиция в ней)
+        let mut current: Option<(usize, usize)> = None;
+        for (program_id, stack_height) in invocations {
+            if stack_height <= 1 {
+                let Some(outer) = (next_outer..tx.instructions.len())
+                    .find(|&index| tx.instructions[index].program_id == program_id)
+                else {
+                    break;
+                };
+                next_outer = outer + 1;
+                current = tx
+                    .inner_instructions
+                    .iter()
+                    .position(|set| set.index == outer)
+                    .map(|set| (set, 0));
+                continue;
+            }
+            let Some((set_index, position)) = current else {
+                continue;
+            };
+            let set = &mut tx.inner_instructions[set_index];
+            match set.instructions.get(position) {
+                Some(ix) if ix.program_id == program_id => {
+                    set.stack_heights[position] = Some(stack_height);
+                    current = Some((set_index, position + 1));
+                }
+                _ => current = None,
+            }
         }
     }
 
+    /// Подставляет owner'ов из кэша для аккаунтов транзакции, которых нет в её balances.
+    pub fn with_owner_cache(mut self, cache: &AccountOwnerCache) -> Self {
+        for key in &self.account_keys {
+            if self.pre_token_index.contains_key(key) || self.post_token_index.contains_key(key) {
+                continue;
+            }
+            if let Some(owner) = cache.get(key) {
+                self.cached_owners.insert(key.to_string(), owner);
+            }
+        }
+        self
+    }
+
+    /// Разобранные аккаунты пулов для параметров, которых нет в транзакции.
+    pub fn with_protocol_accounts(mut self, store: Arc<ProtocolAccountStore>) -> Self {
+        self.protocol_accounts = Some(store);
+        self
+    }
+
+    /// Аккаунт состояния протокола по адресу, если он есть в подключённом хранилище.
+    pub fn protocol_account(&self, address: &str) -> Option<ProtocolAccount> {
+        self.protocol_accounts.as_ref()?.get(address)
+    }
+
     /* ----------------------- базовая информация ----------------------- */
 
     pub fn slot(&self) -> u64 {
@@ -52,6 +163,11 @@ impl TransactionAdapter {
         self.tx.block_time
     }
 
+    /// Исходная транзакция (с восстановленными `stack_heights`).
+    pub fn transaction(&self) -> &Arc<SolanaTransaction> {
+        &self.tx
+    }
+
     pub fn signature(&self) -> &str {
         &self.tx.signature
     }
@@ -79,6 +195,11 @@ impl TransactionAdapter {
         &self.tx.inner_instructions
     }
 
+    /// `meta.logMessages`; пусто, если источник их не передал.
+    pub fn log_messages(&self) -> &[String] {
+        &self.tx.meta.log_messages
+    }
+
     pub fn config(&self) -> &ParseConfig {
         &self.config
     }
@@ -96,32 +217,35 @@ impl TransactionAdapter {
         self.tx.meta.status
     }
 
+    pub fn tx_error(&self) -> Option<&str> {
+        self.tx.meta.err.as_deref()
+    }
+
     /* ----------------------- account keys ----------------------- */
 
     /// Собираем уникальные адреса только из instructions/inner_instructions + signers
-    fn extract_account_keys(tx: &SolanaTransaction) -> Vec<String> {
+    fn extract_account_keys(tx: &SolanaTransaction) -> Vec<AccountKey> {
         // Pre-allocate with estimated capacity
         let estimated_capacity = tx.signers.len() 
             + tx.instructions.len() * 3  // program_id + ~2 accounts per instruction
             + tx.inner_instructions.iter().map(|i| i.instructions.len() * 3).sum::<usize>();
-        let mut set: HashSet<String> = HashSet::with_capacity(estimated_capacity);
+        let mut set: HashSet<AccountKey> = HashSet::with_capacity(estimated_capacity);
         for s in &tx.signers {
-            set.insert(s.clone());
+            if !set.contains(s.as_str()) {
+                set.insert(s.into());
+            }
         }
 
+        // клон AccountKey — инкремент счётчика, без аллокации строки
         for ix in &tx.instructions {
             set.insert(ix.program_id.clone());
-            for a in &ix.accounts {
-                set.insert(a.clone());
-            }
+            set.extend(ix.accounts.iter().cloned());
         }
 
         for set_inner in &tx.inner_instructions {
             for ix in &set_inner.instructions {
                 set.insert(ix.program_id.clone());
-                for a in &ix.accounts {
-                    set.insert(a.clone());
-                }
+                set.extend(ix.accounts.iter().cloned());
             }
         }
 
@@ -135,7 +259,7 @@ impl TransactionAdapter {
         out
     }
 
-    pub fn account_keys(&self) -> &[String] {
+    pub fn account_keys(&self) -> &[AccountKey] {
         &self.account_keys
     }
 
@@ -146,11 +270,40 @@ impl TransactionAdapter {
     
     /// Получить account key как String (для обратной совместимости)
     pub fn get_account_key_string(&self, index: usize) -> String {
-        self.account_keys.get(index).cloned().unwrap_or_default()
+        self.account_keys.get(index).map(ToString::to_string).unwrap_or_default()
     }
 
     pub fn get_account_index(&self, address: &str) -> Option<usize> {
-        self.account_keys.iter().position(|k| k == address)
+        self.account_index.get(address).copied()
+    }
+
+    /// Токен-аккаунт -> индекс в `balances`; при повторах побеждает первая запись,
+    /// как у прежнего `find`. Ключи берутся из `account_index`, чтобы не аллоцировать.
+    fn token_balance_index(
+        balances: &[TokenBalance],
+        account_index: &FxHashMap<AccountKey, usize>,
+    ) -> FxHashMap<AccountKey, usize> {
+        let mut index = FxHashMap::with_capacity_and_hasher(balances.len(), Default::default());
+        for (position, balance) in balances.iter().enumerate() {
+            let key = account_index
+                .get_key_value(balance.account.as_str())
+                .map(|(key, _)| key.clone())
+                .unwrap_or_else(|| AccountKey::new(&balance.account));
+            index.entry(key).or_insert(position);
+        }
+        index
+    }
+
+    pub(crate) fn post_token_balance(&self, account: &str) -> Option<&TokenBalance> {
+        self.post_token_index
+            .get(account)
+            .map(|&position| &self.tx.post_token_balances[position])
+    }
+
+    pub(crate) fn pre_token_balance(&self, account: &str) -> Option<&TokenBalance> {
+        self.pre_token_index
+            .get(account)
+            .map(|&position| &self.tx.pre_token_balances[position])
     }
 
     /* ----------------------- инструкции ----------------------- */
@@ -173,7 +326,7 @@ impl TransactionAdapter {
             .and_then(|s| s.instructions.get(inner_index))
     }
 
-    pub fn get_instruction_accounts<'a>(&self, instruction: &'a SolanaInstruction) -> &'a [String] {
+    pub fn get_instruction_accounts<'a>(&self, instruction: &'a SolanaInstruction) -> &'a [AccountKey] {
         &instruction.accounts
     }
 
@@ -184,7 +337,7 @@ impl TransactionAdapter {
 
     /// Аналог TS getInstructionType: первый байт data → строка
     pub fn get_instruction_type(&self, instruction: &SolanaInstruction) -> Option<String> {
-        let data = crate::core::utils::get_instruction_data(instruction);
+        let data = crate::core::utils::get_instruction_data(instruction).ok()?;
         data.first().map(|b| b.to_string())
     }
 
@@ -243,18 +396,57 @@ impl TransactionAdapter {
     }
 
 
-    /// Владелец токен-аккаунта по post/pre token balances
+    /// Владелец токен-аккаунта по post/pre token balances, иначе из `AccountOwnerCache`
     /// ZERO-COPY: возвращает ссылку вместо клонирования
     pub fn get_token_account_owner(&self, account_key: &str) -> Option<&str> {
-        if let Some(b) = self.post_token_balances().iter().find(|b| b.account == account_key) {
+        if let Some(b) = self.post_token_balance(account_key) {
             return b.owner.as_deref();
         }
-        if let Some(b) = self.pre_token_balances().iter().find(|b| b.account == account_key) {
+        if let Some(b) = self.pre_token_balance(account_key) {
             return b.owner.as_deref();
         }
-        None
+        self.cached_owners.get(account_key).map(String::as_str)
     }
     
+    /// Изменение token balance аккаунта, который обязан быть в meta (vault пула,
+    /// token account кривой). Нет ни pre, ни post — `MissingTokenBalance`;
+    /// нет только pre — аккаунт создан в транзакции.
+    pub fn token_balance_change(&self, account: &str) -> Result<BalanceChange, ParserError> {
+        let amount = |balance: Option<&TokenBalance>| -> Option<i128> {
+            balance.and_then(|b| b.ui_token_amount.amount.parse().ok())
+        };
+        let post = self.post_token_balance(account);
+        let pre = self.pre_token_balance(account);
+        if post.is_none() && pre.is_none() {
+            return Err(ParserError::MissingTokenBalance {
+                account: account.to_string(),
+            });
+        }
+        let pre = amount(pre).unwrap_or(0);
+        let post = amount(post).unwrap_or(0);
+        Ok(BalanceChange {
+            pre,
+            post,
+            change: post - pre,
+        })
+    }
+
+    /// Ошибка протокольного парсера, после которой разбор продолжается (fallback
+    /// на данные инструкции и т.п.). `DexParser` возвращает её из `try_parse_all`,
+    /// а в `parse_all` — в `ParseResult::msg`.
+    pub fn report_error(&self, err: ParserError) {
+        if let Ok(mut errors) = self.errors.lock() {
+            errors.push(err);
+        }
+    }
+
+    pub(crate) fn take_errors(&self) -> Vec<ParserError> {
+        self.errors
+            .lock()
+            .map(|mut errors| std::mem::take(&mut *errors))
+            .unwrap_or_default()
+    }
+
     /// Получить owner как String (для обратной совместимости)
     pub fn get_token_account_owner_string(&self, account_key: &str) -> Option<String> {
         self.get_token_account_owner(account_key).map(|s| s.to_string())
@@ -285,24 +477,14 @@ impl TransactionAdapter {
     pub fn get_token_account_balance(&self, account_keys: &[String]) -> Vec<Option<TokenAmount>> {
         account_keys
             .iter()
-            .map(|key| {
-                self.post_token_balances()
-                    .iter()
-                    .find(|b| &b.account == key)
-                    .map(|b| b.ui_token_amount.clone())
-            })
+            .map(|key| self.post_token_balance(key).map(|b| b.ui_token_amount.clone()))
             .collect()
     }
 
     pub fn get_token_account_pre_balance(&self, account_keys: &[String]) -> Vec<Option<TokenAmount>> {
         account_keys
             .iter()
-            .map(|key| {
-                self.pre_token_balances()
-                    .iter()
-                    .find(|b| &b.account == key)
-                    .map(|b| b.ui_token_amount.clone())
-            })
+            .map(|key| self.pre_token_balance(key).map(|b| b.ui_token_amount.clone()))
             .collect()
     }
 
@@ -325,12 +507,23 @@ impl TransactionAdapter {
     }
 
     /// Алиас для старого кода
+    /// `uiTokenAmount` токен-аккаунта из postTokenBalances.
+    pub fn token_account_balance(&self, account: &str) -> Option<&TokenAmount> {
+        self.post_token_balance(account).map(|b| &b.ui_token_amount)
+    }
+
+    /// `uiTokenAmount` токен-аккаунта из preTokenBalances.
+    pub fn token_account_pre_balance(&self, account: &str) -> Option<&TokenAmount> {
+        self.pre_token_balance(account).map(|b| &b.ui_token_amount)
+    }
+
     pub fn token_account_info(&self, account: &str) -> Option<&TokenInfo> {
         self.spl_token_map.get(account)
     }
 
+    /// Известный токен (`TOKENS`) или quote из `ParseConfig::quote_mints`.
     pub fn is_supported_token(&self, mint: &str) -> bool {
-        TOKENS.values().iter().any(|m| *m == mint)
+        TOKENS.values().contains(&mint) || self.config.quote_priority(mint).is_some()
     }
 
     /// Get SOL balance change for the signer account (optimized: direct lookup)
@@ -479,6 +672,103 @@ impl TransactionAdapter {
         }
     }
 
+    /// LP mint/burn в CPI liquidity-инструкции `outer_index[-inner_index]`: `mintTo(Checked)`
+    /// при `is_add`, иначе `burn(Checked)`. `lp_mint` — mint LP-токена из аккаунтов пула;
+    /// без него берётся первый найденный. В transfer actions RPC-транзакций mint/burn нет.
+    pub fn find_lp_token_action(
+        &self,
+        outer_index: usize,
+        inner_index: Option<usize>,
+        is_add: bool,
+        lp_mint: Option<&str>,
+    ) -> Option<TransferData> {
+        const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+        const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+        const MINT_TO: u8 = 7;
+        const BURN: u8 = 8;
+        const MINT_TO_CHECKED: u8 = 14;
+        const BURN_CHECKED: u8 = 15;
+
+        let inner_set = self
+            .inner_instructions()
+            .iter()
+            .find(|set| set.index == outer_index)?;
+        let parent_height = inner_index.and_then(|position| inner_set.stack_height(position));
+        let start = inner_index.map_or(0, |position| position + 1);
+        for (position, ix) in inner_set.instructions.iter().enumerate().skip(start) {
+            // CPI вызывающей инструкции закончились
+            if let (Some(parent), Some(height)) = (parent_height, inner_set.stack_height(position)) {
+                if height <= parent {
+                    break;
+                }
+            }
+            if ix.program_id != TOKEN_PROGRAM_ID && ix.program_id != TOKEN_2022_PROGRAM_ID {
+                continue;
+            }
+            let Ok(data) = crate::core::utils::get_instruction_data(ix) else {
+                continue;
+            };
+            // mintTo: [mint, account, authority]; burn: [account, mint, authority]
+            let (transfer_type, mint, account) = match (data.first(), is_add) {
+                (Some(&(MINT_TO | MINT_TO_CHECKED)), true) => ("mintTo", 0, 1),
+                (Some(&(BURN | BURN_CHECKED)), false) => ("burn", 1, 0),
+                _ => continue,
+            };
+            let (Some(mint), Some(account)) = (ix.accounts.get(mint), ix.accounts.get(account))
+            else {
+                continue;
+            };
+            if lp_mint.is_some_and(|lp_mint| lp_mint != mint.as_ref()) {
+                continue;
+            }
+            let Some(amount) = data
+                .get(1..9)
+                .and_then(|bytes| bytes.try_into().ok())
+                .map(u64::from_le_bytes)
+            else {
+                continue;
+            };
+            // у *Checked decimals сразу за суммой
+            let decimals = data
+                .get(9)
+                .copied()
+                .or_else(|| self.token_decimals(mint))
+                .unwrap_or(0);
+            let (source, destination) = if is_add {
+                (mint, account)
+            } else {
+                (account, mint)
+            };
+            return Some(TransferData {
+                transfer_type: transfer_type.to_string(),
+                program_id: ix.program_id.clone(),
+                info: crate::types::TransferInfo {
+                    authority: ix.accounts.get(2).cloned(),
+                    destination: destination.clone(),
+                    mint: mint.clone(),
+                    source: source.clone(),
+                    token_amount: TokenAmount::new(
+                        amount.to_string(),
+                        decimals,
+                        Some(crate::protocols::pumpfun::util::convert_to_ui_amount(
+                            amount, decimals,
+                        )),
+                    ),
+                    ..Default::default()
+                },
+                idx: format!("{outer_index}-{position}"),
+                instruction_index: u16::try_from(outer_index).unwrap_or(u16::MAX),
+                inner_index: u16::try_from(position).ok(),
+                timestamp: self.block_time(),
+                signature: self.signature().to_string(),
+                is_fee: false,
+                fee_amount: None,
+                order_key: None,
+            });
+        }
+        None
+    }
+
     /* ----------------------- balance changes: i128 ----------------------- */
 
     /// Полный аналог по смыслу, но под твой `BalanceChange` (pre/post/change: i128)
@@ -490,9 +780,9 @@ impl TransactionAdapter {
 
         for key in &self.account_keys {
             let account_key = if is_owner {
-                self.get_token_account_owner(key).map(|s| s.to_string()).unwrap_or_else(|| key.clone())
+                self.get_token_account_owner(key).map(|s| s.to_string()).unwrap_or_else(|| key.to_string())
             } else {
-                key.clone()
+                key.to_string()
             };
 
             if let Some(change) = self.tx.meta.sol_balance_changes.get(&account_key) {
@@ -580,7 +870,7 @@ impl TransactionAdapter {
         let mut map: TransferMap = HashMap::with_capacity(estimated_programs);
         
         for t in transfers {
-            map.entry(t.program_id.clone()).or_insert_with(|| Vec::with_capacity(4)).push(t.clone());
+            map.entry(t.program_id.to_string()).or_insert_with(|| Vec::with_capacity(4)).push(t.clone());
         }
         
         map
@@ -605,16 +895,16 @@ impl TransactionAdapter {
             });
 
             let token_info = TokenInfo {
-                mint: info.mint.clone(),
+                mint: info.mint.to_string(),
                 amount,
                 amount_raw: info.token_amount.amount.clone(),
                 decimals: info.token_amount.decimals,
-                authority: info.authority.clone(),
-                destination: Some(info.destination.clone()),
-                destination_owner: info.destination_owner.clone(),
+                authority: info.authority.clone().map(String::from),
+                destination: Some(info.destination.to_string()),
+                destination_owner: info.destination_owner.clone().map(String::from),
                 destination_balance: info.destination_balance.clone(),
                 destination_pre_balance: info.destination_pre_balance.clone(),
-                source: Some(info.source.clone()),
+                source: Some(info.source.to_string()),
                 source_balance: info.source_balance.clone(),
                 source_pre_balance: info.source_pre_balance.clone(),
                 destination_balance_change: None,
@@ -622,9 +912,9 @@ impl TransactionAdapter {
                 balance_change: info.sol_balance_change.clone(),
             };
 
-            accounts.entry(info.source.clone()).or_insert_with(|| token_info.clone());
-            accounts.entry(info.destination.clone()).or_insert_with(|| token_info.clone());
-            decimals.entry(info.mint.clone()).or_insert(info.token_amount.decimals);
+            accounts.entry(info.source.to_string()).or_insert_with(|| token_info.clone());
+            accounts.entry(info.destination.to_string()).or_insert_with(|| token_info.clone());
+            decimals.entry(info.mint.to_string()).or_insert(info.token_amount.decimals);
         }
 
         // 2) post balances (as in TypeScript: extractTokenBalances)
@@ -679,6 +969,8 @@ impl TransactionAdapter {
         // SPL Token instruction types
         const TRANSFER: u8 = 3;
         const TRANSFER_CHECKED: u8 = 12;
+        // Token-2022 TransferFeeExtension (подинструкция 1 — TransferCheckedWithFee)
+        const TRANSFER_FEE_EXTENSION: u8 = 26;
         const INITIALIZE_MINT: u8 = 0;
         const MINT_TO: u8 = 7;
         const MINT_TO_CHECKED: u8 = 14;
@@ -766,10 +1058,10 @@ impl TransactionAdapter {
                 continue;
             }
 
-            let data = crate::core::utils::get_instruction_data(ix);
-            if data.is_empty() {
-                continue;
-            }
+            let data = match crate::core::utils::get_instruction_data(ix) {
+                Ok(data) if !data.is_empty() => data,
+                _ => continue,
+            };
 
             let instruction_type = data[0];
             let accounts_vec = &ix.accounts;
@@ -801,6 +1093,15 @@ impl TransactionAdapter {
                         );
                     }
                 }
+                // accounts как у transferChecked, decimals в data[10]
+                TRANSFER_FEE_EXTENSION if data.get(1) == Some(&1) && accounts_vec.len() >= 4 => {
+                    set_token_info(
+                        accounts_vec.first().map(|s| s.as_str()),
+                        accounts_vec.get(2).map(|d| d.as_str()),
+                        accounts_vec.get(1).map(|m| m.as_str()),
+                        data.get(10).copied(),
+                    );
+                }
                 INITIALIZE_MINT => {
                     if accounts_vec.len() >= 2 {
                         let mint = accounts_vec.get(0);
@@ -888,10 +1189,10 @@ impl TransactionAdapter {
                     continue;
                 }
 
-                let data = crate::core::utils::get_instruction_data(ix);
-                if data.is_empty() {
-                    continue;
-                }
+                let data = match crate::core::utils::get_instruction_data(ix) {
+                    Ok(data) if !data.is_empty() => data,
+                    _ => continue,
+                };
 
                 let instruction_type = data[0];
                 let accounts_vec = &ix.accounts;
@@ -923,6 +1224,15 @@ impl TransactionAdapter {
                             );
                         }
                     }
+                    // accounts как у transferChecked, decimals в data[10]
+                    TRANSFER_FEE_EXTENSION if data.get(1) == Some(&1) && accounts_vec.len() >= 4 => {
+                        set_token_info(
+                            accounts_vec.first().map(|s| s.as_str()),
+                            accounts_vec.get(2).map(|d| d.as_str()),
+                            accounts_vec.get(1).map(|m| m.as_str()),
+                            data.get(10).copied(),
+                        );
+                    }
                     INITIALIZE_MINT => {
                         if accounts_vec.len() >= 2 {
                             let mint = accounts_vec.get(0);
@@ -1025,3 +1335,98 @@ impl TransactionAdapter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn balance(account: &str, owner: &str, amount: &str) -> TokenBalance {
+        TokenBalance {
+            account: account.to_string(),
+            mint: "mint".to_string(),
+            owner: Some(owner.to_string()),
+            ui_token_amount: TokenAmount::new(amount, 6, None),
+        }
+    }
+
+    #[test]
+    fn lookups_use_prebuilt_indexes() {
+        let tx = SolanaTransaction {
+            signers: vec!["signer".to_string()],
+            pre_token_balances: vec![balance("vault", "pool", "10"), balance("vault", "other", "99")],
+            post_token_balances: vec![balance("user_ata", "signer", "5")],
+            ..Default::default()
+        };
+        let adapter = TransactionAdapter::new(tx, ParseConfig::default());
+
+        assert_eq!(adapter.get_account_index("signer"), Some(0));
+        assert_eq!(adapter.get_account_index("missing"), None);
+        assert_eq!(adapter.get_token_account_owner("user_ata"), Some("signer"));
+        assert_eq!(adapter.get_token_account_owner("vault"), Some("pool"));
+
+        let keys = ["vault".to_string(), "user_ata".to_string()];
+        let pre = adapter.get_token_account_pre_balance(&keys);
+        assert_eq!(pre[0].as_ref().map(|a| a.amount.as_str()), Some("10"));
+        assert!(pre[1].is_none());
+        let post = adapter.get_token_account_balance(&keys);
+        assert_eq!(post[1].as_ref().map(|a| a.amount.as_str()), Some("5"));
+
+        // общая транзакция не копируется
+        let shared = adapter.transaction().clone();
+        let again = TransactionAdapter::new(shared.clone(), ParseConfig::default());
+        assert!(Arc::ptr_eq(again.transaction(), &shared));
+    }
+
+    #[test]
+    fn stack_heights_from_logs_attribute_transfers_to_caller() {
+        use crate::core::transaction_utils::TransactionUtils;
+        use base64_simd::STANDARD;
+
+        const TOKEN: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+        let ix = |program_id: &str, data: String| SolanaInstruction {
+            program_id: program_id.into(),
+            accounts: vec!["from".into(), "to".into(), "authority".into()],
+            data,
+        };
+        let mut transfer = vec![3];
+        transfer.extend_from_slice(&1_000u64.to_le_bytes());
+        let transfer = STANDARD.encode_to_string(&transfer);
+
+        let mut tx = SolanaTransaction {
+            instructions: vec![ix("aggregator", String::new())],
+            inner_instructions: vec![InnerInstruction {
+                index: 0,
+                instructions: vec![
+                    ix("dex", String::new()),
+                    ix(TOKEN, transfer.clone()),
+                    ix(TOKEN, transfer),
+                ],
+                stack_heights: Vec::new(),
+            }],
+            ..Default::default()
+        };
+        tx.meta.log_messages = [
+            "Program aggregator invoke [1]",
+            "Program dex invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [3]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program dex success",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]",
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success",
+            "Program aggregator success",
+        ]
+        .map(String::from)
+        .to_vec();
+
+        let adapter = TransactionAdapter::new(tx, ParseConfig::default());
+        assert_eq!(
+            adapter.inner_instructions()[0].stack_heights,
+            [Some(2), Some(3), Some(2)]
+        );
+
+        // вторая передача — от агрегатора, а не от последней вызванной программы
+        let actions = TransactionUtils::new(std::sync::Arc::new(adapter)).get_transfer_actions();
+        assert_eq!(actions["dex:0-0"].len(), 1);
+        assert_eq!(actions["aggregator:0"].len(), 1);
+    }
+}
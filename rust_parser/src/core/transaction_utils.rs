@@ -1,16 +1,18 @@
+use std::sync::Arc;
+
 use crate::core::constants::dex_program_names;
 use crate::core::instruction_classifier::InstructionClassifier;
 use crate::core::transaction_adapter::TransactionAdapter;
-use crate::types::{DexInfo, FeeInfo, PoolEvent, TradeInfo, TradeType, TransferData, TransferMap};
-use rustc_hash::FxHashMap;
+use crate::protocols::pumpfun::util::get_trade_type;
+use crate::types::{AccountKey, DecimalAmount, DexInfo, FeeInfo, PoolEvent, TradeConfidence, TradeInfo, TransferData, TransferMap};
 use std::collections::HashMap;
 
 pub struct TransactionUtils {
-    pub(crate) adapter: TransactionAdapter,
+    pub(crate) adapter: Arc<TransactionAdapter>,
 }
 
 impl TransactionUtils {
-    pub fn new(adapter: TransactionAdapter) -> Self {
+    pub fn new(adapter: Arc<TransactionAdapter>) -> Self {
         Self { adapter }
     }
 
@@ -46,8 +48,7 @@ impl TransactionUtils {
     fn create_transfers_from_instructions(adapter: &TransactionAdapter) -> TransferMap {
         use crate::core::constants::SYSTEM_PROGRAMS;
         
-        const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
-        const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+        const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
         
         // Предварительно оцениваем количество transfers (обычно 5-20)
         let estimated_transfers = adapter.inner_instructions().len() * 3 + adapter.instructions().len();
@@ -74,10 +75,31 @@ impl TransactionUtils {
             key_buf.push(':');
             let mut num_buf = itoa::Buffer::new();
             key_buf.push_str(num_buf.format(outer_index));
-            let base_key_len = key_buf.len();
+            let outer_key = key_buf.clone();
+            // Вызовы non-system программ на стеке CPI: (stack_height, ключ). При известной
+            // глубине transfer относится к ближайшему вызывающему, а не к последней
+            // встреченной программе — важно для агрегаторов с вложенными DEX.
+            let mut callers: Vec<(u32, String)> = Vec::new();
+            // Глубина активного вызова Associated Token program: его CPI в System
+            // Program только фондирует rent нового ATA и ногой свопа не является.
+            let mut ata_frame: Option<u32> = None;
             
             for (inner_index, ix) in inner_set.instructions.iter().enumerate() {
                 let inner_program_id = &ix.program_id;
+                let stack_height = inner_set.stack_height(inner_index);
+                if let Some(height) = stack_height {
+                    while callers.last().is_some_and(|(caller, _)| *caller >= height) {
+                        callers.pop();
+                    }
+                    if ata_frame.is_some_and(|frame| frame >= height) {
+                        ata_frame = None;
+                    }
+                    if inner_program_id == ASSOCIATED_TOKEN_PROGRAM_ID {
+                        ata_frame = Some(height);
+                    } else if ata_frame.is_some() && inner_program_id == SYSTEM_PROGRAMS[1] {
+                        continue;
+                    }
+                }
                 
                 // Special case for meteora vault (как в TypeScript)
                 if !SYSTEM_PROGRAMS.contains(&inner_program_id.as_str()) {
@@ -89,6 +111,9 @@ impl TransactionUtils {
                     key_buf.push_str(num_buf.format(outer_index));
                     key_buf.push('-');
                     key_buf.push_str(num_buf.format(inner_index));
+                    if let Some(height) = stack_height {
+                        callers.push((height, key_buf.clone()));
+                    }
                     continue;
                 }
                 
@@ -106,7 +131,11 @@ impl TransactionUtils {
                     &idx_buf,
                 ) {
                     // ZERO-COPY: клонируем key_buf только один раз для HashMap ключа
-                    let key = key_buf.clone();
+                    let key = match (stack_height, callers.last()) {
+                        (Some(_), Some((_, caller))) => caller.clone(),
+                        (Some(_), None) => outer_key.clone(),
+                        (None, _) => key_buf.clone(),
+                    };
                     actions.entry(key).or_insert_with(|| Vec::with_capacity(4)).push(transfer_data);
                 }
             }
@@ -127,7 +156,9 @@ impl TransactionUtils {
                 actions.entry("transfer".to_string()).or_insert_with(|| Vec::with_capacity(4)).push(transfer_data);
             }
         }
-        
+
+        Self::fill_wsol_close_amounts(adapter, &mut actions);
+        Self::fill_withheld_transfer_fees(adapter, &mut actions);
         actions
     }
     
@@ -139,20 +170,27 @@ impl TransactionUtils {
         instruction: &crate::types::SolanaInstruction,
         idx: &str,
     ) -> Option<TransferData> {
-        use crate::core::constants::{TOKENS, SYSTEM_PROGRAMS};
+        use crate::core::constants::{TOKENS, SYSTEM_PROGRAMS, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
         use crate::core::utils::get_instruction_data;
         
-        const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
-        const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
         const TRANSFER: u8 = 3;
         const TRANSFER_CHECKED: u8 = 12;
+        // Token-2022: TransferFeeExtension, подинструкция TransferCheckedWithFee
+        const TRANSFER_FEE_EXTENSION: u8 = 26;
+        const TRANSFER_CHECKED_WITH_FEE: u8 = 1;
         
+        const CLOSE_ACCOUNT: u8 = 9;
+
+        if instruction.program_id == SYSTEM_PROGRAMS[1] {
+            return Self::parse_system_instruction_fast(adapter, instruction, idx);
+        }
+
         // Только для Token Program инструкций
         if instruction.program_id != TOKEN_PROGRAM_ID && instruction.program_id != TOKEN_2022_PROGRAM_ID {
             return None;
         }
         
-        let data = get_instruction_data(instruction);
+        let data = get_instruction_data(instruction).ok()?;
         if data.is_empty() {
             return None;
         }
@@ -207,10 +245,302 @@ impl TransactionUtils {
                     None
                 }
             }
+            TRANSFER_FEE_EXTENSION if data.get(1) == Some(&TRANSFER_CHECKED_WITH_FEE) => {
+                // transferCheckedWithFee: [26, 1, amount u64, decimals u8, fee u64],
+                // accounts как у transferChecked; transfer hook добавляет только extra
+                // accounts после authority, на разбор суммы не влияет
+                if accounts.len() >= 4 && data.len() >= 19 {
+                    let decimals = data[10];
+                    let fee = u64::from_le_bytes(data[11..19].try_into().ok()?);
+                    // раскладка transferChecked: [12, amount, decimals]
+                    let mut checked = Vec::with_capacity(10);
+                    checked.push(TRANSFER_CHECKED);
+                    checked.extend_from_slice(&data[2..11]);
+                    let mut transfer = Self::create_transfer_data_fast(
+                        adapter,
+                        &instruction.program_id,
+                        accounts.first()?,
+                        accounts.get(2)?,
+                        Some(accounts.get(1)?),
+                        Some(decimals),
+                        idx,
+                        "transferCheckedWithFee",
+                        &checked,
+                        TRANSFER_CHECKED,
+                        accounts,
+                    )?;
+                    transfer.fee_amount = Some(crate::types::TokenAmount::new(
+                        fee.to_string(),
+                        decimals,
+                        Some(crate::protocols::pumpfun::util::convert_to_ui_amount(fee, decimals)),
+                    ));
+                    Some(transfer)
+                } else {
+                    None
+                }
+            }
+            CLOSE_ACCOUNT => {
+                // closeAccount: [account, destination, owner]. Только WSOL — unwrap в SOL;
+                // сумма заполняется в `fill_wsol_close_amounts`
+                let account = accounts.first()?;
+                let is_wsol = adapter
+                    .token_account_info(account)
+                    .is_some_and(|info| info.mint == TOKENS.SOL);
+                if !is_wsol || accounts.len() < 3 {
+                    return None;
+                }
+                let mut transfer = Self::create_native_transfer_data(
+                    adapter,
+                    &instruction.program_id,
+                    account,
+                    accounts.get(1)?,
+                    0,
+                    idx,
+                    "closeAccount",
+                );
+                transfer.info.authority = accounts.get(2).cloned();
+                Some(transfer)
+            }
             _ => None,
         }
     }
+
+    /// Движения lamports System Program: transfer, transferWithSeed, createAccount,
+    /// createAccountWithSeed. Mint — SOL (совпадает с WSOL), поэтому перевод на WSOL-аккаунт
+    /// перед `syncNative` (wrap) и SPL-переводы WSOL складываются в одну ногу.
+    /// У createAccount в ногу идут только lamports сверх rent-exempt минимума для `space`:
+    /// создание аккаунта без wrap'а transfer'а не даёт.
+    fn parse_system_instruction_fast(
+        adapter: &TransactionAdapter,
+        instruction: &crate::types::SolanaInstruction,
+        idx: &str,
+    ) -> Option<TransferData> {
+        use crate::core::utils::get_instruction_data;
+
+        const CREATE_ACCOUNT: u32 = 0;
+        const CREATE_ACCOUNT_WITH_SEED: u32 = 3;
+        const TRANSFER: u32 = 2;
+        const TRANSFER_WITH_SEED: u32 = 11;
+
+        let data = get_instruction_data(instruction).ok()?;
+        let read_u64 = |offset: usize| -> Option<u64> {
+            Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+        };
+        let accounts = &instruction.accounts;
+        let instruction_type = u32::from_le_bytes(data.get(0..4)?.try_into().ok()?);
+
+        let above_rent = |lamports: u64, space: u64| -> Option<u64> {
+            let rent = solana_program::rent::Rent::default().minimum_balance(usize::try_from(space).ok()?);
+            lamports.checked_sub(rent).filter(|amount| *amount > 0)
+        };
+
+        // (source, destination, lamports, тип)
+        let (source, destination, lamports, transfer_type) = match instruction_type {
+            // [type, lamports, space, owner]; accounts: [funding, new]
+            CREATE_ACCOUNT => {
+                let lamports = above_rent(read_u64(4)?, read_u64(12)?)?;
+                (accounts.first()?, accounts.get(1)?, lamports, "createAccount")
+            }
+            // [type, base, seed (u64 len + bytes), lamports, space, owner]; accounts: [funding, new, base]
+            CREATE_ACCOUNT_WITH_SEED => {
+                let seed_len = usize::try_from(read_u64(36)?).ok()?;
+                let offset = 44usize.checked_add(seed_len)?;
+                let lamports = above_rent(read_u64(offset)?, read_u64(offset + 8)?)?;
+                (accounts.first()?, accounts.get(1)?, lamports, "createAccountWithSeed")
+            }
+            // [type, lamports]; accounts: [from, to]
+            TRANSFER => (accounts.first()?, accounts.get(1)?, read_u64(4)?, "transfer"),
+            // [type, lamports, seed, owner]; accounts: [from, base, to]
+            TRANSFER_WITH_SEED => (accounts.first()?, accounts.get(2)?, read_u64(4)?, "transferWithSeed"),
+            _ => return None,
+        };
+
+        let mut transfer = Self::create_native_transfer_data(
+            adapter,
+            &instruction.program_id,
+            source,
+            destination,
+            lamports,
+            idx,
+            transfer_type,
+        );
+        transfer.info.authority = Some(source.clone());
+        Some(transfer)
+    }
+
+    /// TransferData для нативного SOL (decimals 9).
+    fn create_native_transfer_data(
+        adapter: &TransactionAdapter,
+        program_id: &str,
+        source: &str,
+        destination: &str,
+        lamports: u64,
+        idx: &str,
+        transfer_type: &str,
+    ) -> TransferData {
+        use crate::core::constants::TOKENS;
+
+        let accounts = [source.to_string(), destination.to_string()];
+        let mut post = adapter.get_account_balance(&accounts).into_iter();
+        let (source_balance, destination_balance) = (post.next().flatten(), post.next().flatten());
+        let mut pre = adapter.get_account_pre_balance(&accounts).into_iter();
+        let (source_pre_balance, destination_pre_balance) = (pre.next().flatten(), pre.next().flatten());
+
+        TransferData {
+            transfer_type: transfer_type.to_string(),
+            program_id: program_id.into(),
+            info: crate::types::TransferInfo {
+                authority: None,
+                destination: destination.into(),
+                destination_owner: adapter
+                    .get_token_account_owner(destination)
+                    .map(AccountKey::from),
+                mint: TOKENS.SOL.into(),
+                source: source.into(),
+                token_amount: crate::types::TokenAmount::new(
+                    lamports.to_string(),
+                    9,
+                    Some(lamports as f64 / 1_000_000_000.0),
+                ),
+                source_balance,
+                source_pre_balance,
+                destination_balance,
+                destination_pre_balance,
+                sol_balance_change: None,
+            },
+            idx: idx.to_string(),
+            timestamp: adapter.block_time(),
+            signature: adapter.signature().to_string(),
+            is_fee: false,
+            fee_amount: None,
+            instruction_index: 0,
+            inner_index: None,
+            order_key: None,
+        }
+    }
+
+    /// Сумма WSOL, выведенная `closeAccount`: WSOL-баланс аккаунта к моменту закрытия —
+    /// pre token amount плюс входящие и минус исходящие SOL-переводы. Token amount WSOL —
+    /// это lamports сверх rent-резерва аккаунта, поэтому размер аккаунта (расширения
+    /// Token-2022) на сумму не влияет. Закрытия пустых аккаунтов удаляются.
+    fn fill_wsol_close_amounts(adapter: &TransactionAdapter, actions: &mut TransferMap) {
+        use crate::core::constants::TOKENS;
+
+        let closed: Vec<String> = actions
+            .values()
+            .flatten()
+            .filter(|t| t.transfer_type == "closeAccount")
+            .map(|t| t.info.source.to_string())
+            .collect();
+        if closed.is_empty() {
+            return;
+        }
+
+        let mut lamports: HashMap<String, i128> = closed
+            .into_iter()
+            .map(|account| {
+                let pre = adapter
+                    .pre_token_balance(&account)
+                    .and_then(|balance| balance.ui_token_amount.amount.parse::<i128>().ok())
+                    .unwrap_or(0);
+                (account, pre)
+            })
+            .collect();
+        for transfer in actions.values().flatten() {
+            if transfer.transfer_type == "closeAccount" || transfer.info.mint != TOKENS.SOL {
+                continue;
+            }
+            let amount = transfer.info.token_amount.amount.parse::<i128>().unwrap_or(0);
+            if let Some(balance) = lamports.get_mut(transfer.info.destination.as_str()) {
+                *balance += amount;
+            }
+            if let Some(balance) = lamports.get_mut(transfer.info.source.as_str()) {
+                *balance -= amount;
+            }
+        }
+
+        for transfers in actions.values_mut() {
+            transfers.retain_mut(|transfer| {
+                if transfer.transfer_type != "closeAccount" {
+                    return true;
+                }
+                let balance = lamports
+                    .get(transfer.info.source.as_str())
+                    .copied()
+                    .unwrap_or(0);
+                let amount = u64::try_from(balance).unwrap_or(0);
+                transfer.info.token_amount = crate::types::TokenAmount::new(
+                    amount.to_string(),
+                    9,
+                    Some(amount as f64 / 1_000_000_000.0),
+                );
+                amount > 0
+            });
+        }
+        actions.retain(|_, transfers| !transfers.is_empty());
+    }
     
+    /// Mint Token-2022 с `TransferFeeConfig` удерживает комиссию и на обычных
+    /// `transfer`/`transferChecked`: получатель видит меньше суммы инструкции. Удержанное
+    /// считается по token balances: ожидаемое изменение аккаунта (входящие нетто минус
+    /// исходящие брутто) против post - pre. Недостача — `fee_amount` единственного
+    /// входящего перевода без известной комиссии; при нескольких таких переводах её
+    /// не разложить, и суммы остаются брутто.
+    fn fill_withheld_transfer_fees(adapter: &TransactionAdapter, actions: &mut TransferMap) {
+        use crate::core::constants::TOKEN_2022_PROGRAM_ID;
+
+        let is_candidate = |transfer: &TransferData| {
+            transfer.program_id == TOKEN_2022_PROGRAM_ID
+                && transfer.fee_amount.is_none()
+                && matches!(transfer.transfer_type.as_str(), "transfer" | "transferChecked")
+        };
+        // аккаунт -> (ожидаемое изменение, входящих переводов без комиссии)
+        let mut expected: HashMap<&str, (i128, usize)> = HashMap::new();
+        for transfer in actions.values().flatten() {
+            if transfer.program_id != TOKEN_2022_PROGRAM_ID {
+                continue;
+            }
+            let gross = transfer.info.token_amount.amount.parse::<i128>().unwrap_or(0);
+            let incoming = expected.entry(transfer.info.destination.as_str()).or_default();
+            incoming.0 += transfer.net_amount_raw() as i128;
+            incoming.1 += usize::from(is_candidate(transfer));
+            expected.entry(transfer.info.source.as_str()).or_default().0 -= gross;
+        }
+
+        let withheld: HashMap<String, i128> = expected
+            .into_iter()
+            .filter(|(_, (_, candidates))| *candidates == 1)
+            .filter_map(|(account, (expected, _))| {
+                // закрытый в транзакции аккаунт не сравнить
+                adapter.post_token_balance(account)?;
+                let actual = adapter.token_balance_change(account).ok()?.change;
+                (actual < expected).then(|| (account.to_string(), expected - actual))
+            })
+            .collect();
+        if withheld.is_empty() {
+            return;
+        }
+
+        for transfer in actions.values_mut().flatten() {
+            if !is_candidate(transfer) {
+                continue;
+            }
+            let Some(fee) = withheld.get(transfer.info.destination.as_str()) else {
+                continue;
+            };
+            let gross = transfer.info.token_amount.amount.parse::<i128>().unwrap_or(0);
+            if *fee > gross {
+                continue;
+            }
+            let decimals = transfer.info.token_amount.decimals;
+            transfer.fee_amount = Some(crate::types::TokenAmount::new(
+                fee.to_string(),
+                decimals,
+                Some(DecimalAmount::new(*fee as u128, decimals).to_f64()),
+            ));
+        }
+    }
+
     /// Создает TransferData из данных инструкции
     /// ОПТИМИЗИРОВАНО: кэширует token_account_info lookups, избегает лишних клонирований
     #[inline]
@@ -225,12 +555,9 @@ impl TransactionUtils {
         transfer_type: &str,
         data: &[u8],
         instruction_type: u8,
-        accounts: &[String],
+        accounts: &[AccountKey],
     ) -> Option<TransferData> {
-        use crate::core::constants::TOKENS;
-        
-        const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
-        const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+        use crate::core::constants::{TOKENS, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID};
         
         // Определяем mint (оптимизировано: используем кэш для lookups)
         let mint = if let Some(m) = mint_opt {
@@ -290,26 +617,12 @@ impl TransactionUtils {
             amount_raw as f64 / 10f64.powi(decimals as i32)
         };
         
-        // Получаем balances
-        let source_info = adapter.token_account_info(source);
-        let dest_info = adapter.token_account_info(destination);
-        
-        let source_balance = source_info.map(|info| {
-            crate::types::TokenAmount {
-                amount: info.amount_raw.clone(),
-                decimals: info.decimals,
-                ui_amount: Some(info.amount),
-            }
-        });
-        
-        let destination_balance = dest_info.map(|info| {
-            crate::types::TokenAmount {
-                amount: info.amount_raw.clone(),
-                decimals: info.decimals,
-                ui_amount: Some(info.amount),
-            }
-        });
-        
+        // Балансы счетов — как `getTokenAccountBalance`/`getTokenAccountPreBalance` в TS
+        let source_balance = adapter.token_account_balance(source).cloned();
+        let source_pre_balance = adapter.token_account_pre_balance(source).cloned();
+        let destination_balance = adapter.token_account_balance(destination).cloned();
+        let destination_pre_balance = adapter.token_account_pre_balance(destination).cloned();
+
         // Получаем authority (быстрый доступ к accounts)
         const TRANSFER: u8 = 3;
         const TRANSFER_CHECKED: u8 = 12;
@@ -331,28 +644,33 @@ impl TransactionUtils {
         
         Some(TransferData {
             transfer_type: transfer_type.to_string(),
-            program_id: program_id.to_string(),
+            program_id: program_id.into(),
             info: crate::types::TransferInfo {
                 authority,
-                destination: destination.to_string(),
-                destination_owner,
-                mint,
-                source: source.to_string(),
+                destination: destination.into(),
+                destination_owner: destination_owner.map(AccountKey::from),
+                mint: mint.into(),
+                source: source.into(),
                 token_amount: crate::types::TokenAmount {
                     amount: amount_buf,
                     decimals,
                     ui_amount: Some(amount_ui),
+                    ui_amount_string: None,
                 },
                 source_balance,
-                source_pre_balance: None,
+                source_pre_balance,
                 destination_balance,
-                destination_pre_balance: None,
+                destination_pre_balance,
                 sol_balance_change: None,
             },
             idx: idx.to_string(),
             timestamp: adapter.block_time(),
             signature: adapter.signature().to_string(),
             is_fee: false,
+            fee_amount: None,
+            instruction_index: 0,
+            inner_index: None,
+            order_key: None,
         })
     }
 
@@ -393,9 +711,8 @@ impl TransactionUtils {
         }
 
         // Суммируем все transfers с каждым mint
-        let mut input_amount = 0.0;
+        // в raw: f64-сумма теряет младшие разряды у токенов с большим supply
         let mut input_amount_raw = 0u128;
-        let mut output_amount = 0.0;
         let mut output_amount_raw = 0u128;
         let mut input_decimals = 0u8;
         let mut output_decimals = 0u8;
@@ -404,23 +721,15 @@ impl TransactionUtils {
 
         for transfer in transfers {
             if transfer.info.mint == input_mint {
-                let amount = transfer.info.token_amount.ui_amount.unwrap_or_else(|| {
-                    transfer.info.token_amount.amount.parse::<f64>().unwrap_or(0.0)
-                });
-                let amount_raw = transfer.info.token_amount.amount.parse::<u128>().unwrap_or(0);
-                input_amount += amount;
-                input_amount_raw += amount_raw;
+                // пул получает сумму за вычетом Token-2022 transfer fee
+                input_amount_raw += transfer.net_amount_raw();
                 input_decimals = transfer.info.token_amount.decimals;
                 if input_transfer_ref.is_none() {
                     input_transfer_ref = Some(transfer);
                 }
             } else if transfer.info.mint == output_mint {
-                let amount = transfer.info.token_amount.ui_amount.unwrap_or_else(|| {
-                    transfer.info.token_amount.amount.parse::<f64>().unwrap_or(0.0)
-                });
-                let amount_raw = transfer.info.token_amount.amount.parse::<u128>().unwrap_or(0);
-                output_amount += amount;
-                output_amount_raw += amount_raw;
+                // получено за вычетом Token-2022 transfer fee
+                output_amount_raw += transfer.net_amount_raw();
                 output_decimals = transfer.info.token_amount.decimals;
                 if output_transfer_ref.is_none() {
                     output_transfer_ref = Some(transfer);
@@ -435,7 +744,7 @@ impl TransactionUtils {
             .program_id
             .as_ref()
             .cloned()
-            .unwrap_or_else(|| input.program_id.clone());
+            .unwrap_or_else(|| input.program_id.to_string());
         let amm = dex_info
             .amm
             .as_ref()
@@ -444,15 +753,15 @@ impl TransactionUtils {
 
         let input_token = crate::types::TokenInfo {
             mint: input_mint.to_string(),
-            amount: input_amount,
+            amount: DecimalAmount::new(input_amount_raw, input_decimals).to_f64(),
             amount_raw: input_amount_raw.to_string(),
             decimals: input_decimals,
-            authority: input.info.authority.clone(),
-            destination: Some(input.info.destination.clone()),
-            destination_owner: input.info.destination_owner.clone(),
+            authority: input.info.authority.clone().map(String::from),
+            destination: Some(input.info.destination.to_string()),
+            destination_owner: input.info.destination_owner.clone().map(String::from),
             destination_balance: input.info.destination_balance.clone(),
             destination_pre_balance: input.info.destination_pre_balance.clone(),
-            source: Some(input.info.source.clone()),
+            source: Some(input.info.source.to_string()),
             source_balance: input.info.source_balance.clone(),
             source_pre_balance: input.info.source_pre_balance.clone(),
             destination_balance_change: None,
@@ -462,15 +771,15 @@ impl TransactionUtils {
 
         let output_token = crate::types::TokenInfo {
             mint: output_mint.to_string(),
-            amount: output_amount,
+            amount: DecimalAmount::new(output_amount_raw, output_decimals).to_f64(),
             amount_raw: output_amount_raw.to_string(),
             decimals: output_decimals,
-            authority: output.info.authority.clone(),
-            destination: Some(output.info.destination.clone()),
-            destination_owner: output.info.destination_owner.clone(),
+            authority: output.info.authority.clone().map(String::from),
+            destination: Some(output.info.destination.to_string()),
+            destination_owner: output.info.destination_owner.clone().map(String::from),
             destination_balance: output.info.destination_balance.clone(),
             destination_pre_balance: output.info.destination_pre_balance.clone(),
-            source: Some(output.info.source.clone()),
+            source: Some(output.info.source.to_string()),
             source_balance: output.info.source_balance.clone(),
             source_pre_balance: output.info.source_pre_balance.clone(),
             destination_balance_change: None,
@@ -479,14 +788,14 @@ impl TransactionUtils {
         };
 
         Some(TradeInfo {
-            trade_type: TradeType::Swap,
+            trade_type: get_trade_type(input_mint, output_mint),
             pool: Vec::new(),
             input_token,
             output_token,
             slippage_bps: None,
             fee: None,
             fees: Vec::new(),
-            user: Some(input.info.source.clone()),
+            user: Some(signer.to_string()),
             program_id: Some(program_id),
             amm: Some(amm),
             amms: None,
@@ -496,6 +805,22 @@ impl TransactionUtils {
             signature: self.adapter.signature().to_string(),
             idx: input.idx.clone(),
             signer: Some(self.adapter.signers().to_vec()),
+            dlmm: None,
+            clob: None,
+            liquidity_role: None,
+            price_quote_per_base: None,
+            value_usd: None,
+            input_symbol: None,
+            output_symbol: None,
+            instruction_index: 0,
+            inner_index: None,
+            pool_reserves: None,
+            order_key: None,
+            side: None,
+            authority: None,
+            flags: Vec::new(),
+            router: None,
+            confidence: TradeConfidence::Unverified,
         })
     }
 
@@ -511,17 +836,59 @@ impl TransactionUtils {
                 dex: None,
                 fee_type: None,
                 recipient: None,
+                kind: None,
             });
         }
         
         trade
     }
 
+    /// `pool_reserves` по postTokenBalances vault'ов: input уходит в
+    /// `input_token.destination`, output приходит из `output_token.source`.
+    pub fn attach_pool_reserves(&self, mut trade: TradeInfo) -> TradeInfo {
+        let vaults = [
+            trade.input_token.destination.clone().unwrap_or_default(),
+            trade.output_token.source.clone().unwrap_or_default(),
+        ];
+        if let [Some(input), Some(output)] = &self.adapter.get_token_account_balance(&vaults)[..] {
+            trade.pool_reserves = Some((input.clone(), output.clone()));
+        }
+        trade
+    }
+
+    /// `balanceChange` ног как в TS: изменение баланса пользователя по mint'у
+    /// (для входа — по модулю), иначе `amount_raw`.
     pub fn attach_token_transfer_info(
         &self,
-        trade: TradeInfo,
+        mut trade: TradeInfo,
         _transfer_actions: &TransferMap,
     ) -> TradeInfo {
+        use crate::core::constants::TOKENS;
+
+        let user = trade.user.clone().unwrap_or_default();
+        let sol_changes = self.adapter.get_account_sol_balance_changes(false);
+        let token_changes = self.adapter.get_account_token_balance_changes(true);
+        let change = |mint: &str| {
+            if mint == TOKENS.SOL {
+                sol_changes.get(&user).map(|balance| balance.change)
+            } else {
+                token_changes
+                    .get(&user)
+                    .and_then(|mints| mints.get(mint))
+                    .map(|balance| balance.change)
+            }
+        };
+
+        let input = &mut trade.input_token;
+        input.balance_change = Some(match change(&input.mint) {
+            Some(change) => change.unsigned_abs().to_string(),
+            None => input.amount_raw.clone(),
+        });
+        let output = &mut trade.output_token;
+        output.balance_change = Some(match change(&output.mint) {
+            Some(change) => change.to_string(),
+            None => output.amount_raw.clone(),
+        });
         trade
     }
 
@@ -553,16 +920,16 @@ impl TransactionUtils {
         });
 
         crate::types::TokenInfo {
-            mint: transfer.info.mint.clone(),
+            mint: transfer.info.mint.to_string(),
             amount,
             amount_raw: transfer.info.token_amount.amount.clone(),
             decimals: transfer.info.token_amount.decimals,
-            authority: transfer.info.authority.clone(),
-            destination: Some(transfer.info.destination.clone()),
-            destination_owner: transfer.info.destination_owner.clone(),
+            authority: transfer.info.authority.clone().map(String::from),
+            destination: Some(transfer.info.destination.to_string()),
+            destination_owner: transfer.info.destination_owner.clone().map(String::from),
             destination_balance: transfer.info.destination_balance.clone(),
             destination_pre_balance: transfer.info.destination_pre_balance.clone(),
-            source: Some(transfer.info.source.clone()),
+            source: Some(transfer.info.source.to_string()),
             source_balance: transfer.info.source_balance.clone(),
             source_pre_balance: transfer.info.source_pre_balance.clone(),
             destination_balance_change: None,
@@ -571,3 +938,248 @@ impl TransactionUtils {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ParseConfig;
+    use crate::core::constants::{dex_programs, TOKENS};
+    use crate::types::{InnerInstruction, SolanaInstruction, SolanaTransaction, TokenAmount, TokenBalance};
+    use base64_simd::STANDARD;
+
+    const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+    const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+    const TOKEN_2022_PROGRAM: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+    const ATA_PROGRAM: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+    const TOKEN_ACCOUNT_RENT: u64 = 2_039_280;
+
+    fn ix(program_id: &str, accounts: &[&str], data: &[u8]) -> SolanaInstruction {
+        SolanaInstruction {
+            program_id: program_id.into(),
+            accounts: accounts.iter().map(|account| (*account).into()).collect(),
+            data: STANDARD.encode_to_string(data),
+        }
+    }
+
+    fn system_transfer(from: &str, to: &str, lamports: u64) -> SolanaInstruction {
+        let mut data = 2u32.to_le_bytes().to_vec();
+        data.extend(lamports.to_le_bytes());
+        ix(SYSTEM_PROGRAM, &[from, to], &data)
+    }
+
+    fn create_account(from: &str, new: &str, lamports: u64, space: u64) -> SolanaInstruction {
+        let mut data = 0u32.to_le_bytes().to_vec();
+        data.extend(lamports.to_le_bytes());
+        data.extend(space.to_le_bytes());
+        data.extend([0u8; 32]);
+        ix(SYSTEM_PROGRAM, &[from, new], &data)
+    }
+
+    fn wsol_balance(account: &str, amount: u64) -> TokenBalance {
+        TokenBalance {
+            account: account.to_string(),
+            mint: TOKENS.SOL.to_string(),
+            owner: Some("user".to_string()),
+            ui_token_amount: TokenAmount::new(amount.to_string(), 9, None),
+        }
+    }
+
+    fn transfer_checked(program_id: &str, accounts: &[&str], amount: u64) -> SolanaInstruction {
+        let mut data = vec![12];
+        data.extend(amount.to_le_bytes());
+        data.push(6);
+        ix(program_id, accounts, &data)
+    }
+
+    fn token_balance(account: &str, mint: &str, amount: u64) -> TokenBalance {
+        TokenBalance {
+            account: account.to_string(),
+            mint: mint.to_string(),
+            owner: Some("owner".to_string()),
+            ui_token_amount: TokenAmount::new(amount.to_string(), 6, None),
+        }
+    }
+
+    fn transfers(tx: SolanaTransaction) -> TransferMap {
+        let adapter = TransactionAdapter::new(tx, ParseConfig::default());
+        TransactionUtils::create_transfers_from_instructions(&adapter)
+    }
+
+    fn amounts(actions: &TransferMap, key: &str) -> Vec<(String, String)> {
+        actions
+            .get(key)
+            .map(|transfers| {
+                transfers
+                    .iter()
+                    .map(|t| (t.transfer_type.clone(), t.info.token_amount.amount.clone()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn wrap_counts_only_lamports_above_rent() {
+        let tx = SolanaTransaction {
+            signers: vec!["user".to_string()],
+            instructions: vec![
+                create_account("user", "user-wsol", TOKEN_ACCOUNT_RENT + 1_000_000_000, 165),
+                create_account("user", "user-mint-account", TOKEN_ACCOUNT_RENT, 165),
+            ],
+            ..Default::default()
+        };
+
+        let actions = transfers(tx);
+        assert_eq!(
+            amounts(&actions, "transfer"),
+            vec![("createAccount".to_string(), "1000000000".to_string())]
+        );
+    }
+
+    #[test]
+    fn unwrap_uses_wsol_balance_regardless_of_account_size() {
+        // Token-2022 аккаунт с расширениями: rent больше, чем у 165-байтового
+        let tx = SolanaTransaction {
+            signers: vec!["user".to_string()],
+            instructions: vec![ix(TOKEN_PROGRAM, &["user-wsol", "user", "user"], &[9])],
+            pre_token_balances: vec![wsol_balance("user-wsol", 500_000_000)],
+            ..Default::default()
+        };
+
+        let actions = transfers(tx);
+        assert_eq!(
+            amounts(&actions, "transfer"),
+            vec![("closeAccount".to_string(), "500000000".to_string())]
+        );
+    }
+
+    #[test]
+    fn empty_wsol_close_is_dropped() {
+        let tx = SolanaTransaction {
+            signers: vec!["user".to_string()],
+            instructions: vec![ix(TOKEN_PROGRAM, &["user-wsol", "user", "user"], &[9])],
+            pre_token_balances: vec![wsol_balance("user-wsol", 0)],
+            ..Default::default()
+        };
+
+        assert!(transfers(tx).is_empty());
+    }
+
+    #[test]
+    fn pumpfun_sol_leg_is_attributed_to_program() {
+        let tx = SolanaTransaction {
+            signers: vec!["user".to_string()],
+            instructions: vec![ix(dex_programs::PUMP_FUN, &["user"], &[])],
+            inner_instructions: vec![InnerInstruction {
+                index: 0,
+                instructions: vec![
+                    system_transfer("user", "bonding-curve", 100_000_000),
+                    system_transfer("user", "fee-recipient", 1_000_000),
+                ],
+                stack_heights: vec![Some(2), Some(2)],
+            }],
+            ..Default::default()
+        };
+
+        let actions = transfers(tx);
+        let key = format!("{}:0", dex_programs::PUMP_FUN);
+        assert_eq!(
+            amounts(&actions, &key),
+            vec![
+                ("transfer".to_string(), "100000000".to_string()),
+                ("transfer".to_string(), "1000000".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn ata_creation_inside_swap_is_not_a_leg() {
+        let tx = SolanaTransaction {
+            signers: vec!["user".to_string()],
+            instructions: vec![ix(dex_programs::PUMP_FUN, &["user"], &[])],
+            inner_instructions: vec![InnerInstruction {
+                index: 0,
+                instructions: vec![
+                    ix(ATA_PROGRAM, &["user", "user-ata"], &[1]),
+                    // ATA уже с lamports: программа доплачивает rent transfer'ом
+                    system_transfer("user", "user-ata", 39_280),
+                    ix(TOKEN_PROGRAM, &["user-ata"], &[18]),
+                    ix(ATA_PROGRAM, &["user", "other-ata"], &[1]),
+                    create_account("user", "other-ata", TOKEN_ACCOUNT_RENT, 165),
+                    system_transfer("user", "bonding-curve", 100_000_000),
+                ],
+                stack_heights: vec![Some(2), Some(3), Some(3), Some(2), Some(3), Some(2)],
+            }],
+            ..Default::default()
+        };
+
+        let actions = transfers(tx);
+        let key = format!("{}:0", dex_programs::PUMP_FUN);
+        assert_eq!(
+            amounts(&actions, &key),
+            vec![("transfer".to_string(), "100000000".to_string())]
+        );
+        assert_eq!(actions.len(), 1);
+    }
+
+    /// user отдаёт 1000 FEE (Token-2022, 1% удерживается у получателя) и получает 500 B.
+    fn fee_mint_swap() -> SolanaTransaction {
+        SolanaTransaction {
+            signers: vec!["user".to_string()],
+            instructions: vec![
+                transfer_checked(TOKEN_2022_PROGRAM, &["user-fee", "FEE", "vault-fee", "user"], 1_000),
+                transfer_checked(TOKEN_PROGRAM, &["vault-b", "B", "user-b", "pool"], 500),
+            ],
+            pre_token_balances: vec![
+                token_balance("user-fee", "FEE", 1_000),
+                token_balance("vault-fee", "FEE", 10_000),
+                token_balance("vault-b", "B", 5_000),
+            ],
+            post_token_balances: vec![
+                token_balance("user-fee", "FEE", 0),
+                token_balance("vault-fee", "FEE", 10_990),
+                token_balance("vault-b", "B", 4_500),
+                token_balance("user-b", "B", 500),
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn withheld_fee_of_plain_transfer_checked_comes_from_balances() {
+        let actions = transfers(fee_mint_swap());
+        let fees: Vec<Option<String>> = actions["transfer"]
+            .iter()
+            .map(|t| t.fee_amount.as_ref().map(|fee| fee.amount.clone()))
+            .collect();
+        assert_eq!(fees, vec![Some("10".to_string()), None]);
+    }
+
+    #[test]
+    fn fee_is_not_guessed_for_several_incoming_transfers() {
+        let mut tx = fee_mint_swap();
+        tx.instructions[0] =
+            transfer_checked(TOKEN_2022_PROGRAM, &["user-fee", "FEE", "vault-fee", "user"], 600);
+        tx.instructions.insert(
+            1,
+            transfer_checked(TOKEN_2022_PROGRAM, &["user-fee", "FEE", "vault-fee", "user"], 400),
+        );
+
+        let actions = transfers(tx);
+        assert!(actions["transfer"].iter().all(|t| t.fee_amount.is_none()));
+    }
+
+    #[test]
+    fn swap_input_leg_uses_net_amount() {
+        let tx = fee_mint_swap();
+        let adapter = Arc::new(TransactionAdapter::new(tx, ParseConfig::default()));
+        let actions = TransactionUtils::create_transfers_from_instructions(&adapter);
+        let utils = TransactionUtils::new(adapter);
+
+        let trade = utils
+            .process_swap_data(&actions["transfer"], &DexInfo::default())
+            .unwrap();
+        assert_eq!(trade.input_token.mint, "FEE");
+        assert_eq!(trade.input_token.amount_raw, "990");
+        assert_eq!(trade.output_token.amount_raw, "500");
+    }
+}
@@ -1,14 +1,20 @@
 use crate::core::constants::dex_program_names;
+use crate::core::error::ParserError;
+use crate::types::{ClassifiedInstruction, TransferData, TransferMap};
 use base64_simd::STANDARD;
 
 /// Get instruction data bytes from a SolanaInstruction.
 /// Decodes base64 string to bytes. Fast path: no caching, no logging, no fallbacks.
+/// Позиция инструкции здесь неизвестна: `idx` в ошибке заполняет вызывающий через
+/// [`ParserError::at`].
 #[inline(always)]
-pub fn get_instruction_data(instruction: &crate::types::SolanaInstruction) -> Vec<u8> {
+pub fn get_instruction_data(instruction: &crate::types::SolanaInstruction) -> Result<Vec<u8>, ParserError> {
     if instruction.data.is_empty() {
-        return Vec::new();
+        return Ok(Vec::new());
     }
-    STANDARD.decode_to_vec(&instruction.data).expect("base64 decode failed")
+    STANDARD.decode_to_vec(&instruction.data).map_err(|err| {
+        ParserError::instruction_data(instruction.program_id.as_str(), format!("invalid base64: {err}"))
+    })
 }
 
 /// Get instruction data bytes from zero-copy instruction (zero-copy, no allocation)
@@ -23,3 +29,67 @@ pub fn get_program_name(program_id: &str) -> &'static str {
     dex_program_names::name(program_id)
 }
 
+
+/// Порядок исполнения: outer-инструкция, затем её inner по возрастанию.
+/// Классификатор отдаёт сначала все outer, потом все inner.
+pub fn sort_by_execution_order(instructions: &mut [ClassifiedInstruction]) {
+    instructions.sort_by_key(|ix| (ix.outer_index, ix.inner_index.map_or(0, |inner| inner + 1)));
+}
+
+/// `(instruction_index, inner_index)` из `idx` вида `"3"`, `"3-1"` или `"<signer>-3-1"`
+/// (pool events с префиксом signer'а). Нечисловой idx — `(0, None)`.
+pub fn parse_idx(idx: &str) -> (u16, Option<u16>) {
+    let mut parts = idx.rsplit('-').map(|part| part.parse::<u16>().ok());
+    match (parts.next().flatten(), parts.next().flatten()) {
+        (Some(inner), Some(outer)) => (outer, Some(inner)),
+        (Some(outer), None) => (outer, None),
+        _ => (0, None),
+    }
+}
+
+/// Все token-действия инструкции (transfer, mintTo, burn, ...): по ключу
+/// `program:outer[-inner]`, если карта построена из инструкций, иначе — действия
+/// программы с тем же outer-индексом.
+pub fn get_instruction_actions<'a>(
+    transfer_actions: &'a TransferMap,
+    program_id: &str,
+    outer_index: usize,
+    inner_index: Option<usize>,
+) -> Vec<&'a TransferData> {
+    let key = match inner_index {
+        Some(inner) => format!("{program_id}:{outer_index}-{inner}"),
+        None => format!("{program_id}:{outer_index}"),
+    };
+    if let Some(transfers) = transfer_actions.get(&key) {
+        return transfers.iter().collect();
+    }
+
+    let outer_prefix = outer_index.to_string();
+    transfer_actions
+        .get(program_id)
+        .map(|transfers| {
+            transfers
+                .iter()
+                .filter(|t| t.idx.split('-').next() == Some(outer_prefix.as_str()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Только transfer/transferChecked/transferCheckedWithFee из [`get_instruction_actions`].
+pub fn get_instruction_transfers<'a>(
+    transfer_actions: &'a TransferMap,
+    program_id: &str,
+    outer_index: usize,
+    inner_index: Option<usize>,
+) -> Vec<&'a TransferData> {
+    get_instruction_actions(transfer_actions, program_id, outer_index, inner_index)
+        .into_iter()
+        .filter(|t| {
+            matches!(
+                t.transfer_type.as_str(),
+                "transfer" | "transferChecked" | "transferCheckedWithFee"
+            )
+        })
+        .collect()
+}
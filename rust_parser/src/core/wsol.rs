@@ -0,0 +1,149 @@
+//! `ParseConfig::normalize_wsol`: WSOL-аккаунты схлопываются в кошелёк владельца.
+
+use std::collections::HashMap;
+
+use crate::core::constants::TOKENS;
+use crate::types::{ParseResult, TokenInfo, TransferData};
+
+/// Ноги trades и transfers через WSOL-аккаунт переписываются на его владельца, так что
+/// SOL виден как движение кошелька. Wrap (SOL владельца на свой WSOL-аккаунт) и unwrap
+/// (`closeAccount` обратно владельцу) удаляются. `wsol_owner` — владелец аккаунта,
+/// если это WSOL token account; для временных аккаунтов владелец берётся из `closeAccount`.
+pub fn normalize_wsol(result: &mut ParseResult, wsol_owner: impl Fn(&str) -> Option<String>) {
+    let mut owners: HashMap<String, String> = HashMap::new();
+    for transfer in result
+        .transfers
+        .iter()
+        .filter(|t| t.info.mint == TOKENS.SOL)
+    {
+        for account in [&transfer.info.source, &transfer.info.destination] {
+            if let Some(owner) = wsol_owner(account) {
+                owners.insert(account.to_string(), owner);
+            }
+        }
+        if let (true, Some(owner)) = (
+            transfer.transfer_type == "closeAccount",
+            transfer.info.authority.as_ref(),
+        ) {
+            owners
+                .entry(transfer.info.source.to_string())
+                .or_insert_with(|| owner.to_string());
+        }
+    }
+    for trade in result.trades.iter().chain(result.aggregate_trade.as_ref()) {
+        for token in [&trade.input_token, &trade.output_token] {
+            let accounts = token.source.iter().chain(&token.destination);
+            for account in accounts.filter(|_| token.mint == TOKENS.SOL) {
+                if let Some(owner) = wsol_owner(account) {
+                    owners.insert(account.clone(), owner);
+                }
+            }
+        }
+    }
+    if owners.is_empty() {
+        return;
+    }
+
+    result
+        .transfers
+        .retain(|transfer| !is_wrap_leg(transfer, &owners));
+    for transfer in &mut result.transfers {
+        if transfer.info.mint != TOKENS.SOL {
+            continue;
+        }
+        if let Some(owner) = owners.get(transfer.info.source.as_str()) {
+            transfer.info.source = owner.as_str().into();
+        }
+        if let Some(owner) = owners.get(transfer.info.destination.as_str()) {
+            transfer.info.destination = owner.as_str().into();
+            transfer.info.destination_owner = None;
+        }
+    }
+    for trade in result
+        .trades
+        .iter_mut()
+        .chain(result.aggregate_trade.as_mut())
+    {
+        rewrite_token(&mut trade.input_token, &owners);
+        rewrite_token(&mut trade.output_token, &owners);
+    }
+}
+
+/// SOL между WSOL-аккаунтом и его же владельцем в любую сторону.
+fn is_wrap_leg(transfer: &TransferData, owners: &HashMap<String, String>) -> bool {
+    let info = &transfer.info;
+    let owned_by = |account: &str, wallet: &str| owners.get(account).is_some_and(|o| o == wallet);
+    info.mint == TOKENS.SOL
+        && (owned_by(&info.destination, &info.source) || owned_by(&info.source, &info.destination))
+}
+
+fn rewrite_token(token: &mut TokenInfo, owners: &HashMap<String, String>) {
+    if token.mint != TOKENS.SOL {
+        return;
+    }
+    if let Some(owner) = token.source.as_ref().and_then(|source| owners.get(source)) {
+        token.source = Some(owner.clone());
+    }
+    if let Some(owner) = token.destination.as_ref().and_then(|dest| owners.get(dest)) {
+        token.destination = Some(owner.clone());
+        token.destination_owner = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{TokenAmount, TradeInfo, TransferInfo};
+
+    fn sol(transfer_type: &str, source: &str, destination: &str, lamports: u64) -> TransferData {
+        TransferData {
+            transfer_type: transfer_type.to_string(),
+            program_id: "11111111111111111111111111111111".into(),
+            info: TransferInfo {
+                mint: TOKENS.SOL.into(),
+                source: source.into(),
+                destination: destination.into(),
+                authority: Some("user".into()),
+                token_amount: TokenAmount::new(lamports.to_string(), 9, None),
+                ..Default::default()
+            },
+            idx: "0".to_string(),
+            timestamp: 0,
+            signature: String::new(),
+            is_fee: false,
+            fee_amount: None,
+            instruction_index: 0,
+            inner_index: None,
+            order_key: None,
+        }
+    }
+
+    #[test]
+    fn collapses_wrap_and_unwrap_legs_into_wallet() {
+        let mut result = ParseResult::new();
+        result.transfers = vec![
+            sol("transfer", "user", "wsol-ata", 1_000),
+            sol("transfer", "wsol-ata", "pool-vault", 1_000),
+            sol("transfer", "pool-vault", "wsol-ata", 500),
+            sol("closeAccount", "wsol-ata", "user", 500),
+        ];
+        result.trades = vec![TradeInfo {
+            input_token: TokenInfo {
+                mint: TOKENS.SOL.to_string(),
+                source: Some("wsol-ata".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }];
+
+        normalize_wsol(&mut result, |_| None);
+
+        let legs: Vec<_> = result
+            .transfers
+            .iter()
+            .map(|t| (t.info.source.as_str(), t.info.destination.as_str()))
+            .collect();
+        assert_eq!(legs, [("user", "pool-vault"), ("pool-vault", "user")]);
+        assert_eq!(result.trades[0].input_token.source.as_deref(), Some("user"));
+    }
+}
@@ -21,9 +21,12 @@
 //! Use `ZcAdapter` for maximum performance when working directly with zero-copy structures.
 //! Use `ZcTransactionAdapter` for compatibility with existing parsers that expect `SolanaInstruction`.
 
+use std::collections::HashMap;
+
 use crate::config::ParseConfig;
+use crate::core::meta_compat;
 use crate::core::zero_copy::{ZcInstruction, ZcTransaction};
-use crate::types::TransactionStatus;
+use crate::types::{BalanceChange, TransactionStatus};
 use serde_json::Value;
 
 /// TRUE zero-copy adapter - NO allocations, NO copies
@@ -84,9 +87,13 @@ impl<'a> ZcAdapter<'a> {
     }
     
     /// Get account key by index (zero-copy: references buffer)
+    /// Indices past the static keys resolve to loaded addresses (ALT)
     #[inline(always)]
     pub fn account_key(&self, index: usize) -> Option<PubkeyRef<'a>> {
-        self.tx.message.get_account_key(index)
+        self.tx.message.get_account_key(index).or_else(|| {
+            let loaded = index.checked_sub(self.tx.message.account_keys_len())?;
+            self.tx.loaded_addresses.get(loaded)
+        })
     }
     
     /// Get all account keys (zero-copy: references to buffer)
@@ -192,29 +199,25 @@ impl<'a> ZcAdapter<'a> {
     
     /// Get compute units from meta (zero-copy: reads from JSON)
     pub fn compute_units(&self) -> u64 {
-        self.meta
-            .and_then(|m| m.get("computeUnitsConsumed").or_else(|| m.get("computeUnits")))
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0)
+        self.meta.map(meta_compat::compute_units).unwrap_or(0)
     }
     
     /// Get transaction status from meta (zero-copy: reads from JSON)
     pub fn tx_status(&self) -> TransactionStatus {
-        if let Some(meta) = self.meta {
-            if let Some(err_val) = meta.get("err") {
-                if err_val.is_null() {
-                    TransactionStatus::Success
-                } else {
-                    TransactionStatus::Failed
-                }
-            } else {
-                TransactionStatus::Success
-            }
+        if self.meta.and_then(meta_compat::meta_error).is_some() {
+            TransactionStatus::Failed
         } else {
             TransactionStatus::Success
         }
     }
     
+    /// `meta.err` as compact JSON, `None` for successful transactions
+    pub fn tx_error(&self) -> Option<String> {
+        self.meta
+            .and_then(meta_compat::meta_error)
+            .map(|err_val| err_val.to_string())
+    }
+
     /// Get inner instructions from meta (lazy: parses from JSON on demand)
     /// Returns zero-copy references to instruction data
     /// NOTE: This requires parsing JSON, but returns references to string data in JSON
@@ -247,6 +250,60 @@ impl<'a> ZcAdapter<'a> {
         &self.tx.loaded_addresses
     }
     
+    /* ----------------------- Balance changes of the signer ----------------------- */
+
+    /// SOL-изменение signer'а (индекс 0) из `preBalances`/`postBalances`; `None`, если
+    /// баланс не изменился — как `meta.sol_balance_changes` owned-пути.
+    pub fn signer_sol_balance_change(&self) -> Option<BalanceChange> {
+        self.signer()?;
+        let lamports = |balances: Option<&Value>| {
+            balances.and_then(|v| v.get(0)).and_then(Value::as_i64).unwrap_or(0) as i128
+        };
+        let pre = lamports(self.pre_balances());
+        let post = lamports(self.post_balances());
+        (pre != post).then_some(BalanceChange {
+            pre,
+            post,
+            change: post - pre,
+        })
+    }
+
+    /// Изменения token-балансов signer'а по mint — та же логика, что у
+    /// `TransactionAdapter::signer_token_balance_changes` (закрытые аккаунты -> post 0).
+    /// Аллоцирует base58 signer'а и ключи результата.
+    pub fn signer_token_balance_changes(&self) -> Option<HashMap<String, BalanceChange>> {
+        let signer = bs58::encode(self.signer()?).into_string();
+        let signer_amounts = |balances: Option<&'a Value>| {
+            let signer = signer.as_str();
+            balances
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter(move |b| b.get("owner").and_then(Value::as_str) == Some(signer))
+                .filter_map(|b| {
+                    let mint = b.get("mint").and_then(Value::as_str).filter(|m| !m.is_empty())?;
+                    let amount = b.pointer("/uiTokenAmount/amount")?.as_str()?;
+                    Some((mint, amount.parse::<i128>().ok()?))
+                })
+        };
+
+        let mut pre_map: HashMap<&str, i128> =
+            signer_amounts(self.pre_token_balances()).collect();
+        let mut changes = HashMap::new();
+        for (mint, post) in signer_amounts(self.post_token_balances()) {
+            let pre = pre_map.remove(mint).unwrap_or(0);
+            if post != pre {
+                let change = BalanceChange { pre, post, change: post - pre };
+                changes.insert(mint.to_string(), change);
+            }
+        }
+        for (mint, pre) in pre_map.into_iter().filter(|(_, pre)| *pre != 0) {
+            let change = BalanceChange { pre, post: 0, change: -pre };
+            changes.insert(mint.to_string(), change);
+        }
+        (!changes.is_empty()).then_some(changes)
+    }
+
     /* ----------------------- Helper methods for working with account keys ----------------------- */
     
     /// Find account key index by pubkey (zero-copy: compares 32-byte arrays)
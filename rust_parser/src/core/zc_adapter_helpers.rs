@@ -116,8 +116,8 @@ impl ZcCachedBalanceMaps {
         // Add transfers to transfer map
         for (_, transfers) in transfer_actions {
             for transfer in transfers {
-                cached.transfer_map.insert(transfer.info.source.clone(), transfer.clone());
-                cached.transfer_map.insert(transfer.info.destination.clone(), transfer.clone());
+                cached.transfer_map.insert(transfer.info.source.to_string(), transfer.clone());
+                cached.transfer_map.insert(transfer.info.destination.to_string(), transfer.clone());
             }
         }
         
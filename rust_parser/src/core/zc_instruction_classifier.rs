@@ -78,8 +78,9 @@ impl<'a> ZcInstructionClassifier<'a> {
     /// 
     /// # Returns
     /// Classifier that groups instructions by program ID
+    #[cfg_attr(feature = "parse-metrics", tracing::instrument(level = "debug", skip_all))]
     pub fn new(adapter: &'a ZcAdapter<'a>) -> Self {
-        #[cfg(debug_assertions)]
+        #[cfg(feature = "parse-metrics")]
         let t0 = std::time::Instant::now();
         
         // Pre-allocate with estimated capacity
@@ -123,7 +124,7 @@ impl<'a> ZcInstructionClassifier<'a> {
             }
         }
         
-        #[cfg(debug_assertions)]
+        #[cfg(feature = "parse-metrics")]
         let t1 = std::time::Instant::now();
 
         // INNER instructions - ZERO-COPY: parse from JSON on demand
@@ -132,8 +133,8 @@ impl<'a> ZcInstructionClassifier<'a> {
         // They can be processed separately if needed
         // TODO: Add support for inner instructions from meta JSON
         
-        #[cfg(debug_assertions)]
-        {
+        #[cfg(feature = "parse-metrics")]
+        if adapter.config().verbose_timing {
             let t2 = std::time::Instant::now();
             tracing::debug!(
                 "ZcInstructionClassifier: processed {} outer instructions",
@@ -145,7 +146,7 @@ impl<'a> ZcInstructionClassifier<'a> {
                 adapter.instructions().len(),
                 (t2 - t0).as_secs_f64() * 1_000_000.0,
             );
-            tracing::info!(
+            tracing::debug!(
                 "ZcInstructionClassifier: found {} unique program IDs",
                 order.len()
             );
@@ -5,11 +5,16 @@
 
 use std::collections::HashMap;
 
+use once_cell::sync::OnceCell;
+
 use crate::config::ParseConfig;
 use crate::core::constants::TOKENS;
+use crate::core::meta_compat;
+use crate::core::zc_adapter::ZcAdapter;
+use crate::core::zc_transaction_utils::ZcTransactionUtils;
 use crate::core::zero_copy::ZcTransaction;
 use crate::types::{
-    BalanceChange, InnerInstruction, SolanaInstruction, TokenAmount, TokenBalance, TokenInfo,
+    AccountKey, BalanceChange, InnerInstruction, SolanaInstruction, TokenAmount, TokenBalance, TokenInfo,
     PoolEventBase, PoolEventType, TransactionStatus, TransferData, TransferMap,
 };
 use bs58;
@@ -44,6 +49,10 @@ pub struct ZcTransactionAdapter<'a> {
     
     // Cached signers (computed once)
     cached_signers: Vec<String>,
+    
+    // Transfers строятся из инструкций при первом запросе (ключи — как на owned пути)
+    transfer_actions: OnceCell<TransferMap>,
+    transfers: OnceCell<Vec<TransferData>>,
 }
 
 /// Cached transaction meta (parsed from JSON once)
@@ -110,6 +119,8 @@ impl<'a> ZcTransactionAdapter<'a> {
             post_token_balances,
             cached_meta,
             cached_signers,
+            transfer_actions: OnceCell::new(),
+            transfers: OnceCell::new(),
         }
     }
     
@@ -197,7 +208,7 @@ impl<'a> ZcTransactionAdapter<'a> {
             .and_then(|s| s.instructions.get(inner_index))
     }
     
-    pub fn get_instruction_accounts<'b>(&self, instruction: &'b SolanaInstruction) -> &'b [String] {
+    pub fn get_instruction_accounts<'b>(&self, instruction: &'b SolanaInstruction) -> &'b [AccountKey] {
         &instruction.accounts
     }
     
@@ -206,7 +217,7 @@ impl<'a> ZcTransactionAdapter<'a> {
     }
     
     pub fn get_instruction_type(&self, instruction: &SolanaInstruction) -> Option<String> {
-        let data = crate::core::utils::get_instruction_data(instruction);
+        let data = crate::core::utils::get_instruction_data(instruction).ok()?;
         data.first().map(|b| b.to_string())
     }
     
@@ -353,7 +364,7 @@ impl<'a> ZcTransactionAdapter<'a> {
     }
     
     pub fn is_supported_token(&self, mint: &str) -> bool {
-        TOKENS.values().iter().any(|m| *m == mint)
+        TOKENS.values().contains(&mint) || self.config.quote_priority(mint).is_some()
     }
     
     pub fn signer_sol_balance_change(&self) -> Option<BalanceChange> {
@@ -539,17 +550,32 @@ impl<'a> ZcTransactionAdapter<'a> {
     
     /* ----------------------- transfers / transfer map ----------------------- */
     
+    /// Все переводы транзакции, отсортированные по `idx`.
     pub fn transfers(&self) -> &[TransferData] {
-        // NOTE: Transfers are created from instructions, not from meta
-        // This is handled by TransactionUtils::create_transfers_from_instructions
-        // For now, return empty slice (transfers will be created later)
-        &[]
+        self.transfers.get_or_init(|| {
+            let mut transfers: Vec<TransferData> =
+                self.transfer_map().values().flatten().cloned().collect();
+            transfers.sort_by(|a, b| a.idx.cmp(&b.idx));
+            transfers
+        })
     }
     
     pub fn get_transfer_actions(&self) -> TransferMap {
-        // NOTE: Transfers are created from instructions
-        // This is handled by TransactionUtils::create_transfers_from_instructions
-        HashMap::new()
+        self.transfer_map().clone()
+    }
+    
+    /// Transfer map без копирования, когда сам адаптер больше не нужен.
+    pub fn into_transfer_actions(self) -> TransferMap {
+        self.transfer_map();
+        self.transfer_actions.into_inner().unwrap_or_default()
+    }
+    
+    /// Transfer map; строится при первом обращении — ему нужны token maps адаптера.
+    fn transfer_map(&self) -> &TransferMap {
+        self.transfer_actions.get_or_init(|| {
+            let zc_adapter = ZcAdapter::new(self.zc_tx, self.meta_json, self.config.clone());
+            ZcTransactionUtils::create_transfers_from_instructions_zc(&zc_adapter, self)
+        })
     }
     
     pub fn get_pool_event_base(&self, r#type: PoolEventType, program_id: &str) -> PoolEventBase {
@@ -582,8 +608,12 @@ impl<'a> ZcTransactionAdapter<'a> {
                 let index = group.get("index").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
                 
                 let mut instructions = Vec::new();
+                let mut stack_heights = Vec::new();
                 if let Some(ixs) = group.get("instructions").and_then(|v| v.as_array()) {
                     for ix_val in ixs {
+                        stack_heights.push(
+                            ix_val.get("stackHeight").and_then(|v| v.as_u64()).map(|h| h as u32),
+                        );
                         let program_id = ix_val
                             .get("programId")
                             .and_then(|v| v.as_str())
@@ -597,16 +627,16 @@ impl<'a> ZcTransactionAdapter<'a> {
                             .unwrap_or("")
                             .to_string();
                         
-                        let accounts: Vec<String> = if let Some(acc_arr) =
+                        let accounts: Vec<AccountKey> = if let Some(acc_arr) =
                             ix_val.get("accounts").and_then(|v| v.as_array())
                         {
                             acc_arr
                                 .iter()
                                 .filter_map(|v| {
                                     if let Some(s) = v.as_str() {
-                                        Some(s.to_string())
+                                        Some(s.into())
                                     } else if let Some(idx) = v.as_u64() {
-                                        account_keys.get(idx as usize).cloned()
+                                        account_keys.get(idx as usize).map(AccountKey::from)
                                     } else {
                                         None
                                     }
@@ -629,7 +659,7 @@ impl<'a> ZcTransactionAdapter<'a> {
                             .unwrap_or_default();
                         
                         instructions.push(SolanaInstruction {
-                            program_id,
+                            program_id: program_id.into(),
                             accounts,
                             data,
                         });
@@ -640,6 +670,7 @@ impl<'a> ZcTransactionAdapter<'a> {
                     result.push(InnerInstruction {
                         index,
                         instructions,
+                        stack_heights,
                     });
                 }
             }
@@ -720,18 +751,10 @@ impl<'a> ZcTransactionAdapter<'a> {
         
         let fee = meta.get("fee").and_then(|v| v.as_u64()).unwrap_or(0);
         
-        let compute_units = meta
-            .get("computeUnitsConsumed")
-            .or_else(|| meta.get("computeUnits"))
-            .and_then(|v| v.as_u64())
-            .unwrap_or(0);
+        let compute_units = meta_compat::compute_units(meta);
         
-        let status = if let Some(err_val) = meta.get("err") {
-            if err_val.is_null() {
-                TransactionStatus::Success
-            } else {
-                TransactionStatus::Failed
-            }
+        let status = if meta_compat::meta_error(meta).is_some() {
+            TransactionStatus::Failed
         } else {
             TransactionStatus::Success
         };
@@ -932,10 +955,10 @@ impl<'a> ZcTransactionAdapter<'a> {
                     continue;
                 }
                 
-                let data = get_instruction_data(ix);
-                if data.is_empty() {
-                    continue;
-                }
+                let data = match get_instruction_data(ix) {
+                    Ok(data) if !data.is_empty() => data,
+                    _ => continue,
+                };
                 
                 let instruction_type = data[0];
                 let accounts_vec = &ix.accounts;
@@ -6,9 +6,20 @@
 use std::collections::HashMap;
 use once_cell::sync::Lazy;
 
-use crate::core::constants::{dex_program_names, SKIP_PROGRAM_IDS, SYSTEM_PROGRAMS};
+use crate::core::constants::{dex_program_names, SYSTEM_PROGRAMS, TOKENS};
+use crate::core::utils::get_instruction_data;
 use crate::core::zc_adapter::ZcAdapter;
-use crate::types::{DexInfo, SolanaInstruction, TradeInfo, TradeType, TransferData, TransferMap};
+use crate::core::zc_transaction_adapter::ZcTransactionAdapter;
+use crate::protocols::pumpfun::util::convert_to_ui_amount;
+use crate::types::{
+    AccountKey, DecimalAmount, DexInfo, TokenAmount, TradeConfidence, TradeInfo, TradeType, TransferData, TransferInfo, TransferMap,
+};
+
+const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+
+/// System Program ID (`11111111111111111111111111111111`) as 32-byte array
+const SYSTEM_PROGRAM_ID_BYTES: [u8; 32] = [0u8; 32];
 
 /// Token Program ID as 32-byte array (decoded once at startup)
 static TOKEN_PROGRAM_ID_BYTES: Lazy<[u8; 32]> = Lazy::new(|| {
@@ -78,347 +89,430 @@ impl<'a> ZcTransactionUtils<'a> {
     /// Get transfer actions from instructions (zero-copy)
     /// 
     /// # Returns
-    /// Transfer map grouped by program ID
+    /// Transfer map grouped by caller key, as on the owned path
     pub fn get_transfer_actions(&self) -> TransferMap {
-        Self::create_transfers_from_instructions_zc(self.adapter)
+        let adapter = self.adapter;
+        ZcTransactionAdapter::new(adapter.tx, adapter.config.clone(), adapter.meta)
+            .into_transfer_actions()
     }
 
     /// Create transfers from instructions (zero-copy version)
     /// 
+    /// Та же раскладка, что у `TransactionUtils::get_transfer_actions`: inner-переводы —
+    /// под `<program>:<outer>` ближайшей вызывающей программы, outer — под `transfer`.
+    /// Token-аккаунты, владельцы и decimals берутся из `tokens` (кэш `ZcTransactionAdapter`).
+    /// 
     /// # Arguments
     /// * `adapter` - Zero-copy adapter
+    /// * `tokens` - Adapter with parsed meta (token maps, inner instructions)
     /// 
     /// # Returns
-    /// Transfer map grouped by program ID
-    fn create_transfers_from_instructions_zc(adapter: &'a ZcAdapter<'a>) -> TransferMap {
+    /// Transfer map grouped by caller key
+    pub(crate) fn create_transfers_from_instructions_zc(
+        adapter: &ZcAdapter<'_>,
+        tokens: &ZcTransactionAdapter<'_>,
+    ) -> TransferMap {
         // Pre-allocate with estimated capacity
-        let estimated_transfers = adapter.instructions().len() * 3;
+        let estimated_transfers = tokens.inner_instructions().len() * 3 + adapter.instructions().len();
         let mut actions: TransferMap = HashMap::with_capacity(estimated_transfers.min(32));
         
         // Buffer for formatting numbers (avoid format!)
         let mut idx_buf = String::with_capacity(16);
+        let mut num_buf = itoa::Buffer::new();
+        
+        // Process inner instructions (already resolved from meta JSON by the adapter)
+        for inner_set in tokens.inner_instructions() {
+            let outer_index = inner_set.index;
+            let outer_program_id = adapter
+                .instruction(outer_index)
+                .and_then(|ix| adapter.program_id(ix))
+                .map(|pid| bs58::encode(pid).into_string())
+                .unwrap_or_default();
+            
+            // Skip system programs
+            if SYSTEM_PROGRAMS.contains(&outer_program_id.as_str()) {
+                continue;
+            }
+            let outer_key = format!("{outer_program_id}:{outer_index}");
+            let mut key = outer_key.clone();
+            // Вызовы non-system программ на стеке CPI: (stack_height, ключ)
+            let mut callers: Vec<(u32, String)> = Vec::new();
+            
+            for (inner_index, ix) in inner_set.instructions.iter().enumerate() {
+                let stack_height = inner_set.stack_height(inner_index);
+                if let Some(height) = stack_height {
+                    while callers.last().is_some_and(|(caller, _)| *caller >= height) {
+                        callers.pop();
+                    }
+                }
+                
+                if !SYSTEM_PROGRAMS.contains(&ix.program_id.as_str()) {
+                    key = format!("{}:{outer_index}-{inner_index}", ix.program_id);
+                    if let Some(height) = stack_height {
+                        callers.push((height, key.clone()));
+                    }
+                    continue;
+                }
+                
+                idx_buf.clear();
+                idx_buf.push_str(num_buf.format(outer_index));
+                idx_buf.push('-');
+                idx_buf.push_str(num_buf.format(inner_index));
+                
+                let Ok(data) = get_instruction_data(ix) else {
+                    continue;
+                };
+                if let Some(transfer_data) = Self::parse_instruction_action_zc(
+                    tokens,
+                    &ix.program_id,
+                    &ix.accounts,
+                    &data,
+                    &idx_buf,
+                ) {
+                    let key = match (stack_height, callers.last()) {
+                        (Some(_), Some((_, caller))) => caller.clone(),
+                        (Some(_), None) => outer_key.clone(),
+                        (None, _) => key.clone(),
+                    };
+                    actions
+                        .entry(key)
+                        .or_insert_with(|| Vec::with_capacity(4))
+                        .push(transfer_data);
+                }
+            }
+        }
         
         // Process outer instructions (zero-copy: work with ZcInstruction directly)
         for (outer_index, instruction) in adapter.instructions().iter().enumerate() {
             // Get program ID (zero-copy: 32-byte array)
-            let program_id = match adapter.program_id(instruction) {
-                Some(pid) => pid,
-                None => continue,
+            let Some(program_id) = adapter.program_id(instruction) else {
+                continue;
             };
             
-            // Check if this is a Token Program instruction (zero-copy: compare 32-byte arrays directly)
-            if program_id != &*TOKEN_PROGRAM_ID_BYTES && program_id != &*TOKEN_2022_PROGRAM_ID_BYTES {
+            // Only token and system programs move funds (zero-copy: compare 32-byte arrays)
+            if program_id != &*TOKEN_PROGRAM_ID_BYTES
+                && program_id != &*TOKEN_2022_PROGRAM_ID_BYTES
+                && program_id != &SYSTEM_PROGRAM_ID_BYTES
+            {
                 continue;
             }
             
-            // Format idx (minimal allocation)
+            // Account keys are converted to strings only for matched instructions
+            let Some(accounts) = adapter
+                .instruction_accounts(instruction)
+                .iter()
+                .map(|&index| adapter.account_key(index as usize))
+                .map(|key| key.map(|key| AccountKey::from(bs58::encode(key).into_string())))
+                .collect::<Option<Vec<_>>>()
+            else {
+                continue;
+            };
+            
             idx_buf.clear();
-            let mut num_buf = itoa::Buffer::new();
             idx_buf.push_str(num_buf.format(outer_index));
             
-            // Parse instruction action (zero-copy: work with instruction data directly)
             if let Some(transfer_data) = Self::parse_instruction_action_zc(
-                adapter,
-                instruction,
-                program_id,
+                tokens,
+                &bs58::encode(program_id).into_string(),
+                &accounts,
+                adapter.instruction_data(instruction),
                 &idx_buf,
             ) {
-                // Convert program_id to String for HashMap key (only once per program)
-                let program_id_str = bs58::encode(program_id).into_string();
                 actions
-                    .entry(program_id_str)
+                    .entry("transfer".to_string())
                     .or_insert_with(|| Vec::with_capacity(4))
                     .push(transfer_data);
             }
         }
         
-        // Process inner instructions from meta JSON (if available)
-        if let Some(inner_instructions_json) = adapter.inner_instructions() {
-            if let Some(inner_array) = inner_instructions_json.as_array() {
-                for inner_set in inner_array {
-                    if let Some(index) = inner_set.get("index").and_then(|v| v.as_u64()) {
-                        let outer_index = index as usize;
-                        
-                        // Get outer instruction program ID
-                        let outer_instruction = adapter.instruction(outer_index);
-                        let outer_program_id = outer_instruction
-                            .and_then(|ix| adapter.program_id(ix))
-                            .map(|pid| bs58::encode(pid).into_string());
-                        
-                        // Skip system programs
-                        if let Some(ref pid_str) = outer_program_id {
-                            if SYSTEM_PROGRAMS.iter().any(|&p| p == pid_str) {
-                                continue;
-                            }
-                            if SKIP_PROGRAM_IDS.iter().any(|&p| p == pid_str) {
-                                continue;
-                            }
-                        }
-                        
-                        // Process inner instructions
-                        if let Some(instructions_array) = inner_set.get("instructions").and_then(|v| v.as_array()) {
-                            for (inner_index, inner_ix_json) in instructions_array.iter().enumerate() {
-                                // Parse inner instruction from JSON
-                                if let Some(transfer_data) = Self::parse_inner_instruction_zc(
-                                    adapter,
-                                    inner_ix_json,
-                                    outer_index,
-                                    inner_index,
-                                    &outer_program_id,
-                                ) {
-                                    // Use outer program ID or "transfer" as key
-                                    let program_id_str = outer_program_id
-                                        .clone()
-                                        .unwrap_or_else(|| "transfer".to_string());
-                                    actions
-                                        .entry(program_id_str)
-                                        .or_insert_with(|| Vec::with_capacity(4))
-                                        .push(transfer_data);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
+        Self::fill_wsol_close_amounts(tokens, &mut actions);
         actions
     }
 
-    /// Parse instruction action (zero-copy version)
+    /// Parse instruction action (zero-copy version of `parse_instruction_action_fast`)
     /// 
     /// # Arguments
-    /// * `adapter` - Zero-copy adapter
-    /// * `instruction` - Zero-copy instruction
-    /// * `program_id` - Program ID as 32-byte array
+    /// * `tokens` - Adapter with parsed meta
+    /// * `program_id` - Program ID
+    /// * `accounts` - Instruction accounts
+    /// * `data` - Decoded instruction data
     /// * `idx` - Instruction index string
     /// 
     /// # Returns
     /// Optional transfer data
     fn parse_instruction_action_zc(
-        adapter: &'a ZcAdapter<'a>,
-        instruction: &crate::core::zero_copy::ZcInstruction<'a>,
-        program_id: &[u8; 32],
+        tokens: &ZcTransactionAdapter<'_>,
+        program_id: &str,
+        accounts: &[AccountKey],
+        data: &[u8],
         idx: &str,
     ) -> Option<TransferData> {
-        use crate::core::utils::get_instruction_data_zc;
-        use crate::types::TokenAmount;
-        
         const TRANSFER: u8 = 3;
         const TRANSFER_CHECKED: u8 = 12;
+        // Token-2022: TransferFeeExtension, подинструкция TransferCheckedWithFee
+        const TRANSFER_FEE_EXTENSION: u8 = 26;
+        const TRANSFER_CHECKED_WITH_FEE: u8 = 1;
+        const CLOSE_ACCOUNT: u8 = 9;
         
-        // Get instruction data (zero-copy: reference to buffer)
-        let data = get_instruction_data_zc(instruction);
-        if data.is_empty() {
-            return None;
+        if program_id == SYSTEM_PROGRAMS[1] {
+            return Self::parse_system_instruction_zc(tokens, program_id, accounts, data, idx);
         }
-        
-        let instruction_type = data[0];
-        
-        // Get instruction accounts (zero-copy: references)
-        let account_indices = adapter.instruction_accounts(instruction);
-        if account_indices.len() < 3 {
+        if program_id != TOKEN_PROGRAM_ID && program_id != TOKEN_2022_PROGRAM_ID {
             return None;
         }
         
-        // Get account keys from indices (zero-copy: convert to String only when needed)
-        let source_index = account_indices[0] as usize;
-        let destination_index = account_indices[1] as usize;
-        let authority_index = account_indices.get(2).copied().map(|i| i as usize);
-        
-        let source_key = adapter.account_key(source_index)?;
-        let destination_key = adapter.account_key(destination_index)?;
-        
-        // Convert to String only once (necessary for TransferData)
-        let source = bs58::encode(source_key).into_string();
-        let destination = bs58::encode(destination_key).into_string();
-        
-        match instruction_type {
-            TRANSFER => {
-                // TRANSFER: [source, destination, authority]
-                Self::create_transfer_data_zc(
-                    adapter,
+        match *data.first()? {
+            // transfer: [source, destination, authority]
+            TRANSFER if accounts.len() >= 3 => Self::create_transfer_data_zc(
+                tokens,
+                program_id,
+                &accounts[0],
+                &accounts[1],
+                None, // mint will be inferred from token accounts
+                None, // decimals will be inferred from token accounts
+                idx,
+                "transfer",
+                data,
+                accounts.get(2),
+            ),
+            // transferChecked: [source, mint, destination, authority]
+            TRANSFER_CHECKED if accounts.len() >= 4 => Self::create_transfer_data_zc(
+                tokens,
+                program_id,
+                &accounts[0],
+                &accounts[2],
+                Some(&accounts[1]),
+                data.get(9).copied(),
+                idx,
+                "transferChecked",
+                data,
+                accounts.get(3),
+            ),
+            // transferCheckedWithFee: [26, 1, amount u64, decimals u8, fee u64]
+            TRANSFER_FEE_EXTENSION
+                if data.get(1) == Some(&TRANSFER_CHECKED_WITH_FEE)
+                    && accounts.len() >= 4
+                    && data.len() >= 19 =>
+            {
+                let decimals = data[10];
+                let fee = u64::from_le_bytes(data[11..19].try_into().ok()?);
+                // раскладка transferChecked: [12, amount, decimals]
+                let mut checked = Vec::with_capacity(10);
+                checked.push(TRANSFER_CHECKED);
+                checked.extend_from_slice(&data[2..11]);
+                let mut transfer = Self::create_transfer_data_zc(
+                    tokens,
                     program_id,
-                    &source,
-                    &destination,
-                    None, // mint will be inferred from token balances
-                    None, // decimals will be inferred from token balances
+                    &accounts[0],
+                    &accounts[2],
+                    Some(&accounts[1]),
+                    Some(decimals),
                     idx,
-                    "transfer",
-                    data,
-                    TRANSFER,
-                    &[],
-                )
+                    "transferCheckedWithFee",
+                    &checked,
+                    accounts.get(3),
+                )?;
+                transfer.fee_amount = Some(TokenAmount::new(
+                    fee.to_string(),
+                    decimals,
+                    Some(convert_to_ui_amount(fee, decimals)),
+                ));
+                Some(transfer)
             }
-            TRANSFER_CHECKED => {
-                // TRANSFER_CHECKED: [source, destination, mint, authority]
-                if account_indices.len() >= 4 {
-                    let mint_index = account_indices[2] as usize;
-                    let mint_key = adapter.account_key(mint_index)?;
-                    let mint = bs58::encode(mint_key).into_string();
-                    
-                    Self::create_transfer_data_zc(
-                        adapter,
-                        program_id,
-                        &source,
-                        &destination,
-                        Some(&mint),
-                        None, // decimals will be inferred from token balances
-                        idx,
-                        "transferChecked",
-                        data,
-                        TRANSFER_CHECKED,
-                        &[],
-                    )
-                } else {
-                    None
+            // closeAccount: [account, destination, owner]; только WSOL, сумма — в
+            // `fill_wsol_close_amounts`
+            CLOSE_ACCOUNT if accounts.len() >= 3 => {
+                let account = &accounts[0];
+                let is_wsol = tokens
+                    .token_account_info(account)
+                    .is_some_and(|info| info.mint == TOKENS.SOL);
+                if !is_wsol {
+                    return None;
                 }
+                let mut transfer = Self::create_native_transfer_data_zc(
+                    tokens,
+                    program_id,
+                    account,
+                    &accounts[1],
+                    0,
+                    idx,
+                    "closeAccount",
+                );
+                transfer.info.authority = Some(accounts[2].clone());
+                Some(transfer)
             }
             _ => None,
         }
     }
 
-    /// Parse inner instruction from JSON (zero-copy where possible)
-    /// 
-    /// # Arguments
-    /// * `adapter` - Zero-copy adapter
-    /// * `inner_ix_json` - Inner instruction JSON
-    /// * `outer_index` - Outer instruction index
-    /// * `inner_index` - Inner instruction index
-    /// * `outer_program_id` - Outer program ID string
-    /// 
-    /// # Returns
-    /// Optional transfer data
-    fn parse_inner_instruction_zc(
-        adapter: &'a ZcAdapter<'a>,
-        inner_ix_json: &serde_json::Value,
-        outer_index: usize,
-        inner_index: usize,
-        outer_program_id: &Option<String>,
+    /// Движения lamports System Program (transfer, transferWithSeed, createAccount,
+    /// createAccountWithSeed), как в `TransactionUtils::parse_system_instruction_fast`.
+    fn parse_system_instruction_zc(
+        tokens: &ZcTransactionAdapter<'_>,
+        program_id: &str,
+        accounts: &[AccountKey],
+        data: &[u8],
+        idx: &str,
     ) -> Option<TransferData> {
-        use crate::types::SolanaInstruction;
+        const CREATE_ACCOUNT: u32 = 0;
+        const CREATE_ACCOUNT_WITH_SEED: u32 = 3;
+        const TRANSFER: u32 = 2;
+        const TRANSFER_WITH_SEED: u32 = 11;
         
-        // Parse inner instruction from JSON (this involves allocations, but necessary)
-        // TODO: Create zero-copy version of InnerInstruction
-        let inner_ix = match serde_json::from_value::<SolanaInstruction>(inner_ix_json.clone()) {
-            Ok(ix) => ix,
-            Err(_) => return None,
+        let read_u64 = |offset: usize| -> Option<u64> {
+            Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
         };
-        
-        // Check if this is a Token Program instruction
-        const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
-        const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
-        
-        if inner_ix.program_id != TOKEN_PROGRAM_ID && inner_ix.program_id != TOKEN_2022_PROGRAM_ID {
-            return None;
-        }
-        
-        // Format idx
-        let mut idx_buf = String::with_capacity(16);
-        let mut num_buf = itoa::Buffer::new();
-        idx_buf.push_str(num_buf.format(outer_index));
-        idx_buf.push('-');
-        idx_buf.push_str(num_buf.format(inner_index));
-        
-        // Decode instruction data from base64
-        let data = match base64_simd::STANDARD.decode_to_vec(&inner_ix.data) {
-            Ok(d) => d,
-            Err(_) => return None,
+        let instruction_type = u32::from_le_bytes(data.get(0..4)?.try_into().ok()?);
+        
+        // (source, destination, lamports, тип)
+        let (source, destination, lamports, transfer_type) = match instruction_type {
+            CREATE_ACCOUNT => (accounts.first()?, accounts.get(1)?, read_u64(4)?, "createAccount"),
+            CREATE_ACCOUNT_WITH_SEED => {
+                let seed_len = usize::try_from(read_u64(36)?).ok()?;
+                let lamports = read_u64(44usize.checked_add(seed_len)?)?;
+                (accounts.first()?, accounts.get(1)?, lamports, "createAccountWithSeed")
+            }
+            TRANSFER => (accounts.first()?, accounts.get(1)?, read_u64(4)?, "transfer"),
+            TRANSFER_WITH_SEED => (accounts.first()?, accounts.get(2)?, read_u64(4)?, "transferWithSeed"),
+            _ => return None,
         };
         
-        if data.is_empty() {
-            return None;
-        }
-        
-        let instruction_type = data[0];
-        let accounts = &inner_ix.accounts;
-        
-        const TRANSFER: u8 = 3;
-        const TRANSFER_CHECKED: u8 = 12;
-        
-        // Decode program ID to 32-byte array
-        let program_id_bytes = match bs58::decode(&inner_ix.program_id).into_vec() {
-            Ok(v) if v.len() == 32 => {
-                let mut array = [0u8; 32];
-                array.copy_from_slice(&v);
-                array
+        let mut transfer = Self::create_native_transfer_data_zc(
+            tokens,
+            program_id,
+            source,
+            destination,
+            lamports,
+            idx,
+            transfer_type,
+        );
+        transfer.info.authority = Some(source.clone());
+        Some(transfer)
+    }
+
+    /// TransferData для нативного SOL (decimals 9).
+    fn create_native_transfer_data_zc(
+        tokens: &ZcTransactionAdapter<'_>,
+        program_id: &str,
+        source: &str,
+        destination: &str,
+        lamports: u64,
+        idx: &str,
+        transfer_type: &str,
+    ) -> TransferData {
+        let accounts = [source.to_string(), destination.to_string()];
+        let mut post = tokens.get_account_balance(&accounts).into_iter();
+        let mut pre = tokens.get_account_pre_balance(&accounts).into_iter();
+
+        TransferData {
+            transfer_type: transfer_type.to_string(),
+            program_id: program_id.into(),
+            info: TransferInfo {
+                authority: None,
+                destination: destination.into(),
+                destination_owner: tokens.get_token_account_owner(destination).map(AccountKey::from),
+                mint: TOKENS.SOL.into(),
+                source: source.into(),
+                token_amount: TokenAmount::new(
+                    lamports.to_string(),
+                    9,
+                    Some(lamports as f64 / 1_000_000_000.0),
+                ),
+                source_balance: post.next().flatten(),
+                source_pre_balance: pre.next().flatten(),
+                destination_balance: post.next().flatten(),
+                destination_pre_balance: pre.next().flatten(),
+                sol_balance_change: None,
             },
-            _ => return None,
-        };
+            idx: idx.to_string(),
+            timestamp: tokens.block_time(),
+            signature: tokens.signature().to_string(),
+            is_fee: false,
+            fee_amount: None,
+            instruction_index: 0,
+            inner_index: None,
+            order_key: None,
+        }
+    }
+
+    /// Сумма WSOL, выведенная `closeAccount`: lamports аккаунта к моменту закрытия без
+    /// rent-exempt резерва; закрытия пустых аккаунтов удаляются.
+    fn fill_wsol_close_amounts(tokens: &ZcTransactionAdapter<'_>, actions: &mut TransferMap) {
+        /// Rent-exempt минимум для 165-байтового SPL token аккаунта.
+        const TOKEN_ACCOUNT_RENT: i128 = 2_039_280;
+        
+        let closed: Vec<String> = actions
+            .values()
+            .flatten()
+            .filter(|t| t.transfer_type == "closeAccount")
+            .map(|t| t.info.source.to_string())
+            .collect();
+        if closed.is_empty() {
+            return;
+        }
         
-        match instruction_type {
-            TRANSFER => {
-                // TRANSFER: [source, destination, authority]
-                if accounts.len() >= 3 {
-                    let source = accounts.get(0)?.clone();
-                    let destination = accounts.get(1)?.clone();
-                    
-                    Self::create_transfer_data_zc(
-                        adapter,
-                        &program_id_bytes,
-                        &source,
-                        &destination,
-                        None, // mint will be inferred from token balances
-                        None, // decimals will be inferred from token balances
-                        &idx_buf,
-                        "transfer",
-                        &data,
-                        TRANSFER,
-                        accounts,
-                    )
-                } else {
-                    None
-                }
+        let sol_changes = tokens.get_account_sol_balance_changes(false);
+        let mut lamports: HashMap<String, i128> = closed
+            .into_iter()
+            .map(|account| {
+                let pre = sol_changes.get(&account).map_or(0, |change| change.pre);
+                (account, pre)
+            })
+            .collect();
+        for transfer in actions.values().flatten() {
+            if transfer.transfer_type == "closeAccount" || transfer.info.mint != TOKENS.SOL {
+                continue;
             }
-            TRANSFER_CHECKED => {
-                // TRANSFER_CHECKED: [source, destination, mint, authority]
-                if accounts.len() >= 4 {
-                    let source = accounts.get(0)?.clone();
-                    let destination = accounts.get(1)?.clone();
-                    let mint = accounts.get(2)?.clone();
-                    
-                    Self::create_transfer_data_zc(
-                        adapter,
-                        &program_id_bytes,
-                        &source,
-                        &destination,
-                        Some(&mint),
-                        None, // decimals will be inferred from token balances
-                        &idx_buf,
-                        "transferChecked",
-                        &data,
-                        TRANSFER_CHECKED,
-                        accounts,
-                    )
-                } else {
-                    None
-                }
+            let amount = transfer.info.token_amount.amount.parse::<i128>().unwrap_or(0);
+            if let Some(balance) = lamports.get_mut(transfer.info.destination.as_str()) {
+                *balance += amount;
+            }
+            if let Some(balance) = lamports.get_mut(transfer.info.source.as_str()) {
+                *balance -= amount;
             }
-            _ => None,
         }
+        
+        for transfers in actions.values_mut() {
+            transfers.retain_mut(|transfer| {
+                if transfer.transfer_type != "closeAccount" {
+                    return true;
+                }
+                let balance = lamports
+                    .get(transfer.info.source.as_str())
+                    .copied()
+                    .unwrap_or(0);
+                let amount = u64::try_from(balance - TOKEN_ACCOUNT_RENT).unwrap_or(0);
+                transfer.info.token_amount = TokenAmount::new(
+                    amount.to_string(),
+                    9,
+                    Some(amount as f64 / 1_000_000_000.0),
+                );
+                amount > 0
+            });
+        }
+        actions.retain(|_, transfers| !transfers.is_empty());
     }
 
-    /// Create transfer data (zero-copy where possible)
+    /// Create transfer data for SPL `transfer` / `transferChecked`
     /// 
     /// # Arguments
-    /// * `adapter` - Zero-copy adapter
-    /// * `program_id` - Program ID as 32-byte array
-    /// * `source` - Source account string
-    /// * `destination` - Destination account string
-    /// * `mint_opt` - Optional mint string
-    /// * `decimals_opt` - Optional decimals
+    /// * `tokens` - Adapter with parsed meta
+    /// * `program_id` - Token program ID
+    /// * `source` - Source account
+    /// * `destination` - Destination account
+    /// * `mint_opt` - Optional mint (transferChecked)
+    /// * `decimals_opt` - Optional decimals (transferChecked)
     /// * `idx` - Instruction index string
     /// * `transfer_type` - Transfer type string
     /// * `data` - Instruction data bytes
-    /// * `instruction_type` - Instruction type byte
-    /// * `accounts` - Instruction accounts (for inner instructions)
+    /// * `authority` - Transfer authority account
     /// 
     /// # Returns
     /// Optional transfer data
-    #[inline]
+    #[allow(clippy::too_many_arguments)]
     fn create_transfer_data_zc(
-        adapter: &'a ZcAdapter<'a>,
-        program_id: &[u8; 32],
+        tokens: &ZcTransactionAdapter<'_>,
+        program_id: &str,
         source: &str,
         destination: &str,
         mint_opt: Option<&str>,
@@ -426,89 +520,65 @@ impl<'a> ZcTransactionUtils<'a> {
         idx: &str,
         transfer_type: &str,
         data: &[u8],
-        instruction_type: u8,
-        accounts: &[String],
+        authority: Option<&AccountKey>,
     ) -> Option<TransferData> {
-        use crate::types::TokenAmount;
-        
-        // Determine mint (optimized: use token balances from meta JSON)
-        let mint = if let Some(m) = mint_opt {
-            m.to_string()
-        } else {
-            // Try to find mint from token balances (parse from meta JSON)
-            let mint_str = Self::find_mint_from_token_balances(adapter, source, destination);
-            
-            if mint_str.is_empty() {
-                // If program_id is Token Program, this might be SOL or native token
-                // For now, return None if mint is not found
-                return None;
-            }
-            
-            mint_str
-        };
-        
+        let source_info = tokens.token_account_info(source);
+        let dest_info = tokens.token_account_info(destination);
+        
+        // Unknown token account of a token program means WSOL, as on the owned path
+        let mint = mint_opt
+            .or_else(|| dest_info.map(|info| info.mint.as_str()))
+            .or_else(|| source_info.map(|info| info.mint.as_str()))
+            .unwrap_or(TOKENS.SOL);
         if mint.is_empty() {
             return None;
         }
         
-        // Get decimals (cache lookup or use default)
         let decimals = decimals_opt
-            .unwrap_or_else(|| {
-                // Try to get decimals from token balances
-                Self::get_decimals_from_token_balances(adapter, &mint)
-                    .unwrap_or(9) // Default to 9 for SOL
-            });
-        
-        // Read amount from data (fast read without checks)
-        let amount_raw = if data.len() >= 9 {
-            // TRANSFER: amount is at offset 1 (u64)
-            // TRANSFER_CHECKED: amount is at offset 1 (u64)
-            let amount_bytes: [u8; 8] = data[1..9].try_into().ok()?;
-            u64::from_le_bytes(amount_bytes)
-        } else {
-            return None; // Early exit if data is insufficient
-        };
-        
-        // Fast calculation of amount_ui (avoid powi if possible)
-        let amount_ui = if decimals == 9 {
-            amount_raw as f64 / 1_000_000_000.0
-        } else if decimals == 6 {
-            amount_raw as f64 / 1_000_000.0
-        } else {
-            amount_raw as f64 / 10f64.powi(decimals as i32)
+            .or_else(|| tokens.token_decimals(mint).filter(|&d| d > 0))
+            .unwrap_or(9);
+        
+        // TRANSFER / TRANSFER_CHECKED: amount is at offset 1 (u64)
+        let amount_raw = u64::from_le_bytes(data.get(1..9)?.try_into().ok()?);
+        let amount_ui = match decimals {
+            9 => amount_raw as f64 / 1_000_000_000.0,
+            6 => amount_raw as f64 / 1_000_000.0,
+            _ => amount_raw as f64 / 10f64.powi(decimals as i32),
         };
         
-        // Get token balances from meta JSON (zero-copy: references to JSON)
-        let source_balance = Self::get_token_balance_from_meta(adapter, source);
-        let dest_balance = Self::get_token_balance_from_meta(adapter, destination);
-        
-        // Create transfer data (allocations only for output struct)
-        let program_id_str = bs58::encode(program_id).into_string();
+        let accounts = [source.to_string(), destination.to_string()];
+        let mut post = tokens.get_token_account_balance(&accounts).into_iter();
+        let mut pre = tokens.get_token_account_pre_balance(&accounts).into_iter();
         
         Some(TransferData {
             transfer_type: transfer_type.to_string(),
-            program_id: program_id_str,
-            info: crate::types::TransferInfo {
-                source: source.to_string(),
-                destination: destination.to_string(),
-                mint: mint.clone(),
+            program_id: program_id.into(),
+            info: TransferInfo {
+                authority: authority.cloned(),
+                destination: destination.into(),
+                destination_owner: tokens.get_token_account_owner(destination).map(AccountKey::from),
+                mint: mint.into(),
+                source: source.into(),
                 token_amount: TokenAmount {
                     amount: amount_raw.to_string(),
                     decimals,
                     ui_amount: Some(amount_ui),
+                    ui_amount_string: None,
                 },
-                authority: accounts.get(2).or_else(|| accounts.get(3)).cloned(),
-                destination_owner: None,
-                destination_balance: dest_balance.clone(),
-                destination_pre_balance: None,
-                source_balance: source_balance.clone(),
-                source_pre_balance: None,
+                source_balance: post.next().flatten(),
+                source_pre_balance: pre.next().flatten(),
+                destination_balance: post.next().flatten(),
+                destination_pre_balance: pre.next().flatten(),
                 sol_balance_change: None,
             },
             idx: idx.to_string(),
-            timestamp: adapter.block_time(),
-            signature: adapter.signature().to_string(),
+            timestamp: tokens.block_time(),
+            signature: tokens.signature().to_string(),
             is_fee: false,
+            fee_amount: None,
+            instruction_index: 0,
+            inner_index: None,
+            order_key: None,
         })
     }
 
@@ -563,9 +633,8 @@ impl<'a> ZcTransactionUtils<'a> {
         }
         
         // Sum all transfers for each mint
-        let mut input_amount = 0.0;
+        // Sum in raw: f64 sums drop low digits of large-supply tokens
         let mut input_amount_raw = 0u128;
-        let mut output_amount = 0.0;
         let mut output_amount_raw = 0u128;
         let mut input_decimals = 0u8;
         let mut output_decimals = 0u8;
@@ -574,22 +643,14 @@ impl<'a> ZcTransactionUtils<'a> {
         
         for transfer in transfers {
             if transfer.info.mint == input_mint {
-                let amount = transfer.info.token_amount.ui_amount.unwrap_or_else(|| {
-                    transfer.info.token_amount.amount.parse::<f64>().unwrap_or(0.0)
-                });
                 let amount_raw = transfer.info.token_amount.amount.parse::<u128>().unwrap_or(0);
-                input_amount += amount;
                 input_amount_raw += amount_raw;
                 input_decimals = transfer.info.token_amount.decimals;
                 if input_transfer_ref.is_none() {
                     input_transfer_ref = Some(transfer);
                 }
             } else if transfer.info.mint == output_mint {
-                let amount = transfer.info.token_amount.ui_amount.unwrap_or_else(|| {
-                    transfer.info.token_amount.amount.parse::<f64>().unwrap_or(0.0)
-                });
                 let amount_raw = transfer.info.token_amount.amount.parse::<u128>().unwrap_or(0);
-                output_amount += amount;
                 output_amount_raw += amount_raw;
                 output_decimals = transfer.info.token_amount.decimals;
                 if output_transfer_ref.is_none() {
@@ -611,7 +672,7 @@ impl<'a> ZcTransactionUtils<'a> {
             .program_id
             .as_ref()
             .cloned()
-            .unwrap_or_else(|| input.program_id.clone());
+            .unwrap_or_else(|| input.program_id.to_string());
         let amm = dex_info
             .amm
             .as_ref()
@@ -619,16 +680,16 @@ impl<'a> ZcTransactionUtils<'a> {
             .unwrap_or_else(|| dex_program_names::name(&program_id).to_string());
         
         let input_token = crate::types::TokenInfo {
-            mint: input.info.mint.clone(),
-            amount: input_amount,
+            mint: input.info.mint.to_string(),
+            amount: DecimalAmount::new(input_amount_raw, input_decimals).to_f64(),
             amount_raw: input_amount_raw.to_string(),
             decimals: input_decimals,
-            authority: input.info.authority.clone(),
-            destination: Some(input.info.destination.clone()),
-            destination_owner: input.info.destination_owner.clone(),
+            authority: input.info.authority.clone().map(String::from),
+            destination: Some(input.info.destination.to_string()),
+            destination_owner: input.info.destination_owner.clone().map(String::from),
             destination_balance: input.info.destination_balance.clone(),
             destination_pre_balance: input.info.destination_pre_balance.clone(),
-            source: Some(input.info.source.clone()),
+            source: Some(input.info.source.to_string()),
             source_balance: input.info.source_balance.clone(),
             source_pre_balance: input.info.source_pre_balance.clone(),
             destination_balance_change: None,
@@ -637,16 +698,16 @@ impl<'a> ZcTransactionUtils<'a> {
         };
         
         let output_token = crate::types::TokenInfo {
-            mint: output.info.mint.clone(),
-            amount: output_amount,
+            mint: output.info.mint.to_string(),
+            amount: DecimalAmount::new(output_amount_raw, output_decimals).to_f64(),
             amount_raw: output_amount_raw.to_string(),
             decimals: output_decimals,
-            authority: output.info.authority.clone(),
-            destination: Some(output.info.destination.clone()),
-            destination_owner: output.info.destination_owner.clone(),
+            authority: output.info.authority.clone().map(String::from),
+            destination: Some(output.info.destination.to_string()),
+            destination_owner: output.info.destination_owner.clone().map(String::from),
             destination_balance: output.info.destination_balance.clone(),
             destination_pre_balance: output.info.destination_pre_balance.clone(),
-            source: Some(output.info.source.clone()),
+            source: Some(output.info.source.to_string()),
             source_balance: output.info.source_balance.clone(),
             source_pre_balance: output.info.source_pre_balance.clone(),
             destination_balance_change: None,
@@ -662,7 +723,7 @@ impl<'a> ZcTransactionUtils<'a> {
             slippage_bps: None,
             fee: None,
             fees: Vec::new(),
-            user: Some(input.info.source.clone()),
+            user: Some(input.info.source.to_string()),
             program_id: Some(program_id),
             amm: Some(amm),
             amms: None,
@@ -676,156 +737,22 @@ impl<'a> ZcTransactionUtils<'a> {
                     .map(|pk| bs58::encode(pk).into_string())
                     .collect()
             ),
+            dlmm: None,
+            clob: None,
+            liquidity_role: None,
+            price_quote_per_base: None,
+            value_usd: None,
+            input_symbol: None,
+            output_symbol: None,
+            instruction_index: 0,
+            inner_index: None,
+            pool_reserves: None,
+            order_key: None,
+            side: None,
+            authority: None,
+            flags: Vec::new(),
+            router: None,
+            confidence: TradeConfidence::Unverified,
         })
     }
-    
-    /// Find mint from token balances (parse from meta JSON)
-    fn find_mint_from_token_balances(
-        adapter: &'a ZcAdapter<'a>,
-        source: &str,
-        destination: &str,
-    ) -> String {
-        // Try post token balances first
-        if let Some(post_balances) = adapter.post_token_balances() {
-            if let Some(balances_array) = post_balances.as_array() {
-                for balance in balances_array {
-                    if let Some(account) = balance.get("account").and_then(|v| v.as_str()) {
-                        if account == source || account == destination {
-                            if let Some(mint) = balance.get("mint").and_then(|v| v.as_str()) {
-                                return mint.to_string();
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Try pre token balances
-        if let Some(pre_balances) = adapter.pre_token_balances() {
-            if let Some(balances_array) = pre_balances.as_array() {
-                for balance in balances_array {
-                    if let Some(account) = balance.get("account").and_then(|v| v.as_str()) {
-                        if account == source || account == destination {
-                            if let Some(mint) = balance.get("mint").and_then(|v| v.as_str()) {
-                                return mint.to_string();
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        String::new()
-    }
-    
-    /// Get decimals from token balances (parse from meta JSON)
-    fn get_decimals_from_token_balances(
-        adapter: &'a ZcAdapter<'a>,
-        mint: &str,
-    ) -> Option<u8> {
-        // Try post token balances first
-        if let Some(post_balances) = adapter.post_token_balances() {
-            if let Some(balances_array) = post_balances.as_array() {
-                for balance in balances_array {
-                    if let Some(mint_str) = balance.get("mint").and_then(|v| v.as_str()) {
-                        if mint_str == mint {
-                            if let Some(ui_token_amount) = balance.get("uiTokenAmount") {
-                                if let Some(decimals) = ui_token_amount.get("decimals").and_then(|v| v.as_u64()) {
-                                    return Some(decimals as u8);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Try pre token balances
-        if let Some(pre_balances) = adapter.pre_token_balances() {
-            if let Some(balances_array) = pre_balances.as_array() {
-                for balance in balances_array {
-                    if let Some(mint_str) = balance.get("mint").and_then(|v| v.as_str()) {
-                        if mint_str == mint {
-                            if let Some(ui_token_amount) = balance.get("uiTokenAmount") {
-                                if let Some(decimals) = ui_token_amount.get("decimals").and_then(|v| v.as_u64()) {
-                                    return Some(decimals as u8);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        None
-    }
-    
-    /// Get token balance from meta JSON
-    fn get_token_balance_from_meta(
-        adapter: &'a ZcAdapter<'a>,
-        account: &str,
-    ) -> Option<crate::types::TokenAmount> {
-        // Try post token balances first
-        if let Some(post_balances) = adapter.post_token_balances() {
-            if let Some(balances_array) = post_balances.as_array() {
-                for balance in balances_array {
-                    if let Some(account_str) = balance.get("account").and_then(|v| v.as_str()) {
-                        if account_str == account {
-                            if let Some(ui_token_amount) = balance.get("uiTokenAmount") {
-                                let amount = ui_token_amount.get("amount").and_then(|v| v.as_str())?;
-                                let decimals = ui_token_amount.get("decimals").and_then(|v| v.as_u64())? as u8;
-                                let ui_amount = ui_token_amount.get("uiAmount").and_then(|v| v.as_f64());
-                                
-                                return Some(crate::types::TokenAmount {
-                                    amount: amount.to_string(),
-                                    decimals,
-                                    ui_amount,
-                                });
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        None
-    }
-    
-    /// Get token account owner from meta JSON
-    fn get_token_account_owner_from_meta(
-        adapter: &'a ZcAdapter<'a>,
-        account: &str,
-    ) -> Option<String> {
-        // Try post token balances first
-        if let Some(post_balances) = adapter.post_token_balances() {
-            if let Some(balances_array) = post_balances.as_array() {
-                for balance in balances_array {
-                    if let Some(account_str) = balance.get("account").and_then(|v| v.as_str()) {
-                        if account_str == account {
-                            if let Some(owner) = balance.get("owner").and_then(|v| v.as_str()) {
-                                return Some(owner.to_string());
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        // Try pre token balances
-        if let Some(pre_balances) = adapter.pre_token_balances() {
-            if let Some(balances_array) = pre_balances.as_array() {
-                for balance in balances_array {
-                    if let Some(account_str) = balance.get("account").and_then(|v| v.as_str()) {
-                        if account_str == account {
-                            if let Some(owner) = balance.get("owner").and_then(|v| v.as_str()) {
-                                return Some(owner.to_string());
-                            }
-                        }
-                    }
-                }
-            }
-        }
-        
-        None
-    }
 }
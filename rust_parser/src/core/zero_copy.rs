@@ -303,28 +303,28 @@ impl fmt::Display for ParseError {
 
 impl std::error::Error for ParseError {}
 
-/// Read compact-u16 from buffer
+/// Read compact-u16 (Solana shortvec: 7 бит на байт, старший бит — продолжение) from buffer
 /// Returns (value, bytes_read)
+///
+/// Как и `solana_sdk::short_vec`, отвергает неканоничную запись (лишний нулевой байт)
+/// и значения больше `u16::MAX`, чтобы zc-разбор совпадал с bincode.
 #[inline(always)]
 fn read_compact_u16(data: &[u8]) -> Result<(u16, usize), ParseError> {
-    if data.is_empty() {
-        return Err(ParseError::InsufficientData);
-    }
-    let b0 = data[0];
-    if b0 <= 0x7f {
-        Ok((b0 as u16, 1))
-    } else if b0 <= 0xbf {
-        if data.len() < 2 {
-            return Err(ParseError::InsufficientData);
+    let mut value = 0u32;
+    for (i, &byte) in data.iter().take(3).enumerate() {
+        if i > 0 && byte == 0 {
+            return Err(ParseError::InvalidCompactU16);
         }
-        let v = (((b0 & 0x3f) as u16) << 8) | data[1] as u16;
-        Ok((v, 2))
-    } else {
-        if data.len() < 3 {
-            return Err(ParseError::InsufficientData);
+        value |= ((byte & 0x7f) as u32) << (7 * i);
+        if byte & 0x80 == 0 {
+            let value = u16::try_from(value).map_err(|_| ParseError::InvalidCompactU16)?;
+            return Ok((value, i + 1));
         }
-        let v = ((((b0 & 0x1f) as u32) << 16) | ((data[1] as u32) << 8) | data[2] as u32) as u16;
-        Ok((v, 3))
+    }
+    if data.len() < 3 {
+        Err(ParseError::InsufficientData)
+    } else {
+        Err(ParseError::InvalidCompactU16)
     }
 }
 
@@ -447,10 +447,10 @@ impl<'a> ZcTransaction<'a> {
         
         // Get account keys (lazy: only convert when needed)
         let all_keys = self.get_all_account_keys();
-        let accounts: Vec<String> = zc_ix.accounts
+        let accounts: Vec<crate::types::AccountKey> = zc_ix.accounts
             .iter()
             .filter_map(|&idx| {
-                all_keys.get(idx as usize).cloned()
+                all_keys.get(idx as usize).map(|key| key.into())
             })
             .collect();
         
@@ -458,7 +458,7 @@ impl<'a> ZcTransaction<'a> {
         let data_base64 = base64_simd::STANDARD.encode_to_string(zc_ix.data);
         
         Some(crate::types::SolanaInstruction {
-            program_id,
+            program_id: program_id.into(),
             accounts,
             data: data_base64,
         })
@@ -542,7 +542,7 @@ pub fn convert_zc_to_solana_tx(
     meta_json: Option<&serde_json::Value>,
 ) -> Result<crate::types::SolanaTransaction, ParseError> {
     use crate::types::{
-        SolanaInstruction, SolanaTransaction,
+        AccountKey, KeyInterner, SolanaInstruction, SolanaTransaction,
         TransactionMeta, TransactionStatus,
     };
     use std::collections::HashMap;
@@ -550,6 +550,9 @@ pub fn convert_zc_to_solana_tx(
     // OPTIMIZATION: Get all account keys once and reuse (cached)
     let all_account_keys = zc_tx.get_all_account_keys();
     let account_keys_len = all_account_keys.len();
+    // инструкции ссылаются на общие `Arc<str>` ключей, а не копируют строки
+    let mut interner = KeyInterner::new();
+    let keys: Vec<AccountKey> = all_account_keys.iter().map(|key| interner.intern(key)).collect();
     
     // OPTIMIZATION: Pre-allocate instructions vector with known capacity
     let instructions_capacity = zc_tx.message.instructions.len();
@@ -559,17 +562,17 @@ pub fn convert_zc_to_solana_tx(
     for ix in &zc_tx.message.instructions {
         // Get program ID (cached lookup)
         let program_id = if (ix.program_id_index as usize) < account_keys_len {
-            all_account_keys[ix.program_id_index as usize].clone()
+            keys[ix.program_id_index as usize].clone()
         } else {
-            "".to_string()
+            AccountKey::default()
         };
         
         // OPTIMIZATION: Pre-allocate accounts vector with known capacity
         let accounts_capacity = ix.accounts.len().min(account_keys_len);
-        let mut accounts: Vec<String> = Vec::with_capacity(accounts_capacity);
+        let mut accounts: Vec<AccountKey> = Vec::with_capacity(accounts_capacity);
         for &idx in ix.accounts.iter() {
             if (idx as usize) < account_keys_len {
-                accounts.push(all_account_keys[idx as usize].clone());
+                accounts.push(keys[idx as usize].clone());
             }
         }
         
@@ -585,7 +588,7 @@ pub fn convert_zc_to_solana_tx(
     
     // Extract inner instructions from meta if present
     let inner_instructions = if let Some(meta_val) = meta_json {
-        extract_inner_instructions_from_meta(meta_val, &all_account_keys)
+        extract_inner_instructions_from_meta(meta_val, &keys, &mut interner)
     } else {
         Vec::new()
     };
@@ -609,6 +612,8 @@ pub fn convert_zc_to_solana_tx(
             status: TransactionStatus::Success,
             sol_balance_changes: HashMap::new(),
             token_balance_changes: HashMap::new(),
+            err: None,
+            log_messages: Vec::new(),
         }
     };
     
@@ -629,9 +634,10 @@ pub fn convert_zc_to_solana_tx(
 /// Extract inner instructions from meta JSON
 fn extract_inner_instructions_from_meta(
     meta: &serde_json::Value,
-    account_keys: &[String],
+    account_keys: &[crate::types::AccountKey],
+    interner: &mut crate::types::KeyInterner,
 ) -> Vec<crate::types::InnerInstruction> {
-    use crate::types::{InnerInstruction, SolanaInstruction};
+    use crate::types::{AccountKey, InnerInstruction, SolanaInstruction};
     use base64_simd::STANDARD as B64;
     
     let mut result = Vec::new();
@@ -641,29 +647,29 @@ fn extract_inner_instructions_from_meta(
             let index = group.get("index").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
             
             let mut instructions = Vec::new();
+            let mut stack_heights = Vec::new();
             if let Some(ixs) = group.get("instructions").and_then(|v| v.as_array()) {
                 for ix_val in ixs {
-                    let program_id = ix_val
-                        .get("programId")
-                        .and_then(|v| v.as_str())
-                        .or_else(|| {
-                            ix_val
-                                .get("programIdIndex")
-                                .and_then(|idx| idx.as_u64())
-                                .and_then(|idx| account_keys.get(idx as usize))
-                                .map(|s| s.as_str())
-                        })
-                        .unwrap_or("")
-                        .to_string();
+                    stack_heights.push(
+                        ix_val.get("stackHeight").and_then(|v| v.as_u64()).map(|h| h as u32),
+                    );
+                    let program_id = match ix_val.get("programId").and_then(|v| v.as_str()) {
+                        Some(program_id) => interner.intern(program_id),
+                        None => ix_val
+                            .get("programIdIndex")
+                            .and_then(|idx| idx.as_u64())
+                            .and_then(|idx| account_keys.get(idx as usize).cloned())
+                            .unwrap_or_default(),
+                    };
                     
-                    let accounts: Vec<String> = if let Some(acc_arr) =
+                    let accounts: Vec<AccountKey> = if let Some(acc_arr) =
                         ix_val.get("accounts").and_then(|v| v.as_array())
                     {
                         acc_arr
                             .iter()
                             .filter_map(|v| {
                                 if let Some(s) = v.as_str() {
-                                    Some(s.to_string())
+                                    Some(interner.intern(s))
                                 } else if let Some(idx) = v.as_u64() {
                                     account_keys.get(idx as usize).cloned()
                                 } else {
@@ -702,6 +708,7 @@ fn extract_inner_instructions_from_meta(
                 result.push(InnerInstruction {
                     index,
                     instructions,
+                    stack_heights,
                 });
             }
         }
@@ -784,19 +791,12 @@ fn extract_transaction_meta_from_json(
     
     let fee = meta.get("fee").and_then(|v| v.as_u64()).unwrap_or(0);
     
-    let compute_units = meta
-        .get("computeUnitsConsumed")
-        .or_else(|| meta.get("computeUnits"))
-        .and_then(|v| v.as_u64())
-        .unwrap_or(0);
+    let compute_units = crate::core::meta_compat::compute_units(meta);
     
     // Check status: if err exists and is not null, then Failed
-    let status = if let Some(err_val) = meta.get("err") {
-        if err_val.is_null() {
-            TransactionStatus::Success
-        } else {
-            TransactionStatus::Failed
-        }
+    let err = crate::core::meta_compat::meta_error(meta).map(|err_val| err_val.to_string());
+    let status = if err.is_some() {
+        TransactionStatus::Failed
     } else {
         TransactionStatus::Success
     };
@@ -809,6 +809,8 @@ fn extract_transaction_meta_from_json(
         status,
         sol_balance_changes,
         token_balance_changes: HashMap::new(), // Will be populated by DexParser
+        err,
+        log_messages: crate::core::rpc_json::log_messages(meta),
     }
 }
 
@@ -865,12 +867,18 @@ mod tests {
         assert_eq!(read_compact_u16(&[0x00]).unwrap(), (0x00, 1));
         
         // Two bytes
-        assert_eq!(read_compact_u16(&[0x80, 0x01]).unwrap(), (0x01, 2));
-        assert_eq!(read_compact_u16(&[0xbf, 0xff]).unwrap(), (0x3fff, 2));
+        assert_eq!(read_compact_u16(&[0x80, 0x01]).unwrap(), (0x80, 2));
+        assert_eq!(read_compact_u16(&[0xff, 0x7f]).unwrap(), (0x3fff, 2));
         
         // Three bytes
-        assert_eq!(read_compact_u16(&[0xc0, 0x00, 0x01]).unwrap(), (0x4000, 3));
-        assert_eq!(read_compact_u16(&[0xff, 0xff, 0xff]).unwrap(), (0xffff, 3));
+        assert_eq!(read_compact_u16(&[0x80, 0x80, 0x01]).unwrap(), (0x4000, 3));
+        assert_eq!(read_compact_u16(&[0xff, 0xff, 0x03]).unwrap(), (0xffff, 3));
+
+        // Truncated, non-canonical, overflow
+        assert_eq!(read_compact_u16(&[0x80]), Err(ParseError::InsufficientData));
+        assert_eq!(read_compact_u16(&[0x80, 0x00]), Err(ParseError::InvalidCompactU16));
+        assert_eq!(read_compact_u16(&[0xff, 0xff, 0x04]), Err(ParseError::InvalidCompactU16));
+        assert_eq!(read_compact_u16(&[0x80, 0x80, 0x80]), Err(ParseError::InvalidCompactU16));
     }
     
     #[test]
@@ -0,0 +1,297 @@
+//! Запись вывода парсера в Parquet/CSV (feature `export`).
+//!
+//! Колонки — схемы из `crate::arrow`. Для исторической индексации `*_partitioned`
+//! раскладывают строки по файлам диапазонов слотов:
+//! `<dir>/trades_<first_slot>_<last_slot>.parquet`, где диапазон — `slots_per_file`
+//! слотов, выровненный по кратному `slots_per_file`.
+//!
+//! CSV не умеет вложенные типы: `List<Utf8>` колонки (`pool`) пишутся строкой через `;`.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, ArrayRef, RecordBatch, StringArray};
+use arrow_schema::{ArrowError, DataType, Field, Schema};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use thiserror::Error;
+
+use crate::arrow::{
+    pool_events_to_record_batch, trades_to_record_batch, transfers_to_record_batch,
+};
+use crate::types::{PoolEvent, TradeInfo, TransferData};
+
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("failed to write {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("arrow conversion failed: {0}")]
+    Arrow(#[from] ArrowError),
+    #[error("parquet write failed: {0}")]
+    Parquet(#[from] ParquetError),
+    #[error("slots_per_file must be positive")]
+    EmptyPartition,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// Snappy-сжатие.
+    Parquet,
+    /// С заголовком.
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Parquet => "parquet",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+fn create(path: &Path) -> Result<File, ExportError> {
+    File::create(path).map_err(|source| ExportError::Io {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+pub fn write_batch(
+    path: &Path,
+    batch: &RecordBatch,
+    format: ExportFormat,
+) -> Result<(), ExportError> {
+    let file = create(path)?;
+    match format {
+        ExportFormat::Parquet => {
+            let props = WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build();
+            let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))?;
+            writer.write(batch)?;
+            writer.close()?;
+        }
+        ExportFormat::Csv => {
+            let mut writer = arrow_csv::Writer::new(file);
+            writer.write(&flatten_lists(batch)?)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn write_trades_parquet(
+    path: impl AsRef<Path>,
+    trades: &[TradeInfo],
+) -> Result<(), ExportError> {
+    write_batch(
+        path.as_ref(),
+        &trades_to_record_batch(trades)?,
+        ExportFormat::Parquet,
+    )
+}
+
+pub fn write_trades_csv(path: impl AsRef<Path>, trades: &[TradeInfo]) -> Result<(), ExportError> {
+    write_batch(
+        path.as_ref(),
+        &trades_to_record_batch(trades)?,
+        ExportFormat::Csv,
+    )
+}
+
+pub fn write_pool_events_parquet(
+    path: impl AsRef<Path>,
+    events: &[PoolEvent],
+) -> Result<(), ExportError> {
+    write_batch(
+        path.as_ref(),
+        &pool_events_to_record_batch(events)?,
+        ExportFormat::Parquet,
+    )
+}
+
+pub fn write_pool_events_csv(
+    path: impl AsRef<Path>,
+    events: &[PoolEvent],
+) -> Result<(), ExportError> {
+    write_batch(
+        path.as_ref(),
+        &pool_events_to_record_batch(events)?,
+        ExportFormat::Csv,
+    )
+}
+
+pub fn write_transfers_parquet(
+    path: impl AsRef<Path>,
+    transfers: &[TransferData],
+) -> Result<(), ExportError> {
+    write_batch(
+        path.as_ref(),
+        &transfers_to_record_batch(transfers)?,
+        ExportFormat::Parquet,
+    )
+}
+
+pub fn write_transfers_csv(
+    path: impl AsRef<Path>,
+    transfers: &[TransferData],
+) -> Result<(), ExportError> {
+    write_batch(
+        path.as_ref(),
+        &transfers_to_record_batch(transfers)?,
+        ExportFormat::Csv,
+    )
+}
+
+/// `List<Utf8>` -> `Utf8` с элементами через `;`; прочие колонки как есть.
+fn flatten_lists(batch: &RecordBatch) -> Result<RecordBatch, ArrowError> {
+    let schema = batch.schema();
+    let mut fields = Vec::with_capacity(schema.fields().len());
+    let mut columns: Vec<ArrayRef> = Vec::with_capacity(batch.num_columns());
+    for (field, column) in schema.fields().iter().zip(batch.columns()) {
+        let DataType::List(item) = field.data_type() else {
+            fields.push(field.as_ref().clone());
+            columns.push(Arc::clone(column));
+            continue;
+        };
+        if item.data_type() != &DataType::Utf8 {
+            return Err(ArrowError::CsvError(format!(
+                "column {} of type {} is not supported in CSV",
+                field.name(),
+                field.data_type()
+            )));
+        }
+        let list = column.as_list::<i32>();
+        let joined: StringArray = (0..list.len())
+            .map(|row| {
+                list.is_valid(row).then(|| {
+                    let values = list.value(row);
+                    let values = values.as_string::<i32>();
+                    values.iter().flatten().collect::<Vec<_>>().join(";")
+                })
+            })
+            .collect();
+        fields.push(Field::new(
+            field.name(),
+            DataType::Utf8,
+            field.is_nullable(),
+        ));
+        columns.push(Arc::new(joined));
+    }
+    RecordBatch::try_new(Arc::new(Schema::new(fields)), columns)
+}
+
+/// Раскладывает строки по диапазонам слотов, пишет по файлу на диапазон и
+/// возвращает пути в порядке слотов. Пустые диапазоны файлов не получают.
+fn write_partitioned<T: Clone>(
+    dir: &Path,
+    prefix: &str,
+    rows: &[T],
+    slot: fn(&T) -> u64,
+    to_batch: fn(&[T]) -> Result<RecordBatch, ArrowError>,
+    slots_per_file: u64,
+    format: ExportFormat,
+) -> Result<Vec<PathBuf>, ExportError> {
+    if slots_per_file == 0 {
+        return Err(ExportError::EmptyPartition);
+    }
+    let mut partitions: BTreeMap<u64, Vec<T>> = BTreeMap::new();
+    for row in rows {
+        let start = slot(row) / slots_per_file * slots_per_file;
+        partitions.entry(start).or_default().push(row.clone());
+    }
+
+    std::fs::create_dir_all(dir).map_err(|source| ExportError::Io {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+    let mut paths = Vec::with_capacity(partitions.len());
+    for (start, rows) in partitions {
+        let end = start + (slots_per_file - 1);
+        let path = dir.join(format!("{prefix}_{start}_{end}.{}", format.extension()));
+        write_batch(&path, &to_batch(&rows)?, format)?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+pub fn write_trades_partitioned(
+    dir: impl AsRef<Path>,
+    trades: &[TradeInfo],
+    slots_per_file: u64,
+    format: ExportFormat,
+) -> Result<Vec<PathBuf>, ExportError> {
+    write_partitioned(
+        dir.as_ref(),
+        "trades",
+        trades,
+        |trade| trade.slot,
+        trades_to_record_batch,
+        slots_per_file,
+        format,
+    )
+}
+
+pub fn write_pool_events_partitioned(
+    dir: impl AsRef<Path>,
+    events: &[PoolEvent],
+    slots_per_file: u64,
+    format: ExportFormat,
+) -> Result<Vec<PathBuf>, ExportError> {
+    write_partitioned(
+        dir.as_ref(),
+        "pool_events",
+        events,
+        |event| event.slot,
+        pool_events_to_record_batch,
+        slots_per_file,
+        format,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partitions_trades_by_slot_range() {
+        let trade = |slot| TradeInfo {
+            slot,
+            signature: format!("sig{slot}"),
+            ..Default::default()
+        };
+        let trades = [trade(1_005), trade(999), trade(1_999), trade(3_000)];
+        let dir = std::env::temp_dir().join(format!("dex_export_{}", std::process::id()));
+
+        let paths = write_trades_partitioned(&dir, &trades, 1_000, ExportFormat::Csv).unwrap();
+        let names: Vec<String> = paths
+            .iter()
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "trades_0_999.csv",
+                "trades_1000_1999.csv",
+                "trades_3000_3999.csv"
+            ]
+        );
+        let csv = std::fs::read_to_string(&paths[1]).unwrap();
+        assert_eq!(csv.lines().count(), 3);
+        assert!(csv.starts_with("signature,slot,"));
+
+        let parquet = dir.join("all.parquet");
+        write_trades_parquet(&parquet, &trades).unwrap();
+        assert!(std::fs::metadata(&parquet).unwrap().len() > 0);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,99 @@
+//! Корпус записанных транзакций из `tests/fixtures/` (бенчмарки, тесты). Модуль есть
+//! только под `cfg(test)` и feature `fixtures`, которую включает dev-зависимость крейта
+//! на самого себя: пути берутся из `CARGO_MANIFEST_DIR` на этапе сборки.
+//!
+//! Корень — `tests/fixtures/`: файлы верхнего уровня — корпус бенчмарков, подкаталоги
+//! `<protocol>/` — регрессионные пары `<sig>.json` + `<sig>.expected.json` от `gen_fixture`.
+//! Формат транзакции — любой из принимаемых [`transaction_from_json`].
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use base64_simd::STANDARD;
+use serde_json::Value;
+
+use crate::core::rpc_json::transaction_from_json;
+use crate::types::SolanaTransaction;
+
+pub fn dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// Имена транзакций корпуса (путь от [`dir`] без `.json`, например
+/// `raydium_clmm/<sig>`) в алфавитном порядке; `*.expected.json` не входят.
+pub fn names() -> Result<Vec<String>> {
+    let mut names = Vec::new();
+    collect_names(&dir(), "", &mut names)?;
+    names.sort();
+    Ok(names)
+}
+
+fn collect_names(dir: &Path, prefix: &str, names: &mut Vec<String>) -> Result<()> {
+    for entry in std::fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if path.is_dir() {
+            collect_names(&path, &format!("{prefix}{file_name}/"), names)?;
+        } else if let Some(stem) = file_name.strip_suffix(".json") {
+            if !stem.ends_with(".expected") {
+                names.push(format!("{prefix}{stem}"));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn load_value(name: &str) -> Result<Value> {
+    let path = dir().join(format!("{name}.json"));
+    let data =
+        std::fs::read(&path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_slice(&data).with_context(|| format!("invalid JSON in {}", path.display()))
+}
+
+pub fn load(name: &str) -> Result<SolanaTransaction> {
+    transaction_from_json(&load_value(name)?).with_context(|| format!("fixture {name}"))
+}
+
+/// Все транзакции корпуса: `(имя, транзакция)`.
+pub fn load_all() -> Result<Vec<(String, SolanaTransaction)>> {
+    names()?
+        .into_iter()
+        .map(|name| Ok((name.clone(), load(&name)?)))
+        .collect()
+}
+
+/// Байты транзакции, если fixture записан в `base64`.
+pub fn raw_transaction(value: &Value) -> Option<Vec<u8>> {
+    match value.get("transaction")?.as_array()?.as_slice() {
+        [Value::String(encoded), Value::String(encoding)] if encoding == "base64" => {
+            STANDARD.decode_to_vec(encoded).ok()
+        }
+        _ => None,
+    }
+}
+
+/// Первая подпись из wire-формата (compact-u16 счётчик + 64 байта).
+pub fn raw_signature(raw: &[u8]) -> Option<String> {
+    // больше 127 подписей в транзакции не бывает, счётчик — один байт
+    if raw.first().is_none_or(|count| *count == 0 || *count > 127) {
+        return None;
+    }
+    Some(bs58::encode(raw.get(1..65)?).into_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_every_fixture_in_corpus() {
+        let fixtures = load_all().unwrap();
+        assert!(fixtures.iter().any(|(name, _)| name == "pumpfun_trade"));
+        assert!(fixtures.iter().any(|(name, _)| name.starts_with("meteora_dlmm/")));
+        for (name, tx) in &fixtures {
+            assert!(!tx.signature.is_empty(), "{name}");
+        }
+    }
+}
@@ -1,15 +1,46 @@
 //! Core library entry point exposing the parser and public data types.
 
+#[cfg(feature = "analysis")]
+pub mod analysis;
+#[cfg(feature = "arrow")]
+pub mod arrow;
+pub mod compat;
 pub mod config;
 pub mod core;
+#[cfg(feature = "export")]
+pub mod export;
+#[cfg(any(test, feature = "fixtures"))]
+pub mod fixtures;
+pub mod lifecycle;
+pub mod metadata;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+#[cfg(feature = "pipeline")]
+pub mod pipeline;
+pub mod price;
 pub mod protocols;
+#[cfg(feature = "rpc")]
 pub mod rpc;
+#[cfg(feature = "sink")]
+pub mod sink;
+pub mod state;
+pub mod state_decoders;
+#[cfg(feature = "grpc")]
+pub mod stream;
 pub mod types;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
-pub use crate::config::ParseConfig;
+pub use crate::config::{AggregateStrategy, BaseTokenConvention, FailedTxMode, ParseConfig};
+pub use crate::core::constants::{ProgramCatalog, ProgramEntry, ProtocolFamily};
 pub use crate::core::dex_parser::DexParser;
+pub use crate::core::idl::{IdlDecoder, IdlRegistry};
+pub use crate::core::owner_cache::AccountOwnerCache;
 pub use crate::types::{
-    BalanceChange, BlockInput, BlockParseResult, ClassifiedInstruction, DexInfo, MemeEvent,
-    ParseResult, PoolEvent, SolanaBlock, SolanaInstruction, SolanaTransaction, TokenAmount,
-    TradeInfo, TransactionMeta, TransactionStatus, TransferData,
+    BalanceChange, BlockContext, BlockEvent, BlockInput, BlockParseResult, ClassifiedInstruction,
+    ClobFillInfo, DecimalAmount, DecodedInstruction, DevBuy, DexInfo, EventOrderKey, FeeInfo,
+    FeeKind, LegDirection, LiquidityRole, MemeEvent, OrderSide, ParseDiagnostics, ParseResult,
+    ParseStage, ParserRun, PoolEvent, SkipReason, SkippedProgram, SolanaBlock, SolanaInstruction,
+    SolanaTransaction, StageTimings, TokenAmount, TokenLifecycleEvent, TradeInfo, TradeInfoLite,
+    TradeSide, TransactionMeta, TransactionStatus, TransferData,
 };
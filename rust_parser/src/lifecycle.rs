@@ -0,0 +1,331 @@
+//! Жизненный цикл meme-токенов по потоку `ParseResult`: события Pumpfun, Meteora DBC,
+//! Raydium LaunchLab и Moonshot сводятся в `TokenLifecycleEvent` и связываются по mint.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{
+    LifecycleMeta, ParseResult, RugReason, TokenLifecycleEvent, TradeSide, TradeType,
+    TransactionStatus,
+};
+
+/// Доля купленного создателем, после продажи которой токен считается слитым.
+const DEFAULT_DUMP_RATIO: f64 = 0.9;
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "camelCase")]
+pub enum LifecycleStage {
+    /// Торгуется на bonding curve.
+    #[default]
+    Curve,
+    Graduated,
+    /// Ликвидность перенесена в AMM-пул.
+    Pooled,
+}
+
+/// Что известно о mint'е по увиденным событиям.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenLifecycle {
+    pub mint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+    /// `None`, если поток начался после create.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator: Option<String>,
+    pub stage: LifecycleStage,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool_dex: Option<String>,
+    pub created_slot: u64,
+    pub curve_trades: u64,
+    /// Raw base, купленные и проданные создателем на кривой.
+    pub creator_bought_raw: u128,
+    pub creator_sold_raw: u128,
+    pub rugged: bool,
+}
+
+/// Сводит meme-события в единый поток и ведёт состояние по mint.
+#[derive(Debug)]
+pub struct LifecycleTracker {
+    tokens: HashMap<String, TokenLifecycle>,
+    /// pool → mint для мигрировавших токенов.
+    pools: HashMap<String, String>,
+    dump_ratio: f64,
+}
+
+impl Default for LifecycleTracker {
+    fn default() -> Self {
+        Self {
+            tokens: HashMap::new(),
+            pools: HashMap::new(),
+            dump_ratio: DEFAULT_DUMP_RATIO,
+        }
+    }
+}
+
+impl LifecycleTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Порог эвристики `RugReason::CreatorDump`: продано / куплено создателем.
+    pub fn with_dump_ratio(mut self, ratio: f64) -> Self {
+        self.dump_ratio = ratio;
+        self
+    }
+
+    /// События транзакции в порядке `meme_events`, затем rug по выводу ликвидности.
+    pub fn ingest(&mut self, result: &ParseResult) -> Vec<TokenLifecycleEvent> {
+        let mut out = Vec::new();
+        if !result.state || result.tx_status == TransactionStatus::Failed {
+            return out;
+        }
+        for event in result
+            .meme_events
+            .iter()
+            .filter_map(TokenLifecycleEvent::from_meme_event)
+        {
+            self.apply(event, &mut out);
+        }
+        for pool in &result.liquidities {
+            if pool.event_type != TradeType::Remove {
+                continue;
+            }
+            let Some(mint) = self.pools.get(&pool.pool_id) else {
+                continue;
+            };
+            let Some(token) = self.tokens.get_mut(mint) else {
+                continue;
+            };
+            if token.rugged {
+                continue;
+            }
+            token.rugged = true;
+            out.push(TokenLifecycleEvent::Rugged {
+                meta: LifecycleMeta {
+                    mint: token.mint.clone(),
+                    protocol: token.protocol.clone(),
+                    signature: pool.signature.clone(),
+                    slot: pool.slot,
+                    timestamp: pool.timestamp,
+                    idx: pool.idx.clone(),
+                },
+                reason: RugReason::LiquidityRemoved,
+                wallet: pool.user.clone(),
+            });
+        }
+        out
+    }
+
+    pub fn get(&self, mint: &str) -> Option<&TokenLifecycle> {
+        self.tokens.get(mint)
+    }
+
+    pub fn tokens(&self) -> impl Iterator<Item = &TokenLifecycle> {
+        self.tokens.values()
+    }
+
+    /// Перестать следить за mint'ом, например после rug.
+    pub fn forget(&mut self, mint: &str) -> Option<TokenLifecycle> {
+        let token = self.tokens.remove(mint)?;
+        if let Some(pool) = &token.pool {
+            self.pools.remove(pool);
+        }
+        Some(token)
+    }
+
+    fn apply(&mut self, event: TokenLifecycleEvent, out: &mut Vec<TokenLifecycleEvent>) {
+        let meta = event.meta();
+        let token = self
+            .tokens
+            .entry(meta.mint.clone())
+            .or_insert_with(|| TokenLifecycle {
+                mint: meta.mint.clone(),
+                created_slot: meta.slot,
+                ..TokenLifecycle::default()
+            });
+        if token.protocol.is_none() {
+            token.protocol.clone_from(&meta.protocol);
+        }
+
+        match &event {
+            TokenLifecycleEvent::Created { meta, creator, .. } => {
+                token.creator.clone_from(creator);
+                token.created_slot = meta.slot;
+            }
+            TokenLifecycleEvent::CurveTrade {
+                meta,
+                side,
+                user,
+                base_amount,
+                ..
+            } => {
+                token.curve_trades += 1;
+                if token.creator.as_ref() == Some(user) {
+                    let amount = base_amount
+                        .as_ref()
+                        .and_then(|amount| amount.amount.parse::<u128>().ok())
+                        .unwrap_or(0);
+                    match side {
+                        TradeSide::Buy => token.creator_bought_raw += amount,
+                        TradeSide::Sell => token.creator_sold_raw += amount,
+                    }
+                    let dumped = token.creator_bought_raw > 0
+                        && token.creator_sold_raw as f64
+                            >= token.creator_bought_raw as f64 * self.dump_ratio;
+                    if dumped && *side == TradeSide::Sell && !token.rugged {
+                        token.rugged = true;
+                        out.push(event.clone());
+                        out.push(TokenLifecycleEvent::Rugged {
+                            meta: meta.clone(),
+                            reason: RugReason::CreatorDump,
+                            wallet: user.clone(),
+                        });
+                        return;
+                    }
+                }
+            }
+            TokenLifecycleEvent::Graduated { .. } => {
+                if token.stage >= LifecycleStage::Graduated {
+                    return;
+                }
+                token.stage = LifecycleStage::Graduated;
+            }
+            TokenLifecycleEvent::PoolCreated {
+                meta,
+                pool,
+                pool_dex,
+                ..
+            } => {
+                if token.stage < LifecycleStage::Graduated {
+                    out.push(TokenLifecycleEvent::Graduated {
+                        meta: meta.clone(),
+                        bonding_curve: None,
+                    });
+                }
+                token.stage = LifecycleStage::Pooled;
+                token.pool_dex.clone_from(pool_dex);
+                if let Some(pool) = pool {
+                    token.pool = Some(pool.clone());
+                    self.pools.insert(pool.clone(), meta.mint.clone());
+                }
+            }
+            TokenLifecycleEvent::Rugged { .. } => {}
+        }
+        out.push(event);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{MemeEvent, PoolEvent, TokenInfo};
+
+    fn meme(event_type: TradeType, user: &str) -> MemeEvent {
+        MemeEvent {
+            event_type,
+            user: user.to_string(),
+            base_mint: "MINT".to_string(),
+            quote_mint: "SOL".to_string(),
+            protocol: Some("MeteoraDBC".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn curve_trade(side: TradeType, user: &str, base_raw: &str) -> MemeEvent {
+        let base = TokenInfo {
+            mint: "MINT".to_string(),
+            amount_raw: base_raw.to_string(),
+            ..TokenInfo::default()
+        };
+        let quote = TokenInfo {
+            mint: "SOL".to_string(),
+            ..TokenInfo::default()
+        };
+        let (input, output) = if side == TradeType::Buy {
+            (quote, base)
+        } else {
+            (base, quote)
+        };
+        MemeEvent {
+            input_token: Some(input),
+            output_token: Some(output),
+            ..meme(side, user)
+        }
+    }
+
+    fn tx(events: Vec<MemeEvent>) -> ParseResult {
+        let mut result = ParseResult::new();
+        result.meme_events = events;
+        result
+    }
+
+    fn kinds(events: &[TokenLifecycleEvent]) -> Vec<&'static str> {
+        events
+            .iter()
+            .map(|event| match event {
+                TokenLifecycleEvent::Created { .. } => "created",
+                TokenLifecycleEvent::CurveTrade { .. } => "trade",
+                TokenLifecycleEvent::Graduated { .. } => "graduated",
+                TokenLifecycleEvent::PoolCreated { .. } => "pool",
+                TokenLifecycleEvent::Rugged { .. } => "rugged",
+            })
+            .collect()
+    }
+
+    #[test]
+    fn synthesizes_graduation_and_tracks_pool() {
+        let mut tracker = LifecycleTracker::new();
+        let created = tracker.ingest(&tx(vec![
+            meme(TradeType::Create, "dev"),
+            curve_trade(TradeType::Buy, "dev", "1000"),
+        ]));
+        assert_eq!(kinds(&created), ["created", "trade"]);
+
+        let mut migrate = meme(TradeType::Migrate, "");
+        migrate.pool = Some("POOL".to_string());
+        assert_eq!(
+            kinds(&tracker.ingest(&tx(vec![migrate]))),
+            ["graduated", "pool"]
+        );
+        let token = tracker.get("MINT").unwrap();
+        assert_eq!(token.stage, LifecycleStage::Pooled);
+        assert_eq!(token.creator.as_deref(), Some("dev"));
+        assert_eq!(token.creator_bought_raw, 1000);
+
+        let mut remove = tx(Vec::new());
+        remove.liquidities.push(PoolEvent {
+            event_type: TradeType::Remove,
+            pool_id: "POOL".to_string(),
+            user: "dev".to_string(),
+            ..Default::default()
+        });
+        let rug = tracker.ingest(&remove);
+        assert!(matches!(
+            rug.as_slice(),
+            [TokenLifecycleEvent::Rugged {
+                reason: RugReason::LiquidityRemoved,
+                ..
+            }]
+        ));
+    }
+
+    #[test]
+    fn flags_creator_dump_once() {
+        let mut tracker = LifecycleTracker::new();
+        tracker.ingest(&tx(vec![
+            meme(TradeType::Create, "dev"),
+            curve_trade(TradeType::Buy, "dev", "1000"),
+            curve_trade(TradeType::Sell, "other", "1000"),
+        ]));
+        let dump = tracker.ingest(&tx(vec![
+            curve_trade(TradeType::Sell, "dev", "950"),
+            curve_trade(TradeType::Sell, "dev", "50"),
+        ]));
+        assert_eq!(kinds(&dump), ["trade", "rugged", "trade"]);
+        assert_eq!(tracker.get("MINT").unwrap().curve_trades, 4);
+    }
+}
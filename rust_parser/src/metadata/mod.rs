@@ -0,0 +1,180 @@
+//! Symbol/name/logo токенов из Metaplex Token Metadata.
+//!
+//! Синхронный `TokenMetadataSource` подключается к парсеру
+//! (`DexParser::with_metadata_source`) и применяется при `ParseConfig::resolve_metadata`.
+//! `TokenMetadataResolver` (feature `rpc`) загружает metadata PDA через RPC и хранит
+//! их в кэше на диске; как источник для парсера он отдаёт только уже загруженное,
+//! недостающее догружается через `TokenMetadataResolver::annotate`.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+
+use crate::types::ParseResult;
+
+#[cfg(feature = "rpc")]
+pub mod resolver;
+
+#[cfg(feature = "rpc")]
+pub use resolver::TokenMetadataResolver;
+
+pub const TOKEN_METADATA_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenMetadata {
+    pub mint: String,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub name: Option<String>,
+    /// Ссылка на off-chain JSON.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub uri: Option<String>,
+    /// `image` из off-chain JSON.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub logo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub decimals: Option<u8>,
+}
+
+pub trait TokenMetadataSource: Send + Sync {
+    /// `None` — metadata неизвестны, поля `*_symbol` останутся пустыми.
+    fn metadata(&self, mint: &str) -> Option<TokenMetadata>;
+}
+
+/// Статическая таблица — для тестов и известных списков токенов.
+impl TokenMetadataSource for HashMap<String, TokenMetadata> {
+    fn metadata(&self, mint: &str) -> Option<TokenMetadata> {
+        self.get(mint).cloned()
+    }
+}
+
+/// Адрес metadata PDA: `["metadata", program_id, mint]`.
+pub fn metadata_pda(mint: &Pubkey) -> Pubkey {
+    let program_id = Pubkey::try_from(TOKEN_METADATA_PROGRAM_ID).expect("valid program id");
+    Pubkey::find_program_address(
+        &[b"metadata", program_id.as_ref(), mint.as_ref()],
+        &program_id,
+    )
+    .0
+}
+
+/// name, symbol и uri из аккаунта Metaplex `Metadata` (v1 layout: key, update authority,
+/// mint, затем borsh-строки, добитые нулями до фиксированной длины).
+pub fn decode_metaplex_metadata(data: &[u8]) -> Option<TokenMetadata> {
+    const STRINGS_OFFSET: usize = 1 + 32 + 32;
+    let mint = Pubkey::try_from(data.get(33..65)?).ok()?;
+    let mut offset = STRINGS_OFFSET;
+    let mut read_string = || -> Option<Option<String>> {
+        let len = u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?) as usize;
+        let bytes = data.get(offset + 4..offset + 4 + len)?;
+        offset += 4 + len;
+        let value = String::from_utf8_lossy(bytes)
+            .trim_end_matches('\0')
+            .trim()
+            .to_string();
+        Some((!value.is_empty()).then_some(value))
+    };
+    let name = read_string()?;
+    let symbol = read_string()?;
+    let uri = read_string()?;
+    Some(TokenMetadata {
+        mint: mint.to_string(),
+        symbol,
+        name,
+        uri,
+        ..Default::default()
+    })
+}
+
+pub(crate) fn collect_mints(result: &ParseResult) -> Vec<String> {
+    let mut mints = HashSet::new();
+    for trade in result.trades.iter().chain(&result.aggregate_trade) {
+        mints.insert(trade.input_token.mint.as_str());
+        mints.insert(trade.output_token.mint.as_str());
+    }
+    for event in &result.liquidities {
+        mints.extend(event.tokens.iter().map(|leg| leg.mint.as_str()));
+    }
+    mints
+        .into_iter()
+        .filter(|mint| !mint.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Заполняет `input_symbol`/`output_symbol` у trades и `symbol` у ног pool events.
+pub fn annotate_metadata(result: &mut ParseResult, source: &dyn TokenMetadataSource) {
+    let mut cache: HashMap<String, Option<String>> = HashMap::new();
+    let mut symbol = |mint: &str| {
+        cache
+            .entry(mint.to_string())
+            .or_insert_with(|| source.metadata(mint).and_then(|meta| meta.symbol))
+            .clone()
+    };
+    for trade in result
+        .trades
+        .iter_mut()
+        .chain(result.aggregate_trade.as_mut())
+    {
+        trade.input_symbol = symbol(&trade.input_token.mint);
+        trade.output_symbol = symbol(&trade.output_token.mint);
+    }
+    for leg in result
+        .liquidities
+        .iter_mut()
+        .flat_map(|event| &mut event.tokens)
+    {
+        leg.symbol = symbol(&leg.mint);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{TokenInfo, TradeInfo};
+
+    fn borsh_string(out: &mut Vec<u8>, value: &str, padded: usize) {
+        let mut bytes = value.as_bytes().to_vec();
+        bytes.resize(padded, 0);
+        out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        out.extend_from_slice(&bytes);
+    }
+
+    #[test]
+    fn decodes_metadata_and_annotates_trades() {
+        let mint = Pubkey::new_unique();
+        let mut data = vec![4];
+        data.extend_from_slice(Pubkey::new_unique().as_ref());
+        data.extend_from_slice(mint.as_ref());
+        borsh_string(&mut data, "Bonk", 32);
+        borsh_string(&mut data, "BONK", 10);
+        borsh_string(&mut data, "https://example.com/bonk.json", 200);
+        data.extend_from_slice(&[0; 16]);
+
+        let meta = decode_metaplex_metadata(&data).unwrap();
+        assert_eq!(meta.mint, mint.to_string());
+        assert_eq!(meta.symbol.as_deref(), Some("BONK"));
+        assert_eq!(meta.name.as_deref(), Some("Bonk"));
+        assert_eq!(meta.uri.as_deref(), Some("https://example.com/bonk.json"));
+
+        let source: HashMap<String, TokenMetadata> = [(meta.mint.clone(), meta)].into();
+        let mut result = ParseResult::new();
+        result.trades.push(TradeInfo {
+            input_token: TokenInfo {
+                mint: mint.to_string(),
+                ..Default::default()
+            },
+            output_token: TokenInfo {
+                mint: "unknown".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        annotate_metadata(&mut result, &source);
+        assert_eq!(result.trades[0].input_symbol.as_deref(), Some("BONK"));
+        assert_eq!(result.trades[0].output_symbol, None);
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::rpc::RpcPool;
+use crate::types::ParseResult;
+
+use super::{
+    annotate_metadata, collect_mints, decode_metaplex_metadata, metadata_pda, TokenMetadata,
+    TokenMetadataSource,
+};
+
+/// Пар (metadata PDA, mint) в одном `getMultipleAccounts` (лимит — 100 аккаунтов).
+const MINTS_PER_REQUEST: usize = 50;
+/// Offset `decimals` в аккаунте SPL Token mint.
+const MINT_DECIMALS_OFFSET: usize = 44;
+
+/// Загрузчик Metaplex metadata с кэшем в памяти и (опционально) JSON-файлом на диске.
+///
+/// Mint'ы без metadata тоже кэшируются (пустые поля), чтобы не запрашивать их повторно.
+pub struct TokenMetadataResolver {
+    pool: Arc<RpcPool>,
+    cache: RwLock<HashMap<String, TokenMetadata>>,
+    cache_path: Option<PathBuf>,
+    fetch_logos: bool,
+    http: reqwest::Client,
+}
+
+impl TokenMetadataResolver {
+    pub fn new(pool: Arc<RpcPool>) -> Self {
+        Self {
+            pool,
+            cache: RwLock::new(HashMap::new()),
+            cache_path: None,
+            fetch_logos: false,
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Кэш читается из `path`, если файл есть, и перезаписывается после каждой загрузки.
+    pub fn with_cache_file(mut self, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if path.exists() {
+            let entries = load_cache(&path)?;
+            if let Ok(mut cache) = self.cache.write() {
+                cache.extend(entries);
+            }
+        }
+        self.cache_path = Some(path);
+        Ok(self)
+    }
+
+    /// Загружать off-chain JSON по `uri` ради `logo` — по запросу на mint.
+    pub fn with_logos(mut self, fetch_logos: bool) -> Self {
+        self.fetch_logos = fetch_logos;
+        self
+    }
+
+    /// Кладёт metadata в кэш (прогрев, тесты).
+    pub fn insert(&self, metadata: TokenMetadata) {
+        if let Ok(mut cache) = self.cache.write() {
+            cache.insert(metadata.mint.clone(), metadata);
+        }
+    }
+
+    pub fn cached_len(&self) -> usize {
+        self.cache
+            .read()
+            .map(|cache| cache.len())
+            .unwrap_or_default()
+    }
+
+    /// Metadata для `mints`; недостающие в кэше загружаются через RPC.
+    pub async fn resolve(&self, mints: &[&str]) -> Result<HashMap<String, TokenMetadata>> {
+        let mut found = HashMap::with_capacity(mints.len());
+        let mut missing = Vec::new();
+        if let Ok(cache) = self.cache.read() {
+            for mint in mints {
+                match cache.get(*mint) {
+                    Some(metadata) => {
+                        found.insert(mint.to_string(), metadata.clone());
+                    }
+                    None => {
+                        if let Ok(key) = mint.parse::<Pubkey>() {
+                            if !missing.contains(&key) {
+                                missing.push(key);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        if missing.is_empty() {
+            return Ok(found);
+        }
+
+        for chunk in missing.chunks(MINTS_PER_REQUEST) {
+            let keys: Vec<Pubkey> = chunk
+                .iter()
+                .flat_map(|mint| [metadata_pda(mint), *mint])
+                .collect();
+            let keys = &keys;
+            let accounts = self
+                .pool
+                .call(|client| async move { client.get_multiple_accounts(keys).await })
+                .await
+                .context("failed to fetch token metadata accounts")?;
+
+            for (mint, pair) in chunk.iter().zip(accounts.chunks(2)) {
+                let mut metadata = pair[0]
+                    .as_ref()
+                    .and_then(|account| decode_metaplex_metadata(&account.data))
+                    .unwrap_or_default();
+                metadata.mint = mint.to_string();
+                metadata.decimals = pair
+                    .get(1)
+                    .and_then(Option::as_ref)
+                    .and_then(|account| account.data.get(MINT_DECIMALS_OFFSET).copied());
+                if self.fetch_logos {
+                    if let Some(uri) = &metadata.uri {
+                        metadata.logo = self.fetch_logo(uri).await;
+                    }
+                }
+                self.insert(metadata.clone());
+                found.insert(metadata.mint.clone(), metadata);
+            }
+        }
+
+        if let Err(err) = self.save() {
+            tracing::warn!("failed to persist token metadata cache: {err:#}");
+        }
+        Ok(found)
+    }
+
+    async fn fetch_logo(&self, uri: &str) -> Option<String> {
+        let response = self.http.get(uri).send().await.ok()?;
+        let json: Value = response.error_for_status().ok()?.json().await.ok()?;
+        json.get("image")?.as_str().map(str::to_string)
+    }
+
+    /// Загружает недостающие metadata и заполняет `*_symbol` в результате.
+    pub async fn annotate(&self, result: &mut ParseResult) -> Result<()> {
+        let mints = collect_mints(result);
+        if mints.is_empty() {
+            return Ok(());
+        }
+        let mint_refs: Vec<&str> = mints.iter().map(String::as_str).collect();
+        let resolved = self.resolve(&mint_refs).await?;
+        annotate_metadata(result, &resolved);
+        Ok(())
+    }
+
+    /// Записывает кэш в файл из `with_cache_file`; без файла — ничего не делает.
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.cache_path else {
+            return Ok(());
+        };
+        let json = match self.cache.read() {
+            Ok(cache) => serde_json::to_vec(&*cache)?,
+            Err(_) => return Ok(()),
+        };
+        // через временный файл, чтобы не оставить обрезанный JSON
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json).with_context(|| format!("failed to write {}", tmp.display()))?;
+        std::fs::rename(&tmp, path).with_context(|| format!("failed to write {}", path.display()))
+    }
+}
+
+fn load_cache(path: &Path) -> Result<HashMap<String, TokenMetadata>> {
+    let data = std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_slice(&data)
+        .with_context(|| format!("invalid metadata cache {}", path.display()))
+}
+
+/// Только закэшированные metadata, без сети.
+impl TokenMetadataSource for TokenMetadataResolver {
+    fn metadata(&self, mint: &str) -> Option<TokenMetadata> {
+        self.cache.read().ok()?.get(mint).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn serves_cached_metadata_and_persists_it() {
+        let path = std::env::temp_dir().join(format!("dex-metadata-{}.json", std::process::id()));
+        let pool = Arc::new(RpcPool::new(["http://127.0.0.1:1"]).unwrap());
+        let resolver = TokenMetadataResolver::new(Arc::clone(&pool))
+            .with_cache_file(&path)
+            .unwrap();
+        let mint = Pubkey::new_unique().to_string();
+        resolver.insert(TokenMetadata {
+            mint: mint.clone(),
+            symbol: Some("TEST".to_string()),
+            ..Default::default()
+        });
+        // RPC не дёргается: mint уже в кэше
+        let resolved = resolver.resolve(&[mint.as_str()]).await.unwrap();
+        assert_eq!(resolved[&mint].symbol.as_deref(), Some("TEST"));
+        resolver.save().unwrap();
+
+        let reloaded = TokenMetadataResolver::new(pool)
+            .with_cache_file(&path)
+            .unwrap();
+        assert_eq!(
+            reloaded.metadata(&mint).and_then(|m| m.symbol).as_deref(),
+            Some("TEST")
+        );
+        std::fs::remove_file(&path).ok();
+    }
+}
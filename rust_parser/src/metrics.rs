@@ -0,0 +1,77 @@
+//! Метрики `DexParser` через фасад `metrics`: без установленного recorder'а вызовы
+//! почти ничего не стоят. Prometheus-эндпоинт для бинарников — `install_prometheus`
+//! (feature `prometheus`).
+
+use std::time::Duration;
+
+use metrics::{counter, histogram};
+
+use crate::core::error::ParserError;
+use crate::types::{ParseResult, TransactionStatus};
+
+/// Разобранные транзакции; labels `path` (`owned`/`zero_copy`), `status` (`success`/`failed`).
+pub const TRANSACTIONS_PARSED: &str = "dex_parser_transactions_parsed_total";
+/// Trades по протоколу; label `amm`.
+pub const TRADES: &str = "dex_parser_trades_total";
+/// Pool events по протоколу; label `amm`.
+pub const POOL_EVENTS: &str = "dex_parser_pool_events_total";
+/// Время разбора одной транзакции, секунды; label `path`.
+pub const PARSE_DURATION: &str = "dex_parser_parse_duration_seconds";
+/// Ошибки разбора; labels `path`, `kind` (`ParserError::kind`).
+pub const PARSE_ERRORS: &str = "dex_parser_errors_total";
+
+/// Бакеты `PARSE_DURATION`: разбор транзакции — от десятков микросекунд до миллисекунд.
+pub const DURATION_BUCKETS: [f64; 10] = [
+    0.000_025, 0.000_05, 0.000_1, 0.000_25, 0.000_5, 0.001, 0.002_5, 0.005, 0.01, 0.05,
+];
+
+pub(crate) fn record_parse(
+    path: &'static str,
+    elapsed: Duration,
+    outcome: &Result<ParseResult, ParserError>,
+) {
+    histogram!(PARSE_DURATION, "path" => path).record(elapsed.as_secs_f64());
+    let result = match outcome {
+        Ok(result) => result,
+        Err(err) => return record_error(path, err),
+    };
+    let status = match result.tx_status {
+        TransactionStatus::Failed => "failed",
+        _ => "success",
+    };
+    counter!(TRANSACTIONS_PARSED, "path" => path, "status" => status).increment(1);
+
+    let amms = result
+        .trades
+        .iter()
+        .map(|trade| &trade.amm)
+        .chain(result.trades_lite.iter().map(|trade| &trade.amm));
+    for amm in amms {
+        counter!(TRADES, "amm" => label(amm)).increment(1);
+    }
+    for event in &result.liquidities {
+        counter!(POOL_EVENTS, "amm" => label(&event.amm)).increment(1);
+    }
+}
+
+pub(crate) fn record_error(path: &'static str, err: &ParserError) {
+    counter!(PARSE_ERRORS, "path" => path, "kind" => err.kind()).increment(1);
+}
+
+fn label(amm: &Option<String>) -> String {
+    amm.clone().unwrap_or_else(|| "unknown".to_string())
+}
+
+/// Глобальный recorder с HTTP-эндпоинтом `/metrics` на `addr`. Внутри tokio runtime
+/// слушатель запускается в нём, иначе — в фоновом потоке.
+#[cfg(feature = "prometheus")]
+pub fn install_prometheus(
+    addr: std::net::SocketAddr,
+) -> Result<(), metrics_exporter_prometheus::BuildError> {
+    use metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
+
+    PrometheusBuilder::new()
+        .with_http_listener(addr)
+        .set_buckets_for_metric(Matcher::Full(PARSE_DURATION.to_string()), &DURATION_BUCKETS)?
+        .install()
+}
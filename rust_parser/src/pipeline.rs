@@ -0,0 +1,551 @@
+//! Стриминговый ingestion: источник → декодер → `DexParser` → sinks.
+//!
+//! Стадии связаны ограниченными очередями; при переполнении очередь либо держит
+//! производителя (`Overflow::Block`, backpressure до источника), либо теряет сообщения
+//! (`DropNewest`/`DropOldest`) и считает их в `PipelineStats`. Декодирование и разбор
+//! идут в `workers` задачах на blocking-пуле tokio (`spawn_blocking`), не занимая потоки
+//! runtime, поэтому порядок результатов не сохраняется — для сортировки
+//! есть `TradeInfo::order_key`/`slot`. Stateful sink'и с порядком по кошельку или пулу
+//! оборачиваются в `ShardedExecutor`.
+//!
+//! ```no_run
+//! # async fn run(sink: impl solana_dex_parser::sink::EventSink + 'static) -> anyhow::Result<()> {
+//! use solana_dex_parser::pipeline::{Overflow, Pipeline, StageConfig};
+//! use solana_dex_parser::rpc::{SubscriberConfig, TransactionSubscriber};
+//!
+//! let source = TransactionSubscriber::new(SubscriberConfig {
+//!     ws_url: "wss://atlas-mainnet.helius-rpc.com/?api-key=...".to_string(),
+//!     ..Default::default()
+//! })
+//! .subscribe_transactions();
+//! let stats = Pipeline::transactions(source)
+//!     .with_workers(4)
+//!     .with_input_stage(StageConfig::new(4096, Overflow::DropOldest))
+//!     .with_sink(sink, StageConfig::new(1024, Overflow::Block))
+//!     .spawn()
+//!     .join()
+//!     .await?;
+//! println!("parsed {} transactions", stats.parsed);
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use async_channel::TrySendError;
+use futures::stream::BoxStream;
+use futures::{Stream, StreamExt};
+use tokio::task::JoinHandle;
+
+use crate::config::ParseConfig;
+use crate::core::dex_parser::DexParser;
+use crate::sink::EventSink;
+use crate::types::{ParseResult, SolanaTransaction};
+
+pub mod sharded;
+
+pub use sharded::{ShardKey, ShardedExecutor};
+
+/// Поведение очереди стадии при заполнении.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// Ждать места: медленный потребитель тормозит всю цепочку до источника.
+    #[default]
+    Block,
+    /// Отбросить новое сообщение.
+    DropNewest,
+    /// Вытеснить самое старое сообщение очереди.
+    DropOldest,
+}
+
+/// Ёмкость и политика очереди перед стадией.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StageConfig {
+    pub capacity: usize,
+    pub overflow: Overflow,
+}
+
+impl StageConfig {
+    pub fn new(capacity: usize, overflow: Overflow) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            overflow,
+        }
+    }
+}
+
+impl Default for StageConfig {
+    fn default() -> Self {
+        Self::new(1024, Overflow::Block)
+    }
+}
+
+/// Счётчики пайплайна; `dropped_*` — потери по политикам `Overflow`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PipelineStats {
+    pub received: u64,
+    /// Декодер вернул ошибку.
+    pub decode_errors: u64,
+    /// Декодер вернул `None` (не та кодировка, служебное сообщение).
+    pub skipped: u64,
+    pub parsed: u64,
+    pub dropped_input: u64,
+    pub dropped_parsed: u64,
+    /// По sink'ам в порядке `with_sink`.
+    pub dropped_sinks: Vec<u64>,
+}
+
+type Decoder<T> = Arc<dyn Fn(T) -> Result<Option<SolanaTransaction>> + Send + Sync>;
+
+struct SinkStage {
+    sink: Box<dyn EventSink>,
+    stage: StageConfig,
+    flush_every: usize,
+}
+
+/// Builder пайплайна; `spawn` запускает задачи в текущем tokio runtime.
+pub struct Pipeline<T> {
+    source: BoxStream<'static, T>,
+    decoder: Decoder<T>,
+    parser: Arc<DexParser>,
+    parse_config: Option<ParseConfig>,
+    workers: usize,
+    input_stage: StageConfig,
+    parsed_stage: StageConfig,
+    sinks: Vec<SinkStage>,
+}
+
+impl Pipeline<SolanaTransaction> {
+    /// Источник уже декодированных транзакций (`TransactionSubscriber::subscribe_transactions`).
+    pub fn transactions(source: impl Stream<Item = SolanaTransaction> + Send + 'static) -> Self {
+        Self::new(source, |tx| Ok(Some(tx)))
+    }
+}
+
+impl<T: Send + 'static> Pipeline<T> {
+    /// Сырые сообщения и их декодер: например `rpc::ws::decode_transaction_notification`
+    /// для JSON уведомлений или `SolanaTransaction::try_from` для Geyser
+    /// `SubscribeUpdateTransaction`.
+    pub fn new<S, D>(source: S, decoder: D) -> Self
+    where
+        S: Stream<Item = T> + Send + 'static,
+        D: Fn(T) -> Result<Option<SolanaTransaction>> + Send + Sync + 'static,
+    {
+        Self {
+            source: source.boxed(),
+            decoder: Arc::new(decoder),
+            parser: Arc::new(DexParser::new()),
+            parse_config: None,
+            workers: 1,
+            input_stage: StageConfig::default(),
+            parsed_stage: StageConfig::default(),
+            sinks: Vec::new(),
+        }
+    }
+
+    pub fn with_parser(mut self, parser: Arc<DexParser>) -> Self {
+        self.parser = parser;
+        self
+    }
+
+    pub fn with_parse_config(mut self, config: ParseConfig) -> Self {
+        self.parse_config = Some(config);
+        self
+    }
+
+    /// Число задач декодирования и разбора.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers.max(1);
+        self
+    }
+
+    /// Очередь между источником и воркерами.
+    pub fn with_input_stage(mut self, stage: StageConfig) -> Self {
+        self.input_stage = stage;
+        self
+    }
+
+    /// Очередь между воркерами и раздачей по sink'ам.
+    pub fn with_parsed_stage(mut self, stage: StageConfig) -> Self {
+        self.parsed_stage = stage;
+        self
+    }
+
+    /// Sink со своей очередью; `flush` — каждые 100 транзакций и в конце потока.
+    pub fn with_sink(self, sink: impl EventSink + 'static, stage: StageConfig) -> Self {
+        self.with_sink_flushing(sink, stage, 100)
+    }
+
+    pub fn with_sink_flushing(
+        mut self,
+        sink: impl EventSink + 'static,
+        stage: StageConfig,
+        flush_every: usize,
+    ) -> Self {
+        self.sinks.push(SinkStage {
+            sink: Box::new(sink),
+            stage,
+            flush_every: flush_every.max(1),
+        });
+        self
+    }
+
+    pub fn spawn(self) -> PipelineHandle {
+        let counters = Arc::new(Counters::default());
+        let input = Arc::new(Queue::new(self.input_stage));
+        let parsed = Arc::new(Queue::new(self.parsed_stage));
+        let sink_queues: Vec<Arc<Queue<Arc<ParseResult>>>> = self
+            .sinks
+            .iter()
+            .map(|sink| Arc::new(Queue::new(sink.stage)))
+            .collect();
+
+        let mut source = self.source;
+        let source_queue = input.clone();
+        let source_counters = counters.clone();
+        let source_task = tokio::spawn(async move {
+            while let Some(item) = source.next().await {
+                source_counters.received.fetch_add(1, Ordering::Relaxed);
+                if !source_queue.push(item).await {
+                    return;
+                }
+            }
+            source_queue.close();
+        });
+
+        let workers: Vec<JoinHandle<()>> = (0..self.workers)
+            .map(|_| {
+                let (input, parsed, counters) = (input.clone(), parsed.clone(), counters.clone());
+                let (decoder, parser) = (self.decoder.clone(), self.parser.clone());
+                let config = self.parse_config.clone();
+                tokio::spawn(async move {
+                    while let Some(item) = input.pop().await {
+                        let (decoder, parser, config) =
+                            (decoder.clone(), parser.clone(), config.clone());
+                        let outcome = tokio::task::spawn_blocking(move || {
+                            decoder(item).map(|tx| tx.map(|tx| parser.parse_all(tx, config)))
+                        })
+                        .await
+                        // паника разбора — паника воркера, её отдаёт `join`
+                        .unwrap_or_else(|err| std::panic::resume_unwind(err.into_panic()));
+                        let result = match outcome {
+                            Ok(Some(result)) => result,
+                            Ok(None) => {
+                                counters.skipped.fetch_add(1, Ordering::Relaxed);
+                                continue;
+                            }
+                            Err(err) => {
+                                tracing::debug!("pipeline decoder error: {err:#}");
+                                counters.decode_errors.fetch_add(1, Ordering::Relaxed);
+                                continue;
+                            }
+                        };
+                        counters.parsed.fetch_add(1, Ordering::Relaxed);
+                        if !parsed.push(Arc::new(result)).await {
+                            return;
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let fanout_task = {
+            let (input, parsed, queues) = (input.clone(), parsed.clone(), sink_queues.clone());
+            tokio::spawn(async move {
+                while let Some(result) = parsed.pop().await {
+                    let mut delivered = queues.is_empty();
+                    for queue in &queues {
+                        delivered |= queue.push(result.clone()).await;
+                    }
+                    // все sink'и остановились — останавливаем воркеры и источник
+                    if !delivered {
+                        input.close();
+                        parsed.close();
+                        return;
+                    }
+                }
+                queues.iter().for_each(|queue| queue.close());
+            })
+        };
+
+        let sink_tasks: Vec<JoinHandle<Result<()>>> = self
+            .sinks
+            .into_iter()
+            .zip(sink_queues.iter().cloned())
+            .map(|(mut stage, queue)| {
+                tokio::spawn(async move {
+                    let outcome = drain(&mut stage, &queue).await;
+                    // упавший sink не должен блокировать раздачу остальным
+                    queue.close();
+                    outcome
+                })
+            })
+            .collect();
+
+        let task = {
+            let parsed = parsed.clone();
+            tokio::spawn(async move {
+                let mut first_error = None;
+                for worker in workers {
+                    if let Err(err) = worker.await {
+                        first_error.get_or_insert(anyhow!("pipeline worker panicked: {err}"));
+                    }
+                }
+                parsed.close();
+                let _ = fanout_task.await;
+                for sink in sink_tasks {
+                    let outcome = sink
+                        .await
+                        .unwrap_or_else(|err| Err(anyhow!("pipeline sink panicked: {err}")));
+                    if let Err(err) = outcome {
+                        first_error.get_or_insert(err);
+                    }
+                }
+                // источник мог остаться в ожидании следующего сообщения
+                source_task.abort();
+                first_error.map_or(Ok(()), Err)
+            })
+        };
+
+        PipelineHandle {
+            task,
+            counters,
+            input,
+            parsed,
+            sinks: sink_queues.into_iter().map(|queue| queue as _).collect(),
+        }
+    }
+}
+
+async fn drain(stage: &mut SinkStage, queue: &Queue<Arc<ParseResult>>) -> Result<()> {
+    let mut unflushed = 0usize;
+    while let Some(result) = queue.pop().await {
+        stage.sink.on_result(&result).await?;
+        unflushed += 1;
+        if unflushed >= stage.flush_every {
+            stage.sink.flush().await?;
+            unflushed = 0;
+        }
+    }
+    stage.sink.flush().await
+}
+
+#[derive(Default)]
+struct Counters {
+    received: AtomicU64,
+    decode_errors: AtomicU64,
+    skipped: AtomicU64,
+    parsed: AtomicU64,
+}
+
+/// Запущенный пайплайн: завершается, когда источник иссяк и все очереди выбраны,
+/// либо когда остановились все sink'и.
+pub struct PipelineHandle {
+    task: JoinHandle<Result<()>>,
+    counters: Arc<Counters>,
+    input: Arc<dyn Stage>,
+    parsed: Arc<dyn Stage>,
+    sinks: Vec<Arc<dyn Stage>>,
+}
+
+impl PipelineHandle {
+    /// Текущие счётчики, пока пайплайн работает.
+    pub fn stats(&self) -> PipelineStats {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        PipelineStats {
+            received: load(&self.counters.received),
+            decode_errors: load(&self.counters.decode_errors),
+            skipped: load(&self.counters.skipped),
+            parsed: load(&self.counters.parsed),
+            dropped_input: self.input.dropped(),
+            dropped_parsed: self.parsed.dropped(),
+            dropped_sinks: self.sinks.iter().map(|sink| sink.dropped()).collect(),
+        }
+    }
+
+    /// Перестаёт принимать сообщения источника; уже принятые дорабатываются и
+    /// сбрасываются в sink'и (graceful shutdown по сигналу).
+    pub fn shutdown(&self) {
+        self.input.close();
+    }
+
+    /// Ждёт завершения; ошибка — первая ошибка sink'а.
+    pub async fn join(mut self) -> Result<PipelineStats> {
+        (&mut self.task)
+            .await
+            .map_err(|err| anyhow!("pipeline task failed: {err}"))??;
+        Ok(self.stats())
+    }
+}
+
+/// Очередь стадии без типа элементов — для `PipelineHandle`.
+trait Stage: Send + Sync {
+    fn close(&self);
+    fn dropped(&self) -> u64;
+}
+
+/// Ограниченная MPMC-очередь (`async_channel`) с политикой `Overflow`. После `close`
+/// push отказывает, а pop отдаёт остаток и затем `None`.
+struct Queue<T> {
+    sender: async_channel::Sender<T>,
+    // свой receiver держит канал открытым до явного `close` и нужен для `DropOldest`
+    receiver: async_channel::Receiver<T>,
+    overflow: Overflow,
+    dropped: AtomicU64,
+}
+
+impl<T> Queue<T> {
+    fn new(stage: StageConfig) -> Self {
+        let (sender, receiver) = async_channel::bounded(stage.capacity);
+        Self {
+            sender,
+            receiver,
+            overflow: stage.overflow,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    /// `false` — очередь закрыта, сообщение не принято.
+    async fn push(&self, item: T) -> bool {
+        let mut item = match self.overflow {
+            Overflow::Block => return self.sender.send(item).await.is_ok(),
+            _ => item,
+        };
+        loop {
+            match self.sender.try_send(item) {
+                Ok(()) => return true,
+                Err(TrySendError::Closed(_)) => return false,
+                Err(TrySendError::Full(rejected)) => {
+                    if self.overflow == Overflow::DropNewest {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        return true;
+                    }
+                    // DropOldest: освобождаем место; гонку с потребителем решает повтор
+                    if self.receiver.try_recv().is_ok() {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                    }
+                    item = rejected;
+                }
+            }
+        }
+    }
+
+    async fn pop(&self) -> Option<T> {
+        self.receiver.recv().await.ok()
+    }
+}
+
+impl<T: Send> Stage for Queue<T> {
+    fn close(&self) {
+        self.sender.close();
+    }
+
+    fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::future::BoxFuture;
+    use std::sync::Mutex;
+
+    use crate::types::{PoolEvent, TradeInfo, TransferData};
+
+    #[derive(Clone, Default)]
+    struct Collect {
+        signatures: Arc<Mutex<Vec<String>>>,
+        flushes: Arc<AtomicU64>,
+    }
+
+    impl EventSink for Collect {
+        fn on_trade<'a>(&'a mut self, _: &'a TradeInfo) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async { Ok(()) })
+        }
+        fn on_pool_event<'a>(&'a mut self, _: &'a PoolEvent) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async { Ok(()) })
+        }
+        fn on_transfer<'a>(&'a mut self, _: &'a TransferData) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async { Ok(()) })
+        }
+        fn flush(&mut self) -> BoxFuture<'_, Result<()>> {
+            self.flushes.fetch_add(1, Ordering::Relaxed);
+            Box::pin(async { Ok(()) })
+        }
+        fn on_result<'a>(&'a mut self, result: &'a ParseResult) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async move {
+                tokio::time::sleep(std::time::Duration::from_millis(1)).await;
+                let signature = result.signature.clone();
+                self.signatures.lock().unwrap().push(signature);
+                Ok(())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn queue_applies_overflow_policy_and_drains_after_close() {
+        let oldest = Queue::new(StageConfig::new(2, Overflow::DropOldest));
+        let newest = Queue::new(StageConfig::new(2, Overflow::DropNewest));
+        for i in 0..4 {
+            assert!(oldest.push(i).await);
+            assert!(newest.push(i).await);
+        }
+        oldest.close();
+        newest.close();
+        assert!(!oldest.push(9).await);
+
+        let (mut kept_oldest, mut kept_newest) = (Vec::new(), Vec::new());
+        while let Some(i) = oldest.pop().await {
+            kept_oldest.push(i);
+        }
+        while let Some(i) = newest.pop().await {
+            kept_newest.push(i);
+        }
+        assert_eq!(kept_oldest, vec![2, 3]);
+        assert_eq!(kept_newest, vec![0, 1]);
+        assert_eq!((oldest.dropped(), newest.dropped()), (2, 2));
+    }
+
+    #[tokio::test]
+    async fn delivers_to_every_sink_and_drops_per_stage_policy() {
+        let messages = (0..50).map(|i| {
+            if i % 10 == 9 {
+                Err(anyhow!("garbage frame"))
+            } else {
+                Ok(SolanaTransaction {
+                    signature: format!("sig-{i}"),
+                    ..Default::default()
+                })
+            }
+        });
+        let (exact, lossy) = (Collect::default(), Collect::default());
+        let stats = Pipeline::new(futures::stream::iter(messages), |msg| msg.map(Some))
+            .with_workers(3)
+            .with_input_stage(StageConfig::new(4, Overflow::Block))
+            .with_sink_flushing(exact.clone(), StageConfig::new(2, Overflow::Block), 10)
+            .with_sink(lossy.clone(), StageConfig::new(1, Overflow::DropOldest))
+            .spawn()
+            .join()
+            .await
+            .unwrap();
+
+        assert_eq!(stats.received, 50);
+        assert_eq!(stats.decode_errors, 5);
+        assert_eq!(stats.parsed, 45);
+        assert_eq!((stats.dropped_input, stats.dropped_parsed), (0, 0));
+
+        let mut delivered = exact.signatures.lock().unwrap().clone();
+        delivered.sort();
+        delivered.dedup();
+        assert_eq!(delivered.len(), 45);
+        assert_eq!(exact.flushes.load(Ordering::Relaxed), 5);
+
+        // медленный sink с очередью на одно сообщение теряет, но счёт сходится
+        let lossy_delivered = lossy.signatures.lock().unwrap().len() as u64;
+        assert_eq!(stats.dropped_sinks[0], 0);
+        assert_eq!(lossy_delivered + stats.dropped_sinks[1], 45);
+    }
+}
@@ -0,0 +1,231 @@
+//! Шардирование событий по кошельку или пулу: каждый ключ всегда попадает в один и тот же
+//! воркер, поэтому события одного ключа обрабатываются по порядку, а разные ключи —
+//! параллельно. Подходит для stateful потребителей (PnL, состояние пулов).
+//!
+//! Порядок сохраняется относительно порядка вызовов `on_*`. В `Pipeline` разбор идёт в
+//! нескольких воркерах и переставляет транзакции — для строгого порядка по ключу нужен
+//! `with_workers(1)`.
+
+use std::hash::{Hash, Hasher};
+
+use anyhow::{anyhow, Result};
+use futures::future::BoxFuture;
+use rustc_hash::FxHasher;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+use crate::sink::EventSink;
+use crate::types::{PoolEvent, TradeInfo, TransferData};
+
+/// По какому полю события выбирается шард.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ShardKey {
+    /// `TradeInfo::user` (иначе первый signer), `PoolEvent::user`.
+    #[default]
+    Signer,
+    /// Первый аккаунт `TradeInfo::pool`, `PoolEvent::pool_id`.
+    Pool,
+}
+
+impl ShardKey {
+    fn trade<'a>(&self, trade: &'a TradeInfo) -> Option<&'a str> {
+        match self {
+            Self::Signer => trade
+                .user
+                .as_deref()
+                .or_else(|| trade.signer.as_ref()?.first().map(String::as_str)),
+            Self::Pool => trade.pool.first().map(String::as_str),
+        }
+    }
+
+    fn pool_event<'a>(&self, event: &'a PoolEvent) -> &'a str {
+        match self {
+            Self::Signer => &event.user,
+            Self::Pool => &event.pool_id,
+        }
+    }
+}
+
+enum ShardMessage {
+    Trade(Box<TradeInfo>),
+    PoolEvent(Box<PoolEvent>),
+    Transfer(Box<TransferData>),
+    Flush(oneshot::Sender<Result<()>>),
+}
+
+/// `EventSink`, раздающий события по `shards` воркерам, у каждого — свой sink.
+pub struct ShardedExecutor {
+    key: ShardKey,
+    shards: Vec<mpsc::Sender<ShardMessage>>,
+    tasks: Vec<JoinHandle<Result<()>>>,
+}
+
+impl ShardedExecutor {
+    /// `make_sink(i)` создаёт sink шарда `i`; `capacity` — очередь шарда, при заполнении
+    /// вызывающий ждёт (drop здесь нарушил бы порядок).
+    pub fn new<S, F>(key: ShardKey, shards: usize, capacity: usize, mut make_sink: F) -> Self
+    where
+        S: EventSink + 'static,
+        F: FnMut(usize) -> S,
+    {
+        let (senders, tasks) = (0..shards.max(1))
+            .map(|shard| {
+                let (tx, rx) = mpsc::channel(capacity.max(1));
+                (tx, tokio::spawn(run_shard(make_sink(shard), rx)))
+            })
+            .unzip();
+        Self {
+            key,
+            shards: senders,
+            tasks,
+        }
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Шард ключа; стабилен между запусками (FxHash без случайного seed).
+    pub fn shard_of(&self, key: &str) -> usize {
+        let mut hasher = FxHasher::default();
+        key.hash(&mut hasher);
+        (hasher.finish() % self.shards.len() as u64) as usize
+    }
+
+    /// Сбрасывает все шарды, закрывает очереди и ждёт воркеры.
+    pub async fn shutdown(mut self) -> Result<()> {
+        self.flush_all().await?;
+        self.shards.clear();
+        for task in std::mem::take(&mut self.tasks) {
+            task.await
+                .map_err(|err| anyhow!("shard task failed: {err}"))??;
+        }
+        Ok(())
+    }
+
+    async fn send(&self, key: &str, message: ShardMessage) -> Result<()> {
+        let shard = self.shard_of(key);
+        self.shards[shard]
+            .send(message)
+            .await
+            .map_err(|_| anyhow!("shard {shard} stopped after a sink error"))
+    }
+
+    async fn flush_all(&self) -> Result<()> {
+        let mut acks = Vec::with_capacity(self.shards.len());
+        for (shard, sender) in self.shards.iter().enumerate() {
+            let (ack, done) = oneshot::channel();
+            sender
+                .send(ShardMessage::Flush(ack))
+                .await
+                .map_err(|_| anyhow!("shard {shard} stopped after a sink error"))?;
+            acks.push(done);
+        }
+        for done in acks {
+            done.await.map_err(|_| anyhow!("shard dropped flush"))??;
+        }
+        Ok(())
+    }
+}
+
+impl EventSink for ShardedExecutor {
+    fn on_trade<'a>(&'a mut self, trade: &'a TradeInfo) -> BoxFuture<'a, Result<()>> {
+        let key = self.key.trade(trade).unwrap_or(&trade.signature);
+        Box::pin(self.send(key, ShardMessage::Trade(Box::new(trade.clone()))))
+    }
+
+    fn on_pool_event<'a>(&'a mut self, event: &'a PoolEvent) -> BoxFuture<'a, Result<()>> {
+        let key = self.key.pool_event(event);
+        Box::pin(self.send(key, ShardMessage::PoolEvent(Box::new(event.clone()))))
+    }
+
+    /// Переводы не знают пула — в обоих режимах шардируются по authority (иначе source).
+    fn on_transfer<'a>(&'a mut self, transfer: &'a TransferData) -> BoxFuture<'a, Result<()>> {
+        let info = &transfer.info;
+        let key = info.authority.as_ref().unwrap_or(&info.source).as_str();
+        Box::pin(self.send(key, ShardMessage::Transfer(Box::new(transfer.clone()))))
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.flush_all())
+    }
+}
+
+async fn run_shard<S: EventSink>(mut sink: S, mut rx: mpsc::Receiver<ShardMessage>) -> Result<()> {
+    while let Some(message) = rx.recv().await {
+        match message {
+            ShardMessage::Trade(trade) => sink.on_trade(&trade).await?,
+            ShardMessage::PoolEvent(event) => sink.on_pool_event(&event).await?,
+            ShardMessage::Transfer(transfer) => sink.on_transfer(&transfer).await?,
+            ShardMessage::Flush(ack) => {
+                let _ = ack.send(sink.flush().await);
+            }
+        }
+    }
+    sink.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    type Seen = Arc<Mutex<Vec<(usize, String, u64)>>>;
+
+    struct Record {
+        shard: usize,
+        seen: Seen,
+    }
+
+    impl EventSink for Record {
+        fn on_trade<'a>(&'a mut self, trade: &'a TradeInfo) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async move {
+                // шарды с разной скоростью: глобальный порядок перемешивается
+                tokio::time::sleep(std::time::Duration::from_micros(50 * self.shard as u64)).await;
+                let user = trade.user.clone().unwrap_or_default();
+                self.seen
+                    .lock()
+                    .unwrap()
+                    .push((self.shard, user, trade.slot));
+                Ok(())
+            })
+        }
+        fn on_pool_event<'a>(&'a mut self, _: &'a PoolEvent) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async { Ok(()) })
+        }
+        fn on_transfer<'a>(&'a mut self, _: &'a TransferData) -> BoxFuture<'a, Result<()>> {
+            Box::pin(async { Ok(()) })
+        }
+        fn flush(&mut self) -> BoxFuture<'_, Result<()>> {
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn keeps_per_wallet_order_across_shards() {
+        let seen = Seen::default();
+        let mut executor = ShardedExecutor::new(ShardKey::Signer, 4, 8, |shard| Record {
+            shard,
+            seen: seen.clone(),
+        });
+        for slot in 0..40 {
+            let trade = TradeInfo {
+                user: Some(format!("wallet-{}", slot % 7)),
+                slot,
+                ..Default::default()
+            };
+            executor.on_trade(&trade).await.unwrap();
+        }
+        executor.flush().await.unwrap();
+        assert_eq!(seen.lock().unwrap().len(), 40);
+        executor.shutdown().await.unwrap();
+
+        let seen = seen.lock().unwrap();
+        for wallet in 0..7 {
+            let user = format!("wallet-{wallet}");
+            let events: Vec<_> = seen.iter().filter(|(_, u, _)| *u == user).collect();
+            assert!(events.windows(2).all(|pair| pair[0].2 < pair[1].2));
+            assert!(events.iter().all(|(shard, _, _)| *shard == events[0].0));
+        }
+    }
+}
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use super::{AsyncPriceOracle, BoxFuture, PriceMap};
+
+const DEFAULT_ENDPOINT: &str = "https://lite-api.jup.ag/price/v2";
+/// Лимит ids в одном запросе Price API.
+const MAX_IDS_PER_REQUEST: usize = 100;
+const DEFAULT_TTL: Duration = Duration::from_secs(30);
+/// Выше этого кеш при вставке чистится от протухших записей.
+const MAX_CACHED_MINTS: usize = 100_000;
+
+/// Цены из Jupiter Price API v2 (`GET {endpoint}?ids=<mint>,<mint>`).
+///
+/// Только `AsyncPriceOracle`: сеть не стоит на пути парсинга, цены навешиваются на
+/// готовый результат через `annotate_usd_async` / `annotate_block_usd_async`. Ответы
+/// (и mint'ы без цены) кешируются на `ttl`, в запрос идут только недостающие mint'ы.
+pub struct JupiterPriceOracle {
+    endpoint: String,
+    client: reqwest::Client,
+    ttl: Duration,
+    cache: Mutex<HashMap<String, CachedPrice>>,
+}
+
+struct CachedPrice {
+    price: Option<f64>,
+    fetched_at: Instant,
+}
+
+impl Default for JupiterPriceOracle {
+    fn default() -> Self {
+        Self::new(DEFAULT_ENDPOINT)
+    }
+}
+
+impl JupiterPriceOracle {
+    pub fn new(endpoint: impl Into<String>) -> Self {
+        Self {
+            endpoint: endpoint.into(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(5))
+                .build()
+                .expect("reqwest client"),
+            ttl: DEFAULT_TTL,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Сколько держать цену в кеше; `Duration::ZERO` — без кеша.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    fn url(&self, mints: &[&str]) -> String {
+        format!("{}?ids={}", self.endpoint, mints.join(","))
+    }
+
+    /// Свежие цены из кеша в `prices`; возвращает mint'ы, за которыми надо в сеть.
+    fn take_cached<'m>(&self, mints: &[&'m str], prices: &mut PriceMap) -> Vec<&'m str> {
+        let cache = self.cache.lock().unwrap_or_else(|err| err.into_inner());
+        let now = Instant::now();
+        mints
+            .iter()
+            .copied()
+            .filter(|mint| match cache.get(*mint) {
+                Some(cached) if now.duration_since(cached.fetched_at) < self.ttl => {
+                    prices.extend(cached.price.map(|price| (mint.to_string(), price)));
+                    false
+                }
+                _ => true,
+            })
+            .collect()
+    }
+
+    /// Запоминает ответ; запрошенные mint'ы без цены — тоже, чтобы не спрашивать их
+    /// каждый раз. Неудачные запросы не кешируются.
+    fn store(&self, requested: &[&str], fetched: &PriceMap) {
+        if self.ttl.is_zero() {
+            return;
+        }
+        let mut cache = self.cache.lock().unwrap_or_else(|err| err.into_inner());
+        let now = Instant::now();
+        if cache.len() + requested.len() > MAX_CACHED_MINTS {
+            cache.retain(|_, cached| now.duration_since(cached.fetched_at) < self.ttl);
+        }
+        for mint in requested {
+            let price = fetched.get(*mint).copied();
+            cache.insert(mint.to_string(), CachedPrice { price, fetched_at: now });
+        }
+    }
+
+    async fn fetch(&self, mints: &[&str]) -> Option<PriceMap> {
+        let response = match self.client.get(self.url(mints)).send().await {
+            Ok(response) => response.json::<Value>().await,
+            Err(err) => Err(err),
+        };
+        match response {
+            Ok(body) => Some(parse_prices(&body)),
+            Err(err) => {
+                tracing::warn!("jupiter price request failed: {err}");
+                None
+            }
+        }
+    }
+}
+
+impl AsyncPriceOracle for JupiterPriceOracle {
+    fn prices_usd_async<'a>(&'a self, mints: &'a [&'a str]) -> BoxFuture<'a, PriceMap> {
+        Box::pin(async move {
+            let mut prices = PriceMap::new();
+            let missing = self.take_cached(mints, &mut prices);
+            for chunk in missing.chunks(MAX_IDS_PER_REQUEST) {
+                if let Some(fetched) = self.fetch(chunk).await {
+                    self.store(chunk, &fetched);
+                    prices.extend(fetched);
+                }
+            }
+            prices
+        })
+    }
+}
+
+/// `{"data": {"<mint>": {"id": "...", "price": "147.41"} | null}}`
+fn parse_prices(body: &Value) -> PriceMap {
+    let Some(data) = body.get("data").and_then(Value::as_object) else {
+        return PriceMap::new();
+    };
+    data.iter()
+        .filter_map(|(mint, entry)| {
+            let price = entry.get("price")?;
+            let price = price
+                .as_f64()
+                .or_else(|| price.as_str().and_then(|s| s.parse().ok()))?;
+            Some((mint.clone(), price))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_price_api_response() {
+        let body = json!({
+            "data": {
+                "So11111111111111111111111111111111111111112": { "id": "So1", "price": "147.41" },
+                "unknown": null
+            }
+        });
+        let prices = parse_prices(&body);
+        assert_eq!(prices.len(), 1);
+        assert_eq!(prices["So11111111111111111111111111111111111111112"], 147.41);
+    }
+
+    #[test]
+    fn fresh_cache_entries_skip_the_request() {
+        let oracle = JupiterPriceOracle::default();
+        let fetched: PriceMap = [("SOL".to_string(), 150.0)].into_iter().collect();
+        oracle.store(&["SOL", "NOPRICE"], &fetched);
+
+        let mut prices = PriceMap::new();
+        let missing = oracle.take_cached(&["SOL", "NOPRICE", "NEW"], &mut prices);
+        assert_eq!(missing, vec!["NEW"]);
+        assert_eq!(prices.len(), 1);
+        assert_eq!(prices["SOL"], 150.0);
+    }
+
+    #[test]
+    fn expired_cache_entries_are_refetched() {
+        let oracle = JupiterPriceOracle::default().with_ttl(Duration::ZERO);
+        let fetched: PriceMap = [("SOL".to_string(), 150.0)].into_iter().collect();
+        oracle.store(&["SOL"], &fetched);
+
+        let mut prices = PriceMap::new();
+        assert_eq!(oracle.take_cached(&["SOL"], &mut prices), vec!["SOL"]);
+        assert!(prices.is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+//! USD-оценка trades и pool events через подключаемый источник цен.
+//!
+//! Синхронный `PriceOracle` (локальные таблицы цен) подключается к парсеру
+//! (`DexParser::with_price_oracle`) и применяется к каждому результату. Сетевые
+//! источники — `AsyncPriceOracle`: они применяются к готовому `ParseResult` через
+//! `annotate_usd_async` или к блоку одним батчем через `annotate_block_usd_async`,
+//! чтобы парсинг не ждал сеть.
+
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::types::{ParseResult, PoolEvent, TradeInfo};
+
+#[cfg(feature = "jupiter-price")]
+pub mod jupiter;
+
+#[cfg(feature = "jupiter-price")]
+pub use jupiter::JupiterPriceOracle;
+
+/// Цены в USD за единицу токена (UI amount), ключ — mint.
+pub type PriceMap = HashMap<String, f64>;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+pub trait PriceOracle: Send + Sync {
+    /// `None` — цена неизвестна, поле `value_usd` останется пустым.
+    fn price_usd(&self, mint: &str) -> Option<f64>;
+
+    /// Батч-запрос; по умолчанию — поштучно через `price_usd`.
+    fn prices_usd(&self, mints: &[&str]) -> PriceMap {
+        mints
+            .iter()
+            .filter_map(|mint| Some((mint.to_string(), self.price_usd(mint)?)))
+            .collect()
+    }
+}
+
+pub trait AsyncPriceOracle: Send + Sync {
+    fn prices_usd_async<'a>(&'a self, mints: &'a [&'a str]) -> BoxFuture<'a, PriceMap>;
+}
+
+/// Статическая таблица цен — для тестов и офлайн-обработки.
+impl PriceOracle for PriceMap {
+    fn price_usd(&self, mint: &str) -> Option<f64> {
+        self.get(mint).copied()
+    }
+}
+
+/// Заполняет `value_usd` у trades, aggregate trade и liquidities.
+pub fn annotate_usd(result: &mut ParseResult, oracle: &dyn PriceOracle) {
+    let mints = collect_mints(result);
+    if mints.is_empty() {
+        return;
+    }
+    let prices = oracle.prices_usd(&mints.iter().map(String::as_str).collect::<Vec<_>>());
+    apply_prices(result, &prices);
+}
+
+pub async fn annotate_usd_async(result: &mut ParseResult, oracle: &dyn AsyncPriceOracle) {
+    let mints = collect_mints(result);
+    if mints.is_empty() {
+        return;
+    }
+    let mint_refs: Vec<&str> = mints.iter().map(String::as_str).collect();
+    let prices = oracle.prices_usd_async(&mint_refs).await;
+    apply_prices(result, &prices);
+}
+
+/// Один запрос цен на все результаты блока вместо запроса на каждую транзакцию.
+pub async fn annotate_block_usd_async(results: &mut [ParseResult], oracle: &dyn AsyncPriceOracle) {
+    let mut mints = HashSet::new();
+    for result in results.iter() {
+        collect_mints_into(result, &mut mints);
+    }
+    if mints.is_empty() {
+        return;
+    }
+    let mint_refs: Vec<&str> = mints.iter().map(String::as_str).collect();
+    let prices = oracle.prices_usd_async(&mint_refs).await;
+    for result in results {
+        apply_prices(result, &prices);
+    }
+}
+
+fn collect_mints(result: &ParseResult) -> Vec<String> {
+    let mut mints = HashSet::new();
+    collect_mints_into(result, &mut mints);
+    mints.into_iter().collect()
+}
+
+fn collect_mints_into(result: &ParseResult, mints: &mut HashSet<String>) {
+    let trade_mints = result
+        .trades
+        .iter()
+        .chain(&result.aggregate_trade)
+        .flat_map(|trade| [trade.input_token.mint.as_str(), trade.output_token.mint.as_str()]);
+    let leg_mints = result
+        .liquidities
+        .iter()
+        .flat_map(|event| event.tokens.iter().map(|leg| leg.mint.as_str()));
+    for mint in trade_mints.chain(leg_mints) {
+        if !mint.is_empty() && !mints.contains(mint) {
+            mints.insert(mint.to_string());
+        }
+    }
+}
+
+fn apply_prices(result: &mut ParseResult, prices: &PriceMap) {
+    for trade in result.trades.iter_mut().chain(result.aggregate_trade.as_mut()) {
+        trade.value_usd = trade_value_usd(trade, prices);
+    }
+    for event in &mut result.liquidities {
+        event.value_usd = pool_event_value_usd(event, prices);
+    }
+}
+
+/// Сначала по цене quote-стороны, она обычно стабильнее; иначе по base.
+fn trade_value_usd(trade: &TradeInfo, prices: &PriceMap) -> Option<f64> {
+    let quote_mint = trade.quote_mint();
+    let (quote, base) = if trade.input_token.mint == quote_mint {
+        (&trade.input_token, &trade.output_token)
+    } else {
+        (&trade.output_token, &trade.input_token)
+    };
+    prices
+        .get(&quote.mint)
+        .map(|price| price * quote.amount)
+        .or_else(|| prices.get(&base.mint).map(|price| price * base.amount))
+}
+
+/// Сумма по ногам с известной ценой; `None`, если не оценена ни одна.
+fn pool_event_value_usd(event: &PoolEvent, prices: &PriceMap) -> Option<f64> {
+    event
+        .tokens
+        .iter()
+        .filter_map(|leg| prices.get(&leg.mint).map(|price| price * leg.amount))
+        .reduce(|total, value| total + value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::constants::TOKENS;
+    use crate::types::{TokenInfo, TokenLeg};
+
+    fn token(mint: &str, amount: f64) -> TokenInfo {
+        TokenInfo {
+            mint: mint.to_string(),
+            amount,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn values_trades_and_pool_events_from_oracle() {
+        let prices: PriceMap = [(TOKENS.SOL.to_string(), 150.0)].into_iter().collect();
+        let mut result = ParseResult::new();
+        result.trades.push(TradeInfo {
+            input_token: token(TOKENS.SOL, 2.0),
+            output_token: token("MEME", 1_000.0),
+            ..Default::default()
+        });
+        result.liquidities.push(PoolEvent {
+            tokens: vec![
+                TokenLeg {
+                    mint: "MEME".to_string(),
+                    amount: 10.0,
+                    ..Default::default()
+                },
+                TokenLeg {
+                    mint: TOKENS.SOL.to_string(),
+                    amount: 1.5,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        });
+
+        annotate_usd(&mut result, &prices);
+        assert_eq!(result.trades[0].value_usd, Some(300.0));
+        assert_eq!(result.liquidities[0].value_usd, Some(225.0));
+    }
+}
@@ -0,0 +1,31 @@
+pub const JUPITER_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+pub const JUPITER_PROGRAM_NAME: &str = "Jupiter";
+
+pub mod discriminators {
+    pub mod jupiter_instructions {
+        pub const ROUTE: [u8; 8] = [229, 23, 203, 151, 122, 227, 173, 42];
+        pub const SHARED_ACCOUNTS_ROUTE: [u8; 8] = [193, 32, 155, 51, 65, 214, 156, 129];
+        pub const ROUTE_WITH_TOKEN_LEDGER: [u8; 8] = [150, 86, 71, 116, 167, 93, 14, 104];
+        pub const SHARED_ACCOUNTS_ROUTE_WITH_TOKEN_LEDGER: [u8; 8] =
+            [230, 121, 143, 80, 119, 159, 106, 170];
+        pub const EXACT_OUT_ROUTE: [u8; 8] = [208, 51, 239, 151, 123, 43, 237, 92];
+        pub const SHARED_ACCOUNTS_EXACT_OUT_ROUTE: [u8; 8] = [176, 209, 105, 168, 154, 125, 69, 62];
+
+        // u64 константы для быстрого сравнения дискриминаторов
+        pub const ROUTE_U64: u64 = u64::from_le_bytes(ROUTE);
+        pub const SHARED_ACCOUNTS_ROUTE_U64: u64 = u64::from_le_bytes(SHARED_ACCOUNTS_ROUTE);
+        pub const ROUTE_WITH_TOKEN_LEDGER_U64: u64 = u64::from_le_bytes(ROUTE_WITH_TOKEN_LEDGER);
+        pub const SHARED_ACCOUNTS_ROUTE_WITH_TOKEN_LEDGER_U64: u64 =
+            u64::from_le_bytes(SHARED_ACCOUNTS_ROUTE_WITH_TOKEN_LEDGER);
+        pub const EXACT_OUT_ROUTE_U64: u64 = u64::from_le_bytes(EXACT_OUT_ROUTE);
+        pub const SHARED_ACCOUNTS_EXACT_OUT_ROUTE_U64: u64 =
+            u64::from_le_bytes(SHARED_ACCOUNTS_EXACT_OUT_ROUTE);
+    }
+
+    pub mod jupiter_events {
+        /// Anchor self-CPI event, эмитится после каждого hop'а маршрута
+        pub const SWAP: [u8; 16] = [
+            228, 69, 165, 46, 81, 203, 154, 29, 64, 198, 205, 232, 38, 8, 113, 226,
+        ];
+    }
+}
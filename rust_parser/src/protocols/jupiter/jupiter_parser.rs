@@ -0,0 +1,533 @@
+use std::sync::Arc;
+
+use crate::core::constants::{dex_program_names, TOKENS};
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::core::transaction_utils::TransactionUtils;
+use crate::core::utils::get_instruction_data;
+use crate::protocols::pumpfun::binary_reader::BinaryReaderRef;
+use crate::protocols::pumpfun::error::PumpfunError;
+use crate::protocols::pumpfun::util::build_token_info;
+use crate::protocols::simple::TradeParser;
+use crate::types::{
+    ClassifiedInstruction, DexInfo, TradeConfidence, TradeInfo, TradeType, TransferMap,
+};
+
+use super::constants::{
+    discriminators::{jupiter_events, jupiter_instructions},
+    JUPITER_PROGRAM_ID, JUPITER_PROGRAM_NAME,
+};
+
+/// Один hop маршрута Jupiter (SwapEvent)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JupiterSwapEvent {
+    pub amm: String,
+    pub input_mint: String,
+    pub input_amount: u64,
+    pub output_mint: String,
+    pub output_amount: u64,
+    pub outer_index: usize,
+    pub inner_index: Option<usize>,
+}
+
+/// Декодирует route/sharedAccountsRoute/exactOutRoute через SwapEvent'ы.
+/// Каждая route-инструкция превращается в одну сделку с заполненными `amms` и `route`.
+pub struct JupiterParser {
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+    utils: TransactionUtils,
+}
+
+impl JupiterParser {
+    pub fn new(
+        adapter: Arc<TransactionAdapter>,
+        dex_info: DexInfo,
+        transfer_actions: Arc<TransferMap>,
+        classified_instructions: Vec<ClassifiedInstruction>,
+    ) -> Self {
+        let utils = TransactionUtils::new(adapter.clone());
+        Self {
+            adapter,
+            dex_info,
+            transfer_actions,
+            classified_instructions,
+            utils,
+        }
+    }
+
+    #[inline]
+    fn is_route_instruction(data: &[u8]) -> bool {
+        if data.len() < 8 {
+            return false;
+        }
+        let disc_bytes: [u8; 8] = match data[..8].try_into() {
+            Ok(b) => b,
+            Err(_) => return false,
+        };
+        matches!(
+            u64::from_le_bytes(disc_bytes),
+            jupiter_instructions::ROUTE_U64
+                | jupiter_instructions::SHARED_ACCOUNTS_ROUTE_U64
+                | jupiter_instructions::ROUTE_WITH_TOKEN_LEDGER_U64
+                | jupiter_instructions::SHARED_ACCOUNTS_ROUTE_WITH_TOKEN_LEDGER_U64
+                | jupiter_instructions::EXACT_OUT_ROUTE_U64
+                | jupiter_instructions::SHARED_ACCOUNTS_EXACT_OUT_ROUTE_U64
+        )
+    }
+
+    /// Все route-варианты заканчиваются на `slippage_bps: u16, platform_fee_bps: u8`,
+    /// поэтому читаем slippage с хвоста, не разбирая вариативный route_plan.
+    #[inline]
+    fn read_slippage_bps(data: &[u8]) -> Option<u64> {
+        if data.len() < 8 + 3 {
+            return None;
+        }
+        let tail = &data[data.len() - 3..];
+        Some(u16::from_le_bytes([tail[0], tail[1]]) as u64)
+    }
+
+    pub fn decode_swap_event(
+        data: &[u8],
+    ) -> Result<(String, String, u64, String, u64), PumpfunError> {
+        let mut reader = BinaryReaderRef::new_ref(data);
+        let amm = reader.read_pubkey()?;
+        let input_mint = reader.read_pubkey()?;
+        let input_amount = reader.read_u64()?;
+        let output_mint = reader.read_pubkey()?;
+        let output_amount = reader.read_u64()?;
+        Ok((amm, input_mint, input_amount, output_mint, output_amount))
+    }
+
+    fn parse_swap_events(&self) -> Vec<JupiterSwapEvent> {
+        let mut events = Vec::new();
+        for classified in &self.classified_instructions {
+            if classified.program_id != JUPITER_PROGRAM_ID || classified.inner_index.is_none() {
+                continue;
+            }
+            let Ok(data) = get_instruction_data(&classified.data) else {
+                continue;
+            };
+            if data.len() < 16 || data[..16] != jupiter_events::SWAP {
+                continue;
+            }
+            if let Ok((amm, input_mint, input_amount, output_mint, output_amount)) =
+                Self::decode_swap_event(&data[16..])
+            {
+                events.push(JupiterSwapEvent {
+                    amm,
+                    input_mint,
+                    input_amount,
+                    output_mint,
+                    output_amount,
+                    outer_index: classified.outer_index,
+                    inner_index: classified.inner_index,
+                });
+            }
+        }
+        events
+    }
+
+    #[inline]
+    fn token_decimals(&self, mint: &str) -> u8 {
+        match self.adapter.get_token_decimals(mint) {
+            0 if mint == TOKENS.SOL => 9,
+            decimals => decimals,
+        }
+    }
+
+    /// Собирает сделку из hop'ов одной route-инструкции.
+    /// Input — mint с наибольшим чистым оттоком, output — с наибольшим притоком;
+    /// промежуточные mints взаимно гасятся.
+    fn build_route_trade(
+        &self,
+        classified: &ClassifiedInstruction,
+        hops: &[&JupiterSwapEvent],
+        slippage_bps: Option<u64>,
+    ) -> Option<TradeInfo> {
+        let mut mints: Vec<&str> = Vec::with_capacity(hops.len() + 1);
+        let mut flows: Vec<i128> = Vec::with_capacity(hops.len() + 1);
+        let mut amms: Vec<String> = Vec::with_capacity(hops.len());
+
+        for hop in hops {
+            add_flow(
+                &mut mints,
+                &mut flows,
+                &hop.input_mint,
+                -(hop.input_amount as i128),
+            );
+            add_flow(
+                &mut mints,
+                &mut flows,
+                &hop.output_mint,
+                hop.output_amount as i128,
+            );
+
+            let amm_name = match dex_program_names::name(&hop.amm) {
+                "Unknown DEX" => hop.amm.clone(),
+                name => name.to_string(),
+            };
+            if amms.last() != Some(&amm_name) {
+                amms.push(amm_name);
+            }
+        }
+
+        let (input_pos, _) = flows
+            .iter()
+            .enumerate()
+            .filter(|(_, flow)| **flow < 0)
+            .min_by_key(|(_, flow)| **flow)?;
+        let (output_pos, _) = flows
+            .iter()
+            .enumerate()
+            .filter(|(_, flow)| **flow > 0)
+            .max_by_key(|(_, flow)| **flow)?;
+
+        let input_mint = mints[input_pos];
+        let output_mint = mints[output_pos];
+        let input_amount = flows[input_pos].unsigned_abs();
+        let output_amount = flows[output_pos] as u128;
+
+        let signer = self.adapter.signer().to_string();
+        let mut input_token = build_token_info(
+            input_mint,
+            input_amount,
+            self.token_decimals(input_mint),
+            None,
+        );
+        input_token.authority = Some(signer.clone());
+        let mut output_token = build_token_info(
+            output_mint,
+            output_amount,
+            self.token_decimals(output_mint),
+            None,
+        );
+        output_token.destination_owner = Some(signer.clone());
+
+        // route: input -> промежуточные mints -> output в порядке hop'ов
+        let mut path: Vec<&str> = Vec::with_capacity(mints.len());
+        path.push(input_mint);
+        for mint in &mints {
+            if *mint != input_mint && *mint != output_mint {
+                path.push(mint);
+            }
+        }
+        path.push(output_mint);
+
+        Some(TradeInfo {
+            trade_type: TradeType::Swap,
+            pool: Vec::new(),
+            input_token,
+            output_token,
+            slippage_bps,
+            fee: None,
+            fees: Vec::new(),
+            user: Some(signer),
+            program_id: Some(JUPITER_PROGRAM_ID.to_string()),
+            amm: Some(
+                self.dex_info
+                    .amm
+                    .clone()
+                    .unwrap_or_else(|| JUPITER_PROGRAM_NAME.to_string()),
+            ),
+            amms: Some(amms),
+            route: Some(path.join("->")),
+            slot: self.adapter.slot(),
+            timestamp: self.adapter.block_time(),
+            signature: self.adapter.signature().to_string(),
+            idx: format!(
+                "{}-{}",
+                classified.outer_index,
+                classified.inner_index.unwrap_or(0)
+            ),
+            signer: Some(self.adapter.signers().to_vec()),
+            dlmm: None,
+            clob: None,
+            liquidity_role: None,
+            price_quote_per_base: None,
+            value_usd: None,
+            input_symbol: None,
+            output_symbol: None,
+            instruction_index: 0,
+            inner_index: None,
+            pool_reserves: None,
+            order_key: None,
+            side: None,
+            authority: None,
+            flags: Vec::new(),
+            router: None,
+            confidence: TradeConfidence::Unverified,
+        })
+    }
+
+    /// Hop'ы route-инструкции — SwapEvent'ы из её CPI-поддерева. Несколько route,
+    /// вызванных через CPI из одной outer инструкции, не смешиваются.
+    fn route_hops<'e>(
+        &self,
+        route: &ClassifiedInstruction,
+        events: &'e [JupiterSwapEvent],
+    ) -> Vec<&'e JupiterSwapEvent> {
+        let start = route.inner_index.map_or(0, |inner| inner + 1);
+        let end = self.subtree_end(route, start);
+        events
+            .iter()
+            .filter(|event| {
+                event.outer_index == route.outer_index
+                    && event
+                        .inner_index
+                        .is_some_and(|inner| (start..end).contains(&inner))
+            })
+            .collect()
+    }
+
+    /// Конец поддерева в inner instructions outer'а: первая инструкция не глубже
+    /// самого route по `stack_height`. Без stack height (старые ноды) — следующая
+    /// route-инструкция того же outer.
+    fn subtree_end(&self, route: &ClassifiedInstruction, start: usize) -> usize {
+        let Some(set) = self
+            .adapter
+            .inner_instructions()
+            .iter()
+            .find(|set| set.index == route.outer_index)
+        else {
+            return start;
+        };
+        let len = set.instructions.len();
+        let heights_known = set.stack_heights.iter().any(Option::is_some);
+        if let (Some(height), true) = (route.stack_height, heights_known) {
+            return (start..len)
+                .find(|&inner| set.stack_height(inner).is_some_and(|h| h <= height))
+                .unwrap_or(len);
+        }
+        self.classified_instructions
+            .iter()
+            .filter(|other| other.outer_index == route.outer_index)
+            .filter_map(|other| {
+                other
+                    .inner_index
+                    .filter(|&inner| inner >= start)
+                    .map(|inner| (inner, other))
+            })
+            .filter(|(_, other)| {
+                get_instruction_data(&other.data)
+                    .is_ok_and(|data| Self::is_route_instruction(&data))
+            })
+            .map(|(inner, _)| inner)
+            .min()
+            .unwrap_or(len)
+    }
+
+    /// Fallback для транзакций без SwapEvent'ов (старые форматы, урезанные inner instructions)
+    fn process_transfers_fallback(&self) -> Vec<TradeInfo> {
+        let program_id = self
+            .dex_info
+            .program_id
+            .clone()
+            .unwrap_or_else(|| JUPITER_PROGRAM_ID.to_string());
+        self.transfer_actions
+            .get(&program_id)
+            .and_then(|transfers| self.utils.process_swap_data(transfers, &self.dex_info))
+            .into_iter()
+            .collect()
+    }
+}
+
+#[inline]
+fn add_flow<'a>(mints: &mut Vec<&'a str>, flows: &mut Vec<i128>, mint: &'a str, delta: i128) {
+    match mints.iter().position(|m| *m == mint) {
+        Some(pos) => flows[pos] += delta,
+        None => {
+            mints.push(mint);
+            flows.push(delta);
+        }
+    }
+}
+
+impl TradeParser for JupiterParser {
+    fn process_trades(&mut self) -> Vec<TradeInfo> {
+        let events = self.parse_swap_events();
+        let mut trades = Vec::new();
+
+        for classified in &self.classified_instructions {
+            let Ok(data) = get_instruction_data(&classified.data) else {
+                continue;
+            };
+            if !Self::is_route_instruction(&data) {
+                continue;
+            }
+
+            let hops = self.route_hops(classified, &events);
+            if hops.is_empty() {
+                continue;
+            }
+
+            if let Some(trade) =
+                self.build_route_trade(classified, &hops, Self::read_slippage_bps(&data))
+            {
+                trades.push(trade);
+            }
+        }
+
+        if trades.is_empty() {
+            return self.process_transfers_fallback();
+        }
+        trades
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ParseConfig;
+    use crate::types::{InnerInstruction, SolanaInstruction, SolanaTransaction};
+    use base64_simd::STANDARD;
+
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    fn swap_event_data(
+        amm: &str,
+        input: &str,
+        input_amount: u64,
+        output: &str,
+        output_amount: u64,
+    ) -> String {
+        let mut data = jupiter_events::SWAP.to_vec();
+        data.extend(bs58::decode(amm).into_vec().unwrap());
+        data.extend(bs58::decode(input).into_vec().unwrap());
+        data.extend(input_amount.to_le_bytes());
+        data.extend(bs58::decode(output).into_vec().unwrap());
+        data.extend(output_amount.to_le_bytes());
+        STANDARD.encode_to_string(&data)
+    }
+
+    fn jupiter_ix(data: String) -> SolanaInstruction {
+        SolanaInstruction {
+            program_id: JUPITER_PROGRAM_ID.into(),
+            accounts: Vec::new(),
+            data,
+        }
+    }
+
+    fn route_data(in_amount: u64, quoted_out_amount: u64) -> String {
+        let mut route_data = jupiter_instructions::ROUTE.to_vec();
+        // route_plan опущен: парсер читает только хвост
+        route_data.extend(in_amount.to_le_bytes());
+        route_data.extend(quoted_out_amount.to_le_bytes());
+        route_data.extend(50u16.to_le_bytes());
+        route_data.push(0);
+        STANDARD.encode_to_string(&route_data)
+    }
+
+    fn parse_trades(tx: SolanaTransaction) -> Vec<TradeInfo> {
+        let adapter = TransactionAdapter::new(tx, ParseConfig::default());
+        let classified = crate::core::instruction_classifier::InstructionClassifier::new(&adapter)
+            .get_instructions(JUPITER_PROGRAM_ID)
+            .to_vec();
+        let mut parser = JupiterParser::new(
+            Arc::new(adapter),
+            DexInfo::default(),
+            Arc::new(TransferMap::new()),
+            classified,
+        );
+        parser.process_trades()
+    }
+
+    #[test]
+    fn decodes_multi_hop_route() {
+        let tx = SolanaTransaction {
+            signature: "route-signature".to_string(),
+            signers: vec!["user".to_string()],
+            instructions: vec![jupiter_ix(route_data(1_000_000_000, 5_000))],
+            inner_instructions: vec![InnerInstruction {
+                index: 0,
+                instructions: vec![
+                    jupiter_ix(swap_event_data(
+                        crate::core::constants::dex_programs::RAYDIUM,
+                        TOKENS.SOL,
+                        1_000_000_000,
+                        USDC,
+                        150_000_000,
+                    )),
+                    jupiter_ix(swap_event_data(
+                        crate::core::constants::dex_programs::ORCA,
+                        USDC,
+                        150_000_000,
+                        BONK,
+                        5_000,
+                    )),
+                ],
+                stack_heights: Vec::new(),
+            }],
+            ..Default::default()
+        };
+
+        let trades = parse_trades(tx);
+
+        assert_eq!(trades.len(), 1);
+        let trade = &trades[0];
+        assert_eq!(trade.input_token.mint, TOKENS.SOL);
+        assert_eq!(trade.input_token.amount_raw, "1000000000");
+        assert_eq!(trade.output_token.mint, BONK);
+        assert_eq!(trade.output_token.amount_raw, "5000");
+        assert_eq!(trade.slippage_bps, Some(50));
+        assert_eq!(
+            trade.amms.as_deref(),
+            Some(&["RaydiumV4".to_string(), "Orca".to_string()][..])
+        );
+        assert_eq!(
+            trade.route.as_deref(),
+            Some(format!("{}->{}->{}", TOKENS.SOL, USDC, BONK).as_str())
+        );
+    }
+
+    #[test]
+    fn separates_routes_called_from_one_outer_instruction() {
+        // Бот через CPI вызывает два независимых route в одной outer инструкции
+        let tx = SolanaTransaction {
+            signature: "cpi-routes-signature".to_string(),
+            signers: vec!["user".to_string()],
+            instructions: vec![SolanaInstruction {
+                program_id: "BotProgram1111111111111111111111111111111111".into(),
+                accounts: Vec::new(),
+                data: String::new(),
+            }],
+            inner_instructions: vec![InnerInstruction {
+                index: 0,
+                instructions: vec![
+                    jupiter_ix(route_data(1_000_000_000, 150_000_000)),
+                    jupiter_ix(swap_event_data(
+                        crate::core::constants::dex_programs::RAYDIUM,
+                        TOKENS.SOL,
+                        1_000_000_000,
+                        USDC,
+                        150_000_000,
+                    )),
+                    jupiter_ix(route_data(20_000_000, 7_000)),
+                    jupiter_ix(swap_event_data(
+                        crate::core::constants::dex_programs::ORCA,
+                        USDC,
+                        20_000_000,
+                        BONK,
+                        7_000,
+                    )),
+                ],
+                stack_heights: vec![Some(2), Some(3), Some(2), Some(3)],
+            }],
+            ..Default::default()
+        };
+
+        let trades = parse_trades(tx);
+
+        assert_eq!(trades.len(), 2);
+        assert_eq!(trades[0].input_token.mint, TOKENS.SOL);
+        assert_eq!(trades[0].output_token.mint, USDC);
+        assert_eq!(
+            trades[0].amms.as_deref(),
+            Some(&["RaydiumV4".to_string()][..])
+        );
+        assert_eq!(trades[1].input_token.mint, USDC);
+        assert_eq!(trades[1].output_token.mint, BONK);
+        assert_eq!(trades[1].amms.as_deref(), Some(&["Orca".to_string()][..]));
+    }
+}
@@ -0,0 +1,24 @@
+pub mod constants;
+pub mod jupiter_parser;
+
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::protocols::simple::TradeParser;
+use crate::types::{ClassifiedInstruction, DexInfo, TransferMap};
+
+use jupiter_parser::JupiterParser;
+
+pub fn build_jupiter_trade_parser(
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+) -> Box<dyn TradeParser> {
+    Box::new(JupiterParser::new(
+        adapter,
+        dex_info,
+        transfer_actions,
+        classified_instructions,
+    ))
+}
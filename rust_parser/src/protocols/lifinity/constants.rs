@@ -0,0 +1,6 @@
+pub const LIFINITY_V2_PROGRAM_ID: &str = "2wT8Yq49kHgDzXuPxZSaeLaH1qbmGXtEyPy64bL7aD3c";
+pub const LIFINITY_V2_PROGRAM_NAME: &str = "LifinityV2";
+
+pub mod discriminators {
+    pub const SWAP: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+}
@@ -0,0 +1,89 @@
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::core::transaction_utils::TransactionUtils;
+use crate::core::utils::get_instruction_transfers;
+use crate::protocols::pumpfun::util::get_instruction_data;
+use crate::protocols::simple::TradeParser;
+use crate::types::{ClassifiedInstruction, DexInfo, TradeInfo, TransferData, TransferMap};
+
+use super::constants::{discriminators, LIFINITY_V2_PROGRAM_NAME};
+
+/// Lifinity v2 не пишет событий: сумма свопа берётся из трансферов инструкции.
+pub struct LifinityParser {
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+    utils: TransactionUtils,
+}
+
+impl LifinityParser {
+    pub fn new(
+        adapter: Arc<TransactionAdapter>,
+        dex_info: DexInfo,
+        transfer_actions: Arc<TransferMap>,
+        classified_instructions: Vec<ClassifiedInstruction>,
+    ) -> Self {
+        let utils = TransactionUtils::new(adapter.clone());
+        Self {
+            adapter,
+            dex_info,
+            transfer_actions,
+            classified_instructions,
+            utils,
+        }
+    }
+}
+
+impl TradeParser for LifinityParser {
+    fn process_trades(&mut self) -> Vec<TradeInfo> {
+        let mut trades = Vec::new();
+
+        for classified in &self.classified_instructions {
+            let is_swap = get_instruction_data(&classified.data)
+                .is_ok_and(|data| data.len() >= 8 && data[..8] == discriminators::SWAP);
+            if !is_swap {
+                continue;
+            }
+
+            let transfers: Vec<TransferData> = get_instruction_transfers(
+                &self.transfer_actions,
+                &classified.program_id,
+                classified.outer_index,
+                classified.inner_index,
+            )
+            .into_iter()
+            .cloned()
+            .collect();
+            if transfers.len() < 2 {
+                continue;
+            }
+
+            let dex_info = DexInfo {
+                program_id: Some(classified.program_id.to_string()),
+                amm: Some(LIFINITY_V2_PROGRAM_NAME.to_string()),
+                route: self.dex_info.route.clone(),
+            };
+            let Some(mut trade) = self.utils.process_swap_data(&transfers, &dex_info) else {
+                continue;
+            };
+
+            // authority, amm, user_transfer_authority, source_info, destination_info, ...
+            let accounts = self.adapter.get_instruction_accounts(&classified.data);
+            if let Some(pool) = accounts.get(1) {
+                trade.pool = vec![pool.to_string()];
+            }
+            if let Some(user) = accounts.get(2) {
+                trade.user = Some(user.to_string());
+            }
+
+            trades.push(
+                self.utils
+                    .attach_token_transfer_info(trade, &self.transfer_actions),
+            );
+        }
+
+        trades
+    }
+}
@@ -0,0 +1,24 @@
+pub mod constants;
+pub mod lifinity_parser;
+
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::protocols::simple::TradeParser;
+use crate::types::{ClassifiedInstruction, DexInfo, TransferMap};
+
+use lifinity_parser::LifinityParser;
+
+pub fn build_lifinity_trade_parser(
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+) -> Box<dyn TradeParser> {
+    Box::new(LifinityParser::new(
+        adapter,
+        dex_info,
+        transfer_actions,
+        classified_instructions,
+    ))
+}
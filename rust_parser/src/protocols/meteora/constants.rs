@@ -18,6 +18,10 @@ pub mod discriminators {
         pub mod swap {
             pub const SWAP: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200]; // swap (same as METEORA_DBC.SWAP)
             pub const SWAP_V2: [u8; 8] = [65, 75, 63, 76, 235, 91, 91, 136]; // swapV2 (same as METEORA_DBC.SWAP_V2)
+            pub const SWAP_EXACT_OUT: [u8; 8] = [250, 73, 101, 33, 38, 207, 75, 184];
+            pub const SWAP_EXACT_OUT2: [u8; 8] = [43, 215, 247, 132, 137, 60, 243, 81];
+            pub const SWAP_WITH_PRICE_IMPACT: [u8; 8] = [56, 173, 230, 208, 173, 228, 156, 205];
+            pub const SWAP_WITH_PRICE_IMPACT2: [u8; 8] = [74, 98, 192, 214, 177, 51, 75, 51];
         }
 
         pub mod add_liquidity {
@@ -38,6 +42,14 @@ pub mod discriminators {
             pub const CLAIM_FEE: [u8; 8] = [169, 32, 79, 137, 136, 232, 70, 137];
             pub const CLAIM_FEE_V2: [u8; 8] = [112, 191, 101, 171, 28, 144, 127, 187];
         }
+
+        pub mod position {
+            pub const INITIALIZE_POSITION: [u8; 8] = [219, 192, 234, 71, 190, 191, 102, 80];
+            pub const INITIALIZE_POSITION_PDA: [u8; 8] = [46, 82, 125, 146, 85, 141, 228, 153];
+            pub const CLOSE_POSITION: [u8; 8] = [123, 134, 81, 0, 49, 68, 98, 98];
+            pub const CLOSE_POSITION2: [u8; 8] = [174, 90, 35, 115, 186, 40, 147, 226];
+            pub const CLOSE_POSITION_IF_EMPTY: [u8; 8] = [59, 124, 212, 118, 91, 152, 110, 157];
+        }
     }
 
     // METEORA_DAMM liquidity discriminators (8 bytes)
@@ -55,8 +67,12 @@ pub mod discriminators {
         pub const INITIALIZE_POOL_WITH_DYNAMIC_CONFIG: [u8; 8] = [149, 82, 72, 197, 253, 252, 68, 15];
         pub const ADD_LIQUIDITY: [u8; 8] = [181, 157, 89, 67, 143, 182, 52, 72];
         pub const CLAIM_POSITION_FEE: [u8; 8] = [180, 38, 154, 17, 133, 33, 162, 211];
+        pub const CREATE_POSITION: [u8; 8] = [48, 215, 197, 153, 96, 203, 180, 133];
+        pub const CLOSE_POSITION: [u8; 8] = [123, 134, 81, 0, 49, 68, 98, 98];
         pub const REMOVE_LIQUIDITY: [u8; 8] = [80, 85, 209, 72, 24, 206, 177, 108];
         pub const REMOVE_ALL_LIQUIDITY: [u8; 8] = [10, 51, 61, 35, 112, 105, 24, 85];
+        pub const SWAP: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+        pub const SWAP2: [u8; 8] = [65, 75, 63, 76, 235, 91, 91, 136];
     }
 
     // u64 константы для быстрого сравнения дискриминаторов (8 bytes)
@@ -64,6 +80,10 @@ pub mod discriminators {
         use super::meteora_dlmm;
         pub const SWAP_U64: u64 = u64::from_le_bytes(meteora_dlmm::swap::SWAP);
         pub const SWAP_V2_U64: u64 = u64::from_le_bytes(meteora_dlmm::swap::SWAP_V2);
+        pub const SWAP_EXACT_OUT_U64: u64 = u64::from_le_bytes(meteora_dlmm::swap::SWAP_EXACT_OUT);
+        pub const SWAP_EXACT_OUT2_U64: u64 = u64::from_le_bytes(meteora_dlmm::swap::SWAP_EXACT_OUT2);
+        pub const SWAP_WITH_PRICE_IMPACT_U64: u64 = u64::from_le_bytes(meteora_dlmm::swap::SWAP_WITH_PRICE_IMPACT);
+        pub const SWAP_WITH_PRICE_IMPACT2_U64: u64 = u64::from_le_bytes(meteora_dlmm::swap::SWAP_WITH_PRICE_IMPACT2);
         pub const ADD_LIQUIDITY_U64: u64 = u64::from_le_bytes(meteora_dlmm::add_liquidity::ADD_LIQUIDITY);
         pub const ADD_LIQUIDITY_BY_STRATEGY_U64: u64 = u64::from_le_bytes(meteora_dlmm::add_liquidity::ADD_LIQUIDITY_BY_STRATEGY);
         pub const ADD_LIQUIDITY_BY_STRATEGY2_U64: u64 = u64::from_le_bytes(meteora_dlmm::add_liquidity::ADD_LIQUIDITY_BY_STRATEGY2);
@@ -77,6 +97,11 @@ pub mod discriminators {
         pub const REMOVE_ALL_LIQUIDITY_U64: u64 = u64::from_le_bytes(meteora_dlmm::remove_liquidity::REMOVE_ALL_LIQUIDITY);
         pub const CLAIM_FEE_U64: u64 = u64::from_le_bytes(meteora_dlmm::remove_liquidity::CLAIM_FEE);
         pub const CLAIM_FEE_V2_U64: u64 = u64::from_le_bytes(meteora_dlmm::remove_liquidity::CLAIM_FEE_V2);
+        pub const INITIALIZE_POSITION_U64: u64 = u64::from_le_bytes(meteora_dlmm::position::INITIALIZE_POSITION);
+        pub const INITIALIZE_POSITION_PDA_U64: u64 = u64::from_le_bytes(meteora_dlmm::position::INITIALIZE_POSITION_PDA);
+        pub const CLOSE_POSITION_U64: u64 = u64::from_le_bytes(meteora_dlmm::position::CLOSE_POSITION);
+        pub const CLOSE_POSITION2_U64: u64 = u64::from_le_bytes(meteora_dlmm::position::CLOSE_POSITION2);
+        pub const CLOSE_POSITION_IF_EMPTY_U64: u64 = u64::from_le_bytes(meteora_dlmm::position::CLOSE_POSITION_IF_EMPTY);
     }
 
     pub mod meteora_damm_u64 {
@@ -94,8 +119,12 @@ pub mod discriminators {
         pub const INITIALIZE_POOL_WITH_DYNAMIC_CONFIG_U64: u64 = u64::from_le_bytes(meteora_damm_v2::INITIALIZE_POOL_WITH_DYNAMIC_CONFIG);
         pub const ADD_LIQUIDITY_U64: u64 = u64::from_le_bytes(meteora_damm_v2::ADD_LIQUIDITY);
         pub const CLAIM_POSITION_FEE_U64: u64 = u64::from_le_bytes(meteora_damm_v2::CLAIM_POSITION_FEE);
+        pub const CREATE_POSITION_U64: u64 = u64::from_le_bytes(meteora_damm_v2::CREATE_POSITION);
+        pub const CLOSE_POSITION_U64: u64 = u64::from_le_bytes(meteora_damm_v2::CLOSE_POSITION);
         pub const REMOVE_LIQUIDITY_U64: u64 = u64::from_le_bytes(meteora_damm_v2::REMOVE_LIQUIDITY);
         pub const REMOVE_ALL_LIQUIDITY_U64: u64 = u64::from_le_bytes(meteora_damm_v2::REMOVE_ALL_LIQUIDITY);
+        pub const SWAP_U64: u64 = u64::from_le_bytes(meteora_damm_v2::SWAP);
+        pub const SWAP2_U64: u64 = u64::from_le_bytes(meteora_damm_v2::SWAP2);
     }
 
     // METEORA_DBC discriminators (8 bytes)
@@ -118,11 +147,28 @@ pub mod discriminators {
         pub const METEORA_DBC_MIGRATE_DAMM_V2_U64: u64 = u64::from_le_bytes(meteora_dbc::METEORA_DBC_MIGRATE_DAMM_V2);
     }
 
+    // METEORA_DBC event discriminators (16 bytes, anchor event CPI)
+    pub mod meteora_dbc_events {
+        pub const EVT_SWAP: [u8; 16] = [
+            228, 69, 165, 46, 81, 203, 154, 29, 27, 60, 21, 213, 138, 170, 187, 147,
+        ];
+    }
+
+    // METEORA_DLMM event discriminators (16 bytes, anchor event CPI)
+    pub mod meteora_dlmm_events {
+        pub const SWAP: [u8; 16] = [
+            228, 69, 165, 46, 81, 203, 154, 29, 81, 108, 227, 190, 205, 208, 10, 196,
+        ];
+    }
+
     // METEORA_DAMM_V2 event discriminators (16 bytes)
     pub mod meteora_damm_v2_events {
         pub const CREATE_POSITION_EVENT: [u8; 16] = [
             228, 69, 165, 46, 81, 203, 154, 29, 156, 15, 119, 198, 29, 181, 221, 55,
         ];
+        pub const EVT_SWAP: [u8; 16] = [
+            228, 69, 165, 46, 81, 203, 154, 29, 27, 60, 21, 213, 138, 170, 187, 147,
+        ];
     }
 
     pub mod meteora_damm_v2_events_u128 {
@@ -131,3 +177,7 @@ pub mod discriminators {
     }
 }
 
+/// Bin step'ы стандартных preset parameters DLMM (для проверки PDA пула).
+pub const METEORA_DLMM_BIN_STEPS: &[u16] = &[
+    1, 2, 4, 5, 8, 10, 15, 16, 20, 25, 30, 50, 80, 100, 125, 150, 200, 250, 300, 400,
+];
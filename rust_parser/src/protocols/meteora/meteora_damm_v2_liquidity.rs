@@ -1,10 +1,14 @@
+use std::sync::Arc;
+
 use crate::core::constants::TOKENS;
 use crate::protocols::simple::LiquidityParser;
-use crate::types::{ClassifiedInstruction, PoolEvent, PoolEventType, TradeType, TransferData, TransferMap};
+use crate::types::{
+    ClassifiedInstruction, LegDirection, PoolEvent, PoolEventType, TradeType, TransferData, TransferMap,
+};
 
 use super::constants::discriminators::{meteora_damm_v2_events, meteora_damm_v2_u64};
 use super::meteora_liquidity_base::MeteoraLiquidityBase;
-use super::util::get_lp_transfers;
+use super::util::{build_token_leg, get_lp_transfers};
 use crate::core::transaction_adapter::TransactionAdapter;
 
 pub struct MeteoraDAMMV2LiquidityParser {
@@ -13,8 +17,8 @@ pub struct MeteoraDAMMV2LiquidityParser {
 
 impl MeteoraDAMMV2LiquidityParser {
     pub fn new(
-        adapter: TransactionAdapter,
-        transfer_actions: TransferMap,
+        adapter: Arc<TransactionAdapter>,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Self {
         Self {
@@ -42,12 +46,14 @@ impl MeteoraDAMMV2LiquidityParser {
                 Some(PoolEventType::Create)
             }
             x if x == meteora_damm_v2_u64::ADD_LIQUIDITY_U64 => Some(PoolEventType::Add),
-            x if x == meteora_damm_v2_u64::CLAIM_POSITION_FEE_U64
-                || x == meteora_damm_v2_u64::REMOVE_LIQUIDITY_U64
+            x if x == meteora_damm_v2_u64::REMOVE_LIQUIDITY_U64
                 || x == meteora_damm_v2_u64::REMOVE_ALL_LIQUIDITY_U64 =>
             {
                 Some(PoolEventType::Remove)
             }
+            x if x == meteora_damm_v2_u64::CLAIM_POSITION_FEE_U64 => Some(PoolEventType::CollectFee),
+            x if x == meteora_damm_v2_u64::CREATE_POSITION_U64 => Some(PoolEventType::OpenPosition),
+            x if x == meteora_damm_v2_u64::CLOSE_POSITION_U64 => Some(PoolEventType::ClosePosition),
             _ => None,
         }
     }
@@ -59,7 +65,7 @@ impl MeteoraDAMMV2LiquidityParser {
         outer_index: usize,
         inner_index: Option<usize>,
     ) -> Option<PoolEvent> {
-        let data = crate::core::utils::get_instruction_data(instruction);
+        let data = crate::core::utils::get_instruction_data(instruction).ok()?;
         let action = self.get_pool_action(&data)?;
 
         let mut transfers = self.base.get_transfers_for_instruction(program_id, outer_index, inner_index);
@@ -74,9 +80,22 @@ impl MeteoraDAMMV2LiquidityParser {
                 self.parse_create_liquidity_event(instruction, outer_index, &data, &transfers_owned)
             }
             PoolEventType::Add => Some(self.parse_add_liquidity_event(instruction, outer_index, &data, &transfers_owned)),
-            PoolEventType::Remove => {
-                Some(self.parse_remove_liquidity_event(instruction, outer_index, &data, &transfers_owned))
+            PoolEventType::Remove | PoolEventType::CollectFee => Some(self.parse_remove_liquidity_event(
+                instruction,
+                outer_index,
+                &data,
+                &transfers_owned,
+                action,
+            )),
+            // createPosition: owner, position_nft_mint, position_nft_account, pool, ...;
+            // closePosition: position_nft_mint, position_nft_account, pool, ...
+            PoolEventType::OpenPosition => {
+                Some(self.base.position_event(instruction, outer_index, action, Some(3)))
+            }
+            PoolEventType::ClosePosition => {
+                Some(self.base.position_event(instruction, outer_index, action, Some(2)))
             }
+            PoolEventType::IncreaseLiquidity | PoolEventType::DecreaseLiquidity => None,
         }
     }
 
@@ -157,27 +176,26 @@ impl MeteoraDAMMV2LiquidityParser {
             signature: base.signature,
             idx: base.idx,
             signer: base.signer,
-            pool_id,
+            pool_id: pool_id.to_string(),
             config: None,
             pool_lp_mint: lp_token
-                .map(|t| t.info.mint.clone())
-                .or_else(|| accounts.get(1).cloned()),
-            token0_mint: Some(token0_mint),
-            token0_amount: token0.as_ref().and_then(|t| t.info.token_amount.ui_amount),
-            token0_amount_raw: token0.as_ref().map(|t| t.info.token_amount.amount.clone()),
-            token0_balance_change: None,
-            token0_decimals: Some(token0_decimals),
-            token1_mint: Some(token1_mint),
-            token1_amount: token1.as_ref().and_then(|t| t.info.token_amount.ui_amount),
-            token1_amount_raw: token1.as_ref().map(|t| t.info.token_amount.amount.clone()),
-            token1_balance_change: None,
-            token1_decimals: Some(token1_decimals),
+                .map(|t| t.info.mint.to_string())
+                .or_else(|| accounts.get(1).map(ToString::to_string)),
             lp_amount: lp_token
                 .and_then(|t| t.info.token_amount.ui_amount)
                 .or(Some(1.0)),
             lp_amount_raw: lp_token
                 .map(|t| t.info.token_amount.amount.clone())
                 .or(Some("1".to_string())),
+            tokens: vec![
+                build_token_leg(token0_mint.to_string(), token0.as_ref(), 0, token0_decimals, LegDirection::In),
+                build_token_leg(token1_mint.to_string(), token1.as_ref(), 0, token1_decimals, LegDirection::In),
+            ],
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
         })
     }
 
@@ -218,27 +236,20 @@ impl MeteoraDAMMV2LiquidityParser {
             signature: base.signature,
             idx: base.idx,
             signer: base.signer,
-            pool_id: accounts.get(0).cloned().unwrap_or_default(),
+            pool_id: accounts.get(0).map(ToString::to_string).unwrap_or_default(),
             config: None,
-            pool_lp_mint: accounts.get(1).cloned(),
-            token0_mint: token0.as_ref().map(|t| t.info.mint.clone()),
-            token0_amount: token0.as_ref().and_then(|t| t.info.token_amount.ui_amount).or(Some(0.0)),
-            token0_amount_raw: token0.as_ref().map(|t| t.info.token_amount.amount.clone()),
-            token0_balance_change: None,
-            token0_decimals: token0
-                .as_ref()
-                .map(|t| self.base.adapter.get_token_decimals(&t.info.mint))
-                .or(Some(0)),
-            token1_mint: token1.as_ref().map(|t| t.info.mint.clone()),
-            token1_amount: token1.as_ref().and_then(|t| t.info.token_amount.ui_amount).or(Some(0.0)),
-            token1_amount_raw: token1.as_ref().map(|t| t.info.token_amount.amount.clone()),
-            token1_balance_change: None,
-            token1_decimals: token1
-                .as_ref()
-                .map(|t| self.base.adapter.get_token_decimals(&t.info.mint))
-                .or(Some(0)),
+            pool_lp_mint: accounts.get(1).map(ToString::to_string),
             lp_amount: None,
             lp_amount_raw: None,
+            tokens: vec![
+                self.base.token_leg(token0.as_ref(), None, LegDirection::In),
+                self.base.token_leg(token1.as_ref(), None, LegDirection::In),
+            ],
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
         }
     }
 
@@ -248,6 +259,7 @@ impl MeteoraDAMMV2LiquidityParser {
         index: usize,
         _data: &[u8],
         transfers: &[TransferData],
+        action: PoolEventType,
     ) -> PoolEvent {
         let accounts = self.base.adapter.get_instruction_accounts(instruction);
         let (mut token0, mut token1) = self.normalize_tokens(transfers);
@@ -282,12 +294,12 @@ impl MeteoraDAMMV2LiquidityParser {
             .unwrap_or_else(|| accounts.get(8).cloned().unwrap_or_default());
         let program_id = self.base.adapter.get_instruction_program_id(instruction);
 
-        let mut base = self.base.adapter.get_pool_event_base(PoolEventType::Remove, program_id);
+        let mut base = self.base.adapter.get_pool_event_base(action.clone(), program_id);
         base.idx = index.to_string();
 
         PoolEvent {
             user: base.user,
-            event_type: TradeType::Remove,
+            event_type: action.into(),
             program_id: base.program_id,
             amm: base.amm,
             slot: base.slot,
@@ -295,29 +307,20 @@ impl MeteoraDAMMV2LiquidityParser {
             signature: base.signature,
             idx: base.idx,
             signer: base.signer,
-            pool_id: accounts.get(1).cloned().unwrap_or_default(),
+            pool_id: accounts.get(1).map(ToString::to_string).unwrap_or_default(),
             config: None,
-            pool_lp_mint: accounts.get(2).cloned(),
-            token0_mint: Some(
-                token0.as_ref()
-                    .map(|t| t.info.mint.clone())
-                    .unwrap_or_else(|| accounts.get(7).cloned().unwrap_or_default()),
-            ),
-            token0_amount: token0.as_ref().and_then(|t| t.info.token_amount.ui_amount).or(Some(0.0)),
-            token0_amount_raw: token0.as_ref().map(|t| t.info.token_amount.amount.clone()),
-            token0_balance_change: None,
-            token0_decimals: Some(self.base.adapter.get_token_decimals(&token0_mint)),
-            token1_mint: Some(
-                token1.as_ref()
-                    .map(|t| t.info.mint.clone())
-                    .unwrap_or_else(|| accounts.get(8).cloned().unwrap_or_default()),
-            ),
-            token1_amount: token1.as_ref().and_then(|t| t.info.token_amount.ui_amount).or(Some(0.0)),
-            token1_amount_raw: token1.as_ref().map(|t| t.info.token_amount.amount.clone()),
-            token1_balance_change: None,
-            token1_decimals: Some(self.base.adapter.get_token_decimals(&token1_mint)),
+            pool_lp_mint: accounts.get(2).map(ToString::to_string),
             lp_amount: None,
             lp_amount_raw: None,
+            tokens: vec![
+                self.base.token_leg(token0.as_ref(), Some(token0_mint.to_string()), LegDirection::Out),
+                self.base.token_leg(token1.as_ref(), Some(token1_mint.to_string()), LegDirection::Out),
+            ],
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
         }
     }
 }
@@ -0,0 +1,324 @@
+use std::sync::Arc;
+
+use crate::core::constants::dex_program_names;
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::core::transaction_utils::TransactionUtils;
+use crate::core::utils::get_instruction_data;
+use crate::protocols::pumpfun::util::build_fee_info;
+use crate::protocols::simple::TradeParser;
+use crate::types::{ClassifiedInstruction, DexInfo, FeeKind, TradeInfo, TransferData, TransferMap};
+
+use super::constants::{
+    discriminators::{meteora_damm_v2_events, meteora_damm_v2_u64},
+    program_ids,
+};
+
+/// pool + trade_direction + has_referral + params + swap_result + actual_amount_in + timestamp
+const SWAP_EVENT_LEN: usize = 32 + 1 + 1 + 16 + (8 + 16 + 8 + 8 + 8 + 8) + 8 + 8;
+
+// accounts swap/swap2: pool_authority, pool, input/output token account, vault a/b, mint a/b, payer, ...
+const POOL: usize = 1;
+const TOKEN_A_VAULT: usize = 4;
+const TOKEN_B_VAULT: usize = 5;
+const REFERRAL_TOKEN_ACCOUNT: usize = 11;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DammV2SwapEvent {
+    pub pool: String,
+    /// 0 — A→B, 1 — B→A.
+    pub trade_direction: u8,
+    pub has_referral: bool,
+    pub amount_in: u64,
+    pub output_amount: u64,
+    pub lp_fee: u64,
+    pub protocol_fee: u64,
+    pub partner_fee: u64,
+    pub referral_fee: u64,
+    pub actual_amount_in: u64,
+    pub outer_index: usize,
+    pub inner_index: Option<usize>,
+}
+
+/// Парсер swap/swap2 Meteora DAMM v2 (cp-amm): пул и vault'ы из accounts,
+/// комиссии из `EvtSwap`.
+pub struct MeteoraDAMMV2Parser {
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+    utils: TransactionUtils,
+}
+
+impl MeteoraDAMMV2Parser {
+    pub fn new(
+        adapter: Arc<TransactionAdapter>,
+        dex_info: DexInfo,
+        transfer_actions: Arc<TransferMap>,
+        classified_instructions: Vec<ClassifiedInstruction>,
+    ) -> Self {
+        let utils = TransactionUtils::new(adapter.clone());
+        Self {
+            adapter,
+            dex_info,
+            transfer_actions,
+            classified_instructions,
+            utils,
+        }
+    }
+
+    #[inline]
+    fn is_swap(data: &[u8]) -> bool {
+        let Some(disc_bytes) = data.get(..8).and_then(|d| <[u8; 8]>::try_from(d).ok()) else {
+            return false;
+        };
+        matches!(
+            u64::from_le_bytes(disc_bytes),
+            meteora_damm_v2_u64::SWAP_U64 | meteora_damm_v2_u64::SWAP2_U64
+        )
+    }
+
+    pub fn decode_swap_event(data: &[u8]) -> Option<DammV2SwapEvent> {
+        if data.len() < SWAP_EVENT_LEN {
+            return None;
+        }
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+
+        // 34: params { amount_in, minimum_amount_out }
+        // 50: swap_result { output_amount, next_sqrt_price: u128, lp_fee, protocol_fee, partner_fee, referral_fee }
+        Some(DammV2SwapEvent {
+            pool: bs58::encode(&data[0..32]).into_string(),
+            trade_direction: data[32],
+            has_referral: data[33] == 1,
+            amount_in: u64_at(34),
+            output_amount: u64_at(50),
+            lp_fee: u64_at(74),
+            protocol_fee: u64_at(82),
+            partner_fee: u64_at(90),
+            referral_fee: u64_at(98),
+            actual_amount_in: u64_at(106),
+            outer_index: 0,
+            inner_index: None,
+        })
+    }
+
+    fn parse_swap_events(&self) -> Vec<DammV2SwapEvent> {
+        let mut events = Vec::new();
+        for classified in &self.classified_instructions {
+            if classified.program_id != program_ids::METEORA_DAMM_V2
+                || classified.inner_index.is_none()
+            {
+                continue;
+            }
+            let Ok(data) = get_instruction_data(&classified.data) else {
+                continue;
+            };
+            if data.len() < 16 || data[..16] != meteora_damm_v2_events::EVT_SWAP {
+                continue;
+            }
+            if let Some(mut event) = Self::decode_swap_event(&data[16..]) {
+                event.outer_index = classified.outer_index;
+                event.inner_index = classified.inner_index;
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Первый ещё не использованный event того же пула, эмитнутый после инструкции.
+    fn take_event(
+        events: &mut [Option<DammV2SwapEvent>],
+        classified: &ClassifiedInstruction,
+        pool: &str,
+    ) -> Option<DammV2SwapEvent> {
+        let slot = events.iter_mut().find(|slot| {
+            slot.as_ref().is_some_and(|event| {
+                event.outer_index == classified.outer_index
+                    && event.inner_index > classified.inner_index
+                    && event.pool == pool
+            })
+        })?;
+        slot.take()
+    }
+
+    #[inline]
+    fn get_transfers_for_instruction(
+        &self,
+        program_id: &str,
+        outer_index: usize,
+        inner_index: Option<usize>,
+    ) -> Vec<TransferData> {
+        let mut key_buf = String::with_capacity(128);
+        key_buf.push_str(program_id);
+        key_buf.push(':');
+        let mut num_buf = itoa::Buffer::new();
+        key_buf.push_str(num_buf.format(outer_index));
+        if let Some(inner) = inner_index {
+            key_buf.push('-');
+            key_buf.push_str(num_buf.format(inner));
+        }
+
+        self.transfer_actions
+            .get(&key_buf)
+            .map(|v| {
+                v.iter()
+                    .filter(|t| {
+                        matches!(
+                            t.transfer_type.as_str(),
+                            "transfer" | "transferChecked" | "transferCheckedWithFee"
+                        )
+                    })
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl TradeParser for MeteoraDAMMV2Parser {
+    fn process_trades(&mut self) -> Vec<TradeInfo> {
+        let mut events: Vec<Option<DammV2SwapEvent>> =
+            self.parse_swap_events().into_iter().map(Some).collect();
+        let amm = self
+            .dex_info
+            .amm
+            .clone()
+            .filter(|a| a != "Unknown DEX")
+            .unwrap_or_else(|| dex_program_names::name(program_ids::METEORA_DAMM_V2).to_string());
+        let mut trades = Vec::new();
+
+        for classified in &self.classified_instructions {
+            if classified.program_id != program_ids::METEORA_DAMM_V2 {
+                continue;
+            }
+            let Ok(data) = get_instruction_data(&classified.data) else {
+                continue;
+            };
+            if !Self::is_swap(&data) {
+                continue;
+            }
+
+            let transfers = self.get_transfers_for_instruction(
+                &classified.program_id,
+                classified.outer_index,
+                classified.inner_index,
+            );
+            let Some(mut trade) = self.utils.process_swap_data(
+                &transfers,
+                &DexInfo {
+                    program_id: Some(classified.program_id.to_string()),
+                    amm: Some(amm.clone()),
+                    route: self.dex_info.route.clone(),
+                },
+            ) else {
+                continue;
+            };
+
+            let accounts = self.adapter.get_instruction_accounts(&classified.data);
+            let Some(pool) = accounts.get(POOL).map(|pool| pool.to_string()) else {
+                continue;
+            };
+            trade.pool = vec![pool.clone()];
+
+            // Vault, в который пришёл input: по нему определяем направление.
+            let vaults = (accounts.get(TOKEN_A_VAULT), accounts.get(TOKEN_B_VAULT));
+            let input_vault = transfers
+                .iter()
+                .find(|t| t.info.mint == trade.input_token.mint)
+                .map(|t| t.info.destination.to_string());
+            if let (Some(a), Some(b)) = vaults {
+                let (input_vault, output_vault) = if input_vault.as_deref() == Some(&**b) {
+                    (b, a)
+                } else {
+                    (a, b)
+                };
+                trade.input_token.destination = Some(input_vault.to_string());
+                trade.output_token.source = Some(output_vault.to_string());
+            }
+
+            if let Some(event) = Self::take_event(&mut events, classified, &pool) {
+                // При collect_fee_mode BothToken комиссия берётся с output; режим OnlyB
+                // в event'е не виден, поэтому считаем её в output-токене.
+                let fee_token = &trade.output_token;
+                let total =
+                    event.lp_fee + event.protocol_fee + event.partner_fee + event.referral_fee;
+                if total > 0 {
+                    trade.fee = Some(build_fee_info(
+                        &fee_token.mint,
+                        total as u128,
+                        fee_token.decimals,
+                        Some(amm.clone()),
+                    ));
+                }
+                let referral = event
+                    .has_referral
+                    .then(|| {
+                        accounts
+                            .get(REFERRAL_TOKEN_ACCOUNT)
+                            .map(|account| account.to_string())
+                    })
+                    .flatten();
+                trade.fees = [
+                    (FeeKind::Lp, event.lp_fee, Some(pool.clone())),
+                    (FeeKind::Protocol, event.protocol_fee, Some(pool.clone())),
+                    (FeeKind::Platform, event.partner_fee, Some(pool.clone())),
+                    (FeeKind::Referral, event.referral_fee, referral),
+                ]
+                .into_iter()
+                .filter(|(_, amount, _)| *amount > 0)
+                .map(|(kind, amount, recipient)| {
+                    build_fee_info(
+                        &fee_token.mint,
+                        amount as u128,
+                        fee_token.decimals,
+                        Some(amm.clone()),
+                    )
+                    .with_kind(kind, recipient)
+                })
+                .collect();
+            }
+
+            let trade = self.utils.attach_pool_reserves(trade);
+            trades.push(
+                self.utils
+                    .attach_token_transfer_info(trade, &self.transfer_actions),
+            );
+        }
+
+        trades
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_swap_event() {
+        let pool = [7u8; 32];
+        let mut data = Vec::with_capacity(SWAP_EVENT_LEN);
+        data.extend_from_slice(&pool);
+        data.push(1);
+        data.push(0);
+        data.extend_from_slice(&1_000_000u64.to_le_bytes());
+        data.extend_from_slice(&990u64.to_le_bytes());
+        data.extend_from_slice(&5_000u64.to_le_bytes());
+        data.extend_from_slice(&123u128.to_le_bytes());
+        data.extend_from_slice(&40u64.to_le_bytes());
+        data.extend_from_slice(&10u64.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+        data.extend_from_slice(&1_000_000u64.to_le_bytes());
+        data.extend_from_slice(&1_700_000_000u64.to_le_bytes());
+
+        let event = MeteoraDAMMV2Parser::decode_swap_event(&data).expect("swap event");
+        assert_eq!(event.pool, bs58::encode(pool).into_string());
+        assert_eq!(event.trade_direction, 1);
+        assert!(!event.has_referral);
+        assert_eq!(event.amount_in, 1_000_000);
+        assert_eq!(event.output_amount, 5_000);
+        assert_eq!((event.lp_fee, event.protocol_fee), (40, 10));
+        assert_eq!(event.actual_amount_in, 1_000_000);
+        assert!(MeteoraDAMMV2Parser::decode_swap_event(&data[..SWAP_EVENT_LEN - 1]).is_none());
+    }
+}
@@ -3,24 +3,27 @@ use std::sync::Arc;
 use crate::core::instruction_classifier::InstructionClassifier;
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::core::transaction_utils::TransactionUtils;
+use crate::core::utils::parse_idx;
 use crate::protocols::simple::MemeEventParser;
-use crate::types::{ClassifiedInstruction, MemeEvent, TradeType, TransferData, TransferMap};
+use crate::types::{
+    AccountKey, ClassifiedInstruction, MemeEvent, TradeType, TransferData, TransferMap,
+};
 
 use super::constants::{
-    discriminators::meteora_dbc_u64,
+    discriminators::{meteora_dbc_events, meteora_dbc_u64},
     program_ids, program_names,
 };
 use crate::protocols::pumpfun::binary_reader::BinaryReaderRef;
-use crate::protocols::pumpfun::util::{build_token_info, get_trade_type, sort_by_idx};
+use crate::protocols::pumpfun::util::{build_token_info, convert_to_ui_amount, sort_by_idx};
 
 pub struct MeteoraDBCEventParser {
-    adapter: TransactionAdapter,
-    transfer_actions: TransferMap,
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
     utils: TransactionUtils,
 }
 
 impl MeteoraDBCEventParser {
-    pub fn new(adapter: TransactionAdapter, transfer_actions: TransferMap) -> Self {
+    pub fn new(adapter: Arc<TransactionAdapter>, transfer_actions: Arc<TransferMap>) -> Self {
         let utils = TransactionUtils::new(adapter.clone());
         Self {
             adapter,
@@ -37,8 +40,8 @@ impl MeteoraDBCEventParser {
 
         for classified in instructions {
             let data = match crate::core::utils::get_instruction_data(&classified.data) {
-                d if d.is_empty() => continue,
-                d => d,
+                Ok(d) if !d.is_empty() => d,
+                _ => continue,
             };
 
             if data.len() < 8 {
@@ -63,7 +66,7 @@ impl MeteoraDBCEventParser {
             } else if disc_u64 == meteora_dbc_u64::METEORA_DBC_MIGRATE_DAMM_U64 {
                 self.decode_dbc_migrate_damm_event(&classified.data).ok()
             } else if disc_u64 == meteora_dbc_u64::METEORA_DBC_MIGRATE_DAMM_V2_U64 {
-                self.decode_dbc_migrate_damm_v2_event(&classified.data).ok()
+                self.decode_dbc_migrate_damm_v2_event(classified).ok()
             } else {
                 None
             };
@@ -85,6 +88,20 @@ impl MeteoraDBCEventParser {
                             meme_event.output_token = Some(trade.output_token);
                         }
                     }
+
+                    if let Some(swap) = find_swap_event(instructions, classified) {
+                        meme_event.platform_fee = Some(swap.trading_fee as f64);
+                        meme_event.protocol_fee = Some(swap.protocol_fee as f64);
+                        if swap.has_referral {
+                            meme_event.share_fee = Some(swap.referral_fee as f64);
+                            // accounts: ..., referral_token_account (12)
+                            meme_event.fee_recipient = self
+                                .adapter
+                                .get_instruction_accounts(&classified.data)
+                                .get(12)
+                                .map(ToString::to_string);
+                        }
+                    }
                 }
 
                 meme_event.protocol = Some(program_names::METEORA_DBC.to_string());
@@ -146,13 +163,13 @@ impl MeteoraDBCEventParser {
             idx: String::new(),
             slot: 0,
             signature: String::new(),
-            user: user_account.clone(),
-            base_mint: base_mint.clone(),
-            quote_mint: quote_mint.clone(),
+            user: user_account.to_string(),
+            base_mint: base_mint.to_string(),
+            quote_mint: quote_mint.to_string(),
             input_token: Some(build_token_info(&input_mint, input_amount as u128, 0, None)),
             output_token: Some(build_token_info(&output_mint, output_amount as u128, 0, None)),
-            bonding_curve: accounts.get(2).cloned(),
-            pool: accounts.get(2).cloned(),
+            bonding_curve: accounts.get(2).map(ToString::to_string),
+            pool: accounts.get(2).map(ToString::to_string),
             ..Default::default()
         })
     }
@@ -179,15 +196,16 @@ impl MeteoraDBCEventParser {
             idx: String::new(),
             slot: 0,
             signature: String::new(),
-            user: accounts.get(2).cloned().unwrap_or_default(),
-            base_mint: accounts.get(3).cloned().unwrap_or_default(),
-            quote_mint: accounts.get(4).cloned().unwrap_or_default(),
+            user: accounts.get(2).map(ToString::to_string).unwrap_or_default(),
+            base_mint: accounts.get(3).map(ToString::to_string).unwrap_or_default(),
+            quote_mint: accounts.get(4).map(ToString::to_string).unwrap_or_default(),
             name: Some(name),
             symbol: Some(symbol),
             uri: Some(uri),
-            pool: accounts.get(5).cloned(),
-            bonding_curve: accounts.get(5).cloned(),
-            platform_config: accounts.get(0).cloned(),
+            pool: accounts.get(5).map(ToString::to_string),
+            bonding_curve: accounts.get(5).map(ToString::to_string),
+            platform_config: accounts.get(0).map(ToString::to_string),
+            creator: accounts.get(2).map(ToString::to_string),
             ..Default::default()
         })
     }
@@ -205,11 +223,11 @@ impl MeteoraDBCEventParser {
             slot: 0,
             signature: String::new(),
             user: String::new(),
-            base_mint: accounts.get(7).cloned().unwrap_or_default(),
-            quote_mint: accounts.get(8).cloned().unwrap_or_default(),
-            platform_config: accounts.get(2).cloned(),
-            bonding_curve: accounts.get(0).cloned(),
-            pool: accounts.get(4).cloned(),
+            base_mint: accounts.get(7).map(ToString::to_string).unwrap_or_default(),
+            quote_mint: accounts.get(8).map(ToString::to_string).unwrap_or_default(),
+            platform_config: accounts.get(2).map(ToString::to_string),
+            bonding_curve: accounts.get(0).map(ToString::to_string),
+            pool: accounts.get(4).map(ToString::to_string),
             pool_dex: Some(program_names::METEORA_DAMM.to_string()),
             ..Default::default()
         })
@@ -217,9 +235,17 @@ impl MeteoraDBCEventParser {
 
     fn decode_dbc_migrate_damm_v2_event(
         &self,
-        instruction: &crate::types::SolanaInstruction,
+        classified: &ClassifiedInstruction,
     ) -> Result<MemeEvent, String> {
-        let accounts = self.adapter.get_instruction_accounts(instruction);
+        let accounts = self.adapter.get_instruction_accounts(&classified.data);
+
+        // Ликвидность, перенесённая в DAMM v2 пул: base -> token_a_vault (15), quote -> token_b_vault (16)
+        let base = accounts
+            .get(15)
+            .and_then(|vault| self.migrated_amount(classified.outer_index, vault));
+        let quote = accounts
+            .get(16)
+            .and_then(|vault| self.migrated_amount(classified.outer_index, vault));
 
         Ok(MemeEvent {
             event_type: TradeType::Migrate,
@@ -227,17 +253,38 @@ impl MeteoraDBCEventParser {
             idx: String::new(),
             slot: 0,
             signature: String::new(),
-            user: String::new(),
-            base_mint: accounts.get(13).cloned().unwrap_or_default(),
-            quote_mint: accounts.get(14).cloned().unwrap_or_default(),
-            platform_config: accounts.get(2).cloned(),
-            bonding_curve: accounts.get(0).cloned(),
-            pool: accounts.get(4).cloned(),
+            user: accounts.get(19).map(ToString::to_string).unwrap_or_default(),
+            base_mint: accounts.get(13).map(ToString::to_string).unwrap_or_default(),
+            quote_mint: accounts.get(14).map(ToString::to_string).unwrap_or_default(),
+            decimals: base.map(|(_, decimals)| decimals),
+            platform_config: accounts.get(2).map(ToString::to_string),
+            bonding_curve: accounts.get(0).map(ToString::to_string),
+            pool: accounts.get(4).map(ToString::to_string),
             pool_dex: Some(program_names::METEORA_DAMM_V2.to_string()),
+            pool_a_reserve: base.map(|(amount, _)| amount),
+            pool_b_reserve: quote.map(|(amount, _)| amount),
             ..Default::default()
         })
     }
 
+    /// UI-сумма и decimals переводов на `vault` внутри outer-инструкции миграции.
+    /// Переводы делает DAMM v2 через CPI, поэтому ищем по всем ключам, а не по ключу DBC.
+    fn migrated_amount(&self, outer_index: usize, vault: &AccountKey) -> Option<(f64, u8)> {
+        let mut total = 0u128;
+        let mut decimals = None;
+        for transfer in self.transfer_actions.values().flatten() {
+            if transfer.info.destination != *vault
+                || usize::from(parse_idx(&transfer.idx).0) != outer_index
+            {
+                continue;
+            }
+            total += transfer.info.token_amount.amount.parse::<u128>().unwrap_or(0);
+            decimals = Some(transfer.info.token_amount.decimals);
+        }
+        let decimals = decimals?;
+        Some((convert_to_ui_amount(total, decimals), decimals))
+    }
+
     /// Получает transfers для инструкции
     #[inline]
     fn get_transfers_for_instruction(
@@ -260,7 +307,7 @@ impl MeteoraDBCEventParser {
                     .filter(|t| {
                         matches!(
                             t.transfer_type.as_str(),
-                            "transfer" | "transferChecked"
+                            "transfer" | "transferChecked" | "transferCheckedWithFee"
                         )
                     })
                     .collect()
@@ -287,12 +334,152 @@ impl MeteoraDBCEventParser {
     }
 }
 
+/// Комиссии из `EvtSwap` (swap_result), суммы в raw единицах fee-токена.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DbcSwapFees {
+    pub has_referral: bool,
+    pub trading_fee: u64,
+    pub protocol_fee: u64,
+    pub referral_fee: u64,
+}
+
+/// `data` — тело `EvtSwap` без 16-байтного префикса event CPI.
+pub fn decode_swap_fees(data: &[u8]) -> Option<DbcSwapFees> {
+    // pool (0), config (32), trade_direction (64), has_referral (65),
+    // params: amount_in, minimum_amount_out (66..82),
+    // swap_result: actual_input_amount, output_amount, next_sqrt_price (u128), trading_fee (114), ...
+    if data.len() < SWAP_FEES_END {
+        return None;
+    }
+    let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+    Some(DbcSwapFees {
+        has_referral: data[65] != 0,
+        trading_fee: u64_at(114),
+        protocol_fee: u64_at(122),
+        referral_fee: u64_at(130),
+    })
+}
+
+const SWAP_FEES_END: usize = 138;
+
+/// `EvtSwap` self-CPI той же outer-инструкции, идущий после swap.
+fn find_swap_event(
+    instructions: &[ClassifiedInstruction],
+    swap: &ClassifiedInstruction,
+) -> Option<DbcSwapFees> {
+    instructions.iter().find_map(|classified| {
+        if classified.outer_index != swap.outer_index
+            || classified.inner_index <= swap.inner_index
+        {
+            return None;
+        }
+        let data = crate::core::utils::get_instruction_data(&classified.data).ok()?;
+        if data.len() < 16 || data[..16] != meteora_dbc_events::EVT_SWAP {
+            return None;
+        }
+        decode_swap_fees(&data[16..])
+    })
+}
+
 impl MemeEventParser for MeteoraDBCEventParser {
     fn process_events(&mut self) -> Vec<MemeEvent> {
         let classifier = InstructionClassifier::new(&self.adapter);
         // ZERO-COPY: получаем ссылку, передаем как срез
         let instructions = classifier.get_instructions(program_ids::METEORA_DBC);
-        self.parse_instructions(instructions)
+        let mut events = self.parse_instructions(instructions);
+        MemeEvent::link_dev_buys(&mut events);
+        events
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ParseConfig;
+    use crate::types::{SolanaInstruction, SolanaTransaction, TokenAmount, TransferInfo};
+    use base64_simd::STANDARD;
+
+    use super::super::constants::discriminators::meteora_dbc;
+
+    fn vault_transfer(
+        destination: &str,
+        mint: &str,
+        amount: u64,
+        decimals: u8,
+        idx: &str,
+    ) -> TransferData {
+        TransferData {
+            transfer_type: "transferChecked".to_string(),
+            program_id: "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA".into(),
+            info: TransferInfo {
+                authority: None,
+                destination: destination.into(),
+                destination_owner: None,
+                mint: mint.into(),
+                source: "payer-token".into(),
+                token_amount: TokenAmount::new(amount.to_string(), decimals, None),
+                source_balance: None,
+                source_pre_balance: None,
+                destination_balance: None,
+                destination_pre_balance: None,
+                sol_balance_change: None,
+            },
+            idx: idx.to_string(),
+            timestamp: 0,
+            signature: String::new(),
+            is_fee: false,
+            fee_amount: None,
+            instruction_index: 0,
+            inner_index: None,
+            order_key: None,
+        }
+    }
+
+    #[test]
+    fn migration_to_damm_v2_reports_migrated_liquidity() {
+        let accounts: Vec<String> = (0..25).map(|i| format!("account-{i}")).collect();
+        let instruction = SolanaInstruction {
+            program_id: program_ids::METEORA_DBC.into(),
+            accounts: accounts.iter().map(|a| a.as_str().into()).collect(),
+            data: STANDARD.encode_to_string(meteora_dbc::METEORA_DBC_MIGRATE_DAMM_V2),
+        };
+        let tx = SolanaTransaction {
+            instructions: vec![instruction.clone()],
+            ..Default::default()
+        };
+        // переводы в vault'ы делает DAMM v2, поэтому они лежат под его ключом
+        let transfers: TransferMap = [(
+            format!("{}:0-2", program_ids::METEORA_DAMM_V2),
+            vec![
+                vault_transfer("account-15", "account-13", 206_900_000_000_000, 6, "0-3"),
+                vault_transfer("account-16", "account-14", 85_000_000_000, 9, "0-4"),
+                vault_transfer("account-15", "account-13", 1, 6, "1-3"),
+            ],
+        )]
+        .into();
+
+        let parser = MeteoraDBCEventParser::new(
+            Arc::new(TransactionAdapter::new(tx, ParseConfig::default())),
+            Arc::new(transfers),
+        );
+        let events = parser.parse_instructions(&[ClassifiedInstruction {
+            program_id: program_ids::METEORA_DBC.into(),
+            outer_index: 0,
+            inner_index: None,
+            data: instruction,
+            data_bytes: Vec::new(),
+            stack_height: None,
+            decoded: None,
+        }]);
+
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.event_type, TradeType::Migrate);
+        assert_eq!(event.bonding_curve.as_deref(), Some("account-0"));
+        assert_eq!(event.pool.as_deref(), Some("account-4"));
+        assert_eq!(event.user, "account-19");
+        assert_eq!(event.pool_a_reserve, Some(206_900_000.0));
+        assert_eq!(event.pool_b_reserve, Some(85.0));
+        assert_eq!(event.decimals, Some(6));
+    }
+}
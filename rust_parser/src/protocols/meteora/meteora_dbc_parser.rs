@@ -1,28 +1,29 @@
+use std::sync::Arc;
+
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::protocols::simple::TradeParser;
-use crate::types::{ClassifiedInstruction, DexInfo, TradeInfo, TransferMap};
+use crate::protocols::pumpfun::util::build_fee_info;
+use crate::types::{ClassifiedInstruction, DexInfo, FeeInfo, FeeKind, MemeEvent, TradeConfidence, TradeInfo, TransferMap};
 
 use super::constants::program_names;
 use super::meteora_dbc_event_parser::MeteoraDBCEventParser;
 
 pub struct MeteoraDBCParser {
-    adapter: TransactionAdapter,
     dex_info: DexInfo,
-    transfer_actions: TransferMap,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
     event_parser: MeteoraDBCEventParser,
 }
 
 impl MeteoraDBCParser {
     pub fn new(
-        adapter: TransactionAdapter,
+        adapter: Arc<TransactionAdapter>,
         dex_info: DexInfo,
-        transfer_actions: TransferMap,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Self {
-        let event_parser = MeteoraDBCEventParser::new(adapter.clone(), transfer_actions.clone());
+        let event_parser = MeteoraDBCEventParser::new(adapter, transfer_actions.clone());
         Self {
-            adapter,
             dex_info,
             transfer_actions,
             classified_instructions,
@@ -30,7 +31,33 @@ impl MeteoraDBCParser {
         }
     }
 
-    fn create_trade_info(&self, event: &crate::types::MemeEvent) -> TradeInfo {
+    /// Комиссии DBC берутся в quote-токене и копятся в пуле до claim;
+    /// referral сразу уходит на referral_token_account.
+    fn swap_fees(event: &MemeEvent) -> Vec<FeeInfo> {
+        let decimals = [&event.input_token, &event.output_token]
+            .into_iter()
+            .flatten()
+            .find(|token| token.mint == event.quote_mint)
+            .map(|token| token.decimals)
+            .unwrap_or(9);
+        let dex = Some(program_names::METEORA_DBC.to_string());
+        [
+            (FeeKind::Platform, event.platform_fee, event.pool.clone()),
+            (FeeKind::Protocol, event.protocol_fee, event.pool.clone()),
+            (FeeKind::Referral, event.share_fee, event.fee_recipient.clone()),
+        ]
+        .into_iter()
+        .filter_map(|(kind, amount, recipient)| {
+            let amount = amount.filter(|amount| *amount > 0.0)? as u128;
+            Some(
+                build_fee_info(&event.quote_mint, amount, decimals, dex.clone())
+                    .with_kind(kind, recipient),
+            )
+        })
+        .collect()
+    }
+
+    fn create_trade_info(&self, event: &MemeEvent) -> TradeInfo {
         TradeInfo {
             trade_type: event.event_type.clone(),
             pool: event.pool.as_ref().map(|p| vec![p.clone()]).unwrap_or_default(),
@@ -38,7 +65,7 @@ impl MeteoraDBCParser {
             output_token: event.output_token.clone().unwrap_or_default(),
             slippage_bps: None,
             fee: None,
-            fees: Vec::new(),
+            fees: Self::swap_fees(event),
             user: Some(event.user.clone()),
             program_id: self.dex_info.program_id.clone(),
             amm: Some(program_names::METEORA_DBC.to_string()),
@@ -49,6 +76,22 @@ impl MeteoraDBCParser {
             signature: event.signature.clone(),
             idx: event.idx.clone(),
             signer: None,
+            dlmm: None,
+            clob: None,
+            liquidity_role: None,
+            price_quote_per_base: None,
+            value_usd: None,
+            input_symbol: None,
+            output_symbol: None,
+            instruction_index: 0,
+            inner_index: None,
+            pool_reserves: None,
+            order_key: None,
+            side: None,
+            authority: None,
+            flags: Vec::new(),
+            router: None,
+            confidence: TradeConfidence::Unverified,
         }
     }
 }
@@ -1,6 +1,10 @@
+use std::sync::Arc;
+
 use crate::core::constants::TOKENS;
 use crate::protocols::simple::LiquidityParser;
-use crate::types::{ClassifiedInstruction, PoolEvent, PoolEventType, TradeType, TransferData, TransferMap};
+use crate::types::{
+    ClassifiedInstruction, LegDirection, PoolEvent, PoolEventType, TradeType, TransferData, TransferMap,
+};
 
 use super::constants::discriminators::{
     meteora_dlmm_u64,
@@ -15,8 +19,8 @@ pub struct MeteoraDLMMLiquidityParser {
 
 impl MeteoraDLMMLiquidityParser {
     pub fn new(
-        adapter: TransactionAdapter,
-        transfer_actions: TransferMap,
+        adapter: Arc<TransactionAdapter>,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Self {
         Self {
@@ -57,13 +61,44 @@ impl MeteoraDLMMLiquidityParser {
                 | meteora_dlmm_u64::REMOVE_LIQUIDITY_BY_RANGE_U64
                 | meteora_dlmm_u64::REMOVE_LIQUIDITY_BY_RANGE2_U64
                 | meteora_dlmm_u64::REMOVE_ALL_LIQUIDITY_U64
-                | meteora_dlmm_u64::CLAIM_FEE_U64
-                | meteora_dlmm_u64::CLAIM_FEE_V2_U64
         ) {
             return Some(("removeLiquidity".to_string(), PoolEventType::Remove));
         }
 
-        None
+        match disc_u64 {
+            meteora_dlmm_u64::CLAIM_FEE_U64 | meteora_dlmm_u64::CLAIM_FEE_V2_U64 => {
+                Some(("claimFee".to_string(), PoolEventType::CollectFee))
+            }
+            meteora_dlmm_u64::INITIALIZE_POSITION_U64
+            | meteora_dlmm_u64::INITIALIZE_POSITION_PDA_U64 => {
+                Some(("initializePosition".to_string(), PoolEventType::OpenPosition))
+            }
+            meteora_dlmm_u64::CLOSE_POSITION_U64
+            | meteora_dlmm_u64::CLOSE_POSITION2_U64
+            | meteora_dlmm_u64::CLOSE_POSITION_IF_EMPTY_U64 => {
+                Some(("closePosition".to_string(), PoolEventType::ClosePosition))
+            }
+            _ => None,
+        }
+    }
+
+    /// Индексы (lb_pair, token_x_mint, token_y_mint): у claimFee пул первым аккаунтом.
+    fn withdraw_accounts(data: &[u8]) -> (usize, usize, usize) {
+        match data.get(..8).and_then(|d| d.try_into().ok()).map(u64::from_le_bytes) {
+            Some(meteora_dlmm_u64::CLAIM_FEE_U64) => (0, 9, 10),
+            Some(meteora_dlmm_u64::CLAIM_FEE_V2_U64) => (0, 7, 8),
+            _ => (1, 7, 8),
+        }
+    }
+
+    /// Аккаунт lb_pair у initializePosition(Pda)/closePosition; closePosition2 пул не передаёт.
+    fn position_pool_index(data: &[u8]) -> Option<usize> {
+        match data.get(..8).and_then(|d| d.try_into().ok()).map(u64::from_le_bytes)? {
+            meteora_dlmm_u64::INITIALIZE_POSITION_U64 => Some(2),
+            meteora_dlmm_u64::INITIALIZE_POSITION_PDA_U64 => Some(3),
+            meteora_dlmm_u64::CLOSE_POSITION_U64 => Some(1),
+            _ => None,
+        }
     }
 
     fn parse_instruction(
@@ -73,7 +108,7 @@ impl MeteoraDLMMLiquidityParser {
         outer_index: usize,
         inner_index: Option<usize>,
     ) -> Option<PoolEvent> {
-        let data = crate::core::utils::get_instruction_data(instruction);
+        let data = crate::core::utils::get_instruction_data(instruction).ok()?;
         let (_name, action) = self.get_pool_action(&data)?;
 
         let mut transfers = self.base.get_transfers_for_instruction(program_id, outer_index, inner_index);
@@ -85,9 +120,19 @@ impl MeteoraDLMMLiquidityParser {
 
         match action {
             PoolEventType::Add => Some(self.parse_add_liquidity_event(instruction, outer_index, &data, &transfers_owned)),
-            PoolEventType::Remove => {
-                Some(self.parse_remove_liquidity_event(instruction, outer_index, &data, &transfers_owned))
-            }
+            PoolEventType::Remove | PoolEventType::CollectFee => Some(self.parse_remove_liquidity_event(
+                instruction,
+                outer_index,
+                &data,
+                &transfers_owned,
+                action,
+            )),
+            PoolEventType::OpenPosition | PoolEventType::ClosePosition => Some(self.base.position_event(
+                instruction,
+                outer_index,
+                action,
+                Self::position_pool_index(&data),
+            )),
             _ => None,
         }
     }
@@ -129,27 +174,20 @@ impl MeteoraDLMMLiquidityParser {
             signature: base.signature,
             idx: base.idx,
             signer: base.signer,
-            pool_id: accounts.get(1).cloned().unwrap_or_default(),
+            pool_id: accounts.get(1).map(ToString::to_string).unwrap_or_default(),
             config: None,
-            pool_lp_mint: accounts.get(1).cloned(),
-            token0_mint: token0.as_ref().map(|t| t.info.mint.clone()),
-            token0_amount: token0.as_ref().and_then(|t| t.info.token_amount.ui_amount).or(Some(0.0)),
-            token0_amount_raw: token0.as_ref().map(|t| t.info.token_amount.amount.clone()),
-            token0_balance_change: None,
-            token0_decimals: token0
-                .as_ref()
-                .map(|t| self.base.adapter.get_token_decimals(&t.info.mint))
-                .or(Some(0)),
-            token1_mint: token1.as_ref().map(|t| t.info.mint.clone()),
-            token1_amount: token1.as_ref().and_then(|t| t.info.token_amount.ui_amount).or(Some(0.0)),
-            token1_amount_raw: token1.as_ref().map(|t| t.info.token_amount.amount.clone()),
-            token1_balance_change: None,
-            token1_decimals: token1
-                .as_ref()
-                .map(|t| self.base.adapter.get_token_decimals(&t.info.mint))
-                .or(Some(0)),
+            pool_lp_mint: accounts.get(1).map(ToString::to_string),
             lp_amount: None,
             lp_amount_raw: None,
+            tokens: vec![
+                self.base.token_leg(token0.as_ref(), None, LegDirection::In),
+                self.base.token_leg(token1.as_ref(), None, LegDirection::In),
+            ],
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
         }
     }
 
@@ -157,26 +195,28 @@ impl MeteoraDLMMLiquidityParser {
         &self,
         instruction: &crate::types::SolanaInstruction,
         index: usize,
-        _data: &[u8],
+        data: &[u8],
         transfers: &[TransferData],
+        action: PoolEventType,
     ) -> PoolEvent {
         let accounts = self.base.adapter.get_instruction_accounts(instruction);
+        let (pool, mint_x, mint_y) = Self::withdraw_accounts(data);
         let (mut token0, mut token1) = self.normalize_tokens(transfers);
 
-        // Специальная логика для remove: если token1 отсутствует и token0.mint == accounts[8], то token1 = token0
+        // Специальная логика для remove: если token1 отсутствует и token0.mint == token_y_mint, то token1 = token0
         if token1.is_none() {
             if let Some(ref t0) = token0 {
-                if t0.info.mint == accounts.get(8).cloned().unwrap_or_default() {
+                if t0.info.mint == accounts.get(mint_y).cloned().unwrap_or_default() {
                     token1 = token0.clone();
                     token0 = None;
                 }
             }
         }
 
-        // Если token0 отсутствует и token1.mint == accounts[7], то token0 = token1
+        // Если token0 отсутствует и token1.mint == token_x_mint, то token0 = token1
         if token0.is_none() {
             if let Some(ref t1) = token1 {
-                if t1.info.mint == accounts.get(7).cloned().unwrap_or_default() {
+                if t1.info.mint == accounts.get(mint_x).cloned().unwrap_or_default() {
                     token0 = token1.clone();
                     token1 = None;
                 }
@@ -186,19 +226,19 @@ impl MeteoraDLMMLiquidityParser {
         let token0_mint = token0
             .as_ref()
             .map(|t| t.info.mint.clone())
-            .unwrap_or_else(|| accounts.get(7).cloned().unwrap_or_default());
+            .unwrap_or_else(|| accounts.get(mint_x).cloned().unwrap_or_default());
         let token1_mint = token1
             .as_ref()
             .map(|t| t.info.mint.clone())
-            .unwrap_or_else(|| accounts.get(8).cloned().unwrap_or_default());
+            .unwrap_or_else(|| accounts.get(mint_y).cloned().unwrap_or_default());
         let program_id = self.base.adapter.get_instruction_program_id(instruction);
 
-        let mut base = self.base.adapter.get_pool_event_base(PoolEventType::Remove, program_id);
+        let mut base = self.base.adapter.get_pool_event_base(action.clone(), program_id);
         base.idx = index.to_string();
 
         PoolEvent {
             user: base.user,
-            event_type: TradeType::Remove,
+            event_type: action.into(),
             program_id: base.program_id,
             amm: base.amm,
             slot: base.slot,
@@ -206,29 +246,20 @@ impl MeteoraDLMMLiquidityParser {
             signature: base.signature,
             idx: base.idx,
             signer: base.signer,
-            pool_id: accounts.get(1).cloned().unwrap_or_default(),
+            pool_id: accounts.get(pool).map(ToString::to_string).unwrap_or_default(),
             config: None,
-            pool_lp_mint: accounts.get(1).cloned(),
-            token0_mint: Some(
-                token0.as_ref()
-                    .map(|t| t.info.mint.clone())
-                    .unwrap_or_else(|| accounts.get(7).cloned().unwrap_or_default()),
-            ),
-            token0_amount: token0.as_ref().and_then(|t| t.info.token_amount.ui_amount).or(Some(0.0)),
-            token0_amount_raw: token0.as_ref().map(|t| t.info.token_amount.amount.clone()),
-            token0_balance_change: None,
-            token0_decimals: Some(self.base.adapter.get_token_decimals(&token0_mint)),
-            token1_mint: Some(
-                token1.as_ref()
-                    .map(|t| t.info.mint.clone())
-                    .unwrap_or_else(|| accounts.get(8).cloned().unwrap_or_default()),
-            ),
-            token1_amount: token1.as_ref().and_then(|t| t.info.token_amount.ui_amount).or(Some(0.0)),
-            token1_amount_raw: token1.as_ref().map(|t| t.info.token_amount.amount.clone()),
-            token1_balance_change: None,
-            token1_decimals: Some(self.base.adapter.get_token_decimals(&token1_mint)),
+            pool_lp_mint: accounts.get(pool).map(ToString::to_string),
             lp_amount: None,
             lp_amount_raw: None,
+            tokens: vec![
+                self.base.token_leg(token0.as_ref(), Some(token0_mint.to_string()), LegDirection::Out),
+                self.base.token_leg(token1.as_ref(), Some(token1_mint.to_string()), LegDirection::Out),
+            ],
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
         }
     }
 }
@@ -0,0 +1,419 @@
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use solana_program::pubkey::Pubkey;
+
+use crate::core::constants::dex_program_names;
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::core::transaction_utils::TransactionUtils;
+use crate::core::utils::get_instruction_data;
+use crate::protocols::pumpfun::util::build_fee_info;
+use crate::protocols::simple::TradeParser;
+use crate::state_decoders::ProtocolAccount;
+use crate::types::{
+    AccountKey, ClassifiedInstruction, DexInfo, DlmmSwapInfo, FeeKind, TradeInfo, TransferData,
+    TransferMap,
+};
+
+use super::constants::{
+    discriminators::{meteora_dlmm_events, meteora_dlmm_u64},
+    program_ids, METEORA_DLMM_BIN_STEPS,
+};
+
+/// lb_pair + from + start/end bin + amount_in/out + swap_for_y + fee + protocol_fee + fee_bps + host_fee
+const SWAP_EVENT_LEN: usize = 32 + 32 + 4 + 4 + 8 + 8 + 1 + 8 + 8 + 16 + 8;
+
+static DLMM_PROGRAM: Lazy<Pubkey> =
+    Lazy::new(|| Pubkey::from_str(program_ids::METEORA).expect("valid DLMM program id"));
+
+/// Сколько пар помнит `BIN_STEP_CACHE`; вытесняются давно не встречавшиеся.
+const BIN_STEP_CACHE_SIZE: usize = 16_384;
+/// Пары, для которых PDA не подобрался, перепроверяются не чаще этого.
+const BIN_STEP_MISS_TTL: Duration = Duration::from_secs(600);
+
+/// lb_pair -> bin_step из подбора PDA (дорогой: до 255 sha256 на каждый шаг).
+/// Промах хранится с моментом проверки и живёт `BIN_STEP_MISS_TTL`.
+static BIN_STEP_CACHE: Lazy<Mutex<LruCache<String, Result<u16, Instant>>>> = Lazy::new(|| {
+    Mutex::new(LruCache::new(
+        NonZeroUsize::new(BIN_STEP_CACHE_SIZE).expect("non-zero cache size"),
+    ))
+});
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DlmmSwapEvent {
+    pub lb_pair: String,
+    pub from: String,
+    pub start_bin_id: i32,
+    pub end_bin_id: i32,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub swap_for_y: bool,
+    pub fee: u64,
+    pub protocol_fee: u64,
+    pub fee_bps: u128,
+    pub host_fee: u64,
+    pub outer_index: usize,
+    pub inner_index: Option<usize>,
+}
+
+/// Парсер swap'ов Meteora DLMM с bin id / bin step / fee bps из `Swap` event'а.
+pub struct MeteoraDLMMParser {
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+    utils: TransactionUtils,
+}
+
+impl MeteoraDLMMParser {
+    pub fn new(
+        adapter: Arc<TransactionAdapter>,
+        dex_info: DexInfo,
+        transfer_actions: Arc<TransferMap>,
+        classified_instructions: Vec<ClassifiedInstruction>,
+    ) -> Self {
+        let utils = TransactionUtils::new(adapter.clone());
+        Self {
+            adapter,
+            dex_info,
+            transfer_actions,
+            classified_instructions,
+            utils,
+        }
+    }
+
+    #[inline]
+    fn is_swap(data: &[u8]) -> bool {
+        let Some(disc_bytes) = data.get(..8).and_then(|d| <[u8; 8]>::try_from(d).ok()) else {
+            return false;
+        };
+        matches!(
+            u64::from_le_bytes(disc_bytes),
+            meteora_dlmm_u64::SWAP_U64
+                | meteora_dlmm_u64::SWAP_V2_U64
+                | meteora_dlmm_u64::SWAP_EXACT_OUT_U64
+                | meteora_dlmm_u64::SWAP_EXACT_OUT2_U64
+                | meteora_dlmm_u64::SWAP_WITH_PRICE_IMPACT_U64
+                | meteora_dlmm_u64::SWAP_WITH_PRICE_IMPACT2_U64
+        )
+    }
+
+    pub fn decode_swap_event(data: &[u8]) -> Option<DlmmSwapEvent> {
+        if data.len() < SWAP_EVENT_LEN {
+            return None;
+        }
+        let u64_at = |offset: usize| u64::from_le_bytes(data[offset..offset + 8].try_into().unwrap());
+        let i32_at = |offset: usize| i32::from_le_bytes(data[offset..offset + 4].try_into().unwrap());
+
+        Some(DlmmSwapEvent {
+            lb_pair: bs58::encode(&data[0..32]).into_string(),
+            from: bs58::encode(&data[32..64]).into_string(),
+            start_bin_id: i32_at(64),
+            end_bin_id: i32_at(68),
+            amount_in: u64_at(72),
+            amount_out: u64_at(80),
+            swap_for_y: data[88] == 1,
+            fee: u64_at(89),
+            protocol_fee: u64_at(97),
+            fee_bps: u128::from_le_bytes(data[105..121].try_into().unwrap()),
+            host_fee: u64_at(121),
+            outer_index: 0,
+            inner_index: None,
+        })
+    }
+
+    fn parse_swap_events(&self) -> Vec<DlmmSwapEvent> {
+        let mut events = Vec::new();
+        for classified in &self.classified_instructions {
+            if classified.program_id != program_ids::METEORA || classified.inner_index.is_none() {
+                continue;
+            }
+            let Ok(data) = get_instruction_data(&classified.data) else {
+                continue;
+            };
+            if data.len() < 16 || data[..16] != meteora_dlmm_events::SWAP {
+                continue;
+            }
+            if let Some(mut event) = Self::decode_swap_event(&data[16..]) {
+                event.outer_index = classified.outer_index;
+                event.inner_index = classified.inner_index;
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Bin step из аккаунта `LbPair`, если он подключён к парсеру, иначе подбором PDA
+    /// по mint'ам пары.
+    fn bin_step(
+        &self,
+        lb_pair: &str,
+        token_x: Option<&AccountKey>,
+        token_y: Option<&AccountKey>,
+    ) -> Option<u16> {
+        if let Some(ProtocolAccount::MeteoraLbPair(pair)) = self.adapter.protocol_account(lb_pair) {
+            return Some(pair.bin_step);
+        }
+        derive_bin_step(lb_pair, token_x?, token_y?)
+    }
+
+    /// Первый ещё не использованный event того же пула, эмитнутый после инструкции.
+    fn take_event(
+        events: &mut [Option<DlmmSwapEvent>],
+        classified: &ClassifiedInstruction,
+        lb_pair: &str,
+    ) -> Option<DlmmSwapEvent> {
+        let slot = events.iter_mut().find(|slot| {
+            slot.as_ref().is_some_and(|event| {
+                event.outer_index == classified.outer_index
+                    && event.inner_index > classified.inner_index
+                    && event.lb_pair == lb_pair
+            })
+        })?;
+        slot.take()
+    }
+
+    #[inline]
+    fn get_transfers_for_instruction(
+        &self,
+        program_id: &str,
+        outer_index: usize,
+        inner_index: Option<usize>,
+    ) -> Vec<TransferData> {
+        let mut key_buf = String::with_capacity(128);
+        key_buf.push_str(program_id);
+        key_buf.push(':');
+        let mut num_buf = itoa::Buffer::new();
+        key_buf.push_str(num_buf.format(outer_index));
+        if let Some(inner) = inner_index {
+            key_buf.push('-');
+            key_buf.push_str(num_buf.format(inner));
+        }
+
+        self.transfer_actions
+            .get(&key_buf)
+            .map(|v| {
+                v.iter()
+                    .filter(|t| matches!(t.transfer_type.as_str(), "transfer" | "transferChecked" | "transferCheckedWithFee"))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+impl TradeParser for MeteoraDLMMParser {
+    fn process_trades(&mut self) -> Vec<TradeInfo> {
+        let mut events: Vec<Option<DlmmSwapEvent>> =
+            self.parse_swap_events().into_iter().map(Some).collect();
+        let amm = self
+            .dex_info
+            .amm
+            .clone()
+            .filter(|a| a != "Unknown DEX")
+            .unwrap_or_else(|| dex_program_names::name(program_ids::METEORA).to_string());
+        let mut trades = Vec::new();
+
+        for classified in &self.classified_instructions {
+            if classified.program_id != program_ids::METEORA {
+                continue;
+            }
+            let Ok(data) = get_instruction_data(&classified.data) else {
+                continue;
+            };
+            if !Self::is_swap(&data) {
+                continue;
+            }
+
+            let transfers = self.get_transfers_for_instruction(
+                &classified.program_id,
+                classified.outer_index,
+                classified.inner_index,
+            );
+            let Some(mut trade) = self.utils.process_swap_data(
+                &transfers,
+                &DexInfo {
+                    program_id: Some(classified.program_id.to_string()),
+                    amm: Some(amm.clone()),
+                    route: self.dex_info.route.clone(),
+                },
+            ) else {
+                continue;
+            };
+
+            let accounts = self.adapter.get_instruction_accounts(&classified.data);
+            let lb_pair = accounts.first().cloned().unwrap_or_default();
+            if !lb_pair.is_empty() {
+                trade.pool = vec![lb_pair.to_string()];
+            }
+
+            if let Some(event) = Self::take_event(&mut events, classified, &lb_pair) {
+                // accounts: ..., token_x_mint (6), token_y_mint (7), ...
+                let bin_step = self.bin_step(&lb_pair, accounts.get(6), accounts.get(7));
+                if event.fee > 0 {
+                    trade.fee = Some(build_fee_info(
+                        &trade.input_token.mint,
+                        event.fee as u128,
+                        trade.input_token.decimals,
+                        Some(amm.clone()),
+                    ));
+                }
+                // fee включает protocol_fee, а тот — host_fee: раскладываем без пересечений
+                let protocol_fee = event.protocol_fee.min(event.fee);
+                let host_fee = event.host_fee.min(protocol_fee);
+                trade.fees = [
+                    (FeeKind::Lp, event.fee - protocol_fee, Some(lb_pair.clone())),
+                    (FeeKind::Protocol, protocol_fee - host_fee, Some(lb_pair.clone())),
+                    // accounts: ..., host_fee_in (9), ...
+                    (FeeKind::Referral, host_fee, accounts.get(9).cloned()),
+                ]
+                .into_iter()
+                .filter(|(_, amount, _)| *amount > 0)
+                .map(|(kind, amount, recipient)| {
+                    build_fee_info(
+                        &trade.input_token.mint,
+                        amount as u128,
+                        trade.input_token.decimals,
+                        Some(amm.clone()),
+                    )
+                    .with_kind(kind, recipient.map(String::from))
+                })
+                .collect();
+                trade.dlmm = Some(DlmmSwapInfo {
+                    lb_pair: event.lb_pair,
+                    start_bin_id: event.start_bin_id,
+                    end_bin_id: event.end_bin_id,
+                    swap_for_y: event.swap_for_y,
+                    bin_step,
+                    fee_bps: u64::try_from(event.fee_bps).ok(),
+                });
+            }
+
+            let trade = self.utils.attach_pool_reserves(trade);
+            trades.push(
+                self.utils
+                    .attach_token_transfer_info(trade, &self.transfer_actions),
+            );
+        }
+
+        trades
+    }
+}
+
+/// Подбирает bin step, при котором PDA `[min(x, y), max(x, y), bin_step]` совпадает с пулом.
+/// Для пулов, созданных с другими seeds (customizable, preset v2), возвращает `None`.
+pub fn derive_bin_step(lb_pair: &str, token_x: &str, token_y: &str) -> Option<u16> {
+    let mut cache = BIN_STEP_CACHE.lock().unwrap_or_else(|err| err.into_inner());
+    match cache.get(lb_pair) {
+        Some(Ok(bin_step)) => return Some(*bin_step),
+        Some(Err(checked_at)) if checked_at.elapsed() < BIN_STEP_MISS_TTL => return None,
+        _ => {}
+    }
+    drop(cache);
+
+    let pair = Pubkey::from_str(lb_pair).ok()?;
+    let x = Pubkey::from_str(token_x).ok()?;
+    let y = Pubkey::from_str(token_y).ok()?;
+    let (min_key, max_key) = if x < y { (x, y) } else { (y, x) };
+    let bin_step = METEORA_DLMM_BIN_STEPS.iter().copied().find(|step| {
+        let (pda, _) = Pubkey::find_program_address(
+            &[min_key.as_ref(), max_key.as_ref(), &step.to_le_bytes()],
+            &DLMM_PROGRAM,
+        );
+        pda == pair
+    });
+
+    BIN_STEP_CACHE
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .put(lb_pair.to_string(), bin_step.ok_or_else(Instant::now));
+    bin_step
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_swap_event_and_bin_step() {
+        let token_x = Pubkey::new_from_array([1u8; 32]);
+        let token_y = Pubkey::new_from_array([2u8; 32]);
+        let (lb_pair, _) = Pubkey::find_program_address(
+            &[token_x.as_ref(), token_y.as_ref(), &25u16.to_le_bytes()],
+            &DLMM_PROGRAM,
+        );
+
+        let mut data = Vec::with_capacity(SWAP_EVENT_LEN);
+        data.extend_from_slice(lb_pair.as_ref());
+        data.extend_from_slice(&[3u8; 32]);
+        data.extend_from_slice(&(-120i32).to_le_bytes());
+        data.extend_from_slice(&(-118i32).to_le_bytes());
+        data.extend_from_slice(&1_000_000u64.to_le_bytes());
+        data.extend_from_slice(&42_000u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(&2_500u64.to_le_bytes());
+        data.extend_from_slice(&125u64.to_le_bytes());
+        data.extend_from_slice(&25u128.to_le_bytes());
+        data.extend_from_slice(&0u64.to_le_bytes());
+
+        let event = MeteoraDLMMParser::decode_swap_event(&data).expect("swap event");
+        assert_eq!(event.lb_pair, lb_pair.to_string());
+        assert_eq!(event.start_bin_id, -120);
+        assert_eq!(event.end_bin_id, -118);
+        assert_eq!(event.amount_in, 1_000_000);
+        assert_eq!(event.amount_out, 42_000);
+        assert!(event.swap_for_y);
+        assert_eq!(event.fee, 2_500);
+        assert_eq!(event.fee_bps, 25);
+
+        let bin_step = derive_bin_step(
+            &lb_pair.to_string(),
+            &token_y.to_string(),
+            &token_x.to_string(),
+        );
+        assert_eq!(bin_step, Some(25));
+    }
+
+    #[test]
+    fn pda_misses_are_cached_with_expiry() {
+        let token_x = Pubkey::new_from_array([4u8; 32]).to_string();
+        let token_y = Pubkey::new_from_array([5u8; 32]).to_string();
+        let lb_pair = Pubkey::new_from_array([6u8; 32]).to_string();
+
+        assert_eq!(derive_bin_step(&lb_pair, &token_x, &token_y), None);
+        let cached = BIN_STEP_CACHE.lock().unwrap().get(&lb_pair).copied();
+        assert!(matches!(cached, Some(Err(checked_at)) if checked_at.elapsed() < BIN_STEP_MISS_TTL));
+    }
+
+    #[test]
+    fn bin_step_comes_from_lb_pair_account_when_available() {
+        use crate::config::ParseConfig;
+        use crate::state::ProtocolAccountStore;
+        use crate::state_decoders::LbPairAccount;
+        use crate::types::SolanaTransaction;
+
+        let store = Arc::new(ProtocolAccountStore::new());
+        store.insert(
+            "pair",
+            ProtocolAccount::MeteoraLbPair(LbPairAccount {
+                bin_step: 80,
+                ..Default::default()
+            }),
+        );
+        let adapter = TransactionAdapter::new(SolanaTransaction::default(), ParseConfig::default())
+            .with_protocol_accounts(store);
+        let parser = MeteoraDLMMParser::new(
+            Arc::new(adapter),
+            DexInfo::default(),
+            Arc::default(),
+            Vec::new(),
+        );
+
+        assert_eq!(parser.bin_step("pair", None, None), Some(80));
+        assert_eq!(parser.bin_step("other", None, None), None);
+    }
+}
@@ -1,7 +1,13 @@
+use std::sync::Arc;
+
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::core::transaction_utils::TransactionUtils;
 use crate::protocols::simple::LiquidityParser;
-use crate::types::{ClassifiedInstruction, PoolEvent, PoolEventType, TransferData, TransferMap};
+use crate::types::{
+    AccountKey, ClassifiedInstruction, LegDirection, PoolEvent, PoolEventType, TokenLeg, TransferData, TransferMap,
+};
+
+use super::util::build_token_leg;
 
 /// Базовый парсер ликвидности для Meteor
 pub trait MeteoraLiquidityParserBase: LiquidityParser {
@@ -38,16 +44,16 @@ pub trait MeteoraLiquidityParserBase: LiquidityParser {
 
 /// Базовая реализация для Meteor liquidity парсеров
 pub struct MeteoraLiquidityBase {
-    pub adapter: TransactionAdapter,
-    pub transfer_actions: TransferMap,
+    pub adapter: Arc<TransactionAdapter>,
+    pub transfer_actions: Arc<TransferMap>,
     pub classified_instructions: Vec<ClassifiedInstruction>,
     pub utils: TransactionUtils,
 }
 
 impl MeteoraLiquidityBase {
     pub fn new(
-        adapter: TransactionAdapter,
-        transfer_actions: TransferMap,
+        adapter: Arc<TransactionAdapter>,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Self {
         let utils = TransactionUtils::new(adapter.clone());
@@ -59,6 +65,66 @@ impl MeteoraLiquidityBase {
         }
     }
 
+    /// `mint` — запасной mint из аккаунтов, если трансфера по токену нет.
+    pub fn token_leg(
+        &self,
+        transfer: Option<&TransferData>,
+        mint: Option<String>,
+        direction: LegDirection,
+    ) -> TokenLeg {
+        let mint = transfer
+            .map(|t| t.info.mint.clone())
+            .or(mint.map(AccountKey::from))
+            .unwrap_or_default();
+        let decimals = if mint.is_empty() {
+            0
+        } else {
+            self.adapter.get_token_decimals(&mint)
+        };
+        build_token_leg(mint.to_string(), transfer, 0, decimals, direction)
+    }
+
+    /// Открытие/закрытие позиции: токены не двигаются, только аккаунт позиции.
+    /// `pool_index` — `None`, если пула нет среди аккаунтов инструкции.
+    pub fn position_event(
+        &self,
+        instruction: &crate::types::SolanaInstruction,
+        index: usize,
+        action: PoolEventType,
+        pool_index: Option<usize>,
+    ) -> PoolEvent {
+        let program_id = self.adapter.get_instruction_program_id(instruction);
+        let accounts = self.adapter.get_instruction_accounts(instruction);
+        let mut base = self.adapter.get_pool_event_base(action.clone(), program_id);
+        base.idx = index.to_string();
+
+        PoolEvent {
+            user: base.user,
+            event_type: action.into(),
+            program_id: base.program_id,
+            amm: base.amm,
+            slot: base.slot,
+            timestamp: base.timestamp,
+            signature: base.signature,
+            idx: base.idx,
+            signer: base.signer,
+            pool_id: pool_index
+                .and_then(|i| accounts.get(i))
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            config: None,
+            pool_lp_mint: None,
+            lp_amount: None,
+            lp_amount_raw: None,
+            tokens: Vec::new(),
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
+        }
+    }
+
     /// Получает transfers для инструкции
     #[inline]
     pub fn get_transfers_for_instruction(
@@ -80,8 +146,8 @@ impl MeteoraLiquidityBase {
     #[inline]
     pub fn get_instruction_by_discriminator(&self, discriminator: &[u8], slice: usize) -> Option<&ClassifiedInstruction> {
         self.classified_instructions.iter().find(|i| {
-            let data = crate::core::utils::get_instruction_data(&i.data);
-            data.len() >= slice && &data[..slice] == discriminator
+            crate::core::utils::get_instruction_data(&i.data)
+                .is_ok_and(|data| data.len() >= slice && &data[..slice] == discriminator)
         })
     }
 }
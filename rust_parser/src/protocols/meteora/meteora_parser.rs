@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use crate::core::constants::dex_program_names;
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::core::transaction_utils::TransactionUtils;
@@ -12,18 +14,18 @@ use super::constants::{
 };
 
 pub struct MeteoraParser {
-    adapter: TransactionAdapter,
+    adapter: Arc<TransactionAdapter>,
     dex_info: DexInfo,
-    transfer_actions: TransferMap,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
     utils: TransactionUtils,
 }
 
 impl MeteoraParser {
     pub fn new(
-        adapter: TransactionAdapter,
+        adapter: Arc<TransactionAdapter>,
         dex_info: DexInfo,
-        transfer_actions: TransferMap,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Self {
         let utils = TransactionUtils::new(adapter.clone());
@@ -99,8 +101,8 @@ impl MeteoraParser {
         let accounts = self.adapter.get_instruction_accounts(instruction);
         if accounts.len() > 5 {
             match program_id {
-                program_ids::METEORA_DAMM | program_ids::METEORA => accounts.get(0).cloned(),
-                program_ids::METEORA_DAMM_V2 => accounts.get(1).cloned(),
+                program_ids::METEORA_DAMM | program_ids::METEORA => accounts.get(0).map(ToString::to_string),
+                program_ids::METEORA_DAMM_V2 => accounts.get(1).map(ToString::to_string),
                 _ => None,
             }
         } else {
@@ -136,7 +138,7 @@ impl MeteoraParser {
         // ZERO-COPY: возвращаем ссылки на transfers из HashMap, фильтруем без клонирования
         self.transfer_actions.get(&key_buf).map(|v| {
             v.iter()
-                .filter(|t| matches!(t.transfer_type.as_str(), "transfer" | "transferChecked"))
+                .filter(|t| matches!(t.transfer_type.as_str(), "transfer" | "transferChecked" | "transferCheckedWithFee"))
                 .collect()
         }).unwrap_or_default()
     }
@@ -158,7 +160,9 @@ impl TradeParser for MeteoraParser {
             }
 
             // Проверяем, что это не liquidity событие
-            let instruction_data = crate::core::utils::get_instruction_data(&classified.data);
+            let Ok(instruction_data) = crate::core::utils::get_instruction_data(&classified.data) else {
+                continue;
+            };
 
             if !self.is_not_liquidity_event(&instruction_data) {
                 continue;
@@ -198,7 +202,7 @@ impl TradeParser for MeteoraParser {
             let mut trade = match self.utils.process_swap_data(
                 &transfers_vec,
                 &DexInfo {
-                    program_id: Some(program_id.clone()),
+                    program_id: Some(program_id.to_string()),
                     amm: self.dex_info.amm.clone()
                         .filter(|a| a != "Unknown DEX")
                         .or_else(|| {
@@ -216,6 +220,7 @@ impl TradeParser for MeteoraParser {
                 trade.pool = vec![pool];
             }
 
+            let trade = self.utils.attach_pool_reserves(trade);
             // Прикрепляем token transfer info
             let final_trade = self.utils.attach_token_transfer_info(trade, &self.transfer_actions);
             trades.push(final_trade);
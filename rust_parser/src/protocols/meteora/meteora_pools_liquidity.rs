@@ -1,10 +1,14 @@
+use std::sync::Arc;
+
 use crate::core::constants::TOKENS;
 use crate::protocols::simple::LiquidityParser;
-use crate::types::{ClassifiedInstruction, PoolEvent, PoolEventType, TradeType, TransferData, TransferMap};
+use crate::types::{
+    ClassifiedInstruction, LegDirection, PoolEvent, PoolEventType, TradeType, TransferData, TransferMap,
+};
 
 use super::constants::discriminators::meteora_damm_u64;
 use super::meteora_liquidity_base::MeteoraLiquidityBase;
-use super::util::{convert_to_ui_amount, get_lp_transfers};
+use super::util::{build_token_leg, convert_to_ui_amount, get_lp_transfers};
 use crate::core::transaction_adapter::TransactionAdapter;
 
 pub struct MeteoraPoolsLiquidityParser {
@@ -13,8 +17,8 @@ pub struct MeteoraPoolsLiquidityParser {
 
 impl MeteoraPoolsLiquidityParser {
     pub fn new(
-        adapter: TransactionAdapter,
-        transfer_actions: TransferMap,
+        adapter: Arc<TransactionAdapter>,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Self {
         Self {
@@ -51,7 +55,7 @@ impl MeteoraPoolsLiquidityParser {
         outer_index: usize,
         inner_index: Option<usize>,
     ) -> Option<PoolEvent> {
-        let data = crate::core::utils::get_instruction_data(instruction);
+        let data = crate::core::utils::get_instruction_data(instruction).ok()?;
         let action = self.get_pool_action(&data)?;
 
         let mut transfers = self.base.get_transfers_for_instruction(program_id, outer_index, inner_index);
@@ -59,7 +63,22 @@ impl MeteoraPoolsLiquidityParser {
             transfers = self.base.get_transfers_for_instruction(program_id, outer_index, Some(inner_index.unwrap_or(0)));
         }
 
-        let transfers_owned: Vec<TransferData> = transfers.iter().map(|t| (*t).clone()).collect();
+        let mut transfers_owned: Vec<TransferData> = transfers.iter().map(|t| (*t).clone()).collect();
+
+        // Фактический mint/burn LP: в RPC-транзакциях его нет в transfer actions, а аргумент
+        // addImbalanceLiquidity — лишь минимум
+        let (is_add, lp_kind, lp_mint_index) = match action {
+            PoolEventType::Create => (true, "mintTo", 2),
+            PoolEventType::Add => (true, "mintTo", 1),
+            _ => (false, "burn", 1),
+        };
+        if !transfers_owned.iter().any(|t| t.transfer_type == lp_kind) {
+            let accounts = self.base.adapter.get_instruction_accounts(instruction);
+            let lp_mint = accounts.get(lp_mint_index).map(|mint| mint.as_ref());
+            if let Some(lp_token) = self.base.adapter.find_lp_token_action(outer_index, inner_index, is_add, lp_mint) {
+                transfers_owned.push(lp_token);
+            }
+        }
 
         match action {
             PoolEventType::Create => {
@@ -69,6 +88,8 @@ impl MeteoraPoolsLiquidityParser {
             PoolEventType::Remove => {
                 Some(self.parse_remove_liquidity_event(instruction, outer_index, &data, &transfers_owned))
             }
+            // DAMM v1 — пул с LP-токеном, без позиций
+            _ => None,
         }
     }
 
@@ -117,39 +138,34 @@ impl MeteoraPoolsLiquidityParser {
             signature: base.signature,
             idx: base.idx,
             signer: base.signer,
-            pool_id: accounts.get(0)?.clone(),
+            pool_id: accounts.get(0)?.to_string(),
             config: None,
-            pool_lp_mint: accounts.get(2).cloned(),
-            token0_mint: Some(token0_mint),
-            token0_amount: Some(
-                token0.as_ref()
-                    .and_then(|t| t.info.token_amount.ui_amount)
-                    .unwrap_or_else(|| convert_to_ui_amount(token0_amount_raw as u128, token0_decimals)),
-            ),
-            token0_amount_raw: Some(
-                token0.as_ref()
-                    .map(|t| t.info.token_amount.amount.clone())
-                    .unwrap_or_else(|| token0_amount_raw.to_string()),
-            ),
-            token0_balance_change: None,
-            token0_decimals: Some(token0_decimals),
-            token1_mint: Some(token1_mint),
-            token1_amount: Some(
-                token1.as_ref()
-                    .and_then(|t| t.info.token_amount.ui_amount)
-                    .unwrap_or_else(|| convert_to_ui_amount(token1_amount_raw as u128, token1_decimals)),
-            ),
-            token1_amount_raw: Some(
-                token1.as_ref()
-                    .map(|t| t.info.token_amount.amount.clone())
-                    .unwrap_or_else(|| token1_amount_raw.to_string()),
-            ),
-            token1_balance_change: None,
-            token1_decimals: Some(token1_decimals),
+            pool_lp_mint: accounts.get(2).map(ToString::to_string),
             lp_amount: lp_token
                 .and_then(|t| t.info.token_amount.ui_amount)
                 .or(Some(0.0)),
             lp_amount_raw: lp_token.map(|t| t.info.token_amount.amount.clone()),
+            tokens: vec![
+                build_token_leg(
+                    token0_mint.to_string(),
+                    token0.as_ref(),
+                    token0_amount_raw,
+                    token0_decimals,
+                    LegDirection::In,
+                ),
+                build_token_leg(
+                    token1_mint.to_string(),
+                    token1.as_ref(),
+                    token1_amount_raw,
+                    token1_decimals,
+                    LegDirection::In,
+                ),
+            ],
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
         })
     }
 
@@ -213,35 +229,9 @@ impl MeteoraPoolsLiquidityParser {
             signature: base.signature,
             idx: base.idx,
             signer: base.signer,
-            pool_id: accounts.get(0).cloned().unwrap_or_default(),
+            pool_id: accounts.get(0).map(ToString::to_string).unwrap_or_default(),
             config: None,
-            pool_lp_mint: Some(lp_mint),
-            token0_mint,
-            token0_amount: Some(
-                token0.as_ref()
-                    .and_then(|t| t.info.token_amount.ui_amount)
-                    .unwrap_or_else(|| convert_to_ui_amount(token0_amount_raw as u128, token0_decimals)),
-            ),
-            token0_amount_raw: Some(
-                token0.as_ref()
-                    .map(|t| t.info.token_amount.amount.clone())
-                    .unwrap_or_else(|| token0_amount_raw.to_string()),
-            ),
-            token0_balance_change: None,
-            token0_decimals: Some(token0_decimals),
-            token1_mint,
-            token1_amount: Some(
-                token1.as_ref()
-                    .and_then(|t| t.info.token_amount.ui_amount)
-                    .unwrap_or_else(|| convert_to_ui_amount(token1_amount_raw as u128, token1_decimals)),
-            ),
-            token1_amount_raw: Some(
-                token1.as_ref()
-                    .map(|t| t.info.token_amount.amount.clone())
-                    .unwrap_or_else(|| token1_amount_raw.to_string()),
-            ),
-            token1_balance_change: None,
-            token1_decimals: Some(token1_decimals),
+            pool_lp_mint: Some(lp_mint.to_string()),
             lp_amount: Some(
                 lp_token
                     .and_then(|t| t.info.token_amount.ui_amount)
@@ -252,6 +242,27 @@ impl MeteoraPoolsLiquidityParser {
                     .map(|t| t.info.token_amount.amount.clone())
                     .unwrap_or_else(|| lp_amount_raw.to_string()),
             ),
+            tokens: vec![
+                build_token_leg(
+                    token0_mint.unwrap_or_default().to_string(),
+                    token0.as_ref(),
+                    token0_amount_raw,
+                    token0_decimals,
+                    LegDirection::In,
+                ),
+                build_token_leg(
+                    token1_mint.unwrap_or_default().to_string(),
+                    token1.as_ref(),
+                    token1_amount_raw,
+                    token1_decimals,
+                    LegDirection::In,
+                ),
+            ],
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
         }
     }
 
@@ -315,35 +326,9 @@ impl MeteoraPoolsLiquidityParser {
             signature: base.signature,
             idx: base.idx,
             signer: base.signer,
-            pool_id: accounts.get(0).cloned().unwrap_or_default(),
+            pool_id: accounts.get(0).map(ToString::to_string).unwrap_or_default(),
             config: None,
-            pool_lp_mint: Some(lp_mint),
-            token0_mint,
-            token0_amount: Some(
-                token0.as_ref()
-                    .and_then(|t| t.info.token_amount.ui_amount)
-                    .unwrap_or_else(|| convert_to_ui_amount(token0_amount_raw as u128, token0_decimals)),
-            ),
-            token0_amount_raw: Some(
-                token0.as_ref()
-                    .map(|t| t.info.token_amount.amount.clone())
-                    .unwrap_or_else(|| token0_amount_raw.to_string()),
-            ),
-            token0_balance_change: None,
-            token0_decimals: Some(token0_decimals),
-            token1_mint,
-            token1_amount: Some(
-                token1.as_ref()
-                    .and_then(|t| t.info.token_amount.ui_amount)
-                    .unwrap_or_else(|| convert_to_ui_amount(token1_amount_raw as u128, token1_decimals)),
-            ),
-            token1_amount_raw: Some(
-                token1.as_ref()
-                    .map(|t| t.info.token_amount.amount.clone())
-                    .unwrap_or_else(|| token1_amount_raw.to_string()),
-            ),
-            token1_balance_change: None,
-            token1_decimals: Some(token1_decimals),
+            pool_lp_mint: Some(lp_mint.to_string()),
             lp_amount: Some(
                 lp_token
                     .and_then(|t| t.info.token_amount.ui_amount)
@@ -354,6 +339,27 @@ impl MeteoraPoolsLiquidityParser {
                     .map(|t| t.info.token_amount.amount.clone())
                     .unwrap_or_else(|| lp_amount_raw.to_string()),
             ),
+            tokens: vec![
+                build_token_leg(
+                    token0_mint.unwrap_or_default().to_string(),
+                    token0.as_ref(),
+                    token0_amount_raw,
+                    token0_decimals,
+                    LegDirection::Out,
+                ),
+                build_token_leg(
+                    token1_mint.unwrap_or_default().to_string(),
+                    token1.as_ref(),
+                    token1_amount_raw,
+                    token1_decimals,
+                    LegDirection::Out,
+                ),
+            ],
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
         }
     }
 }
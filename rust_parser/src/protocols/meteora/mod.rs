@@ -1,13 +1,17 @@
 pub mod constants;
 pub mod meteora_damm_v2_liquidity;
+pub mod meteora_damm_v2_parser;
 pub mod meteora_dbc_event_parser;
 pub mod meteora_dbc_parser;
 pub mod meteora_dlmm_liquidity;
+pub mod meteora_dlmm_parser;
 pub mod meteora_liquidity_base;
 pub mod meteora_parser;
 pub mod meteora_pools_liquidity;
 pub mod util;
 
+use std::sync::Arc;
+
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::protocols::simple::{LiquidityParser, MemeEventParser, TradeParser};
 use crate::types::{ClassifiedInstruction, DexInfo, TransferMap};
@@ -15,14 +19,16 @@ use crate::types::{ClassifiedInstruction, DexInfo, TransferMap};
 use meteora_dbc_event_parser::MeteoraDBCEventParser;
 use meteora_dbc_parser::MeteoraDBCParser;
 use meteora_damm_v2_liquidity::MeteoraDAMMV2LiquidityParser;
+use meteora_damm_v2_parser::MeteoraDAMMV2Parser;
 use meteora_dlmm_liquidity::MeteoraDLMMLiquidityParser;
+use meteora_dlmm_parser::MeteoraDLMMParser;
 use meteora_parser::MeteoraParser;
 use meteora_pools_liquidity::MeteoraPoolsLiquidityParser;
 
 pub fn build_meteora_trade_parser(
-    adapter: TransactionAdapter,
+    adapter: Arc<TransactionAdapter>,
     dex_info: DexInfo,
-    transfer_actions: TransferMap,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
 ) -> Box<dyn TradeParser> {
     Box::new(MeteoraParser::new(
@@ -34,9 +40,9 @@ pub fn build_meteora_trade_parser(
 }
 
 pub fn build_meteora_dbc_trade_parser(
-    adapter: TransactionAdapter,
+    adapter: Arc<TransactionAdapter>,
     dex_info: DexInfo,
-    transfer_actions: TransferMap,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
 ) -> Box<dyn TradeParser> {
     Box::new(MeteoraDBCParser::new(
@@ -47,9 +53,37 @@ pub fn build_meteora_dbc_trade_parser(
     ))
 }
 
+pub fn build_meteora_dlmm_trade_parser(
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+) -> Box<dyn TradeParser> {
+    Box::new(MeteoraDLMMParser::new(
+        adapter,
+        dex_info,
+        transfer_actions,
+        classified_instructions,
+    ))
+}
+
+pub fn build_meteora_damm_v2_trade_parser(
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+) -> Box<dyn TradeParser> {
+    Box::new(MeteoraDAMMV2Parser::new(
+        adapter,
+        dex_info,
+        transfer_actions,
+        classified_instructions,
+    ))
+}
+
 pub fn build_meteora_dlmm_liquidity_parser(
-    adapter: TransactionAdapter,
-    transfer_actions: TransferMap,
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
 ) -> Box<dyn LiquidityParser> {
     Box::new(MeteoraDLMMLiquidityParser::new(
@@ -60,8 +94,8 @@ pub fn build_meteora_dlmm_liquidity_parser(
 }
 
 pub fn build_meteora_pools_liquidity_parser(
-    adapter: TransactionAdapter,
-    transfer_actions: TransferMap,
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
 ) -> Box<dyn LiquidityParser> {
     Box::new(MeteoraPoolsLiquidityParser::new(
@@ -72,8 +106,8 @@ pub fn build_meteora_pools_liquidity_parser(
 }
 
 pub fn build_meteora_damm_v2_liquidity_parser(
-    adapter: TransactionAdapter,
-    transfer_actions: TransferMap,
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
 ) -> Box<dyn LiquidityParser> {
     Box::new(MeteoraDAMMV2LiquidityParser::new(
@@ -84,8 +118,8 @@ pub fn build_meteora_damm_v2_liquidity_parser(
 }
 
 pub fn build_meteora_dbc_meme_parser(
-    adapter: TransactionAdapter,
-    transfer_actions: TransferMap,
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
 ) -> Box<dyn MemeEventParser> {
     Box::new(MeteoraDBCEventParser::new(adapter, transfer_actions))
 }
@@ -1,5 +1,5 @@
 use crate::core::constants::TOKENS;
-use crate::types::TransferData;
+use crate::types::{LegDirection, TokenLeg, TransferData};
 
 /// Получает LP transfers (токены для ликвидности)
 /// Аналог getLPTransfers из TypeScript
@@ -55,3 +55,27 @@ pub fn convert_to_ui_amount(amount: u128, decimals: u8) -> f64 {
     (amount as f64) / scale
 }
 
+
+/// Нога pool-события: сумма из трансфера, если он есть, иначе raw из данных инструкции.
+#[inline]
+pub fn build_token_leg(
+    mint: String,
+    transfer: Option<&TransferData>,
+    amount_raw: u64,
+    decimals: u8,
+    direction: LegDirection,
+) -> TokenLeg {
+    TokenLeg {
+        mint,
+        amount: transfer
+            .and_then(|t| t.info.token_amount.ui_amount)
+            .unwrap_or_else(|| convert_to_ui_amount(amount_raw as u128, decimals)),
+        amount_raw: transfer
+            .map(|t| t.info.token_amount.amount.clone())
+            .unwrap_or_else(|| amount_raw.to_string()),
+        decimals,
+        direction,
+        balance_change: None,
+        symbol: None,
+    }
+}
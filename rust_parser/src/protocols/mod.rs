@@ -1,3 +1,12 @@
+pub mod jupiter;
+pub mod lifinity;
 pub mod meteora;
+pub mod moonshot;
+pub mod openbook_v2;
+pub mod orca;
+pub mod phoenix;
 pub mod pumpfun;
+pub mod raydium;
+pub mod raydium_launchlab;
 pub mod simple;
+pub mod stable;
@@ -0,0 +1,15 @@
+pub const MOONSHOT_PROGRAM_ID: &str = "MoonCVVNZFSYkqNXP6bxHLPL6QQJiMagDL3qcqUQTrG";
+pub const MOONSHOT_PROGRAM_NAME: &str = "Moonshot";
+
+/// Токены Moonshot минтятся с 9 decimals и supply 1_000_000_000.
+pub const MOONSHOT_TOKEN_DECIMALS: u8 = 9;
+pub const MOONSHOT_TOKEN_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000_000;
+
+pub mod discriminators {
+    pub mod moonshot_instructions {
+        pub const TOKEN_MINT: [u8; 8] = [3, 44, 164, 184, 123, 13, 245, 179];
+        pub const BUY: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+        pub const SELL: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+        pub const MIGRATE_FUNDS: [u8; 8] = [42, 229, 10, 231, 189, 62, 193, 174];
+    }
+}
@@ -0,0 +1,32 @@
+pub mod constants;
+pub mod moonshot_instruction_parser;
+pub mod moonshot_parser;
+
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::protocols::simple::{MemeEventParser, TradeParser};
+use crate::types::{ClassifiedInstruction, DexInfo, TransferMap};
+
+use moonshot_parser::{MoonshotMemeParser, MoonshotParser};
+
+pub fn build_moonshot_trade_parser(
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+) -> Box<dyn TradeParser> {
+    Box::new(MoonshotParser::new(
+        adapter,
+        dex_info,
+        transfer_actions,
+        classified_instructions,
+    ))
+}
+
+pub fn build_moonshot_meme_parser(
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
+) -> Box<dyn MemeEventParser> {
+    Box::new(MoonshotMemeParser::new(adapter, transfer_actions))
+}
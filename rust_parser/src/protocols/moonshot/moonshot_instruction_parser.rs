@@ -0,0 +1,277 @@
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::protocols::pumpfun::binary_reader::{BinaryReaderError, BinaryReaderRef};
+use crate::protocols::pumpfun::constants::SOL_MINT;
+use crate::protocols::pumpfun::util::{build_token_info, get_instruction_data, sort_by_idx};
+use crate::types::{AccountKey, ClassifiedInstruction, MemeEvent, TradeType};
+
+use super::constants::{
+    discriminators::moonshot_instructions, MOONSHOT_PROGRAM_NAME, MOONSHOT_TOKEN_DECIMALS,
+    MOONSHOT_TOKEN_TOTAL_SUPPLY,
+};
+
+/// `TradeParams` из данных buy/sell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoonshotTradeArgs {
+    pub token_amount: u64,
+    pub collateral_amount: u64,
+    pub fixed_side: u8,
+    pub slippage_bps: u64,
+}
+
+/// Moonshot не пишет event-логов: события восстанавливаются из инструкций,
+/// фактические суммы — из изменения балансов curve-аккаунтов.
+pub struct MoonshotInstructionParser;
+
+impl MoonshotInstructionParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn parse_instructions(
+        &self,
+        adapter: &TransactionAdapter,
+        instructions: &[ClassifiedInstruction],
+    ) -> Vec<MemeEvent> {
+        let mut events = Vec::with_capacity(instructions.len());
+        let signature = adapter.signature().to_string();
+        let slot = adapter.slot();
+        let timestamp = adapter.block_time();
+
+        for classified in instructions {
+            let Ok(data) = get_instruction_data(&classified.data) else {
+                continue;
+            };
+            if data.len() < 8 {
+                continue;
+            }
+
+            let accounts = adapter.get_instruction_accounts(&classified.data);
+            let discriminator = &data[..8];
+            let payload = &data[8..];
+
+            let event = if discriminator == moonshot_instructions::BUY {
+                self.decode_trade(adapter, accounts, payload, true).ok()
+            } else if discriminator == moonshot_instructions::SELL {
+                self.decode_trade(adapter, accounts, payload, false).ok()
+            } else if discriminator == moonshot_instructions::TOKEN_MINT {
+                self.decode_create(accounts, payload).ok()
+            } else if discriminator == moonshot_instructions::MIGRATE_FUNDS {
+                Some(self.build_migrate_event(accounts))
+            } else {
+                None
+            };
+
+            if let Some(mut event) = event {
+                event.protocol = Some(MOONSHOT_PROGRAM_NAME.to_string());
+                event.signature = signature.clone();
+                event.slot = slot;
+                event.timestamp = timestamp;
+                event.idx = format!(
+                    "{}-{}",
+                    classified.outer_index,
+                    classified.inner_index.unwrap_or(0)
+                );
+                events.push(event);
+            }
+        }
+
+        sort_by_idx(events)
+    }
+
+    fn decode_trade(
+        &self,
+        adapter: &TransactionAdapter,
+        accounts: &[AccountKey],
+        data: &[u8],
+        is_buy: bool,
+    ) -> Result<MemeEvent, BinaryReaderError> {
+        let args = decode_trade_args(data)?;
+
+        // sender, sender_token_account, curve_account, curve_token_account, dex_fee, helio_fee, mint
+        let user = accounts.first().cloned().unwrap_or_default();
+        let curve_account = accounts.get(2).cloned();
+        let mint = accounts.get(6).cloned().unwrap_or_default();
+        let decimals = adapter
+            .token_decimals(&mint)
+            .unwrap_or(MOONSHOT_TOKEN_DECIMALS);
+
+        let token_amount = accounts
+            .get(3)
+            .and_then(|account| token_balance_delta(adapter, account))
+            .unwrap_or(args.token_amount);
+        let collateral_amount = curve_account
+            .as_ref()
+            .and_then(|account| sol_balance_delta(adapter, account))
+            .unwrap_or(args.collateral_amount);
+
+        let sol = build_token_info(SOL_MINT, collateral_amount as u128, 9, None);
+        let token = build_token_info(&mint, token_amount as u128, decimals, None);
+        let (event_type, input_token, output_token) = if is_buy {
+            (TradeType::Buy, sol, token)
+        } else {
+            (TradeType::Sell, token, sol)
+        };
+
+        Ok(MemeEvent {
+            event_type,
+            user: user.to_string(),
+            base_mint: mint.to_string(),
+            quote_mint: SOL_MINT.to_string(),
+            input_token: Some(input_token),
+            output_token: Some(output_token),
+            bonding_curve: curve_account.clone().map(String::from),
+            pool: curve_account.map(String::from),
+            ..Default::default()
+        })
+    }
+
+    fn decode_create(
+        &self,
+        accounts: &[AccountKey],
+        data: &[u8],
+    ) -> Result<MemeEvent, BinaryReaderError> {
+        // TokenMintParams { name, symbol, uri, decimals, collateral_currency, amount, .. }
+        let mut reader = BinaryReaderRef::new_ref(data);
+        let name = reader.read_string()?;
+        let symbol = reader.read_string()?;
+        let uri = reader.read_string()?;
+        let decimals = reader.read_u8()?;
+        let _collateral_currency = reader.read_u8()?;
+        let total_supply = reader.read_u64()?;
+
+        // sender, backend_authority, curve_account, mint, mint_metadata, curve_token_account, config
+        Ok(MemeEvent {
+            event_type: TradeType::Create,
+            user: accounts.first().map(ToString::to_string).unwrap_or_default(),
+            base_mint: accounts.get(3).map(ToString::to_string).unwrap_or_default(),
+            quote_mint: SOL_MINT.to_string(),
+            name: Some(name),
+            symbol: Some(symbol),
+            uri: Some(uri),
+            decimals: Some(decimals),
+            total_supply: Some(total_supply),
+            creator: accounts.first().map(ToString::to_string),
+            platform_config: accounts.get(6).map(ToString::to_string),
+            bonding_curve: accounts.get(2).map(ToString::to_string),
+            ..Default::default()
+        })
+    }
+
+    fn build_migrate_event(&self, accounts: &[AccountKey]) -> MemeEvent {
+        // backend_authority, migration_authority, curve_account, curve_token_account,
+        // migration_authority_token_account, mint
+        MemeEvent {
+            event_type: TradeType::Migrate,
+            user: accounts.get(1).map(ToString::to_string).unwrap_or_default(),
+            base_mint: accounts.get(5).map(ToString::to_string).unwrap_or_default(),
+            quote_mint: SOL_MINT.to_string(),
+            decimals: Some(MOONSHOT_TOKEN_DECIMALS),
+            total_supply: Some(MOONSHOT_TOKEN_TOTAL_SUPPLY),
+            bonding_curve: accounts.get(2).map(ToString::to_string),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for MoonshotInstructionParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn decode_trade_args(data: &[u8]) -> Result<MoonshotTradeArgs, BinaryReaderError> {
+    let mut reader = BinaryReaderRef::new_ref(data);
+    Ok(MoonshotTradeArgs {
+        token_amount: reader.read_u64()?,
+        collateral_amount: reader.read_u64()?,
+        fixed_side: reader.read_u8()?,
+        slippage_bps: reader.read_u64()?,
+    })
+}
+
+/// `None`, если баланс не изменился. Token account кривой всегда есть в meta:
+/// его отсутствие репортится как `MissingTokenBalance`.
+fn token_balance_delta(adapter: &TransactionAdapter, account: &str) -> Option<u64> {
+    match adapter.token_balance_change(account) {
+        Ok(change) => u64::try_from(change.change.unsigned_abs())
+            .ok()
+            .filter(|delta| *delta > 0),
+        Err(err) => {
+            adapter.report_error(err);
+            None
+        }
+    }
+}
+
+fn sol_balance_delta(adapter: &TransactionAdapter, account: &str) -> Option<u64> {
+    let key = [account.to_string()];
+    let post = adapter.get_account_balance(&key).pop().flatten()?;
+    let pre = adapter.get_account_pre_balance(&key).pop().flatten()?;
+    let post: u64 = post.amount.parse().ok()?;
+    let pre: u64 = pre.amount.parse().ok()?;
+    Some(post.abs_diff(pre)).filter(|delta| *delta > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_trade_params() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&1_500_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(&250_000_000u64.to_le_bytes());
+        data.push(1);
+        data.extend_from_slice(&100u64.to_le_bytes());
+
+        let args = decode_trade_args(&data).expect("trade args");
+        assert_eq!(
+            args,
+            MoonshotTradeArgs {
+                token_amount: 1_500_000_000_000,
+                collateral_amount: 250_000_000,
+                fixed_side: 1,
+                slippage_bps: 100,
+            }
+        );
+        assert!(decode_trade_args(&data[..16]).is_err());
+    }
+
+    #[test]
+    fn reports_missing_curve_token_balance() {
+        use crate::config::ParseConfig;
+        use crate::core::error::ParserError;
+        use crate::types::{SolanaInstruction, SolanaTransaction};
+        use base64_simd::STANDARD;
+
+        let mut data = moonshot_instructions::SELL.to_vec();
+        data.extend_from_slice(&2_000_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(&90_000_000u64.to_le_bytes());
+        data.push(0);
+        data.extend_from_slice(&100u64.to_le_bytes());
+        let accounts = ["user", "user-token", "curve", "curve-token", "dex-fee", "helio-fee", "MOON"];
+        let instruction = SolanaInstruction {
+            program_id: crate::core::constants::dex_programs::MOONSHOT.into(),
+            accounts: accounts.map(Into::into).to_vec(),
+            data: STANDARD.encode_to_string(&data),
+        };
+        let tx = SolanaTransaction {
+            signers: vec!["user".to_string()],
+            instructions: vec![instruction],
+            ..Default::default()
+        };
+        let adapter = TransactionAdapter::new(tx, ParseConfig::default());
+        let classified = crate::core::instruction_classifier::InstructionClassifier::new(&adapter)
+            .get_instructions(crate::core::constants::dex_programs::MOONSHOT)
+            .to_vec();
+
+        // без balances — суммы из аргументов, а ошибка уходит в адаптер
+        let events = MoonshotInstructionParser::new().parse_instructions(&adapter, &classified);
+        assert_eq!(events.len(), 1);
+        let errors = adapter.take_errors();
+        assert!(matches!(
+            errors.as_slice(),
+            [ParserError::MissingTokenBalance { account }] if account == "curve-token"
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+use std::sync::Arc;
+
+use crate::core::instruction_classifier::InstructionClassifier;
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::protocols::pumpfun::util::attach_token_transfers;
+use crate::protocols::simple::{MemeEventParser, TradeParser};
+use crate::types::{ClassifiedInstruction, DexInfo, MemeEvent, TradeInfo, TradeType, TransferMap};
+
+use super::constants::{MOONSHOT_PROGRAM_ID, MOONSHOT_PROGRAM_NAME};
+use super::moonshot_instruction_parser::MoonshotInstructionParser;
+
+pub struct MoonshotParser {
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+}
+
+impl MoonshotParser {
+    pub fn new(
+        adapter: Arc<TransactionAdapter>,
+        dex_info: DexInfo,
+        transfer_actions: Arc<TransferMap>,
+        classified_instructions: Vec<ClassifiedInstruction>,
+    ) -> Self {
+        Self {
+            adapter,
+            dex_info,
+            transfer_actions,
+            classified_instructions,
+        }
+    }
+
+    fn create_trade_info(&self, event: &MemeEvent) -> TradeInfo {
+        TradeInfo {
+            trade_type: event.event_type.clone(),
+            pool: event.pool.as_ref().map(|p| vec![p.clone()]).unwrap_or_default(),
+            input_token: event.input_token.clone().unwrap_or_default(),
+            output_token: event.output_token.clone().unwrap_or_default(),
+            user: Some(event.user.clone()),
+            program_id: Some(
+                self.dex_info
+                    .program_id
+                    .clone()
+                    .unwrap_or_else(|| MOONSHOT_PROGRAM_ID.to_string()),
+            ),
+            amm: Some(MOONSHOT_PROGRAM_NAME.to_string()),
+            amms: Some(vec![MOONSHOT_PROGRAM_NAME.to_string()]),
+            route: self.dex_info.route.clone(),
+            slot: event.slot,
+            timestamp: event.timestamp,
+            signature: event.signature.clone(),
+            idx: event.idx.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl TradeParser for MoonshotParser {
+    fn process_trades(&mut self) -> Vec<TradeInfo> {
+        MoonshotInstructionParser::new()
+            .parse_instructions(&self.adapter, &self.classified_instructions)
+            .into_iter()
+            .filter(|event| matches!(event.event_type, TradeType::Buy | TradeType::Sell))
+            .map(|event| {
+                let trade = self.create_trade_info(&event);
+                attach_token_transfers(&self.adapter, trade, &self.transfer_actions)
+            })
+            .collect()
+    }
+}
+
+pub struct MoonshotMemeParser {
+    adapter: Arc<TransactionAdapter>,
+}
+
+impl MoonshotMemeParser {
+    pub fn new(adapter: Arc<TransactionAdapter>, _transfer_actions: Arc<TransferMap>) -> Self {
+        Self { adapter }
+    }
+}
+
+impl MemeEventParser for MoonshotMemeParser {
+    fn process_events(&mut self) -> Vec<MemeEvent> {
+        let classifier = InstructionClassifier::new(&self.adapter);
+        let instructions = classifier.get_instructions(MOONSHOT_PROGRAM_ID);
+        MoonshotInstructionParser::new().parse_instructions(&self.adapter, instructions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::constants::{dex_programs, TOKENS};
+    use crate::core::test_utils::sample_transaction;
+    use crate::types::{BalanceChange, SolanaInstruction, TokenAmount};
+    use crate::DexParser;
+
+    #[test]
+    fn parses_moonshot_sell_from_curve_balances() {
+        use crate::protocols::moonshot::constants::discriminators::moonshot_instructions;
+        use crate::types::{TokenBalance, TradeType};
+        use base64_simd::STANDARD;
+
+        let mut data = moonshot_instructions::SELL.to_vec();
+        data.extend_from_slice(&2_000_000_000_000u64.to_le_bytes()); // token_amount
+        data.extend_from_slice(&90_000_000u64.to_le_bytes()); // collateral_amount (min out)
+        data.push(0); // fixed_side: ExactIn
+        data.extend_from_slice(&100u64.to_le_bytes()); // slippage_bps
+
+        let accounts = [
+            "user",
+            "user-token",
+            "curve",
+            "curve-token",
+            "dex-fee",
+            "helio-fee",
+            "MOON",
+        ];
+        let balance = |amount: &str| TokenBalance {
+            account: "curve-token".to_string(),
+            mint: "MOON".to_string(),
+            owner: Some("curve".to_string()),
+            ui_token_amount: TokenAmount::new(amount, 9, None),
+        };
+        let mut tx = sample_transaction();
+        tx.transfers.clear();
+        tx.instructions = vec![SolanaInstruction {
+            program_id: dex_programs::MOONSHOT.into(),
+            accounts: accounts.map(Into::into).to_vec(),
+            data: STANDARD.encode_to_string(&data),
+        }];
+        tx.pre_token_balances = vec![balance("500000000000000")];
+        tx.post_token_balances = vec![balance("502000000000000")];
+        tx.meta.sol_balance_changes.insert(
+            "curve".to_string(),
+            BalanceChange {
+                pre: 30_000_000_000,
+                post: 29_900_000_000,
+                change: -100_000_000,
+            },
+        );
+
+        let result = DexParser::new().parse_all(tx, None);
+        assert_eq!(result.trades.len(), 1);
+        let trade = &result.trades[0];
+        assert_eq!(trade.trade_type, TradeType::Sell);
+        assert_eq!(trade.input_token.mint, "MOON");
+        assert_eq!(trade.input_token.amount, 2_000.0);
+        assert_eq!(trade.output_token.mint, TOKENS.SOL);
+        // фактический выход по балансу кривой, а не минимальный из аргументов
+        assert_eq!(trade.output_token.amount_raw, "100000000");
+        assert_eq!(trade.amm.as_deref(), Some("Moonshot"));
+        assert_eq!(result.meme_events.len(), 1);
+        assert_eq!(
+            result.meme_events[0].bonding_curve.as_deref(),
+            Some("curve")
+        );
+    }
+}
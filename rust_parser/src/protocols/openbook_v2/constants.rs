@@ -0,0 +1,15 @@
+pub const OPENBOOK_V2_PROGRAM_ID: &str = "opnb2LAfJYbRMAHHvqjCwQxanZn7ReEHp1k81EohpZb";
+pub const OPENBOOK_V2_PROGRAM_NAME: &str = "OpenBookV2";
+
+pub mod discriminators {
+    pub mod openbook_instructions {
+        pub const PLACE_ORDER: [u8; 8] = [51, 194, 155, 175, 109, 130, 96, 106];
+        pub const PLACE_ORDER_PEGGED: [u8; 8] = [141, 185, 251, 63, 74, 85, 210, 145];
+        pub const PLACE_TAKE_ORDER: [u8; 8] = [3, 44, 71, 3, 26, 199, 203, 85];
+    }
+
+    /// Anchor `emit!`: `Program data: base64(discriminator || borsh)`.
+    pub mod openbook_events {
+        pub const FILL_LOG: [u8; 8] = [150, 23, 41, 148, 152, 162, 215, 64];
+    }
+}
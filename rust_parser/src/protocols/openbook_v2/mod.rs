@@ -0,0 +1,25 @@
+pub mod constants;
+pub mod openbook_v2_event_parser;
+pub mod openbook_v2_parser;
+
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::protocols::simple::TradeParser;
+use crate::types::{ClassifiedInstruction, DexInfo, TransferMap};
+
+use openbook_v2_parser::OpenbookV2Parser;
+
+pub fn build_openbook_v2_trade_parser(
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+) -> Box<dyn TradeParser> {
+    Box::new(OpenbookV2Parser::new(
+        adapter,
+        dex_info,
+        transfer_actions,
+        classified_instructions,
+    ))
+}
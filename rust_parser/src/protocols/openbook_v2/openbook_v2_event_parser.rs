@@ -0,0 +1,116 @@
+use crate::core::anchor_events::AnchorEventDecoder;
+use crate::protocols::pumpfun::binary_reader::{BinaryReaderError, BinaryReaderRef};
+
+use super::constants::{discriminators::openbook_events, OPENBOOK_V2_PROGRAM_ID};
+
+/// `FillLog` — эмитится при каждом матче тейкера с мейкером.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenbookFillLog {
+    pub market: String,
+    /// 0 — Bid, 1 — Ask (сторона тейкера).
+    pub taker_side: u8,
+    pub maker_slot: u8,
+    /// Ордер мейкера исполнен полностью и удалён из книги.
+    pub maker_out: bool,
+    pub timestamp: u64,
+    pub seq_num: u64,
+    /// Open-orders аккаунт мейкера.
+    pub maker: String,
+    pub maker_client_order_id: u64,
+    pub maker_fee: u64,
+    pub maker_timestamp: u64,
+    /// Open-orders аккаунт тейкера или подписант для `place_take_order`.
+    pub taker: String,
+    pub taker_client_order_id: u64,
+    pub taker_fee_ceil: u64,
+    pub price: i64,
+    pub quantity: i64,
+}
+
+/// `data` — borsh-тело события без 8-байтного дискриминатора.
+pub fn decode_fill_log(data: &[u8]) -> Result<OpenbookFillLog, BinaryReaderError> {
+    let mut reader = BinaryReaderRef::new_ref(data);
+    Ok(OpenbookFillLog {
+        market: reader.read_pubkey()?,
+        taker_side: reader.read_u8()?,
+        maker_slot: reader.read_u8()?,
+        maker_out: reader.read_u8()? != 0,
+        timestamp: reader.read_u64()?,
+        seq_num: reader.read_u64()?,
+        maker: reader.read_pubkey()?,
+        maker_client_order_id: reader.read_u64()?,
+        maker_fee: reader.read_u64()?,
+        maker_timestamp: reader.read_u64()?,
+        taker: reader.read_pubkey()?,
+        taker_client_order_id: reader.read_u64()?,
+        taker_fee_ceil: reader.read_u64()?,
+        price: reader.read_i64()?,
+        quantity: reader.read_i64()?,
+    })
+}
+
+/// FillLog'и по вызовам программы OpenBook в порядке исполнения:
+/// i-й элемент — события i-го `Program opnb2... invoke`.
+pub fn fill_logs_by_invocation(log_messages: &[String]) -> Vec<Vec<OpenbookFillLog>> {
+    AnchorEventDecoder::new(OPENBOOK_V2_PROGRAM_ID)
+        .register(openbook_events::FILL_LOG, |data| decode_fill_log(data).ok())
+        .by_invocation(log_messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use base64_simd::STANDARD;
+
+    use super::*;
+
+    fn fill_log_data(taker_side: u8, price: i64, quantity: i64) -> Vec<u8> {
+        let mut data = openbook_events::FILL_LOG.to_vec();
+        data.extend_from_slice(&[1u8; 32]); // market
+        data.extend_from_slice(&[taker_side, 0, 1]);
+        data.extend_from_slice(&1_700_000_000u64.to_le_bytes());
+        data.extend_from_slice(&9u64.to_le_bytes()); // seq_num
+        data.extend_from_slice(&[2u8; 32]); // maker
+        for value in [0u64, 10, 1_699_999_000] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&[3u8; 32]); // taker
+        for value in [0u64, 25] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&price.to_le_bytes());
+        data.extend_from_slice(&quantity.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn groups_fill_logs_by_openbook_invocation() {
+        let data = STANDARD.encode_to_string(fill_log_data(1, 15_000, 40));
+        let logs: Vec<String> = [
+            "Program ComputeBudget111111111111111111111111111111 invoke [1]".to_string(),
+            "Program ComputeBudget111111111111111111111111111111 success".to_string(),
+            format!("Program {OPENBOOK_V2_PROGRAM_ID} invoke [1]"),
+            "Program log: Instruction: PlaceTakeOrder".to_string(),
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA invoke [2]".to_string(),
+            // событие токен-программы не относится к OpenBook
+            format!("Program data: {data}"),
+            "Program TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA success".to_string(),
+            format!("Program data: {data}"),
+            format!("Program {OPENBOOK_V2_PROGRAM_ID} success"),
+            format!("Program {OPENBOOK_V2_PROGRAM_ID} invoke [1]"),
+            format!("Program {OPENBOOK_V2_PROGRAM_ID} success"),
+        ]
+        .into();
+
+        let invocations = fill_logs_by_invocation(&logs);
+        assert_eq!(invocations.len(), 2);
+        assert_eq!(invocations[0].len(), 1);
+        assert!(invocations[1].is_empty());
+
+        let fill = &invocations[0][0];
+        assert_eq!(fill.market, bs58::encode([1u8; 32]).into_string());
+        assert_eq!(fill.maker, bs58::encode([2u8; 32]).into_string());
+        assert_eq!(fill.taker_side, 1);
+        assert!(fill.maker_out);
+        assert_eq!((fill.price, fill.quantity), (15_000, 40));
+    }
+}
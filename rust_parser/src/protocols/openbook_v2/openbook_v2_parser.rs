@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::core::transaction_utils::TransactionUtils;
+use crate::core::utils::{get_instruction_transfers, sort_by_execution_order};
+use crate::protocols::pumpfun::util::get_instruction_data;
+use crate::protocols::simple::TradeParser;
+use crate::types::{
+    ClassifiedInstruction, ClobFillInfo, DexInfo, LiquidityRole, OrderSide, TradeInfo,
+    TransferData, TransferMap,
+};
+
+use super::constants::{discriminators::openbook_instructions, OPENBOOK_V2_PROGRAM_NAME};
+use super::openbook_v2_event_parser::{fill_logs_by_invocation, OpenbookFillLog};
+
+/// Тейкерские инструкции OpenBook v2: суммы из трансферов, fills — из `FillLog`
+/// в logMessages. Crank (`consume_events`) двигает балансы мейкеров внутри
+/// open-orders без токен-трансферов, поэтому сделок из него не строим.
+pub struct OpenbookV2Parser {
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+    utils: TransactionUtils,
+}
+
+impl OpenbookV2Parser {
+    pub fn new(
+        adapter: Arc<TransactionAdapter>,
+        dex_info: DexInfo,
+        transfer_actions: Arc<TransferMap>,
+        mut classified_instructions: Vec<ClassifiedInstruction>,
+    ) -> Self {
+        sort_by_execution_order(&mut classified_instructions);
+        let utils = TransactionUtils::new(adapter.clone());
+        Self {
+            adapter,
+            dex_info,
+            transfer_actions,
+            classified_instructions,
+            utils,
+        }
+    }
+
+    fn parse_order(
+        &self,
+        classified: &ClassifiedInstruction,
+        data: &[u8],
+        fills: &[OpenbookFillLog],
+    ) -> Option<TradeInfo> {
+        let is_take_order = data[..8] == openbook_instructions::PLACE_TAKE_ORDER;
+        let transfers: Vec<TransferData> = get_instruction_transfers(
+            &self.transfer_actions,
+            &classified.program_id,
+            classified.outer_index,
+            classified.inner_index,
+        )
+        .into_iter()
+        .cloned()
+        .collect();
+
+        let dex_info = DexInfo {
+            program_id: Some(classified.program_id.to_string()),
+            amm: Some(OPENBOOK_V2_PROGRAM_NAME.to_string()),
+            route: self.dex_info.route.clone(),
+        };
+        let mut trade = self.utils.process_swap_data(&transfers, &dex_info)?;
+
+        // place_take_order: signer, penalty_payer, market, ...
+        // place_order(_pegged): signer, open_orders_account, open_orders_admin, user_token_account, market, ...
+        let accounts = self.adapter.get_instruction_accounts(&classified.data);
+        let signer = accounts.first().cloned().unwrap_or_default();
+        let open_orders = (!is_take_order).then(|| accounts.get(1).cloned()).flatten();
+        let market = fills
+            .first()
+            .map(|fill| fill.market.clone())
+            .or_else(|| accounts.get(if is_take_order { 2 } else { 4 }).map(ToString::to_string))
+            .unwrap_or_default();
+        trade.user = Some(signer.to_string());
+        trade.pool = vec![market.clone()];
+
+        // Первое поле аргументов у всех place-инструкций — side
+        let side = match fills.first().map(|fill| fill.taker_side).or(data.get(8).copied()) {
+            Some(1) => OrderSide::Ask,
+            _ => OrderSide::Bid,
+        };
+        let is_own = |account: &str| account == signer || open_orders.as_deref() == Some(account);
+        trade.liquidity_role = fills.iter().find_map(|fill| {
+            if is_own(&fill.taker) {
+                Some(LiquidityRole::Taker)
+            } else if is_own(&fill.maker) {
+                Some(LiquidityRole::Maker)
+            } else {
+                None
+            }
+        });
+        if trade.liquidity_role.is_none() && is_take_order {
+            trade.liquidity_role = Some(LiquidityRole::Taker);
+        }
+
+        let (base, quote) = match side {
+            OrderSide::Bid => (&trade.output_token, &trade.input_token),
+            OrderSide::Ask => (&trade.input_token, &trade.output_token),
+        };
+        trade.clob = Some(ClobFillInfo {
+            market,
+            side,
+            price: if base.amount > 0.0 {
+                quote.amount / base.amount
+            } else {
+                0.0
+            },
+            fill_size: base.amount,
+            price_in_ticks: fills.last().map(|fill| fill.price.max(0) as u64),
+            base_lots_filled: (!fills.is_empty())
+                .then(|| fills.iter().map(|fill| fill.quantity.max(0) as u64).sum()),
+            makers: fills.iter().map(|fill| fill.maker.clone()).collect(),
+        });
+
+        Some(self.utils.attach_token_transfer_info(trade, &self.transfer_actions))
+    }
+}
+
+impl TradeParser for OpenbookV2Parser {
+    fn process_trades(&mut self) -> Vec<TradeInfo> {
+        // k-й invoke в логах — k-я инструкция программы в порядке исполнения;
+        // при обрезанных логах счёт не сойдётся, и fills не привязываем
+        let mut invocations = fill_logs_by_invocation(self.adapter.log_messages());
+        if invocations.len() != self.classified_instructions.len() {
+            invocations.clear();
+        }
+
+        let mut trades = Vec::new();
+        for (pos, classified) in self.classified_instructions.iter().enumerate() {
+            let Ok(data) = get_instruction_data(&classified.data) else {
+                continue;
+            };
+            if data.len() < 9 {
+                continue;
+            }
+            let discriminator = &data[..8];
+            if discriminator != openbook_instructions::PLACE_TAKE_ORDER
+                && discriminator != openbook_instructions::PLACE_ORDER
+                && discriminator != openbook_instructions::PLACE_ORDER_PEGGED
+            {
+                continue;
+            }
+            let fills = invocations.get(pos).map(Vec::as_slice).unwrap_or_default();
+            if let Some(trade) = self.parse_order(classified, &data, fills) {
+                trades.push(trade);
+            }
+        }
+        trades
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::constants::dex_programs;
+    use crate::core::test_utils::sample_transaction;
+    use crate::types::SolanaInstruction;
+    use crate::DexParser;
+
+    #[test]
+    fn parses_openbook_v2_take_order_with_fill_logs() {
+        use crate::protocols::openbook_v2::constants::discriminators::{
+            openbook_events, openbook_instructions,
+        };
+        use crate::types::{LiquidityRole, OrderSide};
+        use base64_simd::STANDARD;
+
+        let taker = bs58::encode([4u8; 32]).into_string();
+        let mut take_data = openbook_instructions::PLACE_TAKE_ORDER.to_vec();
+        take_data.push(1); // Side::Ask
+        let mut fill = openbook_events::FILL_LOG.to_vec();
+        fill.extend_from_slice(&[1u8; 32]); // market
+        fill.extend_from_slice(&[1, 0, 0]); // taker_side, maker_slot, maker_out
+        fill.extend_from_slice(&[0u8; 16]); // timestamp, seq_num
+        fill.extend_from_slice(&[3u8; 32]); // maker
+        fill.extend_from_slice(&[0u8; 24]);
+        fill.extend_from_slice(&[4u8; 32]); // taker
+        fill.extend_from_slice(&[0u8; 16]);
+        fill.extend_from_slice(&2_000i64.to_le_bytes());
+        fill.extend_from_slice(&1_000i64.to_le_bytes());
+
+        let mut tx = sample_transaction();
+        tx.instructions = vec![SolanaInstruction {
+            program_id: dex_programs::OPENBOOK_V2.into(),
+            accounts: [taker.as_str(), "penalty-payer", "market-account"]
+                .iter()
+                .map(|&a| a.into())
+                .collect(),
+            data: STANDARD.encode_to_string(&take_data),
+        }];
+        tx.transfers.iter_mut().for_each(|transfer| {
+            transfer.program_id = dex_programs::OPENBOOK_V2.into();
+        });
+        tx.meta.log_messages = vec![
+            format!("Program {} invoke [1]", dex_programs::OPENBOOK_V2),
+            "Program log: Instruction: PlaceTakeOrder".to_string(),
+            format!("Program data: {}", STANDARD.encode_to_string(&fill)),
+            format!("Program {} success", dex_programs::OPENBOOK_V2),
+        ];
+
+        let result = DexParser::new().parse_all(tx, None);
+        assert_eq!(result.trades.len(), 1);
+        let trade = &result.trades[0];
+        assert_eq!(trade.amm.as_deref(), Some("OpenBookV2"));
+        assert_eq!(trade.user.as_deref(), Some(taker.as_str()));
+        assert_eq!(trade.liquidity_role, Some(LiquidityRole::Taker));
+        let market = bs58::encode([1u8; 32]).into_string();
+        assert_eq!(trade.pool, vec![market.clone()]);
+        let clob = trade.clob.as_ref().expect("clob fill");
+        assert_eq!(clob.market, market);
+        assert_eq!(clob.side, OrderSide::Ask);
+        assert_eq!(clob.price_in_ticks, Some(2_000));
+        assert_eq!(clob.base_lots_filled, Some(1_000));
+        assert_eq!(clob.makers, vec![bs58::encode([3u8; 32]).into_string()]);
+    }
+}
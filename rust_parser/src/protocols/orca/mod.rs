@@ -0,0 +1,22 @@
+pub mod constants;
+pub mod orca_liquidity;
+
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::protocols::simple::LiquidityParser;
+use crate::types::{ClassifiedInstruction, TransferMap};
+
+use orca_liquidity::OrcaLiquidityParser;
+
+pub fn build_orca_liquidity_parser(
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+) -> Box<dyn LiquidityParser> {
+    Box::new(OrcaLiquidityParser::new(
+        adapter,
+        transfer_actions,
+        classified_instructions,
+    ))
+}
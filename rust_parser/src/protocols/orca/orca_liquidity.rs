@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::core::utils::get_instruction_actions;
+use crate::protocols::pumpfun::util::get_instruction_data;
+use crate::protocols::simple::LiquidityParser;
+use crate::protocols::stable::stable_liquidity::token_legs;
+use crate::types::{ClassifiedInstruction, PoolEvent, PoolEventType, TransferMap};
+
+use super::constants::{whirlpool_instructions, ORCA_PROGRAM_NAME};
+
+/// Позиции Whirlpool: открытие и закрытие, increase/decrease ликвидности, сбор комиссий.
+/// LP-токена нет — `lp_amount_raw` содержит `liquidity` из аргументов инструкции.
+pub struct OrcaLiquidityParser {
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+}
+
+impl OrcaLiquidityParser {
+    pub fn new(
+        adapter: Arc<TransactionAdapter>,
+        transfer_actions: Arc<TransferMap>,
+        classified_instructions: Vec<ClassifiedInstruction>,
+    ) -> Self {
+        Self {
+            adapter,
+            transfer_actions,
+            classified_instructions,
+        }
+    }
+
+    /// Тип события и индекс аккаунта whirlpool; closePosition пул не передаёт.
+    fn pool_action(discriminator: &[u8]) -> Option<(PoolEventType, Option<usize>)> {
+        use whirlpool_instructions::*;
+
+        let action = match discriminator {
+            d if d == INITIALIZE_POOL => (PoolEventType::Create, Some(4)),
+            d if d == INITIALIZE_POOL_V2 => (PoolEventType::Create, Some(6)),
+            d if d == OPEN_POSITION || d == OPEN_POSITION_WITH_TOKEN_EXTENSIONS => {
+                (PoolEventType::OpenPosition, Some(5))
+            }
+            d if d == OPEN_POSITION_WITH_METADATA => (PoolEventType::OpenPosition, Some(6)),
+            d if d == INCREASE_LIQUIDITY || d == INCREASE_LIQUIDITY_V2 => {
+                (PoolEventType::IncreaseLiquidity, Some(0))
+            }
+            d if d == DECREASE_LIQUIDITY || d == DECREASE_LIQUIDITY_V2 => {
+                (PoolEventType::DecreaseLiquidity, Some(0))
+            }
+            d if d == COLLECT_FEES || d == COLLECT_FEES_V2 => (PoolEventType::CollectFee, Some(0)),
+            d if d == CLOSE_POSITION || d == CLOSE_POSITION_WITH_TOKEN_EXTENSIONS => {
+                (PoolEventType::ClosePosition, None)
+            }
+            _ => return None,
+        };
+        Some(action)
+    }
+
+    fn parse_instruction(&self, classified: &ClassifiedInstruction) -> Option<PoolEvent> {
+        let data = get_instruction_data(&classified.data).ok()?;
+        let (action, pool_index) = Self::pool_action(data.get(..8)?)?;
+
+        let actions = get_instruction_actions(
+            &self.transfer_actions,
+            &classified.program_id,
+            classified.outer_index,
+            classified.inner_index,
+        );
+        let legs = token_legs(&actions, None, action.leg_direction());
+        let moves_tokens = matches!(
+            action,
+            PoolEventType::IncreaseLiquidity
+                | PoolEventType::DecreaseLiquidity
+                | PoolEventType::CollectFee
+        );
+        if moves_tokens && legs.is_empty() {
+            return None;
+        }
+        // increase/decrease: liquidity_amount: u128 сразу за дискриминатором
+        let liquidity = matches!(
+            action,
+            PoolEventType::IncreaseLiquidity | PoolEventType::DecreaseLiquidity
+        )
+        .then(|| data.get(8..24)?.try_into().ok().map(u128::from_le_bytes))
+        .flatten();
+
+        let accounts = self.adapter.get_instruction_accounts(&classified.data);
+        let mut base = self
+            .adapter
+            .get_pool_event_base(action.clone(), &classified.program_id);
+        base.idx = format!(
+            "{}-{}",
+            classified.outer_index,
+            classified.inner_index.unwrap_or(0)
+        );
+
+        Some(PoolEvent {
+            user: base.user,
+            event_type: action.into(),
+            program_id: base.program_id,
+            amm: Some(ORCA_PROGRAM_NAME.to_string()),
+            slot: base.slot,
+            timestamp: base.timestamp,
+            signature: base.signature,
+            idx: base.idx,
+            signer: base.signer,
+            pool_id: pool_index
+                .and_then(|index| accounts.get(index))
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            config: None,
+            pool_lp_mint: None,
+            lp_amount: None,
+            lp_amount_raw: liquidity.map(|liquidity| liquidity.to_string()),
+            tokens: legs,
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
+        })
+    }
+}
+
+impl LiquidityParser for OrcaLiquidityParser {
+    fn process_liquidity(&mut self) -> Vec<PoolEvent> {
+        self.classified_instructions
+            .iter()
+            .filter_map(|classified| self.parse_instruction(classified))
+            .collect()
+    }
+}
@@ -0,0 +1,23 @@
+pub const PHOENIX_PROGRAM_ID: &str = "PhoeNiXZ8ByJGLkxNfZRnkUfjvmuYqLR89jjFHGqdXY";
+pub const PHOENIX_PROGRAM_NAME: &str = "Phoenix";
+
+/// Phoenix — не anchor: инструкция определяется первым байтом.
+pub mod instructions {
+    pub const SWAP: u8 = 0;
+    pub const SWAP_WITH_FREE_FUNDS: u8 = 1;
+    /// Self-CPI с событиями рынка, вызывается в конце каждой инструкции.
+    pub const LOG: u8 = 15;
+}
+
+/// Теги `PhoenixMarketEvent` (borsh enum).
+pub mod events {
+    pub const HEADER: u8 = 1;
+    pub const FILL: u8 = 2;
+    pub const PLACE: u8 = 3;
+    pub const REDUCE: u8 = 4;
+    pub const EVICT: u8 = 5;
+    pub const FILL_SUMMARY: u8 = 6;
+    pub const FEE: u8 = 7;
+    pub const TIME_IN_FORCE: u8 = 8;
+    pub const EXPIRED_ORDER: u8 = 9;
+}
@@ -0,0 +1,149 @@
+use crate::protocols::pumpfun::binary_reader::{BinaryReaderError, BinaryReaderRef};
+
+use super::constants::events;
+
+/// Заголовок батча событий (`AuditLogHeader`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhoenixLogHeader {
+    pub instruction: u8,
+    pub sequence_number: u64,
+    pub timestamp: i64,
+    pub slot: u64,
+    pub market: String,
+    pub signer: String,
+    pub total_events: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhoenixFill {
+    pub maker: String,
+    pub order_sequence_number: u64,
+    pub price_in_ticks: u64,
+    pub base_lots_filled: u64,
+    pub base_lots_remaining: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PhoenixFillSummary {
+    pub total_base_lots_filled: u64,
+    pub total_quote_lots_filled: u64,
+    pub total_fee_in_quote_lots: u64,
+}
+
+/// События одной Log-инструкции; Place/Reduce/Evict и прочие пропускаются.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhoenixLog {
+    pub header: PhoenixLogHeader,
+    pub fills: Vec<PhoenixFill>,
+    pub fill_summary: Option<PhoenixFillSummary>,
+}
+
+/// `data` — данные Log-инструкции без первого байта-тега:
+/// `Header`, затем подряд borsh-сериализованные `PhoenixMarketEvent`.
+pub fn decode_log(data: &[u8]) -> Result<PhoenixLog, BinaryReaderError> {
+    let mut reader = BinaryReaderRef::new_ref(data);
+    if reader.read_u8()? != events::HEADER {
+        return Err(BinaryReaderError::Io(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "phoenix log does not start with a header",
+        )));
+    }
+    let header = PhoenixLogHeader {
+        instruction: reader.read_u8()?,
+        sequence_number: reader.read_u64()?,
+        timestamp: reader.read_i64()?,
+        slot: reader.read_u64()?,
+        market: reader.read_pubkey()?,
+        signer: reader.read_pubkey()?,
+        total_events: reader.read_u16()?,
+    };
+
+    let mut fills = Vec::new();
+    let mut fill_summary = None;
+    while reader.remaining() > 0 {
+        let tag = reader.read_u8()?;
+        let _index = reader.read_u16()?;
+        match tag {
+            events::FILL => fills.push(PhoenixFill {
+                maker: reader.read_pubkey()?,
+                order_sequence_number: reader.read_u64()?,
+                price_in_ticks: reader.read_u64()?,
+                base_lots_filled: reader.read_u64()?,
+                base_lots_remaining: reader.read_u64()?,
+            }),
+            events::FILL_SUMMARY => {
+                let _client_order_id = reader.read_fixed_array(16)?;
+                fill_summary = Some(PhoenixFillSummary {
+                    total_base_lots_filled: reader.read_u64()?,
+                    total_quote_lots_filled: reader.read_u64()?,
+                    total_fee_in_quote_lots: reader.read_u64()?,
+                });
+            }
+            events::PLACE => {
+                reader.read_fixed_array(40)?;
+            }
+            events::REDUCE => {
+                reader.read_fixed_array(32)?;
+            }
+            events::EVICT | events::EXPIRED_ORDER => {
+                reader.read_fixed_array(56)?;
+            }
+            events::FEE => {
+                reader.read_u64()?;
+            }
+            events::TIME_IN_FORCE => {
+                reader.read_fixed_array(24)?;
+            }
+            // Неизвестный тег: длину события не знаем, дальше читать нельзя
+            _ => break,
+        }
+    }
+
+    Ok(PhoenixLog {
+        header,
+        fills,
+        fill_summary,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_fills_and_skips_other_events() {
+        let mut data = vec![events::HEADER, 0];
+        data.extend_from_slice(&7u64.to_le_bytes()); // sequence_number
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        data.extend_from_slice(&250_000_000u64.to_le_bytes()); // slot
+        data.extend_from_slice(&[1u8; 32]); // market
+        data.extend_from_slice(&[2u8; 32]); // signer
+        data.extend_from_slice(&3u16.to_le_bytes());
+
+        data.push(events::FILL);
+        data.extend_from_slice(&0u16.to_le_bytes());
+        data.extend_from_slice(&[3u8; 32]); // maker
+        for value in [42u64, 150_123, 1_000, 0] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.push(events::FEE);
+        data.extend_from_slice(&1u16.to_le_bytes());
+        data.extend_from_slice(&15u64.to_le_bytes());
+        data.push(events::FILL_SUMMARY);
+        data.extend_from_slice(&2u16.to_le_bytes());
+        data.extend_from_slice(&[0u8; 16]); // client_order_id
+        for value in [1_000u64, 150_123_000, 15] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+
+        let log = decode_log(&data).expect("phoenix log");
+        assert_eq!(log.header.market, bs58::encode([1u8; 32]).into_string());
+        assert_eq!(log.fills.len(), 1);
+        assert_eq!(log.fills[0].price_in_ticks, 150_123);
+        assert_eq!(log.fills[0].base_lots_filled, 1_000);
+        assert_eq!(
+            log.fill_summary.map(|s| s.total_quote_lots_filled),
+            Some(150_123_000)
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::core::transaction_utils::TransactionUtils;
+use crate::core::utils::{get_instruction_transfers, sort_by_execution_order};
+use crate::protocols::pumpfun::util::get_instruction_data;
+use crate::protocols::simple::TradeParser;
+use crate::types::{
+    ClassifiedInstruction, ClobFillInfo, DexInfo, LiquidityRole, OrderSide, TradeInfo, TransferData,
+    TransferMap,
+};
+
+use super::constants::{instructions, PHOENIX_PROGRAM_NAME};
+use super::phoenix_event_parser::{decode_log, PhoenixLog};
+
+/// Swap-инструкции Phoenix: суммы из трансферов, детали fills — из Log self-CPI.
+pub struct PhoenixParser {
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+    utils: TransactionUtils,
+}
+
+impl PhoenixParser {
+    pub fn new(
+        adapter: Arc<TransactionAdapter>,
+        dex_info: DexInfo,
+        transfer_actions: Arc<TransferMap>,
+        mut classified_instructions: Vec<ClassifiedInstruction>,
+    ) -> Self {
+        sort_by_execution_order(&mut classified_instructions);
+        let utils = TransactionUtils::new(adapter.clone());
+        Self {
+            adapter,
+            dex_info,
+            transfer_actions,
+            classified_instructions,
+            utils,
+        }
+    }
+
+    fn parse_swap(&self, pos: usize, data: &[u8]) -> Option<TradeInfo> {
+        let classified = &self.classified_instructions[pos];
+        let transfers: Vec<TransferData> = get_instruction_transfers(
+            &self.transfer_actions,
+            &classified.program_id,
+            classified.outer_index,
+            classified.inner_index,
+        )
+        .into_iter()
+        .cloned()
+        .collect();
+
+        let dex_info = DexInfo {
+            program_id: Some(classified.program_id.to_string()),
+            amm: Some(PHOENIX_PROGRAM_NAME.to_string()),
+            route: self.dex_info.route.clone(),
+        };
+        let mut trade = self.utils.process_swap_data(&transfers, &dex_info)?;
+
+        // phoenix_program, log_authority, market, trader, base_account, quote_account, ...
+        let accounts = self.adapter.get_instruction_accounts(&classified.data);
+        let market = accounts.get(2).cloned().unwrap_or_default();
+        if let Some(trader) = accounts.get(3) {
+            trade.user = Some(trader.to_string());
+        }
+        trade.pool = vec![market.to_string()];
+        trade.liquidity_role = Some(LiquidityRole::Taker);
+
+        // OrderPacket: [вариант, side, ...] — side первым полем у всех вариантов
+        let side = match data.get(2) {
+            Some(1) => OrderSide::Ask,
+            _ => OrderSide::Bid,
+        };
+        let (base, quote) = match side {
+            OrderSide::Bid => (&trade.output_token, &trade.input_token),
+            OrderSide::Ask => (&trade.input_token, &trade.output_token),
+        };
+        let log = self.find_log(pos);
+        trade.clob = Some(ClobFillInfo {
+            market: market.to_string(),
+            side,
+            price: if base.amount > 0.0 {
+                quote.amount / base.amount
+            } else {
+                0.0
+            },
+            fill_size: base.amount,
+            price_in_ticks: log
+                .as_ref()
+                .and_then(|log| log.fills.last())
+                .map(|fill| fill.price_in_ticks),
+            base_lots_filled: log.as_ref().and_then(|log| {
+                log.fill_summary
+                    .map(|summary| summary.total_base_lots_filled)
+                    .or_else(|| {
+                        (!log.fills.is_empty())
+                            .then(|| log.fills.iter().map(|fill| fill.base_lots_filled).sum())
+                    })
+            }),
+            makers: log
+                .map(|log| log.fills.into_iter().map(|fill| fill.maker).collect())
+                .unwrap_or_default(),
+        });
+
+        Some(self.utils.attach_token_transfer_info(trade, &self.transfer_actions))
+    }
+
+    /// Log той же outer-инструкции, идущий после swap.
+    fn find_log(&self, pos: usize) -> Option<PhoenixLog> {
+        let outer_index = self.classified_instructions[pos].outer_index;
+        self.classified_instructions[pos + 1..]
+            .iter()
+            .take_while(|ix| ix.outer_index == outer_index)
+            .find_map(|ix| {
+                let data = get_instruction_data(&ix.data).ok()?;
+                if data.first() != Some(&instructions::LOG) {
+                    return None;
+                }
+                decode_log(&data[1..]).ok()
+            })
+    }
+}
+
+impl TradeParser for PhoenixParser {
+    fn process_trades(&mut self) -> Vec<TradeInfo> {
+        let mut trades = Vec::new();
+        for pos in 0..self.classified_instructions.len() {
+            let Ok(data) = get_instruction_data(&self.classified_instructions[pos].data) else {
+                continue;
+            };
+            // SwapWithFreeFunds без трансферов отфильтруется в process_swap_data
+            if !matches!(
+                data.first(),
+                Some(&instructions::SWAP) | Some(&instructions::SWAP_WITH_FREE_FUNDS)
+            ) {
+                continue;
+            }
+            if let Some(trade) = self.parse_swap(pos, &data) {
+                trades.push(trade);
+            }
+        }
+        trades
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::constants::dex_programs;
+    use crate::core::test_utils::sample_transaction;
+    use crate::types::SolanaInstruction;
+    use crate::DexParser;
+
+    #[test]
+    fn parses_phoenix_swap_with_fill_events() {
+        use crate::protocols::phoenix::constants::{events, instructions};
+        use crate::types::{InnerInstruction, OrderSide};
+        use base64_simd::STANDARD;
+
+        // Swap, OrderPacket::ImmediateOrCancel, Side::Ask
+        let swap_data = vec![instructions::SWAP, 2, 1];
+        let mut log_data = vec![instructions::LOG, events::HEADER, 0];
+        log_data.extend_from_slice(&[0u8; 24]); // sequence_number, timestamp, slot
+        log_data.extend_from_slice(&[1u8; 32]); // market
+        log_data.extend_from_slice(&[2u8; 32]); // signer
+        log_data.extend_from_slice(&1u16.to_le_bytes());
+        log_data.push(events::FILL);
+        log_data.extend_from_slice(&0u16.to_le_bytes());
+        log_data.extend_from_slice(&[3u8; 32]); // maker
+        for value in [9u64, 2_000, 1_000, 0] {
+            log_data.extend_from_slice(&value.to_le_bytes());
+        }
+
+        let mut tx = sample_transaction();
+        tx.instructions = vec![SolanaInstruction {
+            program_id: dex_programs::PHOENIX.into(),
+            accounts: [
+                "phoenix",
+                "log-authority",
+                "market",
+                "user",
+                "user-base",
+                "user-quote",
+            ]
+            .iter()
+            .map(|&a| a.into())
+            .collect(),
+            data: STANDARD.encode_to_string(&swap_data),
+        }];
+        tx.inner_instructions = vec![InnerInstruction {
+            index: 0,
+            instructions: vec![SolanaInstruction {
+                program_id: dex_programs::PHOENIX.into(),
+                accounts: vec!["log-authority".into()],
+                data: STANDARD.encode_to_string(&log_data),
+            }],
+            stack_heights: Vec::new(),
+        }];
+        tx.transfers.iter_mut().for_each(|transfer| {
+            transfer.program_id = dex_programs::PHOENIX.into();
+        });
+
+        let result = DexParser::new().parse_all(tx, None);
+        assert_eq!(result.trades.len(), 1);
+        let trade = &result.trades[0];
+        assert_eq!(trade.amm.as_deref(), Some("Phoenix"));
+        assert_eq!(trade.pool, vec!["market".to_string()]);
+        let clob = trade.clob.as_ref().expect("clob fill");
+        assert_eq!(clob.side, OrderSide::Ask);
+        assert_eq!(clob.fill_size, 1.0);
+        assert_eq!(clob.price, 2.0);
+        assert_eq!(clob.price_in_ticks, Some(2_000));
+        assert_eq!(clob.base_lots_filled, Some(1_000));
+        assert_eq!(clob.makers, vec![bs58::encode([3u8; 32]).into_string()]);
+    }
+}
@@ -4,8 +4,15 @@ pub const PUMP_FUN_PROGRAM_NAME: &str = "Pumpfun";
 pub const PUMP_SWAP_PROGRAM_ID: &str = "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA";
 pub const PUMP_SWAP_PROGRAM_NAME: &str = "Pumpswap";
 
+/// LP mint Pumpswap-пулов создаётся с 9 decimals.
+pub const PUMP_SWAP_LP_DECIMALS: u8 = 9;
+
 pub const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
 
+/// Все токены Pumpfun минтятся с 6 decimals и supply 1_000_000_000.
+pub const PUMP_FUN_TOKEN_DECIMALS: u8 = 6;
+pub const PUMP_FUN_TOKEN_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000;
+
 pub mod discriminators {
     pub mod pumpfun_instructions {
         pub const CREATE: [u8; 8] = [24, 30, 200, 40, 5, 28, 7, 119];
@@ -35,6 +42,7 @@ pub mod discriminators {
         pub const REMOVE_LIQUIDITY: [u8; 8] = [183, 18, 70, 156, 148, 109, 161, 34];
         pub const BUY: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
         pub const SELL: [u8; 8] = [51, 230, 133, 164, 1, 127, 131, 173];
+        pub const COLLECT_COIN_CREATOR_FEE: [u8; 8] = [160, 57, 89, 42, 181, 139, 43, 66];
     }
 
     pub mod pumpswap_events {
@@ -11,6 +11,8 @@ pub mod pumpswap_parser;
 pub mod pumpswap_parser_zc;
 pub mod util;
 
+use std::sync::Arc;
+
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::protocols::simple::{LiquidityParser, MemeEventParser, TradeParser, TransferParser};
 use crate::types::{ClassifiedInstruction, DexInfo, TransferMap};
@@ -20,9 +22,9 @@ use pumpswap_liquidity_parser::PumpswapLiquidityParser;
 use pumpswap_parser::PumpswapParser;
 
 pub fn build_pumpfun_trade_parser(
-    adapter: TransactionAdapter,
+    adapter: Arc<TransactionAdapter>,
     dex_info: DexInfo,
-    transfer_actions: TransferMap,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
 ) -> Box<dyn TradeParser> {
     Box::new(PumpfunParser::new(
@@ -34,9 +36,9 @@ pub fn build_pumpfun_trade_parser(
 }
 
 pub fn build_pumpswap_trade_parser(
-    adapter: TransactionAdapter,
+    adapter: Arc<TransactionAdapter>,
     dex_info: DexInfo,
-    transfer_actions: TransferMap,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
 ) -> Box<dyn TradeParser> {
     Box::new(PumpswapParser::new(
@@ -48,8 +50,8 @@ pub fn build_pumpswap_trade_parser(
 }
 
 pub fn build_pumpswap_liquidity_parser(
-    adapter: TransactionAdapter,
-    transfer_actions: TransferMap,
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
 ) -> Box<dyn LiquidityParser> {
     Box::new(PumpswapLiquidityParser::new(
@@ -60,8 +62,8 @@ pub fn build_pumpswap_liquidity_parser(
 }
 
 pub fn build_pumpfun_meme_parser(
-    adapter: TransactionAdapter,
-    transfer_actions: TransferMap,
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
 ) -> Box<dyn MemeEventParser> {
     Box::new(pumpfun_parser::PumpfunMemeParser::new(
         adapter,
@@ -70,9 +72,9 @@ pub fn build_pumpfun_meme_parser(
 }
 
 pub fn build_pumpswap_transfer_parser(
-    adapter: TransactionAdapter,
+    adapter: Arc<TransactionAdapter>,
     dex_info: DexInfo,
-    transfer_actions: TransferMap,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
 ) -> Box<dyn TransferParser> {
     // Pumpswap reuses the generic transfer parser for now.
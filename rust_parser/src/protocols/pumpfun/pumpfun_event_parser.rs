@@ -4,12 +4,13 @@ use crate::types::{ClassifiedInstruction, MemeEvent, TradeType};
 
 use super::binary_reader::BinaryReader;
 use super::constants::{
-    discriminators::pumpfun_events, PUMP_FUN_PROGRAM_NAME, PUMP_SWAP_PROGRAM_NAME, SOL_MINT,
+    discriminators::pumpfun_events, PUMP_FUN_PROGRAM_NAME, PUMP_FUN_TOKEN_DECIMALS,
+    PUMP_FUN_TOKEN_TOTAL_SUPPLY, PUMP_SWAP_PROGRAM_NAME, SOL_MINT,
 };
 use super::error::PumpfunError;
 use super::util::{
-    build_token_info, get_instruction_data, get_prev_instruction_by_index, get_trade_type,
-    sort_by_idx, HasIdx,
+    bonding_curve_market_cap, bonding_curve_price, build_token_info, convert_to_ui_amount,
+    get_instruction_data, get_prev_instruction_by_index, get_trade_type, sort_by_idx, HasIdx,
 };
 
 use crate::core::transaction_adapter::TransactionAdapter;
@@ -65,7 +66,7 @@ impl PumpfunEventParser {
                     ) {
                         if prev.data.accounts.len() > 3 {
                             if let Some(account) = prev.data.accounts.get(3) {
-                                meme_event.bonding_curve = Some(account.clone());
+                                meme_event.bonding_curve = Some(account.to_string());
                             }
                         }
                     }
@@ -99,17 +100,20 @@ impl PumpfunEventParser {
         let user_bytes = reader.read_fixed_array(32)?;
         let user = bs58_encode(user_bytes).into_string();
         let _event_timestamp = reader.read_i64()?;
-        let _virtual_sol = reader.read_u64()?;
-        let _virtual_token = reader.read_u64()?;
+        let virtual_sol_reserves = reader.read_u64()?;
+        let virtual_token_reserves = reader.read_u64()?;
 
         let mut fee = None;
+        let mut fee_recipient = None;
         let mut creator = None;
         let mut creator_fee = None;
+        let mut real_sol_reserves = None;
+        let mut real_token_reserves = None;
 
         if reader.remaining() >= 52 {
-            let _real_sol_reserves = reader.read_u64()?;
-            let _real_token_reserves = reader.read_u64()?;
-            let _fee_recipient = reader.read_pubkey()?;
+            real_sol_reserves = Some(reader.read_u64()?);
+            real_token_reserves = Some(reader.read_u64()?);
+            let recipient = reader.read_pubkey()?;
             let _fee_basis_points = reader.read_u16()?;
             let raw_fee = reader.read_u64()?;
             let creator_key = reader.read_pubkey()?;
@@ -117,6 +121,7 @@ impl PumpfunEventParser {
             let raw_creator_fee = reader.read_u64()?;
 
             fee = Some(raw_fee as f64);
+            fee_recipient = Some(recipient);
             creator = Some(creator_key);
             creator_fee = Some(raw_creator_fee as f64);
         }
@@ -131,6 +136,7 @@ impl PumpfunEventParser {
         let input_token = build_token_info(input_mint, input_amount, input_decimals, None);
         let output_token = build_token_info(output_mint, output_amount, output_decimals, None);
         let trade_type = get_trade_type(input_mint, output_mint);
+        let price = bonding_curve_price(virtual_sol_reserves, virtual_token_reserves);
 
         Ok(MemeEvent {
             event_type: trade_type,
@@ -149,6 +155,7 @@ impl PumpfunEventParser {
             decimals: None,
             total_supply: None,
             fee,
+            fee_recipient,
             protocol_fee: None,
             platform_fee: None,
             share_fee: None,
@@ -162,6 +169,13 @@ impl PumpfunEventParser {
             pool_a_reserve: None,
             pool_b_reserve: None,
             pool_fee_rate: None,
+            virtual_sol_reserves: Some(virtual_sol_reserves),
+            virtual_token_reserves: Some(virtual_token_reserves),
+            real_sol_reserves,
+            real_token_reserves,
+            price,
+            market_cap: price.map(|p| bonding_curve_market_cap(p, PUMP_FUN_TOKEN_TOTAL_SUPPLY)),
+            dev_buy: None,
         })
     }
 
@@ -189,12 +203,21 @@ impl PumpfunEventParser {
             }
         }
 
+        let mut reserves = None;
         if reader.remaining() >= 32 {
-            let _virtual_token_reserves = reader.read_u64()?;
-            let _virtual_sol_reserves = reader.read_u64()?;
-            let _real_token_reserves = reader.read_u64()?;
-            let _token_total_supply = reader.read_u64()?;
+            let virtual_token_reserves = reader.read_u64()?;
+            let virtual_sol_reserves = reader.read_u64()?;
+            let real_token_reserves = reader.read_u64()?;
+            let token_total_supply = reader.read_u64()?;
+            reserves = Some((
+                virtual_sol_reserves,
+                virtual_token_reserves,
+                real_token_reserves,
+                token_total_supply,
+            ));
         }
+        let price = reserves.and_then(|(sol, token, _, _)| bonding_curve_price(sol, token));
+        let total_supply = reserves.map(|(_, _, _, supply)| supply);
 
         Ok(MemeEvent {
             event_type: TradeType::Create,
@@ -210,9 +233,10 @@ impl PumpfunEventParser {
             name: Some(name),
             symbol: Some(symbol),
             uri: Some(uri),
-            decimals: None,
-            total_supply: None,
+            decimals: Some(PUMP_FUN_TOKEN_DECIMALS),
+            total_supply,
             fee: None,
+            fee_recipient: None,
             protocol_fee: None,
             platform_fee: None,
             share_fee: None,
@@ -226,6 +250,16 @@ impl PumpfunEventParser {
             pool_a_reserve: None,
             pool_b_reserve: None,
             pool_fee_rate: None,
+            virtual_sol_reserves: reserves.map(|(sol, _, _, _)| sol),
+            virtual_token_reserves: reserves.map(|(_, token, _, _)| token),
+            // Новая кривая: реальных SOL ещё нет
+            real_sol_reserves: reserves.map(|_| 0),
+            real_token_reserves: reserves.map(|(_, _, real_token, _)| real_token),
+            price,
+            market_cap: price
+                .zip(total_supply)
+                .map(|(p, supply)| bonding_curve_market_cap(p, supply)),
+            dev_buy: None,
         })
     }
 
@@ -258,6 +292,7 @@ impl PumpfunEventParser {
             decimals: None,
             total_supply: None,
             fee: None,
+            fee_recipient: None,
             protocol_fee: None,
             platform_fee: None,
             share_fee: None,
@@ -271,6 +306,13 @@ impl PumpfunEventParser {
             pool_a_reserve: None,
             pool_b_reserve: None,
             pool_fee_rate: None,
+            virtual_sol_reserves: None,
+            virtual_token_reserves: None,
+            real_sol_reserves: None,
+            real_token_reserves: None,
+            price: None,
+            market_cap: None,
+            dev_buy: None,
         })
     }
 
@@ -281,9 +323,9 @@ impl PumpfunEventParser {
         let user = bs58_encode(user_bytes).into_string();
         let mint_bytes = reader.read_fixed_array(32)?;
         let mint = bs58_encode(mint_bytes).into_string();
-        let _mint_amount = reader.read_u64()?;
-        let _sol_amount = reader.read_u64()?;
-        let _pool_migrate_fee = reader.read_u64()? as u128;
+        let mint_amount = reader.read_u64()?;
+        let sol_amount = reader.read_u64()?;
+        let pool_migrate_fee = reader.read_u64()?;
         let bonding_curve_bytes = reader.read_fixed_array(32)?;
         let bonding_curve = bs58_encode(bonding_curve_bytes).into_string();
         let ts = reader.read_i64()?;
@@ -304,9 +346,11 @@ impl PumpfunEventParser {
             name: None,
             symbol: None,
             uri: None,
-            decimals: None,
+            decimals: Some(PUMP_FUN_TOKEN_DECIMALS),
             total_supply: None,
-            fee: None,
+            // pool_migrate_fee в лампортах, `fee` мем-событий — в SOL
+            fee: Some(convert_to_ui_amount(pool_migrate_fee, 9)),
+            fee_recipient: None,
             protocol_fee: None,
             platform_fee: None,
             share_fee: None,
@@ -317,9 +361,17 @@ impl PumpfunEventParser {
             bonding_curve: Some(bonding_curve),
             pool: Some(pool),
             pool_dex: Some(PUMP_SWAP_PROGRAM_NAME.to_string()),
-            pool_a_reserve: None,
-            pool_b_reserve: None,
+            // Ликвидность, перенесённая в Pumpswap пул
+            pool_a_reserve: Some(convert_to_ui_amount(mint_amount, PUMP_FUN_TOKEN_DECIMALS)),
+            pool_b_reserve: Some(convert_to_ui_amount(sol_amount, 9)),
             pool_fee_rate: None,
+            virtual_sol_reserves: None,
+            virtual_token_reserves: None,
+            real_sol_reserves: None,
+            real_token_reserves: None,
+            price: None,
+            market_cap: None,
+            dev_buy: None,
         })
     }
 }
@@ -329,4 +381,131 @@ impl HasIdx for MemeEvent {
     fn idx(&self) -> &str {
         &self.idx
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trade_event_exposes_bonding_curve_state() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&[1u8; 32]); // mint
+        data.extend_from_slice(&500_000_000u64.to_le_bytes()); // sol_amount
+        data.extend_from_slice(&12_345_600_000u64.to_le_bytes()); // token_amount
+        data.push(1); // is_buy
+        data.extend_from_slice(&[2u8; 32]); // user
+        data.extend_from_slice(&0i64.to_le_bytes());
+        data.extend_from_slice(&40_000_000_000u64.to_le_bytes()); // virtual_sol
+        data.extend_from_slice(&800_000_000_000_000u64.to_le_bytes()); // virtual_token
+
+        let event = PumpfunEventParser::new()
+            .decode_trade_event(&data)
+            .expect("trade event");
+
+        assert_eq!(event.virtual_sol_reserves, Some(40_000_000_000));
+        assert_eq!(event.virtual_token_reserves, Some(800_000_000_000_000));
+        assert_eq!(event.real_sol_reserves, None);
+        let price = event.price.expect("price");
+        assert!((price - 0.00000005).abs() < 1e-15);
+        let market_cap = event.market_cap.expect("market cap");
+        assert!((market_cap - 50.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn create_links_dev_buy_and_fee_recipient() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&[1u8; 32]); // mint
+        data.extend_from_slice(&500_000_000u64.to_le_bytes()); // sol_amount
+        data.extend_from_slice(&12_345_600_000u64.to_le_bytes()); // token_amount
+        data.push(1); // is_buy
+        data.extend_from_slice(&[2u8; 32]); // user
+        data.extend_from_slice(&0i64.to_le_bytes());
+        data.extend_from_slice(&40_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(&800_000_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(&500_000_000u64.to_le_bytes()); // real_sol
+        data.extend_from_slice(&0u64.to_le_bytes()); // real_token
+        data.extend_from_slice(&[3u8; 32]); // fee_recipient
+        data.extend_from_slice(&95u16.to_le_bytes());
+        data.extend_from_slice(&4_750_000u64.to_le_bytes());
+        data.extend_from_slice(&[2u8; 32]); // creator
+        data.extend_from_slice(&5u16.to_le_bytes());
+        data.extend_from_slice(&250_000u64.to_le_bytes());
+
+        let mut buy = PumpfunEventParser::new()
+            .decode_trade_event(&data)
+            .expect("trade event");
+        buy.idx = "1-0".to_string();
+        let other = MemeEvent {
+            user: bs58_encode([4u8; 32]).into_string(),
+            ..buy.clone()
+        };
+        let create = MemeEvent {
+            event_type: TradeType::Create,
+            idx: "0-0".to_string(),
+            user: buy.user.clone(),
+            base_mint: buy.base_mint.clone(),
+            ..Default::default()
+        };
+        let mut events = vec![create, other, buy];
+        MemeEvent::link_dev_buys(&mut events);
+
+        let create = &events[0];
+        assert_eq!(create.creator.as_deref(), Some(events[2].user.as_str()));
+        assert_eq!(
+            create.fee_recipient,
+            Some(bs58_encode([3u8; 32]).into_string())
+        );
+        let dev_buy = create.dev_buy.as_ref().expect("dev buy");
+        assert_eq!(dev_buy.idx, "1-0");
+        assert_eq!(dev_buy.quote_token.amount_raw, "500000000");
+        assert_eq!(dev_buy.base_token.amount_raw, "12345600000");
+    }
+
+    #[test]
+    fn legacy_trade_event_has_no_fabricated_fee() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&[1u8; 32]); // mint
+        data.extend_from_slice(&500_000_000u64.to_le_bytes()); // sol_amount
+        data.extend_from_slice(&12_345_600_000u64.to_le_bytes()); // token_amount
+        data.push(1); // is_buy
+        data.extend_from_slice(&[2u8; 32]); // user
+        data.extend_from_slice(&0i64.to_le_bytes());
+        data.extend_from_slice(&40_000_000_000u64.to_le_bytes());
+        data.extend_from_slice(&800_000_000_000_000u64.to_le_bytes());
+
+        let event = PumpfunEventParser::new()
+            .decode_trade_event(&data)
+            .expect("trade event");
+        assert_eq!(event.fee, None);
+        assert!(crate::protocols::pumpfun::util::get_pumpfun_fees(&event).is_empty());
+    }
+
+    #[test]
+    fn migrate_event_exposes_migrated_liquidity() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&[1u8; 32]); // user
+        data.extend_from_slice(&[2u8; 32]); // mint
+        data.extend_from_slice(&206_900_000_000_000u64.to_le_bytes()); // mint_amount
+        data.extend_from_slice(&84_990_359_038u64.to_le_bytes()); // sol_amount
+        data.extend_from_slice(&15_000_001u64.to_le_bytes()); // pool_migration_fee
+        data.extend_from_slice(&[3u8; 32]); // bonding_curve
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        data.extend_from_slice(&[4u8; 32]); // pool
+
+        let event = PumpfunEventParser::new()
+            .decode_migrate_event(&data)
+            .expect("migrate event");
+
+        assert_eq!(event.event_type, TradeType::Migrate);
+        assert_eq!(
+            event.pool.as_deref(),
+            Some(bs58_encode([4u8; 32]).into_string().as_str())
+        );
+        assert_eq!(event.pool_dex.as_deref(), Some(PUMP_SWAP_PROGRAM_NAME));
+        assert_eq!(event.pool_a_reserve, Some(206_900_000.0));
+        assert!((event.pool_b_reserve.unwrap() - 84.990359038).abs() < 1e-9);
+        // комиссия миграции в SOL, как и резервы
+        assert_eq!(event.fee, Some(0.015000001));
+    }
+}
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::types::ClassifiedInstruction;
 
@@ -66,11 +68,11 @@ pub struct PumpfunMigrateInstruction {
 }
 
 pub struct PumpfunInstructionParser {
-    adapter: TransactionAdapter,
+    adapter: Arc<TransactionAdapter>,
 }
 
 impl PumpfunInstructionParser {
-    pub fn new(adapter: TransactionAdapter) -> Self {
+    pub fn new(adapter: Arc<TransactionAdapter>) -> Self {
         Self { adapter }
     }
 
@@ -153,11 +155,11 @@ impl PumpfunInstructionParser {
         let mut reader = BinaryReader::new(data);
         let accounts = &instruction.data.accounts;
         Ok(PumpfunTradeInstruction {
-            mint: accounts.get(2).cloned().unwrap_or_default(),
-            bonding_curve: accounts.get(3).cloned().unwrap_or_default(),
+            mint: accounts.get(2).map(ToString::to_string).unwrap_or_default(),
+            bonding_curve: accounts.get(3).map(ToString::to_string).unwrap_or_default(),
             token_amount: reader.read_u64()?,
             sol_amount: reader.read_u64()?,
-            user: accounts.get(6).cloned().unwrap_or_default(),
+            user: accounts.get(6).map(ToString::to_string).unwrap_or_default(),
         })
     }
 
@@ -172,9 +174,9 @@ impl PumpfunInstructionParser {
             name: reader.read_string()?,
             symbol: reader.read_string()?,
             uri: reader.read_string()?,
-            mint: accounts.first().cloned().unwrap_or_default(),
-            bonding_curve: accounts.get(2).cloned().unwrap_or_default(),
-            user: accounts.get(7).cloned().unwrap_or_default(),
+            mint: accounts.first().map(ToString::to_string).unwrap_or_default(),
+            bonding_curve: accounts.get(2).map(ToString::to_string).unwrap_or_default(),
+            user: accounts.get(7).map(ToString::to_string).unwrap_or_default(),
         })
     }
 
@@ -184,15 +186,15 @@ impl PumpfunInstructionParser {
     ) -> Result<PumpfunMigrateInstruction, PumpfunError> {
         let accounts = &instruction.data.accounts;
         Ok(PumpfunMigrateInstruction {
-            mint: accounts.get(2).cloned().unwrap_or_default(),
-            bonding_curve: accounts.get(3).cloned().unwrap_or_default(),
-            user: accounts.get(5).cloned().unwrap_or_default(),
-            pool_mint: accounts.get(9).cloned().unwrap_or_default(),
-            quote_mint: accounts.get(4).cloned().unwrap_or_default(),
-            lp_mint: accounts.get(15).cloned().unwrap_or_default(),
-            user_pool_token_account: accounts.get(16).cloned().unwrap_or_default(),
-            pool_base_token_account: accounts.get(17).cloned().unwrap_or_default(),
-            pool_quote_token_account: accounts.get(18).cloned().unwrap_or_default(),
+            mint: accounts.get(2).map(ToString::to_string).unwrap_or_default(),
+            bonding_curve: accounts.get(3).map(ToString::to_string).unwrap_or_default(),
+            user: accounts.get(5).map(ToString::to_string).unwrap_or_default(),
+            pool_mint: accounts.get(9).map(ToString::to_string).unwrap_or_default(),
+            quote_mint: accounts.get(4).map(ToString::to_string).unwrap_or_default(),
+            lp_mint: accounts.get(15).map(ToString::to_string).unwrap_or_default(),
+            user_pool_token_account: accounts.get(16).map(ToString::to_string).unwrap_or_default(),
+            pool_base_token_account: accounts.get(17).map(ToString::to_string).unwrap_or_default(),
+            pool_quote_token_account: accounts.get(18).map(ToString::to_string).unwrap_or_default(),
         })
     }
 }
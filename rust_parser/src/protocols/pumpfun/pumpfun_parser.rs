@@ -1,26 +1,37 @@
+use std::sync::Arc;
+
 use crate::core::instruction_classifier::InstructionClassifier;
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::protocols::simple::{MemeEventParser, TradeParser};
 use crate::types::{ClassifiedInstruction, DexInfo, MemeEvent, TradeInfo, TradeType, TransferMap};
 
-use super::constants::PUMP_FUN_PROGRAM_ID;
+use super::constants::{
+    discriminators::pumpfun_instructions, PUMP_FUN_PROGRAM_ID, PUMP_FUN_PROGRAM_NAME,
+    PUMP_FUN_TOKEN_DECIMALS, PUMP_SWAP_PROGRAM_NAME, SOL_MINT,
+};
 use super::error::PumpfunError;
 use super::pumpfun_event_parser::PumpfunEventParser;
-use super::util::{attach_token_transfers, get_pumpfun_trade_info};
+use super::pumpfun_instruction_parser::{
+    PumpfunCreateInstruction, PumpfunInstruction, PumpfunInstructionData, PumpfunInstructionParser,
+    PumpfunMigrateInstruction,
+};
+use super::util::{
+    attach_token_transfers, get_instruction_data, get_pumpfun_trade_info, sort_by_idx,
+};
 
 pub struct PumpfunParser {
-    adapter: TransactionAdapter,
+    adapter: Arc<TransactionAdapter>,
     dex_info: DexInfo,
-    transfer_actions: TransferMap,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
     event_parser: PumpfunEventParser,
 }
 
 impl PumpfunParser {
     pub fn new(
-        adapter: TransactionAdapter,
+        adapter: Arc<TransactionAdapter>,
         dex_info: DexInfo,
-        transfer_actions: TransferMap,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Self {
         // Оптимизация: event_parser не хранит адаптер, передаем по ссылке при вызове
@@ -63,12 +74,12 @@ impl TradeParser for PumpfunParser {
 }
 
 pub struct PumpfunMemeParser {
-    adapter: TransactionAdapter,
-    _transfer_actions: TransferMap,
+    adapter: Arc<TransactionAdapter>,
+    _transfer_actions: Arc<TransferMap>,
 }
 
 impl PumpfunMemeParser {
-    pub fn new(adapter: TransactionAdapter, transfer_actions: TransferMap) -> Self {
+    pub fn new(adapter: Arc<TransactionAdapter>, transfer_actions: Arc<TransferMap>) -> Self {
         Self {
             adapter,
             _transfer_actions: transfer_actions,
@@ -83,12 +94,113 @@ impl MemeEventParser for PumpfunMemeParser {
         let instructions = classifier.get_instructions(PUMP_FUN_PROGRAM_ID).to_vec();
         // Оптимизация: не клонируем адаптер, передаем по ссылке
         let parser = PumpfunEventParser::new();
-        match parser.parse_instructions(&self.adapter, &instructions) {
+        let events = match parser.parse_instructions(&self.adapter, &instructions) {
             Ok(events) => events,
             Err(err) => {
                 tracing::error!("failed to parse pumpfun meme events: {err}");
                 Vec::new()
             }
+        };
+        let mut events = self.append_lifecycle_fallback(events, &instructions);
+        MemeEvent::link_dev_buys(&mut events);
+        events
+    }
+}
+
+impl PumpfunMemeParser {
+    /// Create/Migrate без event-лога (обрезанные логи, старые транзакции)
+    /// восстанавливаем из самих инструкций.
+    fn append_lifecycle_fallback(
+        &self,
+        mut events: Vec<MemeEvent>,
+        instructions: &[ClassifiedInstruction],
+    ) -> Vec<MemeEvent> {
+        let has_lifecycle_ix = instructions.iter().any(|ix| {
+            get_instruction_data(&ix.data).is_ok_and(|data| {
+                data.len() >= 8
+                    && (data[..8] == pumpfun_instructions::CREATE
+                        || data[..8] == pumpfun_instructions::MIGRATE)
+            })
+        });
+        if !has_lifecycle_ix {
+            return events;
         }
+
+        let parsed = match PumpfunInstructionParser::new(self.adapter.clone())
+            .parse_instructions(instructions)
+        {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                tracing::debug!("failed to parse pumpfun instructions: {err}");
+                return events;
+            }
+        };
+
+        let mut added = false;
+        for instruction in parsed {
+            let event = match &instruction.data {
+                PumpfunInstructionData::Create(data) => build_create_event(&instruction, data),
+                PumpfunInstructionData::Migrate(data) => build_migrate_event(&instruction, data),
+                _ => continue,
+            };
+            let covered = events
+                .iter()
+                .any(|e| e.event_type == event.event_type && e.base_mint == event.base_mint);
+            if !covered {
+                events.push(event);
+                added = true;
+            }
+        }
+
+        if added {
+            sort_by_idx(events)
+        } else {
+            events
+        }
+    }
+}
+
+fn build_create_event(
+    instruction: &PumpfunInstruction,
+    data: &PumpfunCreateInstruction,
+) -> MemeEvent {
+    MemeEvent {
+        event_type: TradeType::Create,
+        timestamp: instruction.timestamp,
+        idx: instruction.idx.clone(),
+        slot: instruction.slot,
+        signature: instruction.signature.clone(),
+        user: data.user.clone(),
+        base_mint: data.mint.clone(),
+        quote_mint: SOL_MINT.to_string(),
+        name: Some(data.name.clone()),
+        symbol: Some(data.symbol.clone()),
+        uri: Some(data.uri.clone()),
+        decimals: Some(PUMP_FUN_TOKEN_DECIMALS),
+        protocol: Some(PUMP_FUN_PROGRAM_NAME.to_string()),
+        creator: Some(data.user.clone()),
+        bonding_curve: Some(data.bonding_curve.clone()),
+        ..Default::default()
+    }
+}
+
+fn build_migrate_event(
+    instruction: &PumpfunInstruction,
+    data: &PumpfunMigrateInstruction,
+) -> MemeEvent {
+    MemeEvent {
+        event_type: TradeType::Migrate,
+        timestamp: instruction.timestamp,
+        idx: instruction.idx.clone(),
+        slot: instruction.slot,
+        signature: instruction.signature.clone(),
+        user: data.user.clone(),
+        base_mint: data.mint.clone(),
+        quote_mint: SOL_MINT.to_string(),
+        protocol: Some(PUMP_FUN_PROGRAM_NAME.to_string()),
+        bonding_curve: Some(data.bonding_curve.clone()),
+        pool: Some(data.pool_mint.clone()),
+        pool_dex: Some(PUMP_SWAP_PROGRAM_NAME.to_string()),
+        ..Default::default()
     }
 }
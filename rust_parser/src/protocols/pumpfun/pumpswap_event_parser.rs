@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use crate::core::constants::dex_programs;
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::core::zc_adapter::ZcAdapter;
 use crate::core::zc_instruction_classifier::ZcClassifiedInstruction;
@@ -244,9 +245,63 @@ impl PumpswapEventParser {
             }
         }
 
+        // Event CPI лежат в meta.innerInstructions: zc-классификатор их не видит.
+        for (outer_index, inner_index, data) in Self::inner_pumpswap_data(adapter) {
+            if let Some(event) = Self::parse_instruction_data(
+                &data,
+                slot,
+                timestamp,
+                &signature_arc,
+                &signers_arc,
+                outer_index,
+                Some(inner_index),
+            )? {
+                events.push(event);
+            }
+        }
+
         Ok(sort_by_idx(events))
     }
-    
+
+    /// `(outer, inner, data)` inner-инструкций Pumpswap из meta JSON
+    /// (`programIdIndex` или `programId`, data в base58).
+    fn inner_pumpswap_data(adapter: &ZcAdapter) -> Vec<(usize, usize, Vec<u8>)> {
+        let Some(groups) = adapter.inner_instructions().and_then(|v| v.as_array()) else {
+            return Vec::new();
+        };
+        let is_pumpswap = |ix: &serde_json::Value| match ix
+            .get("programId")
+            .and_then(|v| v.as_str())
+        {
+            Some(program_id) => program_id == dex_programs::PUMP_SWAP,
+            None => ix
+                .get("programIdIndex")
+                .and_then(|v| v.as_u64())
+                .and_then(|index| adapter.account_key(index as usize))
+                .is_some_and(|key| bs58::encode(key).into_string() == dex_programs::PUMP_SWAP),
+        };
+
+        let mut result = Vec::new();
+        for group in groups {
+            let outer_index = group.get("index").and_then(|v| v.as_u64()).unwrap_or(0) as usize;
+            let Some(instructions) = group.get("instructions").and_then(|v| v.as_array()) else {
+                continue;
+            };
+            for (inner_index, ix) in instructions.iter().enumerate() {
+                if !is_pumpswap(ix) {
+                    continue;
+                }
+                let Some(data) = ix.get("data").and_then(|v| v.as_str()) else {
+                    continue;
+                };
+                if let Ok(data) = bs58::decode(data).into_vec() {
+                    result.push((outer_index, inner_index, data));
+                }
+            }
+        }
+        result
+    }
+
     /// Parse instruction data (shared logic for both zero-copy and owned versions)
     /// 
     /// # Arguments
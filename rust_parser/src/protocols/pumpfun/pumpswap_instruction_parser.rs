@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::types::ClassifiedInstruction;
 
@@ -40,11 +42,11 @@ pub struct PumpswapInstruction {
 }
 
 pub struct PumpswapInstructionParser {
-    adapter: TransactionAdapter,
+    adapter: Arc<TransactionAdapter>,
 }
 
 impl PumpswapInstructionParser {
-    pub fn new(adapter: TransactionAdapter) -> Self {
+    pub fn new(adapter: Arc<TransactionAdapter>) -> Self {
         Self { adapter }
     }
 
@@ -147,15 +149,15 @@ impl PumpswapInstructionParser {
             protocol_fee: reader.read_u64()?,
             quote_amount_in_with_lp_fee: reader.read_u64()?,
             user_quote_amount_in: reader.read_u64()?,
-            pool: accounts.first().cloned().unwrap_or_default(),
-            user: accounts.get(1).cloned().unwrap_or_default(),
-            user_base_token_account: accounts.get(5).cloned().unwrap_or_default(),
-            user_quote_token_account: accounts.get(6).cloned().unwrap_or_default(),
-            protocol_fee_recipient: accounts.get(9).cloned().unwrap_or_default(),
-            protocol_fee_recipient_token_account: accounts.get(10).cloned().unwrap_or_default(),
+            pool: accounts.first().map(ToString::to_string).unwrap_or_default(),
+            user: accounts.get(1).map(ToString::to_string).unwrap_or_default(),
+            user_base_token_account: accounts.get(5).map(ToString::to_string).unwrap_or_default(),
+            user_quote_token_account: accounts.get(6).map(ToString::to_string).unwrap_or_default(),
+            protocol_fee_recipient: accounts.get(9).map(ToString::to_string).unwrap_or_default(),
+            protocol_fee_recipient_token_account: accounts.get(10).map(ToString::to_string).unwrap_or_default(),
             coin_creator: accounts
                 .get(11)
-                .cloned()
+                .map(ToString::to_string)
                 .unwrap_or_else(|| "11111111111111111111111111111111".to_string()),
             coin_creator_fee_basis_points: reader.read_u64().unwrap_or(0),
             coin_creator_fee: reader.read_u64().unwrap_or(0),
@@ -184,15 +186,15 @@ impl PumpswapInstructionParser {
             protocol_fee: reader.read_u64()?,
             quote_amount_out_without_lp_fee: reader.read_u64()?,
             user_quote_amount_out: reader.read_u64()?,
-            pool: accounts.first().cloned().unwrap_or_default(),
-            user: accounts.get(1).cloned().unwrap_or_default(),
-            user_base_token_account: accounts.get(5).cloned().unwrap_or_default(),
-            user_quote_token_account: accounts.get(6).cloned().unwrap_or_default(),
-            protocol_fee_recipient: accounts.get(9).cloned().unwrap_or_default(),
-            protocol_fee_recipient_token_account: accounts.get(10).cloned().unwrap_or_default(),
+            pool: accounts.first().map(ToString::to_string).unwrap_or_default(),
+            user: accounts.get(1).map(ToString::to_string).unwrap_or_default(),
+            user_base_token_account: accounts.get(5).map(ToString::to_string).unwrap_or_default(),
+            user_quote_token_account: accounts.get(6).map(ToString::to_string).unwrap_or_default(),
+            protocol_fee_recipient: accounts.get(9).map(ToString::to_string).unwrap_or_default(),
+            protocol_fee_recipient_token_account: accounts.get(10).map(ToString::to_string).unwrap_or_default(),
             coin_creator: accounts
                 .get(11)
-                .cloned()
+                .map(ToString::to_string)
                 .unwrap_or_else(|| "11111111111111111111111111111111".to_string()),
             coin_creator_fee_basis_points: reader.read_u64().unwrap_or(0),
             coin_creator_fee: reader.read_u64().unwrap_or(0),
@@ -218,11 +220,11 @@ impl PumpswapInstructionParser {
             base_amount_in: reader.read_u64()?,
             quote_amount_in: reader.read_u64()?,
             lp_mint_supply: reader.read_u64()?,
-            pool: accounts.first().cloned().unwrap_or_default(),
-            user: accounts.get(2).cloned().unwrap_or_default(),
-            user_base_token_account: accounts.get(6).cloned().unwrap_or_default(),
-            user_quote_token_account: accounts.get(7).cloned().unwrap_or_default(),
-            user_pool_token_account: accounts.get(8).cloned().unwrap_or_default(),
+            pool: accounts.first().map(ToString::to_string).unwrap_or_default(),
+            user: accounts.get(2).map(ToString::to_string).unwrap_or_default(),
+            user_base_token_account: accounts.get(6).map(ToString::to_string).unwrap_or_default(),
+            user_quote_token_account: accounts.get(7).map(ToString::to_string).unwrap_or_default(),
+            user_pool_token_account: accounts.get(8).map(ToString::to_string).unwrap_or_default(),
         })
     }
 
@@ -237,9 +239,9 @@ impl PumpswapInstructionParser {
         Ok(PumpswapCreatePoolEvent {
             timestamp: reader.read_i64()? as u64,
             index: 0,
-            creator: accounts.get(2).cloned().unwrap_or_default(),
-            base_mint: accounts.get(3).cloned().unwrap_or_default(),
-            quote_mint: accounts.get(4).cloned().unwrap_or_default(),
+            creator: accounts.get(2).map(ToString::to_string).unwrap_or_default(),
+            base_mint: accounts.get(3).map(ToString::to_string).unwrap_or_default(),
+            quote_mint: accounts.get(4).map(ToString::to_string).unwrap_or_default(),
             base_mint_decimals: reader.read_u8()?,
             quote_mint_decimals: reader.read_u8()?,
             base_amount_in: reader.read_u64()?,
@@ -250,10 +252,10 @@ impl PumpswapInstructionParser {
             initial_liquidity: reader.read_u64()?,
             lp_token_amount_out: reader.read_u64()?,
             pool_bump: reader.read_u8()?,
-            pool: accounts.first().cloned().unwrap_or_default(),
-            lp_mint: accounts.get(5).cloned().unwrap_or_default(),
-            user_base_token_account: accounts.get(6).cloned().unwrap_or_default(),
-            user_quote_token_account: accounts.get(7).cloned().unwrap_or_default(),
+            pool: accounts.first().map(ToString::to_string).unwrap_or_default(),
+            lp_mint: accounts.get(5).map(ToString::to_string).unwrap_or_default(),
+            user_base_token_account: accounts.get(6).map(ToString::to_string).unwrap_or_default(),
+            user_quote_token_account: accounts.get(7).map(ToString::to_string).unwrap_or_default(),
         })
     }
 
@@ -276,11 +278,11 @@ impl PumpswapInstructionParser {
             base_amount_out: reader.read_u64()?,
             quote_amount_out: reader.read_u64()?,
             lp_mint_supply: reader.read_u64()?,
-            pool: accounts.first().cloned().unwrap_or_default(),
-            user: accounts.get(2).cloned().unwrap_or_default(),
-            user_base_token_account: accounts.get(6).cloned().unwrap_or_default(),
-            user_quote_token_account: accounts.get(7).cloned().unwrap_or_default(),
-            user_pool_token_account: accounts.get(8).cloned().unwrap_or_default(),
+            pool: accounts.first().map(ToString::to_string).unwrap_or_default(),
+            user: accounts.get(2).map(ToString::to_string).unwrap_or_default(),
+            user_base_token_account: accounts.get(6).map(ToString::to_string).unwrap_or_default(),
+            user_quote_token_account: accounts.get(7).map(ToString::to_string).unwrap_or_default(),
+            user_pool_token_account: accounts.get(8).map(ToString::to_string).unwrap_or_default(),
         })
     }
 }
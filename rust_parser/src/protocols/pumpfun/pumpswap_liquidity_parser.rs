@@ -1,32 +1,83 @@
+use std::sync::Arc;
+
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::protocols::simple::LiquidityParser;
-use crate::types::{ClassifiedInstruction, PoolEvent, TradeType, TransferMap};
+use crate::types::{
+    ClassifiedInstruction, LegDirection, LiquiditySlippage, PoolEvent, TokenLeg, TradeType,
+    TransferData, TransferMap,
+};
 
-use super::constants::{PUMP_SWAP_PROGRAM_ID, PUMP_SWAP_PROGRAM_NAME};
+use super::constants::discriminators::pumpswap_instructions;
+use super::constants::{PUMP_SWAP_LP_DECIMALS, PUMP_SWAP_PROGRAM_ID, PUMP_SWAP_PROGRAM_NAME};
 use super::pumpswap_event_parser::{
     PumpswapCreatePoolEvent, PumpswapDepositEvent, PumpswapEvent, PumpswapEventData,
     PumpswapEventParser, PumpswapWithdrawEvent,
 };
-use super::util::convert_to_ui_amount;
+use super::util::{convert_to_ui_amount, get_instruction_data};
+
+// accounts deposit/withdraw: pool, global_config, user, base_mint, quote_mint, lp_mint,
+// user base/quote/pool token accounts, pool base/quote token accounts, ...
+const POOL: usize = 0;
+const BASE_MINT: usize = 3;
+const QUOTE_MINT: usize = 4;
+const LP_MINT: usize = 5;
+const POOL_BASE_TOKEN_ACCOUNT: usize = 9;
+const POOL_QUOTE_TOKEN_ACCOUNT: usize = 10;
+// accounts collect_coin_creator_fee: quote_mint, quote_token_program, coin_creator, ...
+const COIN_CREATOR: usize = 2;
+
+/// Аргументы deposit/withdraw (raw).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PumpswapLiquidityArgs {
+    /// `lp_token_amount_out` для deposit, `lp_token_amount_in` для withdraw.
+    pub lp_token_amount: u64,
+    /// `max_base_amount_in` для deposit, `min_base_amount_out` для withdraw.
+    pub base_limit: u64,
+    pub quote_limit: u64,
+}
+
+/// deposit (`true`) или withdraw (`false`) с аргументами.
+pub fn decode_liquidity_args(data: &[u8]) -> Option<(bool, PumpswapLiquidityArgs)> {
+    let is_add = match data.get(..8)? {
+        d if d == pumpswap_instructions::ADD_LIQUIDITY => true,
+        d if d == pumpswap_instructions::REMOVE_LIQUIDITY => false,
+        _ => return None,
+    };
+    let u64_at = |offset: usize| -> Option<u64> {
+        Some(u64::from_le_bytes(
+            data.get(offset..offset + 8)?.try_into().ok()?,
+        ))
+    };
+    Some((
+        is_add,
+        PumpswapLiquidityArgs {
+            lp_token_amount: u64_at(8)?,
+            base_limit: u64_at(16)?,
+            quote_limit: u64_at(24)?,
+        },
+    ))
+}
+
+type LiquidityInstruction<'a> = (&'a ClassifiedInstruction, bool, PumpswapLiquidityArgs);
 
 pub struct PumpswapLiquidityParser {
-    adapter: TransactionAdapter,
-    _transfer_actions: TransferMap,
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
     event_parser: PumpswapEventParser,
 }
 
 impl PumpswapLiquidityParser {
     pub fn new(
-        adapter: TransactionAdapter,
-        transfer_actions: TransferMap,
+        adapter: Arc<TransactionAdapter>,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Self {
         // Event parser больше не хранит адаптер
         let event_parser = PumpswapEventParser::new();
         Self {
             adapter,
-            _transfer_actions: transfer_actions,
+            transfer_actions,
             classified_instructions,
             event_parser,
         }
@@ -38,7 +89,7 @@ impl PumpswapLiquidityParser {
             .parse_instructions(&self.adapter, &self.classified_instructions)
         {
             Ok(events) => events,
-            Err(_) => Vec::new()
+            Err(_) => Vec::new(),
         }
     }
 
@@ -60,27 +111,30 @@ impl PumpswapLiquidityParser {
             pool_id: data.pool.clone(),
             config: None,
             pool_lp_mint: Some(data.lp_mint.clone()),
-            token0_mint: Some(data.base_mint.clone()),
-            token0_amount: Some(convert_to_ui_amount(
-                data.base_amount_in as u128,
-                data.base_mint_decimals,
-            )),
-            token0_amount_raw: Some(data.base_amount_in.to_string()),
-            token0_balance_change: None,
-            token0_decimals: Some(data.base_mint_decimals),
-            token1_mint: Some(data.quote_mint.clone()),
-            token1_amount: Some(convert_to_ui_amount(
-                data.quote_amount_in as u128,
-                data.quote_mint_decimals,
-            )),
-            token1_amount_raw: Some(data.quote_amount_in.to_string()),
-            token1_balance_change: None,
-            token1_decimals: Some(data.quote_mint_decimals),
             lp_amount: Some(convert_to_ui_amount(
                 data.lp_token_amount_out as u128,
                 data.base_mint_decimals,
             )),
             lp_amount_raw: Some(data.lp_token_amount_out.to_string()),
+            tokens: vec![
+                TokenLeg::from_raw(
+                    data.base_mint.clone(),
+                    data.base_amount_in as u128,
+                    data.base_mint_decimals,
+                    LegDirection::In,
+                ),
+                TokenLeg::from_raw(
+                    data.quote_mint.clone(),
+                    data.quote_amount_in as u128,
+                    data.quote_mint_decimals,
+                    LegDirection::In,
+                ),
+            ],
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
         }
     }
 
@@ -125,27 +179,30 @@ impl PumpswapLiquidityParser {
             pool_id: data.pool.clone(),
             config: None,
             pool_lp_mint: Some(lp_info.mint.clone()),
-            token0_mint: Some(token0_info.mint.clone()),
-            token0_amount: Some(convert_to_ui_amount(
-                data.base_amount_in as u128,
-                token0_decimals,
-            )),
-            token0_amount_raw: Some(data.base_amount_in.to_string()),
-            token0_balance_change: None,
-            token0_decimals: Some(token0_decimals),
-            token1_mint: Some(token1_info.mint.clone()),
-            token1_amount: Some(convert_to_ui_amount(
-                data.quote_amount_in as u128,
-                token1_decimals,
-            )),
-            token1_amount_raw: Some(data.quote_amount_in.to_string()),
-            token1_balance_change: None,
-            token1_decimals: Some(token1_decimals),
             lp_amount: Some(convert_to_ui_amount(
                 data.lp_token_amount_out as u128,
                 lp_decimals,
             )),
             lp_amount_raw: Some(data.lp_token_amount_out.to_string()),
+            tokens: vec![
+                TokenLeg::from_raw(
+                    token0_info.mint.clone(),
+                    data.base_amount_in as u128,
+                    token0_decimals,
+                    LegDirection::In,
+                ),
+                TokenLeg::from_raw(
+                    token1_info.mint.clone(),
+                    data.quote_amount_in as u128,
+                    token1_decimals,
+                    LegDirection::In,
+                ),
+            ],
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
         })
     }
 
@@ -190,27 +247,209 @@ impl PumpswapLiquidityParser {
             pool_id: data.pool.clone(),
             config: None,
             pool_lp_mint: Some(lp_info.mint.clone()),
-            token0_mint: Some(token0_info.mint.clone()),
-            token0_amount: Some(convert_to_ui_amount(
-                data.base_amount_out as u128,
-                token0_decimals,
-            )),
-            token0_amount_raw: Some(data.base_amount_out.to_string()),
-            token0_balance_change: None,
-            token0_decimals: Some(token0_decimals),
-            token1_mint: Some(token1_info.mint.clone()),
-            token1_amount: Some(convert_to_ui_amount(
-                data.quote_amount_out as u128,
-                token1_decimals,
-            )),
-            token1_amount_raw: Some(data.quote_amount_out.to_string()),
-            token1_balance_change: None,
-            token1_decimals: Some(token1_decimals),
             lp_amount: Some(convert_to_ui_amount(
                 data.lp_token_amount_in as u128,
                 lp_decimals,
             )),
             lp_amount_raw: Some(data.lp_token_amount_in.to_string()),
+            tokens: vec![
+                TokenLeg::from_raw(
+                    token0_info.mint.clone(),
+                    data.base_amount_out as u128,
+                    token0_decimals,
+                    LegDirection::Out,
+                ),
+                TokenLeg::from_raw(
+                    token1_info.mint.clone(),
+                    data.quote_amount_out as u128,
+                    token1_decimals,
+                    LegDirection::Out,
+                ),
+            ],
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
+        })
+    }
+}
+
+impl PumpswapLiquidityParser {
+    fn liquidity_instructions(&self) -> Vec<Option<LiquidityInstruction<'_>>> {
+        self.classified_instructions
+            .iter()
+            .filter_map(|classified| {
+                let data = get_instruction_data(&classified.data).ok()?;
+                let (is_add, args) = decode_liquidity_args(&data)?;
+                Some(Some((classified, is_add, args)))
+            })
+            .collect()
+    }
+
+    /// Инструкция deposit/withdraw, после которой эмитнут event.
+    fn take_instruction<'a>(
+        instructions: &mut [Option<LiquidityInstruction<'a>>],
+        event: &PumpswapEvent,
+        is_add: bool,
+    ) -> Option<LiquidityInstruction<'a>> {
+        let slot = instructions.iter_mut().find(|slot| {
+            slot.is_some_and(|(classified, add, _)| {
+                add == is_add
+                    && classified.outer_index == usize::from(event.idx_outer)
+                    && classified
+                        .inner_index
+                        .is_none_or(|inner| inner < usize::from(event.idx_inner))
+            })
+        })?;
+        slot.take()
+    }
+
+    fn instruction_transfers(&self, classified: &ClassifiedInstruction) -> &[TransferData] {
+        let key = match classified.inner_index {
+            Some(inner) => format!(
+                "{}:{}-{}",
+                PUMP_SWAP_PROGRAM_ID, classified.outer_index, inner
+            ),
+            None => format!("{}:{}", PUMP_SWAP_PROGRAM_ID, classified.outer_index),
+        };
+        self.transfer_actions
+            .get(&key)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// `collect_coin_creator_fee`: creator забирает накопленную комиссию из vault'а.
+    /// Пул среди аккаунтов не передаётся, `pool_id` пустой.
+    fn parse_collect_fee(&self, classified: &ClassifiedInstruction) -> Option<PoolEvent> {
+        let data = get_instruction_data(&classified.data).ok()?;
+        if data.get(..8)? != pumpswap_instructions::COLLECT_COIN_CREATOR_FEE {
+            return None;
+        }
+        let tokens: Vec<TokenLeg> = self
+            .instruction_transfers(classified)
+            .iter()
+            .map(|transfer| TokenLeg::from_transfer(transfer, LegDirection::Out))
+            .collect();
+        if tokens.is_empty() {
+            return None;
+        }
+        let accounts = self.adapter.get_instruction_accounts(&classified.data);
+
+        Some(PoolEvent {
+            user: accounts
+                .get(COIN_CREATOR)
+                .map(ToString::to_string)
+                .unwrap_or_else(|| self.adapter.signer().to_string()),
+            event_type: TradeType::CollectFee,
+            program_id: Some(PUMP_SWAP_PROGRAM_ID.to_string()),
+            amm: Some(PUMP_SWAP_PROGRAM_NAME.to_string()),
+            slot: self.adapter.slot(),
+            timestamp: self.adapter.block_time(),
+            signature: self.adapter.signature().to_string(),
+            idx: format!(
+                "{}-{}",
+                classified.outer_index,
+                classified.inner_index.unwrap_or(0)
+            ),
+            signer: Some(self.adapter.signers().to_vec()),
+            pool_id: String::new(),
+            config: None,
+            pool_lp_mint: None,
+            lp_amount: None,
+            lp_amount_raw: None,
+            tokens,
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
+        })
+    }
+
+    /// Событие без event-лога: суммы по переводам в vault'ы пула (add) или из них (remove).
+    /// Переводы signer'а на свой WSOL-аккаунт при auto-wrap сюда не попадают.
+    fn parse_from_transfers(
+        &self,
+        (classified, is_add, args): LiquidityInstruction<'_>,
+    ) -> Option<PoolEvent> {
+        let accounts = self.adapter.get_instruction_accounts(&classified.data);
+        let transfers = self.instruction_transfers(classified);
+        let direction = if is_add {
+            LegDirection::In
+        } else {
+            LegDirection::Out
+        };
+        let leg = |vault: usize, mint: usize| -> Option<(TokenLeg, u64)> {
+            let vault = accounts.get(vault)?;
+            let mint = accounts.get(mint)?;
+            let mut amount = 0u64;
+            let mut decimals = self.adapter.token_decimals(mint);
+            for transfer in transfers {
+                let account = if is_add {
+                    &transfer.info.destination
+                } else {
+                    &transfer.info.source
+                };
+                if account == vault {
+                    amount += transfer
+                        .info
+                        .token_amount
+                        .amount
+                        .parse::<u64>()
+                        .unwrap_or(0);
+                    decimals = decimals.or(Some(transfer.info.token_amount.decimals));
+                }
+            }
+            let leg =
+                TokenLeg::from_raw(mint.to_string(), u128::from(amount), decimals?, direction);
+            Some((leg, amount))
+        };
+        let (base, base_amount) = leg(POOL_BASE_TOKEN_ACCOUNT, BASE_MINT)?;
+        let (quote, quote_amount) = leg(POOL_QUOTE_TOKEN_ACCOUNT, QUOTE_MINT)?;
+        let lp_mint = accounts.get(LP_MINT).map(ToString::to_string);
+        let lp_decimals = lp_mint
+            .as_deref()
+            .and_then(|mint| self.adapter.token_decimals(mint))
+            .unwrap_or(PUMP_SWAP_LP_DECIMALS);
+
+        Some(PoolEvent {
+            user: self.adapter.signer().to_string(),
+            event_type: if is_add {
+                TradeType::Add
+            } else {
+                TradeType::Remove
+            },
+            program_id: Some(PUMP_SWAP_PROGRAM_ID.to_string()),
+            amm: Some(PUMP_SWAP_PROGRAM_NAME.to_string()),
+            slot: self.adapter.slot(),
+            timestamp: self.adapter.block_time(),
+            signature: self.adapter.signature().to_string(),
+            idx: format!(
+                "{}-{}",
+                classified.outer_index,
+                classified.inner_index.unwrap_or(0)
+            ),
+            signer: Some(self.adapter.signers().to_vec()),
+            pool_id: accounts
+                .get(POOL)
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            config: None,
+            pool_lp_mint: lp_mint,
+            lp_amount: Some(convert_to_ui_amount(args.lp_token_amount, lp_decimals)),
+            lp_amount_raw: Some(args.lp_token_amount.to_string()),
+            tokens: vec![base, quote],
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: Some(LiquiditySlippage::new(
+                args.lp_token_amount,
+                &[args.base_limit, args.quote_limit],
+                &[base_amount, quote_amount],
+                is_add,
+            )),
+            order_key: None,
         })
     }
 }
@@ -218,28 +457,172 @@ impl PumpswapLiquidityParser {
 impl LiquidityParser for PumpswapLiquidityParser {
     fn process_liquidity(&mut self) -> Vec<PoolEvent> {
         let parsed_events = self.parse_events();
+        let mut instructions = self.liquidity_instructions();
         let mut events = Vec::with_capacity(parsed_events.len());
-        
+
         for event in parsed_events {
             match &event.data {
                 PumpswapEventData::Create(data) => {
                     events.push(self.parse_create_event(&event, data));
                 }
                 PumpswapEventData::Deposit(data) => {
-                    if let Some(pool) = self.parse_deposit_event(&event, data) {
+                    if let Some(mut pool) = self.parse_deposit_event(&event, data) {
+                        if let Some((_, _, args)) =
+                            Self::take_instruction(&mut instructions, &event, true)
+                        {
+                            pool.slippage = Some(LiquiditySlippage::new(
+                                args.lp_token_amount,
+                                &[args.base_limit, args.quote_limit],
+                                &[data.base_amount_in, data.quote_amount_in],
+                                true,
+                            ));
+                        }
                         events.push(pool);
                     }
                 }
                 PumpswapEventData::Withdraw(data) => {
-                    if let Some(pool) = self.parse_withdraw_event(&event, data) {
+                    if let Some(mut pool) = self.parse_withdraw_event(&event, data) {
+                        if let Some((_, _, args)) =
+                            Self::take_instruction(&mut instructions, &event, false)
+                        {
+                            pool.slippage = Some(LiquiditySlippage::new(
+                                args.lp_token_amount,
+                                &[args.base_limit, args.quote_limit],
+                                &[data.base_amount_out, data.quote_amount_out],
+                                false,
+                            ));
+                        }
                         events.push(pool);
                     }
                 }
                 _ => {}
             }
         }
-        
+
+        // deposit/withdraw без event'а (обрезанные логи) — по аргументам и переводам
+        events.extend(
+            instructions
+                .into_iter()
+                .flatten()
+                .filter_map(|instruction| self.parse_from_transfers(instruction)),
+        );
+        events.extend(
+            self.classified_instructions
+                .iter()
+                .filter_map(|classified| self.parse_collect_fee(classified)),
+        );
+
         events
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ParseConfig;
+    use crate::protocols::pumpfun::constants::SOL_MINT;
+    use crate::types::{SolanaInstruction, SolanaTransaction, TokenAmount, TransferInfo};
+    use base64_simd::STANDARD;
+
+    fn transfer(
+        source: &str,
+        destination: &str,
+        mint: &str,
+        amount: u64,
+        decimals: u8,
+    ) -> TransferData {
+        TransferData {
+            transfer_type: "transferChecked".to_string(),
+            program_id: "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA".into(),
+            info: TransferInfo {
+                authority: Some("user".into()),
+                destination: destination.into(),
+                destination_owner: None,
+                mint: mint.into(),
+                source: source.into(),
+                token_amount: TokenAmount::new(amount.to_string(), decimals, None),
+                source_balance: None,
+                source_pre_balance: None,
+                destination_balance: None,
+                destination_pre_balance: None,
+                sol_balance_change: None,
+            },
+            idx: "1-0".to_string(),
+            timestamp: 0,
+            signature: String::new(),
+            is_fee: false,
+            fee_amount: None,
+            instruction_index: 0,
+            inner_index: None,
+            order_key: None,
+        }
+    }
+
+    #[test]
+    fn deposit_without_event_uses_args_and_vault_transfers() {
+        let mut data = pumpswap_instructions::ADD_LIQUIDITY.to_vec();
+        data.extend_from_slice(&5_000_000_000u64.to_le_bytes()); // lp_token_amount_out
+        data.extend_from_slice(&1_010_000u64.to_le_bytes()); // max_base_amount_in
+        data.extend_from_slice(&2_040_000_000u64.to_le_bytes()); // max_quote_amount_in
+        let accounts = [
+            "pool",
+            "config",
+            "user",
+            "BASE",
+            SOL_MINT,
+            "LP",
+            "user-base",
+            "user-wsol",
+            "user-lp",
+            "pool-base",
+            "pool-quote",
+        ];
+        let instruction = SolanaInstruction {
+            program_id: PUMP_SWAP_PROGRAM_ID.into(),
+            accounts: accounts.iter().map(|a| (*a).into()).collect(),
+            data: STANDARD.encode_to_string(&data),
+        };
+        let tx = SolanaTransaction {
+            signers: vec!["user".to_string()],
+            instructions: vec![instruction.clone()],
+            ..Default::default()
+        };
+        let transfers: TransferMap = [(
+            format!("{}:1", PUMP_SWAP_PROGRAM_ID),
+            vec![
+                // auto-wrap SOL на свой WSOL-аккаунт не должен попасть в сумму
+                transfer("user", "user-wsol", SOL_MINT, 2_040_000_000, 9),
+                transfer("user-base", "pool-base", "BASE", 1_000_000, 6),
+                transfer("user-wsol", "pool-quote", SOL_MINT, 2_000_000_000, 9),
+            ],
+        )]
+        .into();
+
+        let mut parser = PumpswapLiquidityParser::new(
+            Arc::new(TransactionAdapter::new(tx, ParseConfig::default())),
+            Arc::new(transfers),
+            vec![ClassifiedInstruction {
+                program_id: PUMP_SWAP_PROGRAM_ID.into(),
+                outer_index: 1,
+                inner_index: None,
+                data: instruction,
+                data_bytes: Vec::new(),
+                stack_height: None,
+                decoded: None,
+            }],
+        );
+        let events = parser.process_liquidity();
+
+        assert_eq!(events.len(), 1);
+        let event = &events[0];
+        assert_eq!(event.event_type, TradeType::Add);
+        assert_eq!(event.pool_id, "pool");
+        assert_eq!(event.lp_amount_raw.as_deref(), Some("5000000000"));
+        assert_eq!(event.token0().unwrap().amount_raw, "1000000");
+        assert_eq!(event.token1().unwrap().amount_raw, "2000000000");
+        let slippage = event.slippage.as_ref().unwrap();
+        assert_eq!(slippage.limits_raw, ["1010000", "2040000000"]);
+        // base: +1%, quote: +2% — жёстче base
+        assert_eq!(slippage.slippage_bps, Some(100));
+    }
+}
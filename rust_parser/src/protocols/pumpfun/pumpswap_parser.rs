@@ -1,3 +1,4 @@
+use std::sync::Arc;
 use std::collections::HashMap;
 
 use crate::core::transaction_adapter::TransactionAdapter;
@@ -7,21 +8,23 @@ use crate::types::{ClassifiedInstruction, DexInfo, TradeInfo, TransferMap};
 use super::pumpswap_event_parser::{
     PumpswapEvent, PumpswapEventData, PumpswapEventParser, PumpswapEventType,
 };
-use super::util::{attach_token_transfers, build_pumpswap_buy_trade, build_pumpswap_sell_trade};
+use super::util::{
+    attach_token_transfers, build_pumpswap_buy_trade, build_pumpswap_sell_trade, pumpswap_pool_reserves,
+};
 
 pub struct PumpswapParser {
-    adapter: TransactionAdapter,
+    adapter: Arc<TransactionAdapter>,
     dex_info: DexInfo,
-    transfer_actions: TransferMap,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
     event_parser: PumpswapEventParser,
 }
 
 impl PumpswapParser {
     pub fn new(
-        adapter: TransactionAdapter,
+        adapter: Arc<TransactionAdapter>,
         dex_info: DexInfo,
-        transfer_actions: TransferMap,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Self {
         // Event parser больше не хранит адаптер - это статическая структура
@@ -108,7 +111,7 @@ impl PumpswapParser {
                 transfer_map.get(buy.user_quote_token_account.as_str())
                     .map(|t| {
                         crate::types::TokenInfo {
-                            mint: t.info.mint.clone(),
+                            mint: t.info.mint.to_string(),
                             amount: t.info.token_amount.ui_amount.unwrap_or(0.0),
                             amount_raw: t.info.token_amount.amount.clone(),
                             decimals: t.info.token_amount.decimals,
@@ -123,7 +126,7 @@ impl PumpswapParser {
                 // Оптимизация: минимум логирования, быстрый fallback
                 // Try to find mint from transfers using cached map
                 let inferred_mint = transfer_map.get(buy.user_quote_token_account.as_str())
-                    .map(|t| t.info.mint.clone())
+                    .map(|t| t.info.mint.to_string())
                     .or_else(|| {
                         // Try to infer from other quote token accounts (SOL, USDC, USDT are common quote tokens)
                         post_balance_map.values()
@@ -183,7 +186,7 @@ impl PumpswapParser {
                 transfer_map.get(buy.user_base_token_account.as_str())
                     .map(|t| {
                         crate::types::TokenInfo {
-                            mint: t.info.mint.clone(),
+                            mint: t.info.mint.to_string(),
                             amount: t.info.token_amount.ui_amount.unwrap_or(0.0),
                             amount_raw: t.info.token_amount.amount.clone(),
                             decimals: t.info.token_amount.decimals,
@@ -217,7 +220,7 @@ impl PumpswapParser {
             None => {
                 // Оптимизация: быстрый fallback без избыточного логирования
                 let inferred_mint = transfer_map.get(buy.user_base_token_account.as_str())
-                    .map(|t| t.info.mint.clone());
+                    .map(|t| t.info.mint.to_string());
                 
                 if let Some(mint) = inferred_mint {
                     let decimals = self.adapter.get_token_decimals(&mint);
@@ -265,7 +268,7 @@ impl PumpswapParser {
         let output_decimals = self.decimals_or(&output_info.mint, output_info.decimals);
         let fee_decimals = self.decimals_or(&fee_info.mint, fee_info.decimals);
 
-        let trade = build_pumpswap_buy_trade(
+        let mut trade = build_pumpswap_buy_trade(
             event,
             buy,
             (&input_info.mint, input_decimals),
@@ -273,6 +276,7 @@ impl PumpswapParser {
             (&fee_info.mint, fee_decimals),
             &self.dex_info,
         );
+        trade.pool_reserves = pumpswap_pool_reserves(post_balance_map, &trade);
 
         Some(attach_token_transfers(
             &self.adapter,
@@ -322,7 +326,7 @@ impl PumpswapParser {
                 transfer_map.get(sell.user_base_token_account.as_str())
                     .map(|t| {
                         crate::types::TokenInfo {
-                            mint: t.info.mint.clone(),
+                            mint: t.info.mint.to_string(),
                             amount: t.info.token_amount.ui_amount.unwrap_or(0.0),
                             amount_raw: t.info.token_amount.amount.clone(),
                             decimals: t.info.token_amount.decimals,
@@ -337,7 +341,7 @@ impl PumpswapParser {
                 // Try to infer from other base token accounts using cached maps
                 // For SELL, input is base token. Look for base mint from other accounts
                 let inferred_mint = transfer_map.get(sell.user_base_token_account.as_str())
-                    .map(|t| t.info.mint.clone())
+                    .map(|t| t.info.mint.to_string())
                     .or_else(|| {
                         // Try to find from post_token_balances using cached map
                         post_balance_map.values()
@@ -412,7 +416,7 @@ impl PumpswapParser {
                 transfer_map.get(sell.user_quote_token_account.as_str())
                     .map(|t| {
                         crate::types::TokenInfo {
-                            mint: t.info.mint.clone(),
+                            mint: t.info.mint.to_string(),
                             amount: t.info.token_amount.ui_amount.unwrap_or(0.0),
                             amount_raw: t.info.token_amount.amount.clone(),
                             decimals: t.info.token_amount.decimals,
@@ -447,7 +451,7 @@ impl PumpswapParser {
                 transfer_map.get(sell.protocol_fee_recipient_token_account.as_str())
                     .map(|t| {
                         crate::types::TokenInfo {
-                            mint: t.info.mint.clone(),
+                            mint: t.info.mint.to_string(),
                             amount: 0.0,
                             amount_raw: "0".to_string(),
                             decimals: t.info.token_amount.decimals,
@@ -470,7 +474,7 @@ impl PumpswapParser {
         let output_decimals = self.decimals_or(&output_info.mint, output_info.decimals);
         let fee_decimals = self.decimals_or(&fee_info.mint, fee_info.decimals);
 
-        let trade = build_pumpswap_sell_trade(
+        let mut trade = build_pumpswap_sell_trade(
             event,
             sell,
             (&input_info.mint, input_decimals),
@@ -478,6 +482,7 @@ impl PumpswapParser {
             (&fee_info.mint, fee_decimals),
             &self.dex_info,
         );
+        trade.pool_reserves = pumpswap_pool_reserves(post_balance_map, &trade);
 
         Some(attach_token_transfers(
             &self.adapter,
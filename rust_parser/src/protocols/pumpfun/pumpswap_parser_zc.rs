@@ -13,7 +13,7 @@ use crate::types::{DexInfo, TradeInfo, TransferMap};
 use super::pumpswap_event_parser::{
     PumpswapEvent, PumpswapEventData, PumpswapEventParser, PumpswapEventType,
 };
-use super::util::{build_pumpswap_buy_trade, build_pumpswap_sell_trade};
+use super::util::{build_pumpswap_buy_trade, build_pumpswap_sell_trade, pumpswap_pool_reserves};
 
 /// Process Pumpswap trades using zero-copy structures
 /// 
@@ -146,7 +146,7 @@ fn create_buy_trade_zc(
             transfer_map.get(buy.user_quote_token_account.as_str())
                 .map(|t| {
                     crate::types::TokenInfo {
-                        mint: t.info.mint.clone(),
+                        mint: t.info.mint.to_string(),
                         amount: t.info.token_amount.ui_amount.unwrap_or(0.0),
                         amount_raw: t.info.token_amount.amount.clone(),
                         decimals: t.info.token_amount.decimals,
@@ -160,7 +160,7 @@ fn create_buy_trade_zc(
         None => {
             // Try to infer from transfers or common quote tokens
             let inferred_mint = transfer_map.get(buy.user_quote_token_account.as_str())
-                .map(|t| t.info.mint.clone())
+                .map(|t| t.info.mint.to_string())
                 .or_else(|| {
                     post_balance_map.values()
                         .find(|b| {
@@ -218,7 +218,7 @@ fn create_buy_trade_zc(
             transfer_map.get(buy.user_base_token_account.as_str())
                 .map(|t| {
                     crate::types::TokenInfo {
-                        mint: t.info.mint.clone(),
+                        mint: t.info.mint.to_string(),
                         amount: t.info.token_amount.ui_amount.unwrap_or(0.0),
                         amount_raw: t.info.token_amount.amount.clone(),
                         decimals: t.info.token_amount.decimals,
@@ -249,7 +249,7 @@ fn create_buy_trade_zc(
         Some(info) => info,
         None => {
             let inferred_mint = transfer_map.get(buy.user_base_token_account.as_str())
-                .map(|t| t.info.mint.clone());
+                .map(|t| t.info.mint.to_string());
             
             if let Some(mint) = inferred_mint {
                 let decimals = cached_maps.get_token_decimals(&mint);
@@ -318,6 +318,7 @@ fn create_buy_trade_zc(
         dex_info,
     );
     
+    trade.pool_reserves = pumpswap_pool_reserves(post_balance_map, &trade);
     // Attach token transfers (zero-copy: work with transfer_actions directly)
     attach_token_transfers_zc(&mut trade, transfer_actions);
     
@@ -368,7 +369,7 @@ fn create_sell_trade_zc(
             transfer_map.get(sell.user_base_token_account.as_str())
                 .map(|t| {
                     crate::types::TokenInfo {
-                        mint: t.info.mint.clone(),
+                        mint: t.info.mint.to_string(),
                         amount: t.info.token_amount.ui_amount.unwrap_or(0.0),
                         amount_raw: t.info.token_amount.amount.clone(),
                         decimals: t.info.token_amount.decimals,
@@ -382,7 +383,7 @@ fn create_sell_trade_zc(
         None => {
             // Try to infer from transfers or other base token accounts
             let inferred_mint = transfer_map.get(sell.user_base_token_account.as_str())
-                .map(|t| t.info.mint.clone())
+                .map(|t| t.info.mint.to_string())
                 .or_else(|| {
                     post_balance_map.values()
                         .find(|b| {
@@ -455,7 +456,7 @@ fn create_sell_trade_zc(
             transfer_map.get(sell.user_quote_token_account.as_str())
                 .map(|t| {
                     crate::types::TokenInfo {
-                        mint: t.info.mint.clone(),
+                        mint: t.info.mint.to_string(),
                         amount: t.info.token_amount.ui_amount.unwrap_or(0.0),
                         amount_raw: t.info.token_amount.amount.clone(),
                         decimals: t.info.token_amount.decimals,
@@ -491,7 +492,7 @@ fn create_sell_trade_zc(
             transfer_map.get(sell.protocol_fee_recipient_token_account.as_str())
                 .map(|t| {
                     crate::types::TokenInfo {
-                        mint: t.info.mint.clone(),
+                        mint: t.info.mint.to_string(),
                         amount: 0.0,
                         amount_raw: "0".to_string(),
                         decimals: t.info.token_amount.decimals,
@@ -535,6 +536,7 @@ fn create_sell_trade_zc(
         dex_info,
     );
     
+    trade.pool_reserves = pumpswap_pool_reserves(post_balance_map, &trade);
     // Attach token transfers (zero-copy: work with transfer_actions directly)
     attach_token_transfers_zc(&mut trade, transfer_actions);
     
@@ -549,13 +551,17 @@ fn attach_token_transfers_zc(
     transfer_actions: &TransferMap,
 ) {
     if let Some(ref program_id) = trade.program_id {
-        if let Some(entries) = transfer_actions.get(program_id) {
-            if let Some(transfer) = entries.iter().find(|entry| {
+        // ключи transfer map: `<program>:<outer>[-<inner>]`
+        let transfer = transfer_actions
+            .iter()
+            .filter(|(key, _)| key.split(':').next() == Some(program_id.as_str()))
+            .flat_map(|(_, entries)| entries)
+            .find(|entry| {
                 entry.info.mint == trade.input_token.mint
                     && entry.info.token_amount.amount == trade.input_token.amount_raw
-            }) {
-                trade.user.get_or_insert_with(|| transfer.info.source.clone());
-            }
+            });
+        if let Some(transfer) = transfer {
+            trade.user.get_or_insert_with(|| transfer.info.source.to_string());
         }
     }
     
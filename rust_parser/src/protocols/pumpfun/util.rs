@@ -1,12 +1,16 @@
+use std::collections::HashMap;
+
 use base64_simd::STANDARD;
 use serde::de::DeserializeOwned;
 
 use crate::core::transaction_adapter::TransactionAdapter;
-use crate::types::{DexInfo, FeeInfo, MemeEvent, TokenInfo, TradeInfo, TradeType, TransferMap};
+use crate::types::{
+    DexInfo, FeeInfo, FeeKind, MemeEvent, TokenAmount, TokenBalance, TokenInfo, TradeConfidence, TradeInfo, TradeType, TransferMap,
+};
 
 use super::constants::{
-    PUMP_FUN_PROGRAM_ID, PUMP_FUN_PROGRAM_NAME, PUMP_SWAP_PROGRAM_ID, PUMP_SWAP_PROGRAM_NAME,
-    SOL_MINT,
+    PUMP_FUN_PROGRAM_ID, PUMP_FUN_PROGRAM_NAME, PUMP_FUN_TOKEN_DECIMALS, PUMP_SWAP_PROGRAM_ID,
+    PUMP_SWAP_PROGRAM_NAME, SOL_MINT,
 };
 use super::error::PumpfunError;
 use super::pumpswap_event_parser::{
@@ -55,6 +59,22 @@ pub fn convert_to_ui_amount(amount: impl Into<u128>, decimals: u8) -> f64 {
     (value as f64) / scale
 }
 
+/// Цена токена в SOL по виртуальным резервам bonding curve.
+pub fn bonding_curve_price(virtual_sol_reserves: u64, virtual_token_reserves: u64) -> Option<f64> {
+    if virtual_token_reserves == 0 {
+        return None;
+    }
+    let sol = convert_to_ui_amount(virtual_sol_reserves, 9);
+    let tokens = convert_to_ui_amount(virtual_token_reserves, PUMP_FUN_TOKEN_DECIMALS);
+    Some(sol / tokens)
+}
+
+/// Market cap в SOL: цена * total supply.
+#[inline]
+pub fn bonding_curve_market_cap(price: f64, total_supply: u64) -> f64 {
+    price * convert_to_ui_amount(total_supply, PUMP_FUN_TOKEN_DECIMALS)
+}
+
 #[inline]
 pub fn get_trade_type(input_mint: &str, output_mint: &str) -> TradeType {
     if input_mint == SOL_MINT {
@@ -179,7 +199,7 @@ pub fn attach_token_transfers(
             }) {
                 trade
                     .user
-                    .get_or_insert_with(|| transfer.info.source.clone());
+                    .get_or_insert_with(|| transfer.info.source.to_string());
             }
         }
     }
@@ -202,6 +222,7 @@ pub fn build_fee_info(mint: &str, amount: u128, decimals: u8, dex: Option<String
         dex,
         fee_type: None,
         recipient: None,
+        kind: None,
     }
 }
 
@@ -254,7 +275,7 @@ pub fn get_pumpfun_trade_info(
             .unwrap_or_else(|| build_token_info(&event.quote_mint, 0, 9, None)),
         slippage_bps: None,
         fee: None,
-        fees: Vec::new(),
+        fees: get_pumpfun_fees(event),
         user: Some(event.user.clone()),
         program_id: Some(
             dex_info
@@ -277,7 +298,43 @@ pub fn get_pumpfun_trade_info(
         idx: event.idx.clone(),
         // ZERO-COPY: клонируем signers только один раз
         signer: Some(adapter.signers().to_vec()),
+        dlmm: None,
+        clob: None,
+        liquidity_role: None,
+        price_quote_per_base: None,
+        value_usd: None,
+        input_symbol: None,
+        output_symbol: None,
+        instruction_index: 0,
+        inner_index: None,
+        pool_reserves: None,
+        order_key: None,
+        side: None,
+        authority: None,
+        flags: Vec::new(),
+        router: None,
+        confidence: TradeConfidence::Unverified,
+    }
+}
+
+/// Комиссии bonding curve в SOL: protocol и creator — только те, что есть в TradeEvent.
+/// Старые события без `fee` комиссий не получают: ставка менялась, угадывать её нельзя.
+pub fn get_pumpfun_fees(event: &MemeEvent) -> Vec<FeeInfo> {
+    let dex = Some(PUMP_FUN_PROGRAM_NAME.to_string());
+    let mut fees = Vec::with_capacity(2);
+    if let Some(protocol_fee) = event.fee.map(|fee| fee as u128).filter(|fee| *fee > 0) {
+        fees.push(
+            build_fee_info(SOL_MINT, protocol_fee, 9, dex.clone())
+                .with_kind(FeeKind::Protocol, event.fee_recipient.clone()),
+        );
     }
+    if let Some(creator_fee) = event.creator_fee.filter(|fee| *fee > 0.0) {
+        fees.push(
+            build_fee_info(SOL_MINT, creator_fee as u128, 9, dex)
+                .with_kind(FeeKind::Creator, event.creator.clone()),
+        );
+    }
+    fees
 }
 
 pub fn get_pumpswap_trade_info(
@@ -326,9 +383,41 @@ pub fn get_pumpswap_trade_info(
         signature: event.signature.as_ref().clone(),
         idx: event.idx.clone(),
         signer: event.signer.as_ref().map(|s| s.as_ref().clone()),
+        dlmm: None,
+        clob: None,
+        liquidity_role: None,
+        price_quote_per_base: None,
+        value_usd: None,
+        input_symbol: None,
+        output_symbol: None,
+        instruction_index: 0,
+        inner_index: None,
+        pool_reserves: None,
+        order_key: None,
+        side: None,
+        authority: None,
+        flags: Vec::new(),
+        router: None,
+        confidence: TradeConfidence::Unverified,
     }
 }
 
+/// Vault'ы Pumpswap принадлежат пулу: ищем post-балансы аккаунтов `trade.pool`
+/// с input/output mint.
+pub fn pumpswap_pool_reserves(
+    post_balance_map: &HashMap<&str, &TokenBalance>,
+    trade: &TradeInfo,
+) -> Option<(TokenAmount, TokenAmount)> {
+    let pool = trade.pool.first()?;
+    let reserve = |mint: &str| {
+        post_balance_map
+            .values()
+            .find(|b| b.mint == mint && b.owner.as_deref() == Some(pool.as_str()))
+            .map(|b| b.ui_token_amount.clone())
+    };
+    Some((reserve(&trade.input_token.mint)?, reserve(&trade.output_token.mint)?))
+}
+
 pub fn build_pumpswap_buy_trade(
     event: &PumpswapEvent,
     buy: &PumpswapBuyEvent,
@@ -343,27 +432,27 @@ pub fn build_pumpswap_buy_trade(
 
     let total_fee = (buy.protocol_fee + buy.coin_creator_fee) as u128;
 
-    let mut fees = Vec::with_capacity(2);
+    let dex = Some(PUMP_SWAP_PROGRAM_NAME.to_string());
+    let mut fees = Vec::with_capacity(3);
     fees.push(FeeInfo {
-        mint: fee_mint.to_string(),
-        amount: convert_to_ui_amount(buy.protocol_fee as u128, fee_decimals),
-        amount_raw: buy.protocol_fee.to_string(),
-        decimals: fee_decimals,
-        dex: Some(PUMP_SWAP_PROGRAM_NAME.to_string()),
         fee_type: Some("protocol".to_string()),
-        recipient: Some(buy.protocol_fee_recipient.clone()),
+        ..build_fee_info(fee_mint, buy.protocol_fee as u128, fee_decimals, dex.clone())
+            .with_kind(FeeKind::Protocol, Some(buy.protocol_fee_recipient.clone()))
     });
     if buy.coin_creator_fee > 0 {
         fees.push(FeeInfo {
-            mint: fee_mint.to_string(),
-            amount: convert_to_ui_amount(buy.coin_creator_fee as u128, fee_decimals),
-            amount_raw: buy.coin_creator_fee.to_string(),
-            decimals: fee_decimals,
-            dex: Some(PUMP_SWAP_PROGRAM_NAME.to_string()),
             fee_type: Some("coinCreator".to_string()),
-            recipient: Some(buy.coin_creator.clone()),
+            ..build_fee_info(fee_mint, buy.coin_creator_fee as u128, fee_decimals, dex.clone())
+                .with_kind(FeeKind::Creator, Some(buy.coin_creator.clone()))
         });
     }
+    // LP fee остаётся в резервах пула
+    if buy.lp_fee > 0 {
+        fees.push(
+            build_fee_info(fee_mint, buy.lp_fee as u128, fee_decimals, dex)
+                .with_kind(FeeKind::Lp, Some(buy.pool.clone())),
+        );
+    }
 
     let fee_info = FeeInfo {
         mint: fee_mint.to_string(),
@@ -373,6 +462,7 @@ pub fn build_pumpswap_buy_trade(
         dex: None,
         fee_type: None,
         recipient: None,
+        kind: None,
     };
 
     get_pumpswap_trade_info(
@@ -404,27 +494,27 @@ pub fn build_pumpswap_sell_trade(
 
     let total_fee = (sell.protocol_fee + sell.coin_creator_fee) as u128;
 
-    let mut fees = Vec::with_capacity(2);
+    let dex = Some(PUMP_SWAP_PROGRAM_NAME.to_string());
+    let mut fees = Vec::with_capacity(3);
     fees.push(FeeInfo {
-        mint: fee_mint.to_string(),
-        amount: convert_to_ui_amount(sell.protocol_fee as u128, fee_decimals),
-        amount_raw: sell.protocol_fee.to_string(),
-        decimals: fee_decimals,
-        dex: Some(PUMP_SWAP_PROGRAM_NAME.to_string()),
         fee_type: Some("protocol".to_string()),
-        recipient: Some(sell.protocol_fee_recipient.clone()),
+        ..build_fee_info(fee_mint, sell.protocol_fee as u128, fee_decimals, dex.clone())
+            .with_kind(FeeKind::Protocol, Some(sell.protocol_fee_recipient.clone()))
     });
     if sell.coin_creator_fee > 0 {
         fees.push(FeeInfo {
-            mint: fee_mint.to_string(),
-            amount: convert_to_ui_amount(sell.coin_creator_fee as u128, fee_decimals),
-            amount_raw: sell.coin_creator_fee.to_string(),
-            decimals: fee_decimals,
-            dex: Some(PUMP_SWAP_PROGRAM_NAME.to_string()),
             fee_type: Some("coinCreator".to_string()),
-            recipient: Some(sell.coin_creator.clone()),
+            ..build_fee_info(fee_mint, sell.coin_creator_fee as u128, fee_decimals, dex.clone())
+                .with_kind(FeeKind::Creator, Some(sell.coin_creator.clone()))
         });
     }
+    // LP fee остаётся в резервах пула
+    if sell.lp_fee > 0 {
+        fees.push(
+            build_fee_info(fee_mint, sell.lp_fee as u128, fee_decimals, dex)
+                .with_kind(FeeKind::Lp, Some(sell.pool.clone())),
+        );
+    }
 
     let fee_info = FeeInfo {
         mint: fee_mint.to_string(),
@@ -434,6 +524,7 @@ pub fn build_pumpswap_sell_trade(
         dex: None,
         fee_type: None,
         recipient: None,
+        kind: None,
     };
 
     get_pumpswap_trade_info(
@@ -0,0 +1,63 @@
+pub mod constants;
+pub mod raydium_liquidity;
+pub mod raydium_parser;
+
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::protocols::simple::{LiquidityParser, TradeParser};
+use crate::types::{ClassifiedInstruction, DexInfo, TransferMap};
+
+use constants::{RAYDIUM_CL_PROGRAM_ID, RAYDIUM_CL_PROGRAM_NAME, RAYDIUM_V4_PROGRAM_NAME};
+use raydium_liquidity::RaydiumLiquidityParser;
+use raydium_parser::RaydiumParser;
+
+/// AMM v4 — пул с LP-токеном; CLMM — позиции с NFT, ликвидность без LP-токена.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RaydiumProgram {
+    AmmV4,
+    Clmm,
+}
+
+impl RaydiumProgram {
+    pub fn from_program_id(program_id: &str) -> Self {
+        if program_id == RAYDIUM_CL_PROGRAM_ID {
+            Self::Clmm
+        } else {
+            Self::AmmV4
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::AmmV4 => RAYDIUM_V4_PROGRAM_NAME,
+            Self::Clmm => RAYDIUM_CL_PROGRAM_NAME,
+        }
+    }
+}
+
+pub fn build_raydium_trade_parser(
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+) -> Box<dyn TradeParser> {
+    Box::new(RaydiumParser::new(
+        adapter,
+        dex_info,
+        transfer_actions,
+        classified_instructions,
+    ))
+}
+
+pub fn build_raydium_liquidity_parser(
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+) -> Box<dyn LiquidityParser> {
+    Box::new(RaydiumLiquidityParser::new(
+        adapter,
+        transfer_actions,
+        classified_instructions,
+    ))
+}
@@ -0,0 +1,297 @@
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::core::utils::get_instruction_actions;
+use crate::protocols::pumpfun::util::get_instruction_data;
+use crate::protocols::simple::LiquidityParser;
+use crate::protocols::stable::stable_liquidity::{token_legs, transfer_ui_amount};
+use crate::types::{ClassifiedInstruction, PoolEvent, PoolEventType, TransferMap};
+
+use super::constants::{amm_v4_instructions, clmm_instructions};
+use super::RaydiumProgram;
+
+/// Инструкция ликвидности: индексы пула и LP mint среди аккаунтов, смещение `liquidity`
+/// (u128) в данных у CLMM.
+struct PoolAction {
+    action: PoolEventType,
+    pool: Option<usize>,
+    lp_mint: Option<usize>,
+    liquidity_offset: Option<usize>,
+}
+
+impl PoolAction {
+    fn new(action: PoolEventType, pool: Option<usize>) -> Self {
+        Self {
+            action,
+            pool,
+            lp_mint: None,
+            liquidity_offset: None,
+        }
+    }
+}
+
+/// Deposit/Withdraw AMM v4 и позиции CLMM. `decreaseLiquidity` с нулевой `liquidity` —
+/// так CLMM забирает комиссии позиции — отдаётся как `CollectFee`.
+pub struct RaydiumLiquidityParser {
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+}
+
+impl RaydiumLiquidityParser {
+    pub fn new(
+        adapter: Arc<TransactionAdapter>,
+        transfer_actions: Arc<TransferMap>,
+        classified_instructions: Vec<ClassifiedInstruction>,
+    ) -> Self {
+        Self {
+            adapter,
+            transfer_actions,
+            classified_instructions,
+        }
+    }
+
+    fn amm_v4_action(data: &[u8]) -> Option<PoolAction> {
+        let (action, pool, lp_mint) = match *data.first()? {
+            amm_v4_instructions::INITIALIZE2 => (PoolEventType::Create, 4, 7),
+            amm_v4_instructions::DEPOSIT => (PoolEventType::Add, 1, 5),
+            amm_v4_instructions::WITHDRAW => (PoolEventType::Remove, 1, 5),
+            _ => return None,
+        };
+        Some(PoolAction {
+            lp_mint: Some(lp_mint),
+            ..PoolAction::new(action, Some(pool))
+        })
+    }
+
+    fn clmm_action(data: &[u8]) -> Option<PoolAction> {
+        use clmm_instructions::*;
+
+        let discriminator = data.get(..8)?;
+        // openPosition: 4 x i32 тиков перед liquidity
+        let (action, pool, liquidity_offset) = match discriminator {
+            d if d == CREATE_POOL => (PoolEventType::Create, Some(2), None),
+            d if d == OPEN_POSITION || d == OPEN_POSITION_V2 => {
+                (PoolEventType::OpenPosition, Some(5), Some(24))
+            }
+            d if d == OPEN_POSITION_WITH_TOKEN22_NFT => {
+                (PoolEventType::OpenPosition, Some(4), Some(24))
+            }
+            d if d == INCREASE_LIQUIDITY || d == INCREASE_LIQUIDITY_V2 => {
+                (PoolEventType::IncreaseLiquidity, Some(2), Some(8))
+            }
+            d if d == DECREASE_LIQUIDITY || d == DECREASE_LIQUIDITY_V2 => {
+                (PoolEventType::DecreaseLiquidity, Some(3), Some(8))
+            }
+            d if d == CLOSE_POSITION => (PoolEventType::ClosePosition, None, None),
+            _ => return None,
+        };
+        Some(PoolAction {
+            liquidity_offset,
+            ..PoolAction::new(action, pool)
+        })
+    }
+
+    fn parse_instruction(&self, classified: &ClassifiedInstruction) -> Option<PoolEvent> {
+        let program = RaydiumProgram::from_program_id(&classified.program_id);
+        let data = get_instruction_data(&classified.data).ok()?;
+        let PoolAction {
+            mut action,
+            pool,
+            lp_mint,
+            liquidity_offset,
+        } = match program {
+            RaydiumProgram::AmmV4 => Self::amm_v4_action(&data)?,
+            RaydiumProgram::Clmm => Self::clmm_action(&data)?,
+        };
+        let liquidity = liquidity_offset
+            .and_then(|offset| data.get(offset..offset + 16)?.try_into().ok())
+            .map(u128::from_le_bytes);
+        if action == PoolEventType::DecreaseLiquidity && liquidity == Some(0) {
+            action = PoolEventType::CollectFee;
+        }
+
+        let actions = get_instruction_actions(
+            &self.transfer_actions,
+            &classified.program_id,
+            classified.outer_index,
+            classified.inner_index,
+        );
+        let accounts = self.adapter.get_instruction_accounts(&classified.data);
+        let account = |index: Option<usize>| index.and_then(|i| accounts.get(i));
+        let is_add = action != PoolEventType::Remove;
+        let lp_kind = if is_add { "mintTo" } else { "burn" };
+        // RPC-транзакции mint/burn в transfer actions не несут — ищем в inner instructions
+        let lp_token = lp_mint.and_then(|_| {
+            actions
+                .iter()
+                .find(|t| t.transfer_type.starts_with(lp_kind))
+                .map(|t| (*t).clone())
+                .or_else(|| {
+                    self.adapter.find_lp_token_action(
+                        classified.outer_index,
+                        classified.inner_index,
+                        is_add,
+                        account(lp_mint).map(|mint| mint.as_ref()),
+                    )
+                })
+        });
+        let legs = token_legs(
+            &actions,
+            lp_token.as_ref().map(|t| t.info.mint.as_str()),
+            action.leg_direction(),
+        );
+        let lifecycle = matches!(
+            action,
+            PoolEventType::Create | PoolEventType::OpenPosition | PoolEventType::ClosePosition
+        );
+        if legs.is_empty() && !lifecycle {
+            return None;
+        }
+
+        let mut base = self
+            .adapter
+            .get_pool_event_base(action.clone(), &classified.program_id);
+        base.idx = format!(
+            "{}-{}",
+            classified.outer_index,
+            classified.inner_index.unwrap_or(0)
+        );
+
+        Some(PoolEvent {
+            user: base.user,
+            event_type: action.into(),
+            program_id: base.program_id,
+            amm: Some(program.name().to_string()),
+            slot: base.slot,
+            timestamp: base.timestamp,
+            signature: base.signature,
+            idx: base.idx,
+            signer: base.signer,
+            pool_id: account(pool).map(ToString::to_string).unwrap_or_default(),
+            config: None,
+            pool_lp_mint: lp_token
+                .as_ref()
+                .map(|t| t.info.mint.to_string())
+                .or_else(|| account(lp_mint).map(ToString::to_string)),
+            lp_amount: lp_token.as_ref().map(transfer_ui_amount),
+            lp_amount_raw: lp_token
+                .map(|t| t.info.token_amount.amount)
+                .or_else(|| liquidity.map(|liquidity| liquidity.to_string())),
+            tokens: legs,
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
+        })
+    }
+}
+
+impl LiquidityParser for RaydiumLiquidityParser {
+    fn process_liquidity(&mut self) -> Vec<PoolEvent> {
+        self.classified_instructions
+            .iter()
+            .filter_map(|classified| self.parse_instruction(classified))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::constants::dex_programs;
+    use crate::core::test_utils::sample_transaction;
+    use crate::types::SolanaInstruction;
+    use crate::DexParser;
+
+    #[test]
+    fn raydium_clmm_zero_liquidity_decrease_is_fee_collection() {
+        use crate::protocols::raydium::constants::clmm_instructions;
+        use crate::types::{LegDirection, TradeType};
+        use base64_simd::STANDARD;
+
+        let mut tx = sample_transaction();
+        let mut decrease = clmm_instructions::DECREASE_LIQUIDITY.to_vec();
+        decrease.extend_from_slice(&0u128.to_le_bytes());
+        tx.instructions = vec![
+            SolanaInstruction {
+                program_id: dex_programs::RAYDIUM_CL.into(),
+                accounts: ["owner", "nft-account", "position", "clmm-pool"]
+                    .map(Into::into)
+                    .to_vec(),
+                data: STANDARD.encode_to_string(decrease),
+            },
+            SolanaInstruction {
+                program_id: dex_programs::RAYDIUM_CL.into(),
+                accounts: vec!["owner".into()],
+                data: STANDARD.encode_to_string(clmm_instructions::CLOSE_POSITION),
+            },
+        ];
+        tx.transfers.iter_mut().for_each(|transfer| {
+            transfer.program_id = dex_programs::RAYDIUM_CL.into();
+        });
+
+        let result = DexParser::new().parse_all(tx, None);
+        let kinds: Vec<_> = result
+            .liquidities
+            .iter()
+            .map(|e| e.event_type.clone())
+            .collect();
+        assert_eq!(kinds, [TradeType::CollectFee, TradeType::ClosePosition]);
+        let fee = &result.liquidities[0];
+        assert_eq!(fee.pool_id, "clmm-pool");
+        assert_eq!(fee.amm.as_deref(), Some("RaydiumCL"));
+        assert!(fee
+            .tokens
+            .iter()
+            .all(|leg| leg.direction == LegDirection::Out));
+        assert_eq!(
+            serde_json::to_value(&fee.event_type).unwrap(),
+            "COLLECT_FEE"
+        );
+    }
+
+    #[test]
+    fn raydium_v4_deposit_reports_lp_minted_by_inner_mint_to() {
+        use crate::protocols::raydium::constants::amm_v4_instructions;
+        use crate::types::{InnerInstruction, TradeType};
+        use base64_simd::STANDARD;
+
+        let mut tx = sample_transaction();
+        let mut accounts = ["token-program", "amm-pool", "authority", "open-orders"]
+            .map(String::from)
+            .to_vec();
+        accounts.extend(["target-orders", "lp-mint"].map(String::from));
+        tx.instructions = vec![SolanaInstruction {
+            program_id: dex_programs::RAYDIUM.into(),
+            accounts: accounts.iter().map(|a| a.as_str().into()).collect(),
+            data: STANDARD.encode_to_string([amm_v4_instructions::DEPOSIT]),
+        }];
+        let mut mint_to = vec![7u8];
+        mint_to.extend_from_slice(&2_500u64.to_le_bytes());
+        tx.inner_instructions = vec![InnerInstruction {
+            index: 0,
+            instructions: vec![SolanaInstruction {
+                program_id: "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA".into(),
+                accounts: ["lp-mint", "user-lp-account", "authority"]
+                    .map(Into::into)
+                    .to_vec(),
+                data: STANDARD.encode_to_string(mint_to),
+            }],
+            stack_heights: Vec::new(),
+        }];
+        tx.transfers.iter_mut().for_each(|transfer| {
+            transfer.program_id = dex_programs::RAYDIUM.into();
+        });
+
+        let result = DexParser::new().parse_all(tx, None);
+        let deposit = result
+            .liquidities
+            .iter()
+            .find(|e| e.event_type == TradeType::Add)
+            .expect("deposit event");
+        assert_eq!(deposit.pool_id, "amm-pool");
+        assert_eq!(deposit.pool_lp_mint.as_deref(), Some("lp-mint"));
+        assert_eq!(deposit.lp_amount_raw.as_deref(), Some("2500"));
+    }
+}
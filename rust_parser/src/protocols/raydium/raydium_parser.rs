@@ -0,0 +1,264 @@
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::core::transaction_utils::TransactionUtils;
+use crate::core::utils::get_instruction_transfers;
+use crate::protocols::pumpfun::util::{build_fee_info, get_instruction_data};
+use crate::protocols::simple::TradeParser;
+use crate::state_decoders::ProtocolAccount;
+use crate::types::{ClassifiedInstruction, DexInfo, FeeInfo, FeeKind, TradeInfo, TransferData, TransferMap};
+
+use super::constants::{amm_v4_instructions, clmm_instructions};
+use super::RaydiumProgram;
+
+/// Свапы AMM v4 и CLMM: событий нет, сделка собирается из первых двух трансферов
+/// каждой инструкции, третий (если есть) — комиссия.
+pub struct RaydiumParser {
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+    utils: TransactionUtils,
+}
+
+impl RaydiumParser {
+    pub fn new(
+        adapter: Arc<TransactionAdapter>,
+        dex_info: DexInfo,
+        transfer_actions: Arc<TransferMap>,
+        classified_instructions: Vec<ClassifiedInstruction>,
+    ) -> Self {
+        let utils = TransactionUtils::new(adapter.clone());
+        Self {
+            adapter,
+            dex_info,
+            transfer_actions,
+            classified_instructions,
+            utils,
+        }
+    }
+
+    /// Инструкции ликвидности разбирает `RaydiumLiquidityParser`.
+    fn is_liquidity_instruction(program: RaydiumProgram, data: &[u8]) -> bool {
+        use clmm_instructions::*;
+
+        match program {
+            RaydiumProgram::AmmV4 => matches!(
+                data.first(),
+                Some(&amm_v4_instructions::INITIALIZE2)
+                    | Some(&amm_v4_instructions::DEPOSIT)
+                    | Some(&amm_v4_instructions::WITHDRAW)
+            ),
+            RaydiumProgram::Clmm => data.get(..8).is_some_and(|discriminator| {
+                [
+                    CREATE_POOL,
+                    OPEN_POSITION,
+                    OPEN_POSITION_V2,
+                    OPEN_POSITION_WITH_TOKEN22_NFT,
+                    INCREASE_LIQUIDITY,
+                    INCREASE_LIQUIDITY_V2,
+                    DECREASE_LIQUIDITY,
+                    DECREASE_LIQUIDITY_V2,
+                ]
+                .iter()
+                .any(|known| discriminator == known)
+            }),
+        }
+    }
+
+    /// Аккаунт пула: `amm` у AMM v4, `pool_state` у CLMM.
+    fn pool_index(program: RaydiumProgram) -> usize {
+        match program {
+            RaydiumProgram::AmmV4 => 1,
+            RaydiumProgram::Clmm => 2,
+        }
+    }
+
+    /// Raydium AMM v4: LP- и протокольная части комиссии по ставкам из `AmmInfo` пула.
+    /// Без аккаунта пула в `ProtocolAccountStore` разбивки нет — ставки задаёт админ пула.
+    fn amm_v4_fees(&self, trade: &TradeInfo) -> Vec<FeeInfo> {
+        let Some(ProtocolAccount::RaydiumAmmV4(amm)) = trade
+            .pool
+            .first()
+            .and_then(|pool| self.adapter.protocol_account(pool))
+        else {
+            return Vec::new();
+        };
+        let input = &trade.input_token;
+        let Some((lp_fee, protocol_fee)) = input
+            .amount_raw
+            .parse::<u128>()
+            .ok()
+            .and_then(|amount_in| amm.swap_fees(amount_in))
+        else {
+            return Vec::new();
+        };
+        // LP-доля остаётся в пуле; pnl owner протокола из AmmInfo не декодируется
+        let pool = trade.pool.first().cloned();
+        [(FeeKind::Lp, lp_fee, pool), (FeeKind::Protocol, protocol_fee, None)]
+            .into_iter()
+            .filter(|(_, amount, _)| *amount > 0)
+            .map(|(kind, amount, recipient)| {
+                build_fee_info(&input.mint, amount, input.decimals, trade.amm.clone())
+                    .with_kind(kind, recipient)
+            })
+            .collect()
+    }
+}
+
+impl TradeParser for RaydiumParser {
+    fn process_trades(&mut self) -> Vec<TradeInfo> {
+        let mut trades = Vec::new();
+
+        for classified in &self.classified_instructions {
+            let program = RaydiumProgram::from_program_id(&classified.program_id);
+            let is_liquidity = get_instruction_data(&classified.data)
+                .is_ok_and(|data| Self::is_liquidity_instruction(program, &data));
+            if is_liquidity {
+                continue;
+            }
+
+            let transfers: Vec<TransferData> = get_instruction_transfers(
+                &self.transfer_actions,
+                &classified.program_id,
+                classified.outer_index,
+                classified.inner_index,
+            )
+            .into_iter()
+            .cloned()
+            .collect();
+            if transfers.len() < 2 {
+                continue;
+            }
+
+            let dex_info = DexInfo {
+                program_id: Some(classified.program_id.to_string()),
+                amm: self
+                    .dex_info
+                    .amm
+                    .clone()
+                    .or_else(|| Some(program.name().to_string())),
+                route: self.dex_info.route.clone(),
+            };
+            let Some(mut trade) = self.utils.process_swap_data(&transfers[..2], &dex_info)
+            else {
+                continue;
+            };
+
+            let accounts = self.adapter.get_instruction_accounts(&classified.data);
+            if accounts.len() > 5 {
+                if let Some(pool) = accounts.get(Self::pool_index(program)) {
+                    trade.pool = vec![pool.to_string()];
+                }
+            }
+            if let Some(fee) = transfers.get(2) {
+                let info = &fee.info;
+                if let Ok(amount) = info.token_amount.amount.parse::<u128>() {
+                    trade.fee = Some(build_fee_info(
+                        &info.mint,
+                        amount,
+                        info.token_amount.decimals,
+                        None,
+                    ));
+                }
+            }
+            if program == RaydiumProgram::AmmV4 {
+                trade.fees = self.amm_v4_fees(&trade);
+            }
+
+            let trade = self.utils.attach_pool_reserves(trade);
+            trades.push(
+                self.utils
+                    .attach_token_transfer_info(trade, &self.transfer_actions),
+            );
+        }
+
+        trades
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use crate::core::constants::dex_programs;
+    use crate::core::test_utils::sample_transaction;
+    use crate::state::ProtocolAccountStore;
+    use crate::types::TokenAmount;
+    use crate::DexParser;
+
+    #[test]
+    fn splits_raydium_v4_fee_by_pool_rates() {
+        use crate::state_decoders::{AmmInfoAccount, ProtocolAccount};
+        use crate::types::FeeKind;
+
+        let mut tx = sample_transaction();
+        tx.instructions[0].program_id = dex_programs::RAYDIUM.into();
+        tx.instructions[0].accounts = (0..18).map(|i| format!("account-{i}").into()).collect();
+        tx.instructions[0].accounts[1] = "amm-pool".into();
+        tx.transfers.iter_mut().for_each(|transfer| {
+            transfer.program_id = dex_programs::RAYDIUM.into();
+        });
+
+        // без AmmInfo пула ставки неизвестны — разбивки нет
+        let result = DexParser::new().parse_all(tx.clone(), None);
+        assert!(result.trades[0].fees.is_empty());
+
+        let store = Arc::new(ProtocolAccountStore::new());
+        store.insert(
+            "amm-pool",
+            ProtocolAccount::RaydiumAmmV4(AmmInfoAccount {
+                swap_fee_numerator: 25,
+                swap_fee_denominator: 10_000,
+                pnl_numerator: 12,
+                pnl_denominator: 100,
+                ..Default::default()
+            }),
+        );
+        let result = DexParser::new().with_protocol_accounts(store).parse_all(tx, None);
+        let fees = &result.trades[0].fees;
+        let kinds: Vec<_> = fees
+            .iter()
+            .map(|fee| (fee.kind, fee.amount_raw.as_str(), fee.recipient.as_deref()))
+            .collect();
+        // вход 1_000_000 BASE, 0.25% с долей протокола 12%: 0.22% и 0.03%
+        assert_eq!(
+            kinds,
+            [
+                (Some(FeeKind::Lp), "2200", Some("amm-pool")),
+                (Some(FeeKind::Protocol), "300", None)
+            ]
+        );
+        assert!(fees.iter().all(|fee| fee.mint == "BASE"));
+    }
+
+    #[test]
+    fn raydium_trade_reports_post_swap_pool_reserves() {
+        use crate::types::TokenBalance;
+
+        let mut tx = sample_transaction();
+        tx.instructions[0].program_id = dex_programs::RAYDIUM.into();
+        tx.transfers.iter_mut().for_each(|transfer| {
+            transfer.program_id = dex_programs::RAYDIUM.into();
+        });
+        let vault = |account: &str, mint: &str, amount: &str| TokenBalance {
+            account: account.to_string(),
+            mint: mint.to_string(),
+            owner: Some("amm-authority".to_string()),
+            ui_token_amount: TokenAmount::new(amount, 6, None),
+        };
+        tx.post_token_balances = vec![
+            vault("pool", "BASE", "51000000"),
+            vault("pool-token", "QUOTE", "98000000"),
+        ];
+
+        let result = DexParser::new().parse_all(tx.clone(), None);
+        let (input, output) = result.trades[0].pool_reserves.clone().expect("pool reserves");
+        assert_eq!((input.amount.as_str(), output.amount.as_str()), ("51000000", "98000000"));
+
+        // без post-баланса одного из vault'ов резервы не заполняются
+        tx.post_token_balances.pop();
+        let result = DexParser::new().parse_all(tx, None);
+        assert!(result.trades[0].pool_reserves.is_none());
+    }
+}
@@ -0,0 +1,33 @@
+pub const RAYDIUM_LAUNCHLAB_PROGRAM_ID: &str = "LanMV9sAd7wArD4vJFi2qDdfnVhFxYSUg6eADduJ3uj";
+pub const RAYDIUM_LAUNCHLAB_PROGRAM_NAME: &str = "RaydiumLaunchLab";
+
+/// Куда мигрирует пул после заполнения кривой.
+pub const RAYDIUM_V4_PROGRAM_NAME: &str = "RaydiumV4";
+pub const RAYDIUM_CPMM_PROGRAM_NAME: &str = "RaydiumCPMM";
+
+/// Токены LaunchLab по умолчанию: 6 decimals, supply 1_000_000_000.
+pub const LAUNCHLAB_TOKEN_DECIMALS: u8 = 6;
+pub const LAUNCHLAB_TOKEN_TOTAL_SUPPLY: u64 = 1_000_000_000_000_000;
+
+pub mod discriminators {
+    pub mod launchlab_instructions {
+        pub const INITIALIZE: [u8; 8] = [175, 175, 109, 31, 13, 152, 155, 237];
+        pub const INITIALIZE_V2: [u8; 8] = [67, 153, 175, 39, 218, 16, 38, 32];
+        pub const BUY_EXACT_IN: [u8; 8] = [250, 234, 13, 123, 213, 156, 19, 236];
+        pub const BUY_EXACT_OUT: [u8; 8] = [24, 211, 116, 40, 105, 3, 153, 56];
+        pub const SELL_EXACT_IN: [u8; 8] = [149, 39, 222, 155, 211, 124, 152, 26];
+        pub const SELL_EXACT_OUT: [u8; 8] = [95, 200, 71, 34, 8, 9, 11, 166];
+        pub const MIGRATE_TO_AMM: [u8; 8] = [207, 82, 192, 145, 254, 207, 145, 223];
+        pub const MIGRATE_TO_CPSWAP: [u8; 8] = [136, 92, 200, 103, 28, 218, 144, 140];
+    }
+
+    /// emit_cpi: 8 байт тега event-инструкции + 8 байт дискриминатора события.
+    pub mod launchlab_events {
+        pub const TRADE: [u8; 16] = [
+            228, 69, 165, 46, 81, 203, 154, 29, 189, 219, 127, 211, 78, 230, 97, 238,
+        ];
+        pub const POOL_CREATE: [u8; 16] = [
+            228, 69, 165, 46, 81, 203, 154, 29, 151, 215, 226, 9, 118, 161, 115, 174,
+        ];
+    }
+}
@@ -0,0 +1,32 @@
+pub mod constants;
+pub mod raydium_launchlab_event_parser;
+pub mod raydium_launchlab_parser;
+
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::protocols::simple::{MemeEventParser, TradeParser};
+use crate::types::{ClassifiedInstruction, DexInfo, TransferMap};
+
+use raydium_launchlab_parser::{RaydiumLaunchLabMemeParser, RaydiumLaunchLabParser};
+
+pub fn build_raydium_launchlab_trade_parser(
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+) -> Box<dyn TradeParser> {
+    Box::new(RaydiumLaunchLabParser::new(
+        adapter,
+        dex_info,
+        transfer_actions,
+        classified_instructions,
+    ))
+}
+
+pub fn build_raydium_launchlab_meme_parser(
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
+) -> Box<dyn MemeEventParser> {
+    Box::new(RaydiumLaunchLabMemeParser::new(adapter, transfer_actions))
+}
@@ -0,0 +1,340 @@
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::core::utils::sort_by_execution_order;
+use crate::protocols::pumpfun::binary_reader::{BinaryReaderError, BinaryReaderRef};
+use crate::protocols::pumpfun::util::{
+    bonding_curve_market_cap, build_token_info, convert_to_ui_amount, get_instruction_data,
+    sort_by_idx,
+};
+use crate::types::{AccountKey, ClassifiedInstruction, MemeEvent, TradeType};
+
+use super::constants::{
+    discriminators::{launchlab_events, launchlab_instructions},
+    LAUNCHLAB_TOKEN_DECIMALS, LAUNCHLAB_TOKEN_TOTAL_SUPPLY, RAYDIUM_CPMM_PROGRAM_NAME,
+    RAYDIUM_LAUNCHLAB_PROGRAM_NAME, RAYDIUM_V4_PROGRAM_NAME,
+};
+
+/// `PoolStatus::Migrate` в TradeEvent: кривая заполнена, пул ждёт миграции.
+const POOL_STATUS_MIGRATE: u8 = 1;
+
+/// Поля TradeEvent, нужные для MemeEvent.
+struct TradeEventData {
+    virtual_base: u64,
+    virtual_quote: u64,
+    real_base_after: u64,
+    real_quote_after: u64,
+    amount_in: u64,
+    amount_out: u64,
+    protocol_fee: u64,
+    platform_fee: u64,
+    creator_fee: u64,
+    share_fee: u64,
+    is_buy: bool,
+    pool_status: u8,
+}
+
+/// Разбирает инструкции LaunchLab. TradeEvent не содержит mint-ов и пользователя,
+/// поэтому trade собирается из инструкции buy/sell и следующего за ней emit_cpi события.
+pub struct RaydiumLaunchLabEventParser;
+
+impl RaydiumLaunchLabEventParser {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn parse_instructions(
+        &self,
+        adapter: &TransactionAdapter,
+        instructions: &[ClassifiedInstruction],
+    ) -> Vec<MemeEvent> {
+        let mut events = Vec::with_capacity(instructions.len());
+        let signature = adapter.signature().to_string();
+        let slot = adapter.slot();
+        let timestamp = adapter.block_time();
+        let mut instructions = instructions.to_vec();
+        sort_by_execution_order(&mut instructions);
+
+        for (pos, classified) in instructions.iter().enumerate() {
+            let Ok(data) = get_instruction_data(&classified.data) else {
+                continue;
+            };
+            if data.len() < 8 {
+                continue;
+            }
+
+            let accounts = adapter.get_instruction_accounts(&classified.data);
+            let discriminator = &data[..8];
+            let payload = &data[8..];
+
+            let decoded: Vec<MemeEvent> = if is_trade_instruction(discriminator) {
+                find_trade_event(&instructions, pos)
+                    .and_then(|event| decode_trade_event(&event).ok())
+                    .map(|event| self.build_trade_events(adapter, accounts, &event))
+                    .unwrap_or_default()
+            } else if discriminator == launchlab_instructions::INITIALIZE
+                || discriminator == launchlab_instructions::INITIALIZE_V2
+            {
+                self.decode_create(accounts, payload).ok().into_iter().collect()
+            } else if discriminator == launchlab_instructions::MIGRATE_TO_AMM {
+                // amm_pool — 14-й аккаунт migrate_to_amm
+                vec![self.build_migrate_event(accounts, 13, RAYDIUM_V4_PROGRAM_NAME)]
+            } else if discriminator == launchlab_instructions::MIGRATE_TO_CPSWAP {
+                vec![self.build_migrate_event(accounts, 5, RAYDIUM_CPMM_PROGRAM_NAME)]
+            } else {
+                Vec::new()
+            };
+
+            for mut event in decoded {
+                event.protocol = Some(RAYDIUM_LAUNCHLAB_PROGRAM_NAME.to_string());
+                event.signature = signature.clone();
+                event.slot = slot;
+                event.timestamp = timestamp;
+                event.idx = format!(
+                    "{}-{}",
+                    classified.outer_index,
+                    classified.inner_index.unwrap_or(0)
+                );
+                events.push(event);
+            }
+        }
+
+        sort_by_idx(events)
+    }
+
+    /// Buy/Sell и, если сделка заполнила кривую, Complete.
+    fn build_trade_events(
+        &self,
+        adapter: &TransactionAdapter,
+        accounts: &[AccountKey],
+        event: &TradeEventData,
+    ) -> Vec<MemeEvent> {
+        // payer, authority, global_config, platform_config, pool_state, ..., base_mint, quote_mint
+        if accounts.len() < 11 {
+            return Vec::new();
+        }
+        let user = accounts[0].clone();
+        let base_mint = accounts[9].clone();
+        let quote_mint = accounts[10].clone();
+        let base_decimals = adapter
+            .token_decimals(&base_mint)
+            .unwrap_or(LAUNCHLAB_TOKEN_DECIMALS);
+        // quote по умолчанию — WSOL
+        let quote_decimals = adapter.token_decimals(&quote_mint).unwrap_or(9);
+
+        let (input_token, output_token) = if event.is_buy {
+            (
+                build_token_info(&quote_mint, event.amount_in as u128, quote_decimals, None),
+                build_token_info(&base_mint, event.amount_out as u128, base_decimals, None),
+            )
+        } else {
+            (
+                build_token_info(&base_mint, event.amount_in as u128, base_decimals, None),
+                build_token_info(&quote_mint, event.amount_out as u128, quote_decimals, None),
+            )
+        };
+
+        // Кривая x*y=k по виртуальным резервам, сдвинутым на реальные
+        let curve_quote = event.virtual_quote.saturating_add(event.real_quote_after);
+        let curve_base = event.virtual_base.saturating_sub(event.real_base_after);
+        let price = (curve_base > 0).then(|| {
+            convert_to_ui_amount(curve_quote, quote_decimals)
+                / convert_to_ui_amount(curve_base, base_decimals)
+        });
+
+        let trade = MemeEvent {
+            event_type: if event.is_buy {
+                TradeType::Buy
+            } else {
+                TradeType::Sell
+            },
+            user: user.to_string(),
+            base_mint: base_mint.to_string(),
+            quote_mint: quote_mint.to_string(),
+            input_token: Some(input_token),
+            output_token: Some(output_token),
+            protocol_fee: Some(event.protocol_fee as f64),
+            platform_fee: Some(event.platform_fee as f64),
+            creator_fee: Some(event.creator_fee as f64),
+            share_fee: Some(event.share_fee as f64),
+            platform_config: accounts.get(3).map(ToString::to_string),
+            bonding_curve: accounts.get(4).map(ToString::to_string),
+            pool: accounts.get(4).map(ToString::to_string),
+            virtual_sol_reserves: Some(event.virtual_quote),
+            virtual_token_reserves: Some(event.virtual_base),
+            real_sol_reserves: Some(event.real_quote_after),
+            real_token_reserves: Some(event.real_base_after),
+            price,
+            market_cap: price.map(|p| bonding_curve_market_cap(p, LAUNCHLAB_TOKEN_TOTAL_SUPPLY)),
+            ..Default::default()
+        };
+
+        if event.pool_status != POOL_STATUS_MIGRATE {
+            return vec![trade];
+        }
+        let complete = MemeEvent {
+            event_type: TradeType::Complete,
+            user: user.to_string(),
+            base_mint: base_mint.to_string(),
+            quote_mint: quote_mint.to_string(),
+            platform_config: trade.platform_config.clone(),
+            bonding_curve: trade.bonding_curve.clone(),
+            pool: trade.pool.clone(),
+            ..Default::default()
+        };
+        vec![trade, complete]
+    }
+
+    fn decode_create(
+        &self,
+        accounts: &[AccountKey],
+        data: &[u8],
+    ) -> Result<MemeEvent, BinaryReaderError> {
+        // MintParams { decimals, name, symbol, uri }
+        let mut reader = BinaryReaderRef::new_ref(data);
+        let decimals = reader.read_u8()?;
+        let name = reader.read_string()?;
+        let symbol = reader.read_string()?;
+        let uri = reader.read_string()?;
+
+        // payer, creator, global_config, platform_config, authority, pool_state, base_mint, quote_mint
+        Ok(MemeEvent {
+            event_type: TradeType::Create,
+            user: accounts.first().map(ToString::to_string).unwrap_or_default(),
+            base_mint: accounts.get(6).map(ToString::to_string).unwrap_or_default(),
+            quote_mint: accounts.get(7).map(ToString::to_string).unwrap_or_default(),
+            name: Some(name),
+            symbol: Some(symbol),
+            uri: Some(uri),
+            decimals: Some(decimals),
+            creator: accounts.get(1).map(ToString::to_string),
+            platform_config: accounts.get(3).map(ToString::to_string),
+            bonding_curve: accounts.get(5).map(ToString::to_string),
+            pool: accounts.get(5).map(ToString::to_string),
+            ..Default::default()
+        })
+    }
+
+    fn build_migrate_event(
+        &self,
+        accounts: &[AccountKey],
+        pool_index: usize,
+        pool_dex: &str,
+    ) -> MemeEvent {
+        MemeEvent {
+            event_type: TradeType::Migrate,
+            user: accounts.first().map(ToString::to_string).unwrap_or_default(),
+            base_mint: accounts.get(1).map(ToString::to_string).unwrap_or_default(),
+            quote_mint: accounts.get(2).map(ToString::to_string).unwrap_or_default(),
+            pool: accounts.get(pool_index).map(ToString::to_string),
+            pool_dex: Some(pool_dex.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for RaydiumLaunchLabEventParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[inline]
+fn is_trade_instruction(discriminator: &[u8]) -> bool {
+    discriminator == launchlab_instructions::BUY_EXACT_IN
+        || discriminator == launchlab_instructions::BUY_EXACT_OUT
+        || discriminator == launchlab_instructions::SELL_EXACT_IN
+        || discriminator == launchlab_instructions::SELL_EXACT_OUT
+}
+
+/// TradeEvent — inner-инструкция той же outer, до следующего buy/sell.
+fn find_trade_event(instructions: &[ClassifiedInstruction], pos: usize) -> Option<Vec<u8>> {
+    let outer_index = instructions[pos].outer_index;
+    for classified in instructions[pos + 1..]
+        .iter()
+        .take_while(|ix| ix.outer_index == outer_index)
+    {
+        let Ok(data) = get_instruction_data(&classified.data) else {
+            continue;
+        };
+        if data.len() >= 16 && data[..16] == launchlab_events::TRADE {
+            return Some(data[16..].to_vec());
+        }
+        if data.len() >= 8 && is_trade_instruction(&data[..8]) {
+            return None;
+        }
+    }
+    None
+}
+
+fn decode_trade_event(data: &[u8]) -> Result<TradeEventData, BinaryReaderError> {
+    let mut reader = BinaryReaderRef::new_ref(data);
+    let _pool_state = reader.read_pubkey()?;
+    let _total_base_sell = reader.read_u64()?;
+    let virtual_base = reader.read_u64()?;
+    let virtual_quote = reader.read_u64()?;
+    let _real_base_before = reader.read_u64()?;
+    let _real_quote_before = reader.read_u64()?;
+    let real_base_after = reader.read_u64()?;
+    let real_quote_after = reader.read_u64()?;
+    let amount_in = reader.read_u64()?;
+    let amount_out = reader.read_u64()?;
+    let protocol_fee = reader.read_u64()?;
+    let platform_fee = reader.read_u64()?;
+    let creator_fee = reader.read_u64()?;
+    let share_fee = reader.read_u64()?;
+    let is_buy = reader.read_u8()? == 0;
+    let pool_status = reader.read_u8()?;
+
+    Ok(TradeEventData {
+        virtual_base,
+        virtual_quote,
+        real_base_after,
+        real_quote_after,
+        amount_in,
+        amount_out,
+        protocol_fee,
+        platform_fee,
+        creator_fee,
+        share_fee,
+        is_buy,
+        pool_status,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_trade_event_layout() {
+        let mut data = Vec::new();
+        data.extend_from_slice(&[1u8; 32]); // pool_state
+        for value in [
+            793_100_000_000_000u64, // total_base_sell
+            1_073_025_605_596_382,  // virtual_base
+            30_000_852_951,         // virtual_quote
+            0,                      // real_base_before
+            0,                      // real_quote_before
+            35_000_000_000_000,     // real_base_after
+            1_000_000_000,          // real_quote_after
+            1_000_000_000,          // amount_in
+            35_000_000_000_000,     // amount_out
+            2_500_000,              // protocol_fee
+            10_000_000,             // platform_fee
+            0,                      // creator_fee
+            0,                      // share_fee
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.push(0); // trade_direction: Buy
+        data.push(1); // pool_status: Migrate
+        data.push(1); // exact_in
+
+        let event = decode_trade_event(&data).expect("trade event");
+        assert!(event.is_buy);
+        assert_eq!(event.amount_in, 1_000_000_000);
+        assert_eq!(event.amount_out, 35_000_000_000_000);
+        assert_eq!(event.real_quote_after, 1_000_000_000);
+        assert_eq!(event.platform_fee, 10_000_000);
+        assert_eq!(event.pool_status, POOL_STATUS_MIGRATE);
+    }
+}
@@ -0,0 +1,177 @@
+use std::sync::Arc;
+
+use crate::core::instruction_classifier::InstructionClassifier;
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::protocols::pumpfun::util::attach_token_transfers;
+use crate::protocols::simple::{MemeEventParser, TradeParser};
+use crate::types::{ClassifiedInstruction, DexInfo, MemeEvent, TradeInfo, TradeType, TransferMap};
+
+use super::constants::{RAYDIUM_LAUNCHLAB_PROGRAM_ID, RAYDIUM_LAUNCHLAB_PROGRAM_NAME};
+use super::raydium_launchlab_event_parser::RaydiumLaunchLabEventParser;
+
+pub struct RaydiumLaunchLabParser {
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+}
+
+impl RaydiumLaunchLabParser {
+    pub fn new(
+        adapter: Arc<TransactionAdapter>,
+        dex_info: DexInfo,
+        transfer_actions: Arc<TransferMap>,
+        classified_instructions: Vec<ClassifiedInstruction>,
+    ) -> Self {
+        Self {
+            adapter,
+            dex_info,
+            transfer_actions,
+            classified_instructions,
+        }
+    }
+
+    fn create_trade_info(&self, event: &MemeEvent) -> TradeInfo {
+        TradeInfo {
+            trade_type: event.event_type.clone(),
+            pool: event.pool.as_ref().map(|p| vec![p.clone()]).unwrap_or_default(),
+            input_token: event.input_token.clone().unwrap_or_default(),
+            output_token: event.output_token.clone().unwrap_or_default(),
+            user: Some(event.user.clone()),
+            program_id: Some(
+                self.dex_info
+                    .program_id
+                    .clone()
+                    .unwrap_or_else(|| RAYDIUM_LAUNCHLAB_PROGRAM_ID.to_string()),
+            ),
+            amm: Some(RAYDIUM_LAUNCHLAB_PROGRAM_NAME.to_string()),
+            amms: Some(vec![RAYDIUM_LAUNCHLAB_PROGRAM_NAME.to_string()]),
+            route: self.dex_info.route.clone(),
+            slot: event.slot,
+            timestamp: event.timestamp,
+            signature: event.signature.clone(),
+            idx: event.idx.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+impl TradeParser for RaydiumLaunchLabParser {
+    fn process_trades(&mut self) -> Vec<TradeInfo> {
+        RaydiumLaunchLabEventParser::new()
+            .parse_instructions(&self.adapter, &self.classified_instructions)
+            .into_iter()
+            .filter(|event| matches!(event.event_type, TradeType::Buy | TradeType::Sell))
+            .map(|event| {
+                let trade = self.create_trade_info(&event);
+                attach_token_transfers(&self.adapter, trade, &self.transfer_actions)
+            })
+            .collect()
+    }
+}
+
+pub struct RaydiumLaunchLabMemeParser {
+    adapter: Arc<TransactionAdapter>,
+}
+
+impl RaydiumLaunchLabMemeParser {
+    pub fn new(adapter: Arc<TransactionAdapter>, _transfer_actions: Arc<TransferMap>) -> Self {
+        Self { adapter }
+    }
+}
+
+impl MemeEventParser for RaydiumLaunchLabMemeParser {
+    fn process_events(&mut self) -> Vec<MemeEvent> {
+        let classifier = InstructionClassifier::new(&self.adapter);
+        let instructions = classifier.get_instructions(RAYDIUM_LAUNCHLAB_PROGRAM_ID);
+        RaydiumLaunchLabEventParser::new().parse_instructions(&self.adapter, instructions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::constants::{dex_programs, TOKENS};
+    use crate::core::test_utils::sample_transaction;
+    use crate::types::SolanaInstruction;
+    use crate::DexParser;
+
+    #[test]
+    fn parses_raydium_launchlab_buy_that_completes_curve() {
+        use crate::protocols::raydium_launchlab::constants::discriminators::{
+            launchlab_events, launchlab_instructions,
+        };
+        use crate::types::{InnerInstruction, TradeType};
+        use base64_simd::STANDARD;
+
+        let mut ix_data = launchlab_instructions::BUY_EXACT_IN.to_vec();
+        ix_data.extend_from_slice(&[0u8; 24]); // amount_in, minimum_amount_out, share_fee_rate
+        let mut event_data = launchlab_events::TRADE.to_vec();
+        event_data.extend_from_slice(&[1u8; 32]); // pool_state
+        for value in [
+            793_100_000_000_000u64, // total_base_sell
+            1_073_025_605_596_382,  // virtual_base
+            30_000_852_951,         // virtual_quote
+            793_000_000_000_000,    // real_base_before
+            84_000_000_000,         // real_quote_before
+            793_100_000_000_000,    // real_base_after
+            85_000_000_000,         // real_quote_after
+            1_000_000_000,          // amount_in
+            100_000_000_000,        // amount_out
+            2_500_000,              // protocol_fee
+            10_000_000,             // platform_fee
+            0,                      // creator_fee
+            0,                      // share_fee
+        ] {
+            event_data.extend_from_slice(&value.to_le_bytes());
+        }
+        event_data.extend_from_slice(&[0, 1, 1]); // Buy, PoolStatus::Migrate, exact_in
+
+        let accounts = [
+            "user",
+            "authority",
+            "global-config",
+            "platform-config",
+            "pool-state",
+            "user-base",
+            "user-quote",
+            "base-vault",
+            "quote-vault",
+            "MEME",
+            TOKENS.SOL,
+        ];
+        let mut tx = sample_transaction();
+        tx.transfers.clear();
+        tx.instructions = vec![SolanaInstruction {
+            program_id: dex_programs::RAYDIUM_LAUNCHLAB.into(),
+            accounts: accounts.map(Into::into).to_vec(),
+            data: STANDARD.encode_to_string(&ix_data),
+        }];
+        tx.inner_instructions = vec![InnerInstruction {
+            index: 0,
+            instructions: vec![SolanaInstruction {
+                program_id: dex_programs::RAYDIUM_LAUNCHLAB.into(),
+                accounts: vec!["event-authority".into()],
+                data: STANDARD.encode_to_string(&event_data),
+            }],
+            stack_heights: Vec::new(),
+        }];
+
+        let result = DexParser::new().parse_all(tx, None);
+        assert_eq!(result.trades.len(), 1);
+        let trade = &result.trades[0];
+        assert_eq!(trade.trade_type, TradeType::Buy);
+        assert_eq!(trade.input_token.mint, TOKENS.SOL);
+        assert_eq!(trade.input_token.amount, 1.0);
+        assert_eq!(trade.output_token.mint, "MEME");
+        assert_eq!(trade.output_token.amount, 100_000.0);
+        assert_eq!(trade.amm.as_deref(), Some("RaydiumLaunchLab"));
+
+        let types: Vec<_> = result
+            .meme_events
+            .iter()
+            .map(|e| e.event_type.clone())
+            .collect();
+        assert_eq!(types, vec![TradeType::Buy, TradeType::Complete]);
+        assert_eq!(result.meme_events[1].pool.as_deref(), Some("pool-state"));
+    }
+}
@@ -1,19 +1,21 @@
+use std::sync::Arc;
+
 use crate::core::constants::dex_program_names;
 use crate::core::transaction_adapter::TransactionAdapter;
-use crate::types::{ClassifiedInstruction, PoolEvent, TradeType, TransferMap};
+use crate::types::{ClassifiedInstruction, PoolEvent, TokenLeg, TradeType, TransferMap};
 
 use super::LiquidityParser;
 
 pub struct SimpleLiquidityParser {
-    adapter: TransactionAdapter,
-    transfer_actions: TransferMap,
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
 }
 
 impl SimpleLiquidityParser {
     pub fn new(
-        adapter: TransactionAdapter,
-        transfer_actions: TransferMap,
+        adapter: Arc<TransactionAdapter>,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Self {
         Self {
@@ -24,8 +26,8 @@ impl SimpleLiquidityParser {
     }
 
     pub fn boxed(
-        adapter: TransactionAdapter,
-        transfer_actions: TransferMap,
+        adapter: Arc<TransactionAdapter>,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Box<dyn LiquidityParser> {
         Box::new(Self::new(
@@ -40,21 +42,20 @@ impl LiquidityParser for SimpleLiquidityParser {
     fn process_liquidity(&mut self) -> Vec<PoolEvent> {
         self.classified_instructions
             .iter()
-            .map(|instruction| {
-                let liquidity: f64 = self
+            .filter_map(|instruction| {
+                // без переводов программы события нет — пустую ногу не выдумываем
+                let transfers = self
                     .transfer_actions
-                    .get(&instruction.program_id)
-                    .map(|transfers| {
-                        transfers
-                            .iter()
-                            .map(|t| {
-                                t.info.token_amount.ui_amount.unwrap_or_else(|| {
-                                    t.info.token_amount.amount.parse::<f64>().unwrap_or(0.0)
-                                })
-                            })
-                            .sum()
+                    .get(instruction.program_id.as_str())
+                    .filter(|transfers| !transfers.is_empty())?;
+                let liquidity: f64 = transfers
+                    .iter()
+                    .map(|t| {
+                        t.info.token_amount.ui_amount.unwrap_or_else(|| {
+                            t.info.token_amount.amount.parse::<f64>().unwrap_or(0.0)
+                        })
                     })
-                    .unwrap_or(0.0);
+                    .sum();
 
                 let idx = format!(
                     "{}-{}",
@@ -69,40 +70,36 @@ impl LiquidityParser for SimpleLiquidityParser {
                     .cloned()
                     .unwrap_or_default();
                 let token1 = instruction.data.accounts.get(1).cloned();
+                // сумма известна только по всем переводам сразу — второй ноги без суммы нет
+                let tokens = vec![TokenLeg {
+                    mint: pool_id.to_string(),
+                    amount: liquidity,
+                    amount_raw: liquidity.to_string(),
+                    ..Default::default()
+                }];
 
-                PoolEvent {
+                Some(PoolEvent {
                     user: self.adapter.signer().to_string(),
                     event_type: TradeType::Add,
-                    program_id: Some(instruction.program_id.clone()),
+                    program_id: Some(instruction.program_id.to_string()),
                     amm: Some(dex_program_names::name(&instruction.program_id).to_string()),
                     slot: self.adapter.slot(),
                     timestamp: self.adapter.block_time(),
                     signature: self.adapter.signature().to_string(),
                     idx,
                     signer: Some(self.adapter.signers().to_vec()),
-                    pool_id,
+                    pool_id: pool_id.to_string(),
                     config: None,
-                    pool_lp_mint: token1.clone(),
-                    token0_mint: Some(
-                        instruction
-                            .data
-                            .accounts
-                            .first()
-                            .cloned()
-                            .unwrap_or_default(),
-                    ),
-                    token0_amount: Some(liquidity),
-                    token0_amount_raw: Some(liquidity.to_string()),
-                    token0_balance_change: None,
-                    token0_decimals: None,
-                    token1_mint: token1,
-                    token1_amount: None,
-                    token1_amount_raw: None,
-                    token1_balance_change: None,
-                    token1_decimals: None,
+                    pool_lp_mint: token1.map(String::from),
                     lp_amount: None,
                     lp_amount_raw: None,
-                }
+                    tokens,
+                    value_usd: None,
+                    instruction_index: 0,
+                    inner_index: None,
+                    slippage: None,
+                    order_key: None,
+                })
             })
             .collect()
     }
@@ -1,15 +1,17 @@
+use std::sync::Arc;
+
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::types::{MemeEvent, TradeType, TransferMap};
 
 use super::MemeEventParser;
 
 pub struct SimpleMemeParser {
-    adapter: TransactionAdapter,
-    transfer_actions: TransferMap,
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
 }
 
 impl SimpleMemeParser {
-    pub fn new(adapter: TransactionAdapter, transfer_actions: TransferMap) -> Self {
+    pub fn new(adapter: Arc<TransactionAdapter>, transfer_actions: Arc<TransferMap>) -> Self {
         Self {
             adapter,
             transfer_actions,
@@ -17,8 +19,8 @@ impl SimpleMemeParser {
     }
 
     pub fn boxed(
-        adapter: TransactionAdapter,
-        transfer_actions: TransferMap,
+        adapter: Arc<TransactionAdapter>,
+        transfer_actions: Arc<TransferMap>,
     ) -> Box<dyn MemeEventParser> {
         Box::new(Self::new(adapter, transfer_actions))
     }
@@ -35,9 +37,9 @@ impl MemeEventParser for SimpleMemeParser {
                 idx: transfer.idx.clone(),
                 slot: self.adapter.slot(),
                 signature: transfer.signature.clone(),
-                user: transfer.info.source.clone(),
-                base_mint: transfer.info.mint.clone(),
-                quote_mint: transfer.info.mint.clone(),
+                user: transfer.info.source.to_string(),
+                base_mint: transfer.info.mint.to_string(),
+                quote_mint: transfer.info.mint.to_string(),
                 input_token: None,
                 output_token: None,
                 name: None,
@@ -46,19 +48,27 @@ impl MemeEventParser for SimpleMemeParser {
                 decimals: Some(transfer.info.token_amount.decimals),
                 total_supply: None,
                 fee: None,
+                fee_recipient: None,
                 protocol_fee: None,
                 platform_fee: None,
                 share_fee: None,
                 creator_fee: None,
-                protocol: Some(transfer.program_id.clone()),
+                protocol: Some(transfer.program_id.to_string()),
                 platform_config: None,
-                creator: transfer.info.authority.clone(),
+                creator: transfer.info.authority.clone().map(String::from),
                 bonding_curve: None,
                 pool: None,
                 pool_dex: None,
                 pool_a_reserve: None,
                 pool_b_reserve: None,
                 pool_fee_rate: None,
+                virtual_sol_reserves: None,
+                virtual_token_reserves: None,
+                real_sol_reserves: None,
+                real_token_reserves: None,
+                price: None,
+                market_cap: None,
+                dev_buy: None,
             })
             .collect()
     }
@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::core::transaction_utils::TransactionUtils;
 use crate::types::{ClassifiedInstruction, DexInfo, TradeInfo, TransferMap};
@@ -7,15 +9,15 @@ use super::TradeParser;
 pub struct SimpleTradeParser {
     utils: TransactionUtils,
     dex_info: DexInfo,
-    transfer_actions: TransferMap,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
 }
 
 impl SimpleTradeParser {
     pub fn new(
-        adapter: TransactionAdapter,
+        adapter: Arc<TransactionAdapter>,
         dex_info: DexInfo,
-        transfer_actions: TransferMap,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Self {
         Self {
@@ -27,9 +29,9 @@ impl SimpleTradeParser {
     }
 
     pub fn boxed(
-        adapter: TransactionAdapter,
+        adapter: Arc<TransactionAdapter>,
         dex_info: DexInfo,
-        transfer_actions: TransferMap,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Box<dyn TradeParser> {
         Box::new(Self::new(
@@ -51,12 +53,15 @@ impl TradeParser for SimpleTradeParser {
                 }
             }
         } else if let Some(first) = self.classified_instructions.first() {
-            if let Some(transfers) = self.transfer_actions.get(&first.program_id) {
+            if let Some(transfers) = self.transfer_actions.get(first.program_id.as_str()) {
                 if let Some(trade) = self.utils.process_swap_data(transfers, &self.dex_info) {
                     trades.push(trade);
                 }
             }
         }
         trades
+            .into_iter()
+            .map(|trade| self.utils.attach_pool_reserves(trade))
+            .collect()
     }
 }
@@ -1,20 +1,22 @@
+use std::sync::Arc;
+
 use crate::core::transaction_adapter::TransactionAdapter;
 use crate::types::{ClassifiedInstruction, DexInfo, TransferData, TransferMap};
 
 use super::TransferParser;
 
 pub struct SimpleTransferParser {
-    adapter: TransactionAdapter,
+    adapter: Arc<TransactionAdapter>,
     dex_info: DexInfo,
-    transfer_actions: TransferMap,
+    transfer_actions: Arc<TransferMap>,
     classified_instructions: Vec<ClassifiedInstruction>,
 }
 
 impl SimpleTransferParser {
     pub fn new(
-        adapter: TransactionAdapter,
+        adapter: Arc<TransactionAdapter>,
         dex_info: DexInfo,
-        transfer_actions: TransferMap,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Self {
         Self {
@@ -26,9 +28,9 @@ impl SimpleTransferParser {
     }
 
     pub fn boxed(
-        adapter: TransactionAdapter,
+        adapter: Arc<TransactionAdapter>,
         dex_info: DexInfo,
-        transfer_actions: TransferMap,
+        transfer_actions: Arc<TransferMap>,
         classified_instructions: Vec<ClassifiedInstruction>,
     ) -> Box<dyn TransferParser> {
         Box::new(Self::new(
@@ -52,7 +54,7 @@ impl TransferParser for SimpleTransferParser {
 
         self.classified_instructions
             .first()
-            .and_then(|instruction| self.transfer_actions.get(&instruction.program_id))
+            .and_then(|instruction| self.transfer_actions.get(instruction.program_id.as_str()))
             .cloned()
             .unwrap_or_else(|| self.adapter.transfers().to_vec())
     }
@@ -0,0 +1,19 @@
+pub const SABER_PROGRAM_ID: &str = "SSwpkEEcbUqx4vtoEByFjSkhKdCT862DNVb52nZg1UZ";
+pub const SABER_PROGRAM_NAME: &str = "Saber";
+pub const MERCURIAL_PROGRAM_ID: &str = "MERLuDFBMmsHnsBPZw2sDQZHvXFMwp8EdjudcU2HKky";
+pub const MERCURIAL_PROGRAM_NAME: &str = "Mercurial";
+
+/// Обе программы — не anchor: инструкция определяется первым байтом.
+pub mod saber_instructions {
+    pub const SWAP: u8 = 1;
+    pub const DEPOSIT: u8 = 2;
+    pub const WITHDRAW: u8 = 3;
+    pub const WITHDRAW_ONE: u8 = 4;
+}
+
+pub mod mercurial_instructions {
+    pub const ADD_LIQUIDITY: u8 = 1;
+    pub const REMOVE_LIQUIDITY: u8 = 2;
+    pub const REMOVE_LIQUIDITY_ONE_TOKEN: u8 = 3;
+    pub const EXCHANGE: u8 = 4;
+}
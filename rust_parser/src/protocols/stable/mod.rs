@@ -0,0 +1,63 @@
+pub mod constants;
+pub mod stable_liquidity;
+pub mod stable_parser;
+
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::protocols::simple::{LiquidityParser, TradeParser};
+use crate::types::{ClassifiedInstruction, DexInfo, TransferMap};
+
+use constants::{MERCURIAL_PROGRAM_ID, MERCURIAL_PROGRAM_NAME, SABER_PROGRAM_NAME};
+use stable_liquidity::StableSwapLiquidityParser;
+use stable_parser::StableSwapParser;
+
+/// Saber и Mercurial — одна модель stable-swap, отличаются только тегами инструкций.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StableProgram {
+    Saber,
+    Mercurial,
+}
+
+impl StableProgram {
+    pub fn from_program_id(program_id: &str) -> Self {
+        if program_id == MERCURIAL_PROGRAM_ID {
+            Self::Mercurial
+        } else {
+            Self::Saber
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Saber => SABER_PROGRAM_NAME,
+            Self::Mercurial => MERCURIAL_PROGRAM_NAME,
+        }
+    }
+}
+
+pub fn build_stable_trade_parser(
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+) -> Box<dyn TradeParser> {
+    Box::new(StableSwapParser::new(
+        adapter,
+        dex_info,
+        transfer_actions,
+        classified_instructions,
+    ))
+}
+
+pub fn build_stable_liquidity_parser(
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+) -> Box<dyn LiquidityParser> {
+    Box::new(StableSwapLiquidityParser::new(
+        adapter,
+        transfer_actions,
+        classified_instructions,
+    ))
+}
@@ -0,0 +1,213 @@
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::core::utils::get_instruction_actions;
+use crate::protocols::pumpfun::util::{convert_to_ui_amount, get_instruction_data};
+use crate::protocols::simple::LiquidityParser;
+use crate::types::{
+    ClassifiedInstruction, LegDirection, PoolEvent, PoolEventType, TokenLeg, TradeType,
+    TransferData, TransferMap,
+};
+
+use super::constants::{mercurial_instructions, saber_instructions};
+use super::StableProgram;
+
+/// Deposit/Withdraw stable-пулов; в пуле может быть больше двух токенов (Mercurial 3pool).
+pub struct StableSwapLiquidityParser {
+    adapter: Arc<TransactionAdapter>,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+}
+
+impl StableSwapLiquidityParser {
+    pub fn new(
+        adapter: Arc<TransactionAdapter>,
+        transfer_actions: Arc<TransferMap>,
+        classified_instructions: Vec<ClassifiedInstruction>,
+    ) -> Self {
+        Self {
+            adapter,
+            transfer_actions,
+            classified_instructions,
+        }
+    }
+
+    fn pool_action(program: StableProgram, tag: u8) -> Option<PoolEventType> {
+        match (program, tag) {
+            (StableProgram::Saber, saber_instructions::DEPOSIT)
+            | (StableProgram::Mercurial, mercurial_instructions::ADD_LIQUIDITY) => {
+                Some(PoolEventType::Add)
+            }
+            (StableProgram::Saber, saber_instructions::WITHDRAW)
+            | (StableProgram::Saber, saber_instructions::WITHDRAW_ONE)
+            | (StableProgram::Mercurial, mercurial_instructions::REMOVE_LIQUIDITY)
+            | (StableProgram::Mercurial, mercurial_instructions::REMOVE_LIQUIDITY_ONE_TOKEN) => {
+                Some(PoolEventType::Remove)
+            }
+            _ => None,
+        }
+    }
+
+    fn parse_instruction(&self, classified: &ClassifiedInstruction) -> Option<PoolEvent> {
+        let program = StableProgram::from_program_id(&classified.program_id);
+        let data = get_instruction_data(&classified.data).ok()?;
+        let action = Self::pool_action(program, *data.first()?)?;
+
+        let actions = get_instruction_actions(
+            &self.transfer_actions,
+            &classified.program_id,
+            classified.outer_index,
+            classified.inner_index,
+        );
+        let lp_kind = match action {
+            PoolEventType::Add => "mintTo",
+            _ => "burn",
+        };
+        let lp_token = actions
+            .iter()
+            .find(|t| t.transfer_type.starts_with(lp_kind))
+            .map(|t| (*t).clone())
+            .or_else(|| {
+                self.adapter.find_lp_token_action(
+                    classified.outer_index,
+                    classified.inner_index,
+                    action == PoolEventType::Add,
+                    None,
+                )
+            });
+        let direction = match action {
+            PoolEventType::Add => LegDirection::In,
+            _ => LegDirection::Out,
+        };
+        let legs = token_legs(
+            &actions,
+            lp_token.as_ref().map(|t| t.info.mint.as_str()),
+            direction,
+        );
+        if legs.is_empty() {
+            return None;
+        }
+
+        let accounts = self.adapter.get_instruction_accounts(&classified.data);
+        let mut base = self
+            .adapter
+            .get_pool_event_base(action.clone(), &classified.program_id);
+        base.idx = format!(
+            "{}-{}",
+            classified.outer_index,
+            classified.inner_index.unwrap_or(0)
+        );
+
+        Some(PoolEvent {
+            user: base.user,
+            event_type: match action {
+                PoolEventType::Add => TradeType::Add,
+                _ => TradeType::Remove,
+            },
+            program_id: base.program_id,
+            amm: Some(program.name().to_string()),
+            slot: base.slot,
+            timestamp: base.timestamp,
+            signature: base.signature,
+            idx: base.idx,
+            signer: base.signer,
+            pool_id: accounts.first().map(ToString::to_string).unwrap_or_default(),
+            config: None,
+            pool_lp_mint: lp_token.as_ref().map(|t| t.info.mint.to_string()),
+            lp_amount: lp_token.as_ref().map(transfer_ui_amount),
+            lp_amount_raw: lp_token.map(|t| t.info.token_amount.amount),
+            tokens: legs,
+            value_usd: None,
+            instruction_index: 0,
+            inner_index: None,
+            slippage: None,
+            order_key: None,
+        })
+    }
+}
+
+impl LiquidityParser for StableSwapLiquidityParser {
+    fn process_liquidity(&mut self) -> Vec<PoolEvent> {
+        self.classified_instructions
+            .iter()
+            .filter_map(|classified| self.parse_instruction(classified))
+            .collect()
+    }
+}
+
+/// Первый трансфер каждого mint, кроме LP: следом Saber переводит admin fee
+/// тем же mint, его в ногу не включаем.
+pub(crate) fn token_legs(
+    actions: &[&TransferData],
+    lp_mint: Option<&str>,
+    direction: LegDirection,
+) -> Vec<TokenLeg> {
+    let mut legs: Vec<TokenLeg> = Vec::new();
+    for transfer in actions {
+        if !matches!(transfer.transfer_type.as_str(), "transfer" | "transferChecked" | "transferCheckedWithFee")
+            || Some(transfer.info.mint.as_str()) == lp_mint
+            || legs.iter().any(|leg| leg.mint == transfer.info.mint)
+        {
+            continue;
+        }
+        legs.push(TokenLeg::from_transfer(transfer, direction));
+    }
+    legs
+}
+
+pub(crate) fn transfer_ui_amount(transfer: &TransferData) -> f64 {
+    let amount = &transfer.info.token_amount;
+    amount.ui_amount.unwrap_or_else(|| {
+        convert_to_ui_amount(amount.amount.parse::<u128>().unwrap_or(0), amount.decimals)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::constants::dex_programs;
+    use crate::core::test_utils::sample_transaction;
+    use crate::types::{SolanaInstruction, TokenAmount};
+    use crate::DexParser;
+
+    #[test]
+    fn parses_mercurial_three_token_deposit() {
+        use crate::protocols::stable::constants::mercurial_instructions;
+        use crate::types::LegDirection;
+        use base64_simd::STANDARD;
+
+        let mut tx = sample_transaction();
+        tx.instructions = vec![SolanaInstruction {
+            program_id: dex_programs::MERCURIAL.into(),
+            accounts: vec!["3pool".into()],
+            data: STANDARD.encode_to_string([mercurial_instructions::ADD_LIQUIDITY]),
+        }];
+        let mut third = tx.transfers[0].clone();
+        third.info.mint = "USDT".into();
+        third.idx = "0-2".to_string();
+        let mut lp = tx.transfers[0].clone();
+        lp.transfer_type = "mintTo".to_string();
+        lp.info.mint = "LP".into();
+        lp.info.token_amount = TokenAmount::new("2990000", 6, Some(2.99));
+        lp.idx = "0-3".to_string();
+        tx.transfers.extend([third, lp]);
+        tx.transfers.iter_mut().for_each(|transfer| {
+            transfer.program_id = dex_programs::MERCURIAL.into();
+        });
+
+        let result = DexParser::new().parse_all(tx, None);
+        assert_eq!(result.liquidities.len(), 1);
+        let event = &result.liquidities[0];
+        assert_eq!(event.amm.as_deref(), Some("Mercurial"));
+        assert_eq!(event.pool_id, "3pool");
+        assert_eq!(event.pool_lp_mint.as_deref(), Some("LP"));
+        assert_eq!(event.lp_amount, Some(2.99));
+        let mints: Vec<&str> = event.tokens.iter().map(|leg| leg.mint.as_str()).collect();
+        assert_eq!(mints, ["BASE", "QUOTE", "USDT"]);
+        assert_eq!(event.token0().map(|leg| leg.mint.as_str()), Some("BASE"));
+        assert_eq!(event.token1().map(|leg| leg.mint.as_str()), Some("QUOTE"));
+        assert!(event
+            .tokens
+            .iter()
+            .all(|leg| leg.direction == LegDirection::In));
+    }
+}
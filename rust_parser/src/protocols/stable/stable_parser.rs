@@ -0,0 +1,100 @@
+use std::sync::Arc;
+
+use crate::core::transaction_adapter::TransactionAdapter;
+use crate::core::transaction_utils::TransactionUtils;
+use crate::core::utils::get_instruction_transfers;
+use crate::protocols::pumpfun::util::get_instruction_data;
+use crate::protocols::simple::TradeParser;
+use crate::types::{AccountKey, ClassifiedInstruction, DexInfo, TradeInfo, TransferData, TransferMap};
+
+use super::constants::{mercurial_instructions, saber_instructions};
+use super::StableProgram;
+
+/// Swap/Exchange Saber и Mercurial: событий нет, суммы из трансферов инструкции.
+pub struct StableSwapParser {
+    adapter: Arc<TransactionAdapter>,
+    dex_info: DexInfo,
+    transfer_actions: Arc<TransferMap>,
+    classified_instructions: Vec<ClassifiedInstruction>,
+    utils: TransactionUtils,
+}
+
+impl StableSwapParser {
+    pub fn new(
+        adapter: Arc<TransactionAdapter>,
+        dex_info: DexInfo,
+        transfer_actions: Arc<TransferMap>,
+        classified_instructions: Vec<ClassifiedInstruction>,
+    ) -> Self {
+        let utils = TransactionUtils::new(adapter.clone());
+        Self {
+            adapter,
+            dex_info,
+            transfer_actions,
+            classified_instructions,
+            utils,
+        }
+    }
+}
+
+impl TradeParser for StableSwapParser {
+    fn process_trades(&mut self) -> Vec<TradeInfo> {
+        let mut trades = Vec::new();
+
+        for classified in &self.classified_instructions {
+            let program = StableProgram::from_program_id(&classified.program_id);
+            let swap_tag = match program {
+                StableProgram::Saber => saber_instructions::SWAP,
+                StableProgram::Mercurial => mercurial_instructions::EXCHANGE,
+            };
+            let is_swap = get_instruction_data(&classified.data)
+                .is_ok_and(|data| data.first() == Some(&swap_tag));
+            if !is_swap {
+                continue;
+            }
+
+            // swap_info, authority, user_authority, source, swap_source, swap_destination,
+            // destination, admin_fee_destination, ... — у Mercurial первым тоже swap_info
+            let accounts = self.adapter.get_instruction_accounts(&classified.data);
+            // Saber отдельно переводит admin fee тем же mint, что и выход: иначе
+            // process_swap_data сложит его с суммой пользователя
+            let admin_fee = match program {
+                StableProgram::Saber => accounts.get(7).map(AccountKey::as_str),
+                StableProgram::Mercurial => None,
+            };
+            let transfers: Vec<TransferData> = get_instruction_transfers(
+                &self.transfer_actions,
+                &classified.program_id,
+                classified.outer_index,
+                classified.inner_index,
+            )
+            .into_iter()
+            .filter(|transfer| Some(transfer.info.destination.as_str()) != admin_fee)
+            .cloned()
+            .collect();
+            if transfers.len() < 2 {
+                continue;
+            }
+
+            let dex_info = DexInfo {
+                program_id: Some(classified.program_id.to_string()),
+                amm: Some(program.name().to_string()),
+                route: self.dex_info.route.clone(),
+            };
+            let Some(mut trade) = self.utils.process_swap_data(&transfers, &dex_info) else {
+                continue;
+            };
+
+            if let Some(pool) = accounts.first() {
+                trade.pool = vec![pool.to_string()];
+            }
+
+            trades.push(
+                self.utils
+                    .attach_token_transfer_info(trade, &self.transfer_actions),
+            );
+        }
+
+        trades
+    }
+}
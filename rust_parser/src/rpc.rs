@@ -2,29 +2,52 @@ use std::collections::HashMap;
 use std::str::FromStr;
 
 use anyhow::{anyhow, Context, Result};
+use base64_simd::STANDARD;
 use solana_client::rpc_client::RpcClient;
 use solana_client::rpc_config::RpcTransactionConfig;
 use solana_sdk::commitment_config::CommitmentConfig;
 use solana_sdk::signature::Signature;
 use solana_transaction_status::{
-    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, UiCompiledInstruction,
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction,
+    EncodedTransactionWithStatusMeta, TransactionBinaryEncoding, UiCompiledInstruction,
     UiInnerInstructions, UiInstruction, UiLoadedAddresses, UiMessage, UiParsedInstruction,
     UiTransactionEncoding, UiTransactionStatusMeta, UiTransactionTokenBalance,
 };
 
+use crate::core::error::ParserError;
+use crate::core::meta_compat::compute_units_from_logs;
+use crate::core::rpc_json::transaction_from_raw;
 use crate::types::{
-    BalanceChange, InnerInstruction, SolanaInstruction, SolanaTransaction, TokenAmount,
+    AccountKey, BalanceChange, InnerInstruction, KeyInterner, SolanaInstruction, SolanaTransaction, TokenAmount,
     TokenBalance, TransactionMeta, TransactionStatus,
 };
 
-type MessageExtraction = (Vec<SolanaInstruction>, Vec<String>, Vec<String>, String);
+pub mod alt;
+pub mod block_fetcher;
+pub mod discovery;
+pub mod finalization;
+pub mod pool;
+pub mod replay;
+pub mod ws;
+
+pub use alt::LookupTableResolver;
+pub use block_fetcher::{BlockFetcher, BlockFetcherConfig};
+pub use discovery::{discover_pools, FeeConfig, PoolDiscovery, PoolFilters, PoolKind, PoolState};
+pub use finalization::{FinalizationConfig, FinalizationEvent, FinalizationTracker};
+pub use pool::{EndpointHealth, RpcPool, RpcPoolConfig};
+pub use replay::{replay, FrameRecorder, RecordedFrame, ReplaySpeed};
+pub use ws::{connect_with_failover, SubscriberConfig, SubscriptionFilter, TransactionSubscriber};
+
+type MessageExtraction = (Vec<SolanaInstruction>, Vec<AccountKey>, Vec<String>, String);
 
 /// Fetch a transaction from RPC and convert it into the internal SolanaTransaction type.
 pub fn fetch_transaction(rpc_url: &str, signature: &str) -> Result<SolanaTransaction> {
     let client = RpcClient::new(rpc_url.to_string());
-    let signature = Signature::from_str(signature).context("invalid signature")?;
+    let signature = Signature::from_str(signature).map_err(|_| ParserError::Base58Decode {
+        value: signature.to_string(),
+    })?;
     let config = RpcTransactionConfig {
-        encoding: Some(UiTransactionEncoding::Json), // Uses base64 encoding for instruction data (20–50× faster than bs58)
+        encoding: Some(UiTransactionEncoding::Json),
         commitment: Some(CommitmentConfig::confirmed()),
         max_supported_transaction_version: Some(0),
     };
@@ -36,25 +59,52 @@ pub fn fetch_transaction(rpc_url: &str, signature: &str) -> Result<SolanaTransac
 }
 
 fn convert_transaction(tx: EncodedConfirmedTransactionWithStatusMeta) -> Result<SolanaTransaction> {
+    SolanaTransaction::try_from_encoded(&tx)
+}
+
+impl SolanaTransaction {
+    /// Ответ `get_transaction_with_config` из solana-client в любом encoding:
+    /// `Json`, `JsonParsed`, `Base58`, `Base64`.
+    pub fn try_from_encoded(tx: &EncodedConfirmedTransactionWithStatusMeta) -> Result<Self> {
+        convert_encoded_transaction(tx.slot, tx.block_time, &tx.transaction)
+    }
+}
+
+/// Общий конвертер для getTransaction и транзакций из getBlock
+pub(crate) fn convert_encoded_transaction(
+    slot: u64,
+    block_time: Option<i64>,
+    tx: &EncodedTransactionWithStatusMeta,
+) -> Result<SolanaTransaction> {
     let meta = tx
-        .transaction
         .meta
         .as_ref()
         .context("transaction missing status meta")?;
+    if let Some(raw) = binary_transaction(&tx.transaction)? {
+        // meta сериализуется обратно в JSON RPC и идёт через zero-copy конвертер
+        let meta = serde_json::to_value(meta)?;
+        let block_time = block_time.unwrap_or_default() as u64;
+        return Ok(transaction_from_raw(&raw, slot, block_time, &meta)?);
+    }
+    // инструкции ссылаются на общие `Arc<str>` ключей, а не копируют строки
+    let mut interner = KeyInterner::new();
     let (instructions, account_keys, signers, signature) =
-        extract_message(&tx.transaction.transaction, meta)?;
+        extract_message(&tx.transaction, meta, &mut interner)?;
 
-    let inner_instructions =
-        convert_inner_instructions(meta.inner_instructions.as_ref().into(), &account_keys);
+    let inner_instructions = convert_inner_instructions(
+        meta.inner_instructions.as_ref().into(),
+        &account_keys,
+        &mut interner,
+    )?;
     let pre_token_balances =
         convert_token_balances(meta.pre_token_balances.as_ref().into(), &account_keys);
     let post_token_balances =
         convert_token_balances(meta.post_token_balances.as_ref().into(), &account_keys);
 
     let solana_tx = SolanaTransaction {
-        slot: tx.slot,
+        slot,
         signature,
-        block_time: tx.block_time.unwrap_or_default() as u64,
+        block_time: block_time.unwrap_or_default() as u64,
         signers,
         instructions,
         inner_instructions,
@@ -63,7 +113,12 @@ fn convert_transaction(tx: EncodedConfirmedTransactionWithStatusMeta) -> Result<
         post_token_balances,
         meta: TransactionMeta {
             fee: meta.fee,
-            compute_units: Option::<u64>::from(meta.compute_units_consumed.clone()).unwrap_or(0),
+            compute_units: Option::<u64>::from(meta.compute_units_consumed.clone())
+                .or_else(|| {
+                    let logs = Option::<&Vec<String>>::from(meta.log_messages.as_ref())?;
+                    compute_units_from_logs(logs.iter().map(String::as_str))
+                })
+                .unwrap_or(0),
             status: if meta.err.is_some() {
                 TransactionStatus::Failed
             } else {
@@ -71,15 +126,41 @@ fn convert_transaction(tx: EncodedConfirmedTransactionWithStatusMeta) -> Result<
             },
             sol_balance_changes: collect_sol_balance_changes(meta, &account_keys),
             token_balance_changes: HashMap::new(),
+            err: meta
+                .err
+                .as_ref()
+                .and_then(|err| serde_json::to_string(err).ok()),
+            log_messages: Option::<Vec<String>>::from(meta.log_messages.clone()).unwrap_or_default(),
         },
     };
 
     Ok(solana_tx)
 }
 
+/// Байты транзакции для `base58`/`base64`; `None` для JSON-кодировок.
+fn binary_transaction(encoded: &EncodedTransaction) -> Result<Option<Vec<u8>>> {
+    let raw = match encoded {
+        EncodedTransaction::Json(_) => return Ok(None),
+        EncodedTransaction::LegacyBinary(data)
+        | EncodedTransaction::Binary(data, TransactionBinaryEncoding::Base58) => {
+            bs58::decode(data).into_vec().map_err(|_| ParserError::Base58Decode {
+                value: data.clone(),
+            })?
+        }
+        EncodedTransaction::Binary(data, TransactionBinaryEncoding::Base64) => STANDARD
+            .decode_to_vec(data)
+            .map_err(|err| anyhow!("invalid base64 transaction: {err}"))?,
+        EncodedTransaction::Accounts(_) => {
+            return Err(anyhow!("`accounts` transaction details carry no instructions"))
+        }
+    };
+    Ok(Some(raw))
+}
+
 fn extract_message(
     encoded: &EncodedTransaction,
     meta: &UiTransactionStatusMeta,
+    interner: &mut KeyInterner,
 ) -> Result<MessageExtraction> {
     let ui_tx = match encoded {
         EncodedTransaction::Json(tx) => tx,
@@ -99,17 +180,16 @@ fn extract_message(
                 .take(raw.header.num_required_signatures as usize)
                 .cloned()
                 .collect();
-            let mut account_keys = raw.account_keys.clone();
-            append_loaded_addresses(&mut account_keys, meta);
+            let account_keys = intern_account_keys(&raw.account_keys, meta, interner);
             let instructions = raw
                 .instructions
                 .iter()
                 .map(|ix| convert_compiled_instruction(ix, &account_keys))
-                .collect();
+                .collect::<Result<_>>()?;
             Ok((instructions, account_keys, signers, signature))
         }
         UiMessage::Parsed(parsed) => {
-            let mut account_keys: Vec<String> = parsed
+            let pubkeys: Vec<String> = parsed
                 .account_keys
                 .iter()
                 .map(|account| account.pubkey.clone())
@@ -120,54 +200,67 @@ fn extract_message(
                 .filter(|account| account.signer)
                 .map(|account| account.pubkey.clone())
                 .collect();
-            append_loaded_addresses(&mut account_keys, meta);
+            let account_keys = intern_account_keys(&pubkeys, meta, interner);
             let instructions = parsed
                 .instructions
                 .iter()
-                .map(|ix| convert_ui_instruction(ix, &account_keys))
-                .collect();
+                .map(|ix| convert_ui_instruction(ix, &account_keys, interner))
+                .collect::<Result<_>>()?;
             Ok((instructions, account_keys, signers, signature))
         }
     }
 }
 
-fn append_loaded_addresses(keys: &mut Vec<String>, meta: &UiTransactionStatusMeta) {
+/// Ключи сообщения + адреса из lookup-таблиц (`meta.loadedAddresses`).
+fn intern_account_keys(
+    static_keys: &[String],
+    meta: &UiTransactionStatusMeta,
+    interner: &mut KeyInterner,
+) -> Vec<AccountKey> {
+    let mut keys: Vec<AccountKey> = static_keys.iter().map(|key| interner.intern(key)).collect();
     if let Some(loaded) = Option::<&UiLoadedAddresses>::from(meta.loaded_addresses.as_ref()) {
-        keys.extend(loaded.writable.iter().cloned());
-        keys.extend(loaded.readonly.iter().cloned());
+        keys.extend(loaded.writable.iter().map(|key| interner.intern(key)));
+        keys.extend(loaded.readonly.iter().map(|key| interner.intern(key)));
     }
+    keys
 }
 
 fn convert_inner_instructions(
     sets: Option<&Vec<UiInnerInstructions>>,
-    account_keys: &[String],
-) -> Vec<InnerInstruction> {
-    sets.map(|inner_sets| {
-        inner_sets
-            .iter()
-            .map(|set| InnerInstruction {
+    account_keys: &[AccountKey],
+    interner: &mut KeyInterner,
+) -> Result<Vec<InnerInstruction>> {
+    let Some(inner_sets) = sets else {
+        return Ok(Vec::new());
+    };
+    inner_sets
+        .iter()
+        .map(|set| {
+            let instructions = set
+                .instructions
+                .iter()
+                .map(|ix| convert_ui_instruction(ix, account_keys, interner))
+                .collect::<Result<_>>()
+                .with_context(|| format!("inner instructions of #{}", set.index))?;
+            Ok(InnerInstruction {
                 index: set.index as usize,
-                instructions: set
-                    .instructions
-                    .iter()
-                    .map(|ix| convert_ui_instruction(ix, account_keys))
-                    .collect(),
+                instructions,
+                stack_heights: set.instructions.iter().map(ui_stack_height).collect(),
             })
-            .collect()
-    })
-    .unwrap_or_default()
+        })
+        .collect()
 }
 
 fn convert_token_balances(
     balances: Option<&Vec<UiTransactionTokenBalance>>,
-    account_keys: &[String],
+    account_keys: &[AccountKey],
 ) -> Vec<TokenBalance> {
     balances
         .map(|items| {
             items
                 .iter()
                 .filter_map(|balance| {
-                    let account = account_keys.get(balance.account_index as usize)?.clone();
+                    let account = account_keys.get(balance.account_index as usize)?.to_string();
                     Some(TokenBalance {
                         account,
                         mint: balance.mint.clone(),
@@ -176,6 +269,7 @@ fn convert_token_balances(
                             amount: balance.ui_token_amount.amount.clone(),
                             ui_amount: balance.ui_token_amount.ui_amount,
                             decimals: balance.ui_token_amount.decimals,
+                            ui_amount_string: Some(balance.ui_token_amount.ui_amount_string.clone()),
                         },
                     })
                 })
@@ -186,14 +280,14 @@ fn convert_token_balances(
 
 fn collect_sol_balance_changes(
     meta: &UiTransactionStatusMeta,
-    account_keys: &[String],
+    account_keys: &[AccountKey],
 ) -> HashMap<String, BalanceChange> {
     let mut changes = HashMap::new();
     for (idx, key) in account_keys.iter().enumerate() {
         if let (Some(pre), Some(post)) = (meta.pre_balances.get(idx), meta.post_balances.get(idx)) {
             if pre != post {
                 changes.insert(
-                    key.clone(),
+                    key.to_string(),
                     BalanceChange {
                         pre: *pre as i128,
                         post: *post as i128,
@@ -206,10 +300,21 @@ fn collect_sol_balance_changes(
     changes
 }
 
+/// JSON encoding отдаёт data инструкций в bs58, а парсер ожидает base64. Раньше data
+/// передавалась как есть; не-bs58 строка теперь ошибка, а не сырые данные в парсере.
+fn instruction_data(data: &str) -> Result<String> {
+    let bytes = bs58::decode(data)
+        .into_vec()
+        .map_err(|_| ParserError::Base58Decode {
+            value: data.to_string(),
+        })?;
+    Ok(STANDARD.encode_to_string(&bytes))
+}
+
 fn convert_compiled_instruction(
     instruction: &UiCompiledInstruction,
-    account_keys: &[String],
-) -> SolanaInstruction {
+    account_keys: &[AccountKey],
+) -> Result<SolanaInstruction> {
     let program_id = account_keys
         .get(instruction.program_id_index as usize)
         .cloned()
@@ -219,30 +324,73 @@ fn convert_compiled_instruction(
         .iter()
         .filter_map(|index| account_keys.get(*index as usize).cloned())
         .collect();
-    SolanaInstruction {
+    Ok(SolanaInstruction {
         program_id,
         accounts,
-        data: instruction.data.clone(),
+        data: instruction_data(&instruction.data)?,
+    })
+}
+
+fn ui_stack_height(instruction: &UiInstruction) -> Option<u32> {
+    match instruction {
+        UiInstruction::Compiled(compiled) => compiled.stack_height,
+        UiInstruction::Parsed(UiParsedInstruction::PartiallyDecoded(instruction)) => {
+            instruction.stack_height
+        }
+        UiInstruction::Parsed(UiParsedInstruction::Parsed(instruction)) => instruction.stack_height,
     }
 }
 
 fn convert_ui_instruction(
     instruction: &UiInstruction,
-    account_keys: &[String],
-) -> SolanaInstruction {
+    account_keys: &[AccountKey],
+    interner: &mut KeyInterner,
+) -> Result<SolanaInstruction> {
     match instruction {
         UiInstruction::Compiled(compiled) => convert_compiled_instruction(compiled, account_keys),
         UiInstruction::Parsed(parsed) => match parsed {
-            UiParsedInstruction::PartiallyDecoded(instruction) => SolanaInstruction {
-                program_id: instruction.program_id.clone(),
-                accounts: instruction.accounts.clone(),
-                data: instruction.data.clone(),
-            },
-            UiParsedInstruction::Parsed(instruction) => SolanaInstruction {
-                program_id: instruction.program_id.clone(),
+            UiParsedInstruction::PartiallyDecoded(instruction) => Ok(SolanaInstruction {
+                program_id: interner.intern(&instruction.program_id),
+                accounts: instruction.accounts.iter().map(|key| interner.intern(key)).collect(),
+                data: instruction_data(&instruction.data)?,
+            }),
+            UiParsedInstruction::Parsed(instruction) => Ok(SolanaInstruction {
+                program_id: interner.intern(&instruction.program_id),
                 accounts: Vec::new(),
                 data: instruction.parsed.to_string(),
-            },
+            }),
         },
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compiled_instruction_data_is_reencoded_to_base64() {
+        let keys: Vec<AccountKey> = vec!["program".into(), "user".into()];
+        let instruction = UiCompiledInstruction {
+            program_id_index: 0,
+            accounts: vec![1],
+            data: bs58::encode([1u8, 2, 3]).into_string(),
+            stack_height: None,
+        };
+        let converted = convert_compiled_instruction(&instruction, &keys).unwrap();
+        assert_eq!(converted.data, STANDARD.encode_to_string([1u8, 2, 3]));
+        assert_eq!(converted.accounts, vec![AccountKey::from("user")]);
+    }
+
+    #[test]
+    fn rejects_instruction_data_that_is_not_base58() {
+        let keys: Vec<AccountKey> = vec!["program".into()];
+        let instruction = UiCompiledInstruction {
+            program_id_index: 0,
+            accounts: Vec::new(),
+            data: "0OIl".to_string(),
+            stack_height: None,
+        };
+        let err = convert_compiled_instruction(&instruction, &keys).unwrap_err();
+        assert!(err.downcast_ref::<ParserError>().is_some(), "{err:#}");
+    }
+}
@@ -0,0 +1,402 @@
+//! Разворачивание address lookup tables для v0 транзакций без `meta.loadedAddresses`.
+//!
+//! Без них список ключей обрезан статическими адресами, и индексы аккаунтов
+//! инструкций указывают не туда. Resolver дописывает `loadedAddresses` в JSON
+//! транзакции до конвертации, содержимое таблиц кэшируется (LRU с TTL).
+
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use base64_simd::STANDARD as B64;
+use lru::LruCache;
+use serde_json::{json, Value};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+
+use super::pool::{RpcPool, RpcPoolConfig};
+
+/// Ссылка сообщения на таблицу: адрес таблицы и индексы внутри неё.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TableLookup {
+    pub table: String,
+    pub writable_indexes: Vec<u8>,
+    pub readonly_indexes: Vec<u8>,
+}
+
+/// Адреса из таблиц в порядке рантайма: все writable, затем все readonly.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoadedAddresses {
+    pub writable: Vec<String>,
+    pub readonly: Vec<String>,
+}
+
+pub struct LookupTableResolver {
+    pool: Arc<RpcPool>,
+    cache: Mutex<LruCache<String, CachedTable>>,
+    ttl: Duration,
+}
+
+/// Содержимое таблицы и слот, на котором оно прочитано. Таблицы только дописываются,
+/// поэтому устаревшая копия — это короткая копия.
+#[derive(Clone)]
+struct CachedTable {
+    addresses: Arc<Vec<String>>,
+    slot: u64,
+    fetched_at: Instant,
+}
+
+impl CachedTable {
+    /// Копия не покрывает индексы lookup'а, а транзакция новее неё — таблицу дописали.
+    fn outdated_for(&self, lookup: &TableLookup, slot: Option<u64>) -> bool {
+        let len = self.addresses.len();
+        let out_of_range = lookup
+            .writable_indexes
+            .iter()
+            .chain(&lookup.readonly_indexes)
+            .any(|index| *index as usize >= len);
+        out_of_range && slot.is_none_or(|slot| slot > self.slot)
+    }
+}
+
+impl LookupTableResolver {
+    pub const DEFAULT_CACHE_SIZE: usize = 4096;
+    /// Таблицы могут деактивироваться и закрываться, поэтому копия живёт ограниченно.
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(600);
+
+    pub fn new(rpc_url: &str) -> Self {
+        Self::with_client(
+            Arc::new(RpcClient::new(rpc_url.to_string())),
+            Self::DEFAULT_CACHE_SIZE,
+        )
+    }
+
+    pub fn with_client(client: Arc<RpcClient>, cache_size: usize) -> Self {
+        let pool = RpcPool::from_clients(vec![client], RpcPoolConfig::default())
+            .expect("single endpoint pool");
+        Self::with_pool(Arc::new(pool), cache_size)
+    }
+
+    /// Таблицы запрашиваются через общий пул endpoints.
+    pub fn with_pool(pool: Arc<RpcPool>, cache_size: usize) -> Self {
+        let capacity = NonZeroUsize::new(cache_size).unwrap_or(NonZeroUsize::MIN);
+        Self {
+            pool,
+            cache: Mutex::new(LruCache::new(capacity)),
+            ttl: Self::DEFAULT_TTL,
+        }
+    }
+
+    /// Сколько держать таблицу в кэше до повторного чтения.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Кладёт содержимое таблицы, прочитанное на `slot`, в кэш (прогрев, тесты).
+    pub fn insert_table(&self, table: &str, addresses: Vec<String>, slot: u64) {
+        self.store(
+            table.to_string(),
+            CachedTable {
+                addresses: Arc::new(addresses),
+                slot,
+                fetched_at: Instant::now(),
+            },
+        );
+    }
+
+    fn store(&self, table: String, cached: CachedTable) {
+        if let Ok(mut cache) = self.cache.lock() {
+            cache.put(table, cached);
+        }
+    }
+
+    /// Адреса таблиц; недостающие в кэше или протухшие запрашиваются одним
+    /// `getMultipleAccounts`.
+    pub async fn fetch_tables(
+        &self,
+        tables: &[String],
+    ) -> Result<HashMap<String, Arc<Vec<String>>>> {
+        Ok(self
+            .cached_or_load(tables)
+            .await?
+            .into_iter()
+            .map(|(table, cached)| (table, cached.addresses))
+            .collect())
+    }
+
+    async fn cached_or_load(&self, tables: &[String]) -> Result<HashMap<String, CachedTable>> {
+        let mut found = HashMap::with_capacity(tables.len());
+        let mut missing = Vec::new();
+        if let Ok(mut cache) = self.cache.lock() {
+            for table in tables {
+                match cache.get(table) {
+                    Some(cached) if cached.fetched_at.elapsed() < self.ttl => {
+                        found.insert(table.clone(), cached.clone());
+                    }
+                    _ if !missing.contains(table) => missing.push(table.clone()),
+                    _ => {}
+                }
+            }
+        }
+        found.extend(self.load(&missing).await?);
+        Ok(found)
+    }
+
+    /// Читает таблицы из RPC мимо кэша и обновляет его.
+    async fn load(&self, tables: &[String]) -> Result<HashMap<String, CachedTable>> {
+        let mut loaded = HashMap::with_capacity(tables.len());
+        for chunk in tables.chunks(100) {
+            let keys = chunk
+                .iter()
+                .map(|table| {
+                    Pubkey::from_str(table).with_context(|| format!("invalid lookup table {table}"))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let keys = &keys;
+            let response = self
+                .pool
+                .call(|client| async move {
+                    client
+                        .get_multiple_accounts_with_commitment(keys, client.commitment())
+                        .await
+                })
+                .await?;
+            let slot = response.context.slot;
+            for (table, account) in chunk.iter().zip(response.value) {
+                let account = account.with_context(|| format!("lookup table {table} not found"))?;
+                let state = AddressLookupTable::deserialize(&account.data)
+                    .map_err(|err| anyhow::anyhow!("lookup table {table}: {err}"))?;
+                let cached = CachedTable {
+                    addresses: Arc::new(state.addresses.iter().map(Pubkey::to_string).collect()),
+                    slot,
+                    fetched_at: Instant::now(),
+                };
+                self.store(table.clone(), cached.clone());
+                loaded.insert(table.clone(), cached);
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Адреса lookup'ов транзакции из слота `slot` (`None` — слот неизвестен). Если
+    /// индекс не попадает в кэшированную копию, прочитанную раньше транзакции,
+    /// таблица перечитывается: её успели дописать.
+    pub async fn resolve(
+        &self,
+        lookups: &[TableLookup],
+        slot: Option<u64>,
+    ) -> Result<LoadedAddresses> {
+        let tables: Vec<String> = lookups.iter().map(|lookup| lookup.table.clone()).collect();
+        let mut contents = self.cached_or_load(&tables).await?;
+        let mut outdated: Vec<String> = Vec::new();
+        for lookup in lookups {
+            if contents[&lookup.table].outdated_for(lookup, slot)
+                && !outdated.contains(&lookup.table)
+            {
+                outdated.push(lookup.table.clone());
+            }
+        }
+        if !outdated.is_empty() {
+            contents.extend(self.load(&outdated).await?);
+        }
+
+        let mut loaded = LoadedAddresses::default();
+        for lookup in lookups {
+            let addresses = &contents[&lookup.table].addresses;
+            let pick = |index: &u8| {
+                addresses.get(*index as usize).cloned().with_context(|| {
+                    format!(
+                        "index {index} out of range for lookup table {}",
+                        lookup.table
+                    )
+                })
+            };
+            for index in &lookup.writable_indexes {
+                loaded.writable.push(pick(index)?);
+            }
+            for index in &lookup.readonly_indexes {
+                loaded.readonly.push(pick(index)?);
+            }
+        }
+        Ok(loaded)
+    }
+
+    /// Дописывает `meta.loadedAddresses` в транзакцию формата getTransaction/getBlock
+    /// (`{transaction, meta}`, JSON или `[base64, "base64"]`), если их нет, а сообщение
+    /// ссылается на таблицы. `slot` — слот транзакции, если он не лежит в `tx.slot`.
+    /// `Ok(true)` — адреса добавлены.
+    pub async fn fill_loaded_addresses(&self, tx: &mut Value, slot: Option<u64>) -> Result<bool> {
+        let has_loaded = tx
+            .pointer("/meta/loadedAddresses")
+            .is_some_and(|loaded| !loaded.is_null());
+        if has_loaded || !tx.get("meta").is_some_and(Value::is_object) {
+            return Ok(false);
+        }
+        let lookups = extract_lookups(tx.get("transaction").unwrap_or(&Value::Null))?;
+        if lookups.is_empty() {
+            return Ok(false);
+        }
+
+        let slot = slot.or_else(|| tx.get("slot").and_then(Value::as_u64));
+        let loaded = self.resolve(&lookups, slot).await?;
+        tx["meta"]["loadedAddresses"] = json!({
+            "writable": loaded.writable,
+            "readonly": loaded.readonly,
+        });
+        Ok(true)
+    }
+}
+
+/// Lookups из `transaction`: JSON-сообщение (`addressTableLookups`) или bincode в base64.
+pub fn extract_lookups(transaction: &Value) -> Result<Vec<TableLookup>> {
+    if let Some([Value::String(encoded), Value::String(encoding)]) =
+        transaction.as_array().map(Vec::as_slice)
+    {
+        if encoding != "base64" {
+            return Ok(Vec::new());
+        }
+        let bytes = B64.decode_to_vec(encoded).context("base64 decode failed")?;
+        let versioned: VersionedTransaction =
+            bincode::deserialize(&bytes).context("failed to deserialize VersionedTransaction")?;
+        return Ok(versioned
+            .message
+            .address_table_lookups()
+            .unwrap_or_default()
+            .iter()
+            .map(|lookup| TableLookup {
+                table: lookup.account_key.to_string(),
+                writable_indexes: lookup.writable_indexes.clone(),
+                readonly_indexes: lookup.readonly_indexes.clone(),
+            })
+            .collect());
+    }
+
+    let Some(lookups) = transaction
+        .pointer("/message/addressTableLookups")
+        .and_then(Value::as_array)
+    else {
+        return Ok(Vec::new());
+    };
+    let indexes = |lookup: &Value, key: &str| -> Vec<u8> {
+        lookup
+            .get(key)
+            .and_then(Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(Value::as_u64)
+                    .map(|i| i as u8)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    Ok(lookups
+        .iter()
+        .filter_map(|lookup| {
+            Some(TableLookup {
+                table: lookup.get("accountKey")?.as_str()?.to_string(),
+                writable_indexes: indexes(lookup, "writableIndexes"),
+                readonly_indexes: indexes(lookup, "readonlyIndexes"),
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Resolver на закрытом порту: любой поход в RPC — ошибка и запись в `health`.
+    fn offline_resolver() -> (LookupTableResolver, Arc<RpcPool>) {
+        let config = RpcPoolConfig {
+            max_attempts: 1,
+            ..Default::default()
+        };
+        let pool = Arc::new(RpcPool::with_config(["http://127.0.0.1:1"], config).unwrap());
+        let resolver = LookupTableResolver::with_pool(pool.clone(), 16);
+        (resolver, pool)
+    }
+
+    fn lookup(table: &str, index: u8) -> TableLookup {
+        TableLookup {
+            table: table.to_string(),
+            writable_indexes: vec![index],
+            readonly_indexes: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn fills_loaded_addresses_from_cached_tables() {
+        let table = Pubkey::new_unique().to_string();
+        let addresses: Vec<String> = (0..4).map(|_| Pubkey::new_unique().to_string()).collect();
+        // RPC не дёргается: таблица уже в кэше
+        let resolver = LookupTableResolver::new("http://127.0.0.1:1");
+        resolver.insert_table(&table, addresses.clone(), 100);
+
+        let mut tx = json!({
+            "slot": 120,
+            "transaction": {
+                "message": {
+                    "addressTableLookups": [
+                        {"accountKey": table, "writableIndexes": [2], "readonlyIndexes": [0, 3]}
+                    ]
+                }
+            },
+            "meta": {"fee": 5000}
+        });
+        assert!(resolver.fill_loaded_addresses(&mut tx, None).await.unwrap());
+        assert_eq!(
+            tx["meta"]["loadedAddresses"]["writable"],
+            json!([addresses[2]])
+        );
+        assert_eq!(
+            tx["meta"]["loadedAddresses"]["readonly"],
+            json!([addresses[0], addresses[3]])
+        );
+        // повторно не трогаем
+        assert!(!resolver.fill_loaded_addresses(&mut tx, None).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn index_past_older_copy_refetches_table() {
+        let (resolver, pool) = offline_resolver();
+        let table = Pubkey::new_unique().to_string();
+        resolver.insert_table(&table, vec![Pubkey::new_unique().to_string()], 100);
+
+        // транзакция новее копии: таблицу могли дописать
+        let err = resolver
+            .resolve(&[lookup(&table, 3)], Some(200))
+            .await
+            .unwrap_err();
+        assert!(!err.to_string().contains("out of range"), "{err:#}");
+        assert_eq!(pool.health()[0].requests, 1);
+
+        // копия не старше транзакции: индекс действительно битый
+        let err = resolver
+            .resolve(&[lookup(&table, 3)], Some(100))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{err:#}");
+        assert_eq!(pool.health()[0].requests, 1);
+    }
+
+    #[tokio::test]
+    async fn expired_tables_are_refetched() {
+        let (resolver, pool) = offline_resolver();
+        let resolver = resolver.with_ttl(Duration::ZERO);
+        let table = Pubkey::new_unique().to_string();
+        resolver.insert_table(&table, vec![Pubkey::new_unique().to_string()], 100);
+
+        assert!(resolver
+            .resolve(&[lookup(&table, 0)], Some(100))
+            .await
+            .is_err());
+        assert_eq!(pool.health()[0].requests, 1);
+    }
+}
@@ -0,0 +1,288 @@
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use futures::stream::{self, Stream, StreamExt};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_config::RpcBlockConfig;
+use solana_client::rpc_custom_error::{
+    JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED, JSON_RPC_SERVER_ERROR_SLOT_SKIPPED,
+};
+use solana_client::rpc_request::RpcError;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_transaction_status::{TransactionDetails, UiTransactionEncoding};
+
+use crate::config::ParseConfig;
+use crate::core::dex_parser::DexParser;
+use crate::types::{BlockParseResult, SolanaBlock};
+
+use super::convert_encoded_transaction;
+use super::pool::{RpcPool, RpcPoolConfig};
+
+/// Настройки backfill'а через getBlock.
+#[derive(Clone, Debug)]
+pub struct BlockFetcherConfig {
+    /// Сколько getBlock запросов выполняется одновременно.
+    pub concurrency: usize,
+    /// Повторы на один слот (не считая первой попытки).
+    pub max_retries: u32,
+    /// Задержка перед первым повтором, дальше удваивается.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub commitment: CommitmentConfig,
+    pub parse_config: Option<ParseConfig>,
+}
+
+impl Default for BlockFetcherConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(10),
+            commitment: CommitmentConfig::confirmed(),
+            parse_config: None,
+        }
+    }
+}
+
+/// Асинхронный загрузчик блоков для backfill'а по диапазону слотов.
+///
+/// Блоки запрашиваются параллельно, но stream отдаёт результаты в порядке слотов.
+/// Пропущенные слоты (skipped) молча опускаются. Запросы идут через `RpcPool`:
+/// при нескольких endpoints нагрузка распределяется, 429 уводит на соседний.
+#[derive(Clone)]
+pub struct BlockFetcher {
+    pool: Arc<RpcPool>,
+    parser: Arc<DexParser>,
+    config: BlockFetcherConfig,
+}
+
+impl BlockFetcher {
+    pub fn new(rpc_url: &str) -> Self {
+        Self::with_config(rpc_url, BlockFetcherConfig::default())
+    }
+
+    pub fn with_config(rpc_url: &str, config: BlockFetcherConfig) -> Self {
+        let pool = RpcPool::with_config(
+            [rpc_url],
+            RpcPoolConfig {
+                commitment: config.commitment,
+                ..Default::default()
+            },
+        )
+        .expect("single endpoint pool");
+        Self::with_pool(Arc::new(pool), config)
+    }
+
+    pub fn with_pool(pool: Arc<RpcPool>, config: BlockFetcherConfig) -> Self {
+        Self {
+            pool,
+            parser: Arc::new(DexParser::new()),
+            config,
+        }
+    }
+
+    /// Позволяет переиспользовать уже настроенный DexParser.
+    pub fn with_parser(mut self, parser: Arc<DexParser>) -> Self {
+        self.parser = parser;
+        self
+    }
+
+    pub fn config(&self) -> &BlockFetcherConfig {
+        &self.config
+    }
+
+    pub fn pool(&self) -> &Arc<RpcPool> {
+        &self.pool
+    }
+
+    /// Stream распарсенных блоков для `slots` в порядке возрастания слота.
+    pub fn stream(
+        &self,
+        slots: RangeInclusive<u64>,
+    ) -> impl Stream<Item = Result<BlockParseResult>> + Send + 'static {
+        let fetcher = self.clone();
+        let concurrency = self.config.concurrency.max(1);
+        stream::iter(slots)
+            .map(move |slot| {
+                let fetcher = fetcher.clone();
+                async move { fetcher.fetch_block(slot).await }
+            })
+            .buffered(concurrency)
+            .filter_map(|result| async move { result.transpose() })
+    }
+
+    /// Загружает и парсит один блок. `Ok(None)` — слот пропущен.
+    pub async fn fetch_block(&self, slot: u64) -> Result<Option<BlockParseResult>> {
+        let Some(block) = self.fetch_raw_block(slot).await? else {
+            return Ok(None);
+        };
+        let parser = Arc::clone(&self.parser);
+        let parse_config = self.config.parse_config.clone();
+        // Парсинг CPU-bound, не блокируем runtime
+        let result = tokio::task::spawn_blocking(move || {
+            parser.parse_block_parsed(&block, parse_config)
+        })
+        .await
+        .context("block parse task panicked")?;
+        Ok(Some(result))
+    }
+
+    async fn fetch_raw_block(&self, slot: u64) -> Result<Option<SolanaBlock>> {
+        let block_config = RpcBlockConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            transaction_details: Some(TransactionDetails::Full),
+            rewards: Some(false),
+            commitment: Some(self.config.commitment),
+            max_supported_transaction_version: Some(0),
+        };
+
+        let mut attempt = 0;
+        let mut backoff = self.config.initial_backoff;
+        let block = loop {
+            let request = self
+                .pool
+                .call(|client| async move { client.get_block_with_config(slot, block_config).await });
+            match request.await {
+                Ok(block) => break block,
+                Err(err) if is_skipped_slot(&err) => return Ok(None),
+                Err(err) if attempt < self.config.max_retries => {
+                    tracing::warn!("getBlock {slot} failed (attempt {}): {err}", attempt + 1);
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(self.config.max_backoff);
+                    attempt += 1;
+                }
+                Err(err) => {
+                    return Err(err).with_context(|| format!("failed to fetch block {slot}"))
+                }
+            }
+        };
+
+        let transactions = block
+            .transactions
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|tx| match convert_encoded_transaction(slot, block.block_time, tx) {
+                Ok(tx) => Some(tx),
+                Err(err) => {
+                    tracing::debug!("skipping transaction in block {slot}: {err}");
+                    None
+                }
+            })
+            .collect();
+
+        Ok(Some(SolanaBlock {
+            slot,
+            block_time: block.block_time.map(|ts| ts as u64),
+            transactions,
+        }))
+    }
+}
+
+fn is_skipped_slot(err: &ClientError) -> bool {
+    matches!(
+        err.kind(),
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+            if *code == JSON_RPC_SERVER_ERROR_SLOT_SKIPPED
+                || *code == JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_client::rpc_custom_error::JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    fn rpc_error(code: i64) -> ClientError {
+        ClientError::from(RpcError::RpcResponseError {
+            code,
+            message: "slot error".to_string(),
+            data: solana_client::rpc_request::RpcResponseErrorData::Empty,
+        })
+    }
+
+    #[test]
+    fn classifies_skipped_slot_errors() {
+        let skipped = rpc_error(JSON_RPC_SERVER_ERROR_SLOT_SKIPPED);
+        let long_term_skipped = rpc_error(JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED);
+        let not_available = rpc_error(JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE);
+        assert!(is_skipped_slot(&skipped));
+        assert!(is_skipped_slot(&long_term_skipped));
+        // блок ещё не доступен — это повод повторить, а не пропуск
+        assert!(!is_skipped_slot(&not_available));
+        assert!(!is_skipped_slot(&ClientError::from(RpcError::ForUser(
+            "timeout".to_string()
+        ))));
+    }
+
+    /// HTTP JSON-RPC сервер, отвечающий на `getBlock` ошибкой `code`.
+    async fn rpc_error_server(code: i64) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 4096];
+                // заголовки и тело запроса читаем целиком, иначе клиент увидит reset
+                loop {
+                    let read = socket.read(&mut buf).await.unwrap_or(0);
+                    request.extend_from_slice(&buf[..read]);
+                    let text = String::from_utf8_lossy(&request);
+                    let complete = text.split_once("\r\n\r\n").is_some_and(|(head, body)| {
+                        let length = head
+                            .lines()
+                            .find_map(|line| {
+                                let (name, value) = line.split_once(':')?;
+                                name.eq_ignore_ascii_case("content-length")
+                                    .then(|| value.trim().parse::<usize>().ok())?
+                            })
+                            .unwrap_or(0);
+                        body.len() >= length
+                    });
+                    if read == 0 || complete {
+                        break;
+                    }
+                }
+                // RpcClient перед getBlock спрашивает версию ноды
+                let body = if String::from_utf8_lossy(&request).contains("getVersion") {
+                    r#"{"jsonrpc":"2.0","result":{"solana-core":"1.18.0","feature-set":1},"id":1}"#
+                        .to_string()
+                } else {
+                    format!(
+                        r#"{{"jsonrpc":"2.0","error":{{"code":{code},"message":"Slot was skipped"}},"id":1}}"#
+                    )
+                };
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn skipped_slots_are_omitted_from_stream() {
+        for code in [
+            JSON_RPC_SERVER_ERROR_SLOT_SKIPPED,
+            JSON_RPC_SERVER_ERROR_LONG_TERM_STORAGE_SLOT_SKIPPED,
+        ] {
+            let url = rpc_error_server(code).await;
+            let fetcher = BlockFetcher::with_config(
+                &url,
+                BlockFetcherConfig {
+                    max_retries: 0,
+                    ..Default::default()
+                },
+            );
+            assert!(fetcher.fetch_block(7).await.unwrap().is_none());
+            let blocks: Vec<_> = fetcher.stream(1..=3).collect().await;
+            assert!(blocks.is_empty());
+        }
+    }
+}
@@ -0,0 +1,554 @@
+//! Поиск пулов через `getProgramAccounts`: снимок состояния (mints, vaults, резервы,
+//! комиссии) до подписки на поток, чтобы `state::PoolTracker` стартовал не с нуля.
+//!
+//! Поддерживаются Raydium AMM v4, Pumpswap и Meteora DLMM. Резервы читаются из vault
+//! token-аккаунтов отдельным `getMultipleAccounts`; decimals — из аккаунта пула
+//! (Raydium) или из mint-аккаунтов. Layout'ы Pumpswap и DLMM — из `state_decoders`.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context, Result};
+use base64_simd::STANDARD as B64;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::core::constants::dex_programs;
+use crate::protocols::pumpfun::util::convert_to_ui_amount;
+use crate::state;
+use crate::state_decoders::{decode_account, discriminators, ProtocolAccount};
+use crate::types::TokenAmount;
+
+use super::pool::{RpcPool, RpcPoolConfig};
+
+/// Программа пула и, соответственно, layout его аккаунта.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolKind {
+    RaydiumAmmV4,
+    Pumpswap,
+    MeteoraDlmm,
+}
+
+impl PoolKind {
+    pub fn from_program_id(program_id: &str) -> Option<Self> {
+        match program_id {
+            dex_programs::RAYDIUM => Some(Self::RaydiumAmmV4),
+            dex_programs::PUMP_SWAP => Some(Self::Pumpswap),
+            dex_programs::METEORA => Some(Self::MeteoraDlmm),
+            _ => None,
+        }
+    }
+
+    pub fn program_id(self) -> &'static str {
+        match self {
+            Self::RaydiumAmmV4 => dex_programs::RAYDIUM,
+            Self::Pumpswap => dex_programs::PUMP_SWAP,
+            Self::MeteoraDlmm => dex_programs::METEORA,
+        }
+    }
+
+    /// Имя как в `TradeInfo::amm`.
+    pub fn amm(self) -> &'static str {
+        match self {
+            Self::RaydiumAmmV4 => "Raydium",
+            Self::Pumpswap => "Pumpswap",
+            Self::MeteoraDlmm => "MeteoraDLMM",
+        }
+    }
+
+    /// `(offset, discriminator/размер)` общих фильтров и offsets base/quote mint.
+    fn layout(self) -> Layout {
+        match self {
+            Self::RaydiumAmmV4 => Layout {
+                discriminator: None,
+                data_size: Some(RAYDIUM_AMM_V4_SIZE),
+                base_mint: 400,
+                quote_mint: 432,
+            },
+            Self::Pumpswap => Layout {
+                discriminator: Some(discriminators::PUMPSWAP_POOL),
+                data_size: None,
+                base_mint: 43,
+                quote_mint: 75,
+            },
+            Self::MeteoraDlmm => Layout {
+                discriminator: Some(discriminators::METEORA_LB_PAIR),
+                data_size: None,
+                base_mint: 88,
+                quote_mint: 120,
+            },
+        }
+    }
+}
+
+struct Layout {
+    discriminator: Option<[u8; 8]>,
+    data_size: Option<u64>,
+    base_mint: usize,
+    quote_mint: usize,
+}
+
+const RAYDIUM_AMM_V4_SIZE: u64 = 752;
+
+/// Дополнительные условия `getProgramAccounts`; mints сравниваются `memcmp` на стороне RPC.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PoolFilters {
+    /// Base-сторона пула: `coin_mint` Raydium, `base_mint` Pumpswap, `token_x` DLMM.
+    pub base_mint: Option<String>,
+    pub quote_mint: Option<String>,
+    /// Не читать vault/mint аккаунты: только layout пула, `reserves` пустые.
+    pub skip_reserves: bool,
+}
+
+/// Комиссии пула как доли суммы входа (`0.0025` — 0.25%).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeConfig {
+    /// Всё, что платит трейдер; у DLMM — базовая ставка без волатильной части.
+    pub trade_fee_rate: f64,
+    /// Часть `trade_fee_rate`, уходящая протоколу.
+    pub protocol_fee_rate: f64,
+}
+
+/// Снимок пула из аккаунтов программы.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolState {
+    pub pool_id: String,
+    pub kind: PoolKind,
+    pub base_mint: String,
+    pub quote_mint: String,
+    pub base_vault: String,
+    pub quote_vault: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lp_mint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lp_supply_raw: Option<u64>,
+    /// `None` у Pumpswap, если `GlobalConfig` не прочитался.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee: Option<FeeConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_reserve: Option<TokenAmount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_reserve: Option<TokenAmount>,
+    /// Для Raydium: decimals из аккаунта пула, у остальных — из mint-аккаунтов.
+    #[serde(skip)]
+    decimals: Option<(u8, u8)>,
+    /// Raydium: `need_take_pnl` не принадлежит LP и вычитается из vault.
+    #[serde(skip)]
+    pending_pnl: (u64, u64),
+}
+
+impl PoolState {
+    /// Разбирает аккаунт пула `kind`; `None`, если данные не того layout'а.
+    pub fn decode(kind: PoolKind, pool_id: &str, data: &[u8]) -> Option<Self> {
+        match kind {
+            PoolKind::RaydiumAmmV4 => decode_raydium_amm_v4(pool_id, data),
+            PoolKind::Pumpswap => decode_pumpswap_pool(pool_id, data),
+            PoolKind::MeteoraDlmm => decode_dlmm_lb_pair(pool_id, data),
+        }
+    }
+
+    fn new(
+        kind: PoolKind,
+        pool_id: &str,
+        mints: (String, String),
+        vaults: (String, String),
+    ) -> Self {
+        Self {
+            pool_id: pool_id.to_string(),
+            kind,
+            base_mint: mints.0,
+            quote_mint: mints.1,
+            base_vault: vaults.0,
+            quote_vault: vaults.1,
+            lp_mint: None,
+            lp_supply_raw: None,
+            fee: None,
+            base_reserve: None,
+            quote_reserve: None,
+            decimals: None,
+            pending_pnl: (0, 0),
+        }
+    }
+}
+
+/// Начальное состояние для `PoolTracker::seed`.
+impl From<&PoolState> for state::PoolState {
+    fn from(pool: &PoolState) -> Self {
+        let mut seeded = state::PoolState::new(&pool.pool_id);
+        seeded.program_id = Some(pool.kind.program_id().to_string());
+        seeded.amm = Some(pool.kind.amm().to_string());
+        for (mint, reserve) in [
+            (&pool.base_mint, &pool.base_reserve),
+            (&pool.quote_mint, &pool.quote_reserve),
+        ] {
+            if let Some(reserve) = reserve {
+                seeded.reserves.insert(mint.clone(), reserve.clone());
+            }
+        }
+        seeded.lp_mint.clone_from(&pool.lp_mint);
+        seeded.lp_supply_raw = pool.lp_supply_raw.map(u128::from);
+        seeded
+    }
+}
+
+/// Пулы `program_id` по одному endpoint'у; см. `PoolDiscovery`.
+pub async fn discover_pools(
+    rpc_url: &str,
+    program_id: &str,
+    filters: &PoolFilters,
+) -> Result<Vec<PoolState>> {
+    PoolDiscovery::new(rpc_url)
+        .discover(program_id, filters)
+        .await
+}
+
+pub struct PoolDiscovery {
+    pool: Arc<RpcPool>,
+}
+
+impl PoolDiscovery {
+    pub fn new(rpc_url: &str) -> Self {
+        let client = Arc::new(RpcClient::new(rpc_url.to_string()));
+        let pool = RpcPool::from_clients(vec![client], RpcPoolConfig::default())
+            .expect("single endpoint pool");
+        Self::with_pool(Arc::new(pool))
+    }
+
+    pub fn with_pool(pool: Arc<RpcPool>) -> Self {
+        Self { pool }
+    }
+
+    /// Все пулы программы, подходящие под `filters`. Без mint-фильтров это весь
+    /// `getProgramAccounts` программы — у Raydium это сотни тысяч аккаунтов.
+    pub async fn discover(
+        &self,
+        program_id: &str,
+        filters: &PoolFilters,
+    ) -> Result<Vec<PoolState>> {
+        let kind = PoolKind::from_program_id(program_id)
+            .ok_or_else(|| anyhow!("pool discovery does not support program {program_id}"))?;
+        let accounts = self
+            .program_accounts(program_id, rpc_filters(kind, filters)?)
+            .await?;
+        let mut pools: Vec<PoolState> = accounts
+            .iter()
+            .filter_map(|(pool_id, data)| {
+                let pool = PoolState::decode(kind, pool_id, data);
+                if pool.is_none() {
+                    tracing::debug!("skipping {pool_id}: not a {kind:?} pool account");
+                }
+                pool
+            })
+            .collect();
+        if kind == PoolKind::Pumpswap {
+            let fee = self.pumpswap_fee().await?;
+            pools.iter_mut().for_each(|pool| pool.fee = fee);
+        }
+        if !filters.skip_reserves {
+            self.fill_reserves(&mut pools).await?;
+        }
+        Ok(pools)
+    }
+
+    async fn program_accounts(
+        &self,
+        program_id: &str,
+        filters: Vec<RpcFilterType>,
+    ) -> Result<Vec<(String, Vec<u8>)>> {
+        // encoding задаётся явно: base58 по умолчанию не отдаёт аккаунты > 128 байт
+        let params = json!([
+            program_id,
+            { "encoding": "base64", "filters": filters, "commitment": self.pool.config().commitment.commitment },
+        ]);
+        let params = &params;
+        let accounts: Vec<KeyedAccount> = self
+            .pool
+            .call(|client| async move {
+                client
+                    .send(RpcRequest::GetProgramAccounts, params.clone())
+                    .await
+            })
+            .await
+            .with_context(|| format!("getProgramAccounts {program_id} failed"))?;
+        accounts
+            .into_iter()
+            .map(|keyed| {
+                let data = B64
+                    .decode_to_vec(&keyed.account.data.0)
+                    .map_err(|err| anyhow!("invalid base64 in account {}: {err}", keyed.pubkey))?;
+                Ok((keyed.pubkey, data))
+            })
+            .collect()
+    }
+
+    async fn multiple_accounts(&self, keys: &[String]) -> Result<HashMap<String, Vec<u8>>> {
+        let mut found = HashMap::with_capacity(keys.len());
+        for chunk in keys.chunks(100) {
+            let pubkeys = chunk
+                .iter()
+                .map(|key| Pubkey::from_str(key).with_context(|| format!("invalid account {key}")))
+                .collect::<Result<Vec<_>>>()?;
+            let pubkeys = &pubkeys;
+            let accounts = self
+                .pool
+                .call(|client| async move { client.get_multiple_accounts(pubkeys).await })
+                .await?;
+            for (key, account) in chunk.iter().zip(accounts) {
+                if let Some(account) = account {
+                    found.insert(key.clone(), account.data);
+                }
+            }
+        }
+        Ok(found)
+    }
+
+    /// `GlobalConfig` Pumpswap общий для всех пулов; `None`, если аккаунт не найден.
+    async fn pumpswap_fee(&self) -> Result<Option<FeeConfig>> {
+        let program = Pubkey::from_str(dex_programs::PUMP_SWAP)?;
+        let (config, _) = Pubkey::find_program_address(&[b"global_config"], &program);
+        let accounts = self.multiple_accounts(&[config.to_string()]).await?;
+        Ok(accounts
+            .get(&config.to_string())
+            .and_then(|data| decode_pumpswap_fee(data)))
+    }
+
+    async fn fill_reserves(&self, pools: &mut [PoolState]) -> Result<()> {
+        let mut keys: Vec<String> = Vec::new();
+        for pool in pools.iter() {
+            keys.extend([pool.base_vault.clone(), pool.quote_vault.clone()]);
+            if pool.decimals.is_none() {
+                keys.extend([pool.base_mint.clone(), pool.quote_mint.clone()]);
+            }
+        }
+        keys.sort();
+        keys.dedup();
+        let accounts = self.multiple_accounts(&keys).await?;
+        let amount = |vault: &str| accounts.get(vault).and_then(|data| u64_at(data, 64));
+        let decimals = |mint: &str| accounts.get(mint).and_then(|data| data.get(44).copied());
+        for pool in pools {
+            let Some((base_decimals, quote_decimals)) = pool
+                .decimals
+                .or_else(|| Some((decimals(&pool.base_mint)?, decimals(&pool.quote_mint)?)))
+            else {
+                continue;
+            };
+            let reserve = |raw: u64, decimals: u8| {
+                TokenAmount::new(
+                    raw.to_string(),
+                    decimals,
+                    Some(convert_to_ui_amount(raw, decimals)),
+                )
+            };
+            pool.base_reserve = amount(&pool.base_vault)
+                .map(|raw| reserve(raw.saturating_sub(pool.pending_pnl.0), base_decimals));
+            pool.quote_reserve = amount(&pool.quote_vault)
+                .map(|raw| reserve(raw.saturating_sub(pool.pending_pnl.1), quote_decimals));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct KeyedAccount {
+    pubkey: String,
+    account: EncodedAccount,
+}
+
+#[derive(Deserialize)]
+struct EncodedAccount {
+    /// `["<base64>", "base64"]`.
+    data: (String, String),
+}
+
+fn rpc_filters(kind: PoolKind, filters: &PoolFilters) -> Result<Vec<RpcFilterType>> {
+    let layout = kind.layout();
+    let mut rpc = Vec::new();
+    if let Some(size) = layout.data_size {
+        rpc.push(RpcFilterType::DataSize(size));
+    }
+    if let Some(discriminator) = layout.discriminator {
+        rpc.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            &discriminator,
+        )));
+    }
+    for (mint, offset) in [
+        (&filters.base_mint, layout.base_mint),
+        (&filters.quote_mint, layout.quote_mint),
+    ] {
+        if let Some(mint) = mint {
+            let mint = Pubkey::from_str(mint).with_context(|| format!("invalid mint {mint}"))?;
+            rpc.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+                offset,
+                mint.as_ref(),
+            )));
+        }
+    }
+    if rpc.is_empty() {
+        bail!("no account filters for {kind:?}");
+    }
+    Ok(rpc)
+}
+
+fn u64_at(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset + 8)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+fn pubkey_at(data: &[u8], offset: usize) -> Option<String> {
+    Some(bs58::encode(data.get(offset..offset + 32)?).into_string())
+}
+
+fn ratio(numerator: u64, denominator: u64) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+/// `AmmInfo` (752 байта, без discriminator'а): 16 u64 параметров, `Fees`, `StateData`,
+/// затем ключи с 336.
+fn decode_raydium_amm_v4(pool_id: &str, data: &[u8]) -> Option<PoolState> {
+    if data.len() != RAYDIUM_AMM_V4_SIZE as usize {
+        return None;
+    }
+    let mut pool = PoolState::new(
+        PoolKind::RaydiumAmmV4,
+        pool_id,
+        (pubkey_at(data, 400)?, pubkey_at(data, 432)?),
+        (pubkey_at(data, 336)?, pubkey_at(data, 368)?),
+    );
+    pool.lp_mint = pubkey_at(data, 464);
+    pool.lp_supply_raw = u64_at(data, 720);
+    pool.decimals = Some((u64_at(data, 32)? as u8, u64_at(data, 40)? as u8));
+    pool.pending_pnl = (u64_at(data, 192)?, u64_at(data, 200)?);
+    // swap fee целиком списывается с входа; pnl — доля протокола от неё
+    let trade_fee_rate = ratio(u64_at(data, 176)?, u64_at(data, 184)?);
+    pool.fee = Some(FeeConfig {
+        trade_fee_rate,
+        protocol_fee_rate: trade_fee_rate * ratio(u64_at(data, 160)?, u64_at(data, 168)?),
+    });
+    Some(pool)
+}
+
+fn decode_pumpswap_pool(pool_id: &str, data: &[u8]) -> Option<PoolState> {
+    let Ok(ProtocolAccount::PumpswapPool(account)) = decode_account(dex_programs::PUMP_SWAP, data)
+    else {
+        return None;
+    };
+    let mut pool = PoolState::new(
+        PoolKind::Pumpswap,
+        pool_id,
+        (account.base_mint, account.quote_mint),
+        (
+            account.pool_base_token_account,
+            account.pool_quote_token_account,
+        ),
+    );
+    pool.lp_mint = Some(account.lp_mint);
+    pool.lp_supply_raw = Some(account.lp_supply);
+    Some(pool)
+}
+
+fn decode_pumpswap_fee(data: &[u8]) -> Option<FeeConfig> {
+    let Ok(ProtocolAccount::PumpswapGlobalConfig(config)) =
+        decode_account(dex_programs::PUMP_SWAP, data)
+    else {
+        return None;
+    };
+    let trade_bps = config.lp_fee_basis_points
+        + config.protocol_fee_basis_points
+        + config.coin_creator_fee_basis_points.unwrap_or(0);
+    Some(FeeConfig {
+        trade_fee_rate: trade_bps as f64 / 10_000.0,
+        protocol_fee_rate: config.protocol_fee_basis_points as f64 / 10_000.0,
+    })
+}
+
+fn decode_dlmm_lb_pair(pool_id: &str, data: &[u8]) -> Option<PoolState> {
+    let Ok(ProtocolAccount::MeteoraLbPair(pair)) = decode_account(dex_programs::METEORA, data)
+    else {
+        return None;
+    };
+    let trade_fee_rate = pair.base_fee_rate();
+    let mut pool = PoolState::new(
+        PoolKind::MeteoraDlmm,
+        pool_id,
+        (pair.token_x_mint, pair.token_y_mint),
+        (pair.reserve_x, pair.reserve_y),
+    );
+    pool.fee = Some(FeeConfig {
+        trade_fee_rate,
+        protocol_fee_rate: trade_fee_rate * pair.protocol_share as f64 / 10_000.0,
+    });
+    Some(pool)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    #[test]
+    fn decodes_raydium_amm_v4_and_seeds_tracker() {
+        let (coin, pc) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = vec![0u8; RAYDIUM_AMM_V4_SIZE as usize];
+        put(&mut data, 32, &6u64.to_le_bytes());
+        put(&mut data, 40, &9u64.to_le_bytes());
+        put(&mut data, 160, &12u64.to_le_bytes());
+        put(&mut data, 168, &100u64.to_le_bytes());
+        put(&mut data, 176, &25u64.to_le_bytes());
+        put(&mut data, 184, &10_000u64.to_le_bytes());
+        put(&mut data, 400, coin.as_ref());
+        put(&mut data, 432, pc.as_ref());
+        put(&mut data, 720, &1_000u64.to_le_bytes());
+
+        let mut pool = PoolState::decode(PoolKind::RaydiumAmmV4, "amm", &data).unwrap();
+        assert_eq!(
+            (pool.base_mint.as_str(), pool.quote_mint.as_str()),
+            (&*coin.to_string(), &*pc.to_string())
+        );
+        assert_eq!(pool.decimals, Some((6, 9)));
+        assert_eq!(pool.lp_supply_raw, Some(1_000));
+        let fee = pool.fee.unwrap();
+        assert!((fee.trade_fee_rate - 0.0025).abs() < 1e-12);
+        assert!((fee.protocol_fee_rate - 0.0003).abs() < 1e-12);
+        assert!(PoolState::decode(PoolKind::RaydiumAmmV4, "amm", &data[..700]).is_none());
+
+        pool.quote_reserve = Some(TokenAmount::new("5000000000", 9, Some(5.0)));
+        let seeded = state::PoolState::from(&pool);
+        assert_eq!(seeded.amm.as_deref(), Some("Raydium"));
+        assert_eq!(
+            seeded.reserve(&pc.to_string()).unwrap().amount,
+            "5000000000"
+        );
+        assert_eq!(seeded.lp_supply_raw, Some(1_000));
+    }
+
+    #[test]
+    fn decodes_dlmm_base_fee() {
+        let mut data = vec![0u8; 904];
+        put(&mut data, 0, &discriminators::METEORA_LB_PAIR);
+        put(&mut data, 8, &10_000u16.to_le_bytes());
+        put(&mut data, 32, &500u16.to_le_bytes());
+        put(&mut data, 80, &25u16.to_le_bytes());
+        let pool = PoolState::decode(PoolKind::MeteoraDlmm, "pair", &data).unwrap();
+        let fee = pool.fee.unwrap();
+        // bin_step 25 при base_factor 10000 — 0.25%
+        assert!((fee.trade_fee_rate - 0.0025).abs() < 1e-12);
+        assert!((fee.protocol_fee_rate - 0.000125).abs() < 1e-12);
+        assert!(PoolState::decode(PoolKind::Pumpswap, "pair", &data).is_none());
+    }
+}
@@ -0,0 +1,290 @@
+//! Отслеживание финализации транзакций, распарсенных на `processed`.
+//!
+//! На `processed` транзакция может оказаться на отброшенном форке. Tracker запоминает
+//! подписи по слотам, периодически проверяет их через `getSignatureStatuses` и
+//! отдаёт события: подтверждена на целевом уровне или отозвана — downstream-хранилище
+//! помечает сделки отозванной транзакции как удалённые.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use futures::stream::{self, Stream};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::TransactionConfirmationStatus;
+
+use crate::config::ParseConfig;
+use crate::core::dex_parser::DexParser;
+use crate::types::ParseResult;
+
+use super::pool::RpcPool;
+use super::ws::Commitment;
+
+/// Лимит `getSignatureStatuses` на один запрос.
+const STATUS_BATCH: usize = 256;
+
+#[derive(Clone, Debug)]
+pub struct FinalizationConfig {
+    /// Уровень, на котором транзакция считается окончательной.
+    pub target: Commitment,
+    /// Через сколько слотов после записанного отсутствие статуса на `target` — откат.
+    pub rollback_after_slots: u64,
+    pub poll_interval: Duration,
+    /// Перепарсить подтверждённую транзакцию, загрузив её на `target`.
+    pub reparse: bool,
+    pub parse_config: Option<ParseConfig>,
+}
+
+impl Default for FinalizationConfig {
+    fn default() -> Self {
+        Self {
+            target: Commitment::Finalized,
+            rollback_after_slots: 150,
+            poll_interval: Duration::from_secs(2),
+            reparse: false,
+            parse_config: None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum FinalizationEvent {
+    /// Транзакция достигла целевого уровня. `slot` — слот, в котором она подтверждена
+    /// (может отличаться от записанного, если транзакция попала в другой форк).
+    Confirmed {
+        signature: String,
+        slot: u64,
+        tracked_slot: u64,
+        /// Результат повторного парсинга при `reparse`.
+        result: Option<Box<ParseResult>>,
+    },
+    /// Транзакция не найдена на целевом уровне: её сделки нужно удалить.
+    Revoked { signature: String, slot: u64 },
+}
+
+impl FinalizationEvent {
+    pub fn signature(&self) -> &str {
+        match self {
+            FinalizationEvent::Confirmed { signature, .. }
+            | FinalizationEvent::Revoked { signature, .. } => signature,
+        }
+    }
+}
+
+fn commitment_config(commitment: Commitment) -> CommitmentConfig {
+    match commitment {
+        Commitment::Processed => CommitmentConfig::processed(),
+        Commitment::Confirmed => CommitmentConfig::confirmed(),
+        Commitment::Finalized => CommitmentConfig::finalized(),
+    }
+}
+
+fn reached(status: Option<&TransactionConfirmationStatus>, target: Commitment) -> bool {
+    use TransactionConfirmationStatus::{Confirmed, Finalized};
+    matches!(
+        (status, target),
+        (_, Commitment::Processed)
+            | (Some(Confirmed | Finalized), Commitment::Confirmed)
+            | (Some(Finalized), Commitment::Finalized)
+    )
+}
+
+/// Подписи, ожидающие подтверждения, сгруппированные по слоту.
+pub struct FinalizationTracker {
+    pool: Arc<RpcPool>,
+    parser: Arc<DexParser>,
+    config: FinalizationConfig,
+    pending: Mutex<BTreeMap<u64, Vec<Signature>>>,
+}
+
+impl FinalizationTracker {
+    pub fn new(pool: Arc<RpcPool>, config: FinalizationConfig) -> Self {
+        Self {
+            pool,
+            parser: Arc::new(DexParser::new()),
+            config,
+            pending: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn with_parser(mut self, parser: Arc<DexParser>) -> Self {
+        self.parser = parser;
+        self
+    }
+
+    pub fn config(&self) -> &FinalizationConfig {
+        &self.config
+    }
+
+    /// Невалидная подпись не запоминается.
+    pub fn track(&self, signature: &str, slot: u64) {
+        let Ok(signature) = signature.parse::<Signature>() else {
+            tracing::debug!("not tracking invalid signature {signature}");
+            return;
+        };
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.entry(slot).or_default().push(signature);
+        }
+    }
+
+    /// Запоминает транзакцию из `ParseResult` (обычно — из подписки на `processed`).
+    pub fn track_result(&self, result: &ParseResult) {
+        if !result.signature.is_empty() {
+            self.track(&result.signature, result.slot);
+        }
+    }
+
+    pub fn pending_len(&self) -> usize {
+        self.pending
+            .lock()
+            .map(|pending| pending.values().map(Vec::len).sum())
+            .unwrap_or_default()
+    }
+
+    /// Одна проверка всех ожидающих подписей.
+    pub async fn poll(&self) -> Result<Vec<FinalizationEvent>> {
+        let snapshot: Vec<(u64, Signature)> = match self.pending.lock() {
+            Ok(pending) => pending
+                .iter()
+                .flat_map(|(slot, signatures)| signatures.iter().map(|sig| (*slot, *sig)))
+                .collect(),
+            Err(_) => return Ok(Vec::new()),
+        };
+        if snapshot.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let commitment = commitment_config(self.config.target);
+        let target_slot = self
+            .pool
+            .call(|client| async move { client.get_slot_with_commitment(commitment).await })
+            .await
+            .context("failed to fetch target slot")?;
+
+        let mut events = Vec::new();
+        let mut resolved = Vec::new();
+        for chunk in snapshot.chunks(STATUS_BATCH) {
+            let signatures: Vec<Signature> = chunk.iter().map(|(_, sig)| *sig).collect();
+            let signatures = &signatures;
+            let statuses = self
+                .pool
+                .call(|client| async move { client.get_signature_statuses(signatures).await })
+                .await
+                .context("getSignatureStatuses failed")?
+                .value;
+
+            for ((tracked_slot, signature), status) in chunk.iter().zip(statuses) {
+                let len = events.len();
+                match status {
+                    Some(status)
+                        if reached(status.confirmation_status.as_ref(), self.config.target) =>
+                    {
+                        events.push(FinalizationEvent::Confirmed {
+                            signature: signature.to_string(),
+                            slot: status.slot,
+                            tracked_slot: *tracked_slot,
+                            result: None,
+                        });
+                    }
+                    // статус ещё не на целевом уровне — ждём
+                    Some(_) => {}
+                    None if target_slot >= tracked_slot + self.config.rollback_after_slots => {
+                        events.push(FinalizationEvent::Revoked {
+                            signature: signature.to_string(),
+                            slot: *tracked_slot,
+                        });
+                    }
+                    None => {}
+                }
+                if events.len() > len {
+                    resolved.push((*tracked_slot, *signature));
+                }
+            }
+        }
+
+        if let Ok(mut pending) = self.pending.lock() {
+            for (slot, signature) in resolved {
+                if let Some(signatures) = pending.get_mut(&slot) {
+                    signatures.retain(|sig| *sig != signature);
+                    if signatures.is_empty() {
+                        pending.remove(&slot);
+                    }
+                }
+            }
+        }
+
+        if self.config.reparse {
+            self.reparse(&mut events).await;
+        }
+        Ok(events)
+    }
+
+    async fn reparse(&self, events: &mut [FinalizationEvent]) {
+        let mut parsed = HashMap::new();
+        for event in events.iter() {
+            let FinalizationEvent::Confirmed { signature, .. } = event else {
+                continue;
+            };
+            match self.pool.fetch_transaction(signature).await {
+                Ok(Some(tx)) => {
+                    let result = self.parser.parse_all(tx, self.config.parse_config.clone());
+                    parsed.insert(signature.clone(), Box::new(result));
+                }
+                Ok(None) => {
+                    tracing::debug!("confirmed transaction {signature} not returned by rpc")
+                }
+                Err(err) => tracing::warn!("failed to re-parse {signature}: {err:#}"),
+            }
+        }
+        for event in events {
+            if let FinalizationEvent::Confirmed {
+                signature, result, ..
+            } = event
+            {
+                *result = parsed.remove(signature.as_str());
+            }
+        }
+    }
+
+    /// Бесконечный stream событий: `poll` каждые `poll_interval`. Ошибки RPC
+    /// логируются, подписи остаются в очереди до следующей проверки.
+    pub fn events(self: Arc<Self>) -> impl Stream<Item = FinalizationEvent> + Send + 'static {
+        let batches = stream::unfold(self, |tracker| async move {
+            tokio::time::sleep(tracker.config.poll_interval).await;
+            let events = match tracker.poll().await {
+                Ok(events) => events,
+                Err(err) => {
+                    tracing::warn!("finalization check failed: {err:#}");
+                    Vec::new()
+                }
+            };
+            Some((stream::iter(events), tracker))
+        });
+        futures::StreamExt::flatten(batches)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn confirmation_levels_satisfy_target() {
+        use TransactionConfirmationStatus::*;
+        assert!(reached(Some(&Finalized), Commitment::Confirmed));
+        assert!(reached(Some(&Confirmed), Commitment::Confirmed));
+        assert!(!reached(Some(&Confirmed), Commitment::Finalized));
+        assert!(!reached(Some(&Processed), Commitment::Confirmed));
+        assert!(!reached(None, Commitment::Finalized));
+
+        let pool = Arc::new(RpcPool::new(["http://127.0.0.1:1"]).unwrap());
+        let tracker = FinalizationTracker::new(pool, FinalizationConfig::default());
+        tracker.track(&Signature::new_unique().to_string(), 10);
+        tracker.track(&Signature::new_unique().to_string(), 10);
+        tracker.track(&Signature::new_unique().to_string(), 11);
+        tracker.track("not-a-signature", 11);
+        assert_eq!(tracker.pending_len(), 3);
+    }
+}
@@ -0,0 +1,381 @@
+//! Пул RPC endpoints: round-robin, failover и backoff на 429.
+//!
+//! Каждый вызов уходит на следующий по кругу endpoint, который сейчас не в backoff.
+//! 429 (rate limit) ставит endpoint на паузу с удвоением до `max_backoff`, сетевые
+//! ошибки и 5xx — тоже, но считаются отдельно. Ошибки уровня JSON-RPC (skipped slot,
+//! неверные параметры) не зависят от endpoint'а и возвращаются сразу.
+
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY;
+use solana_client::rpc_request::RpcError;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::config::{redact_url, RuntimeConfig};
+use crate::core::error::ParserError;
+use crate::types::SolanaTransaction;
+
+use super::convert_encoded_transaction;
+
+#[derive(Clone, Debug)]
+pub struct RpcPoolConfig {
+    /// Попыток на один вызов по всем endpoint'ам (не меньше числа endpoints).
+    pub max_attempts: usize,
+    /// Пауза endpoint'а после первой ошибки, дальше удваивается.
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub commitment: CommitmentConfig,
+    pub timeout: Duration,
+}
+
+impl Default for RpcPoolConfig {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            commitment: CommitmentConfig::confirmed(),
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Снимок состояния endpoint'а для метрик и логов.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EndpointHealth {
+    /// URL без api-key.
+    pub url: String,
+    pub requests: u64,
+    pub successes: u64,
+    pub errors: u64,
+    pub rate_limited: u64,
+    pub consecutive_failures: u32,
+    /// Сколько ещё endpoint пропускается; `None` — доступен.
+    pub backoff_remaining: Option<Duration>,
+    /// Средняя задержка успешных ответов.
+    pub avg_latency: Option<Duration>,
+}
+
+#[derive(Default)]
+struct Backoff {
+    until: Option<Instant>,
+    consecutive_failures: u32,
+}
+
+struct Endpoint {
+    url: String,
+    client: Arc<RpcClient>,
+    backoff: Mutex<Backoff>,
+    requests: AtomicU64,
+    successes: AtomicU64,
+    errors: AtomicU64,
+    rate_limited: AtomicU64,
+    latency_micros: AtomicU64,
+}
+
+impl Endpoint {
+    fn new(client: Arc<RpcClient>) -> Self {
+        Self {
+            url: client.url(),
+            client,
+            backoff: Mutex::default(),
+            requests: AtomicU64::new(0),
+            successes: AtomicU64::new(0),
+            errors: AtomicU64::new(0),
+            rate_limited: AtomicU64::new(0),
+            latency_micros: AtomicU64::new(0),
+        }
+    }
+
+    fn available_at(&self) -> Option<Instant> {
+        self.backoff.lock().ok()?.until
+    }
+
+    fn record_success(&self, latency: Duration) {
+        self.successes.fetch_add(1, Ordering::Relaxed);
+        self.latency_micros
+            .fetch_add(latency.as_micros() as u64, Ordering::Relaxed);
+        if let Ok(mut backoff) = self.backoff.lock() {
+            *backoff = Backoff::default();
+        }
+    }
+
+    fn record_failure(&self, rate_limited: bool, config: &RpcPoolConfig) -> Duration {
+        let counter = if rate_limited {
+            &self.rate_limited
+        } else {
+            &self.errors
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        let Ok(mut backoff) = self.backoff.lock() else {
+            return config.initial_backoff;
+        };
+        let pause = config
+            .initial_backoff
+            .saturating_mul(1 << backoff.consecutive_failures.min(16))
+            .min(config.max_backoff);
+        backoff.consecutive_failures += 1;
+        backoff.until = Some(Instant::now() + pause);
+        pause
+    }
+
+    fn health(&self) -> EndpointHealth {
+        let successes = self.successes.load(Ordering::Relaxed);
+        let (until, consecutive_failures) = self
+            .backoff
+            .lock()
+            .map(|backoff| (backoff.until, backoff.consecutive_failures))
+            .unwrap_or_default();
+        EndpointHealth {
+            url: redact_url(&self.url),
+            requests: self.requests.load(Ordering::Relaxed),
+            successes,
+            errors: self.errors.load(Ordering::Relaxed),
+            rate_limited: self.rate_limited.load(Ordering::Relaxed),
+            consecutive_failures,
+            backoff_remaining: until
+                .map(|until| until.saturating_duration_since(Instant::now()))
+                .filter(|remaining| !remaining.is_zero()),
+            avg_latency: (successes > 0).then(|| {
+                Duration::from_micros(self.latency_micros.load(Ordering::Relaxed) / successes)
+            }),
+        }
+    }
+}
+
+/// Как классифицировать ошибку вызова.
+#[derive(Debug, PartialEq, Eq)]
+enum Failure {
+    RateLimited,
+    /// Сеть, таймаут, 5xx — пробуем другой endpoint.
+    Endpoint,
+    /// Ответ JSON-RPC с ошибкой — одинаков на любом endpoint'е.
+    Request,
+}
+
+fn classify(err: &ClientError) -> Failure {
+    match err.kind() {
+        ClientErrorKind::Reqwest(err) => match err.status() {
+            Some(status) if status.as_u16() == 429 => Failure::RateLimited,
+            Some(status) if status.is_client_error() => Failure::Request,
+            _ => Failure::Endpoint,
+        },
+        // RpcRequestError — запрос не дошёл или ответ не разобран (в т.ч. getVersion внутри клиента)
+        ClientErrorKind::Io(_) | ClientErrorKind::RpcError(RpcError::RpcRequestError(_)) => {
+            Failure::Endpoint
+        }
+        // отстающий узел: другой endpoint может ответить
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+            if *code == JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY =>
+        {
+            Failure::Endpoint
+        }
+        _ => Failure::Request,
+    }
+}
+
+/// Набор RPC endpoints с общим выбором и учётом здоровья.
+///
+/// ```no_run
+/// # async fn run() -> anyhow::Result<()> {
+/// use solana_dex_parser::rpc::RpcPool;
+///
+/// let pool = RpcPool::new(["https://rpc-a.example", "https://rpc-b.example"])?;
+/// let slot = pool.call(|client| async move { client.get_slot().await }).await?;
+/// # Ok(()) }
+/// ```
+pub struct RpcPool {
+    endpoints: Vec<Endpoint>,
+    next: AtomicUsize,
+    config: RpcPoolConfig,
+}
+
+impl RpcPool {
+    pub fn new<I, S>(urls: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::with_config(urls, RpcPoolConfig::default())
+    }
+
+    pub fn with_config<I, S>(urls: I, config: RpcPoolConfig) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let clients = urls
+            .into_iter()
+            .map(|url| {
+                Arc::new(RpcClient::new_with_timeout_and_commitment(
+                    url.into(),
+                    config.timeout,
+                    config.commitment,
+                ))
+            })
+            .collect();
+        Self::from_clients(clients, config)
+    }
+
+    /// Для уже настроенных клиентов (свои заголовки, sender).
+    pub fn from_clients(clients: Vec<Arc<RpcClient>>, config: RpcPoolConfig) -> Result<Self> {
+        if clients.is_empty() {
+            bail!("RpcPool needs at least one endpoint");
+        }
+        Ok(Self {
+            endpoints: clients.into_iter().map(Endpoint::new).collect(),
+            next: AtomicUsize::new(0),
+            config,
+        })
+    }
+
+    /// Endpoints из `RuntimeConfig::rpc_endpoints`.
+    pub fn from_runtime(runtime: &RuntimeConfig, config: RpcPoolConfig) -> Result<Self> {
+        Self::with_config(runtime.rpc_endpoints(), config)
+    }
+
+    pub fn config(&self) -> &RpcPoolConfig {
+        &self.config
+    }
+
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+
+    pub fn health(&self) -> Vec<EndpointHealth> {
+        self.endpoints.iter().map(Endpoint::health).collect()
+    }
+
+    /// Следующий по кругу endpoint вне backoff'а; если все на паузе — тот,
+    /// что освободится раньше, и сколько его ждать.
+    fn pick(&self) -> (usize, Option<Duration>) {
+        let now = Instant::now();
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let len = self.endpoints.len();
+        let mut earliest: Option<(usize, Instant)> = None;
+        for offset in 0..len {
+            let index = (start + offset) % len;
+            match self.endpoints[index].available_at() {
+                Some(until) if until > now => {
+                    if earliest.is_none_or(|(_, best)| until < best) {
+                        earliest = Some((index, until));
+                    }
+                }
+                _ => return (index, None),
+            }
+        }
+        let (index, until) = earliest.expect("pool is never empty");
+        (index, Some(until - now))
+    }
+
+    /// Выполняет `op` на очередном endpoint'е; при 429 и сетевых ошибках повторяет
+    /// на следующих, пока не кончится `max_attempts`. Возвращает последнюю ошибку.
+    pub async fn call<T, F, Fut>(&self, op: F) -> Result<T, ClientError>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let attempts = self.config.max_attempts.max(1);
+        let mut attempt = 0;
+        loop {
+            let (index, wait) = self.pick();
+            if let Some(wait) = wait {
+                tokio::time::sleep(wait).await;
+            }
+            let endpoint = &self.endpoints[index];
+            endpoint.requests.fetch_add(1, Ordering::Relaxed);
+            let started = Instant::now();
+            let err = match op(Arc::clone(&endpoint.client)).await {
+                Ok(value) => {
+                    endpoint.record_success(started.elapsed());
+                    return Ok(value);
+                }
+                Err(err) => err,
+            };
+            let failure = classify(&err);
+            if failure == Failure::Request {
+                return Err(err);
+            }
+            let pause = endpoint.record_failure(failure == Failure::RateLimited, &self.config);
+            attempt += 1;
+            tracing::warn!(
+                "rpc {} failed ({failure:?}), paused for {pause:?}: {err}",
+                redact_url(&endpoint.url)
+            );
+            if attempt >= attempts {
+                return Err(err);
+            }
+        }
+    }
+
+    /// `rpc::fetch_transaction` через пул. `Ok(None)` — транзакция не найдена.
+    pub async fn fetch_transaction(&self, signature: &str) -> Result<Option<SolanaTransaction>> {
+        let parsed = Signature::from_str(signature).map_err(|_| ParserError::Base58Decode {
+            value: signature.to_string(),
+        })?;
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Json),
+            commitment: Some(self.config.commitment),
+            max_supported_transaction_version: Some(0),
+        };
+        let encoded = match self
+            .call(|client| async move { client.get_transaction_with_config(&parsed, config).await })
+            .await
+        {
+            Ok(encoded) => encoded,
+            // узел отвечает `null` для неизвестной подписи
+            Err(err) if matches!(err.kind(), ClientErrorKind::SerdeJson(_)) => return Ok(None),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed to fetch transaction {signature}"))
+            }
+        };
+        convert_encoded_transaction(encoded.slot, encoded.block_time, &encoded.transaction)
+            .map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn fails_over_and_backs_off_unreachable_endpoint() {
+        let config = RpcPoolConfig {
+            max_attempts: 2,
+            timeout: Duration::from_millis(200),
+            ..Default::default()
+        };
+        // оба порта закрыты: каждая попытка — сетевая ошибка
+        let pool = RpcPool::with_config(
+            ["http://127.0.0.1:1", "http://127.0.0.1:2/?api-key=secret"],
+            config,
+        )
+        .unwrap();
+        let err = pool
+            .call(|client| async move { client.get_slot().await })
+            .await
+            .unwrap_err();
+        assert_eq!(classify(&err), Failure::Endpoint);
+
+        let health = pool.health();
+        assert_eq!(health[1].url, "http://127.0.0.1:2/?api-key=***");
+        for endpoint in &health {
+            assert_eq!((endpoint.requests, endpoint.errors), (1, 1));
+            assert!(endpoint.backoff_remaining.is_some());
+        }
+    }
+}
@@ -0,0 +1,264 @@
+//! Запись WebSocket кадров в NDJSON и их воспроизведение через парсер.
+//!
+//! Формат — одна строка на кадр: `{"ts_ms":<unix ms приёма>,"frame":<JSON кадра>}`.
+//! `FrameRecorder` пишет такие файлы (`--record <dir>` у стримингового клиента,
+//! `SubscriberConfig::record_dir`), `replay` читает файл или каталог `*.jsonl` и
+//! отдаёт кадры с исходными интервалами, ускоренно или без пауз — для нагрузочных
+//! прогонов и разбора инцидентов на одном и том же потоке.
+
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{LineWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use futures::Stream;
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, BufReader, Lines};
+
+use crate::types::SolanaTransaction;
+
+use super::ws::decode_transaction_notification;
+
+/// Темп воспроизведения относительно интервалов между `ts_ms`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ReplaySpeed {
+    #[default]
+    Original,
+    /// Интервалы делятся на множитель: `Accelerated(10.0)` — в десять раз быстрее.
+    Accelerated(f64),
+    /// Без пауз, насколько успевает потребитель.
+    Unthrottled,
+}
+
+impl ReplaySpeed {
+    /// `max` — без пауз, иначе множитель (`1` — исходная скорость).
+    pub fn parse(value: &str) -> Option<Self> {
+        if value == "max" {
+            return Some(Self::Unthrottled);
+        }
+        let factor: f64 = value.trim_end_matches('x').parse().ok()?;
+        (factor.is_finite() && factor > 0.0).then_some(Self::Accelerated(factor))
+    }
+
+    fn factor(self) -> Option<f64> {
+        match self {
+            Self::Original => Some(1.0),
+            Self::Accelerated(factor) => Some(factor),
+            Self::Unthrottled => None,
+        }
+    }
+}
+
+/// Кадр из записи.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RecordedFrame {
+    pub ts_ms: u64,
+    pub frame: Value,
+}
+
+/// Пишет принятые кадры в `<dir>/ws-<unix ms>.jsonl`, по строке на кадр.
+pub struct FrameRecorder {
+    path: PathBuf,
+    out: LineWriter<File>,
+}
+
+impl FrameRecorder {
+    /// Новый файл в `dir` (каталог создаётся); у каждой сессии — свой файл.
+    pub fn create(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("failed to create record dir {}", dir.display()))?;
+        let path = dir.join(format!("ws-{}.jsonl", unix_ms()));
+        let file = File::options()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("failed to open {}", path.display()))?;
+        Ok(Self {
+            path,
+            out: LineWriter::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// `frame` — текст кадра, уже проверенный как JSON. Многострочный кадр
+    /// пересериализуется, чтобы не сломать NDJSON.
+    pub fn record(&mut self, frame: &[u8]) -> Result<()> {
+        let mut line = Vec::with_capacity(frame.len() + 32);
+        write!(line, "{{\"ts_ms\":{},\"frame\":", unix_ms())?;
+        if frame.contains(&b'\n') {
+            let value: Value = serde_json::from_slice(frame).context("frame is not JSON")?;
+            serde_json::to_writer(&mut line, &value)?;
+        } else {
+            line.extend_from_slice(frame);
+        }
+        line.extend_from_slice(b"}\n");
+        self.out
+            .write_all(&line)
+            .with_context(|| format!("failed to write {}", self.path.display()))
+    }
+}
+
+fn unix_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Файлы записи: сам `path` либо `*.jsonl` каталога в порядке имён (имена
+/// `FrameRecorder` сортируются по времени начала сессии).
+pub fn recording_files(path: impl AsRef<Path>) -> Result<Vec<PathBuf>> {
+    let path = path.as_ref();
+    if !path.is_dir() {
+        anyhow::ensure!(path.exists(), "recording {} not found", path.display());
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = Vec::new();
+    for entry in
+        std::fs::read_dir(path).with_context(|| format!("failed to read {}", path.display()))?
+    {
+        let file = entry?.path();
+        if file.extension().is_some_and(|ext| ext == "jsonl") {
+            files.push(file);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Кадры записи в темпе `speed`. Файлы читаются по мере потребления; битые строки
+/// пропускаются с предупреждением.
+pub fn replay(
+    path: impl AsRef<Path>,
+    speed: ReplaySpeed,
+) -> Result<impl Stream<Item = RecordedFrame> + Send + 'static> {
+    let state = ReplayState {
+        files: recording_files(path)?.into(),
+        lines: None,
+        factor: speed.factor(),
+        clock: None,
+    };
+    Ok(futures::stream::unfold(state, |mut state| async move {
+        let frame = state.next_frame().await?;
+        state.pace(frame.ts_ms).await;
+        Some((frame, state))
+    }))
+}
+
+/// `params.result` кадра `transactionNotification`; `Ok(None)` для остальных кадров
+/// и не-base64 транзакций. Декодер для `pipeline::Pipeline::new`.
+pub fn decode_frame(frame: &Value) -> Result<Option<SolanaTransaction>> {
+    if frame.get("method").and_then(Value::as_str) != Some("transactionNotification") {
+        return Ok(None);
+    }
+    match frame.pointer("/params/result") {
+        Some(result) => decode_transaction_notification(result),
+        None => Ok(None),
+    }
+}
+
+struct ReplayState {
+    files: VecDeque<PathBuf>,
+    lines: Option<(PathBuf, Lines<BufReader<tokio::fs::File>>)>,
+    factor: Option<f64>,
+    /// Момент выдачи первого кадра и его `ts_ms`.
+    clock: Option<(tokio::time::Instant, u64)>,
+}
+
+impl ReplayState {
+    async fn next_frame(&mut self) -> Option<RecordedFrame> {
+        loop {
+            let Some((path, lines)) = &mut self.lines else {
+                let path = self.files.pop_front()?;
+                match tokio::fs::File::open(&path).await {
+                    Ok(file) => self.lines = Some((path, BufReader::new(file).lines())),
+                    Err(err) => tracing::warn!("skipping recording {}: {err}", path.display()),
+                }
+                continue;
+            };
+            match lines.next_line().await {
+                Ok(Some(line)) if line.trim().is_empty() => {}
+                Ok(Some(line)) => match serde_json::from_str(&line) {
+                    Ok(frame) => return Some(frame),
+                    Err(err) => {
+                        tracing::warn!("skipping malformed line in {}: {err}", path.display())
+                    }
+                },
+                Ok(None) => self.lines = None,
+                Err(err) => {
+                    tracing::warn!("failed to read {}: {err}", path.display());
+                    self.lines = None;
+                }
+            }
+        }
+    }
+
+    async fn pace(&mut self, ts_ms: u64) {
+        let Some(factor) = self.factor else {
+            return;
+        };
+        let (start, first_ts) = *self
+            .clock
+            .get_or_insert_with(|| (tokio::time::Instant::now(), ts_ms));
+        let offset = ts_ms.saturating_sub(first_ts) as f64 / 1_000.0 / factor;
+        tokio::time::sleep_until(start + Duration::from_secs_f64(offset)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn replays_recorded_frames_in_order() {
+        let dir = std::env::temp_dir().join(format!("dex_replay_{}", std::process::id()));
+        let mut recorder = FrameRecorder::create(&dir).unwrap();
+        recorder
+            .record(br#"{"jsonrpc":"2.0","result":7,"id":1}"#)
+            .unwrap();
+        recorder
+            .record(b"{\n\"method\": \"transactionNotification\",\n\"params\": {}\n}")
+            .unwrap();
+        let path = recorder.path().to_path_buf();
+        drop(recorder);
+        std::fs::write(
+            dir.join("ws-9999999999999.jsonl"),
+            "not json\n\n{\"ts_ms\":5,\"frame\":{\"id\":2}}\n",
+        )
+        .unwrap();
+
+        let frames: Vec<RecordedFrame> = replay(&dir, ReplaySpeed::Unthrottled)
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].frame["result"], 7);
+        assert_eq!(frames[1].frame["method"], "transactionNotification");
+        assert_eq!(frames[2].frame["id"], 2);
+        // уведомление без result и ответ на подписку — не транзакции
+        assert!(frames
+            .iter()
+            .all(|f| decode_frame(&f.frame).unwrap().is_none()));
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn parses_speed() {
+        assert_eq!(ReplaySpeed::parse("max"), Some(ReplaySpeed::Unthrottled));
+        assert_eq!(
+            ReplaySpeed::parse("10x"),
+            Some(ReplaySpeed::Accelerated(10.0))
+        );
+        assert_eq!(ReplaySpeed::parse("0"), None);
+    }
+}
@@ -0,0 +1,792 @@
+//! Helius `transactionSubscribe` WebSocket клиент.
+//!
+//! Подписка с base64 encoding, декодирование уведомлений в `SolanaTransaction`,
+//! парсинг через `DexParser` и автоматическое переподключение. Принятые кадры можно
+//! записывать (`SubscriberConfig::record_dir`) и потом воспроизводить через `rpc::replay`.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use base64_simd::STANDARD as B64;
+use futures::channel::mpsc;
+use futures::{future, Future, SinkExt, Stream, StreamExt};
+use serde_json::{json, Value};
+use solana_sdk::transaction::VersionedTransaction;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::config::{redact_url, Failover, ParseConfig};
+use crate::core::dex_parser::DexParser;
+use crate::core::rpc_json::json_stack_height;
+use crate::types::{
+    AccountKey, BalanceChange, InnerInstruction, KeyInterner, ParseResult, SolanaInstruction,
+    SolanaTransaction, TokenAmount, TokenBalance, TransactionMeta, TransactionStatus,
+};
+
+use super::alt::LookupTableResolver;
+use super::replay::FrameRecorder;
+
+/// Уровень подтверждения подписки.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Commitment {
+    #[default]
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl Commitment {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Commitment::Processed => "processed",
+            Commitment::Confirmed => "confirmed",
+            Commitment::Finalized => "finalized",
+        }
+    }
+}
+
+/// Фильтр `transactionSubscribe` (первый параметр запроса).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SubscriptionFilter {
+    pub account_include: Vec<String>,
+    pub account_exclude: Vec<String>,
+    pub account_required: Vec<String>,
+    pub vote: bool,
+    /// `None` — и успешные, и упавшие транзакции.
+    pub failed: Option<bool>,
+}
+
+impl Default for SubscriptionFilter {
+    fn default() -> Self {
+        Self {
+            account_include: Vec::new(),
+            account_exclude: Vec::new(),
+            account_required: Vec::new(),
+            vote: false,
+            failed: Some(false),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SubscriberConfig {
+    /// Полный WebSocket URL, включая api-key.
+    pub ws_url: String,
+    pub filter: SubscriptionFilter,
+    pub commitment: Commitment,
+    /// Задержка перед первым переподключением, дальше удваивается до `max_reconnect_delay`.
+    pub reconnect_delay: Duration,
+    pub max_reconnect_delay: Duration,
+    /// Ёмкость выходного канала ParseResult'ов.
+    pub channel_capacity: usize,
+    pub parse_config: Option<ParseConfig>,
+    /// Каталог для записи принятых кадров (`replay::FrameRecorder`); файл на сессию.
+    pub record_dir: Option<PathBuf>,
+}
+
+impl Default for SubscriberConfig {
+    fn default() -> Self {
+        Self {
+            ws_url: String::new(),
+            filter: SubscriptionFilter::default(),
+            commitment: Commitment::default(),
+            reconnect_delay: Duration::from_millis(500),
+            max_reconnect_delay: Duration::from_secs(30),
+            channel_capacity: 1024,
+            parse_config: None,
+            record_dir: None,
+        }
+    }
+}
+
+/// Подписка на транзакции через Helius `transactionSubscribe`.
+pub struct TransactionSubscriber {
+    config: SubscriberConfig,
+    parser: Arc<DexParser>,
+    lookup_tables: Option<Arc<LookupTableResolver>>,
+}
+
+impl TransactionSubscriber {
+    pub fn new(config: SubscriberConfig) -> Self {
+        Self {
+            config,
+            parser: Arc::new(DexParser::new()),
+            lookup_tables: None,
+        }
+    }
+
+    pub fn with_parser(mut self, parser: Arc<DexParser>) -> Self {
+        self.parser = parser;
+        self
+    }
+
+    /// Для уведомлений без `meta.loadedAddresses` адреса v0 транзакций достаются из
+    /// lookup-таблиц; без resolver'а такие транзакции разбираются по статическим ключам.
+    pub fn with_lookup_tables(mut self, resolver: Arc<LookupTableResolver>) -> Self {
+        self.lookup_tables = Some(resolver);
+        self
+    }
+
+    /// JSON-RPC запрос подписки для текущего конфига.
+    pub fn subscription_request(&self) -> Value {
+        let filter = &self.config.filter;
+        let mut params = json!({
+            "accountInclude": filter.account_include,
+            "accountExclude": filter.account_exclude,
+            "accountRequired": filter.account_required,
+            "vote": filter.vote,
+        });
+        if let Some(failed) = filter.failed {
+            params["failed"] = json!(failed);
+        }
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "transactionSubscribe",
+            "params": [
+                params,
+                {
+                    "commitment": self.config.commitment.as_str(),
+                    "encoding": "base64",
+                    "transactionDetails": "full",
+                    "maxSupportedTransactionVersion": 0
+                }
+            ]
+        })
+    }
+
+    /// Запускает подписку в фоновой задаче и возвращает stream распарсенных транзакций.
+    /// Задача завершается, когда stream дропнут. Разбор идёт в `spawn_blocking`, чтобы
+    /// не занимать поток runtime, читающий сокет.
+    pub fn subscribe(self) -> impl Stream<Item = ParseResult> + Send + 'static {
+        let parser = self.parser.clone();
+        let parse_config = self.config.parse_config.clone();
+        self.spawn(move |tx| {
+            let parser = parser.clone();
+            let parse_config = parse_config.clone();
+            async move {
+                tokio::task::spawn_blocking(move || parser.parse_all(tx, parse_config))
+                    .await
+                    .map_err(|err| tracing::warn!("parse task failed: {err}"))
+                    .ok()
+            }
+        })
+    }
+
+    /// Декодированные, но не распарсенные транзакции — источник для `pipeline::Pipeline`,
+    /// где разбор идёт в отдельных воркерах.
+    pub fn subscribe_transactions(self) -> impl Stream<Item = SolanaTransaction> + Send + 'static {
+        self.spawn(|tx| future::ready(Some(tx)))
+    }
+
+    fn spawn<T, F, Fut>(self, map: F) -> mpsc::Receiver<T>
+    where
+        T: Send + 'static,
+        F: Fn(SolanaTransaction) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Option<T>> + Send,
+    {
+        let (tx, rx) = mpsc::channel(self.config.channel_capacity.max(1));
+        tokio::spawn(async move { self.run(tx, map).await });
+        rx
+    }
+
+    async fn run<T, F, Fut>(self, mut output: mpsc::Sender<T>, map: F)
+    where
+        F: Fn(SolanaTransaction) -> Fut,
+        Fut: Future<Output = Option<T>>,
+    {
+        let mut delay = self.config.reconnect_delay;
+        loop {
+            let mut subscribed = false;
+            match self
+                .subscribe_once(&mut output, &map, &mut subscribed)
+                .await
+            {
+                // Stream закрыт потребителем — выходим
+                Ok(true) => return,
+                Ok(false) => {
+                    tracing::warn!("websocket closed, reconnecting");
+                }
+                Err(err) => {
+                    tracing::warn!("websocket error: {err:#}, reconnecting");
+                }
+            }
+            if output.is_closed() {
+                return;
+            }
+            // после успешной подписки backoff начинается заново
+            if subscribed {
+                delay = self.config.reconnect_delay;
+            }
+            tracing::debug!("reconnecting in {delay:?}");
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(self.config.max_reconnect_delay);
+        }
+    }
+
+    /// Одна сессия подписки. `Ok(true)` — потребитель закрыл stream; `subscribed`
+    /// выставляется, когда сервер подтвердил подписку.
+    async fn subscribe_once<T, F, Fut>(
+        &self,
+        output: &mut mpsc::Sender<T>,
+        map: &F,
+        subscribed: &mut bool,
+    ) -> Result<bool>
+    where
+        F: Fn(SolanaTransaction) -> Fut,
+        Fut: Future<Output = Option<T>>,
+    {
+        let (ws_stream, _) = tokio_tungstenite::connect_async(self.config.ws_url.as_str())
+            .await
+            .context("WebSocket connection failed")?;
+        let (mut sink, mut stream) = ws_stream.split();
+        sink.send(Message::Text(self.subscription_request().to_string()))
+            .await
+            .context("failed to send subscription")?;
+        let mut recorder = match &self.config.record_dir {
+            Some(dir) => Some(FrameRecorder::create(dir)?),
+            None => None,
+        };
+
+        while let Some(msg) = stream.next().await {
+            let raw = match msg.context("websocket read failed")? {
+                Message::Text(text) => text.into_bytes(),
+                Message::Binary(bytes) => bytes,
+                Message::Ping(payload) => {
+                    sink.send(Message::Pong(payload)).await.ok();
+                    continue;
+                }
+                Message::Pong(_) | Message::Frame(_) => continue,
+                Message::Close(_) => return Ok(false),
+            };
+
+            let mut value: Value = match serde_json::from_slice(&raw) {
+                Ok(value) => value,
+                Err(err) => {
+                    tracing::debug!("skipping malformed websocket frame: {err}");
+                    continue;
+                }
+            };
+            if let Some(recorder) = &mut recorder {
+                recorder.record(&raw)?;
+            }
+            if let Some(err) = value.get("error") {
+                return Err(anyhow!("subscription rejected: {err}"));
+            }
+            if value.get("id").is_some() && value.get("result").is_some() {
+                *subscribed = true;
+            }
+            if value.get("method").and_then(Value::as_str) != Some("transactionNotification") {
+                continue;
+            }
+            let Some(result) = value.pointer_mut("/params/result") else {
+                continue;
+            };
+            if let Some(resolver) = &self.lookup_tables {
+                let slot = result.get("slot").and_then(Value::as_u64);
+                if let Some(tx) = result.get_mut("transaction") {
+                    if let Err(err) = resolver.fill_loaded_addresses(tx, slot).await {
+                        tracing::warn!("failed to resolve lookup tables: {err:#}");
+                        continue;
+                    }
+                }
+            }
+
+            let tx = match decode_transaction_notification(result) {
+                Ok(Some(tx)) => tx,
+                Ok(None) => continue,
+                Err(err) => {
+                    tracing::warn!("skipping transaction notification: {err:#}");
+                    continue;
+                }
+            };
+            *subscribed = true;
+            let Some(item) = map(tx).await else {
+                continue;
+            };
+            if output.send(item).await.is_err() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+}
+
+pub type WsStream =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// Подключается к первому доступному endpoint'у, начиная с текущего; при ошибке
+/// переключает `endpoints` на следующий.
+pub async fn connect_with_failover(endpoints: &Failover) -> Result<WsStream> {
+    let mut url = endpoints.current();
+    for _ in 0..endpoints.len() {
+        match tokio_tungstenite::connect_async(url).await {
+            Ok((stream, _)) => return Ok(stream),
+            Err(err) => {
+                tracing::warn!("websocket {} failed: {err}", redact_url(url));
+                url = endpoints.advance();
+            }
+        }
+    }
+    Err(anyhow!(
+        "WebSocket connection failed on all {} endpoints",
+        endpoints.len()
+    ))
+}
+
+/// Декодирует `params.result` уведомления `transactionNotification` (base64 encoding).
+/// `Ok(None)` — транзакция пришла не в base64.
+pub fn decode_transaction_notification(result: &Value) -> Result<Option<SolanaTransaction>> {
+    let signature = result
+        .get("signature")
+        .and_then(Value::as_str)
+        .unwrap_or_default();
+    let slot = result.get("slot").and_then(Value::as_u64).unwrap_or(0);
+
+    let Some(tx_bytes) = extract_base64_tx(result)? else {
+        return Ok(None);
+    };
+    let meta = result
+        .pointer("/transaction/meta")
+        .or_else(|| result.get("meta"));
+    convert_binary_to_solana_tx(&tx_bytes, slot, signature, meta).map(Some)
+}
+
+/// Helius присылает `["<base64>", "base64"]` либо в `result.transaction.transaction`,
+/// либо в `result.transaction`.
+pub fn extract_base64_tx(result: &Value) -> Result<Option<Vec<u8>>> {
+    let candidates = [
+        result.pointer("/transaction/transaction"),
+        result.get("transaction"),
+    ];
+    for arr in candidates.into_iter().flatten().filter_map(Value::as_array) {
+        if let [Value::String(b64), Value::String(enc)] = arr.as_slice() {
+            if enc == "base64" {
+                let bytes = B64.decode_to_vec(b64).context("base64 decode failed")?;
+                return Ok(Some(bytes));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Собирает `SolanaTransaction` из bincode `VersionedTransaction` и JSON meta.
+pub fn convert_binary_to_solana_tx(
+    bytes: &[u8],
+    slot: u64,
+    signature: &str,
+    meta: Option<&Value>,
+) -> Result<SolanaTransaction> {
+    let versioned_tx: VersionedTransaction =
+        bincode::deserialize(bytes).context("failed to deserialize VersionedTransaction")?;
+
+    let message = &versioned_tx.message;
+    let static_keys = message.static_account_keys();
+    let num_signatures = message.header().num_required_signatures as usize;
+    let signers: Vec<String> = static_keys
+        .iter()
+        .take(num_signatures)
+        .map(|pk| bs58::encode(pk.as_ref()).into_string())
+        .collect();
+
+    // Статические ключи + адреса из ALT (writable, затем readonly)
+    let mut account_keys: Vec<String> = static_keys
+        .iter()
+        .map(|pk| bs58::encode(pk.as_ref()).into_string())
+        .collect();
+    if let Some(loaded) = meta.and_then(|m| m.get("loadedAddresses")) {
+        for key in ["writable", "readonly"] {
+            if let Some(addresses) = loaded.get(key).and_then(Value::as_array) {
+                account_keys.extend(addresses.iter().filter_map(Value::as_str).map(String::from));
+            }
+        }
+    }
+
+    // инструкции ссылаются на общие `Arc<str>` ключей, а не копируют строки
+    let mut interner = KeyInterner::new();
+    let keys: Vec<AccountKey> = account_keys
+        .iter()
+        .map(|key| interner.intern(key))
+        .collect();
+
+    // индекс за пределами ключей — адреса ALT не подгружены; сжимать список нельзя,
+    // иначе позиции аккаунтов у парсеров съедут
+    let instructions = message
+        .instructions()
+        .iter()
+        .map(|ix| -> Result<SolanaInstruction> {
+            Ok(SolanaInstruction {
+                program_id: account_key(&keys, ix.program_id_index as u64)?,
+                accounts: ix
+                    .accounts
+                    .iter()
+                    .map(|&idx| account_key(&keys, idx as u64))
+                    .collect::<Result<_>>()?,
+                data: B64.encode_to_string(&ix.data),
+            })
+        })
+        .enumerate()
+        .map(|(i, ix)| ix.with_context(|| format!("instruction #{i}")))
+        .collect::<Result<Vec<_>>>()?;
+
+    let (inner_instructions, pre_token_balances, post_token_balances, tx_meta) = match meta {
+        Some(meta) => (
+            extract_inner_instructions(meta, &keys, &mut interner)?,
+            extract_token_balances(meta.get("preTokenBalances"), &account_keys),
+            extract_token_balances(meta.get("postTokenBalances"), &account_keys),
+            extract_transaction_meta(meta, &account_keys),
+        ),
+        None => (
+            Vec::new(),
+            Vec::new(),
+            Vec::new(),
+            TransactionMeta {
+                fee: 0,
+                compute_units: 0,
+                status: TransactionStatus::Success,
+                sol_balance_changes: HashMap::new(),
+                token_balance_changes: HashMap::new(),
+                err: None,
+                log_messages: Vec::new(),
+            },
+        ),
+    };
+
+    let block_time = meta
+        .and_then(|m| m.get("blockTime").and_then(Value::as_u64))
+        .unwrap_or(0);
+
+    Ok(SolanaTransaction {
+        slot,
+        signature: signature.to_string(),
+        block_time,
+        signers,
+        instructions,
+        inner_instructions,
+        transfers: Vec::new(),
+        pre_token_balances,
+        post_token_balances,
+        meta: tx_meta,
+    })
+}
+
+fn account_key(keys: &[AccountKey], idx: u64) -> Result<AccountKey> {
+    keys.get(idx as usize).cloned().ok_or_else(|| {
+        anyhow!(
+            "account index {idx} out of range ({} keys, lookup tables not resolved?)",
+            keys.len()
+        )
+    })
+}
+
+fn extract_inner_instructions(
+    meta: &Value,
+    keys: &[AccountKey],
+    interner: &mut KeyInterner,
+) -> Result<Vec<InnerInstruction>> {
+    let Some(groups) = meta.get("innerInstructions").and_then(Value::as_array) else {
+        return Ok(Vec::new());
+    };
+
+    groups
+        .iter()
+        .filter_map(|group| {
+            let index = group.get("index").and_then(Value::as_u64).unwrap_or(0) as usize;
+            let items = group
+                .get("instructions")
+                .and_then(Value::as_array)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let instructions = match items
+                .iter()
+                .map(|ix| convert_json_instruction(ix, keys, interner))
+                .collect::<Result<Vec<_>>>()
+            {
+                Ok(instructions) => instructions,
+                Err(err) => {
+                    return Some(Err(err.context(format!("inner instructions of #{index}"))))
+                }
+            };
+            (!instructions.is_empty()).then(|| {
+                Ok(InnerInstruction {
+                    index,
+                    instructions,
+                    stack_heights: items.iter().map(json_stack_height).collect(),
+                })
+            })
+        })
+        .collect()
+}
+
+fn convert_json_instruction(
+    ix: &Value,
+    keys: &[AccountKey],
+    interner: &mut KeyInterner,
+) -> Result<SolanaInstruction> {
+    let program_id = match ix.get("programId").and_then(Value::as_str) {
+        Some(program_id) => interner.intern(program_id),
+        None => match ix.get("programIdIndex").and_then(Value::as_u64) {
+            Some(idx) => account_key(keys, idx)?,
+            None => AccountKey::default(),
+        },
+    };
+
+    let accounts = ix
+        .get("accounts")
+        .and_then(Value::as_array)
+        .map(|accounts| {
+            accounts
+                .iter()
+                .filter_map(|v| match v {
+                    Value::String(s) => Some(Ok(interner.intern(s))),
+                    _ => v.as_u64().map(|idx| account_key(keys, idx)),
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .transpose()?
+        .unwrap_or_default();
+
+    // В meta data inner инструкций приходит в bs58, парсер ожидает base64
+    let data = match ix.get("data").and_then(Value::as_str) {
+        Some(s) => {
+            let bytes = bs58::decode(s)
+                .into_vec()
+                .with_context(|| format!("inner instruction data is not base58: {s}"))?;
+            B64.encode_to_string(&bytes)
+        }
+        None => String::new(),
+    };
+
+    Ok(SolanaInstruction {
+        program_id,
+        accounts,
+        data,
+    })
+}
+
+fn extract_token_balances(balances: Option<&Value>, account_keys: &[String]) -> Vec<TokenBalance> {
+    let Some(balances) = balances.and_then(Value::as_array) else {
+        return Vec::new();
+    };
+
+    balances
+        .iter()
+        .map(|balance| {
+            let account_value = balance
+                .get("accountIndex")
+                .or_else(|| balance.get("account"));
+            let account = account_value
+                .and_then(Value::as_u64)
+                .and_then(|idx| account_keys.get(idx as usize).cloned())
+                .or_else(|| account_value.and_then(Value::as_str).map(String::from))
+                .unwrap_or_default();
+            let ui_token_amount = balance
+                .get("uiTokenAmount")
+                .map(|v| {
+                    let amount = v.get("amount").and_then(Value::as_str).unwrap_or("0");
+                    let decimals = v.get("decimals").and_then(Value::as_u64).unwrap_or(0) as u8;
+                    let ui_amount = v.get("uiAmount").and_then(Value::as_f64);
+                    TokenAmount::new(amount, decimals, ui_amount)
+                })
+                .unwrap_or_default();
+
+            TokenBalance {
+                account,
+                mint: balance
+                    .get("mint")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                owner: balance
+                    .get("owner")
+                    .and_then(Value::as_str)
+                    .map(String::from),
+                ui_token_amount,
+            }
+        })
+        .collect()
+}
+
+fn extract_transaction_meta(meta: &Value, account_keys: &[String]) -> TransactionMeta {
+    let compute_units = meta
+        .get("computeUnitsConsumed")
+        .or_else(|| meta.get("computeUnits"))
+        .and_then(Value::as_u64)
+        .unwrap_or(0);
+    let err = meta
+        .get("err")
+        .filter(|err| !err.is_null())
+        .map(Value::to_string);
+
+    TransactionMeta {
+        fee: meta.get("fee").and_then(Value::as_u64).unwrap_or(0),
+        compute_units,
+        status: if err.is_some() {
+            TransactionStatus::Failed
+        } else {
+            TransactionStatus::Success
+        },
+        sol_balance_changes: extract_sol_balance_changes(meta, account_keys),
+        token_balance_changes: HashMap::new(),
+        err,
+        log_messages: crate::core::rpc_json::log_messages(meta),
+    }
+}
+
+fn extract_sol_balance_changes(
+    meta: &Value,
+    account_keys: &[String],
+) -> HashMap<String, BalanceChange> {
+    let mut changes = HashMap::new();
+    let Some(pre_balances) = meta.get("preBalances").and_then(Value::as_array) else {
+        return changes;
+    };
+    let post_balances = meta.get("postBalances").and_then(Value::as_array);
+
+    for (idx, pre_value) in pre_balances.iter().enumerate() {
+        let pre = pre_value.as_i64().unwrap_or(0) as i128;
+        let post = post_balances
+            .and_then(|arr| arr.get(idx))
+            .and_then(Value::as_i64)
+            .unwrap_or(0) as i128;
+        if pre != post {
+            let account = account_keys
+                .get(idx)
+                .cloned()
+                .unwrap_or_else(|| format!("unknown_{idx}"));
+            changes.insert(
+                account,
+                BalanceChange {
+                    pre,
+                    post,
+                    change: post - pre,
+                },
+            );
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_sdk::message::Message as LegacyMessage;
+    use solana_sdk::pubkey::Pubkey;
+    use solana_sdk::transaction::Transaction;
+
+    #[test]
+    fn decodes_base64_notification() {
+        let payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        let ix =
+            Instruction::new_with_bytes(program, &[1, 2, 3], vec![AccountMeta::new(payer, true)]);
+        let tx = VersionedTransaction::from(Transaction::new_unsigned(LegacyMessage::new(
+            &[ix],
+            Some(&payer),
+        )));
+        let encoded = B64.encode_to_string(bincode::serialize(&tx).unwrap());
+
+        let result = json!({
+            "signature": "sig",
+            "slot": 42,
+            "transaction": {
+                "transaction": [encoded, "base64"],
+                "meta": {
+                    "err": null,
+                    "fee": 5000,
+                    "preBalances": [10_000, 1],
+                    "postBalances": [5_000, 1],
+                    "computeUnitsConsumed": 1234
+                }
+            }
+        });
+
+        let tx = decode_transaction_notification(&result)
+            .unwrap()
+            .expect("base64 transaction");
+        assert_eq!(tx.slot, 42);
+        assert_eq!(tx.signers, vec![payer.to_string()]);
+        assert_eq!(tx.instructions[0].program_id, program.to_string());
+        assert_eq!(tx.instructions[0].data, B64.encode_to_string([1u8, 2, 3]));
+        assert_eq!(tx.meta.status, TransactionStatus::Success);
+        assert_eq!(tx.meta.compute_units, 1234);
+        assert_eq!(
+            tx.meta.sol_balance_changes[&payer.to_string()].change,
+            -5_000
+        );
+    }
+
+    #[test]
+    fn rejects_inner_instruction_data_that_is_not_base58() {
+        let payer = Pubkey::new_unique();
+        let tx = VersionedTransaction::from(Transaction::new_unsigned(LegacyMessage::new(
+            &[],
+            Some(&payer),
+        )));
+        let bytes = bincode::serialize(&tx).unwrap();
+        let meta = json!({
+            "innerInstructions": [{
+                "index": 0,
+                "instructions": [{ "programIdIndex": 0, "accounts": [0], "data": "0OIl" }]
+            }]
+        });
+
+        let err = convert_binary_to_solana_tx(&bytes, 1, "sig", Some(&meta)).unwrap_err();
+        assert!(format!("{err:#}").contains("not base58"), "{err:#}");
+    }
+
+    #[test]
+    fn rejects_account_index_outside_loaded_keys() {
+        use solana_sdk::instruction::CompiledInstruction;
+        use solana_sdk::message::{v0, MessageHeader, VersionedMessage};
+
+        let payer = Pubkey::new_unique();
+        let program = Pubkey::new_unique();
+        // индекс 2 указывает в ALT, а `loadedAddresses` в meta нет
+        let message = v0::Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            },
+            account_keys: vec![payer, program],
+            instructions: vec![CompiledInstruction::new_from_raw_parts(
+                1,
+                vec![1],
+                vec![0, 2],
+            )],
+            ..Default::default()
+        };
+        let tx = VersionedTransaction {
+            signatures: vec![Default::default()],
+            message: VersionedMessage::V0(message),
+        };
+        let bytes = bincode::serialize(&tx).unwrap();
+
+        let err = convert_binary_to_solana_tx(&bytes, 1, "sig", Some(&json!({}))).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("account index 2 out of range"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn subscription_request_uses_filter() {
+        let subscriber = TransactionSubscriber::new(SubscriberConfig {
+            filter: SubscriptionFilter {
+                account_include: vec!["pool".to_string()],
+                failed: None,
+                ..Default::default()
+            },
+            commitment: Commitment::Confirmed,
+            ..Default::default()
+        });
+        let request = subscriber.subscription_request();
+        assert_eq!(request["params"][0]["accountInclude"], json!(["pool"]));
+        assert!(request["params"][0].get("failed").is_none());
+        assert_eq!(request["params"][1]["commitment"], "confirmed");
+    }
+}
@@ -0,0 +1,131 @@
+use anyhow::{bail, Context, Result};
+
+use crate::price::BoxFuture;
+use crate::types::{PoolEvent, TradeInfo, TransferData};
+
+use super::{EventSink, JsonRows, SinkTables};
+
+/// `EventSink` поверх ClickHouse HTTP интерфейса:
+/// `POST {url}/?query=INSERT INTO <table> FORMAT JSONEachRow`.
+///
+/// Строки копятся в памяти и отправляются на `flush` или когда буфер дорос до
+/// `batch_size` — ClickHouse плохо переносит частые мелкие вставки.
+pub struct ClickHouseSink {
+    client: reqwest::Client,
+    url: String,
+    user: Option<String>,
+    password: Option<String>,
+    tables: SinkTables,
+    batch_size: usize,
+    rows: JsonRows,
+}
+
+impl ClickHouseSink {
+    /// `url` — `http://host:8123`; база указывается в именах таблиц (`db.dex_trades`).
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into().trim_end_matches('/').to_string(),
+            user: None,
+            password: None,
+            tables: SinkTables::default(),
+            batch_size: 10_000,
+            rows: JsonRows::default(),
+        }
+    }
+
+    pub fn with_credentials(
+        mut self,
+        user: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Self {
+        self.user = Some(user.into());
+        self.password = Some(password.into());
+        self
+    }
+
+    pub fn with_tables(mut self, tables: SinkTables) -> Self {
+        self.tables = tables;
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    async fn insert(&self, table: &str, rows: &[String]) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let mut request = self
+            .client
+            .post(format!("{}/", self.url))
+            .query(&[("query", format!("INSERT INTO {table} FORMAT JSONEachRow"))])
+            .body(rows.join("\n"));
+        if let Some(user) = &self.user {
+            request = request.header("X-ClickHouse-User", user);
+        }
+        if let Some(password) = &self.password {
+            request = request.header("X-ClickHouse-Key", password);
+        }
+        let response = request
+            .send()
+            .await
+            .with_context(|| format!("clickhouse insert into {table}"))?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            bail!("clickhouse insert into {table} failed: {status}: {body}");
+        }
+        Ok(())
+    }
+
+    async fn flush_if_full(&mut self) -> Result<()> {
+        if self.rows.len() >= self.batch_size {
+            self.flush_rows().await?;
+        }
+        Ok(())
+    }
+
+    /// Буфер таблицы очищается только после успешной вставки: при ошибке `flush`
+    /// можно повторить.
+    async fn flush_rows(&mut self) -> Result<()> {
+        self.insert(&self.tables.trades, &self.rows.trades).await?;
+        self.rows.trades.clear();
+        self.insert(&self.tables.pool_events, &self.rows.pool_events)
+            .await?;
+        self.rows.pool_events.clear();
+        self.insert(&self.tables.transfers, &self.rows.transfers)
+            .await?;
+        self.rows.transfers.clear();
+        Ok(())
+    }
+}
+
+impl EventSink for ClickHouseSink {
+    fn on_trade<'a>(&'a mut self, trade: &'a TradeInfo) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.rows.push_trade(trade)?;
+            self.flush_if_full().await
+        })
+    }
+
+    fn on_pool_event<'a>(&'a mut self, event: &'a PoolEvent) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.rows.push_pool_event(event)?;
+            self.flush_if_full().await
+        })
+    }
+
+    fn on_transfer<'a>(&'a mut self, transfer: &'a TransferData) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.rows.push_transfer(transfer)?;
+            self.flush_if_full().await
+        })
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.flush_rows())
+    }
+}
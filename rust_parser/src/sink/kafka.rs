@@ -0,0 +1,149 @@
+use std::time::Duration;
+
+use anyhow::{anyhow, Context, Result};
+use rdkafka::error::KafkaError;
+use rdkafka::producer::{DeliveryFuture, FutureProducer, FutureRecord, Producer};
+use rdkafka::types::RDKafkaErrorCode;
+use rdkafka::util::Timeout;
+use rdkafka::ClientConfig;
+
+use crate::price::BoxFuture;
+use crate::types::{ParseResult, PoolEvent, TradeInfo, TransferData};
+
+use super::publish::{Message, PartitionBy, PayloadFormat};
+use super::{EventSink, SinkTables};
+
+/// Сколько сообщений ждут подтверждения доставки, прежде чем `on_*` дождётся их.
+const MAX_IN_FLIGHT: usize = 10_000;
+
+#[derive(Clone, Copy)]
+enum Topic {
+    Results,
+    Trades,
+    PoolEvents,
+    Transfers,
+}
+
+/// Kafka publisher (rdkafka).
+///
+/// `on_result` кладёт `ParseResult` целиком в `topic`; `on_trade`/`on_pool_event`/
+/// `on_transfer` — отдельные события в топики из `SinkTables`. Ключ сообщения —
+/// `PartitionBy`: сообщения одного ключа попадают в одну партицию и не перемешиваются.
+/// Доставка подтверждается асинхронно; `flush` дожидается всех подтверждений.
+pub struct KafkaSink {
+    producer: FutureProducer,
+    topic: String,
+    topics: SinkTables,
+    format: PayloadFormat,
+    partition_by: PartitionBy,
+    pending: Vec<DeliveryFuture>,
+}
+
+impl KafkaSink {
+    /// `brokers` — `bootstrap.servers`, например `localhost:9092`.
+    pub fn new(brokers: &str, topic: impl Into<String>) -> Result<Self> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("message.timeout.ms", "30000")
+            .create()
+            .context("failed to create kafka producer")?;
+        Ok(Self::from_producer(producer, topic))
+    }
+
+    /// Для своей конфигурации producer'а (SASL, idempotence и т.п.).
+    pub fn from_producer(producer: FutureProducer, topic: impl Into<String>) -> Self {
+        Self {
+            producer,
+            topic: topic.into(),
+            topics: SinkTables::default(),
+            format: PayloadFormat::default(),
+            partition_by: PartitionBy::default(),
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn with_topics(mut self, topics: SinkTables) -> Self {
+        self.topics = topics;
+        self
+    }
+
+    pub fn with_format(mut self, format: PayloadFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_partition_by(mut self, partition_by: PartitionBy) -> Self {
+        self.partition_by = partition_by;
+        self
+    }
+
+    async fn publish<M: Message + ?Sized>(&mut self, topic: Topic, message: &M) -> Result<()> {
+        if self.pending.len() >= MAX_IN_FLIGHT {
+            self.await_pending().await?;
+        }
+        let topic = match topic {
+            Topic::Results => &self.topic,
+            Topic::Trades => &self.topics.trades,
+            Topic::PoolEvents => &self.topics.pool_events,
+            Topic::Transfers => &self.topics.transfers,
+        };
+        let payload = message.encode(self.format)?;
+        let key = message.partition_key(self.partition_by);
+        let mut record = FutureRecord::to(topic).payload(&payload);
+        if !key.is_empty() {
+            record = record.key(key);
+        }
+        match self.producer.send_result(record) {
+            Ok(delivery) => {
+                self.pending.push(delivery);
+                Ok(())
+            }
+            // очередь librdkafka полна: ждём, пока она разгрузится
+            Err((KafkaError::MessageProduction(RDKafkaErrorCode::QueueFull), record)) => self
+                .producer
+                .send(record, Timeout::Never)
+                .await
+                .map(|_| ())
+                .map_err(|(err, _)| anyhow!("kafka publish to {topic} failed: {err}")),
+            Err((err, _)) => Err(anyhow!("kafka publish to {topic} failed: {err}")),
+        }
+    }
+
+    async fn await_pending(&mut self) -> Result<()> {
+        for delivery in std::mem::take(&mut self.pending) {
+            match delivery.await {
+                Ok(Ok(_)) => {}
+                Ok(Err((err, _))) => return Err(anyhow!("kafka delivery failed: {err}")),
+                Err(_) => return Err(anyhow!("kafka delivery cancelled")),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl EventSink for KafkaSink {
+    fn on_trade<'a>(&'a mut self, trade: &'a TradeInfo) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.publish(Topic::Trades, trade))
+    }
+
+    fn on_pool_event<'a>(&'a mut self, event: &'a PoolEvent) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.publish(Topic::PoolEvents, event))
+    }
+
+    fn on_transfer<'a>(&'a mut self, transfer: &'a TransferData) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.publish(Topic::Transfers, transfer))
+    }
+
+    fn on_result<'a>(&'a mut self, result: &'a ParseResult) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.publish(Topic::Results, result))
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.await_pending().await?;
+            self.producer
+                .flush(Timeout::After(Duration::from_secs(30)))
+                .context("kafka flush failed")
+        })
+    }
+}
@@ -0,0 +1,323 @@
+//! Запись результатов WS/gRPC стриминга во внешние хранилища.
+//!
+//! Доступен с feature `sink`; реализации — `clickhouse` (HTTP insert, `JSONEachRow`)
+//! и `postgres` (sqlx, `json_populate_recordset`). Обе пишут плоские строки
+//! `TradeRow`/`PoolEventRow`/`TransferRow`: имена колонок совпадают со схемами `arrow`,
+//! таблицы создаёт пользователь.
+//!
+//! Брокерные `kafka`/`nats` публикуют `ParseResult` целиком (JSON или borsh) с ключом
+//! `PartitionBy`. `drain` связывает sink с выходом `TransactionSubscriber`/`GeyserStream`.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::price::BoxFuture;
+use crate::types::{ParseResult, PoolEvent, TradeInfo, TradeType, TransferData};
+
+#[cfg(feature = "clickhouse")]
+pub mod clickhouse;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+#[cfg(feature = "nats")]
+pub mod nats;
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(any(feature = "kafka", feature = "nats"))]
+pub mod publish;
+
+#[cfg(feature = "clickhouse")]
+pub use clickhouse::ClickHouseSink;
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaSink;
+#[cfg(feature = "nats")]
+pub use nats::NatsSink;
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresSink;
+#[cfg(any(feature = "kafka", feature = "nats"))]
+pub use publish::{Message, PartitionBy, PayloadFormat};
+
+/// Приёмник событий. `on_*` могут буферизовать, данные гарантированно записаны
+/// только после `flush`.
+pub trait EventSink: Send {
+    fn on_trade<'a>(&'a mut self, trade: &'a TradeInfo) -> BoxFuture<'a, Result<()>>;
+    fn on_pool_event<'a>(&'a mut self, event: &'a PoolEvent) -> BoxFuture<'a, Result<()>>;
+    fn on_transfer<'a>(&'a mut self, transfer: &'a TransferData) -> BoxFuture<'a, Result<()>>;
+    fn flush(&mut self) -> BoxFuture<'_, Result<()>>;
+
+    /// Все сделки, события пулов и переводы транзакции; `aggregate_trade` не пишется.
+    fn on_result<'a>(&'a mut self, result: &'a ParseResult) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            for trade in &result.trades {
+                self.on_trade(trade).await?;
+            }
+            for event in &result.liquidities {
+                self.on_pool_event(event).await?;
+            }
+            for transfer in &result.transfers {
+                self.on_transfer(transfer).await?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Пишет поток результатов (`TransactionSubscriber::subscribe`) в sink; `flush` — каждые
+/// `flush_every` транзакций и при завершении потока. Канал `GeyserStream::spawn`
+/// оборачивается через `futures::stream::poll_fn(|cx| rx.poll_recv(cx))`.
+#[cfg(feature = "rpc")]
+pub async fn drain<S, R>(results: R, sink: &mut S, flush_every: usize) -> Result<()>
+where
+    S: EventSink + ?Sized,
+    R: futures::Stream<Item = ParseResult>,
+{
+    use futures::StreamExt;
+
+    let mut results = std::pin::pin!(results);
+    let mut unflushed = 0usize;
+    while let Some(result) = results.next().await {
+        sink.on_result(&result).await?;
+        unflushed += 1;
+        if unflushed >= flush_every.max(1) {
+            sink.flush().await?;
+            unflushed = 0;
+        }
+    }
+    sink.flush().await
+}
+
+/// Имена таблиц для реализаций `EventSink`.
+#[derive(Clone, Debug)]
+pub struct SinkTables {
+    pub trades: String,
+    pub pool_events: String,
+    pub transfers: String,
+}
+
+impl Default for SinkTables {
+    fn default() -> Self {
+        Self {
+            trades: "dex_trades".to_string(),
+            pool_events: "dex_pool_events".to_string(),
+            transfers: "dex_transfers".to_string(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct TradeRow<'a> {
+    pub signature: &'a str,
+    pub slot: u64,
+    pub timestamp: u64,
+    pub idx: &'a str,
+    pub trade_type: &'a TradeType,
+    pub user: Option<&'a str>,
+    pub program_id: Option<&'a str>,
+    pub amm: Option<&'a str>,
+    pub route: Option<&'a str>,
+    pub pool: &'a [String],
+    pub input_mint: &'a str,
+    pub input_amount: f64,
+    pub input_amount_raw: &'a str,
+    pub input_decimals: u8,
+    pub output_mint: &'a str,
+    pub output_amount: f64,
+    pub output_amount_raw: &'a str,
+    pub output_decimals: u8,
+    pub fee_mint: Option<&'a str>,
+    pub fee_amount: Option<f64>,
+    pub fee_amount_raw: Option<&'a str>,
+    pub slippage_bps: Option<u64>,
+    pub price_quote_per_base: Option<f64>,
+    pub value_usd: Option<f64>,
+}
+
+impl<'a> From<&'a TradeInfo> for TradeRow<'a> {
+    fn from(t: &'a TradeInfo) -> Self {
+        Self {
+            signature: &t.signature,
+            slot: t.slot,
+            timestamp: t.timestamp,
+            idx: &t.idx,
+            trade_type: &t.trade_type,
+            user: t.user.as_deref(),
+            program_id: t.program_id.as_deref(),
+            amm: t.amm.as_deref(),
+            route: t.route.as_deref(),
+            pool: &t.pool,
+            input_mint: &t.input_token.mint,
+            input_amount: t.input_token.amount,
+            input_amount_raw: &t.input_token.amount_raw,
+            input_decimals: t.input_token.decimals,
+            output_mint: &t.output_token.mint,
+            output_amount: t.output_token.amount,
+            output_amount_raw: &t.output_token.amount_raw,
+            output_decimals: t.output_token.decimals,
+            fee_mint: t.fee.as_ref().map(|fee| fee.mint.as_str()),
+            fee_amount: t.fee.as_ref().map(|fee| fee.amount),
+            fee_amount_raw: t.fee.as_ref().map(|fee| fee.amount_raw.as_str()),
+            slippage_bps: t.slippage_bps,
+            price_quote_per_base: t.price_quote_per_base,
+            value_usd: t.value_usd,
+        }
+    }
+}
+
+/// Первые две ноги события в `token0_*`/`token1_*`, как в `arrow::pool_event_schema`.
+#[derive(Serialize)]
+pub struct PoolEventRow<'a> {
+    pub signature: &'a str,
+    pub slot: u64,
+    pub timestamp: u64,
+    pub idx: &'a str,
+    pub event_type: &'a TradeType,
+    pub user: &'a str,
+    pub program_id: Option<&'a str>,
+    pub amm: Option<&'a str>,
+    pub pool_id: &'a str,
+    pub pool_lp_mint: Option<&'a str>,
+    pub lp_amount: Option<f64>,
+    pub lp_amount_raw: Option<&'a str>,
+    pub token0_mint: Option<&'a str>,
+    pub token0_amount: Option<f64>,
+    pub token0_amount_raw: Option<&'a str>,
+    pub token0_decimals: Option<u8>,
+    pub token1_mint: Option<&'a str>,
+    pub token1_amount: Option<f64>,
+    pub token1_amount_raw: Option<&'a str>,
+    pub token1_decimals: Option<u8>,
+    pub value_usd: Option<f64>,
+}
+
+impl<'a> From<&'a PoolEvent> for PoolEventRow<'a> {
+    fn from(e: &'a PoolEvent) -> Self {
+        let token0 = e.token0();
+        let token1 = e.token1();
+        Self {
+            signature: &e.signature,
+            slot: e.slot,
+            timestamp: e.timestamp,
+            idx: &e.idx,
+            event_type: &e.event_type,
+            user: &e.user,
+            program_id: e.program_id.as_deref(),
+            amm: e.amm.as_deref(),
+            pool_id: &e.pool_id,
+            pool_lp_mint: e.pool_lp_mint.as_deref(),
+            lp_amount: e.lp_amount,
+            lp_amount_raw: e.lp_amount_raw.as_deref(),
+            token0_mint: token0.map(|leg| leg.mint.as_str()),
+            token0_amount: token0.map(|leg| leg.amount),
+            token0_amount_raw: token0.map(|leg| leg.amount_raw.as_str()),
+            token0_decimals: token0.map(|leg| leg.decimals),
+            token1_mint: token1.map(|leg| leg.mint.as_str()),
+            token1_amount: token1.map(|leg| leg.amount),
+            token1_amount_raw: token1.map(|leg| leg.amount_raw.as_str()),
+            token1_decimals: token1.map(|leg| leg.decimals),
+            value_usd: e.value_usd,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct TransferRow<'a> {
+    pub signature: &'a str,
+    pub timestamp: u64,
+    pub idx: &'a str,
+    pub transfer_type: &'a str,
+    pub program_id: &'a str,
+    pub mint: &'a str,
+    pub source: &'a str,
+    pub destination: &'a str,
+    pub authority: Option<&'a str>,
+    pub destination_owner: Option<&'a str>,
+    pub amount_raw: &'a str,
+    pub decimals: u8,
+    pub ui_amount: Option<f64>,
+    pub is_fee: bool,
+}
+
+impl<'a> From<&'a TransferData> for TransferRow<'a> {
+    fn from(t: &'a TransferData) -> Self {
+        Self {
+            signature: &t.signature,
+            timestamp: t.timestamp,
+            idx: &t.idx,
+            transfer_type: &t.transfer_type,
+            program_id: &t.program_id,
+            mint: &t.info.mint,
+            source: &t.info.source,
+            destination: &t.info.destination,
+            authority: t.info.authority.as_deref(),
+            destination_owner: t.info.destination_owner.as_deref(),
+            amount_raw: &t.info.token_amount.amount,
+            decimals: t.info.token_amount.decimals,
+            ui_amount: t.info.token_amount.ui_amount,
+            is_fee: t.is_fee,
+        }
+    }
+}
+
+/// Буфер строк, уже сериализованных в JSON, по таблицам.
+#[cfg_attr(
+    not(any(feature = "clickhouse", feature = "postgres")),
+    allow(dead_code)
+)]
+#[derive(Debug, Default)]
+pub(crate) struct JsonRows {
+    pub trades: Vec<String>,
+    pub pool_events: Vec<String>,
+    pub transfers: Vec<String>,
+}
+
+#[cfg_attr(
+    not(any(feature = "clickhouse", feature = "postgres")),
+    allow(dead_code)
+)]
+impl JsonRows {
+    pub fn push_trade(&mut self, trade: &TradeInfo) -> Result<()> {
+        self.trades
+            .push(serde_json::to_string(&TradeRow::from(trade))?);
+        Ok(())
+    }
+
+    pub fn push_pool_event(&mut self, event: &PoolEvent) -> Result<()> {
+        self.pool_events
+            .push(serde_json::to_string(&PoolEventRow::from(event))?);
+        Ok(())
+    }
+
+    pub fn push_transfer(&mut self, transfer: &TransferData) -> Result<()> {
+        self.transfers
+            .push(serde_json::to_string(&TransferRow::from(transfer))?);
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.trades.len() + self.pool_events.len() + self.transfers.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_serialize_flat_with_json_enum_names() {
+        let trade = TradeInfo {
+            trade_type: TradeType::Sell,
+            pool: vec!["pool".to_string()],
+            slot: 7,
+            ..Default::default()
+        };
+        let mut rows = JsonRows::default();
+        rows.push_trade(&trade).unwrap();
+        rows.push_pool_event(&PoolEvent::default()).unwrap();
+        assert_eq!(rows.len(), 2);
+
+        let row: serde_json::Value = serde_json::from_str(&rows.trades[0]).unwrap();
+        assert_eq!(row["trade_type"], "SELL");
+        assert_eq!(row["pool"], serde_json::json!(["pool"]));
+        assert_eq!(row["slot"], 7);
+        assert!(row["fee_mint"].is_null());
+    }
+}
@@ -0,0 +1,520 @@
+use std::fmt;
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use tokio::io::{AsyncReadExt, AsyncWriteExt, BufWriter};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpStream;
+use url::Url;
+
+use crate::price::BoxFuture;
+use crate::types::{ParseResult, PoolEvent, TradeInfo, TransferData};
+
+use super::publish::{Message, PartitionBy, PayloadFormat};
+use super::{EventSink, SinkTables};
+
+#[derive(Clone, Copy)]
+enum Subject {
+    Results,
+    Trades,
+    PoolEvents,
+    Transfers,
+}
+
+/// `-ERR` от сервера: авторизация, права, неверный subject. Это ошибка конфигурации,
+/// а не обрыв связи, поэтому без повторов.
+#[derive(Debug)]
+struct ServerError(String);
+
+impl fmt::Display for ServerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "nats error: {}", self.0)
+    }
+}
+
+impl std::error::Error for ServerError {}
+
+/// NATS publisher на core-протоколе поверх TCP (`PUB`), без TLS и JetStream.
+///
+/// Subject — `<subject>.<key>`, где key — `PartitionBy` (подписчик фильтрует по
+/// `dex.results.*` или конкретному mint'у); без ключа — просто `<subject>`.
+/// `on_result` публикует `ParseResult` целиком в `subject`, `on_*` — отдельные события
+/// в subjects из `SinkTables`. `flush` отправляет буфер и ждёт `PONG`: после него
+/// сервер принял все сообщения.
+///
+/// Сообщения с последнего `flush` хранятся до его `PONG`. Обрыв соединения не ошибка
+/// для `on_*`: `flush` переподключается (`with_reconnect`) и отправляет их заново, так
+/// что доставка at-least-once — часть сообщений может прийти дважды.
+pub struct NatsSink {
+    url: Url,
+    conn: Option<Connection>,
+    /// PUB-кадры с последнего подтверждённого `flush`.
+    unacked: Vec<u8>,
+    /// Сколько байт `unacked` уже записано в текущее соединение.
+    written: usize,
+    max_payload: usize,
+    max_buffered: usize,
+    reconnect_attempts: u32,
+    reconnect_delay: Duration,
+    subject: String,
+    subjects: SinkTables,
+    format: PayloadFormat,
+    partition_by: PartitionBy,
+}
+
+impl NatsSink {
+    /// `url` — `nats://[user:pass@]host:4222` или `nats://token@host:4222`.
+    pub async fn connect(url: &str, subject: impl Into<String>) -> Result<Self> {
+        let url = Url::parse(url).with_context(|| format!("invalid nats url {url}"))?;
+        let conn = Connection::open(&url).await?;
+        Ok(Self {
+            url,
+            max_payload: conn.max_payload,
+            conn: Some(conn),
+            unacked: Vec::new(),
+            written: 0,
+            max_buffered: 64 * 1024 * 1024,
+            reconnect_attempts: 5,
+            reconnect_delay: Duration::from_millis(500),
+            subject: subject.into(),
+            subjects: SinkTables::default(),
+            format: PayloadFormat::default(),
+            partition_by: PartitionBy::default(),
+        })
+    }
+
+    pub fn with_subjects(mut self, subjects: SinkTables) -> Self {
+        self.subjects = subjects;
+        self
+    }
+
+    pub fn with_format(mut self, format: PayloadFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn with_partition_by(mut self, partition_by: PartitionBy) -> Self {
+        self.partition_by = partition_by;
+        self
+    }
+
+    /// Попытки переподключения в `flush`; задержка удваивается с каждой.
+    pub fn with_reconnect(mut self, attempts: u32, delay: Duration) -> Self {
+        self.reconnect_attempts = attempts;
+        self.reconnect_delay = delay;
+        self
+    }
+
+    /// Предел неподтверждённых байт: при недоступном сервере `on_*` падают, а не
+    /// копят память без конца.
+    pub fn with_max_buffered(mut self, bytes: usize) -> Self {
+        self.max_buffered = bytes;
+        self
+    }
+
+    async fn publish<M: Message + ?Sized>(&mut self, subject: Subject, message: &M) -> Result<()> {
+        let payload = message.encode(self.format)?;
+        if payload.len() > self.max_payload {
+            bail!(
+                "nats message of {} bytes exceeds max_payload {}",
+                payload.len(),
+                self.max_payload
+            );
+        }
+        let base = match subject {
+            Subject::Results => &self.subject,
+            Subject::Trades => &self.subjects.trades,
+            Subject::PoolEvents => &self.subjects.pool_events,
+            Subject::Transfers => &self.subjects.transfers,
+        };
+        let key = message.partition_key(self.partition_by);
+        let header = if key.is_empty() {
+            format!("PUB {base} {}\r\n", payload.len())
+        } else {
+            format!("PUB {base}.{key} {}\r\n", payload.len())
+        };
+        let frame_len = header.len() + payload.len() + 2;
+        if self.unacked.len() + frame_len > self.max_buffered {
+            bail!(
+                "nats buffer of {} unacknowledged bytes is full",
+                self.unacked.len()
+            );
+        }
+        self.unacked.extend_from_slice(header.as_bytes());
+        self.unacked.extend_from_slice(&payload);
+        self.unacked.extend_from_slice(b"\r\n");
+        self.send_unacked().await
+    }
+
+    /// Дописывает буфер в текущее соединение; обрыв оставляет его до `flush`.
+    async fn send_unacked(&mut self) -> Result<()> {
+        let Some(conn) = self.conn.as_mut() else {
+            return Ok(());
+        };
+        match conn.write(&self.unacked[self.written..]).await {
+            Ok(()) => {
+                self.written = self.unacked.len();
+                Ok(())
+            }
+            Err(err) if err.is::<ServerError>() => {
+                self.drop_unacked();
+                Err(err)
+            }
+            Err(err) => {
+                tracing::warn!("nats connection lost: {err:#}");
+                self.disconnect();
+                Ok(())
+            }
+        }
+    }
+
+    async fn flush_unacked(&mut self) -> Result<()> {
+        let mut delay = self.reconnect_delay;
+        let mut attempt = 0;
+        loop {
+            match self.try_flush().await {
+                Ok(()) => {
+                    self.unacked.clear();
+                    self.written = 0;
+                    return Ok(());
+                }
+                // повтор отправил бы тот же отвергнутый кадр
+                Err(err) if err.is::<ServerError>() => {
+                    self.drop_unacked();
+                    return Err(err);
+                }
+                Err(err) if attempt >= self.reconnect_attempts => {
+                    self.disconnect();
+                    return Err(err);
+                }
+                Err(err) => {
+                    tracing::warn!("nats flush failed: {err:#}, reconnecting in {delay:?}");
+                    self.disconnect();
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+
+    async fn try_flush(&mut self) -> Result<()> {
+        if self.conn.is_none() {
+            let conn = Connection::open(&self.url).await?;
+            self.max_payload = conn.max_payload;
+            self.conn = Some(conn);
+        }
+        let conn = self.conn.as_mut().context("nats connection missing")?;
+        conn.write(&self.unacked[self.written..]).await?;
+        self.written = self.unacked.len();
+        conn.ping().await
+    }
+
+    /// Следующий `flush` откроет новое соединение и отправит весь буфер.
+    fn disconnect(&mut self) {
+        self.conn = None;
+        self.written = 0;
+    }
+
+    fn drop_unacked(&mut self) {
+        tracing::warn!(
+            "dropping {} unacknowledged nats bytes after server error",
+            self.unacked.len()
+        );
+        self.unacked.clear();
+        self.disconnect();
+    }
+}
+
+/// Одно TCP-соединение с сервером после `CONNECT`.
+struct Connection {
+    reader: OwnedReadHalf,
+    writer: BufWriter<OwnedWriteHalf>,
+    /// Непрочитанный хвост ответов сервера (неполная строка).
+    inbox: Vec<u8>,
+    /// Сервер прислал PING, PONG ещё не отправлен.
+    pong_due: bool,
+    max_payload: usize,
+}
+
+impl Connection {
+    async fn open(url: &Url) -> Result<Self> {
+        let host = url.host_str().context("nats url without host")?;
+        let port = url.port().unwrap_or(4222);
+        let stream = TcpStream::connect((host, port))
+            .await
+            .with_context(|| format!("failed to connect to nats {host}:{port}"))?;
+        stream.set_nodelay(true)?;
+        let (reader, writer) = stream.into_split();
+        let mut conn = Self {
+            reader,
+            writer: BufWriter::new(writer),
+            inbox: Vec::new(),
+            pong_due: false,
+            max_payload: 1024 * 1024,
+        };
+
+        let info = conn.read_line().await?;
+        let Some(info) = info.strip_prefix("INFO ") else {
+            bail!("unexpected nats greeting: {info}");
+        };
+        let info: Value = serde_json::from_str(info).context("invalid nats INFO")?;
+        if info.get("tls_required").and_then(Value::as_bool) == Some(true) {
+            bail!("nats server requires TLS, which NatsSink does not support");
+        }
+        if let Some(max_payload) = info.get("max_payload").and_then(Value::as_u64) {
+            conn.max_payload = max_payload as usize;
+        }
+
+        let mut connect = json!({
+            "verbose": false,
+            "pedantic": false,
+            "lang": "rust",
+            "name": "solana-dex-parser",
+        });
+        match (url.username(), url.password()) {
+            ("", _) => {}
+            (token, None) => connect["auth_token"] = token.into(),
+            (user, Some(pass)) => {
+                connect["user"] = user.into();
+                connect["pass"] = pass.into();
+            }
+        }
+        conn.writer
+            .write_all(format!("CONNECT {connect}\r\n").as_bytes())
+            .await?;
+        // PONG подтверждает, что CONNECT (и авторизация) приняты
+        conn.ping().await?;
+        Ok(conn)
+    }
+
+    /// Пишет кадры, предварительно ответив на PING сервера, иначе тот рвёт соединение.
+    async fn write(&mut self, frames: &[u8]) -> Result<()> {
+        self.poll()?;
+        self.answer_ping().await?;
+        self.writer.write_all(frames).await?;
+        Ok(())
+    }
+
+    /// Разбирает уже пришедшие строки сервера, не блокируясь.
+    fn poll(&mut self) -> Result<()> {
+        let mut buf = [0u8; 4096];
+        loop {
+            match self.reader.try_read(&mut buf) {
+                Ok(0) => bail!("nats connection closed"),
+                Ok(n) => self.inbox.extend_from_slice(&buf[..n]),
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(err) => return Err(err.into()),
+            }
+        }
+        while let Some(line) = self.take_line() {
+            self.handle_line(&line)?;
+        }
+        Ok(())
+    }
+
+    fn take_line(&mut self) -> Option<String> {
+        let end = self.inbox.windows(2).position(|window| window == b"\r\n")?;
+        let line = String::from_utf8_lossy(&self.inbox[..end]).into_owned();
+        self.inbox.drain(..end + 2);
+        Some(line)
+    }
+
+    async fn read_line(&mut self) -> Result<String> {
+        let mut buf = [0u8; 4096];
+        loop {
+            if let Some(line) = self.take_line() {
+                return Ok(line);
+            }
+            let n = self.reader.read(&mut buf).await?;
+            if n == 0 {
+                bail!("nats connection closed");
+            }
+            self.inbox.extend_from_slice(&buf[..n]);
+        }
+    }
+
+    /// `true` — строка была `PONG`.
+    fn handle_line(&mut self, line: &str) -> Result<bool> {
+        if line == "PING" {
+            self.pong_due = true;
+        }
+        if let Some(err) = line.strip_prefix("-ERR") {
+            return Err(ServerError(err.trim().to_string()).into());
+        }
+        Ok(line == "PONG")
+    }
+
+    async fn answer_ping(&mut self) -> Result<()> {
+        if std::mem::take(&mut self.pong_due) {
+            self.writer.write_all(b"PONG\r\n").await?;
+            self.writer.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn ping(&mut self) -> Result<()> {
+        self.writer.write_all(b"PING\r\n").await?;
+        self.writer.flush().await?;
+        loop {
+            let line = self.read_line().await?;
+            let pong = self.handle_line(&line)?;
+            self.answer_ping().await?;
+            if pong {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl EventSink for NatsSink {
+    fn on_trade<'a>(&'a mut self, trade: &'a TradeInfo) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.publish(Subject::Trades, trade))
+    }
+
+    fn on_pool_event<'a>(&'a mut self, event: &'a PoolEvent) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.publish(Subject::PoolEvents, event))
+    }
+
+    fn on_transfer<'a>(&'a mut self, transfer: &'a TransferData) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.publish(Subject::Transfers, transfer))
+    }
+
+    fn on_result<'a>(&'a mut self, result: &'a ParseResult) -> BoxFuture<'a, Result<()>> {
+        Box::pin(self.publish(Subject::Results, result))
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.flush_unacked())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+    use tokio::net::TcpListener;
+
+    /// Серверная сторона одного соединения mock-сервера.
+    struct ServerConn {
+        reader: BufReader<OwnedReadHalf>,
+        writer: OwnedWriteHalf,
+    }
+
+    impl ServerConn {
+        /// Принимает клиента: INFO, затем CONNECT и PING, на который отвечает `reply`.
+        async fn accept(listener: &TcpListener, reply: &str) -> Self {
+            let (socket, _) = listener.accept().await.unwrap();
+            let (reader, writer) = socket.into_split();
+            let mut conn = Self {
+                reader: BufReader::new(reader),
+                writer,
+            };
+            conn.send(r#"INFO {"server_id":"mock","max_payload":1048576}"#)
+                .await;
+            assert!(conn.line().await.starts_with("CONNECT {"));
+            assert_eq!(conn.line().await, "PING");
+            conn.send(reply).await;
+            conn
+        }
+
+        async fn line(&mut self) -> String {
+            let mut line = String::new();
+            self.reader.read_line(&mut line).await.unwrap();
+            line.trim_end().to_string()
+        }
+
+        async fn send(&mut self, line: &str) {
+            self.writer
+                .write_all(format!("{line}\r\n").as_bytes())
+                .await
+                .unwrap();
+        }
+
+        /// Читает `PUB <subject> <len>` с телом.
+        async fn publication(&mut self) -> (String, Value) {
+            let line = self.line().await;
+            let mut parts = line.split(' ');
+            assert_eq!(parts.next(), Some("PUB"), "{line}");
+            let subject = parts.next().unwrap().to_string();
+            let len: usize = parts.next().unwrap().parse().unwrap();
+            let mut payload = vec![0u8; len + 2];
+            self.reader.read_exact(&mut payload).await.unwrap();
+            (subject, serde_json::from_slice(&payload[..len]).unwrap())
+        }
+    }
+
+    fn result(signature: &str) -> ParseResult {
+        ParseResult {
+            signature: signature.to_string(),
+            ..Default::default()
+        }
+    }
+
+    async fn listen() -> (TcpListener, String) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("nats://{}", listener.local_addr().unwrap());
+        (listener, url)
+    }
+
+    #[tokio::test]
+    async fn publishes_results_and_confirms_flush_with_pong() {
+        let (listener, url) = listen().await;
+        let server = tokio::spawn(async move {
+            let mut conn = ServerConn::accept(&listener, "PONG").await;
+            let publication = conn.publication().await;
+            assert_eq!(conn.line().await, "PING");
+            conn.send("PONG").await;
+            publication
+        });
+
+        let mut sink = NatsSink::connect(&url, "dex.results").await.unwrap();
+        sink.on_result(&result("sig-1")).await.unwrap();
+        sink.flush().await.unwrap();
+
+        let (subject, payload) = server.await.unwrap();
+        assert_eq!(subject, "dex.results.sig-1");
+        assert_eq!(payload["signature"], "sig-1");
+    }
+
+    #[tokio::test]
+    async fn resends_unacknowledged_messages_after_reconnect() {
+        let (listener, url) = listen().await;
+        let server = tokio::spawn(async move {
+            // первое соединение обрывается до PONG на flush
+            let mut first = ServerConn::accept(&listener, "PONG").await;
+            first.publication().await;
+            drop(first);
+
+            let mut second = ServerConn::accept(&listener, "PONG").await;
+            let publication = second.publication().await;
+            assert_eq!(second.line().await, "PING");
+            second.send("PONG").await;
+            publication
+        });
+
+        let mut sink = NatsSink::connect(&url, "dex.results")
+            .await
+            .unwrap()
+            .with_reconnect(3, Duration::from_millis(10));
+        sink.on_result(&result("sig-1")).await.unwrap();
+        sink.flush().await.unwrap();
+
+        let (subject, _) = server.await.unwrap();
+        assert_eq!(subject, "dex.results.sig-1");
+        assert!(sink.unacked.is_empty());
+    }
+
+    #[tokio::test]
+    async fn server_error_fails_without_retry() {
+        let (listener, url) = listen().await;
+        tokio::spawn(async move {
+            ServerConn::accept(&listener, "-ERR 'Authorization Violation'").await;
+        });
+
+        let err = NatsSink::connect(&url, "dex.results").await.err().unwrap();
+        assert!(err.is::<ServerError>(), "{err:#}");
+        assert!(format!("{err}").contains("Authorization Violation"));
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::{Context, Result};
+use sqlx::postgres::PgPool;
+
+use crate::price::BoxFuture;
+use crate::types::{PoolEvent, TradeInfo, TransferData};
+
+use super::{EventSink, JsonRows, SinkTables};
+
+/// `EventSink` поверх Postgres (sqlx).
+///
+/// Пачка строк уходит одним запросом
+/// `INSERT INTO t SELECT * FROM json_populate_recordset(NULL::t, $1::json)`:
+/// колонки таблицы сопоставляются с полями `TradeRow`/`PoolEventRow`/`TransferRow`
+/// по имени, колонки таблицы без пары в строке получают NULL.
+pub struct PostgresSink {
+    pool: PgPool,
+    tables: SinkTables,
+    batch_size: usize,
+    rows: JsonRows,
+}
+
+impl PostgresSink {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            tables: SinkTables::default(),
+            batch_size: 1_000,
+            rows: JsonRows::default(),
+        }
+    }
+
+    pub async fn connect(url: &str) -> Result<Self> {
+        let pool = PgPool::connect(url)
+            .await
+            .context("failed to connect to postgres")?;
+        Ok(Self::new(pool))
+    }
+
+    pub fn with_tables(mut self, tables: SinkTables) -> Self {
+        self.tables = tables;
+        self
+    }
+
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    async fn insert(&self, table: &str, rows: &[String]) -> Result<()> {
+        if rows.is_empty() {
+            return Ok(());
+        }
+        let query = format!(
+            "INSERT INTO {table} SELECT * FROM json_populate_recordset(NULL::{table}, $1::json)"
+        );
+        sqlx::query(&query)
+            .bind(format!("[{}]", rows.join(",")))
+            .execute(&self.pool)
+            .await
+            .with_context(|| format!("postgres insert into {table}"))?;
+        Ok(())
+    }
+
+    async fn flush_if_full(&mut self) -> Result<()> {
+        if self.rows.len() >= self.batch_size {
+            self.flush_rows().await?;
+        }
+        Ok(())
+    }
+
+    /// Как у `ClickHouseSink`: буфер таблицы чистится только после успешной вставки.
+    async fn flush_rows(&mut self) -> Result<()> {
+        self.insert(&self.tables.trades, &self.rows.trades).await?;
+        self.rows.trades.clear();
+        self.insert(&self.tables.pool_events, &self.rows.pool_events)
+            .await?;
+        self.rows.pool_events.clear();
+        self.insert(&self.tables.transfers, &self.rows.transfers)
+            .await?;
+        self.rows.transfers.clear();
+        Ok(())
+    }
+}
+
+impl EventSink for PostgresSink {
+    fn on_trade<'a>(&'a mut self, trade: &'a TradeInfo) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.rows.push_trade(trade)?;
+            self.flush_if_full().await
+        })
+    }
+
+    fn on_pool_event<'a>(&'a mut self, event: &'a PoolEvent) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.rows.push_pool_event(event)?;
+            self.flush_if_full().await
+        })
+    }
+
+    fn on_transfer<'a>(&'a mut self, transfer: &'a TransferData) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            self.rows.push_transfer(transfer)?;
+            self.flush_if_full().await
+        })
+    }
+
+    fn flush(&mut self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(self.flush_rows())
+    }
+}
@@ -0,0 +1,147 @@
+//! Общее для брокерных sink'ов (`kafka`, `nats`): формат сообщения и ключ партиционирования.
+
+use anyhow::Result;
+#[cfg(feature = "borsh")]
+use borsh::BorshSerialize;
+use serde::Serialize;
+
+use crate::types::{ParseResult, PoolEvent, TradeInfo, TransferData};
+
+/// Кодировка тела сообщения.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PayloadFormat {
+    /// camelCase JSON, как у `ParseResult` в остальном API.
+    #[default]
+    Json,
+    /// Компактный бинарный формат; NaN в f64 полях borsh не сериализует.
+    #[cfg(feature = "borsh")]
+    Borsh,
+}
+
+/// Чем ключуются сообщения: Kafka-ключ (партиция) или суффикс NATS subject.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PartitionBy {
+    #[default]
+    Signature,
+    /// `program_id` сделки/события; у `ParseResult` — первой сделки или события пула.
+    ProgramId,
+    /// Base mint сделки, token0 события пула, mint перевода.
+    Mint,
+}
+
+/// Сообщение для брокера.
+pub trait Message {
+    /// Пустая строка, если ключа нет (например, транзакция без сделок).
+    fn partition_key(&self, by: PartitionBy) -> &str;
+    fn encode(&self, format: PayloadFormat) -> Result<Vec<u8>>;
+}
+
+#[cfg(feature = "borsh")]
+fn encode<T: Serialize + BorshSerialize>(value: &T, format: PayloadFormat) -> Result<Vec<u8>> {
+    Ok(match format {
+        PayloadFormat::Json => serde_json::to_vec(value)?,
+        PayloadFormat::Borsh => borsh::to_vec(value)?,
+    })
+}
+
+#[cfg(not(feature = "borsh"))]
+fn encode<T: Serialize>(value: &T, format: PayloadFormat) -> Result<Vec<u8>> {
+    match format {
+        PayloadFormat::Json => Ok(serde_json::to_vec(value)?),
+    }
+}
+
+impl Message for TradeInfo {
+    fn partition_key(&self, by: PartitionBy) -> &str {
+        match by {
+            PartitionBy::Signature => &self.signature,
+            PartitionBy::ProgramId => self.program_id.as_deref().unwrap_or_default(),
+            PartitionBy::Mint => self.base_mint(),
+        }
+    }
+
+    fn encode(&self, format: PayloadFormat) -> Result<Vec<u8>> {
+        encode(self, format)
+    }
+}
+
+impl Message for PoolEvent {
+    fn partition_key(&self, by: PartitionBy) -> &str {
+        match by {
+            PartitionBy::Signature => &self.signature,
+            PartitionBy::ProgramId => self.program_id.as_deref().unwrap_or_default(),
+            PartitionBy::Mint => self.token0().map_or("", |leg| leg.mint.as_str()),
+        }
+    }
+
+    fn encode(&self, format: PayloadFormat) -> Result<Vec<u8>> {
+        encode(self, format)
+    }
+}
+
+impl Message for TransferData {
+    fn partition_key(&self, by: PartitionBy) -> &str {
+        match by {
+            PartitionBy::Signature => &self.signature,
+            PartitionBy::ProgramId => &self.program_id,
+            PartitionBy::Mint => &self.info.mint,
+        }
+    }
+
+    fn encode(&self, format: PayloadFormat) -> Result<Vec<u8>> {
+        encode(self, format)
+    }
+}
+
+impl Message for ParseResult {
+    fn partition_key(&self, by: PartitionBy) -> &str {
+        if by == PartitionBy::Signature {
+            return &self.signature;
+        }
+        if let Some(trade) = self.aggregate_trade.as_ref().or(self.trades.first()) {
+            return trade.partition_key(by);
+        }
+        self.liquidities
+            .first()
+            .map_or("", |event| event.partition_key(by))
+    }
+
+    fn encode(&self, format: PayloadFormat) -> Result<Vec<u8>> {
+        encode(self, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{TokenInfo, TradeType};
+
+    #[test]
+    fn result_key_follows_first_trade() {
+        let trade = TradeInfo {
+            trade_type: TradeType::Buy,
+            program_id: Some("pump".to_string()),
+            input_token: TokenInfo {
+                mint: "So11111111111111111111111111111111111111112".to_string(),
+                ..Default::default()
+            },
+            output_token: TokenInfo {
+                mint: "meme".to_string(),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let result = ParseResult {
+            signature: "sig".to_string(),
+            trades: vec![trade],
+            ..ParseResult::new()
+        };
+        assert_eq!(result.partition_key(PartitionBy::Signature), "sig");
+        assert_eq!(result.partition_key(PartitionBy::ProgramId), "pump");
+        assert_eq!(result.partition_key(PartitionBy::Mint), "meme");
+
+        let body = result.encode(PayloadFormat::Json).unwrap();
+        let back: ParseResult = serde_json::from_slice(&body).unwrap();
+        assert_eq!(back.signature, "sig");
+    }
+}
@@ -0,0 +1,8 @@
+//! Состояние DEX по потоку `ParseResult`: резервы, LP supply и объём по пулам;
+//! разобранные аккаунты пулов для параметров, которых нет в транзакциях.
+
+pub mod pool_tracker;
+pub mod protocol_accounts;
+
+pub use pool_tracker::{PoolState, PoolTracker};
+pub use protocol_accounts::ProtocolAccountStore;
@@ -0,0 +1,406 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::protocols::pumpfun::util::convert_to_ui_amount;
+use crate::types::{
+    LegDirection, ParseResult, PoolEvent, TokenAmount, TradeInfo, TradeType, TransactionStatus,
+};
+
+/// Сколько хранить объём по умолчанию: окна запросов длиннее не имеют смысла.
+const DEFAULT_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Текущее состояние пула по всем увиденным событиям.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PoolState {
+    pub pool_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub program_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amm: Option<String>,
+    /// Резервы по mint; после свапа берутся из `TradeInfo::pool_reserves`, иначе
+    /// сдвигаются на суммы событий.
+    pub reserves: BTreeMap<String, TokenAmount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lp_mint: Option<String>,
+    /// Raw LP supply; известен только если поток начался с create пула.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lp_supply_raw: Option<u128>,
+    pub trade_count: u64,
+    pub last_slot: u64,
+    pub last_timestamp: u64,
+    /// `(timestamp, объём)` сделок в пределах retention трекера.
+    #[serde(skip)]
+    volume: VecDeque<(u64, f64)>,
+}
+
+impl PoolState {
+    pub fn new(pool_id: &str) -> Self {
+        Self {
+            pool_id: pool_id.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn reserve(&self, mint: &str) -> Option<&TokenAmount> {
+        self.reserves.get(mint)
+    }
+
+    /// Объём сделок с `timestamp >= since`.
+    pub fn volume_since(&self, since: u64) -> f64 {
+        self.volume
+            .iter()
+            .rev()
+            .take_while(|(timestamp, _)| *timestamp >= since)
+            .map(|(_, volume)| volume)
+            .sum()
+    }
+
+    fn touch(
+        &mut self,
+        program_id: &Option<String>,
+        amm: &Option<String>,
+        slot: u64,
+        timestamp: u64,
+    ) {
+        if program_id.is_some() {
+            self.program_id.clone_from(program_id);
+        }
+        if amm.is_some() {
+            self.amm.clone_from(amm);
+        }
+        self.last_slot = self.last_slot.max(slot);
+        self.last_timestamp = self.last_timestamp.max(timestamp);
+    }
+
+    fn set_reserve(&mut self, mint: &str, amount: TokenAmount) {
+        self.reserves.insert(mint.to_string(), amount);
+    }
+
+    /// Сдвиг известного резерва; неизвестный при притоке заводится с нуля.
+    fn shift_reserve(&mut self, mint: &str, amount_raw: &str, decimals: u8, inflow: bool) {
+        let delta = amount_raw.parse::<u128>().unwrap_or(0);
+        let current = match self.reserves.get(mint) {
+            Some(reserve) => reserve.amount.parse::<u128>().unwrap_or(0),
+            None if inflow => 0,
+            None => return,
+        };
+        let raw = if inflow {
+            current.saturating_add(delta)
+        } else {
+            current.saturating_sub(delta)
+        };
+        self.set_reserve(
+            mint,
+            TokenAmount::new(
+                raw.to_string(),
+                decimals,
+                Some(convert_to_ui_amount(raw, decimals)),
+            ),
+        );
+    }
+}
+
+/// Резервы, LP supply и объём по пулам из потока `ParseResult` (в порядке подачи).
+///
+/// Объём сделки — `value_usd`, если подключён `PriceOracle`, иначе сумма в quote-токене;
+/// окна считаются по `timestamp` транзакций, а не по wall clock.
+#[derive(Clone, Debug)]
+pub struct PoolTracker {
+    pools: HashMap<String, PoolState>,
+    retention: Duration,
+    latest_timestamp: u64,
+}
+
+impl Default for PoolTracker {
+    fn default() -> Self {
+        Self {
+            pools: HashMap::new(),
+            retention: DEFAULT_RETENTION,
+            latest_timestamp: 0,
+        }
+    }
+}
+
+impl PoolTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Сколько истории объёма держать для `top_pools_by_volume`.
+    pub fn with_retention(mut self, retention: Duration) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    pub fn ingest(&mut self, result: &ParseResult) {
+        if !result.state || result.tx_status == TransactionStatus::Failed {
+            return;
+        }
+        // liquidities и trades одной транзакции — в порядке инструкций
+        let mut events: Vec<(u16, Option<u16>, Event)> = result
+            .liquidities
+            .iter()
+            .map(|pool| (pool.instruction_index, pool.inner_index, Event::Pool(pool)))
+            .chain(result.trades.iter().map(|trade| {
+                (
+                    trade.instruction_index,
+                    trade.inner_index,
+                    Event::Trade(trade),
+                )
+            }))
+            .collect();
+        events.sort_by_key(|(outer, inner, _)| (*outer, *inner));
+        for (_, _, event) in events {
+            match event {
+                Event::Pool(pool) => self.apply_pool_event(pool),
+                Event::Trade(trade) => self.apply_trade(trade),
+            }
+        }
+        self.latest_timestamp = self.latest_timestamp.max(result.timestamp);
+        self.prune();
+    }
+
+    /// Начальное состояние пула до потока (снимок аккаунтов, `rpc::discover_pools`):
+    /// резервы и LP заменяют известные, счётчики сделок и объём остаются.
+    pub fn seed(&mut self, seed: PoolState) {
+        let pool = self
+            .pools
+            .entry(seed.pool_id.clone())
+            .or_insert_with(|| PoolState::new(&seed.pool_id));
+        pool.touch(&seed.program_id, &seed.amm, seed.last_slot, seed.last_timestamp);
+        pool.reserves.extend(seed.reserves);
+        if seed.lp_mint.is_some() {
+            pool.lp_mint = seed.lp_mint;
+        }
+        if seed.lp_supply_raw.is_some() {
+            pool.lp_supply_raw = seed.lp_supply_raw;
+        }
+    }
+
+    pub fn ingest_all<'a>(&mut self, results: impl IntoIterator<Item = &'a ParseResult>) {
+        for result in results {
+            self.ingest(result);
+        }
+    }
+
+    pub fn get_pool(&self, pool_id: &str) -> Option<&PoolState> {
+        self.pools.get(pool_id)
+    }
+
+    pub fn pools(&self) -> impl Iterator<Item = &PoolState> {
+        self.pools.values()
+    }
+
+    /// Пулы с ненулевым объёмом за `window` до последней увиденной транзакции,
+    /// по убыванию объёма.
+    pub fn top_pools_by_volume(&self, window: Duration) -> Vec<(&PoolState, f64)> {
+        let since = self.latest_timestamp.saturating_sub(window.as_secs());
+        let mut pools: Vec<(&PoolState, f64)> = self
+            .pools
+            .values()
+            .map(|pool| (pool, pool.volume_since(since)))
+            .filter(|(_, volume)| *volume > 0.0)
+            .collect();
+        pools.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap_or(Ordering::Equal)
+                .then_with(|| a.0.pool_id.cmp(&b.0.pool_id))
+        });
+        pools
+    }
+
+    fn apply_trade(&mut self, trade: &TradeInfo) {
+        let Some(pool_id) = trade.pool.first() else {
+            return;
+        };
+        let pool = self
+            .pools
+            .entry(pool_id.clone())
+            .or_insert_with(|| PoolState::new(pool_id));
+        pool.touch(&trade.program_id, &trade.amm, trade.slot, trade.timestamp);
+        pool.trade_count += 1;
+
+        let (input, output) = (&trade.input_token, &trade.output_token);
+        match &trade.pool_reserves {
+            Some((input_reserve, output_reserve)) => {
+                pool.set_reserve(&input.mint, input_reserve.clone());
+                pool.set_reserve(&output.mint, output_reserve.clone());
+            }
+            None => {
+                pool.shift_reserve(&input.mint, &input.amount_raw, input.decimals, true);
+                pool.shift_reserve(&output.mint, &output.amount_raw, output.decimals, false);
+            }
+        }
+
+        let quote = trade.quote_mint();
+        let quote_amount = if input.mint == quote {
+            input.amount
+        } else {
+            output.amount
+        };
+        pool.volume
+            .push_back((trade.timestamp, trade.value_usd.unwrap_or(quote_amount)));
+    }
+
+    fn apply_pool_event(&mut self, event: &PoolEvent) {
+        if event.pool_id.is_empty() {
+            return;
+        }
+        let pool = self
+            .pools
+            .entry(event.pool_id.clone())
+            .or_insert_with(|| PoolState::new(&event.pool_id));
+        pool.touch(&event.program_id, &event.amm, event.slot, event.timestamp);
+        if event.pool_lp_mint.is_some() {
+            pool.lp_mint.clone_from(&event.pool_lp_mint);
+        }
+
+        if event.event_type == TradeType::Create {
+            pool.reserves.clear();
+        }
+        for leg in &event.tokens {
+            let inflow = leg.direction == LegDirection::In;
+            pool.shift_reserve(&leg.mint, &leg.amount_raw, leg.decimals, inflow);
+        }
+
+        let lp = event
+            .lp_amount_raw
+            .as_deref()
+            .and_then(|raw| raw.parse::<u128>().ok());
+        pool.lp_supply_raw = match (&event.event_type, pool.lp_supply_raw, lp) {
+            (TradeType::Create, _, lp) => lp,
+            (TradeType::Add, Some(supply), Some(lp)) => Some(supply.saturating_add(lp)),
+            (TradeType::Remove, Some(supply), Some(lp)) => Some(supply.saturating_sub(lp)),
+            (_, supply, _) => supply,
+        };
+    }
+
+    fn prune(&mut self) {
+        let cutoff = self
+            .latest_timestamp
+            .saturating_sub(self.retention.as_secs());
+        for pool in self.pools.values_mut() {
+            while pool
+                .volume
+                .front()
+                .is_some_and(|(timestamp, _)| *timestamp < cutoff)
+            {
+                pool.volume.pop_front();
+            }
+        }
+    }
+}
+
+enum Event<'a> {
+    Pool(&'a PoolEvent),
+    Trade(&'a TradeInfo),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{TokenInfo, TokenLeg};
+
+    fn token(mint: &str, raw: u64) -> TokenInfo {
+        TokenInfo {
+            mint: mint.to_string(),
+            amount: convert_to_ui_amount(raw, 6),
+            amount_raw: raw.to_string(),
+            decimals: 6,
+            ..Default::default()
+        }
+    }
+
+    fn result(timestamp: u64, trades: Vec<TradeInfo>, liquidities: Vec<PoolEvent>) -> ParseResult {
+        let mut result = ParseResult::new();
+        result.timestamp = timestamp;
+        result.trades = trades;
+        result.liquidities = liquidities;
+        result
+    }
+
+    fn buy(pool: &str, timestamp: u64, input: TokenInfo, output: TokenInfo) -> TradeInfo {
+        TradeInfo {
+            trade_type: TradeType::Buy,
+            pool: vec![pool.to_string()],
+            input_token: input,
+            output_token: output,
+            timestamp,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn tracks_reserves_lp_supply_and_volume() {
+        let create = PoolEvent {
+            event_type: TradeType::Create,
+            pool_id: "pool-a".to_string(),
+            lp_amount_raw: Some("1000".to_string()),
+            tokens: vec![
+                TokenLeg::from_raw("MEME", 1_000_000, 6, LegDirection::In),
+                TokenLeg::from_raw("USDC", 500_000, 6, LegDirection::In),
+            ],
+            ..Default::default()
+        };
+        let remove = PoolEvent {
+            event_type: TradeType::Remove,
+            pool_id: "pool-a".to_string(),
+            lp_amount_raw: Some("100".to_string()),
+            tokens: vec![TokenLeg::from_raw("USDC", 50_000, 6, LegDirection::Out)],
+            instruction_index: 1,
+            ..Default::default()
+        };
+        let mut tracker = PoolTracker::new().with_retention(Duration::from_secs(3_600));
+        tracker.ingest(&result(
+            1_000,
+            vec![buy(
+                "pool-a",
+                1_000,
+                token("USDC", 100_000),
+                token("MEME", 150_000),
+            )],
+            vec![create, remove],
+        ));
+
+        let pool = tracker.get_pool("pool-a").unwrap();
+        assert_eq!(pool.lp_supply_raw, Some(900));
+        assert_eq!(pool.reserve("MEME").unwrap().amount, "850000");
+        // create → buy (+100k) → remove (-50k)
+        assert_eq!(pool.reserve("USDC").unwrap().amount, "550000");
+
+        let mut reserves_trade = buy("pool-b", 2_000, token("USDC", 2_000_000), token("SOL", 10));
+        reserves_trade.pool_reserves = Some((
+            TokenAmount::new("7000000", 6, None),
+            TokenAmount::new("40", 6, None),
+        ));
+        tracker.ingest(&result(2_000, vec![reserves_trade], Vec::new()));
+        assert_eq!(
+            tracker
+                .get_pool("pool-b")
+                .unwrap()
+                .reserve("SOL")
+                .unwrap()
+                .amount,
+            "40"
+        );
+
+        let top = tracker.top_pools_by_volume(Duration::from_secs(86_400));
+        let ranked: Vec<_> = top.iter().map(|(pool, _)| pool.pool_id.as_str()).collect();
+        assert_eq!(ranked, ["pool-b", "pool-a"]);
+        assert_eq!(top[1].1, 0.1);
+        // окно 10 минут от последней транзакции
+        assert_eq!(
+            tracker.top_pools_by_volume(Duration::from_secs(600)).len(),
+            1
+        );
+        // старше retention (1 час) объём выбрасывается
+        tracker.ingest(&result(6_000, Vec::new(), Vec::new()));
+        assert!(tracker
+            .top_pools_by_volume(Duration::from_secs(86_400))
+            .is_empty());
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use crate::state_decoders::{decode_account, AccountDecodeError, ProtocolAccount};
+
+/// Разобранные аккаунты состояния протоколов по адресу: параметры пулов, которых
+/// нет в самой транзакции (bin step DLMM, ставки комиссий).
+///
+/// Парсер только читает; заполняет вызывающий код — из `getMultipleAccounts`,
+/// account updates geyser'а или снапшота. Аккаунт по адресу перезаписывается
+/// целиком, поэтому свежий update просто вставляется поверх.
+#[derive(Debug, Default)]
+pub struct ProtocolAccountStore {
+    accounts: RwLock<HashMap<String, ProtocolAccount>>,
+}
+
+impl ProtocolAccountStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&self, address: impl Into<String>, account: ProtocolAccount) {
+        if let Ok(mut accounts) = self.accounts.write() {
+            accounts.insert(address.into(), account);
+        }
+    }
+
+    /// Декодирует сырые данные аккаунта программы `program_id` и запоминает их.
+    pub fn insert_raw(
+        &self,
+        program_id: &str,
+        address: impl Into<String>,
+        data: &[u8],
+    ) -> Result<(), AccountDecodeError> {
+        let account = decode_account(program_id, data)?;
+        self.insert(address, account);
+        Ok(())
+    }
+
+    /// Аккаунт закрыт или больше не нужен.
+    pub fn remove(&self, address: &str) -> Option<ProtocolAccount> {
+        self.accounts.write().ok()?.remove(address)
+    }
+
+    pub fn get(&self, address: &str) -> Option<ProtocolAccount> {
+        self.accounts.read().ok()?.get(address).cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.accounts
+            .read()
+            .map(|accounts| accounts.len())
+            .unwrap_or(0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::constants::dex_programs;
+    use crate::state_decoders::LbPairAccount;
+
+    #[test]
+    fn stores_and_replaces_accounts_by_address() {
+        let store = ProtocolAccountStore::new();
+        let pair = |bin_step| {
+            ProtocolAccount::MeteoraLbPair(LbPairAccount {
+                bin_step,
+                ..Default::default()
+            })
+        };
+        store.insert("pair", pair(25));
+        store.insert("pair", pair(100));
+
+        assert_eq!(store.len(), 1);
+        assert_eq!(store.get("pair"), Some(pair(100)));
+        assert_eq!(store.remove("pair"), Some(pair(100)));
+        assert!(store.get("pair").is_none());
+    }
+
+    #[test]
+    fn raw_accounts_of_unknown_layout_are_rejected() {
+        let store = ProtocolAccountStore::new();
+        let err = store
+            .insert_raw(dex_programs::METEORA, "pair", &[0; 16])
+            .unwrap_err();
+
+        assert!(matches!(err, AccountDecodeError::UnknownAccount { .. }));
+        assert!(store.is_empty());
+    }
+}
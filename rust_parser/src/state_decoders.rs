@@ -0,0 +1,524 @@
+//! Декодирование аккаунтов состояния протоколов (не транзакций): bonding curve Pumpfun,
+//! пулы Pumpswap, `LbPair` Meteora DLMM, `PoolConfig` Meteora DBC и `AmmInfo` Raydium
+//! AMM v4.
+//!
+//! Layout'ы Anchor: 8 байт discriminator'а и поля по фиксированным offsets. Raydium
+//! AMM v4 не Anchor — его `AmmInfo` узнаётся по размеру.
+//! Поля, добавленные программами позже (creator у bonding curve, coin creator у пула
+//! Pumpswap), в старых аккаунтах короче и приходят `None`.
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::core::constants::dex_programs;
+
+pub mod discriminators {
+    /// `sha256("account:BondingCurve")[..8]`.
+    pub const PUMPFUN_BONDING_CURVE: [u8; 8] = [23, 183, 248, 55, 96, 216, 172, 96];
+    /// `sha256("account:Pool")[..8]`.
+    pub const PUMPSWAP_POOL: [u8; 8] = [241, 154, 109, 4, 17, 177, 109, 188];
+    /// `sha256("account:GlobalConfig")[..8]`.
+    pub const PUMPSWAP_GLOBAL_CONFIG: [u8; 8] = [149, 8, 156, 202, 160, 252, 176, 217];
+    /// `sha256("account:LbPair")[..8]`.
+    pub const METEORA_LB_PAIR: [u8; 8] = [33, 11, 49, 98, 181, 101, 177, 13];
+    /// `sha256("account:PoolConfig")[..8]`.
+    pub const METEORA_DBC_POOL_CONFIG: [u8; 8] = [26, 108, 14, 123, 116, 230, 129, 43];
+}
+
+/// Размер `AmmInfo` Raydium AMM v4.
+pub const RAYDIUM_AMM_INFO_LEN: usize = 752;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AccountDecodeError {
+    #[error("no account decoders for program {0}")]
+    UnsupportedProgram(String),
+    #[error("unknown account of program {program_id} (discriminator {discriminator})")]
+    UnknownAccount {
+        program_id: String,
+        discriminator: String,
+    },
+    #[error("{account} account is truncated: {len} bytes")]
+    Truncated { account: &'static str, len: usize },
+}
+
+/// Разобранный аккаунт; тег `type` в JSON.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ProtocolAccount {
+    PumpfunBondingCurve(BondingCurveAccount),
+    PumpswapPool(PumpswapPoolAccount),
+    PumpswapGlobalConfig(PumpswapGlobalConfigAccount),
+    MeteoraLbPair(LbPairAccount),
+    MeteoraDbcConfig(DbcConfigAccount),
+    RaydiumAmmV4(AmmInfoAccount),
+}
+
+/// Bonding curve токена Pumpfun.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BondingCurveAccount {
+    pub virtual_token_reserves: u64,
+    pub virtual_sol_reserves: u64,
+    pub real_token_reserves: u64,
+    pub real_sol_reserves: u64,
+    pub token_total_supply: u64,
+    /// Кривая заполнена, токен мигрирует в Pumpswap.
+    pub complete: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator: Option<String>,
+}
+
+impl BondingCurveAccount {
+    /// Цена токена в lamports за raw-единицу по виртуальным резервам.
+    pub fn price_lamports(&self) -> Option<f64> {
+        (self.virtual_token_reserves > 0)
+            .then(|| self.virtual_sol_reserves as f64 / self.virtual_token_reserves as f64)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PumpswapPoolAccount {
+    pub pool_bump: u8,
+    pub index: u16,
+    pub creator: String,
+    pub base_mint: String,
+    pub quote_mint: String,
+    pub lp_mint: String,
+    pub pool_base_token_account: String,
+    pub pool_quote_token_account: String,
+    pub lp_supply: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coin_creator: Option<String>,
+}
+
+/// Общие для всех пулов Pumpswap комиссии (bps от суммы входа).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PumpswapGlobalConfigAccount {
+    pub admin: String,
+    pub lp_fee_basis_points: u64,
+    pub protocol_fee_basis_points: u64,
+    pub disable_flags: u8,
+    pub protocol_fee_recipients: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coin_creator_fee_basis_points: Option<u64>,
+}
+
+/// Пара Meteora DLMM. Резервы лежат в token-аккаунтах `reserve_x`/`reserve_y`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LbPairAccount {
+    pub base_factor: u16,
+    pub base_fee_power_factor: u8,
+    /// Доля протокола в комиссии, bps.
+    pub protocol_share: u16,
+    pub volatility_accumulator: u32,
+    pub active_id: i32,
+    pub bin_step: u16,
+    pub status: u8,
+    pub activation_type: u8,
+    pub token_x_mint: String,
+    pub token_y_mint: String,
+    pub reserve_x: String,
+    pub reserve_y: String,
+    pub protocol_fee_x: u64,
+    pub protocol_fee_y: u64,
+    pub oracle: String,
+    pub activation_point: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub creator: Option<String>,
+}
+
+impl LbPairAccount {
+    /// Базовая ставка комиссии (доля входа) без волатильной части:
+    /// `base_factor * bin_step * 10 * 10^power / 1e9`.
+    pub fn base_fee_rate(&self) -> f64 {
+        self.base_factor as f64
+            * self.bin_step as f64
+            * 10.0
+            * 10f64.powi(self.base_fee_power_factor as i32)
+            / 1e9
+    }
+}
+
+/// Конфиг партнёра Meteora DBC, общий для всех его bonding curve пулов.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DbcConfigAccount {
+    pub quote_mint: String,
+    pub fee_claimer: String,
+    pub leftover_receiver: String,
+    /// Начальный числитель базовой комиссии (знаменатель 1e9).
+    pub cliff_fee_numerator: u64,
+    pub protocol_fee_percent: u8,
+    pub referral_fee_percent: u8,
+    pub collect_fee_mode: u8,
+    /// 0 — DAMM v1, 1 — DAMM v2.
+    pub migration_option: u8,
+    pub activation_type: u8,
+    pub token_decimal: u8,
+    pub swap_base_amount: u64,
+    /// Сколько quote нужно собрать кривой до миграции.
+    pub migration_quote_threshold: u64,
+    pub migration_base_threshold: u64,
+}
+
+/// Пул Raydium AMM v4. Комиссия свопа — `swap_fee_numerator / swap_fee_denominator`
+/// от входа; из неё доля `pnl_numerator / pnl_denominator` уходит протоколу, остальное LP.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AmmInfoAccount {
+    pub status: u64,
+    pub coin_decimals: u64,
+    pub pc_decimals: u64,
+    pub trade_fee_numerator: u64,
+    pub trade_fee_denominator: u64,
+    pub pnl_numerator: u64,
+    pub pnl_denominator: u64,
+    pub swap_fee_numerator: u64,
+    pub swap_fee_denominator: u64,
+    pub coin_vault: String,
+    pub pc_vault: String,
+    pub coin_mint: String,
+    pub pc_mint: String,
+    pub lp_mint: String,
+    pub open_orders: String,
+    pub market: String,
+}
+
+impl AmmInfoAccount {
+    /// Комиссия со входа `amount_in`: (LP, протокол). `None` при нулевых знаменателях.
+    pub fn swap_fees(&self, amount_in: u128) -> Option<(u128, u128)> {
+        if self.swap_fee_denominator == 0 || self.pnl_denominator == 0 {
+            return None;
+        }
+        let total = amount_in * self.swap_fee_numerator as u128 / self.swap_fee_denominator as u128;
+        let protocol = total * self.pnl_numerator as u128 / self.pnl_denominator as u128;
+        Some((total - protocol, protocol))
+    }
+}
+
+/// Аккаунт программы `program_id` по его данным.
+pub fn decode_account(
+    program_id: &str,
+    data: &[u8],
+) -> Result<ProtocolAccount, AccountDecodeError> {
+    let unknown = || AccountDecodeError::UnknownAccount {
+        program_id: program_id.to_string(),
+        discriminator: hex::encode(data.get(..8).unwrap_or(data)),
+    };
+    if program_id == dex_programs::RAYDIUM {
+        return match data.len() {
+            RAYDIUM_AMM_INFO_LEN => decode_amm_info(data).map(ProtocolAccount::RaydiumAmmV4),
+            _ => Err(unknown()),
+        };
+    }
+    let discriminator: [u8; 8] = data
+        .get(..8)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(unknown)?;
+    match (program_id, discriminator) {
+        (dex_programs::PUMP_FUN, discriminators::PUMPFUN_BONDING_CURVE) => {
+            decode_bonding_curve(data).map(ProtocolAccount::PumpfunBondingCurve)
+        }
+        (dex_programs::PUMP_SWAP, discriminators::PUMPSWAP_POOL) => {
+            decode_pumpswap_pool(data).map(ProtocolAccount::PumpswapPool)
+        }
+        (dex_programs::PUMP_SWAP, discriminators::PUMPSWAP_GLOBAL_CONFIG) => {
+            decode_pumpswap_global_config(data).map(ProtocolAccount::PumpswapGlobalConfig)
+        }
+        (dex_programs::METEORA, discriminators::METEORA_LB_PAIR) => {
+            decode_lb_pair(data).map(ProtocolAccount::MeteoraLbPair)
+        }
+        (dex_programs::METEORA_DBC, discriminators::METEORA_DBC_POOL_CONFIG) => {
+            decode_dbc_config(data).map(ProtocolAccount::MeteoraDbcConfig)
+        }
+        (
+            dex_programs::PUMP_FUN
+            | dex_programs::PUMP_SWAP
+            | dex_programs::METEORA
+            | dex_programs::METEORA_DBC,
+            _,
+        ) => Err(unknown()),
+        _ => Err(AccountDecodeError::UnsupportedProgram(
+            program_id.to_string(),
+        )),
+    }
+}
+
+/// Поля по offsets; выход за границы — `Truncated` с именем аккаунта.
+struct Fields<'a> {
+    data: &'a [u8],
+    account: &'static str,
+}
+
+impl<'a> Fields<'a> {
+    fn new(
+        data: &'a [u8],
+        account: &'static str,
+        min_len: usize,
+    ) -> Result<Self, AccountDecodeError> {
+        let fields = Self { data, account };
+        if data.len() < min_len {
+            return Err(fields.truncated());
+        }
+        Ok(fields)
+    }
+
+    fn truncated(&self) -> AccountDecodeError {
+        AccountDecodeError::Truncated {
+            account: self.account,
+            len: self.data.len(),
+        }
+    }
+
+    fn bytes<const N: usize>(&self, offset: usize) -> Result<[u8; N], AccountDecodeError> {
+        self.data
+            .get(offset..offset + N)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| self.truncated())
+    }
+
+    fn u8(&self, offset: usize) -> Result<u8, AccountDecodeError> {
+        Ok(self.bytes::<1>(offset)?[0])
+    }
+
+    fn u16(&self, offset: usize) -> Result<u16, AccountDecodeError> {
+        Ok(u16::from_le_bytes(self.bytes(offset)?))
+    }
+
+    fn u32(&self, offset: usize) -> Result<u32, AccountDecodeError> {
+        Ok(u32::from_le_bytes(self.bytes(offset)?))
+    }
+
+    fn i32(&self, offset: usize) -> Result<i32, AccountDecodeError> {
+        Ok(i32::from_le_bytes(self.bytes(offset)?))
+    }
+
+    fn u64(&self, offset: usize) -> Result<u64, AccountDecodeError> {
+        Ok(u64::from_le_bytes(self.bytes(offset)?))
+    }
+
+    fn pubkey(&self, offset: usize) -> Result<String, AccountDecodeError> {
+        Ok(bs58::encode(self.bytes::<32>(offset)?).into_string())
+    }
+
+    /// Поле, которого нет в аккаунтах старых версий программы.
+    fn optional<T>(
+        &self,
+        offset: usize,
+        read: impl Fn(&Self, usize) -> Result<T, AccountDecodeError>,
+    ) -> Option<T> {
+        read(self, offset).ok()
+    }
+}
+
+/// virtual token/sol, real token/sol, total supply (u64), complete (bool), creator.
+pub fn decode_bonding_curve(data: &[u8]) -> Result<BondingCurveAccount, AccountDecodeError> {
+    let f = Fields::new(data, "BondingCurve", 49)?;
+    Ok(BondingCurveAccount {
+        virtual_token_reserves: f.u64(8)?,
+        virtual_sol_reserves: f.u64(16)?,
+        real_token_reserves: f.u64(24)?,
+        real_sol_reserves: f.u64(32)?,
+        token_total_supply: f.u64(40)?,
+        complete: f.u8(48)? != 0,
+        creator: f.optional(49, Fields::pubkey),
+    })
+}
+
+/// bump u8, index u16, creator, base/quote/lp mint, base/quote vault, lp_supply,
+/// coin_creator.
+pub fn decode_pumpswap_pool(data: &[u8]) -> Result<PumpswapPoolAccount, AccountDecodeError> {
+    let f = Fields::new(data, "Pool", 211)?;
+    Ok(PumpswapPoolAccount {
+        pool_bump: f.u8(8)?,
+        index: f.u16(9)?,
+        creator: f.pubkey(11)?,
+        base_mint: f.pubkey(43)?,
+        quote_mint: f.pubkey(75)?,
+        lp_mint: f.pubkey(107)?,
+        pool_base_token_account: f.pubkey(139)?,
+        pool_quote_token_account: f.pubkey(171)?,
+        lp_supply: f.u64(203)?,
+        coin_creator: f.optional(211, Fields::pubkey),
+    })
+}
+
+/// admin, lp/protocol fee bps, disable_flags, 8 получателей комиссии, coin creator bps.
+pub fn decode_pumpswap_global_config(
+    data: &[u8],
+) -> Result<PumpswapGlobalConfigAccount, AccountDecodeError> {
+    let f = Fields::new(data, "GlobalConfig", 313)?;
+    Ok(PumpswapGlobalConfigAccount {
+        admin: f.pubkey(8)?,
+        lp_fee_basis_points: f.u64(40)?,
+        protocol_fee_basis_points: f.u64(48)?,
+        disable_flags: f.u8(56)?,
+        protocol_fee_recipients: (0..8)
+            .map(|i| f.pubkey(57 + i * 32))
+            .collect::<Result<_, _>>()?,
+        coin_creator_fee_basis_points: f.optional(313, Fields::u64),
+    })
+}
+
+/// `StaticParameters` (8..40), `VariableParameters` (40..72), служебные поля, затем
+/// mints/reserves с 88, `ProtocolFee` (216), reward infos, oracle (552), activation
+/// point (816), creator (848). Полный аккаунт — 904 байта.
+pub fn decode_lb_pair(data: &[u8]) -> Result<LbPairAccount, AccountDecodeError> {
+    let f = Fields::new(data, "LbPair", 824)?;
+    Ok(LbPairAccount {
+        base_factor: f.u16(8)?,
+        protocol_share: f.u16(32)?,
+        base_fee_power_factor: f.u8(34)?,
+        volatility_accumulator: f.u32(40)?,
+        active_id: f.i32(76)?,
+        bin_step: f.u16(80)?,
+        status: f.u8(82)?,
+        activation_type: f.u8(86)?,
+        token_x_mint: f.pubkey(88)?,
+        token_y_mint: f.pubkey(120)?,
+        reserve_x: f.pubkey(152)?,
+        reserve_y: f.pubkey(184)?,
+        protocol_fee_x: f.u64(216)?,
+        protocol_fee_y: f.u64(224)?,
+        oracle: f.pubkey(552)?,
+        activation_point: f.u64(816)?,
+        creator: f.optional(848, Fields::pubkey),
+    })
+}
+
+/// quote mint, fee claimer, leftover receiver, `PoolFeesConfig` (104..232: base fee,
+/// dynamic fee, protocol/referral percent в конце), однобайтовые флаги с 232, пороги
+/// миграции с 256.
+pub fn decode_dbc_config(data: &[u8]) -> Result<DbcConfigAccount, AccountDecodeError> {
+    let f = Fields::new(data, "PoolConfig", 280)?;
+    Ok(DbcConfigAccount {
+        quote_mint: f.pubkey(8)?,
+        fee_claimer: f.pubkey(40)?,
+        leftover_receiver: f.pubkey(72)?,
+        cliff_fee_numerator: f.u64(104)?,
+        protocol_fee_percent: f.u8(230)?,
+        referral_fee_percent: f.u8(231)?,
+        collect_fee_mode: f.u8(232)?,
+        migration_option: f.u8(233)?,
+        activation_type: f.u8(234)?,
+        token_decimal: f.u8(235)?,
+        swap_base_amount: f.u64(256)?,
+        migration_quote_threshold: f.u64(264)?,
+        migration_base_threshold: f.u64(272)?,
+    })
+}
+
+/// 16 u64 параметров, `Fees` (128..192), `StateData` (192..336), затем vaults, mints,
+/// lp mint, open orders и market с 336.
+pub fn decode_amm_info(data: &[u8]) -> Result<AmmInfoAccount, AccountDecodeError> {
+    let f = Fields::new(data, "AmmInfo", RAYDIUM_AMM_INFO_LEN)?;
+    Ok(AmmInfoAccount {
+        status: f.u64(0)?,
+        coin_decimals: f.u64(32)?,
+        pc_decimals: f.u64(40)?,
+        trade_fee_numerator: f.u64(144)?,
+        trade_fee_denominator: f.u64(152)?,
+        pnl_numerator: f.u64(160)?,
+        pnl_denominator: f.u64(168)?,
+        swap_fee_numerator: f.u64(176)?,
+        swap_fee_denominator: f.u64(184)?,
+        coin_vault: f.pubkey(336)?,
+        pc_vault: f.pubkey(368)?,
+        coin_mint: f.pubkey(400)?,
+        pc_mint: f.pubkey(432)?,
+        lp_mint: f.pubkey(464)?,
+        open_orders: f.pubkey(496)?,
+        market: f.pubkey(528)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn put(data: &mut [u8], offset: usize, bytes: &[u8]) {
+        data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+
+    #[test]
+    fn decodes_bonding_curve_with_and_without_creator() {
+        let mut data = vec![0u8; 81];
+        put(&mut data, 0, &discriminators::PUMPFUN_BONDING_CURVE);
+        put(&mut data, 8, &1_073_000_000_000_000u64.to_le_bytes());
+        put(&mut data, 16, &30_000_000_000u64.to_le_bytes());
+        data[48] = 1;
+        put(&mut data, 49, &[7u8; 32]);
+
+        let ProtocolAccount::PumpfunBondingCurve(curve) =
+            decode_account(dex_programs::PUMP_FUN, &data).unwrap()
+        else {
+            panic!("expected bonding curve");
+        };
+        assert!(curve.complete);
+        assert_eq!(curve.creator, Some(bs58::encode([7u8; 32]).into_string()));
+        assert!((curve.price_lamports().unwrap() - 2.7958993476e-5).abs() < 1e-12);
+
+        let legacy = decode_bonding_curve(&data[..49]).unwrap();
+        assert_eq!(legacy.creator, None);
+        assert_eq!(
+            decode_bonding_curve(&data[..40]),
+            Err(AccountDecodeError::Truncated {
+                account: "BondingCurve",
+                len: 40
+            })
+        );
+    }
+
+    #[test]
+    fn decodes_lb_pair_and_rejects_foreign_accounts() {
+        let mut data = vec![0u8; 904];
+        put(&mut data, 0, &discriminators::METEORA_LB_PAIR);
+        put(&mut data, 8, &10_000u16.to_le_bytes());
+        put(&mut data, 76, &(-42i32).to_le_bytes());
+        put(&mut data, 80, &25u16.to_le_bytes());
+        put(&mut data, 88, &[1u8; 32]);
+
+        let ProtocolAccount::MeteoraLbPair(pair) =
+            decode_account(dex_programs::METEORA, &data).unwrap()
+        else {
+            panic!("expected lb pair");
+        };
+        assert_eq!(pair.active_id, -42);
+        assert_eq!(pair.token_x_mint, bs58::encode([1u8; 32]).into_string());
+        assert!((pair.base_fee_rate() - 0.0025).abs() < 1e-12);
+
+        assert!(matches!(
+            decode_account(dex_programs::PUMP_SWAP, &data),
+            Err(AccountDecodeError::UnknownAccount { .. })
+        ));
+        assert!(matches!(
+            decode_account(dex_programs::ORCA, &data),
+            Err(AccountDecodeError::UnsupportedProgram(_))
+        ));
+    }
+
+    #[test]
+    fn decodes_raydium_amm_info_by_size() {
+        let mut data = vec![0u8; RAYDIUM_AMM_INFO_LEN];
+        for (offset, value) in [(160, 12u64), (168, 100), (176, 25), (184, 10_000)] {
+            put(&mut data, offset, &value.to_le_bytes());
+        }
+        put(&mut data, 400, &[5u8; 32]);
+
+        let ProtocolAccount::RaydiumAmmV4(amm) =
+            decode_account(dex_programs::RAYDIUM, &data).unwrap()
+        else {
+            panic!("expected amm info");
+        };
+        assert_eq!(amm.coin_mint, bs58::encode([5u8; 32]).into_string());
+        assert_eq!(amm.swap_fees(1_000_000), Some((2_200, 300)));
+
+        assert!(matches!(
+            decode_account(dex_programs::RAYDIUM, &data[..600]),
+            Err(AccountDecodeError::UnknownAccount { .. })
+        ));
+    }
+}
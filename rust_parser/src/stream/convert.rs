@@ -0,0 +1,274 @@
+use std::collections::HashMap;
+
+use base64_simd::STANDARD;
+
+use crate::core::error::ParserError;
+use crate::types::{
+    AccountKey, BalanceChange, InnerInstruction, SolanaInstruction, SolanaTransaction, TokenAmount,
+    TokenBalance, TransactionMeta, TransactionStatus,
+};
+
+use super::proto::{self, SubscribeUpdateTransaction, SubscribeUpdateTransactionInfo};
+
+/// Конвертирует транзакцию из Yellowstone update в `SolanaTransaction`.
+///
+/// Transaction update не несёт block_time: он приходит отдельным `BlockMeta`
+/// слота, `GeyserStream` проставляет его перед парсингом. Здесь `block_time` — 0.
+/// Возвращает `None`, если в update нет message или meta.
+pub fn convert_transaction_info(
+    slot: u64,
+    info: &SubscribeUpdateTransactionInfo,
+) -> Option<SolanaTransaction> {
+    let message = info.transaction.as_ref()?.message.as_ref()?;
+    let meta = info.meta.as_ref()?;
+
+    // Статические ключи + адреса из lookup tables (writable, затем readonly), как в RPC
+    let mut account_keys: Vec<String> = Vec::with_capacity(
+        message.account_keys.len()
+            + meta.loaded_writable_addresses.len()
+            + meta.loaded_readonly_addresses.len(),
+    );
+    account_keys.extend(message.account_keys.iter().map(encode_key));
+    account_keys.extend(meta.loaded_writable_addresses.iter().map(encode_key));
+    account_keys.extend(meta.loaded_readonly_addresses.iter().map(encode_key));
+
+    let num_signers = message
+        .header
+        .as_ref()
+        .map(|header| header.num_required_signatures as usize)
+        .unwrap_or(1);
+    let signers = account_keys.iter().take(num_signers).cloned().collect();
+    // инструкции ссылаются на общие `Arc<str>` ключей, а не копируют строки
+    let keys: Vec<AccountKey> = account_keys
+        .iter()
+        .map(|key| AccountKey::new(key))
+        .collect();
+
+    let instructions = message
+        .instructions
+        .iter()
+        .map(|ix| convert_instruction(ix.program_id_index, &ix.accounts, &ix.data, &keys))
+        .collect();
+
+    let inner_instructions = meta
+        .inner_instructions
+        .iter()
+        .map(|set| InnerInstruction {
+            index: set.index as usize,
+            instructions: set
+                .instructions
+                .iter()
+                .map(|ix| convert_instruction(ix.program_id_index, &ix.accounts, &ix.data, &keys))
+                .collect(),
+            stack_heights: set.instructions.iter().map(|ix| ix.stack_height).collect(),
+        })
+        .collect();
+
+    Some(SolanaTransaction {
+        slot,
+        signature: bs58::encode(&info.signature).into_string(),
+        block_time: 0,
+        signers,
+        instructions,
+        inner_instructions,
+        transfers: Vec::new(),
+        pre_token_balances: convert_token_balances(&meta.pre_token_balances, &account_keys),
+        post_token_balances: convert_token_balances(&meta.post_token_balances, &account_keys),
+        meta: TransactionMeta {
+            fee: meta.fee,
+            compute_units: meta.compute_units_consumed.unwrap_or(0),
+            status: if meta.err.is_some() {
+                TransactionStatus::Failed
+            } else {
+                TransactionStatus::Success
+            },
+            sol_balance_changes: collect_sol_balance_changes(meta, &account_keys),
+            token_balance_changes: HashMap::new(),
+            err: meta.err.as_ref().map(decode_transaction_error),
+            log_messages: meta.log_messages.clone(),
+        },
+    })
+}
+
+/// Без слота: `SubscribeUpdateTransactionInfo` его не несёт, `slot` остаётся 0.
+impl TryFrom<&SubscribeUpdateTransactionInfo> for SolanaTransaction {
+    type Error = ParserError;
+
+    fn try_from(info: &SubscribeUpdateTransactionInfo) -> Result<Self, Self::Error> {
+        convert_transaction_info(0, info).ok_or_else(missing_message)
+    }
+}
+
+impl TryFrom<&SubscribeUpdateTransaction> for SolanaTransaction {
+    type Error = ParserError;
+
+    fn try_from(update: &SubscribeUpdateTransaction) -> Result<Self, Self::Error> {
+        update
+            .transaction
+            .as_ref()
+            .and_then(|info| convert_transaction_info(update.slot, info))
+            .ok_or_else(missing_message)
+    }
+}
+
+impl TryFrom<SubscribeUpdateTransaction> for SolanaTransaction {
+    type Error = ParserError;
+
+    fn try_from(update: SubscribeUpdateTransaction) -> Result<Self, Self::Error> {
+        Self::try_from(&update)
+    }
+}
+
+fn missing_message() -> ParserError {
+    ParserError::Deserialize("geyser update has no transaction message or meta".to_string())
+}
+
+/// В geyser `err` — bincode-сериализованный `TransactionError`.
+fn decode_transaction_error(err: &proto::TransactionError) -> String {
+    bincode::deserialize::<solana_sdk::transaction::TransactionError>(&err.err)
+        .ok()
+        .and_then(|err| serde_json::to_string(&err).ok())
+        .unwrap_or_else(|| format!("\"{}\"", STANDARD.encode_to_string(&err.err)))
+}
+
+#[inline]
+fn encode_key(key: &Vec<u8>) -> String {
+    bs58::encode(key).into_string()
+}
+
+fn convert_instruction(
+    program_id_index: u32,
+    accounts: &[u8],
+    data: &[u8],
+    keys: &[AccountKey],
+) -> SolanaInstruction {
+    SolanaInstruction {
+        program_id: keys
+            .get(program_id_index as usize)
+            .cloned()
+            .unwrap_or_default(),
+        accounts: accounts
+            .iter()
+            .filter_map(|index| keys.get(*index as usize).cloned())
+            .collect(),
+        data: STANDARD.encode_to_string(data),
+    }
+}
+
+fn convert_token_balances(
+    balances: &[proto::TokenBalance],
+    account_keys: &[String],
+) -> Vec<TokenBalance> {
+    balances
+        .iter()
+        .filter_map(|balance| {
+            let account = account_keys.get(balance.account_index as usize)?.clone();
+            let ui_amount = balance.ui_token_amount.as_ref();
+            Some(TokenBalance {
+                account,
+                mint: balance.mint.clone(),
+                owner: (!balance.owner.is_empty()).then(|| balance.owner.clone()),
+                ui_token_amount: TokenAmount {
+                    amount: ui_amount.map(|a| a.amount.clone()).unwrap_or_default(),
+                    ui_amount: ui_amount.map(|a| a.ui_amount),
+                    decimals: ui_amount.map(|a| a.decimals as u8).unwrap_or(0),
+                    ui_amount_string: ui_amount.map(|a| a.ui_amount_string.clone()),
+                },
+            })
+        })
+        .collect()
+}
+
+fn collect_sol_balance_changes(
+    meta: &proto::TransactionStatusMeta,
+    account_keys: &[String],
+) -> HashMap<String, BalanceChange> {
+    let mut changes = HashMap::new();
+    for (idx, key) in account_keys.iter().enumerate() {
+        if let (Some(pre), Some(post)) = (meta.pre_balances.get(idx), meta.post_balances.get(idx)) {
+            if pre != post {
+                changes.insert(
+                    key.clone(),
+                    BalanceChange {
+                        pre: *pre as i128,
+                        post: *post as i128,
+                        change: *post as i128 - *pre as i128,
+                    },
+                );
+            }
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::proto::{
+        CompiledInstruction, Message, MessageHeader, Transaction, TransactionStatusMeta,
+    };
+
+    #[test]
+    fn converts_update_with_loaded_addresses() {
+        let keys: Vec<Vec<u8>> = (1u8..=3).map(|b| vec![b; 32]).collect();
+        let loaded = vec![9u8; 32];
+        let info = SubscribeUpdateTransactionInfo {
+            signature: vec![7u8; 64],
+            is_vote: false,
+            transaction: Some(Transaction {
+                signatures: vec![vec![7u8; 64]],
+                message: Some(Message {
+                    header: Some(MessageHeader {
+                        num_required_signatures: 1,
+                        ..Default::default()
+                    }),
+                    account_keys: keys.clone(),
+                    instructions: vec![CompiledInstruction {
+                        program_id_index: 2,
+                        accounts: vec![0, 3],
+                        data: vec![1, 2, 3],
+                    }],
+                    ..Default::default()
+                }),
+            }),
+            meta: Some(TransactionStatusMeta {
+                fee: 5_000,
+                pre_balances: vec![10_000, 0, 1, 0],
+                post_balances: vec![5_000, 0, 1, 0],
+                loaded_writable_addresses: vec![loaded.clone()],
+                compute_units_consumed: Some(42),
+                ..Default::default()
+            }),
+            index: 0,
+        };
+
+        let tx = convert_transaction_info(100, &info).expect("converted");
+        assert_eq!(tx.slot, 100);
+        assert_eq!(tx.signers, vec![bs58::encode(&keys[0]).into_string()]);
+        let ix = &tx.instructions[0];
+        assert_eq!(ix.program_id, bs58::encode(&keys[2]).into_string());
+        assert_eq!(ix.accounts[1], bs58::encode(&loaded).into_string());
+        assert_eq!(ix.data, STANDARD.encode_to_string([1u8, 2, 3]));
+        assert_eq!(tx.meta.compute_units, 42);
+        assert_eq!(tx.meta.status, TransactionStatus::Success);
+        assert_eq!(tx.meta.sol_balance_changes.len(), 1);
+
+        let update = SubscribeUpdateTransaction {
+            transaction: Some(info.clone()),
+            slot: 100,
+        };
+        assert_eq!(SolanaTransaction::try_from(&update).expect("converted"), tx);
+        let at_other_slot = SubscribeUpdateTransaction {
+            slot: 101,
+            ..update
+        };
+        assert_eq!(
+            SolanaTransaction::try_from(at_other_slot)
+                .expect("converted")
+                .slot,
+            101
+        );
+        let empty = SubscribeUpdateTransactionInfo::default();
+        assert!(SolanaTransaction::try_from(&empty).is_err());
+    }
+}
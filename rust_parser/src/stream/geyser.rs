@@ -0,0 +1,506 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use futures::channel::mpsc as futures_mpsc;
+use tokio::sync::mpsc;
+use tonic::codec::ProstCodec;
+use tonic::codegen::http::uri::PathAndQuery;
+use tonic::metadata::MetadataValue;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+use tonic::Code;
+
+use crate::config::ParseConfig;
+use crate::core::dex_parser::DexParser;
+use crate::types::{ParseResult, SolanaTransaction};
+
+use super::convert::convert_transaction_info;
+use super::proto::{
+    subscribe_update::UpdateOneof, CommitmentLevel, SubscribeRequest,
+    SubscribeRequestFilterBlocksMeta, SubscribeRequestFilterTransactions, SubscribeRequestPing,
+    SubscribeUpdate,
+};
+
+const SUBSCRIBE_PATH: &str = "/geyser.Geyser/Subscribe";
+const FILTER_NAME: &str = "dex-parser";
+/// Сколько слотов без BlockMeta держим, прежде чем отдать самый старый без block_time.
+const MAX_PENDING_SLOTS: usize = 32;
+
+/// Уровень подтверждения для Yellowstone подписки.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GeyserCommitment {
+    Processed,
+    #[default]
+    Confirmed,
+    Finalized,
+}
+
+impl From<GeyserCommitment> for CommitmentLevel {
+    fn from(value: GeyserCommitment) -> Self {
+        match value {
+            GeyserCommitment::Processed => CommitmentLevel::Processed,
+            GeyserCommitment::Confirmed => CommitmentLevel::Confirmed,
+            GeyserCommitment::Finalized => CommitmentLevel::Finalized,
+        }
+    }
+}
+
+/// Фильтр транзакций (соответствует `SubscribeRequestFilterTransactions`).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransactionFilter {
+    pub account_include: Vec<String>,
+    pub account_exclude: Vec<String>,
+    pub account_required: Vec<String>,
+    /// `None` — и vote, и non-vote; по умолчанию vote отключены.
+    pub vote: Option<bool>,
+    pub failed: Option<bool>,
+}
+
+#[derive(Clone, Debug)]
+pub struct GeyserConfig {
+    pub endpoint: String,
+    pub x_token: Option<String>,
+    pub filter: TransactionFilter,
+    pub commitment: GeyserCommitment,
+    /// Задержка перед первым переподключением, дальше удваивается до `max_reconnect_delay`.
+    pub reconnect_delay: Duration,
+    pub max_reconnect_delay: Duration,
+    /// Ёмкость выходного канала ParseResult'ов.
+    pub channel_capacity: usize,
+    pub parse_config: Option<ParseConfig>,
+}
+
+impl Default for GeyserConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: String::new(),
+            x_token: None,
+            filter: TransactionFilter {
+                vote: Some(false),
+                ..Default::default()
+            },
+            commitment: GeyserCommitment::default(),
+            reconnect_delay: Duration::from_millis(500),
+            max_reconnect_delay: Duration::from_secs(30),
+            channel_capacity: 1024,
+            parse_config: None,
+        }
+    }
+}
+
+/// Yellowstone gRPC подписка на транзакции с парсингом и автопереподключением.
+pub struct GeyserStream {
+    config: GeyserConfig,
+    parser: Arc<DexParser>,
+}
+
+impl GeyserStream {
+    pub fn new(config: GeyserConfig) -> Self {
+        Self {
+            config,
+            parser: Arc::new(DexParser::new()),
+        }
+    }
+
+    pub fn with_parser(mut self, parser: Arc<DexParser>) -> Self {
+        self.parser = parser;
+        self
+    }
+
+    /// Запускает подписку в фоновой задаче и возвращает канал с результатами.
+    /// Задача завершается, когда receiver дропнут.
+    pub fn spawn(self) -> mpsc::Receiver<ParseResult> {
+        let (tx, rx) = mpsc::channel(self.config.channel_capacity.max(1));
+        tokio::spawn(async move { self.run(tx).await });
+        rx
+    }
+
+    async fn run(self, output: mpsc::Sender<ParseResult>) {
+        let mut delay = self.config.reconnect_delay;
+        // первый ещё не отданный слот: после переподключения подписка продолжается с него
+        let mut resume_from = None;
+        loop {
+            let mut subscribed = false;
+            match self
+                .subscribe_once(&output, &mut resume_from, &mut subscribed)
+                .await
+            {
+                // Receiver закрыт — выходим
+                Ok(true) => return,
+                Ok(false) => {
+                    tracing::warn!("geyser stream ended, reconnecting");
+                }
+                Err(err) => {
+                    tracing::warn!("geyser stream error: {err:#}, reconnecting");
+                }
+            }
+            if output.is_closed() {
+                return;
+            }
+            // после успешной подписки backoff начинается заново
+            if subscribed {
+                delay = self.config.reconnect_delay;
+            }
+            tracing::debug!("reconnecting in {delay:?}, resume from slot {resume_from:?}");
+            tokio::time::sleep(delay).await;
+            delay = (delay * 2).min(self.config.max_reconnect_delay);
+        }
+    }
+
+    /// Одна сессия подписки. `Ok(true)` — потребитель закрыл канал.
+    ///
+    /// `resume_from` продвигается по мере отдачи слотов; `subscribed` выставляется,
+    /// когда сервер принял подписку.
+    async fn subscribe_once(
+        &self,
+        output: &mpsc::Sender<ParseResult>,
+        resume_from: &mut Option<u64>,
+        subscribed: &mut bool,
+    ) -> Result<bool> {
+        let channel = self.connect().await?;
+        let mut grpc = tonic::client::Grpc::new(channel);
+        grpc.ready().await.context("geyser channel not ready")?;
+
+        let (requests, outbound) = futures_mpsc::unbounded::<ResumableRequest>();
+        requests
+            .unbounded_send(ResumableRequest {
+                request: self.subscribe_request(),
+                from_slot: *resume_from,
+            })
+            .context("failed to queue subscribe request")?;
+
+        let mut request = tonic::Request::new(outbound);
+        if let Some(token) = self.config.x_token.as_deref() {
+            let value: MetadataValue<_> = token.parse().context("invalid x-token")?;
+            request.metadata_mut().insert("x-token", value);
+        }
+
+        let codec: ProstCodec<ResumableRequest, SubscribeUpdate> = ProstCodec::default();
+        let response = grpc
+            .streaming(request, PathAndQuery::from_static(SUBSCRIBE_PATH), codec)
+            .await;
+        let mut inbound = match response {
+            Ok(response) => response.into_inner(),
+            // слот вне окна replay сервера — подписываемся с текущего
+            Err(status) if status.code() == Code::InvalidArgument && resume_from.is_some() => {
+                tracing::warn!(
+                    "geyser cannot resume from slot {resume_from:?}: {}",
+                    status.message()
+                );
+                *resume_from = None;
+                return Err(status).context("geyser subscribe failed");
+            }
+            Err(status) => return Err(status).context("geyser subscribe failed"),
+        };
+        *subscribed = true;
+
+        let mut pending = PendingSlots::default();
+        while let Some(update) = inbound.message().await? {
+            match update.update_oneof {
+                Some(UpdateOneof::Transaction(tx_update)) => {
+                    let Some(info) = tx_update.transaction.as_ref() else {
+                        continue;
+                    };
+                    let Some(tx) = convert_transaction_info(tx_update.slot, info) else {
+                        continue;
+                    };
+                    let overflow = pending.push(tx);
+                    if !self.emit(output, overflow, resume_from).await {
+                        return Ok(true);
+                    }
+                }
+                Some(UpdateOneof::BlockMeta(meta)) => {
+                    let block_time = meta
+                        .block_time
+                        .and_then(|time| u64::try_from(time.timestamp).ok());
+                    let ready = pending.complete(meta.slot, block_time);
+                    if !self.emit(output, ready, resume_from).await {
+                        return Ok(true);
+                    }
+                    advance(resume_from, meta.slot);
+                }
+                // Отвечаем на ping, иначе балансировщики рвут простаивающий стрим
+                Some(UpdateOneof::Ping(_)) => {
+                    let _ = requests.unbounded_send(ResumableRequest {
+                        request: SubscribeRequest {
+                            ping: Some(SubscribeRequestPing { id: 1 }),
+                            ..Default::default()
+                        },
+                        from_slot: None,
+                    });
+                }
+                // остальные виды update не запрашиваем
+                _ => {}
+            }
+        }
+        // Стрим оборвался: слоты с `from_slot` сервер повторит, остальное отдаём как есть
+        let rest = match *resume_from {
+            Some(from_slot) => pending.take_before(from_slot),
+            None => pending.drain(),
+        };
+        if !self.emit(output, rest, resume_from).await {
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    /// Парсит транзакции в `spawn_blocking` и отправляет результаты. `false` —
+    /// потребитель закрыл канал.
+    async fn emit(
+        &self,
+        output: &mpsc::Sender<ParseResult>,
+        txs: Vec<SolanaTransaction>,
+        resume_from: &mut Option<u64>,
+    ) -> bool {
+        let Some(last_slot) = txs.iter().map(|tx| tx.slot).max() else {
+            return true;
+        };
+        let parser = self.parser.clone();
+        let parse_config = self.config.parse_config.clone();
+        let results = match tokio::task::spawn_blocking(move || {
+            txs.into_iter()
+                .map(|tx| parser.parse_all(tx, parse_config.clone()))
+                .collect::<Vec<_>>()
+        })
+        .await
+        {
+            Ok(results) => results,
+            Err(err) => {
+                tracing::warn!("geyser parse task failed: {err}");
+                return true;
+            }
+        };
+        for result in results {
+            if output.send(result).await.is_err() {
+                return false;
+            }
+        }
+        advance(resume_from, last_slot);
+        true
+    }
+
+    async fn connect(&self) -> Result<Channel> {
+        let mut endpoint = Endpoint::from_shared(self.config.endpoint.clone())
+            .context("invalid geyser endpoint")?
+            .tcp_keepalive(Some(Duration::from_secs(30)))
+            .http2_keep_alive_interval(Duration::from_secs(15));
+        if self.config.endpoint.starts_with("https://") {
+            endpoint = endpoint
+                .tls_config(ClientTlsConfig::new())
+                .context("failed to configure TLS")?;
+        }
+        endpoint
+            .connect()
+            .await
+            .with_context(|| format!("failed to connect to {}", self.config.endpoint))
+    }
+
+    fn subscribe_request(&self) -> SubscribeRequest {
+        let filter = &self.config.filter;
+        let mut transactions = HashMap::with_capacity(1);
+        transactions.insert(
+            FILTER_NAME.to_string(),
+            SubscribeRequestFilterTransactions {
+                vote: filter.vote,
+                failed: filter.failed,
+                signature: None,
+                account_include: filter.account_include.clone(),
+                account_exclude: filter.account_exclude.clone(),
+                account_required: filter.account_required.clone(),
+            },
+        );
+        // block_time приходит только в BlockMeta слота
+        let mut blocks_meta = HashMap::with_capacity(1);
+        blocks_meta.insert(FILTER_NAME.to_string(), SubscribeRequestFilterBlocksMeta {});
+        SubscribeRequest {
+            transactions,
+            blocks_meta,
+            commitment: Some(CommitmentLevel::from(self.config.commitment) as i32),
+            ..Default::default()
+        }
+    }
+}
+
+/// Сдвигает точку возобновления за отданный слот.
+fn advance(resume_from: &mut Option<u64>, slot: u64) {
+    let next = slot + 1;
+    *resume_from = Some(resume_from.map_or(next, |current| current.max(next)));
+}
+
+/// `SubscribeRequest` с полем `from_slot` (тег 11), которого нет в `yellowstone-grpc-proto`
+/// 1.14. Сервер с replay отдаёт слоты, пропущенные за время переподключения; старые
+/// серверы незнакомое поле игнорируют.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct ResumableRequest {
+    request: SubscribeRequest,
+    from_slot: Option<u64>,
+}
+
+const FROM_SLOT_TAG: u32 = 11;
+
+impl prost::Message for ResumableRequest {
+    fn encode_raw<B: prost::bytes::BufMut>(&self, buf: &mut B) {
+        self.request.encode_raw(buf);
+        if let Some(slot) = &self.from_slot {
+            prost::encoding::uint64::encode(FROM_SLOT_TAG, slot, buf);
+        }
+    }
+
+    fn merge_field<B: prost::bytes::Buf>(
+        &mut self,
+        tag: u32,
+        wire_type: prost::encoding::WireType,
+        buf: &mut B,
+        ctx: prost::encoding::DecodeContext,
+    ) -> Result<(), prost::DecodeError> {
+        if tag == FROM_SLOT_TAG {
+            let slot = self.from_slot.get_or_insert(0);
+            prost::encoding::uint64::merge(wire_type, slot, buf, ctx)
+        } else {
+            self.request.merge_field(tag, wire_type, buf, ctx)
+        }
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.request.encoded_len()
+            + self.from_slot.as_ref().map_or(0, |slot| {
+                prost::encoding::uint64::encoded_len(FROM_SLOT_TAG, slot)
+            })
+    }
+
+    fn clear(&mut self) {
+        self.request.clear();
+        self.from_slot = None;
+    }
+}
+
+/// Транзакции, ждущие BlockMeta своего слота.
+///
+/// BlockMeta приходит после транзакций слота. Слоты, для которых он так и не
+/// пришёл (форк, пропуск), отдаются с `block_time = 0`, когда приходит BlockMeta
+/// более нового слота или буфер переполнен.
+#[derive(Default)]
+struct PendingSlots {
+    slots: BTreeMap<u64, Vec<SolanaTransaction>>,
+}
+
+impl PendingSlots {
+    /// Добавляет транзакцию; возвращает вытесненные из переполненного буфера.
+    fn push(&mut self, tx: SolanaTransaction) -> Vec<SolanaTransaction> {
+        self.slots.entry(tx.slot).or_default().push(tx);
+        if self.slots.len() <= MAX_PENDING_SLOTS {
+            return Vec::new();
+        }
+        self.slots
+            .pop_first()
+            .map(|(_, txs)| txs)
+            .unwrap_or_default()
+    }
+
+    /// Отдаёт транзакции слота `slot` с его block_time и все более старые.
+    fn complete(&mut self, slot: u64, block_time: Option<u64>) -> Vec<SolanaTransaction> {
+        let newer = self.slots.split_off(&(slot + 1));
+        let ready = std::mem::replace(&mut self.slots, newer);
+        let mut txs = Vec::new();
+        for (tx_slot, slot_txs) in ready {
+            if tx_slot == slot {
+                txs.extend(slot_txs.into_iter().map(|mut tx| {
+                    tx.block_time = block_time.unwrap_or_default();
+                    tx
+                }));
+            } else {
+                tracing::debug!("slot {tx_slot} has no block meta, emitting without block_time");
+                txs.extend(slot_txs);
+            }
+        }
+        txs
+    }
+
+    /// Отдаёт транзакции слотов старше `slot`.
+    fn take_before(&mut self, slot: u64) -> Vec<SolanaTransaction> {
+        let newer = self.slots.split_off(&slot);
+        std::mem::replace(&mut self.slots, newer)
+            .into_values()
+            .flatten()
+            .collect()
+    }
+
+    fn drain(&mut self) -> Vec<SolanaTransaction> {
+        std::mem::take(&mut self.slots)
+            .into_values()
+            .flatten()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx(slot: u64, signature: &str) -> SolanaTransaction {
+        SolanaTransaction {
+            slot,
+            signature: signature.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pending_slots_take_block_time_from_block_meta() {
+        let mut pending = PendingSlots::default();
+        assert!(pending.push(tx(10, "a")).is_empty());
+        assert!(pending.push(tx(11, "b")).is_empty());
+        assert!(pending.push(tx(12, "c")).is_empty());
+
+        let ready = pending.complete(11, Some(1_700_000_000));
+        let emitted: Vec<(&str, u64)> = ready
+            .iter()
+            .map(|tx| (tx.signature.as_str(), tx.block_time))
+            .collect();
+        // слот 10 без BlockMeta уходит как есть, слот 12 ещё ждёт
+        assert_eq!(emitted, vec![("a", 0), ("b", 1_700_000_000)]);
+        assert_eq!(pending.drain().len(), 1);
+    }
+
+    #[test]
+    fn resumable_request_encodes_from_slot() {
+        use prost::Message;
+
+        let request = ResumableRequest {
+            request: SubscribeRequest {
+                commitment: Some(CommitmentLevel::Confirmed as i32),
+                ..Default::default()
+            },
+            from_slot: Some(300_000_000),
+        };
+        let bytes = request.encode_to_vec();
+        assert_eq!(bytes.len(), request.encoded_len());
+        assert_eq!(ResumableRequest::decode(bytes.as_slice()).unwrap(), request);
+        // сервер без поддержки replay видит обычный запрос
+        assert_eq!(
+            SubscribeRequest::decode(bytes.as_slice()).unwrap(),
+            request.request
+        );
+    }
+
+    #[test]
+    fn resume_point_only_moves_forward() {
+        let mut resume_from = None;
+        advance(&mut resume_from, 10);
+        assert_eq!(resume_from, Some(11));
+        advance(&mut resume_from, 7);
+        assert_eq!(resume_from, Some(11));
+    }
+
+    #[test]
+    fn pending_slots_are_bounded() {
+        let mut pending = PendingSlots::default();
+        for slot in 0..MAX_PENDING_SLOTS as u64 {
+            assert!(pending.push(tx(slot, "tx")).is_empty());
+        }
+        let evicted = pending.push(tx(MAX_PENDING_SLOTS as u64, "tx"));
+        assert_eq!(evicted.len(), 1);
+        assert_eq!(evicted[0].slot, 0);
+    }
+}
@@ -0,0 +1,10 @@
+//! Потоковый ingestion из Yellowstone (Geyser) gRPC.
+//!
+//! Доступен с feature `grpc`.
+
+pub mod convert;
+pub mod geyser;
+pub mod proto;
+
+pub use convert::convert_transaction_info;
+pub use geyser::{GeyserCommitment, GeyserConfig, GeyserStream, TransactionFilter};
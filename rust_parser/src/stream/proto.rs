@@ -0,0 +1,5 @@
+//! Типы Yellowstone gRPC (`geyser.proto` / `solana-storage.proto`) из
+//! `yellowstone-grpc-proto`: подписка `GeyserStream` и `TryFrom` в `SolanaTransaction`
+//! работают с теми же сообщениями, что и клиенты upstream.
+
+pub use yellowstone_grpc_proto::prelude::*;
@@ -1,18 +1,37 @@
 use std::collections::HashMap;
 
 use anyhow::{anyhow, Result};
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::config::ParseConfig;
+use crate::config::{BaseTokenConvention, ParseConfig};
+use crate::core::constants::{QUOTE_MINTS, TOKENS};
+
+mod account_key;
+mod decimal_amount;
+mod pool_event_tokens;
+mod route_graph;
+mod token_lifecycle;
+
+pub use account_key::{AccountKey, KeyInterner};
+pub use decimal_amount::DecimalAmount;
+pub use route_graph::{RouteEdge, RouteGraph, RouteNode};
+pub use token_lifecycle::{LifecycleMeta, RugReason, TokenLifecycleEvent};
 
 /// Representation of a raw token amount and its UI value.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "camelCase")]
 pub struct TokenAmount {
     pub amount: String,
     #[serde(default)]
     pub ui_amount: Option<f64>,
     pub decimals: u8,
+    /// `uiAmountString` из RPC; у вычисленных сумм отсутствует.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ui_amount_string: Option<String>,
 }
 
 impl TokenAmount {
@@ -21,8 +40,14 @@ impl TokenAmount {
             amount: amount.into(),
             ui_amount,
             decimals,
+            ui_amount_string: None,
         }
     }
+
+    /// Точная сумма из `amount`; `None`, если строка не raw-число.
+    pub fn amount_decimal(&self) -> Option<DecimalAmount> {
+        DecimalAmount::parse(&self.amount, self.decimals)
+    }
 }
 
 impl Default for TokenAmount {
@@ -31,12 +56,14 @@ impl Default for TokenAmount {
             amount: "0".to_string(),
             ui_amount: Some(0.0),
             decimals: 9,
+            ui_amount_string: None,
         }
     }
 }
 
 /// Token balance change helper struct used for SOL/token deltas.
-#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "camelCase")]
 pub struct BalanceChange {
     pub pre: i128,
@@ -45,7 +72,8 @@ pub struct BalanceChange {
 }
 
 /// Snapshot of a token account balance from transaction meta.
-#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "camelCase")]
 pub struct TokenBalance {
     pub account: String,
@@ -57,7 +85,8 @@ pub struct TokenBalance {
 }
 
 /// Execution status for a Solana transaction.
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TransactionStatus {
     #[serde(alias = "UNKNOWN")]
@@ -73,7 +102,8 @@ impl Default for TransactionStatus {
 }
 
 /// Trade directions supported by the parser.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "UPPERCASE")]
 pub enum TradeType {
     Buy,
@@ -87,20 +117,96 @@ pub enum TradeType {
     Remove,
     Lock,
     Burn,
+    #[serde(rename = "COLLECT_FEE")]
+    CollectFee,
+    #[serde(rename = "OPEN_POSITION")]
+    OpenPosition,
+    #[serde(rename = "CLOSE_POSITION")]
+    ClosePosition,
+    #[serde(rename = "INCREASE_LIQUIDITY")]
+    IncreaseLiquidity,
+    #[serde(rename = "DECREASE_LIQUIDITY")]
+    DecreaseLiquidity,
 }
 
-/// Pool event types (CREATE, ADD, REMOVE).
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Default)]
+impl TradeType {
+    /// Имя как в JSON (`BUY`, `ADD`, ...).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeType::Buy => "BUY",
+            TradeType::Sell => "SELL",
+            TradeType::Swap => "SWAP",
+            TradeType::Create => "CREATE",
+            TradeType::Migrate => "MIGRATE",
+            TradeType::Complete => "COMPLETE",
+            TradeType::Add => "ADD",
+            TradeType::Remove => "REMOVE",
+            TradeType::Lock => "LOCK",
+            TradeType::Burn => "BURN",
+            TradeType::CollectFee => "COLLECT_FEE",
+            TradeType::OpenPosition => "OPEN_POSITION",
+            TradeType::ClosePosition => "CLOSE_POSITION",
+            TradeType::IncreaseLiquidity => "INCREASE_LIQUIDITY",
+            TradeType::DecreaseLiquidity => "DECREASE_LIQUIDITY",
+        }
+    }
+}
+
+/// Pool event types. `Add`/`Remove` — депозит в пул за LP-токены; у позиционных
+/// пулов (Whirlpool, Raydium CLMM) — `IncreaseLiquidity`/`DecreaseLiquidity`.
+/// Сбор комиссий позиции — `CollectFee`, а не вывод ликвидности.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "UPPERCASE")]
 pub enum PoolEventType {
     #[default]
     Create,
     Add,
     Remove,
+    #[serde(rename = "COLLECT_FEE")]
+    CollectFee,
+    #[serde(rename = "OPEN_POSITION")]
+    OpenPosition,
+    #[serde(rename = "CLOSE_POSITION")]
+    ClosePosition,
+    #[serde(rename = "INCREASE_LIQUIDITY")]
+    IncreaseLiquidity,
+    #[serde(rename = "DECREASE_LIQUIDITY")]
+    DecreaseLiquidity,
+}
+
+impl PoolEventType {
+    /// Направление токенов относительно пула: что вносится — `In`, что выводится — `Out`.
+    pub fn leg_direction(&self) -> LegDirection {
+        match self {
+            Self::Create | Self::Add | Self::OpenPosition | Self::IncreaseLiquidity => {
+                LegDirection::In
+            }
+            Self::Remove | Self::CollectFee | Self::ClosePosition | Self::DecreaseLiquidity => {
+                LegDirection::Out
+            }
+        }
+    }
+}
+
+impl From<PoolEventType> for TradeType {
+    fn from(event_type: PoolEventType) -> Self {
+        match event_type {
+            PoolEventType::Create => Self::Create,
+            PoolEventType::Add => Self::Add,
+            PoolEventType::Remove => Self::Remove,
+            PoolEventType::CollectFee => Self::CollectFee,
+            PoolEventType::OpenPosition => Self::OpenPosition,
+            PoolEventType::ClosePosition => Self::ClosePosition,
+            PoolEventType::IncreaseLiquidity => Self::IncreaseLiquidity,
+            PoolEventType::DecreaseLiquidity => Self::DecreaseLiquidity,
+        }
+    }
 }
 
 /// Base pool event structure (shared fields).
-#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "camelCase")]
 pub struct PoolEventBase {
     pub user: String,
@@ -119,7 +225,8 @@ pub struct PoolEventBase {
 }
 
 /// Detailed token information used for trades and events.
-#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "camelCase")]
 pub struct TokenInfo {
     pub mint: String,
@@ -150,8 +257,16 @@ pub struct TokenInfo {
     pub balance_change: Option<String>,
 }
 
+impl TokenInfo {
+    /// `amount_raw` с `decimals`; `amount` — его `f64`-представление для отображения.
+    pub fn amount_decimal(&self) -> Option<DecimalAmount> {
+        DecimalAmount::parse(&self.amount_raw, self.decimals)
+    }
+}
+
 /// Fee information associated with a trade.
-#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "camelCase")]
 pub struct FeeInfo {
     pub mint: String,
@@ -164,10 +279,40 @@ pub struct FeeInfo {
     pub fee_type: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub recipient: Option<String>,
+    /// Кому достаётся комиссия; `None` — сетевой fee или неизвестно.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kind: Option<FeeKind>,
+}
+
+impl FeeInfo {
+    pub fn amount_decimal(&self) -> Option<DecimalAmount> {
+        DecimalAmount::parse(&self.amount_raw, self.decimals)
+    }
+
+    pub fn with_kind(mut self, kind: FeeKind, recipient: Option<String>) -> Self {
+        self.kind = Some(kind);
+        self.recipient = recipient;
+        self
+    }
+}
+
+/// Recipient class of a swap fee.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "lowercase")]
+pub enum FeeKind {
+    Protocol,
+    Lp,
+    Referral,
+    /// Партнёр/лаунчпад (Meteora DBC trading fee).
+    Platform,
+    /// Создатель токена (Pumpfun/Pumpswap coin creator).
+    Creator,
 }
 
 /// High level trade information extracted from a transaction.
-#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "camelCase")]
 pub struct TradeInfo {
     #[serde(rename = "type")]
@@ -192,25 +337,273 @@ pub struct TradeInfo {
     pub amms: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub route: Option<String>,
+    /// Роутер или бот, чья outer-инструкция вызвала свап (`KNOWN_ROUTERS`,
+    /// `DexParser::register_router`).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub router: Option<String>,
     pub slot: u64,
     pub timestamp: u64,
     pub signature: String,
     pub idx: String,
+    /// Индекс outer-инструкции из `idx`.
+    #[serde(default)]
+    pub instruction_index: u16,
+    /// Позиция в inner-инструкциях outer-инструкции; `None` — сама outer-инструкция.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inner_index: Option<u16>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub signer: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub dlmm: Option<DlmmSwapInfo>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub clob: Option<ClobFillInfo>,
+    /// Maker или taker со стороны `user` — для orderbook-сделок.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub liquidity_role: Option<LiquidityRole>,
+    /// Цена base в quote, заполняется при `ParseConfig::compute_prices`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub price_quote_per_base: Option<f64>,
+    /// Buy/Sell относительно base-токена (`TradeInfo::side_for`).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub side: Option<TradeSide>,
+    /// Authority входной ноги — кто реально подписал списание (делегат, бот-кошелёк).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub authority: Option<String>,
+    /// Оценка в USD от `PriceOracle`, если он подключён к парсеру.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub value_usd: Option<f64>,
+    /// Символы токенов из Metaplex metadata при `ParseConfig::resolve_metadata`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub input_symbol: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub output_symbol: Option<String>,
+    /// Балансы vault'ов пула после свапа из postTokenBalances: (input mint, output mint).
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub pool_reserves: Option<(TokenAmount, TokenAmount)>,
+    /// Позиция в блоке; заполняется в `parse_block_*`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_key: Option<EventOrderKey>,
+    /// Пометки пост-анализа (`analysis::flag_self_trades`); парсер их не ставит.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flags: Vec<TradeFlag>,
+    /// Сверка сумм с pre/post token balances signer'а и vault'ов пула.
+    #[serde(default)]
+    pub confidence: TradeConfidence,
+}
+
+impl TradeInfo {
+    /// (quote, base): сначала по `QUOTE_MINTS`, иначе quote — то, чем платят при buy
+    /// и что получают при sell/swap.
+    fn quote_and_base(&self) -> (&TokenInfo, &TokenInfo) {
+        self.quote_and_base_by(|mint| QUOTE_MINTS.iter().position(|quote| *quote == mint))
+    }
+
+    fn quote_and_base_by(
+        &self,
+        priority: impl Fn(&str) -> Option<usize>,
+    ) -> (&TokenInfo, &TokenInfo) {
+        let input_is_quote = match (
+            priority(&self.input_token.mint),
+            priority(&self.output_token.mint),
+        ) {
+            (Some(input), Some(output)) => input <= output,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => self.trade_type == TradeType::Buy,
+        };
+        if input_is_quote {
+            (&self.input_token, &self.output_token)
+        } else {
+            (&self.output_token, &self.input_token)
+        }
+    }
+
+    pub fn quote_mint(&self) -> &str {
+        &self.quote_and_base().0.mint
+    }
+
+    pub fn base_mint(&self) -> &str {
+        &self.quote_and_base().1.mint
+    }
+
+    /// Сколько quote стоит одна единица base (в UI amount). `None`, если base amount нулевой.
+    pub fn price(&self) -> Option<f64> {
+        let (quote, base) = self.quote_and_base();
+        (base.amount > 0.0).then(|| quote.amount / base.amount)
+    }
+
+    /// `quote_mint` по `ParseConfig::quote_mints`.
+    pub fn quote_mint_for(&self, config: &ParseConfig) -> &str {
+        &self.quote_and_base_by(|mint| config.quote_priority(mint)).0.mint
+    }
+
+    /// Buy, если user отдал quote (вход сделки), Sell — если получил его. Quote —
+    /// по `ParseConfig::quote_mints` с учётом `base_token`; если quote среди mint'ов
+    /// нет — по `trade_type` протокола. `None` для не-свапов (create, migrate, ...).
+    pub fn side_for(&self, config: &ParseConfig) -> Option<TradeSide> {
+        if !matches!(self.trade_type, TradeType::Buy | TradeType::Sell | TradeType::Swap) {
+            return None;
+        }
+        let priority = |mint: &str| match config.base_token {
+            BaseTokenConvention::QuotePriority => config.quote_priority(mint),
+            BaseTokenConvention::SolQuote if mint == TOKENS.SOL => Some(0),
+            BaseTokenConvention::SolQuote => config.quote_priority(mint).map(|p| p + 1),
+        };
+        let input_is_quote = match (
+            priority(&self.input_token.mint),
+            priority(&self.output_token.mint),
+        ) {
+            (Some(input), Some(output)) => input <= output,
+            (Some(_), None) => true,
+            (None, Some(_)) => false,
+            (None, None) => match self.trade_type {
+                TradeType::Buy => true,
+                TradeType::Sell => false,
+                _ => return None,
+            },
+        };
+        Some(if input_is_quote {
+            TradeSide::Buy
+        } else {
+            TradeSide::Sell
+        })
+    }
+
+    /// `authority` из входной ноги. Если протокольный парсер взял в `user` `signer[0]`
+    /// или token account входа (или не задал), а authority — подписант, `user`
+    /// переносится на него. PDA роутера подписантом не является — такой не трогаем.
+    pub fn attribute_authority(&mut self, signers: &[String]) {
+        self.authority = self.input_token.authority.clone();
+        let Some(authority) = &self.authority else {
+            return;
+        };
+        let user_is_fallback = match &self.user {
+            None => true,
+            Some(user) => {
+                Some(user) == signers.first() || Some(user) == self.input_token.source.as_ref()
+            }
+        };
+        if user_is_fallback && signers.contains(authority) {
+            self.user = Some(authority.clone());
+        }
+    }
+
+    /// `price` по `ParseConfig::quote_mints`.
+    pub fn price_for(&self, config: &ParseConfig) -> Option<f64> {
+        let (quote, base) = self.quote_and_base_by(|mint| config.quote_priority(mint));
+        (base.amount > 0.0).then(|| quote.amount / base.amount)
+    }
+}
+
+/// Bin-level details of a Meteora DLMM swap (from the program's `Swap` event).
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct DlmmSwapInfo {
+    pub lb_pair: String,
+    pub start_bin_id: i32,
+    /// Active bin after the swap.
+    pub end_bin_id: i32,
+    pub swap_for_y: bool,
+    /// Taken from the `LbPair` account when it is registered in the parser's
+    /// `ProtocolAccountStore`, otherwise derived by matching the default
+    /// `[min(x, y), max(x, y), bin_step]` PDA against the pair (cached per pair).
+    /// `None` when the account is absent and the derivation fails: the instruction
+    /// lacks the mints or the pair was created with other seeds (customizable, preset v2).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bin_step: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee_bps: Option<u64>,
+}
+
+/// Пометка сделки от анализа поверх результата парсера.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub enum TradeFlag {
+    /// Списание и зачисление у одного кошелька или связанных кошельков — фиктивный объём.
+    SelfTrade,
+}
+
+/// Насколько суммы trade подтверждены изменениями token balances (`core::confidence`).
+#[derive(
+    Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub enum TradeConfidence {
+    /// Обе ноги совпали с изменением баланса до единицы.
+    Exact,
+    /// Расхождение в пределах 1% (комиссии, хопы через тот же аккаунт).
+    Approximate,
+    /// Балансов нет (native SOL) или они не сходятся.
+    #[default]
+    Unverified,
+}
+
+/// Направление сделки user'а относительно base-токена.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "lowercase")]
+pub enum TradeSide {
+    /// Получил base, отдал quote.
+    Buy,
+    Sell,
+}
+
+/// Taker side of an orderbook fill.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "lowercase")]
+pub enum OrderSide {
+    /// Тейкер покупает base за quote.
+    #[default]
+    Bid,
+    Ask,
+}
+
+/// Role of the trade's user in an orderbook fill.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "lowercase")]
+pub enum LiquidityRole {
+    Maker,
+    Taker,
+}
+
+/// Fill details of an orderbook (CLOB) trade, e.g. Phoenix.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct ClobFillInfo {
+    pub market: String,
+    pub side: OrderSide,
+    /// Quote per base in UI units.
+    pub price: f64,
+    /// Filled base amount in UI units.
+    pub fill_size: f64,
+    /// Raw market units; converting them needs the market's tick/lot sizes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_in_ticks: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_lots_filled: Option<u64>,
+    /// Maker orders owners matched by the taker.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub makers: Vec<String>,
 }
 
 /// Detailed transfer information mirroring the TypeScript structure.
-#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "camelCase")]
 pub struct TransferInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub authority: Option<String>,
-    pub destination: String,
+    pub authority: Option<AccountKey>,
+    pub destination: AccountKey,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub destination_owner: Option<String>,
-    pub mint: String,
-    pub source: String,
+    pub destination_owner: Option<AccountKey>,
+    pub mint: AccountKey,
+    pub source: AccountKey,
     pub token_amount: TokenAmount,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source_balance: Option<TokenAmount>,
@@ -225,22 +618,126 @@ pub struct TransferInfo {
 }
 
 /// Transfer data emitted by the meta simulation.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "camelCase")]
 pub struct TransferData {
     #[serde(rename = "type")]
     pub transfer_type: String,
-    pub program_id: String,
+    pub program_id: AccountKey,
     pub info: TransferInfo,
     pub idx: String,
+    /// Индекс outer-инструкции из `idx`.
+    #[serde(default)]
+    pub instruction_index: u16,
+    /// Позиция в inner-инструкциях outer-инструкции; `None` — сама outer-инструкция.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inner_index: Option<u16>,
     pub timestamp: u64,
     pub signature: String,
     #[serde(default)]
     pub is_fee: bool,
+    /// Комиссия Token-2022 transfer fee, удержанная в аккаунте получателя
+    /// (`transferCheckedWithFee`). `info.token_amount` — сумма инструкции, брутто.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub fee_amount: Option<TokenAmount>,
+    /// Позиция в блоке; заполняется в `parse_block_*`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_key: Option<EventOrderKey>,
+}
+
+impl TransferData {
+    /// Сколько получил `destination`: сумма инструкции за вычетом transfer fee.
+    pub fn net_amount_raw(&self) -> u128 {
+        let gross = self.info.token_amount.amount.parse::<u128>().unwrap_or(0);
+        let fee = self
+            .fee_amount
+            .as_ref()
+            .and_then(|fee| fee.amount.parse::<u128>().ok())
+            .unwrap_or(0);
+        gross.saturating_sub(fee)
+    }
+
+    /// UI-сумма `net_amount_raw`; без fee совпадает с `info.token_amount`.
+    pub fn net_ui_amount(&self) -> f64 {
+        let amount = &self.info.token_amount;
+        match &self.fee_amount {
+            None => amount
+                .ui_amount
+                .unwrap_or_else(|| amount.amount.parse::<f64>().unwrap_or(0.0)),
+            Some(_) => DecimalAmount::new(self.net_amount_raw(), amount.decimals).to_f64(),
+        }
+    }
+}
+
+/// Direction of a liquidity leg relative to the pool.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "lowercase")]
+pub enum LegDirection {
+    /// В пул: create/add.
+    #[default]
+    In,
+    /// Из пула: remove.
+    Out,
+}
+
+/// One token leg of a liquidity event.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct TokenLeg {
+    pub mint: String,
+    pub amount: f64,
+    pub amount_raw: String,
+    pub decimals: u8,
+    pub direction: LegDirection,
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub balance_change: Option<String>,
+    /// Символ из Metaplex metadata при `ParseConfig::resolve_metadata`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub symbol: Option<String>,
+}
+
+impl TokenLeg {
+    /// UI-сумма считается из raw и decimals.
+    pub fn from_raw(
+        mint: impl Into<String>,
+        amount_raw: u128,
+        decimals: u8,
+        direction: LegDirection,
+    ) -> Self {
+        Self {
+            mint: mint.into(),
+            amount: crate::protocols::pumpfun::util::convert_to_ui_amount(amount_raw, decimals),
+            amount_raw: amount_raw.to_string(),
+            decimals,
+            direction,
+            balance_change: None,
+            symbol: None,
+        }
+    }
+
+    pub fn from_transfer(transfer: &TransferData, direction: LegDirection) -> Self {
+        let token_amount = &transfer.info.token_amount;
+        let amount_raw = token_amount.amount.parse::<u128>().unwrap_or(0);
+        let mut leg = Self::from_raw(
+            transfer.info.mint.clone(),
+            amount_raw,
+            token_amount.decimals,
+            direction,
+        );
+        if let Some(ui_amount) = token_amount.ui_amount {
+            leg.amount = ui_amount;
+        }
+        leg.amount_raw = token_amount.amount.clone();
+        leg
+    }
 }
 
 /// High level liquidity pool event (add/remove liquidity etc.).
-#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "camelCase")]
 pub struct PoolEvent {
     pub user: String,
@@ -254,6 +751,12 @@ pub struct PoolEvent {
     pub timestamp: u64,
     pub signature: String,
     pub idx: String,
+    /// Индекс outer-инструкции из `idx`.
+    #[serde(default)]
+    pub instruction_index: u16,
+    /// Позиция в inner-инструкциях outer-инструкции; `None` — сама outer-инструкция.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inner_index: Option<u16>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub signer: Option<Vec<String>>,
     pub pool_id: String,
@@ -262,33 +765,77 @@ pub struct PoolEvent {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pool_lp_mint: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub token0_mint: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub token0_amount: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub token0_amount_raw: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub token0_balance_change: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub token0_decimals: Option<u8>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub token1_mint: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub token1_amount: Option<f64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub token1_amount_raw: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub token1_balance_change: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub token1_decimals: Option<u8>,
-    #[serde(skip_serializing_if = "Option::is_none")]
     pub lp_amount: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lp_amount_raw: Option<String>,
+    /// Токены события в порядке пула; для двухтокенных пулов — token0, token1.
+    /// В JSON первые два дублируются плоскими `token0*`/`token1*`.
+    #[serde(flatten, with = "pool_event_tokens")]
+    #[schemars(with = "pool_event_tokens::PoolEventTokens")]
+    pub tokens: Vec<TokenLeg>,
+    /// Сумма всех `tokens` в USD по `PriceOracle`.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub value_usd: Option<f64>,
+    /// Лимиты из аргументов add/remove liquidity, если протокол их декодирует.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slippage: Option<LiquiditySlippage>,
+    /// Позиция в блоке; заполняется в `parse_block_*`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub order_key: Option<EventOrderKey>,
+}
+
+/// Аргументы add/remove liquidity относительно фактических сумм события.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct LiquiditySlippage {
+    /// Запрошенный LP: выпуск для add, сжигание для remove.
+    pub lp_amount_raw: String,
+    /// max in для add, min out для remove — в порядке `tokens`.
+    pub limits_raw: Vec<String>,
+    /// Допуск в bps от фактической суммы по самому жёсткому из токенов.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub slippage_bps: Option<u64>,
+}
+
+impl LiquiditySlippage {
+    /// `actual` — фактические суммы в порядке `limits`; `is_add` — лимиты сверху (max in).
+    pub fn new(lp_amount: u64, limits: &[u64], actual: &[u64], is_add: bool) -> Self {
+        let slippage_bps = limits
+            .iter()
+            .zip(actual)
+            .filter(|(_, actual)| **actual > 0)
+            .map(|(limit, actual)| {
+                let headroom = if is_add {
+                    limit.saturating_sub(*actual)
+                } else {
+                    actual.saturating_sub(*limit)
+                };
+                let bps = u128::from(headroom) * 10_000 / u128::from(*actual);
+                u64::try_from(bps).unwrap_or(u64::MAX)
+            })
+            .min();
+        Self {
+            lp_amount_raw: lp_amount.to_string(),
+            limits_raw: limits.iter().map(u64::to_string).collect(),
+            slippage_bps,
+        }
+    }
+}
+
+impl PoolEvent {
+    pub fn token0(&self) -> Option<&TokenLeg> {
+        self.tokens.first()
+    }
+
+    pub fn token1(&self) -> Option<&TokenLeg> {
+        self.tokens.get(1)
+    }
 }
 
 /// Meme/launch events emitted by platforms such as Pumpfun.
-#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "camelCase")]
 pub struct MemeEvent {
     #[serde(rename = "type")]
@@ -316,6 +863,8 @@ pub struct MemeEvent {
     pub total_supply: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub fee: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_recipient: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub protocol_fee: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -342,10 +891,94 @@ pub struct MemeEvent {
     pub pool_b_reserve: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pool_fee_rate: Option<f64>,
+    /// Bonding-curve state after the event (raw units).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtual_sol_reserves: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub virtual_token_reserves: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub real_sol_reserves: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub real_token_reserves: Option<u64>,
+    /// Price of one base token in quote (SOL), derived from virtual reserves.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    /// `price * total supply`, in quote (SOL).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market_cap: Option<f64>,
+    /// Только у Create: первая покупка создателя в той же транзакции или бандле
+    /// (`MemeEvent::link_dev_buys`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dev_buy: Option<DevBuy>,
+}
+
+/// Покупка создателя токена сразу после create.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct DevBuy {
+    pub signature: String,
+    pub idx: String,
+    /// Чем заплатил создатель (SOL, USDC для DBC-кривых с таким quote).
+    pub quote_token: TokenInfo,
+    pub base_token: TokenInfo,
+}
+
+impl MemeEvent {
+    /// Кошелёк создателя: `creator` из события, иначе `user` create-инструкции.
+    pub fn creator_wallet(&self) -> Option<&str> {
+        self.creator.as_deref().or(
+            (self.event_type == TradeType::Create && !self.user.is_empty())
+                .then_some(self.user.as_str()),
+        )
+    }
+
+    /// Связывает каждый Create с первой следующей за ним покупкой создателя того же
+    /// mint: заполняет `dev_buy`, а также `creator` и `fee_recipient`, если create их
+    /// не несёт. `events` — в порядке исполнения; для бандла — события всех его
+    /// транзакций подряд.
+    pub fn link_dev_buys(events: &mut [MemeEvent]) {
+        for create in 0..events.len() {
+            if events[create].event_type != TradeType::Create || events[create].dev_buy.is_some() {
+                continue;
+            }
+            let Some(creator) = events[create].creator_wallet().map(str::to_string) else {
+                continue;
+            };
+            let mint = &events[create].base_mint;
+            let Some(buy) = events[create + 1..].iter().find(|event| {
+                event.event_type == TradeType::Buy
+                    && event.base_mint == *mint
+                    && event.user == creator
+            }) else {
+                continue;
+            };
+            let (Some(quote_token), Some(base_token)) =
+                (buy.input_token.clone(), buy.output_token.clone())
+            else {
+                continue;
+            };
+            let dev_buy = DevBuy {
+                signature: buy.signature.clone(),
+                idx: buy.idx.clone(),
+                quote_token,
+                base_token,
+            };
+            let fee_recipient = buy.fee_recipient.clone();
+
+            let event = &mut events[create];
+            event.creator = Some(creator);
+            if event.fee_recipient.is_none() {
+                event.fee_recipient = fee_recipient;
+            }
+            event.dev_buy = Some(dev_buy);
+        }
+    }
 }
 
 /// Additional context information about the parsed transaction.
-#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "camelCase")]
 pub struct DexInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -357,7 +990,8 @@ pub struct DexInfo {
 }
 
 /// Aggregated parsing result returned by the Rust parser.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "camelCase")]
 pub struct ParseResult {
     pub state: bool,
@@ -385,12 +1019,164 @@ pub struct ParseResult {
     pub signature: String,
     #[serde(default)]
     pub signer: Vec<String>,
+    /// Плательщик комиссии — `signer[0]`; может не совпадать с `TradeInfo::user`.
+    #[serde(default)]
+    pub fee_payer: String,
     #[serde(default)]
     pub compute_units: u64,
+    /// `SetComputeUnitPrice`, micro-lamports за CU; 0 — не задана.
+    #[serde(default)]
+    pub cu_price_micro_lamports: u64,
+    /// Priority fee: цена CU × запрошенный лимит (не потреблённые CU).
+    #[serde(default)]
+    pub priority_fee_lamports: u64,
+    /// Сумма SOL-переводов на Jito tip-аккаунты.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jito_tip: Option<TokenAmount>,
     #[serde(default)]
     pub tx_status: TransactionStatus,
     #[serde(default)]
     pub msg: Option<String>,
+    /// Error from `meta.err` for failed transactions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_error: Option<String>,
+    /// Filled only when `ParseConfig::collect_diagnostics` is set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<ParseDiagnostics>,
+    /// Все инструкции в порядке исполнения; только при
+    /// `ParseConfig::include_classified_instructions`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "borsh", borsh(skip))]
+    pub classified_instructions: Option<Vec<ClassifiedInstruction>>,
+    /// Filled only when `ParseConfig::detect_arbitrage` is set and trades loop back to the input mint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arbitrage: Option<ArbitrageInfo>,
+    /// Только при `ParseConfig::collect_unknown_programs`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unknown_programs: Vec<UnknownProgramInfo>,
+    /// Trades, не прошедшие проверки `ParseConfig::strict`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rejected_trades: Vec<RejectedTrade>,
+    /// `trades` в компактном виде при `ParseConfig::lite_trades`; `trades` тогда пуст.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trades_lite: Vec<TradeInfoLite>,
+    /// Транзакция не прошла `ParseConfig::program_ids` / `mint_filter` и не разбиралась:
+    /// `state` при этом `true`, события пусты.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub filtered: bool,
+}
+
+/// Плоская выжимка `TradeInfo` для стриминга: без вложенных `TokenInfo`, балансов,
+/// комиссий и деталей протоколов. Суммы — raw-строки и их `f64` для отображения.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct TradeInfoLite {
+    pub signature: String,
+    pub idx: String,
+    pub slot: u64,
+    pub timestamp: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amm: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pool: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub side: Option<TradeSide>,
+    pub input_mint: String,
+    pub input_amount: f64,
+    pub input_amount_raw: String,
+    pub output_mint: String,
+    pub output_amount: f64,
+    pub output_amount_raw: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_usd: Option<f64>,
+}
+
+impl From<&TradeInfo> for TradeInfoLite {
+    fn from(trade: &TradeInfo) -> Self {
+        Self {
+            signature: trade.signature.clone(),
+            idx: trade.idx.clone(),
+            slot: trade.slot,
+            timestamp: trade.timestamp,
+            amm: trade.amm.clone(),
+            pool: trade.pool.first().cloned(),
+            user: trade.user.clone(),
+            side: trade.side,
+            input_mint: trade.input_token.mint.clone(),
+            input_amount: trade.input_token.amount,
+            input_amount_raw: trade.input_token.amount_raw.clone(),
+            output_mint: trade.output_token.mint.clone(),
+            output_amount: trade.output_token.amount,
+            output_amount_raw: trade.output_token.amount_raw.clone(),
+            value_usd: trade.value_usd,
+        }
+    }
+}
+
+/// Trade, отброшенный strict-режимом, с причинами.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct RejectedTrade {
+    pub trade: TradeInfo,
+    pub reasons: Vec<TradeRejection>,
+}
+
+/// Нарушенный инвариант trade; суммы — raw.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(tag = "reason", rename_all = "camelCase")]
+pub enum TradeRejection {
+    /// Вход и выход — один mint.
+    SameMint,
+    ZeroAmount {
+        mint: String,
+    },
+    /// Decimals ноги не совпадают с token balances транзакции.
+    DecimalsMismatch {
+        mint: String,
+        expected: u8,
+        actual: u8,
+    },
+    /// Сумма ног через token account не сходится с изменением его баланса.
+    BalanceMismatch {
+        account: String,
+        expected: String,
+        actual: String,
+    },
+}
+
+/// Инструкции программы без парсера с одним дискриминатором и числом аккаунтов —
+/// чтобы выбирать, какие протоколы поддержать следующими.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct UnknownProgramInfo {
+    pub program_id: String,
+    /// Первые 8 байт data в hex (короче, если data короче).
+    pub discriminator: String,
+    pub account_count: usize,
+    /// Сколько таких инструкций в транзакции.
+    pub count: usize,
+    /// Эвристика `try_unknown_dex` нашла по программе свап.
+    pub matched_swap: bool,
+}
+
+/// Atomic arbitrage: trades of one transaction forming a cycle back to the starting mint.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct ArbitrageInfo {
+    /// Индексы в `ParseResult::trades` в порядке хопов.
+    pub legs: Vec<usize>,
+    pub profit_mint: String,
+    /// Выход последнего хопа минус вход первого; может быть отрицательным.
+    pub profit_amount: f64,
+    pub profit_amount_raw: String,
+    pub decimals: u8,
 }
 
 impl ParseResult {
@@ -409,13 +1195,190 @@ impl ParseResult {
             timestamp: 0,
             signature: String::new(),
             signer: Vec::new(),
+            fee_payer: String::new(),
             compute_units: 0,
+            cu_price_micro_lamports: 0,
+            priority_fee_lamports: 0,
+            jito_tip: None,
             tx_status: TransactionStatus::default(),
             msg: None,
+            tx_error: None,
+            diagnostics: None,
+            classified_instructions: None,
+            arbitrage: None,
+            unknown_programs: Vec::new(),
+            rejected_trades: Vec::new(),
+            trades_lite: Vec::new(),
+            filtered: false,
+        }
+    }
+
+    /// Слот и время блока для результата и событий, у которых они не заполнены.
+    pub fn apply_block_context(&mut self, context: &BlockContext) {
+        let slot = context.slot;
+        let timestamp = context.block_time.unwrap_or(0);
+        let fill = |value: &mut u64, with: u64| {
+            if *value == 0 {
+                *value = with;
+            }
+        };
+        fill(&mut self.slot, slot);
+        fill(&mut self.timestamp, timestamp);
+        for trade in self.trades.iter_mut().chain(self.aggregate_trade.as_mut()) {
+            fill(&mut trade.slot, slot);
+            fill(&mut trade.timestamp, timestamp);
+        }
+        for pool in &mut self.liquidities {
+            fill(&mut pool.slot, slot);
+            fill(&mut pool.timestamp, timestamp);
+        }
+        for event in &mut self.meme_events {
+            fill(&mut event.slot, slot);
+            fill(&mut event.timestamp, timestamp);
+        }
+        for transfer in &mut self.transfers {
+            fill(&mut transfer.timestamp, timestamp);
+        }
+    }
+
+    /// `order_key` для trades, liquidities и transfers транзакции `tx_index` блока.
+    pub fn assign_order_keys(&mut self, slot: u64, tx_index: u32) {
+        let key = |instruction_index, inner_index| {
+            Some(EventOrderKey {
+                slot,
+                tx_index,
+                instruction_index,
+                inner_index,
+            })
+        };
+        for trade in self.trades.iter_mut().chain(self.aggregate_trade.as_mut()) {
+            trade.order_key = key(trade.instruction_index, trade.inner_index);
+        }
+        for pool in &mut self.liquidities {
+            pool.order_key = key(pool.instruction_index, pool.inner_index);
+        }
+        for transfer in &mut self.transfers {
+            transfer.order_key = key(transfer.instruction_index, transfer.inner_index);
         }
     }
 }
 
+/// Структурированный отчёт о том, как разбиралась транзакция.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct ParseDiagnostics {
+    /// Все программы транзакции в порядке появления.
+    pub program_ids: Vec<String>,
+    pub parsers_run: Vec<ParserRun>,
+    pub skipped: Vec<SkippedProgram>,
+    pub timings: StageTimings,
+}
+
+/// Стадия `try_parse`, в которой запускался парсер или пропускалась программа.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub enum ParseStage {
+    Trades,
+    Liquidity,
+    Meme,
+    Transfer,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct ParserRun {
+    pub program_id: String,
+    pub stage: ParseStage,
+    /// `true` — сработал эвристический разбор неизвестного DEX по трансферам.
+    pub unknown_dex: bool,
+    pub instructions: usize,
+    /// Сколько trades / pool events / meme events / transfers вернул парсер.
+    pub outputs: usize,
+    pub elapsed_us: u64,
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub enum SkipReason {
+    /// Не входит в `ParseConfig::program_ids`.
+    NotInProgramFilter,
+    /// Указана в `ParseConfig::ignore_program_ids`.
+    Ignored,
+    /// Нет зарегистрированного парсера, а `try_unknown_dex` выключен.
+    NoParser,
+    /// Эвристика неизвестного DEX не нашла пары трансферов с поддерживаемым токеном.
+    UnknownDexNoMatch,
+    /// Data инструкции программы не декодируются; ошибка — в `ParseResult::msg`.
+    InvalidInstructionData,
+    /// Инструкции программы не затрагивают mint'ы `ParseConfig::mint_filter`.
+    MintFilter,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedProgram {
+    pub program_id: String,
+    pub stage: ParseStage,
+    pub reason: SkipReason,
+    /// Сколько инструкций программы осталось без разбора.
+    pub instructions: usize,
+}
+
+/// Время стадий в микросекундах.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct StageTimings {
+    /// Adapter, classifier, dex info и сбор transfer actions.
+    pub setup_us: u64,
+    pub trades_us: u64,
+    pub liquidity_us: u64,
+    pub meme_us: u64,
+    pub transfers_us: u64,
+    pub total_us: u64,
+}
+
+impl ParseDiagnostics {
+    pub(crate) fn record_run(
+        &mut self,
+        program_id: &str,
+        stage: ParseStage,
+        unknown_dex: bool,
+        instructions: usize,
+        outputs: usize,
+        elapsed_us: u64,
+    ) {
+        self.parsers_run.push(ParserRun {
+            program_id: program_id.to_string(),
+            stage,
+            unknown_dex,
+            instructions,
+            outputs,
+            elapsed_us,
+        });
+    }
+
+    pub(crate) fn record_skip(
+        &mut self,
+        program_id: &str,
+        stage: ParseStage,
+        reason: SkipReason,
+        instructions: usize,
+    ) {
+        self.skipped.push(SkippedProgram {
+            program_id: program_id.to_string(),
+            stage,
+            reason,
+            instructions,
+        });
+    }
+}
+
 impl Default for ParseResult {
     fn default() -> Self {
         Self::new()
@@ -423,21 +1386,48 @@ impl Default for ParseResult {
 }
 
 /// Minimal instruction representation with bookkeeping indices.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ClassifiedInstruction {
-    pub program_id: String,
+    pub program_id: AccountKey,
     pub outer_index: usize,
     pub inner_index: Option<usize>,
     pub data: SolanaInstruction,
+    /// `data.data`, декодированные из base64.
+    #[serde(default)]
+    pub data_bytes: Vec<u8>,
+    /// Глубина вызова: 1 — outer-инструкция, 2+ — CPI. `None`, если неизвестна.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stack_height: Option<u32>,
+    /// Аргументы по IDL программы, если она есть в `IdlRegistry` парсера.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub decoded: Option<DecodedInstruction>,
+}
+
+/// Инструкция, разобранная по Anchor IDL (`core::idl`).
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct DecodedInstruction {
+    pub name: String,
+    /// Аргументы объектом `{arg: value}`; u128/i128 — строками, pubkey — base58.
+    pub args: serde_json::Value,
+    pub accounts: Vec<IdlAccountMeta>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct IdlAccountMeta {
+    /// Имя из IDL; `None` для remaining accounts.
+    pub name: Option<String>,
+    pub pubkey: AccountKey,
 }
 
 /// Basic representation of a Solana instruction.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct SolanaInstruction {
-    pub program_id: String,
-    pub accounts: Vec<String>,
+    pub program_id: AccountKey,
+    pub accounts: Vec<AccountKey>,
     #[serde(default)]
     pub data: String,
 }
@@ -449,6 +1439,17 @@ pub struct InnerInstruction {
     pub index: usize,
     #[serde(default)]
     pub instructions: Vec<SolanaInstruction>,
+    /// `stackHeight` инструкций, параллельно `instructions`; пусто, если источник
+    /// его не отдаёт (тогда адаптер восстанавливает глубину по `invoke [N]` в логах).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub stack_heights: Vec<Option<u32>>,
+}
+
+impl InnerInstruction {
+    /// Глубина CPI `instructions[position]`: 2 — вызов из outer-инструкции.
+    pub fn stack_height(&self, position: usize) -> Option<u32> {
+        self.stack_heights.get(position).copied().flatten()
+    }
 }
 
 /// Transaction meta information used by the adapter.
@@ -462,6 +1463,12 @@ pub struct TransactionMeta {
     pub sol_balance_changes: HashMap<String, BalanceChange>,
     #[serde(default)]
     pub token_balance_changes: HashMap<String, HashMap<String, BalanceChange>>,
+    /// `meta.err` as compact JSON, e.g. `{"InstructionError":[2,{"Custom":6001}]}`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub err: Option<String>,
+    /// `meta.logMessages`; нужны парсерам, читающим события из логов (`Program data:`).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub log_messages: Vec<String>,
 }
 
 /// Simplified transaction representation consumed by the parser.
@@ -504,22 +1511,170 @@ pub struct SolanaBlock {
 pub enum BlockInput {
     Raw {
         transactions: Vec<serde_json::Value>,
+        #[serde(default)]
+        context: Option<BlockContext>,
     },
     Parsed {
         block: SolanaBlock,
     },
+    /// `result` ответа `getBlock` как есть (`json`, `jsonParsed`, `base64`).
+    RpcJson {
+        block: serde_json::Value,
+        #[serde(default)]
+        slot: Option<u64>,
+    },
+}
+
+/// Слот, время и blockhash блока для транзакций, в JSON которых их нет (`getBlock`).
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockContext {
+    pub slot: u64,
+    #[serde(default)]
+    pub block_time: Option<u64>,
+    #[serde(default)]
+    pub blockhash: Option<String>,
+}
+
+impl BlockContext {
+    /// Из ответа `getBlock`: `blockTime`, `blockhash` и `slot`, если провайдер его добавил,
+    /// иначе `parentSlot + 1` (неверно только сразу после пропущенного слота).
+    pub fn from_block_json(block: &serde_json::Value) -> Self {
+        Self {
+            slot: block
+                .get("slot")
+                .and_then(|v| v.as_u64())
+                .or_else(|| block.get("parentSlot").and_then(|v| v.as_u64()).map(|p| p + 1))
+                .unwrap_or(0),
+            block_time: block.get("blockTime").and_then(|v| v.as_u64()),
+            blockhash: block
+                .get("blockhash")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+        }
+    }
 }
 
 /// Wrapper returned by `parse_block` helper functions.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
 #[serde(rename_all = "camelCase")]
 pub struct BlockParseResult {
     pub slot: u64,
     #[serde(default)]
     pub timestamp: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blockhash: Option<String>,
     pub transactions: Vec<ParseResult>,
 }
 
+impl BlockParseResult {
+    /// Результат блока из транзакций в порядке входа: повторы одной подписи
+    /// (ретраи) схлопываются — остаётся успешная, иначе первая; событиям
+    /// проставляется `order_key`.
+    pub fn new(slot: u64, timestamp: Option<u64>, transactions: Vec<ParseResult>) -> Self {
+        let mut deduped: Vec<ParseResult> = Vec::with_capacity(transactions.len());
+        let mut positions: HashMap<String, usize> = HashMap::with_capacity(transactions.len());
+        for tx in transactions {
+            if tx.signature.is_empty() {
+                deduped.push(tx);
+                continue;
+            }
+            match positions.get(&tx.signature) {
+                Some(&position) => {
+                    let kept = &deduped[position];
+                    if kept.tx_status == TransactionStatus::Failed
+                        && tx.tx_status != TransactionStatus::Failed
+                    {
+                        deduped[position] = tx;
+                    }
+                }
+                None => {
+                    positions.insert(tx.signature.clone(), deduped.len());
+                    deduped.push(tx);
+                }
+            }
+        }
+        for (tx_index, tx) in deduped.iter_mut().enumerate() {
+            let slot = if tx.slot != 0 { tx.slot } else { slot };
+            tx.assign_order_keys(slot, tx_index as u32);
+        }
+        Self {
+            slot,
+            timestamp,
+            blockhash: None,
+            transactions: deduped,
+        }
+    }
+
+    /// Trades, liquidity и transfers всех транзакций в порядке исполнения.
+    /// В пределах одной инструкции: transfers, затем trade, затем liquidity.
+    pub fn iter_events_ordered(&self) -> impl Iterator<Item = BlockEvent<'_>> {
+        let mut events: Vec<BlockEvent<'_>> = self
+            .transactions
+            .iter()
+            .flat_map(|tx| {
+                let transfers = tx.transfers.iter().map(BlockEvent::Transfer);
+                let trades = tx.trades.iter().map(BlockEvent::Trade);
+                let liquidities = tx.liquidities.iter().map(BlockEvent::Liquidity);
+                transfers.chain(trades).chain(liquidities)
+            })
+            .collect();
+        events.sort_by_key(|event| (event.order_key(), event.rank()));
+        events.into_iter()
+    }
+}
+
+/// Каноническая позиция события: `(slot, tx_index, instruction_index, inner_index)`.
+/// `inner_index: None` — сама outer-инструкция, идёт раньше своих inner.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, JsonSchema)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct EventOrderKey {
+    pub slot: u64,
+    pub tx_index: u32,
+    pub instruction_index: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inner_index: Option<u16>,
+}
+
+/// Событие блока из `BlockParseResult::iter_events_ordered`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlockEvent<'a> {
+    Trade(&'a TradeInfo),
+    Liquidity(&'a PoolEvent),
+    Transfer(&'a TransferData),
+}
+
+impl BlockEvent<'_> {
+    pub fn order_key(&self) -> EventOrderKey {
+        match self {
+            Self::Trade(trade) => trade.order_key,
+            Self::Liquidity(pool) => pool.order_key,
+            Self::Transfer(transfer) => transfer.order_key,
+        }
+        .unwrap_or_default()
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Self::Transfer(_) => 0,
+            Self::Trade(_) => 1,
+            Self::Liquidity(_) => 2,
+        }
+    }
+}
+
+/// JSON Schema выходных типов: корень — `ParseResult`, в `definitions` также `BlockParseResult`.
+/// Для валидации результата на стороне потребителей на других языках.
+pub fn schema() -> serde_json::Value {
+    let mut generator = schemars::gen::SchemaSettings::draft07().into_generator();
+    generator.subschema_for::<BlockParseResult>();
+    let root = generator.into_root_schema_for::<ParseResult>();
+    serde_json::to_value(root).expect("JSON schema is serializable")
+}
+
 /// Convenience alias used by parsers.
 pub type TransferMap = HashMap<String, Vec<TransferData>>;
 
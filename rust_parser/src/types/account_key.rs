@@ -0,0 +1,277 @@
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+use schemars::JsonSchema;
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Base58-адрес аккаунта в разделяемом `Arc<str>`: клон — инкремент счётчика,
+/// а не аллокация. В JSON, JSON Schema и borsh — обычная строка.
+#[derive(Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AccountKey(Arc<str>);
+
+impl AccountKey {
+    pub fn new(key: &str) -> Self {
+        Self(Arc::from(key))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Default for AccountKey {
+    fn default() -> Self {
+        Self(Arc::from(""))
+    }
+}
+
+impl Deref for AccountKey {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for AccountKey {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for AccountKey {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for AccountKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl fmt::Debug for AccountKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+impl From<&str> for AccountKey {
+    fn from(key: &str) -> Self {
+        Self::new(key)
+    }
+}
+
+impl From<String> for AccountKey {
+    fn from(key: String) -> Self {
+        Self(Arc::from(key))
+    }
+}
+
+impl From<&String> for AccountKey {
+    fn from(key: &String) -> Self {
+        Self::new(key)
+    }
+}
+
+impl From<AccountKey> for String {
+    fn from(key: AccountKey) -> Self {
+        key.0.to_string()
+    }
+}
+
+impl PartialEq<str> for AccountKey {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl PartialEq<&str> for AccountKey {
+    fn eq(&self, other: &&str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for AccountKey {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl PartialEq<AccountKey> for str {
+    fn eq(&self, other: &AccountKey) -> bool {
+        self == &*other.0
+    }
+}
+
+impl PartialEq<AccountKey> for &str {
+    fn eq(&self, other: &AccountKey) -> bool {
+        *self == &*other.0
+    }
+}
+
+impl PartialEq<AccountKey> for String {
+    fn eq(&self, other: &AccountKey) -> bool {
+        **self == *other.0
+    }
+}
+
+impl Serialize for AccountKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for AccountKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_str(AccountKeyVisitor)
+    }
+}
+
+/// Строка читается по ссылке (без промежуточного `String`) и, внутри
+/// [`KeyInterner::scope`], берётся из пула.
+struct AccountKeyVisitor;
+
+impl Visitor<'_> for AccountKeyVisitor {
+    type Value = AccountKey;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a base58 account key")
+    }
+
+    fn visit_str<E: de::Error>(self, key: &str) -> Result<AccountKey, E> {
+        Ok(KeyInterner::intern_scoped(key))
+    }
+}
+
+impl JsonSchema for AccountKey {
+    fn schema_name() -> String {
+        String::schema_name()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        String::json_schema(gen)
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshSerialize for AccountKey {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        BorshSerialize::serialize(&*self.0, writer)
+    }
+}
+
+#[cfg(feature = "borsh")]
+impl BorshDeserialize for AccountKey {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        String::deserialize_reader(reader).map(|key| KeyInterner::intern_scoped(&key))
+    }
+}
+
+thread_local! {
+    static SCOPED: RefCell<Option<KeyInterner>> = const { RefCell::new(None) };
+}
+
+/// Пул ключей: одинаковые адреса (account keys, mint'ы, программы) одной транзакции
+/// или блока разделяют одну аллокацию.
+#[derive(Debug, Default)]
+pub struct KeyInterner {
+    keys: HashSet<AccountKey>,
+}
+
+impl KeyInterner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, key: &str) -> AccountKey {
+        if let Some(existing) = self.keys.get(key) {
+            return existing.clone();
+        }
+        let key = AccountKey::new(key);
+        self.keys.insert(key.clone());
+        key
+    }
+
+    /// Выполняет `f` с пулом на текущем потоке: все `AccountKey`, десериализованные
+    /// внутри (serde, `SolanaTransaction` из JSON), разделяют одну аллокацию на адрес.
+    /// Пул живёт до конца внешнего `scope`; вложенные вызовы используют его же.
+    pub fn scope<R>(f: impl FnOnce() -> R) -> R {
+        struct Reset;
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                SCOPED.with(|scoped| scoped.borrow_mut().take());
+            }
+        }
+
+        if SCOPED.with(|scoped| scoped.borrow().is_some()) {
+            return f();
+        }
+        SCOPED.with(|scoped| *scoped.borrow_mut() = Some(KeyInterner::new()));
+        let _reset = Reset;
+        f()
+    }
+
+    fn intern_scoped(key: &str) -> AccountKey {
+        SCOPED.with(|scoped| match scoped.borrow_mut().as_mut() {
+            Some(interner) => interner.intern(key),
+            None => AccountKey::new(key),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interner_shares_allocation_and_serializes_as_string() {
+        let mut interner = KeyInterner::new();
+        let a = interner.intern("So11111111111111111111111111111111111111112");
+        let b = interner.intern("So11111111111111111111111111111111111111112");
+        assert!(std::ptr::eq(a.as_str(), b.as_str()));
+        assert_eq!(interner.len(), 1);
+
+        let json = serde_json::to_string(&a).unwrap();
+        assert_eq!(json, "\"So11111111111111111111111111111111111111112\"");
+        let back: AccountKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, a);
+        assert_eq!(back, "So11111111111111111111111111111111111111112");
+    }
+
+    #[test]
+    fn deserialization_interns_inside_scope() {
+        let json = r#"["So11111111111111111111111111111111111111112","So11111111111111111111111111111111111111112"]"#;
+
+        let keys: Vec<AccountKey> = KeyInterner::scope(|| serde_json::from_str(json).unwrap());
+        assert!(std::ptr::eq(keys[0].as_str(), keys[1].as_str()));
+
+        let keys: Vec<AccountKey> = serde_json::from_str(json).unwrap();
+        assert!(!std::ptr::eq(keys[0].as_str(), keys[1].as_str()));
+        // serde_json::Value отдаёт строки владением, а не по ссылке
+        let value: serde_json::Value = serde_json::from_str(json).unwrap();
+        let keys: Vec<AccountKey> =
+            KeyInterner::scope(|| serde_json::from_value(value).unwrap());
+        assert!(std::ptr::eq(keys[0].as_str(), keys[1].as_str()));
+    }
+}
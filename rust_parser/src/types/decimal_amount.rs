@@ -0,0 +1,92 @@
+use std::fmt;
+
+/// Точная сумма токена: raw `u128` и decimals. Суммы считаются в raw, а `f64`
+/// (`TokenInfo::amount`, `ui_amount`) — только для отображения: у meme-токенов
+/// с большим supply он теряет младшие разряды.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DecimalAmount {
+    raw: u128,
+    decimals: u8,
+}
+
+impl DecimalAmount {
+    pub fn new(raw: u128, decimals: u8) -> Self {
+        Self { raw, decimals }
+    }
+
+    /// Raw-строка из meta/инструкции (`"1500000"`), без знака и дробной части.
+    pub fn parse(raw: &str, decimals: u8) -> Option<Self> {
+        raw.parse().ok().map(|raw| Self::new(raw, decimals))
+    }
+
+    pub fn raw(&self) -> u128 {
+        self.raw
+    }
+
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+
+    /// Та же сумма с большим числом знаков; `None` при переполнении или потере точности.
+    pub fn rescale(self, decimals: u8) -> Option<Self> {
+        let shift = decimals.checked_sub(self.decimals)?;
+        let raw = 10u128
+            .checked_pow(u32::from(shift))
+            .and_then(|factor| self.raw.checked_mul(factor))?;
+        Some(Self::new(raw, decimals))
+    }
+
+    /// Сумма в большем из decimals.
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let decimals = self.decimals.max(other.decimals);
+        let (a, b) = (self.rescale(decimals)?, other.rescale(decimals)?);
+        Some(Self::new(a.raw.checked_add(b.raw)?, decimals))
+    }
+
+    /// Ближайшее `f64` к точному значению (через десятичную строку, без двойного округления).
+    pub fn to_f64(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::NAN)
+    }
+}
+
+/// Точная десятичная запись без хвостовых нулей: `1500000` при 6 decimals — `1.5`.
+impl fmt::Display for DecimalAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.raw.to_string();
+        let decimals = usize::from(self.decimals);
+        if decimals == 0 {
+            return f.write_str(&digits);
+        }
+        let padded = format!("{digits:0>width$}", width = decimals + 1);
+        let (int, frac) = padded.split_at(padded.len() - decimals);
+        let frac = frac.trim_end_matches('0');
+        if frac.is_empty() {
+            f.write_str(int)
+        } else {
+            write!(f, "{int}.{frac}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_large_supply_amounts_exact() {
+        // 1e18 raw при 6 decimals: f64-сумма двух таких ног теряет последние разряды
+        let leg = DecimalAmount::parse("999999999999999999", 6).unwrap();
+        let total = leg.checked_add(DecimalAmount::new(1, 6)).unwrap();
+        assert_eq!(total.raw(), 1_000_000_000_000_000_000);
+        assert_eq!(total.to_string(), "1000000000000");
+        assert_eq!(leg.to_string(), "999999999999.999999");
+        assert_eq!(DecimalAmount::new(42, 9).to_string(), "0.000000042");
+
+        let mixed = DecimalAmount::new(15, 1)
+            .checked_add(DecimalAmount::new(25, 2))
+            .unwrap();
+        assert_eq!((mixed.raw(), mixed.decimals()), (175, 2));
+        assert_eq!(mixed.to_f64(), 1.75);
+        assert!(DecimalAmount::parse("-1", 6).is_none());
+    }
+}
@@ -0,0 +1,177 @@
+//! JSON-форма `PoolEvent::tokens`: массив `tokens` плюс плоские `token0*`/`token1*`,
+//! как в TS `PoolEvent` и в нашем JSON до перехода на `Vec<TokenLeg>`. Подключается
+//! через `#[serde(flatten, with = "pool_event_tokens")]`.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{LegDirection, TokenLeg};
+
+/// Схема и десериализация; старые события без `tokens` собираются из плоских полей.
+#[derive(Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub(super) struct PoolEventTokens {
+    #[serde(default)]
+    tokens: Vec<TokenLeg>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token0_mint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token0_amount: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token0_amount_raw: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token0_balance_change: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token0_decimals: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token1_mint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token1_amount: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token1_amount_raw: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token1_balance_change: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token1_decimals: Option<u8>,
+}
+
+/// То же без копирования ног при сериализации.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PoolEventTokensRef<'a> {
+    tokens: &'a [TokenLeg],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token0_mint: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token0_amount: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token0_amount_raw: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token0_balance_change: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token0_decimals: Option<u8>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token1_mint: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token1_amount: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token1_amount_raw: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token1_balance_change: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    token1_decimals: Option<u8>,
+}
+
+pub(super) fn serialize<S: Serializer>(
+    tokens: &[TokenLeg],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let token0 = tokens.first();
+    let token1 = tokens.get(1);
+    PoolEventTokensRef {
+        tokens,
+        token0_mint: token0.map(|leg| leg.mint.as_str()),
+        token0_amount: token0.map(|leg| leg.amount),
+        token0_amount_raw: token0.map(|leg| leg.amount_raw.as_str()),
+        token0_balance_change: token0.and_then(|leg| leg.balance_change.as_deref()),
+        token0_decimals: token0.map(|leg| leg.decimals),
+        token1_mint: token1.map(|leg| leg.mint.as_str()),
+        token1_amount: token1.map(|leg| leg.amount),
+        token1_amount_raw: token1.map(|leg| leg.amount_raw.as_str()),
+        token1_balance_change: token1.and_then(|leg| leg.balance_change.as_deref()),
+        token1_decimals: token1.map(|leg| leg.decimals),
+    }
+    .serialize(serializer)
+}
+
+pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<TokenLeg>, D::Error> {
+    let repr = PoolEventTokens::deserialize(deserializer)?;
+    if !repr.tokens.is_empty() {
+        return Ok(repr.tokens);
+    }
+    let flat = [
+        (
+            repr.token0_mint,
+            repr.token0_amount,
+            repr.token0_amount_raw,
+            repr.token0_balance_change,
+            repr.token0_decimals,
+        ),
+        (
+            repr.token1_mint,
+            repr.token1_amount,
+            repr.token1_amount_raw,
+            repr.token1_balance_change,
+            repr.token1_decimals,
+        ),
+    ];
+    // ноги по порядку: без token0 token1 не читается
+    Ok(flat
+        .into_iter()
+        .map_while(|(mint, amount, amount_raw, balance_change, decimals)| {
+            Some(TokenLeg {
+                mint: mint?,
+                amount: amount.unwrap_or_default(),
+                amount_raw: amount_raw.unwrap_or_else(|| "0".to_string()),
+                decimals: decimals.unwrap_or_default(),
+                direction: LegDirection::default(),
+                balance_change,
+                symbol: None,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::{LegDirection, PoolEvent, TokenLeg};
+
+    #[test]
+    fn serializes_flat_token_fields_next_to_tokens() {
+        let event = PoolEvent {
+            tokens: vec![
+                TokenLeg::from_raw("A", 1_500_000, 6, LegDirection::In),
+                TokenLeg::from_raw("B", 2, 0, LegDirection::In),
+            ],
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["token0Mint"], "A");
+        assert_eq!(json["token0Amount"], 1.5);
+        assert_eq!(json["token0AmountRaw"], "1500000");
+        assert_eq!(json["token1Mint"], "B");
+        assert_eq!(json["tokens"].as_array().unwrap().len(), 2);
+
+        let back: PoolEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(back, event);
+    }
+
+    #[test]
+    fn single_leg_event_has_no_token1_fields() {
+        let event = PoolEvent {
+            tokens: vec![TokenLeg::from_raw("A", 1, 0, LegDirection::Out)],
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert!(json.get("token1Mint").is_none());
+        assert!(json.get("token1AmountRaw").is_none());
+    }
+
+    #[test]
+    fn legacy_flat_event_deserializes_into_tokens() {
+        let json = serde_json::json!({
+            "user": "u", "type": "ADD", "slot": 1, "timestamp": 2, "signature": "s",
+            "idx": "0-0", "poolId": "p",
+            "token0Mint": "A", "token0Amount": 1.5, "token0AmountRaw": "1500000", "token0Decimals": 6,
+            "token1Mint": "B", "token1AmountRaw": "7",
+        });
+        let event: PoolEvent = serde_json::from_value(json).unwrap();
+        assert_eq!(event.tokens.len(), 2);
+        assert_eq!(event.tokens[0].amount_raw, "1500000");
+        assert_eq!(event.tokens[0].decimals, 6);
+        assert_eq!(event.tokens[1].mint, "B");
+        assert_eq!(event.tokens[1].amount_raw, "7");
+    }
+}
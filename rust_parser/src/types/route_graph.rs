@@ -0,0 +1,211 @@
+use std::fmt::Write;
+
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{ParseResult, TradeInfo};
+
+/// Маршрут свапов транзакции: узлы — mint'ы, рёбра — trades в порядке исполнения.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct RouteGraph {
+    pub signature: String,
+    /// В порядке первого появления mint'а в маршруте.
+    pub nodes: Vec<RouteNode>,
+    /// В порядке исполнения (`instruction_index`, `inner_index`).
+    pub edges: Vec<RouteEdge>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct RouteNode {
+    pub mint: String,
+    pub decimals: u8,
+    /// Из `TradeInfo::input_symbol`/`output_symbol`, если metadata разрешались.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+}
+
+/// Хоп маршрута; `from`/`to` — индексы в `RouteGraph::nodes`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct RouteEdge {
+    pub from: usize,
+    pub to: usize,
+    /// Индекс в `ParseResult::trades`.
+    pub trade_index: usize,
+    pub amount_in: f64,
+    pub amount_in_raw: String,
+    pub amount_out: f64,
+    pub amount_out_raw: String,
+    /// `TradeInfo::amm`, иначе `program_id`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub venue: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pool: Option<String>,
+    pub idx: String,
+}
+
+impl RouteGraph {
+    /// Граф по `trades` в порядке исполнения; `ParseResult::aggregate_trade` не входит.
+    pub fn from_trades(signature: &str, trades: &[TradeInfo]) -> Self {
+        let mut order: Vec<usize> = (0..trades.len()).collect();
+        order.sort_by_key(|&i| (trades[i].instruction_index, trades[i].inner_index));
+
+        let mut graph = Self {
+            signature: signature.to_string(),
+            ..Self::default()
+        };
+        for trade_index in order {
+            let trade = &trades[trade_index];
+            let from = graph.node(
+                &trade.input_token.mint,
+                trade.input_token.decimals,
+                &trade.input_symbol,
+            );
+            let to = graph.node(
+                &trade.output_token.mint,
+                trade.output_token.decimals,
+                &trade.output_symbol,
+            );
+            graph.edges.push(RouteEdge {
+                from,
+                to,
+                trade_index,
+                amount_in: trade.input_token.amount,
+                amount_in_raw: trade.input_token.amount_raw.clone(),
+                amount_out: trade.output_token.amount,
+                amount_out_raw: trade.output_token.amount_raw.clone(),
+                venue: trade.amm.clone().or_else(|| trade.program_id.clone()),
+                pool: trade.pool.first().cloned(),
+                idx: trade.idx.clone(),
+            });
+        }
+        graph
+    }
+
+    fn node(&mut self, mint: &str, decimals: u8, symbol: &Option<String>) -> usize {
+        if let Some(pos) = self.nodes.iter().position(|node| node.mint == mint) {
+            let node = &mut self.nodes[pos];
+            if node.symbol.is_none() {
+                node.symbol = symbol.clone();
+            }
+            return pos;
+        }
+        self.nodes.push(RouteNode {
+            mint: mint.to_string(),
+            decimals,
+            symbol: symbol.clone(),
+        });
+        self.nodes.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edges.is_empty()
+    }
+
+    /// Маршрут замыкается на входной mint первого хопа.
+    pub fn is_cyclic(&self) -> bool {
+        match (self.edges.first(), self.edges.last()) {
+            (Some(first), Some(last)) => first.from == last.to,
+            _ => false,
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    /// Graphviz `digraph`: узлы подписаны символом или mint'ом, рёбра — номером
+    /// хопа, venue и суммами.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph route {\n  rankdir=LR;\n");
+        if !self.signature.is_empty() {
+            let _ = writeln!(dot, "  label=\"{}\";", escape(&self.signature));
+        }
+        for (i, node) in self.nodes.iter().enumerate() {
+            let label = match &node.symbol {
+                Some(symbol) => format!("{}\\n{}", escape(symbol), escape(&node.mint)),
+                None => escape(&node.mint),
+            };
+            let _ = writeln!(dot, "  n{i} [label=\"{label}\"];");
+        }
+        for (hop, edge) in self.edges.iter().enumerate() {
+            let venue = edge.venue.as_deref().unwrap_or("unknown");
+            let _ = writeln!(
+                dot,
+                "  n{} -> n{} [label=\"#{} {}\\n{} -> {}\"];",
+                edge.from,
+                edge.to,
+                hop + 1,
+                escape(venue),
+                edge.amount_in,
+                edge.amount_out
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl ParseResult {
+    /// Хопы свапов транзакции как граф mint'ов (`RouteGraph::to_dot`, `to_json`).
+    pub fn route_graph(&self) -> RouteGraph {
+        RouteGraph::from_trades(&self.signature, &self.trades)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::TokenInfo;
+
+    fn hop(input: &str, output: &str, amm: &str, instruction_index: u16) -> TradeInfo {
+        let token = |mint: &str, amount: f64| TokenInfo {
+            mint: mint.to_string(),
+            amount,
+            amount_raw: (amount as u64).to_string(),
+            ..TokenInfo::default()
+        };
+        TradeInfo {
+            input_token: token(input, 10.0),
+            output_token: token(output, 20.0),
+            amm: Some(amm.to_string()),
+            instruction_index,
+            ..TradeInfo::default()
+        }
+    }
+
+    #[test]
+    fn builds_route_in_execution_order() {
+        let mut result = ParseResult::new();
+        result.signature = "sig".to_string();
+        result.trades = vec![
+            hop("USDC", "SOL", "Orca", 3),
+            hop("SOL", "BONK", "Raydium", 1),
+            hop("BONK", "USDC", "Meteora", 2),
+        ];
+
+        let graph = result.route_graph();
+        let mints: Vec<&str> = graph.nodes.iter().map(|n| n.mint.as_str()).collect();
+        assert_eq!(mints, ["SOL", "BONK", "USDC"]);
+        let trades: Vec<usize> = graph.edges.iter().map(|e| e.trade_index).collect();
+        assert_eq!(trades, [1, 2, 0]);
+        assert!(graph.is_cyclic());
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("n0 -> n1 [label=\"#1 Raydium\\n10 -> 20\"];"));
+        let json: serde_json::Value = serde_json::from_str(&graph.to_json().unwrap()).unwrap();
+        assert_eq!(json["edges"][0]["venue"], "Raydium");
+        assert_eq!(json["edges"][2]["to"], 0);
+    }
+}
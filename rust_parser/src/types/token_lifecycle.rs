@@ -0,0 +1,184 @@
+#[cfg(feature = "borsh")]
+use borsh::{BorshDeserialize, BorshSerialize};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{MemeEvent, TokenAmount, TokenInfo, TradeSide, TradeType};
+
+/// Общие поля событий жизненного цикла токена.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleMeta {
+    pub mint: String,
+    /// Launchpad токена (`MemeEvent::protocol`): Pumpfun, MeteoraDBC, RaydiumLaunchLab, Moonshot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol: Option<String>,
+    pub signature: String,
+    pub slot: u64,
+    pub timestamp: u64,
+    pub idx: String,
+}
+
+/// Почему токен помечен как rug — эвристика `lifecycle::LifecycleTracker`.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(rename_all = "camelCase")]
+pub enum RugReason {
+    /// Создатель продал на кривой почти всё, что купил.
+    CreatorDump,
+    /// Из пула, куда мигрировал токен, вывели ликвидность.
+    LiquidityRemoved,
+}
+
+/// Нормализованное событие meme-токена: create → торговля на кривой → graduation → пул.
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
+#[cfg_attr(feature = "borsh", derive(BorshSerialize, BorshDeserialize))]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TokenLifecycleEvent {
+    #[serde(rename_all = "camelCase")]
+    Created {
+        #[serde(flatten)]
+        meta: LifecycleMeta,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        creator: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        symbol: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        uri: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bonding_curve: Option<String>,
+    },
+    #[serde(rename_all = "camelCase")]
+    CurveTrade {
+        #[serde(flatten)]
+        meta: LifecycleMeta,
+        side: TradeSide,
+        user: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base_amount: Option<TokenAmount>,
+        quote_mint: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        quote_amount: Option<TokenAmount>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        price: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        market_cap: Option<f64>,
+    },
+    /// Кривая завершена. DBC и Moonshot без complete-события получают его
+    /// от трекера перед `PoolCreated`.
+    #[serde(rename_all = "camelCase")]
+    Graduated {
+        #[serde(flatten)]
+        meta: LifecycleMeta,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bonding_curve: Option<String>,
+    },
+    /// Миграция ликвидности в AMM-пул.
+    #[serde(rename_all = "camelCase")]
+    PoolCreated {
+        #[serde(flatten)]
+        meta: LifecycleMeta,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pool: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pool_dex: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base_reserve: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        quote_reserve: Option<f64>,
+    },
+    #[serde(rename_all = "camelCase")]
+    Rugged {
+        #[serde(flatten)]
+        meta: LifecycleMeta,
+        reason: RugReason,
+        /// Кошелёк, чьё действие сработало эвристикой.
+        wallet: String,
+    },
+}
+
+impl TokenLifecycleEvent {
+    /// Событие для одного `MemeEvent`, без учёта истории mint'а; `Rugged` так не
+    /// получить — он требует `lifecycle::LifecycleTracker`.
+    pub fn from_meme_event(event: &MemeEvent) -> Option<Self> {
+        let meta = LifecycleMeta {
+            mint: event.base_mint.clone(),
+            protocol: event.protocol.clone(),
+            signature: event.signature.clone(),
+            slot: event.slot,
+            timestamp: event.timestamp,
+            idx: event.idx.clone(),
+        };
+        if meta.mint.is_empty() {
+            return None;
+        }
+        Some(match event.event_type {
+            TradeType::Create => Self::Created {
+                meta,
+                creator: event.creator.clone().or_else(|| non_empty(&event.user)),
+                name: event.name.clone(),
+                symbol: event.symbol.clone(),
+                uri: event.uri.clone(),
+                bonding_curve: event.bonding_curve.clone(),
+            },
+            TradeType::Buy | TradeType::Sell => {
+                let mut tokens = event.input_token.iter().chain(&event.output_token);
+                let amount = |token: &TokenInfo| {
+                    TokenAmount::new(&token.amount_raw, token.decimals, Some(token.amount))
+                };
+                let base_amount = tokens
+                    .clone()
+                    .find(|t| t.mint == event.base_mint)
+                    .map(amount);
+                let quote_amount = tokens.find(|t| t.mint != event.base_mint).map(amount);
+                Self::CurveTrade {
+                    meta,
+                    side: if event.event_type == TradeType::Buy {
+                        TradeSide::Buy
+                    } else {
+                        TradeSide::Sell
+                    },
+                    user: event.user.clone(),
+                    base_amount,
+                    quote_mint: event.quote_mint.clone(),
+                    quote_amount,
+                    price: event.price,
+                    market_cap: event.market_cap,
+                }
+            }
+            TradeType::Complete => Self::Graduated {
+                meta,
+                bonding_curve: event.bonding_curve.clone(),
+            },
+            TradeType::Migrate => Self::PoolCreated {
+                meta,
+                pool: event.pool.clone(),
+                pool_dex: event.pool_dex.clone(),
+                base_reserve: event.pool_a_reserve,
+                quote_reserve: event.pool_b_reserve,
+            },
+            _ => return None,
+        })
+    }
+
+    pub fn meta(&self) -> &LifecycleMeta {
+        match self {
+            Self::Created { meta, .. }
+            | Self::CurveTrade { meta, .. }
+            | Self::Graduated { meta, .. }
+            | Self::PoolCreated { meta, .. }
+            | Self::Rugged { meta, .. } => meta,
+        }
+    }
+
+    pub fn mint(&self) -> &str {
+        &self.meta().mint
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    (!value.is_empty()).then(|| value.to_string())
+}
@@ -0,0 +1,40 @@
+//! wasm-bindgen обёртки для браузера и edge-рантаймов.
+//!
+//! Вход — JSON-строка в любом виде, который принимает [`transaction_from_json`]
+//! (тот же декодер, что у N-API биндингов); выход — JSON-строка `ParseResult`
+//! (в форме TS-пакета при `ParseConfig::ts_compat_output`).
+
+use once_cell::sync::Lazy;
+use wasm_bindgen::prelude::*;
+
+use crate::compat;
+use crate::config::ParseConfig;
+use crate::core::dex_parser::DexParser;
+use crate::core::rpc_json::transaction_from_json;
+use crate::types::SolanaTransaction;
+
+static PARSER: Lazy<DexParser> = Lazy::new(DexParser::new);
+
+#[wasm_bindgen(js_name = parseAll)]
+pub fn parse_all(tx_json: &str, config_json: Option<String>) -> Result<String, JsError> {
+    let tx = decode_transaction(tx_json)?;
+    let config = config_json
+        .map(|json| serde_json::from_str::<ParseConfig>(&json))
+        .transpose()?;
+    let config = config.unwrap_or_default();
+    let result = PARSER.parse_all(tx, Some(config.clone()));
+    Ok(compat::to_json(&result, &config)?)
+}
+
+#[wasm_bindgen(js_name = parseTrades)]
+pub fn parse_trades(tx_json: &str, config_json: Option<String>) -> Result<String, JsError> {
+    let tx = decode_transaction(tx_json)?;
+    let config = config_json
+        .map(|json| serde_json::from_str::<ParseConfig>(&json))
+        .transpose()?;
+    Ok(serde_json::to_string(&PARSER.parse_trades(tx, config))?)
+}
+
+fn decode_transaction(tx_json: &str) -> Result<SolanaTransaction, JsError> {
+    Ok(transaction_from_json(&serde_json::from_str(tx_json)?)?)
+}
@@ -0,0 +1,79 @@
+//! Число аллокаций на горячем пути: классификатор инструкций и десериализация ключей
+//! не должны аллоцировать на каждую инструкцию / каждое вхождение адреса.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use solana_dex_parser::core::instruction_classifier::InstructionClassifier;
+use solana_dex_parser::core::transaction_adapter::TransactionAdapter;
+use solana_dex_parser::types::{AccountKey, KeyInterner};
+use solana_dex_parser::{ParseConfig, SolanaInstruction, SolanaTransaction};
+
+struct CountingAlloc;
+
+thread_local! {
+    // считаем только на потоке теста: харнесс параллельно аллоцирует в других
+    static COUNT: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = COUNT.try_with(|count| count.set(count.get().map(|n| n + 1)));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    COUNT.with(|count| count.set(Some(0)));
+    let result = f();
+    let allocated = COUNT.with(|count| count.take()).unwrap_or(0);
+    (result, allocated)
+}
+
+const JUPITER: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+const INSTRUCTIONS: usize = 1_000;
+
+#[test]
+fn classifier_does_not_allocate_per_instruction() {
+    let program_id = AccountKey::new(JUPITER);
+    let tx = SolanaTransaction {
+        signature: "sig".to_string(),
+        instructions: (0..INSTRUCTIONS)
+            .map(|_| SolanaInstruction {
+                program_id: program_id.clone(),
+                accounts: Vec::new(),
+                data: String::new(),
+            })
+            .collect(),
+        ..Default::default()
+    };
+    let adapter = TransactionAdapter::new(tx, ParseConfig::default());
+
+    let (classifier, allocated) = allocations(|| InstructionClassifier::new(&adapter));
+
+    assert_eq!(classifier.get_instructions(JUPITER).len(), INSTRUCTIONS);
+    // рост Vec'ов и HashMap'ов, но не по аллокации на инструкцию
+    assert!(allocated < 64, "{allocated} allocations for {INSTRUCTIONS} instructions");
+}
+
+#[test]
+fn deserialized_keys_share_allocation_inside_interner_scope() {
+    let json = serde_json::to_string(&vec![JUPITER; INSTRUCTIONS]).unwrap();
+
+    let (keys, allocated) = allocations(|| {
+        KeyInterner::scope(|| serde_json::from_str::<Vec<AccountKey>>(&json).unwrap())
+    });
+    assert_eq!(keys.len(), INSTRUCTIONS);
+    assert!(allocated < 64, "{allocated} allocations for {INSTRUCTIONS} keys");
+
+    // без пула — ровно одна аллокация на ключ, без промежуточного String
+    let (_, allocated) = allocations(|| serde_json::from_str::<Vec<AccountKey>>(&json).unwrap());
+    assert!(allocated <= INSTRUCTIONS + 64, "{allocated} allocations for {INSTRUCTIONS} keys");
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "borsh")]
+
+use std::fs;
+
+use anyhow::Result;
+use solana_dex_parser::{BlockParseResult, DexParser, ParseResult, SolanaTransaction};
+
+#[test]
+fn parse_result_roundtrips_through_borsh() -> Result<()> {
+    let tx_data = fs::read_to_string("tests/fixtures/sample_tx.json")?;
+    let tx: SolanaTransaction = serde_json::from_str(&tx_data)?;
+    let result = DexParser::new().parse_all(tx, None);
+
+    let bytes = borsh::to_vec(&result)?;
+    assert_eq!(borsh::from_slice::<ParseResult>(&bytes)?, result);
+
+    let block = BlockParseResult {
+        slot: result.slot,
+        timestamp: Some(result.timestamp),
+        transactions: vec![result],
+        blockhash: None,
+    };
+    let bytes = borsh::to_vec(&block)?;
+    assert_eq!(borsh::from_slice::<BlockParseResult>(&bytes)?, block);
+    Ok(())
+}
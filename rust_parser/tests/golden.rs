@@ -0,0 +1,89 @@
+//! Golden-тесты против TypeScript-парсера: каждый подкаталог `tests/golden/<case>/`
+//! содержит `transaction.json` (см. `rpc_json::transaction_from_json`), `ts_output.json`
+//! (вывод `parseAll` TS-версии) и опционально `compat.json` (`CompatOptions`).
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use solana_dex_parser::compat::{compare_parse_result, to_ts_json, CompatOptions};
+use solana_dex_parser::core::rpc_json::transaction_from_json;
+use solana_dex_parser::DexParser;
+
+fn read_json(path: &Path) -> Result<Value> {
+    let data = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_slice(&data).with_context(|| format!("invalid JSON in {}", path.display()))
+}
+
+#[test]
+fn outputs_match_typescript_parser() -> Result<()> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let parser = DexParser::new();
+    let mut failures = Vec::new();
+    let mut cases = 0;
+
+    for entry in fs::read_dir(&root)? {
+        let case = entry?.path();
+        if !case.is_dir() {
+            continue;
+        }
+        let name = case
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let tx = transaction_from_json(&read_json(&case.join("transaction.json"))?)
+            .with_context(|| format!("golden case {name}"))?;
+        let ts_output = read_json(&case.join("ts_output.json"))?;
+        let options: CompatOptions = match case.join("compat.json") {
+            path if path.exists() => serde_json::from_value(read_json(&path)?)?,
+            _ => CompatOptions::default(),
+        };
+
+        cases += 1;
+        let result = parser.parse_all(tx, None);
+        for mismatch in compare_parse_result(&ts_output, &result, &options) {
+            failures.push(format!("{name}: {mismatch}"));
+        }
+    }
+
+    assert!(cases > 0, "no golden cases in {}", root.display());
+    assert!(
+        failures.is_empty(),
+        "golden mismatches:\n{}",
+        failures.join("\n")
+    );
+    Ok(())
+}
+
+/// Числа `to_ts_json` байт-в-байт как у `JSON.stringify`: комиссия (`0.000005`, а не
+/// `5e-6`) каждого кейса и `3.3e-8` SOL входа в `raydium_v4_swap`.
+#[test]
+fn numbers_are_byte_identical_to_typescript() -> Result<()> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    let parser = DexParser::new();
+    for entry in fs::read_dir(&root)? {
+        let case = entry?.path();
+        if !case.is_dir() {
+            continue;
+        }
+        let tx = transaction_from_json(&read_json(&case.join("transaction.json"))?)?;
+        let actual = to_ts_json(&parser.parse_all(tx, None))?;
+
+        // `ts_output.json` отформатирован с отступами; поле `fee` корня идёт первым
+        let ts_output = fs::read_to_string(case.join("ts_output.json"))?;
+        let start = ts_output.find("\"fee\":").context("no fee in ts_output.json")?;
+        let end = start + ts_output[start..].find('}').context("unterminated fee")? + 1;
+        let fee: String = ts_output[start..end]
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .collect();
+        assert!(actual.contains(&fee), "{}: {fee} not in {actual}", case.display());
+
+        if case.ends_with("raydium_v4_swap") {
+            assert!(actual.contains("\"amount\":3.3e-8,\"amountRaw\":\"33\""));
+        }
+    }
+    Ok(())
+}
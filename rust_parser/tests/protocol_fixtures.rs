@@ -0,0 +1,59 @@
+//! Регрессия по `tests/fixtures/<protocol>/`: текущий `ParseResult` каждой транзакции
+//! против записанного `gen_fixture` `<sig>.expected.json`.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde_json::Value;
+use solana_dex_parser::core::rpc_json::transaction_from_json;
+use solana_dex_parser::{fixtures, DexParser};
+
+fn read_json(path: &Path) -> Result<Value> {
+    let data = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    serde_json::from_slice(&data).with_context(|| format!("invalid JSON in {}", path.display()))
+}
+
+#[test]
+fn protocol_fixtures_match_expected_output() -> Result<()> {
+    let parser = DexParser::new();
+    let mut failures = Vec::new();
+    let mut cases = 0;
+
+    for protocol in fs::read_dir(fixtures::dir())? {
+        let protocol = protocol?.path();
+        if !protocol.is_dir() {
+            continue;
+        }
+        for entry in fs::read_dir(&protocol)? {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            let Some(sig) = name.strip_suffix(".json") else {
+                continue;
+            };
+            if sig.ends_with(".expected") {
+                continue;
+            }
+            let tx = transaction_from_json(&read_json(&path)?)
+                .with_context(|| format!("fixture {}", path.display()))?;
+            cases += 1;
+            let expected = read_json(&protocol.join(format!("{sig}.expected.json")))?;
+            // через строку, чтобы f64 прошли тот же round-trip, что и в записанном файле
+            let actual: Value =
+                serde_json::from_str(&serde_json::to_string(&parser.parse_all(tx, None))?)?;
+            if actual != expected {
+                failures.push(path.display().to_string());
+            }
+        }
+    }
+
+    assert!(cases > 0, "no protocol fixtures in {}", fixtures::dir().display());
+    assert!(
+        failures.is_empty(),
+        "output changed for:\n{}",
+        failures.join("\n")
+    );
+    Ok(())
+}
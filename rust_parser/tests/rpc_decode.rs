@@ -1,3 +1,5 @@
+#![cfg(feature = "rpc")]
+
 use anyhow::Result;
 use serde_json::Value;
 use solana_dex_parser::{DexParser, ParseConfig};
@@ -35,3 +37,80 @@ fn fetch_and_decode_live_transaction() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+#[ignore]
+async fn backfill_recent_block_range() -> Result<()> {
+    use futures::StreamExt;
+    use solana_dex_parser::rpc::{BlockFetcher, BlockFetcherConfig};
+
+    let rpc_url = std::env::var("SOLANA_RPC_URL")
+        .unwrap_or_else(|_| "https://api.mainnet-beta.solana.com".to_string());
+    let client = solana_client::nonblocking::rpc_client::RpcClient::new(rpc_url.clone());
+    let tip = client.get_slot().await?;
+    // Отступаем от tip, чтобы блоки точно были доступны
+    let start = tip.saturating_sub(64);
+
+    let fetcher = BlockFetcher::with_config(
+        &rpc_url,
+        BlockFetcherConfig {
+            concurrency: 2,
+            ..Default::default()
+        },
+    );
+    let blocks: Vec<_> = fetcher.stream(start..=start + 3).collect().await;
+
+    let mut last_slot = 0;
+    for block in blocks {
+        let block = block?;
+        assert!(block.slot > last_slot, "blocks must be yielded in slot order");
+        last_slot = block.slot;
+        println!("slot {} -> {} transactions", block.slot, block.transactions.len());
+    }
+
+    Ok(())
+}
+
+#[test]
+fn converts_base64_encoded_confirmed_transaction() -> Result<()> {
+    use base64_simd::STANDARD;
+    use solana_dex_parser::SolanaTransaction;
+    use solana_transaction_status::EncodedConfirmedTransactionWithStatusMeta;
+
+    let (user, program) = ([1u8; 32], [2u8; 32]);
+    // legacy сообщение: 1 подпись, ключи [user, program], одна инструкция
+    let mut raw = vec![1];
+    raw.extend([7u8; 64]);
+    raw.extend([1, 0, 1, 2]);
+    raw.extend(user);
+    raw.extend(program);
+    raw.extend([0u8; 32]);
+    raw.extend([1, 1, 1, 0, 2, 0xAA, 0xBB]);
+    let value = serde_json::json!({
+        "slot": 42,
+        "blockTime": 1_700_000_000,
+        "transaction": [STANDARD.encode_to_string(&raw), "base64"],
+        "meta": {
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [1_000_000, 1],
+            "postBalances": [995_000, 1],
+            "innerInstructions": [],
+            "logMessages": ["Program log: hi"],
+            "preTokenBalances": [],
+            "postTokenBalances": [],
+            "rewards": []
+        }
+    });
+    let encoded: EncodedConfirmedTransactionWithStatusMeta = serde_json::from_value(value)?;
+
+    let tx = SolanaTransaction::try_from_encoded(&encoded)?;
+    assert_eq!((tx.slot, tx.block_time), (42, 1_700_000_000));
+    assert_eq!(tx.signature, bs58::encode([7u8; 64]).into_string());
+    assert_eq!(tx.instructions[0].program_id, bs58::encode(program).into_string());
+    assert_eq!(tx.instructions[0].data, STANDARD.encode_to_string([0xAA, 0xBB]));
+    assert_eq!(tx.meta.fee, 5000);
+    assert_eq!(tx.meta.log_messages, vec!["Program log: hi".to_string()]);
+    Ok(())
+}
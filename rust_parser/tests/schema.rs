@@ -0,0 +1,31 @@
+use serde_json::Value;
+use solana_dex_parser::types::schema;
+
+fn properties<'a>(schema: &'a Value, definition: &str) -> &'a serde_json::Map<String, Value> {
+    schema["definitions"][definition]["properties"]
+        .as_object()
+        .unwrap_or_else(|| panic!("missing definition {definition}"))
+}
+
+#[test]
+fn schema_uses_camel_case_field_names() {
+    let schema = schema();
+
+    let root = schema["properties"].as_object().expect("root properties");
+    for field in ["aggregateTrade", "solBalanceChange", "memeEvents", "txStatus", "computeUnits"] {
+        assert!(root.contains_key(field), "ParseResult.{field}");
+    }
+
+    let trade = properties(&schema, "TradeInfo");
+    for field in ["type", "Pool", "inputToken", "outputToken", "programId"] {
+        assert!(trade.contains_key(field), "TradeInfo.{field}");
+    }
+    let pool_event = properties(&schema, "PoolEvent");
+    for field in ["tokens", "token0Mint", "token1AmountRaw"] {
+        assert!(pool_event.contains_key(field), "PoolEvent.{field}");
+    }
+    assert!(properties(&schema, "TokenLeg").contains_key("amountRaw"));
+    assert!(properties(&schema, "TransferData").contains_key("isFee"));
+    assert!(properties(&schema, "MemeEvent").contains_key("baseMint"));
+    assert!(properties(&schema, "BlockParseResult").contains_key("transactions"));
+}
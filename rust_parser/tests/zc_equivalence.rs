@@ -0,0 +1,215 @@
+//! Дифференциальные тесты: `TransactionAdapter` и `ZcTransactionAdapter` (и `parse_all`
+//! против `parse_zc`) на одних и тех же транзакциях корпуса. Берутся fixtures из
+//! `tests/fixtures/` и `tests/golden/`, записанные в `base64` — только у них есть wire-байты.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use serde_json::Value;
+use solana_dex_parser::core::transaction_adapter::TransactionAdapter;
+use solana_dex_parser::core::transaction_utils::TransactionUtils;
+use solana_dex_parser::core::zc_transaction_adapter::ZcTransactionAdapter;
+use solana_dex_parser::core::zero_copy::ZcTransaction;
+use solana_dex_parser::core::rpc_json::transaction_from_json;
+use solana_dex_parser::{fixtures, DexParser, ParseConfig};
+
+struct RawCase {
+    name: String,
+    raw: Vec<u8>,
+    value: Value,
+}
+
+impl RawCase {
+    fn meta(&self) -> Option<&Value> {
+        self.value.get("meta")
+    }
+
+    fn zc_tx(&self) -> ZcTransaction<'_> {
+        let signature = fixtures::raw_signature(&self.raw).unwrap_or_default();
+        let slot = self.value.get("slot").and_then(Value::as_u64).unwrap_or(0);
+        let block_time = self
+            .value
+            .get("blockTime")
+            .and_then(Value::as_u64)
+            .unwrap_or(0);
+        ZcTransaction::parse(&self.raw, slot, &signature, block_time, self.meta())
+            .unwrap_or_else(|err| panic!("{}: zc parse failed: {err}", self.name))
+    }
+}
+
+fn raw_cases() -> Vec<RawCase> {
+    let mut cases = Vec::new();
+    for name in fixtures::names().unwrap() {
+        let value = fixtures::load_value(&name).unwrap();
+        if let Some(raw) = fixtures::raw_transaction(&value) {
+            cases.push(RawCase { name, raw, value });
+        }
+    }
+    let golden = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden");
+    for entry in std::fs::read_dir(golden).unwrap() {
+        let path = entry.unwrap().path().join("transaction.json");
+        let Ok(data) = std::fs::read(&path) else {
+            continue;
+        };
+        let value: Value = serde_json::from_slice(&data).unwrap();
+        if let Some(raw) = fixtures::raw_transaction(&value) {
+            let name = path.display().to_string();
+            cases.push(RawCase { name, raw, value });
+        }
+    }
+    assert!(!cases.is_empty(), "no base64 fixtures in corpus");
+    cases
+}
+
+#[test]
+fn adapters_expose_identical_transaction_data() {
+    for case in raw_cases() {
+        let name = &case.name;
+        let owned_tx = transaction_from_json(&case.value).unwrap();
+        let owned = TransactionAdapter::new(owned_tx, ParseConfig::default());
+        let zc_tx = case.zc_tx();
+        let zc = ZcTransactionAdapter::new(&zc_tx, ParseConfig::default(), case.meta());
+
+        // owned-адаптер хранит ключи отсортированными, zc — в порядке сообщения
+        let owned_keys: Vec<&str> = owned
+            .account_keys()
+            .iter()
+            .map(|key| key.as_ref())
+            .collect();
+        let mut zc_keys: Vec<&str> = zc.account_keys().iter().map(String::as_str).collect();
+        zc_keys.sort_unstable();
+        assert_eq!(owned_keys, zc_keys, "{name}: account keys");
+        assert_eq!(owned.signers(), zc.signers(), "{name}: signers");
+        assert_eq!(owned.fee(), zc.fee(), "{name}: fee");
+        assert_eq!(
+            owned.compute_units(),
+            zc.compute_units(),
+            "{name}: compute units"
+        );
+        assert_eq!(owned.tx_status(), zc.tx_status(), "{name}: status");
+        assert_eq!(
+            owned.instructions(),
+            zc.instructions(),
+            "{name}: instructions"
+        );
+        assert_eq!(
+            owned.inner_instructions(),
+            zc.inner_instructions(),
+            "{name}: inner instructions"
+        );
+        assert_eq!(
+            owned.pre_balances(),
+            zc.pre_balances(),
+            "{name}: pre balances"
+        );
+        assert_eq!(
+            owned.post_balances(),
+            zc.post_balances(),
+            "{name}: post balances"
+        );
+        assert_eq!(
+            owned.pre_token_balances(),
+            zc.pre_token_balances(),
+            "{name}: pre token balances"
+        );
+        assert_eq!(
+            owned.post_token_balances(),
+            zc.post_token_balances(),
+            "{name}: post token balances"
+        );
+        assert_eq!(
+            owned.spl_token_map(),
+            zc.spl_token_map(),
+            "{name}: spl token map"
+        );
+        assert_eq!(
+            owned.spl_decimals_map(),
+            zc.spl_decimals_map(),
+            "{name}: decimals"
+        );
+        assert_eq!(
+            owned.signer_sol_balance_change(),
+            zc.signer_sol_balance_change(),
+            "{name}: signer SOL change"
+        );
+        assert_eq!(
+            owned.signer_token_balance_changes(),
+            zc.signer_token_balance_changes(),
+            "{name}: signer token changes"
+        );
+        for is_owner in [false, true] {
+            assert_eq!(
+                owned.get_account_sol_balance_changes(is_owner),
+                zc.get_account_sol_balance_changes(is_owner),
+                "{name}: account SOL changes (is_owner={is_owner})"
+            );
+            assert_eq!(
+                owned.get_account_token_balance_changes(is_owner),
+                zc.get_account_token_balance_changes(is_owner),
+                "{name}: account token changes (is_owner={is_owner})"
+            );
+        }
+    }
+}
+
+#[test]
+fn zero_copy_parse_matches_owned_parse() {
+    let parser = DexParser::new();
+    for case in raw_cases() {
+        let name = &case.name;
+        let owned_tx = transaction_from_json(&case.value).unwrap();
+        let owned = parser.parse_all(owned_tx, None);
+        let zc_tx = case.zc_tx();
+        let config = ParseConfig {
+            prefer_zero_copy: true,
+            ..Default::default()
+        };
+        let zero_copy = parser.parse_zc(&zc_tx, case.meta(), Some(config)).unwrap();
+
+        assert_eq!(zero_copy.trades, owned.trades, "{name}: trades");
+        assert_eq!(
+            zero_copy.aggregate_trade, owned.aggregate_trade,
+            "{name}: aggregate"
+        );
+        assert_eq!(
+            zero_copy.liquidities, owned.liquidities,
+            "{name}: liquidities"
+        );
+        assert_eq!(
+            zero_copy.meme_events, owned.meme_events,
+            "{name}: meme events"
+        );
+        assert_eq!(zero_copy.fee, owned.fee, "{name}: fee");
+        assert_eq!(
+            zero_copy.sol_balance_change, owned.sol_balance_change,
+            "{name}: SOL balance change"
+        );
+        assert_eq!(
+            zero_copy.token_balance_change, owned.token_balance_change,
+            "{name}: token balance change"
+        );
+    }
+}
+
+#[test]
+fn zc_adapter_transfers_match_owned() {
+    for case in raw_cases() {
+        let name = &case.name;
+        let owned_tx = transaction_from_json(&case.value).unwrap();
+        let owned = Arc::new(TransactionAdapter::new(owned_tx, ParseConfig::default()));
+        // RPC-транзакции приходят без готовых transfers: owned-путь строит их из инструкций
+        let owned_actions = TransactionUtils::new(owned).get_transfer_actions();
+        let zc_tx = case.zc_tx();
+        let zc = ZcTransactionAdapter::new(&zc_tx, ParseConfig::default(), case.meta());
+
+        assert!(!owned_actions.is_empty(), "{name}: no transfers in fixture");
+        assert_eq!(
+            owned_actions,
+            zc.get_transfer_actions(),
+            "{name}: transfer actions"
+        );
+        let mut owned_transfers: Vec<_> = owned_actions.into_values().flatten().collect();
+        owned_transfers.sort_by(|a, b| a.idx.cmp(&b.idx));
+        assert_eq!(owned_transfers, zc.transfers(), "{name}: transfers");
+    }
+}
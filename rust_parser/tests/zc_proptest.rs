@@ -0,0 +1,112 @@
+#![cfg(feature = "rpc")]
+//! Property-тесты zero-copy разбора: произвольные байты не паникуют, а валидные
+//! транзакции из `solana_sdk` разбираются так же, как их сериализует bincode.
+
+use proptest::prelude::*;
+use solana_dex_parser::core::zero_copy::{parse_signatures, ZcMessage, ZcTransaction};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::message::{v0, Message, MessageHeader, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+
+fn instruction() -> impl Strategy<Value = CompiledInstruction> {
+    // длины больше 127 и 16383 — многобайтовый shortvec
+    let data = prop_oneof![
+        prop::collection::vec(any::<u8>(), 0..200),
+        prop::collection::vec(any::<u8>(), 16_380..16_400),
+    ];
+    (
+        any::<u8>(),
+        prop::collection::vec(any::<u8>(), 0..140),
+        data,
+    )
+        .prop_map(|(program_id_index, accounts, data)| CompiledInstruction {
+            program_id_index,
+            accounts,
+            data,
+        })
+}
+
+fn transaction() -> impl Strategy<Value = VersionedTransaction> {
+    (
+        1u8..4,
+        prop::collection::vec(any::<[u8; 32]>(), 1..40),
+        any::<[u8; 32]>(),
+        prop::collection::vec(instruction(), 0..4),
+        any::<bool>(),
+    )
+        .prop_map(|(signers, keys, blockhash, instructions, versioned)| {
+            let header = MessageHeader {
+                num_required_signatures: signers,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 1,
+            };
+            let account_keys = keys.into_iter().map(Pubkey::new_from_array).collect();
+            let recent_blockhash = Hash::new_from_array(blockhash);
+            let message = if versioned {
+                VersionedMessage::V0(v0::Message {
+                    header,
+                    account_keys,
+                    recent_blockhash,
+                    instructions,
+                    address_table_lookups: Vec::new(),
+                })
+            } else {
+                VersionedMessage::Legacy(Message {
+                    header,
+                    account_keys,
+                    recent_blockhash,
+                    instructions,
+                })
+            };
+            VersionedTransaction {
+                signatures: vec![Signature::from([7u8; 64]); signers as usize],
+                message,
+            }
+        })
+}
+
+proptest! {
+    #[test]
+    fn arbitrary_bytes_never_panic(buffer in prop::collection::vec(any::<u8>(), 0..512)) {
+        if let Ok((_, message_start)) = parse_signatures(&buffer) {
+            if let Ok(message) = ZcMessage::parse(&buffer, message_start) {
+                for ix in &message.instructions {
+                    message.get_program_id(ix);
+                    message.get_instruction_accounts(ix);
+                }
+            }
+        }
+        if let Ok(tx) = ZcTransaction::parse(&buffer, 0, "sig", 0, None) {
+            tx.get_signers();
+            tx.get_instructions();
+        }
+    }
+
+    #[test]
+    fn zc_instructions_match_bincode(tx in transaction()) {
+        let raw = bincode::serialize(&tx).unwrap();
+        let decoded: VersionedTransaction = bincode::deserialize(&raw).unwrap();
+        let message = &decoded.message;
+
+        let (num_sigs, message_start) = parse_signatures(&raw).unwrap();
+        prop_assert_eq!(num_sigs, decoded.signatures.len());
+        let zc = ZcMessage::parse(&raw, message_start).unwrap();
+
+        let header = message.header();
+        prop_assert_eq!(zc.header.num_required_signatures, header.num_required_signatures);
+        prop_assert_eq!(zc.account_keys_len(), message.static_account_keys().len());
+        for (zc_key, key) in zc.account_keys_iter().zip(message.static_account_keys()) {
+            prop_assert_eq!(zc_key, &key.to_bytes());
+        }
+        prop_assert_eq!(zc.recent_blockhash, &message.recent_blockhash().to_bytes());
+        prop_assert_eq!(zc.instructions.len(), message.instructions().len());
+        for (zc_ix, ix) in zc.instructions.iter().zip(message.instructions()) {
+            prop_assert_eq!(zc_ix.program_id_index, ix.program_id_index);
+            prop_assert_eq!(zc_ix.accounts, ix.accounts.as_slice());
+            prop_assert_eq!(zc_ix.data, ix.data.as_slice());
+        }
+    }
+}
@@ -0,0 +1,243 @@
+//! `DexParser::parse_zc`: zero-copy Pumpswap-парсер против owned-парсера на одной транзакции.
+
+use serde_json::{json, Value};
+use solana_dex_parser::core::zero_copy::ZcTransaction;
+use solana_dex_parser::{DexParser, ParseConfig, ParseResult};
+
+const PUMP_SWAP: &str = "pAMMBay6oceH9fJKBRHGP5D4bD4sWpmSwMn52FMfXEA";
+const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+const WSOL: &str = "So11111111111111111111111111111111111111112";
+
+const BUY: [u8; 8] = [102, 6, 61, 18, 1, 218, 235, 234];
+const BUY_EVENT: [u8; 16] = [
+    228, 69, 165, 46, 81, 203, 154, 29, 103, 244, 82, 31, 44, 245, 119, 119,
+];
+
+// индексы в account keys
+const USER: u8 = 0;
+const POOL: u8 = 1;
+const USER_BASE: u8 = 2;
+const USER_QUOTE: u8 = 3;
+const POOL_BASE: u8 = 4;
+const POOL_QUOTE: u8 = 5;
+const FEE_TOKEN_ACCOUNT: u8 = 6;
+const GLOBAL_CONFIG: u8 = 7;
+const BASE_MINT: u8 = 8;
+const QUOTE_MINT: u8 = 9;
+const FEE_RECIPIENT: u8 = 10;
+const TOKEN: u8 = 11;
+const EVENT_AUTHORITY: u8 = 12;
+const PROGRAM: u8 = 13;
+
+const BASE_OUT: u64 = 5_000_000_000;
+const QUOTE_IN: u64 = 1_000_000_000;
+const PROTOCOL_FEE: u64 = 500_000;
+
+fn account_keys() -> Vec<[u8; 32]> {
+    let decode =
+        |key: &str| -> [u8; 32] { bs58::decode(key).into_vec().unwrap().try_into().unwrap() };
+    let mut keys: Vec<[u8; 32]> = (1..=9).map(|i| [i; 32]).collect();
+    keys.push(decode(WSOL));
+    keys.push([11; 32]);
+    keys.push(decode(TOKEN_PROGRAM));
+    keys.push([13; 32]);
+    keys.push(decode(PUMP_SWAP));
+    keys
+}
+
+fn key(index: u8) -> String {
+    bs58::encode(account_keys()[usize::from(index)]).into_string()
+}
+
+fn compact_u16(out: &mut Vec<u8>, mut value: usize) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+/// Legacy-транзакция в wire-формате с одной outer-инструкцией `buy`.
+fn raw_transaction() -> Vec<u8> {
+    let keys = account_keys();
+    let mut raw = Vec::new();
+    compact_u16(&mut raw, 1);
+    raw.extend_from_slice(&[7; 64]);
+    // header: 1 подпись, 0 readonly signed, 6 readonly unsigned (с конца)
+    raw.extend_from_slice(&[1, 0, 6]);
+    compact_u16(&mut raw, keys.len());
+    for key in &keys {
+        raw.extend_from_slice(key);
+    }
+    raw.extend_from_slice(&[9; 32]);
+
+    let accounts = [
+        POOL,
+        USER,
+        GLOBAL_CONFIG,
+        BASE_MINT,
+        QUOTE_MINT,
+        USER_BASE,
+        USER_QUOTE,
+        POOL_BASE,
+        POOL_QUOTE,
+        FEE_RECIPIENT,
+        FEE_TOKEN_ACCOUNT,
+        TOKEN,
+        TOKEN,
+        EVENT_AUTHORITY,
+        PROGRAM,
+    ];
+    let mut data = BUY.to_vec();
+    data.extend_from_slice(&BASE_OUT.to_le_bytes());
+    data.extend_from_slice(&(QUOTE_IN * 2).to_le_bytes());
+
+    compact_u16(&mut raw, 1);
+    raw.push(PROGRAM);
+    compact_u16(&mut raw, accounts.len());
+    raw.extend_from_slice(&accounts);
+    compact_u16(&mut raw, data.len());
+    raw.extend_from_slice(&data);
+    raw
+}
+
+fn buy_event() -> Vec<u8> {
+    let mut data = BUY_EVENT.to_vec();
+    data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+    for value in [
+        BASE_OUT,
+        QUOTE_IN * 2,
+        BASE_OUT,
+        0,
+        100_000_000_000_000,
+        80_000_000_000,
+        QUOTE_IN - PROTOCOL_FEE,
+        20,
+        0,
+        5,
+        PROTOCOL_FEE,
+        QUOTE_IN,
+        QUOTE_IN,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    for index in [
+        POOL,
+        USER,
+        USER_BASE,
+        USER_QUOTE,
+        FEE_RECIPIENT,
+        FEE_TOKEN_ACCOUNT,
+    ] {
+        data.extend_from_slice(&account_keys()[usize::from(index)]);
+    }
+    data
+}
+
+fn token_transfer(source: u8, destination: u8, authority: u8, amount: u64) -> Value {
+    let mut data = vec![3];
+    data.extend_from_slice(&amount.to_le_bytes());
+    json!({
+        "programIdIndex": TOKEN,
+        "accounts": [source, destination, authority],
+        "data": bs58::encode(data).into_string(),
+        "stackHeight": 2,
+    })
+}
+
+fn token_balance(account: u8, mint: u8, owner: u8, amount: u64, decimals: u8) -> Value {
+    json!({
+        "accountIndex": account,
+        "mint": key(mint),
+        "owner": key(owner),
+        "programId": TOKEN_PROGRAM,
+        "uiTokenAmount": {
+            "amount": amount.to_string(),
+            "decimals": decimals,
+            "uiAmount": amount as f64 / 10f64.powi(i32::from(decimals)),
+            "uiAmountString": "",
+        },
+    })
+}
+
+fn meta() -> Value {
+    let lamports = vec![10_000_000_000u64; account_keys().len()];
+    json!({
+        "err": null,
+        "fee": 5000,
+        "preBalances": lamports,
+        "postBalances": lamports,
+        "innerInstructions": [{
+            "index": 0,
+            "instructions": [
+                token_transfer(USER_QUOTE, POOL_QUOTE, USER, QUOTE_IN - PROTOCOL_FEE),
+                token_transfer(USER_QUOTE, FEE_TOKEN_ACCOUNT, USER, PROTOCOL_FEE),
+                token_transfer(POOL_BASE, USER_BASE, POOL, BASE_OUT),
+                {
+                    "programIdIndex": PROGRAM,
+                    "accounts": [EVENT_AUTHORITY],
+                    "data": bs58::encode(buy_event()).into_string(),
+                    "stackHeight": 2,
+                },
+            ],
+        }],
+        "preTokenBalances": [
+            token_balance(USER_BASE, BASE_MINT, USER, 0, 6),
+            token_balance(USER_QUOTE, QUOTE_MINT, USER, QUOTE_IN, 9),
+            token_balance(POOL_BASE, BASE_MINT, POOL, 100_000_000_000_000, 6),
+            token_balance(POOL_QUOTE, QUOTE_MINT, POOL, 80_000_000_000, 9),
+            token_balance(FEE_TOKEN_ACCOUNT, QUOTE_MINT, FEE_RECIPIENT, 0, 9),
+        ],
+        "postTokenBalances": [
+            token_balance(USER_BASE, BASE_MINT, USER, BASE_OUT, 6),
+            token_balance(USER_QUOTE, QUOTE_MINT, USER, 0, 9),
+            token_balance(POOL_BASE, BASE_MINT, POOL, 100_000_000_000_000 - BASE_OUT, 6),
+            token_balance(POOL_QUOTE, QUOTE_MINT, POOL, 80_000_000_000 + QUOTE_IN - PROTOCOL_FEE, 9),
+            token_balance(FEE_TOKEN_ACCOUNT, QUOTE_MINT, FEE_RECIPIENT, PROTOCOL_FEE, 9),
+        ],
+        "logMessages": [],
+    })
+}
+
+fn parse(prefer_zero_copy: bool) -> ParseResult {
+    let raw = raw_transaction();
+    let meta = meta();
+    let signature = bs58::encode([7u8; 64]).into_string();
+    let zc_tx = ZcTransaction::parse(&raw, 1, &signature, 1_700_000_000, Some(&meta)).unwrap();
+    let config = ParseConfig {
+        prefer_zero_copy,
+        ..Default::default()
+    };
+    DexParser::new()
+        .parse_zc(&zc_tx, Some(&meta), Some(config))
+        .unwrap()
+}
+
+#[test]
+fn pumpswap_zero_copy_matches_owned_parser() {
+    let zero_copy = parse(true);
+    let owned = parse(false);
+
+    assert_eq!(owned.trades.len(), 1);
+    let trade = &owned.trades[0];
+    assert_eq!(trade.input_token.mint, WSOL);
+    assert_eq!(trade.output_token.mint, key(BASE_MINT));
+    assert_eq!(trade.output_token.amount_raw, BASE_OUT.to_string());
+    assert_eq!(trade.pool, [key(POOL)]);
+    let (quote_reserve, base_reserve) = trade.pool_reserves.clone().expect("pool reserves");
+    assert_eq!(
+        quote_reserve.amount,
+        (80_000_000_000 + QUOTE_IN - PROTOCOL_FEE).to_string()
+    );
+    assert_eq!(
+        base_reserve.amount,
+        (100_000_000_000_000 - BASE_OUT).to_string()
+    );
+
+    assert_eq!(zero_copy.trades, owned.trades);
+    assert_eq!(zero_copy.aggregate_trade, owned.aggregate_trade);
+}